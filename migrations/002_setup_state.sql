@@ -0,0 +1,8 @@
+-- Onboarding wizard progress so setup can be resumed across restarts
+CREATE TABLE IF NOT EXISTS setup_steps (
+    step TEXT PRIMARY KEY,
+    completed BOOLEAN NOT NULL DEFAULT FALSE,
+    details TEXT, -- JSON blob with step output worth keeping
+    completed_at DATETIME,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
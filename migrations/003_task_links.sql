@@ -0,0 +1,18 @@
+-- Links a working branch to the project task it was started from
+CREATE TABLE IF NOT EXISTS task_links (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    repository TEXT NOT NULL, -- owner/name
+    branch TEXT NOT NULL,
+    issue_number INTEGER NOT NULL,
+    project_owner TEXT,
+    project_number TEXT,
+    project_item_id TEXT,
+    assignee TEXT,
+    status TEXT NOT NULL DEFAULT 'in_progress', -- 'in_progress', 'completed'
+    pull_request_number INTEGER,
+    started_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    completed_at DATETIME,
+    UNIQUE(repository, branch)
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_links_issue ON task_links(repository, issue_number);
@@ -0,0 +1,6 @@
+-- Per-user preferences (scan targets, defaults) keyed by GitHub login
+CREATE TABLE IF NOT EXISTS user_preferences (
+    login TEXT PRIMARY KEY,
+    preferences TEXT NOT NULL, -- JSON blob
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
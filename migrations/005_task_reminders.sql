@@ -0,0 +1,3 @@
+-- Tracks due-date reminders so each claimed task is reminded at most once a day
+ALTER TABLE task_links ADD COLUMN due_date DATE;
+ALTER TABLE task_links ADD COLUMN last_reminded_on DATE;
@@ -0,0 +1,9 @@
+-- Named checkouts the server can operate on, selected with the `repo` tool argument
+CREATE TABLE IF NOT EXISTS repositories (
+    name TEXT PRIMARY KEY,
+    path TEXT NOT NULL,       -- local checkout
+    owner TEXT NOT NULL,      -- GitHub owner
+    repo TEXT NOT NULL,       -- GitHub repository name
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
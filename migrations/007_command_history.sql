@@ -0,0 +1,16 @@
+-- One row per workflow command run, for success ratios and duration percentiles
+CREATE TABLE IF NOT EXISTS command_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    command TEXT NOT NULL,
+    success BOOLEAN NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_command_runs_created_at ON command_runs(created_at);
+
+CREATE TRIGGER IF NOT EXISTS cleanup_old_command_runs
+    AFTER INSERT ON command_runs
+    BEGIN
+        DELETE FROM command_runs WHERE created_at < datetime('now', '-30 days');
+    END;
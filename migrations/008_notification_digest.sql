@@ -0,0 +1,12 @@
+-- Notifications held back for a digest, per delivery channel and recipient
+CREATE TABLE IF NOT EXISTS pending_notifications (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    channel TEXT NOT NULL,
+    recipient TEXT NOT NULL DEFAULT '', -- GitHub login; empty for events without one
+    event TEXT NOT NULL,
+    text TEXT NOT NULL,
+    details TEXT NOT NULL, -- JSON blob
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_pending_notifications_channel ON pending_notifications(channel, recipient);
@@ -0,0 +1,21 @@
+-- Verified GitHub webhook deliveries, kept for replay and debugging
+CREATE TABLE IF NOT EXISTS webhook_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    delivery_id TEXT UNIQUE NOT NULL, -- X-GitHub-Delivery; redeliveries are ignored
+    event TEXT NOT NULL,
+    action TEXT,
+    repository TEXT,
+    sender TEXT,
+    payload TEXT NOT NULL, -- JSON blob
+    received_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    handled_at DATETIME,
+    error TEXT -- handler failures, one per line
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_events_event ON webhook_events(event, received_at);
+
+CREATE TRIGGER IF NOT EXISTS cleanup_old_webhook_events
+    AFTER INSERT ON webhook_events
+    BEGIN
+        DELETE FROM webhook_events WHERE received_at < datetime('now', '-30 days');
+    END;
@@ -0,0 +1,4 @@
+-- OAuth state is sealed into the state parameter itself, so the table is unused
+DROP TRIGGER IF EXISTS cleanup_expired_csrf_tokens;
+DROP INDEX IF EXISTS idx_csrf_tokens_expires_at;
+DROP TABLE IF EXISTS csrf_tokens;
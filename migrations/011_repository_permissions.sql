@@ -0,0 +1,9 @@
+-- Per-user repository permissions, enforced on top of GitHub's own
+CREATE TABLE IF NOT EXISTS repository_permissions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    login TEXT NOT NULL, -- GitHub login, or * for everyone
+    repository TEXT NOT NULL, -- owner/name, owner/* or *
+    operations TEXT NOT NULL, -- comma-separated: read, push, merge, admin
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(login, repository)
+);
@@ -0,0 +1,11 @@
+-- Repositories an admin has cleared for workflows, plus requests awaiting review
+CREATE TABLE IF NOT EXISTS repository_approvals (
+    repository TEXT PRIMARY KEY, -- owner/name, lowercase
+    status TEXT NOT NULL DEFAULT 'pending', -- pending or approved
+    requested_by TEXT, -- login whose tool call first hit the repository
+    requested_tool TEXT,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    reviewed_by TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
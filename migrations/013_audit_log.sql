@@ -0,0 +1,15 @@
+-- One row per request made with a valid session token, kept AUDIT_RETENTION_DAYS
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    method TEXT NOT NULL,
+    path TEXT NOT NULL,
+    login TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    source_ip TEXT, -- client address, or the first X-Forwarded-For hop
+    status INTEGER NOT NULL,
+    latency_ms INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_created_at ON audit_log(created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_log_login ON audit_log(login, created_at);
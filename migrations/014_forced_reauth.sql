@@ -0,0 +1,9 @@
+-- Users an admin has made sign in again; session tokens issued at or before
+-- `not_before` are refused
+CREATE TABLE IF NOT EXISTS forced_reauth (
+    user_id INTEGER PRIMARY KEY,
+    username TEXT NOT NULL,
+    not_before INTEGER NOT NULL, -- unix seconds
+    forced_by TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,14 @@
+-- Tool calls held until a human approves them (CONFIRM_TOOLS)
+CREATE TABLE IF NOT EXISTS pending_operations (
+    id TEXT PRIMARY KEY, -- random hex, returned to the caller as approval_id
+    tool TEXT NOT NULL,
+    arguments TEXT NOT NULL, -- JSON
+    status TEXT NOT NULL DEFAULT 'pending', -- pending, approved (running), rejected, executed or failed
+    result TEXT, -- JSON result, or the error message when failed
+    decided_by TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    decided_at DATETIME,
+    expires_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_pending_operations_status ON pending_operations(status, created_at);
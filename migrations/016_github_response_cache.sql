@@ -0,0 +1,11 @@
+-- GitHub API responses kept for conditional requests (GITHUB_CACHE_PERSIST)
+CREATE TABLE IF NOT EXISTS github_response_cache (
+    cache_key TEXT PRIMARY KEY, -- token digest and URL
+    etag TEXT,
+    last_modified TEXT,
+    headers TEXT NOT NULL, -- JSON object of the headers replayed on a hit
+    body BLOB NOT NULL,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_github_response_cache_updated_at ON github_response_cache(updated_at);
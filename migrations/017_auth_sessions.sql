@@ -0,0 +1,14 @@
+-- Sign-in sessions; every access token carries its session's `jti`, and a
+-- session's refresh token is stored only as a hash
+CREATE TABLE IF NOT EXISTS auth_sessions (
+    jti TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    username TEXT NOT NULL,
+    refresh_token_hash TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    refreshed_at DATETIME,
+    expires_at DATETIME NOT NULL,
+    revoked_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_auth_sessions_user ON auth_sessions(user_id);
@@ -0,0 +1,16 @@
+-- Pull requests left to GitHub auto-merge or a merge queue, tracked until
+-- they land so the requester can be notified
+CREATE TABLE IF NOT EXISTS pending_merges (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    repository TEXT NOT NULL,
+    pull_request_number INTEGER NOT NULL,
+    branch TEXT NOT NULL,
+    mode TEXT NOT NULL, -- auto or queue
+    requested_by TEXT NOT NULL, -- GitHub login whose token polls the PR
+    status TEXT NOT NULL DEFAULT 'pending', -- pending, merged, closed or cancelled
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    finished_at DATETIME,
+    UNIQUE(repository, pull_request_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_pending_merges_status ON pending_merges(status);
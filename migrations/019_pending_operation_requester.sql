@@ -0,0 +1,4 @@
+-- Who made each held call: it runs as them once approved, and they can't
+-- approve it themselves
+ALTER TABLE pending_operations ADD COLUMN requested_by TEXT;
+ALTER TABLE pending_operations ADD COLUMN requested_by_id INTEGER; -- GitHub user id
@@ -0,0 +1,49 @@
+-- The request audit trail (013) and sign-in sessions (017) replace the
+-- audit_logs and sessions tables from 001, which nothing wrote. Each pair
+-- becomes one table under the original name. Audit entries are pruned after
+-- AUDIT_RETENTION_DAYS and ended sessions by the server, so the cleanup
+-- triggers go too.
+
+DROP TRIGGER IF EXISTS cleanup_old_audit_logs;
+DROP TRIGGER IF EXISTS cleanup_expired_sessions;
+
+CREATE TABLE audit_logs_merged (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    method TEXT NOT NULL,
+    path TEXT NOT NULL,
+    login TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    source_ip TEXT, -- client address, or the first X-Forwarded-For hop
+    status INTEGER NOT NULL,
+    latency_ms INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Rows of the old audit_logs recorded an action rather than a request
+INSERT INTO audit_logs_merged (method, path, login, user_id, source_ip, status, latency_ms, created_at)
+SELECT a.action, COALESCE(a.resource, ''), COALESCE(u.username, ''), COALESCE(a.user_id, 0), a.ip_address,
+       CASE WHEN a.success THEN 200 ELSE 500 END, 0, a.created_at
+FROM audit_logs a
+LEFT JOIN users u ON u.github_id = a.user_id
+ORDER BY a.id;
+
+INSERT INTO audit_logs_merged (method, path, login, user_id, source_ip, status, latency_ms, created_at)
+SELECT method, path, login, user_id, source_ip, status, latency_ms, created_at
+FROM audit_log
+ORDER BY id;
+
+DROP TABLE audit_logs;
+DROP TABLE audit_log;
+ALTER TABLE audit_logs_merged RENAME TO audit_logs;
+
+CREATE INDEX IF NOT EXISTS idx_audit_logs_created_at ON audit_logs(created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_logs_login ON audit_logs(login, created_at);
+
+-- Rows of the old sessions table hold no jti any access token carries, so
+-- none of them could be used and they aren't kept
+DROP TABLE sessions;
+ALTER TABLE auth_sessions RENAME TO sessions;
+
+DROP INDEX IF EXISTS idx_auth_sessions_user;
+CREATE INDEX IF NOT EXISTS idx_sessions_user_id ON sessions(user_id);
+CREATE INDEX IF NOT EXISTS idx_sessions_expires_at ON sessions(expires_at);
@@ -0,0 +1,11 @@
+-- A workflow state is identified by who ran which workflow on which branch
+-- and when, so importing the same archive twice updates rows instead of
+-- duplicating them. Duplicates left by earlier imports keep their newest row.
+DELETE FROM workflow_states
+WHERE id NOT IN (
+    SELECT MAX(id) FROM workflow_states
+    GROUP BY user_id, repository, branch, workflow_type, created_at
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_workflow_states_key
+    ON workflow_states(user_id, repository, branch, workflow_type, created_at);
@@ -0,0 +1,4 @@
+-- Access tokens now carry their issue time in milliseconds, so a sign-in in
+-- the same second as a forced re-auth is told apart from the tokens it
+-- refuses. `not_before` moves to milliseconds to match.
+UPDATE forced_reauth SET not_before = not_before * 1000;
@@ -0,0 +1,3 @@
+-- The refresh token a session's current one replaced, so a refresh that
+-- lost a race with another using the same token isn't taken for reuse
+ALTER TABLE sessions ADD COLUMN previous_refresh_token_hash TEXT;
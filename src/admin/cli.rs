@@ -0,0 +1,183 @@
+// `github-mcp-server admin ...`: a terminal client for a running server's
+// /admin API. It needs no database or configuration of its own, only
+// `ADMIN_URL` (default `http://$HOST:$PORT`) and `ADMIN_TOKEN`, the session
+// token of a user in `ADMIN_USERS`.
+
+use reqwest::{Client, Method};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+type CliResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const USAGE: &str = "Usage: github-mcp-server admin <command>
+
+Commands:
+  sessions list            Live MCP sessions
+  jobs list                Background jobs and their schedule
+  jobs cancel <name>       Stop a background job until the next restart
+  tool list                Tools and whether they are disabled
+  tool disable <name>      Hide a tool and refuse calls to it
+  tool enable <name>       Re-enable a disabled tool
+  audit tail [--login L]   Print recent audit log entries and follow new ones
+
+Environment: ADMIN_URL (default http://$HOST:$PORT), ADMIN_TOKEN";
+
+/// How often `audit tail` asks for new entries
+const TAIL_INTERVAL: Duration = Duration::from_secs(2);
+
+struct AdminClient {
+    client: Client,
+    base_url: String,
+    token: String,
+}
+
+impl AdminClient {
+    fn from_env() -> CliResult<Self> {
+        let base_url = std::env::var("ADMIN_URL").unwrap_or_else(|_| {
+            format!(
+                "http://{}:{}",
+                std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string()),
+                std::env::var("PORT").unwrap_or_else(|_| "8443".to_string())
+            )
+        });
+        let token = std::env::var("ADMIN_TOKEN").map_err(|_| "ADMIN_TOKEN must be set to an admin's session token")?;
+
+        Ok(Self {
+            client: Client::builder().timeout(Duration::from_secs(30)).build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+
+    async fn request(&self, method: Method, path: &str, body: Option<Value>) -> CliResult<Value> {
+        let mut request = self
+            .client
+            .request(method, format!("{}/admin/{}", self.base_url, path))
+            .bearer_auth(&self.token);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            let message = body["message"].as_str().unwrap_or_else(|| status.canonical_reason().unwrap_or("error"));
+            return Err(format!("{}: {}", status.as_u16(), message).into());
+        }
+        Ok(body)
+    }
+
+    async fn get(&self, path: &str) -> CliResult<Value> {
+        self.request(Method::GET, path, None).await
+    }
+}
+
+/// Run the admin command in `args`, the arguments after `admin`
+pub async fn run(args: &[String]) -> CliResult<()> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    if matches!(args.as_slice(), [] | ["help" | "--help" | "-h", ..]) {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let admin = AdminClient::from_env()?;
+    match args.as_slice() {
+        ["sessions", "list"] => {
+            let body = admin.get("sessions").await?;
+            for session in body["sessions"].as_array().into_iter().flatten() {
+                println!(
+                    "{}  {:<14} {:<24} {:<11} idle {}s{}",
+                    session["id_prefix"].as_str().unwrap_or_default(),
+                    session["phase"].as_str().unwrap_or_default(),
+                    session["client"].as_str().unwrap_or("-"),
+                    session["protocol_version"].as_str().unwrap_or("-"),
+                    session["idle_seconds"],
+                    if session["compact"] == true { "  compact" } else { "" }
+                );
+            }
+            println!("{} sessions", body["count"]);
+        }
+        ["jobs", "list"] => {
+            let body = admin.get("jobs").await?;
+            for job in body["jobs"].as_array().into_iter().flatten() {
+                let state = match (&job["running"], &job["deferred_reason"]) {
+                    (Value::Bool(true), _) => "running".to_string(),
+                    (_, Value::String(reason)) => format!("deferred: {}", reason),
+                    _ => format!("next {}", job["next_run_at"].as_str().unwrap_or("-")),
+                };
+                println!(
+                    "{:<28} {:<11} every {}s  runs {}  {}{}",
+                    job["name"].as_str().unwrap_or_default(),
+                    job["priority"].as_str().unwrap_or_default(),
+                    job["interval_seconds"],
+                    job["runs"],
+                    state,
+                    job["last_error"].as_str().map(|e| format!("  last error: {}", e)).unwrap_or_default()
+                );
+            }
+        }
+        ["jobs", "cancel", name] => {
+            admin.request(Method::POST, &format!("jobs/{}/cancel", name), None).await?;
+            println!("Cancelled {}", name);
+        }
+        ["tool", "list"] => {
+            let body = admin.get("tools").await?;
+            for tool in body["tools"].as_array().into_iter().flatten() {
+                let disabled = if tool["disabled"] == true { "  disabled" } else { "" };
+                println!("{}{}", tool["name"].as_str().unwrap_or_default(), disabled);
+            }
+        }
+        ["tool", action @ ("disable" | "enable"), name] => {
+            let disabled = *action == "disable";
+            admin.request(Method::PUT, &format!("tools/{}", name), Some(json!({ "disabled": disabled }))).await?;
+            println!("{} {}", if disabled { "Disabled" } else { "Enabled" }, name);
+        }
+        ["audit", "tail", rest @ ..] => {
+            let login = match rest {
+                [] => None,
+                ["--login", login] => Some(*login),
+                _ => return Err(USAGE.into()),
+            };
+            tail_audit(&admin, login).await?;
+        }
+        _ => return Err(USAGE.into()),
+    }
+
+    Ok(())
+}
+
+/// Print the last 20 entries, then new ones as they arrive, until interrupted
+async fn tail_audit(admin: &AdminClient, login: Option<&str>) -> CliResult<()> {
+    let login = login.map(|login| format!("&login={}", login)).unwrap_or_default();
+    let mut after = None;
+    loop {
+        let query = match after {
+            Some(id) => format!("audit?after_id={}&limit=1000{}", id, login),
+            None => format!("audit?limit=20{}", login),
+        };
+        let body = admin.get(&query).await?;
+
+        // Entries come newest first
+        let entries = body["entries"].as_array().cloned().unwrap_or_default();
+        for entry in entries.iter().rev() {
+            println!(
+                "{}  {:<16} {:<15} {:<6} {} {}  {}ms",
+                entry["created_at"].as_str().unwrap_or_default(),
+                entry["login"].as_str().unwrap_or_default(),
+                entry["source_ip"].as_str().unwrap_or("-"),
+                entry["method"].as_str().unwrap_or_default(),
+                entry["path"].as_str().unwrap_or_default(),
+                entry["status"],
+                entry["latency_ms"]
+            );
+        }
+        if let Some(newest) = entries.first().and_then(|e| e["id"].as_i64()) {
+            after = Some(newest);
+        } else if after.is_none() {
+            after = Some(0);
+        }
+
+        tokio::time::sleep(TAIL_INTERVAL).await;
+    }
+}
@@ -0,0 +1,312 @@
+pub mod cli;
+
+use axum::{extract::{Path, Query, State}, http::HeaderMap, Json};
+use serde_json::{json, Value};
+
+use crate::{AppState, approvals, audit::{self, AuditFilter}, mcp::caller::Caller, operations, error::{AppError, Result}, permissions::{self, PermissionRule}, security::{jwt::ADMIN_ROLE, JwtClaims}, users};
+
+/// Resolve the session token on the request and require its admin role claim.
+/// The login must also still be in `ADMIN_USERS`, so removing it takes effect
+/// before the token expires.
+pub fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<JwtClaims> {
+    let token = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or_else(|| AppError::Authentication("Missing bearer token".to_string()))?;
+
+    let claims = state.jwt.validate(token)?;
+    let listed = state.config.security.admin_users.iter().any(|u| u.eq_ignore_ascii_case(&claims.username));
+    if claims.role.as_deref() != Some(ADMIN_ROLE) || !listed {
+        return Err(AppError::Authorization(format!("{} is not an administrator", claims.username)));
+    }
+
+    Ok(claims)
+}
+
+pub async fn overview(State(state): State<AppState>, headers: HeaderMap) -> Result<Json<Value>> {
+    require_admin(&state, &headers)?;
+
+    Ok(Json(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "read_only": state.config.security.read_only,
+        "scheduler": state.scheduler.overview(),
+        "websocket_connections": state.connections.total(),
+        "rate_limits": crate::github::budget::all(),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+/// `GET /admin/permissions`
+pub async fn list_permissions(State(state): State<AppState>, headers: HeaderMap) -> Result<Json<Value>> {
+    require_admin(&state, &headers)?;
+
+    let rules = permissions::list(&state.db).await?;
+    Ok(Json(json!({
+        "enforced": !rules.is_empty(),
+        "permissions": rules
+    })))
+}
+
+/// `PUT /admin/permissions`: create or replace the rule for a login and repository pattern
+pub async fn save_permission(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(rule): Json<PermissionRule>,
+) -> Result<Json<Value>> {
+    let admin = require_admin(&state, &headers)?;
+
+    permissions::save(&state.db, &rule).await?;
+    tracing::info!("{} set permissions for {} on {}: {:?}", admin.username, rule.login, rule.repository, rule.operations);
+    Ok(Json(json!({ "status": "success", "permission": rule })))
+}
+
+/// `DELETE /admin/permissions/:id`
+pub async fn delete_permission(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<i64>,
+) -> Result<Json<Value>> {
+    let admin = require_admin(&state, &headers)?;
+
+    if !permissions::remove(&state.db, id).await? {
+        return Err(AppError::Validation(format!("No permission with id {}", id)));
+    }
+    tracing::info!("{} removed permission {}", admin.username, id);
+    Ok(Json(json!({ "status": "success", "removed": id })))
+}
+
+/// `GET /admin/approvals`: the repository allow-list and requests awaiting review
+pub async fn list_approvals(State(state): State<AppState>, headers: HeaderMap) -> Result<Json<Value>> {
+    require_admin(&state, &headers)?;
+
+    let (approved, pending): (Vec<_>, Vec<_>) = approvals::list(&state.db)
+        .await?
+        .into_iter()
+        .partition(|approval| approval.status == "approved");
+    Ok(Json(json!({
+        "required": state.config.security.repository_approval,
+        "approved": approved,
+        "pending": pending
+    })))
+}
+
+/// `PUT /admin/approvals/:owner/:name`: allow workflows on a repository
+pub async fn approve_repository(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((owner, name)): Path<(String, String)>,
+) -> Result<Json<Value>> {
+    let admin = require_admin(&state, &headers)?;
+
+    let repository = approvals::approve(&state.db, &format!("{}/{}", owner, name), &admin.username).await?;
+    tracing::info!("{} approved repository {}", admin.username, repository);
+    Ok(Json(json!({ "status": "success", "approved": repository })))
+}
+
+/// `DELETE /admin/approvals/:owner/:name`: revoke an approval or dismiss a request
+pub async fn remove_approval(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((owner, name)): Path<(String, String)>,
+) -> Result<Json<Value>> {
+    let admin = require_admin(&state, &headers)?;
+
+    let repository = format!("{}/{}", owner, name);
+    if !approvals::remove(&state.db, &repository).await? {
+        return Err(AppError::Validation(format!("{} is neither approved nor pending", repository)));
+    }
+    tracing::info!("{} removed repository {} from the allow-list", admin.username, repository);
+    Ok(Json(json!({ "status": "success", "removed": repository })))
+}
+
+/// `GET /admin/audit`: recent requests made with a session token, newest first
+pub async fn list_audit(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(filter): Query<AuditFilter>,
+) -> Result<Json<Value>> {
+    require_admin(&state, &headers)?;
+
+    let entries = audit::query(&state.db, &filter).await?;
+    Ok(Json(json!({
+        "enabled": state.config.security.audit_log_enabled,
+        "retention_days": state.config.security.audit_retention_days,
+        "count": entries.len(),
+        "entries": entries
+    })))
+}
+
+/// `GET /admin/users`: users with a stored GitHub token and when it expires
+pub async fn list_users(State(state): State<AppState>, headers: HeaderMap) -> Result<Json<Value>> {
+    require_admin(&state, &headers)?;
+
+    let users = users::list(&state).await?;
+    Ok(Json(json!({
+        "count": users.len(),
+        "session_timeout_hours": state.config.security.session_timeout_hours,
+        "users": users
+    })))
+}
+
+/// `GET /admin/users/:user_id`
+pub async fn get_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<i64>,
+) -> Result<Json<Value>> {
+    require_admin(&state, &headers)?;
+
+    Ok(Json(json!({ "user": users::find(&state, user_id).await? })))
+}
+
+/// `DELETE /admin/users/:user_id/token`: forget the user's GitHub token and
+/// revoke it at GitHub; their calls fail until they connect again
+pub async fn revoke_user_token(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<i64>,
+) -> Result<Json<Value>> {
+    let admin = require_admin(&state, &headers)?;
+
+    let token = users::remove_token(&state.db, user_id)
+        .await?
+        .ok_or_else(|| AppError::Validation(format!("No connected user with id {}", user_id)))?;
+    let revoked_at_github = match crate::auth::revoke_github_token(&state, &token).await {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!("Removed user {}'s token but GitHub didn't revoke it: {}", user_id, e);
+            false
+        }
+    };
+    tracing::info!("{} revoked the GitHub token of user {}", admin.username, user_id);
+    Ok(Json(json!({ "status": "success", "user_id": user_id, "revoked_at_github": revoked_at_github })))
+}
+
+/// `POST /admin/users/:user_id/reauth`: refuse the user's current session
+/// tokens so they must sign in again
+pub async fn force_reauth(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(user_id): Path<i64>,
+) -> Result<Json<Value>> {
+    let admin = require_admin(&state, &headers)?;
+
+    let user = users::find(&state, user_id).await?;
+    users::force_reauth(&state, user_id, &user.username, &admin.username).await?;
+    tracing::info!("{} forced {} to sign in again", admin.username, user.username);
+    Ok(Json(json!({ "status": "success", "user_id": user_id, "username": user.username })))
+}
+
+/// `GET /admin/sessions`: live MCP sessions started over `POST /mcp`
+pub async fn list_sessions(State(state): State<AppState>, headers: HeaderMap) -> Result<Json<Value>> {
+    require_admin(&state, &headers)?;
+
+    let sessions = state.sessions.list().await?;
+    Ok(Json(json!({ "count": sessions.len(), "sessions": sessions })))
+}
+
+/// `GET /admin/jobs`: background jobs and their schedule
+pub async fn list_jobs(State(state): State<AppState>, headers: HeaderMap) -> Result<Json<Value>> {
+    require_admin(&state, &headers)?;
+
+    Ok(Json(state.scheduler.overview()))
+}
+
+/// `POST /admin/jobs/:name/cancel`: stop a background job until the next restart
+pub async fn cancel_job(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<Json<Value>> {
+    let admin = require_admin(&state, &headers)?;
+
+    if !state.scheduler.cancel(&name) {
+        return Err(AppError::Validation(format!("No background job named {}", name)));
+    }
+    tracing::info!("{} cancelled background job {}", admin.username, name);
+    Ok(Json(json!({ "status": "success", "cancelled": name })))
+}
+
+/// `GET /admin/tools`: every registered tool and whether it is disabled
+pub async fn list_tools(State(state): State<AppState>, headers: HeaderMap) -> Result<Json<Value>> {
+    require_admin(&state, &headers)?;
+
+    let tools: Vec<Value> = state
+        .tools
+        .states()
+        .into_iter()
+        .map(|(name, disabled)| json!({ "name": name, "disabled": disabled }))
+        .collect();
+    Ok(Json(json!({ "tools": tools })))
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ToolUpdate {
+    pub disabled: bool,
+}
+
+/// `PUT /admin/tools/:name`: disable or re-enable a tool until the next restart
+pub async fn update_tool(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(update): Json<ToolUpdate>,
+) -> Result<Json<Value>> {
+    let admin = require_admin(&state, &headers)?;
+
+    if !state.tools.set_disabled(&name, update.disabled) {
+        return Err(AppError::Validation(format!("No tool named {}", name)));
+    }
+    tracing::info!("{} {} tool {}", admin.username, if update.disabled { "disabled" } else { "enabled" }, name);
+    Ok(Json(json!({ "status": "success", "tool": name, "disabled": update.disabled })))
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct OperationsQuery {
+    pub status: Option<String>,
+}
+
+/// `GET /admin/operations`: tool calls held for approval, pending ones by default
+pub async fn list_operations(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<OperationsQuery>,
+) -> Result<Json<Value>> {
+    require_admin(&state, &headers)?;
+
+    let status = query.status.as_deref().unwrap_or("pending");
+    let status = (status != "all").then_some(status);
+    let operations = operations::list(&state.db, status).await?;
+    Ok(Json(json!({
+        "confirm_tools": state.config.security.confirm_tools,
+        "operations": operations
+    })))
+}
+
+/// `POST /admin/operations/:id/approve`: run a held tool call and return its result
+pub async fn approve_operation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<Value>> {
+    let admin = Caller::from_claims(&state.config, &require_admin(&state, &headers)?);
+
+    let result = operations::approve(&state, &id, &admin).await?;
+    Ok(Json(json!({ "status": "success", "approval_id": id, "result": result })))
+}
+
+/// `POST /admin/operations/:id/reject`
+pub async fn reject_operation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Result<Json<Value>> {
+    let admin = Caller::from_claims(&state.config, &require_admin(&state, &headers)?);
+
+    if !operations::reject(&state.db, &id, &admin).await? {
+        return Err(AppError::Validation(format!("No pending operation {}", id)));
+    }
+    tracing::info!("{} rejected operation {}", admin.login, id);
+    Ok(Json(json!({ "status": "success", "approval_id": id, "rejected": true })))
+}
@@ -0,0 +1,115 @@
+use serde::Serialize;
+
+use crate::{AppState, error::{AppError, Result}};
+
+/// A repository on the allow-list, or a request to add one
+#[derive(Debug, Clone, Serialize)]
+pub struct Approval {
+    pub repository: String,
+    /// `pending` until an admin approves it
+    pub status: String,
+    pub requested_by: Option<String>,
+    pub requested_tool: Option<String>,
+    /// Workflow calls refused while pending
+    pub attempts: i64,
+    pub reviewed_by: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// `owner/name`, lowercased so approvals match however the repository is spelled
+fn normalize(repository: &str) -> Result<String> {
+    let repository = repository.trim().to_lowercase();
+    match repository.split_once('/') {
+        Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => Ok(repository),
+        _ => Err(AppError::Validation(format!("Invalid repository: {}, expected owner/name", repository))),
+    }
+}
+
+pub async fn list(db: &sqlx::SqlitePool) -> Result<Vec<Approval>> {
+    let approvals = sqlx::query_as!(
+        Approval,
+        r#"
+        SELECT repository as "repository!", status, requested_by, requested_tool, attempts,
+               reviewed_by, created_at as "created_at!: String", updated_at as "updated_at!: String"
+        FROM repository_approvals
+        ORDER BY status DESC, updated_at DESC
+        "#
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(approvals)
+}
+
+/// Add `repository` to the allow-list, approving its pending request if any
+pub async fn approve(db: &sqlx::SqlitePool, repository: &str, admin: &str) -> Result<String> {
+    let repository = normalize(repository)?;
+    sqlx::query!(
+        r#"
+        INSERT INTO repository_approvals (repository, status, reviewed_by)
+        VALUES (?, 'approved', ?)
+        ON CONFLICT(repository) DO UPDATE SET
+            status = 'approved',
+            reviewed_by = excluded.reviewed_by,
+            updated_at = datetime('now')
+        "#,
+        repository,
+        admin
+    )
+    .execute(db)
+    .await?;
+
+    Ok(repository)
+}
+
+/// Take `repository` off the allow-list or dismiss its request; a later
+/// attempt files a new one
+pub async fn remove(db: &sqlx::SqlitePool, repository: &str) -> Result<bool> {
+    let repository = normalize(repository)?;
+    let result = sqlx::query!("DELETE FROM repository_approvals WHERE repository = ?", repository)
+        .execute(db)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Refuse `tool` on `repository` unless an admin has approved it, recording
+/// the attempt as a pending request for the admin to review
+pub async fn require(state: &AppState, repository: &str, tool: &str) -> Result<()> {
+    if !state.config.security.repository_approval {
+        return Ok(());
+    }
+
+    let repository = normalize(repository)?;
+    let approved = sqlx::query!(
+        r#"SELECT status FROM repository_approvals WHERE repository = ?"#,
+        repository
+    )
+    .fetch_optional(&state.db)
+    .await?
+    .map_or(false, |row| row.status == "approved");
+    if approved {
+        return Ok(());
+    }
+
+    // Only used to tell the admin who asked
+    let requested_by = crate::mcp::caller::current().map(|caller| caller.login);
+    sqlx::query!(
+        r#"
+        INSERT INTO repository_approvals (repository, status, requested_by, requested_tool, attempts)
+        VALUES (?, 'pending', ?, ?, 1)
+        ON CONFLICT(repository) DO UPDATE SET
+            attempts = attempts + 1,
+            updated_at = datetime('now')
+        "#,
+        repository,
+        requested_by,
+        tool
+    )
+    .execute(&state.db)
+    .await?;
+
+    tracing::info!("Workflow {} on unapproved repository {} is awaiting admin approval", tool, repository);
+    Err(AppError::ApprovalRequired(repository))
+}
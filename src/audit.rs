@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::info;
+
+use crate::{AppState, error::{AppError, Result}, scheduler::Priority};
+
+/// How often rows older than `AUDIT_RETENTION_DAYS` are deleted
+const PRUNE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+
+/// One request made with a valid session token
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub method: String,
+    pub path: String,
+    pub login: String,
+    pub user_id: i64,
+    pub source_ip: Option<String>,
+    pub status: i64,
+    pub latency_ms: i64,
+    pub created_at: String,
+}
+
+/// An entry about to be written by the audit middleware
+#[derive(Debug)]
+pub struct NewAuditEntry {
+    pub method: String,
+    pub path: String,
+    pub login: String,
+    pub user_id: i64,
+    pub source_ip: Option<String>,
+    pub status: i64,
+    pub latency_ms: i64,
+}
+
+/// `GET /admin/audit` query; every filter is optional
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditFilter {
+    pub login: Option<String>,
+    pub method: Option<String>,
+    /// Path prefix, e.g. `/admin`
+    pub path: Option<String>,
+    pub status: Option<i64>,
+    pub source_ip: Option<String>,
+    /// RFC 3339 timestamps bounding `created_at`
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Only entries newer than this one, for following the log
+    pub after_id: Option<i64>,
+    /// Newest first, up to 1000; 100 by default
+    pub limit: Option<i64>,
+}
+
+pub async fn record(db: &sqlx::SqlitePool, entry: &NewAuditEntry) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO audit_logs (method, path, login, user_id, source_ip, status, latency_ms)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+        entry.method,
+        entry.path,
+        entry.login,
+        entry.user_id,
+        entry.source_ip,
+        entry.status,
+        entry.latency_ms
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn query(db: &sqlx::SqlitePool, filter: &AuditFilter) -> Result<Vec<AuditEntry>> {
+    for bound in [&filter.since, &filter.until].into_iter().flatten() {
+        chrono::DateTime::parse_from_rfc3339(bound)
+            .map_err(|e| AppError::Validation(format!("Invalid timestamp {}: {}", bound, e)))?;
+    }
+    let limit = filter.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let method = filter.method.as_deref().map(str::to_uppercase);
+
+    let entries = sqlx::query_as!(
+        AuditEntry,
+        r#"
+        SELECT id as "id!", method, path, login, user_id, source_ip, status, latency_ms,
+               created_at as "created_at!: String"
+        FROM audit_logs
+        WHERE (?1 IS NULL OR login = ?1 COLLATE NOCASE)
+          AND (?2 IS NULL OR method = ?2)
+          AND (?3 IS NULL OR substr(path, 1, length(?3)) = ?3)
+          AND (?4 IS NULL OR status = ?4)
+          AND (?5 IS NULL OR source_ip = ?5)
+          AND (?6 IS NULL OR created_at >= datetime(?6))
+          AND (?7 IS NULL OR created_at < datetime(?7))
+          AND (?8 IS NULL OR id > ?8)
+        ORDER BY id DESC
+        LIMIT ?9
+        "#,
+        filter.login,
+        method,
+        filter.path,
+        filter.status,
+        filter.source_ip,
+        filter.since,
+        filter.until,
+        filter.after_id,
+        limit
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Delete entries older than `retention_days`; returns how many went
+pub async fn prune(db: &sqlx::SqlitePool, retention_days: u64) -> Result<u64> {
+    let cutoff = format!("-{} days", retention_days);
+    let result = sqlx::query!("DELETE FROM audit_logs WHERE created_at < datetime('now', ?)", cutoff)
+        .execute(db)
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Periodically prune the audit log; entries are kept forever with a retention of 0
+pub fn spawn_pruning(state: AppState) {
+    if !state.config.security.audit_log_enabled || state.config.security.audit_retention_days == 0 {
+        return;
+    }
+
+    let scheduler = state.scheduler.clone();
+    scheduler.spawn("audit_log_pruning", Priority::Low, PRUNE_INTERVAL, move || {
+        let state = state.clone();
+        async move {
+            let removed = prune(&state.db, state.config.security.audit_retention_days).await?;
+            if removed > 0 {
+                info!("Pruned {} audit log entries", removed);
+            }
+            Ok(())
+        }
+    });
+}
@@ -1,5 +1,6 @@
 use axum::{
     extract::{Query, State},
+    http::{header, HeaderMap},
     response::{Html, Redirect},
     Json,
 };
@@ -14,6 +15,11 @@ use tracing::{info, error};
 
 use crate::{AppState, error::{AppError, Result}};
 
+pub mod sessions;
+mod state;
+
+use state::OAuthState;
+
 #[derive(Debug, Deserialize)]
 pub struct GitHubCallbackQuery {
     code: Option<String>,
@@ -22,56 +28,66 @@ pub struct GitHubCallbackQuery {
     error_description: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct GitHubStartQuery {
+    return_to: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TokenRefreshRequest {
     refresh_token: String,
 }
 
-pub async fn github_oauth_start(State(state): State<AppState>) -> Result<Redirect> {
+pub async fn github_oauth_start(
+    State(state): State<AppState>,
+    Query(params): Query<GitHubStartQuery>,
+    headers: HeaderMap,
+) -> Result<([(header::HeaderName, String); 1], Redirect)> {
     info!("Starting GitHub OAuth flow");
 
     let client = create_oauth_client(&state)?;
-    
-    let (auth_url, csrf_token) = client
-        .authorize_url(CsrfToken::new_random)
+    let login = OAuthState::new(params.return_to, &headers)?;
+    let sealed = login.seal(&crate::secrets::resolve("JWT_SECRET", &state.config.jwt_secret))?;
+
+    let (auth_url, _) = client
+        .authorize_url(move || CsrfToken::new(sealed))
         .add_scope(Scope::new("repo".to_string()))
         .add_scope(Scope::new("read:user".to_string()))
         .add_scope(Scope::new("read:project".to_string()))
         .url();
 
-    // Store CSRF token in database for validation
-    store_csrf_token(&state.db, csrf_token.secret()).await?;
-
     info!("Redirecting to GitHub OAuth: {}", auth_url);
-    Ok(Redirect::to(auth_url.as_str()))
+    Ok(([(header::SET_COOKIE, login.cookie())], Redirect::to(auth_url.as_str())))
 }
 
 pub async fn github_oauth_callback(
     State(state): State<AppState>,
     Query(params): Query<GitHubCallbackQuery>,
-) -> Result<Html<String>> {
+    headers: HeaderMap,
+) -> Result<([(header::HeaderName, String); 1], Html<String>)> {
     info!("GitHub OAuth callback received");
 
+    let sealed = params.state.ok_or_else(|| {
+        AppError::OAuth2("No OAuth state received".to_string())
+    })?;
+
+    // Authenticity and expiry come from the sealed state itself; the nonce
+    // cookie ties it to this browser
+    let login = OAuthState::open(&sealed, &crate::secrets::resolve("JWT_SECRET", &state.config.jwt_secret))?;
+    login.verify_client(&headers)?;
+    let clear_cookie = [(header::SET_COOKIE, login.clear_cookie())];
+
     // Check for OAuth errors
     if let Some(error) = params.error {
         let description = params.error_description.unwrap_or_else(|| "Unknown error".to_string());
         error!("OAuth error: {} - {}", error, description);
-        return Ok(Html(create_error_page(&error, &description)));
+        return Ok((clear_cookie, Html(create_error_page(&error, &description))));
     }
 
     let code = params.code.ok_or_else(|| {
         AppError::OAuth2("No authorization code received".to_string())
     })?;
 
-    let csrf_state = params.state.ok_or_else(|| {
-        AppError::OAuth2("No CSRF state received".to_string())
-    })?;
-
-    // Validate CSRF token
-    if !validate_csrf_token(&state.db, &csrf_state).await? {
-        return Err(AppError::OAuth2("Invalid CSRF state".to_string()));
-    }
-
     let client = create_oauth_client(&state)?;
     
     // Exchange code for token
@@ -87,7 +103,7 @@ pub async fn github_oauth_callback(
     // Get user info from GitHub
     let github_client = crate::github::api::GitHubClient::new(
         access_token.clone(),
-        Some(state.config.github.api_base_url.clone()),
+        &state.config.github.host,
     )?;
     
     let user = github_client.get_user().await?;
@@ -102,38 +118,46 @@ pub async fn github_oauth_callback(
         refresh_token.as_deref(),
     ).await?;
 
-    // Generate JWT for session
-    let jwt_token = generate_jwt_token(&state.config.jwt_secret, user.id, &user.login)?;
+    let tokens = sessions::open(&state, user.id, &user.login).await?;
 
-    Ok(Html(create_success_page(&user.login, &jwt_token)))
+    Ok((clear_cookie, Html(create_success_page(&state, &user.login, &tokens, login.return_to.as_deref()))))
 }
 
+/// `POST /auth/token/refresh`: a new access token and refresh token for the
+/// session the refresh token belongs to
 pub async fn refresh_token(
     State(state): State<AppState>,
     Json(request): Json<TokenRefreshRequest>,
-) -> Result<Json<Value>> {
-    info!("Refreshing GitHub token");
-
-    // TODO: Implement token refresh logic
-    // 1. Validate refresh token
-    // 2. Exchange for new access token
-    // 3. Update database
-    // 4. Return new JWT
-
-    Ok(Json(json!({
-        "status": "success",
-        "message": "Token refresh not implemented yet"
-    })))
+) -> Result<Json<sessions::IssuedTokens>> {
+    Ok(Json(sessions::refresh(&state, &request.refresh_token).await?))
+}
+
+/// `POST /auth/logout`: end the session of the bearer token, which also
+/// stops its refresh token working
+pub async fn logout(State(state): State<AppState>, headers: HeaderMap) -> Result<Json<Value>> {
+    let token = headers
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .ok_or_else(|| AppError::Authentication("Missing bearer token".to_string()))?;
+    let claims = state.jwt.validate(token)?;
+
+    sessions::revoke(&state, &claims.jti).await?;
+    info!("{} signed out", claims.username);
+    Ok(Json(json!({ "status": "success" })))
 }
 
 fn create_oauth_client(state: &AppState) -> Result<BasicClient> {
     let client = BasicClient::new(
         ClientId::new(state.config.github.client_id.clone()),
-        Some(ClientSecret::new(state.config.github.client_secret.clone())),
-        AuthUrl::new("https://github.com/login/oauth/authorize".to_string())
+        Some(ClientSecret::new(crate::secrets::resolve(
+            "GITHUB_CLIENT_SECRET",
+            &state.config.github.client_secret,
+        ))),
+        AuthUrl::new(state.config.github.host.authorize_url())
             .map_err(|e| AppError::OAuth2(format!("Invalid auth URL: {}", e)))?,
         Some(
-            TokenUrl::new("https://github.com/login/oauth/access_token".to_string())
+            TokenUrl::new(state.config.github.host.token_url())
                 .map_err(|e| AppError::OAuth2(format!("Invalid token URL: {}", e)))?,
         ),
     )
@@ -145,33 +169,6 @@ fn create_oauth_client(state: &AppState) -> Result<BasicClient> {
     Ok(client)
 }
 
-async fn store_csrf_token(db: &sqlx::SqlitePool, token: &str) -> Result<()> {
-    sqlx::query!(
-        "INSERT INTO csrf_tokens (token, expires_at) VALUES (?, datetime('now', '+10 minutes'))",
-        token
-    )
-    .execute(db)
-    .await?;
-
-    Ok(())
-}
-
-async fn validate_csrf_token(db: &sqlx::SqlitePool, token: &str) -> Result<bool> {
-    let row = sqlx::query!(
-        "SELECT COUNT(*) as count FROM csrf_tokens WHERE token = ? AND expires_at > datetime('now')",
-        token
-    )
-    .fetch_one(db)
-    .await?;
-
-    // Clean up used token
-    sqlx::query!("DELETE FROM csrf_tokens WHERE token = ?", token)
-        .execute(db)
-        .await?;
-
-    Ok(row.count > 0)
-}
-
 async fn store_github_token(
     db: &sqlx::SqlitePool,
     user_id: u64,
@@ -200,46 +197,36 @@ async fn store_github_token(
     Ok(())
 }
 
-fn encrypt_token(token: &str) -> Result<String> {
-    // TODO: Implement proper token encryption using a secure key
-    // For now, return the token as-is (NOT SECURE - implement proper encryption)
-    Ok(token.to_string())
-}
+/// Revoke `token` at GitHub, so it stops working everywhere rather than just
+/// here. Only tokens issued to this server's OAuth app can be revoked.
+pub(crate) async fn revoke_github_token(state: &AppState, token: &str) -> Result<()> {
+    let github = &state.config.github;
+    let client_secret = crate::secrets::resolve("GITHUB_CLIENT_SECRET", &github.client_secret);
+    let response = reqwest::Client::new()
+        .delete(format!("{}/applications/{}/token", github.host.api_url, github.client_id))
+        .basic_auth(&github.client_id, Some(&client_secret))
+        .header(reqwest::header::USER_AGENT, "github-mcp-server/1.0")
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .json(&json!({ "access_token": token }))
+        .send()
+        .await?;
 
-fn generate_jwt_token(secret: &str, user_id: u64, username: &str) -> Result<String> {
-    use jsonwebtoken::{encode, Header, EncodingKey};
-    use serde::{Serialize};
-
-    #[derive(Serialize)]
-    struct Claims {
-        sub: String,
-        user_id: u64,
-        username: String,
-        exp: usize,
-        iat: usize,
+    // 404 means GitHub no longer knows the token, which is what we wanted
+    if !response.status().is_success() && response.status() != reqwest::StatusCode::NOT_FOUND {
+        return Err(AppError::GitHubApi(format!("Token revocation failed: {}", response.status())));
     }
+    Ok(())
+}
 
-    let now = chrono::Utc::now();
-    let exp = now + chrono::Duration::hours(24);
-
-    let claims = Claims {
-        sub: user_id.to_string(),
-        user_id,
-        username: username.to_string(),
-        exp: exp.timestamp() as usize,
-        iat: now.timestamp() as usize,
-    };
-
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(secret.as_ref()),
-    )?;
-
-    Ok(token)
+pub(crate) fn encrypt_token(token: &str) -> Result<String> {
+    crate::security::tokens::encrypt(token)
 }
 
-fn create_success_page(username: &str, jwt_token: &str) -> String {
+fn create_success_page(state: &AppState, username: &str, tokens: &sessions::IssuedTokens, return_to: Option<&str>) -> String {
+    // `return_to` was checked to be a local path when the login started
+    let continue_link = return_to
+        .map(|path| format!(r#"<p><a href="{}">Continue</a></p>"#, path.replace('"', "%22").replace('<', "%3C")))
+        .unwrap_or_default();
     format!(
         r#"
 <!DOCTYPE html>
@@ -259,20 +246,25 @@ fn create_success_page(username: &str, jwt_token: &str) -> String {
     
     <h3>Your Session Token:</h3>
     <div class="token" id="token">{}</div>
-    <button class="copy-btn" onclick="copyToken()">Copy Token</button>
+    <button class="copy-btn" onclick="copyToken('token')">Copy Token</button>
+
+    <h3>Your Refresh Token:</h3>
+    <div class="token" id="refresh-token">{}</div>
+    <button class="copy-btn" onclick="copyToken('refresh-token')">Copy Refresh Token</button>
     
     <h3>Next Steps:</h3>
     <ol>
-        <li>Copy the token above</li>
-        <li>Configure your Claude/Cursor client with this token</li>
+        <li>Copy the tokens above</li>
+        <li>Configure your Claude/Cursor client with them</li>
         <li>Start using the GitHub workflow commands: <code>push</code>, <code>scan tasks</code>, <code>merge</code></li>
     </ol>
     
-    <p><em>This token will expire in 24 hours. You can refresh it using the MCP server.</em></p>
+    <p><em>The session token expires in {} minutes. Exchange the refresh token for a new one at <code>POST /auth/token/refresh</code> for up to {} hours.</em></p>
+    {}
     
     <script>
-        function copyToken() {{
-            const token = document.getElementById('token').textContent;
+        function copyToken(id) {{
+            const token = document.getElementById(id).textContent;
             navigator.clipboard.writeText(token).then(() => {{
                 alert('Token copied to clipboard!');
             }});
@@ -281,7 +273,12 @@ fn create_success_page(username: &str, jwt_token: &str) -> String {
 </body>
 </html>
         "#,
-        username, jwt_token
+        username,
+        tokens.access_token,
+        tokens.refresh_token,
+        state.config.security.access_token_minutes,
+        state.config.security.session_timeout_hours,
+        continue_link
     )
 }
 
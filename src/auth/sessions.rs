@@ -0,0 +1,238 @@
+// Sign-in sessions behind short-lived access tokens.
+//
+// Signing in opens a session: a row keyed by the `jti` that every access
+// token issued for it carries, holding a hash of its refresh token. Access
+// tokens last `ACCESS_TOKEN_MINUTES`; `POST /auth/token/refresh` trades the
+// refresh token for a new access token and a new refresh token, and the old
+// refresh token stops working. Presenting one that was already used ends the
+// session, since someone else has a copy, unless it was replaced moments ago
+// by a refresh racing this one. A session lasts `SESSION_TIMEOUT_HOURS` from
+// sign-in, until `POST /auth/logout`, or until an admin forces its user to
+// sign in again; WebSockets opened with it are closed when it's revoked.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use rand::RngCore;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::{AppState, error::{AppError, Result}, mcp::caller::{self, Caller}, scheduler::Priority};
+
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How long after a refresh its old token is taken for a concurrent
+/// refresh rather than reuse
+const REFRESH_RACE_WINDOW: &str = "-30 seconds";
+
+/// What a client gets on sign-in and on every refresh
+#[derive(Debug, Serialize)]
+pub struct IssuedTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub token_type: &'static str,
+    /// Seconds until the access token expires
+    pub expires_in: u64,
+}
+
+fn hash(refresh_token: &str) -> String {
+    hex::encode(Sha256::digest(refresh_token.as_bytes()))
+}
+
+/// `<jti>.<random>`, so a refresh finds its session without a scan
+fn new_refresh_token(jti: &str) -> String {
+    let mut secret = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+    format!("{}.{}", jti, hex::encode(secret))
+}
+
+fn tokens(state: &AppState, user_id: u64, username: &str, jti: &str, refresh_token: String) -> Result<IssuedTokens> {
+    Ok(IssuedTokens {
+        access_token: state.jwt.issue(user_id, username, jti)?,
+        refresh_token,
+        token_type: "Bearer",
+        expires_in: state.config.security.access_token_minutes * 60,
+    })
+}
+
+/// Start a session for a user who just signed in
+pub async fn open(state: &AppState, user_id: u64, username: &str) -> Result<IssuedTokens> {
+    let jti = uuid::Uuid::new_v4().to_string();
+    let refresh_token = new_refresh_token(&jti);
+    let refresh_hash = hash(&refresh_token);
+    let lifetime = format!("+{} hours", state.config.security.session_timeout_hours);
+    let user_id_db = user_id as i64;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO sessions (jti, user_id, username, refresh_token_hash, expires_at)
+        VALUES (?, ?, ?, ?, datetime('now', ?))
+        "#,
+        jti,
+        user_id_db,
+        username,
+        refresh_hash,
+        lifetime
+    )
+    .execute(&state.db)
+    .await?;
+
+    tokens(state, user_id, username, &jti, refresh_token)
+}
+
+/// Swap `refresh_token` for new tokens on the same session
+pub async fn refresh(state: &AppState, refresh_token: &str) -> Result<IssuedTokens> {
+    let invalid = || AppError::Authentication("Invalid or expired refresh token".to_string());
+    // Another refresh with the same token got there first; the client has
+    // the tokens it returned
+    let raced = || {
+        AppError::Validation("This refresh token was just used by another refresh; use the tokens it returned".to_string())
+    };
+    let (jti, _) = refresh_token.split_once('.').ok_or_else(invalid)?;
+
+    let session = sqlx::query!(
+        r#"
+        SELECT user_id, username, refresh_token_hash, previous_refresh_token_hash,
+               COALESCE(refreshed_at > datetime('now', ?), 0) as "just_refreshed!: bool"
+        FROM sessions
+        WHERE jti = ? AND revoked_at IS NULL AND expires_at > datetime('now')
+        "#,
+        REFRESH_RACE_WINDOW,
+        jti
+    )
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(invalid)?;
+
+    let presented = hash(refresh_token);
+    if session.refresh_token_hash != presented {
+        if session.just_refreshed && session.previous_refresh_token_hash.as_deref() == Some(presented.as_str()) {
+            return Err(raced());
+        }
+        warn!("Refresh token for {}'s session {} was reused; ending the session", session.username, jti);
+        revoke(state, jti).await?;
+        return Err(invalid());
+    }
+
+    let next = new_refresh_token(jti);
+    let next_hash = hash(&next);
+    // Matching on the old hash makes two refreshes racing with one token
+    // produce one winner
+    let swapped = sqlx::query!(
+        r#"
+        UPDATE sessions
+        SET previous_refresh_token_hash = refresh_token_hash, refresh_token_hash = ?, refreshed_at = datetime('now')
+        WHERE jti = ? AND refresh_token_hash = ? AND revoked_at IS NULL
+        "#,
+        next_hash,
+        jti,
+        presented
+    )
+    .execute(&state.db)
+    .await?
+    .rows_affected();
+
+    if swapped == 0 {
+        return Err(raced());
+    }
+
+    tokens(state, session.user_id as u64, &session.username, jti, next)
+}
+
+/// End one session and close the WebSockets opened with it; false if it had
+/// already ended
+pub async fn revoke(state: &AppState, jti: &str) -> Result<bool> {
+    let result = sqlx::query!(
+        "UPDATE sessions SET revoked_at = datetime('now') WHERE jti = ? AND revoked_at IS NULL",
+        jti
+    )
+    .execute(&state.db)
+    .await?;
+
+    state.connections.close_session(jti);
+    Ok(result.rows_affected() > 0)
+}
+
+/// End every session `user_id` has open and close their WebSockets,
+/// returning how many sessions there were
+pub async fn revoke_user(state: &AppState, user_id: i64) -> Result<u64> {
+    let result = sqlx::query!(
+        "UPDATE sessions SET revoked_at = datetime('now') WHERE user_id = ? AND revoked_at IS NULL",
+        user_id
+    )
+    .execute(&state.db)
+    .await?;
+
+    state.connections.close_user(user_id as u64);
+    Ok(result.rows_affected())
+}
+
+/// Whether `jti` names a session that is neither revoked nor expired
+pub async fn is_active(db: &sqlx::SqlitePool, jti: &str) -> Result<bool> {
+    let row = sqlx::query!(
+        "SELECT jti FROM sessions WHERE jti = ? AND revoked_at IS NULL AND expires_at > datetime('now')",
+        jti
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.is_some())
+}
+
+/// Refuse requests whose access token is valid but whose session is unknown,
+/// revoked or expired. Requests without a valid token pass through for the
+/// handler to reject, or to serve if it's public; the rest are served as the
+/// token's user (see `mcp::caller`).
+pub async fn require_session(State(state): State<AppState>, req: Request<Body>, next: Next) -> Response {
+    let claims = req
+        .headers()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .and_then(|token| state.jwt.validate(token).ok());
+
+    if let Some(claims) = &claims {
+        match is_active(&state.db, &claims.jti).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return AppError::Authentication("Session has ended; sign in again".to_string()).into_response()
+            }
+            Err(e) => return e.into_response(),
+        }
+    }
+
+    let caller = claims.map(|claims| Caller::from_claims(&state.config, &claims));
+    caller::scope(caller, next.run(req)).await
+}
+
+/// Delete sessions that have expired or been revoked
+pub async fn prune(db: &sqlx::SqlitePool) -> Result<u64> {
+    let result = sqlx::query!(
+        "DELETE FROM sessions WHERE expires_at <= datetime('now') OR revoked_at IS NOT NULL"
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Periodically delete ended sessions
+pub fn spawn_pruning(state: AppState) {
+    let scheduler = state.scheduler.clone();
+    scheduler.spawn("auth_session_pruning", Priority::Low, PRUNE_INTERVAL, move || {
+        let state = state.clone();
+        async move {
+            let removed = prune(&state.db).await?;
+            if removed > 0 {
+                info!("Pruned {} ended sign-in sessions", removed);
+            }
+            Ok(())
+        }
+    });
+}
@@ -0,0 +1,155 @@
+// Stateless OAuth `state` parameter.
+//
+// Everything the callback needs is sealed into the parameter with AES-256-GCM
+// under a key derived from `JWT_SECRET`, so nothing is stored between the
+// redirect and the callback. The payload's nonce is also set as a short-lived
+// cookie, binding the callback to the browser that started the login; each
+// login gets its own cookie, so several can be in flight at once.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use axum::http::{header, HeaderMap};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL, Engine};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{AppError, Result};
+
+/// How long a login may take between the redirect and the callback
+const STATE_TTL_SECONDS: i64 = 10 * 60;
+const NONCE_COOKIE_PREFIX: &str = "mcp_oauth_";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OAuthState {
+    pub nonce: String,
+    /// Local path to offer once signed in
+    pub return_to: Option<String>,
+    /// Hash of the starting browser's User-Agent
+    client: String,
+    issued_at: i64,
+}
+
+impl OAuthState {
+    pub fn new(return_to: Option<String>, headers: &HeaderMap) -> Result<Self> {
+        if return_to.as_deref().map_or(false, |path| !is_local_path(path)) {
+            return Err(AppError::Validation("return_to must be a path on this server".to_string()));
+        }
+
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        Ok(Self {
+            nonce: hex::encode(nonce),
+            return_to,
+            client: client_fingerprint(headers),
+            issued_at: chrono::Utc::now().timestamp(),
+        })
+    }
+
+    /// Encrypt into a URL-safe `state` value
+    pub fn seal(&self, secret: &str) -> Result<String> {
+        let mut nonce = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let plaintext = serde_json::to_vec(self)?;
+        let ciphertext = cipher(secret)?
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|e| AppError::Internal(format!("OAuth state encryption failed: {}", e)))?;
+
+        let mut out = nonce.to_vec();
+        out.extend_from_slice(&ciphertext);
+        Ok(BASE64_URL.encode(out))
+    }
+
+    /// Decrypt a `state` value from the callback, rejecting tampered,
+    /// foreign or expired ones
+    pub fn open(sealed: &str, secret: &str) -> Result<Self> {
+        let invalid = || AppError::OAuth2("Invalid OAuth state".to_string());
+        let bytes = BASE64_URL.decode(sealed).map_err(|_| invalid())?;
+        if bytes.len() < 12 {
+            return Err(invalid());
+        }
+
+        let (nonce, ciphertext) = bytes.split_at(12);
+        let plaintext = cipher(secret)?
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| invalid())?;
+        let state: Self = serde_json::from_slice(&plaintext).map_err(|_| invalid())?;
+
+        if chrono::Utc::now().timestamp() - state.issued_at > STATE_TTL_SECONDS {
+            return Err(AppError::OAuth2("OAuth login expired; please start again".to_string()));
+        }
+        Ok(state)
+    }
+
+    /// Check the callback came from the browser that started this login
+    pub fn verify_client(&self, headers: &HeaderMap) -> Result<()> {
+        let cookie = format!("{}{}", NONCE_COOKIE_PREFIX, self.nonce);
+        let has_cookie = headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(';'))
+            .any(|pair| pair.trim().split('=').next() == Some(cookie.as_str()));
+
+        if !has_cookie || client_fingerprint(headers) != self.client {
+            return Err(AppError::OAuth2("OAuth callback doesn't match the browser that started the login".to_string()));
+        }
+        Ok(())
+    }
+
+    /// `Set-Cookie` value marking this login as started in the browser
+    pub fn cookie(&self) -> String {
+        format!(
+            "{}{}=1; Max-Age={}; Path=/auth/github; HttpOnly; Secure; SameSite=Lax",
+            NONCE_COOKIE_PREFIX, self.nonce, STATE_TTL_SECONDS
+        )
+    }
+
+    /// `Set-Cookie` value removing the cookie once the login completes
+    pub fn clear_cookie(&self) -> String {
+        format!("{}{}=; Max-Age=0; Path=/auth/github; HttpOnly; Secure; SameSite=Lax", NONCE_COOKIE_PREFIX, self.nonce)
+    }
+}
+
+/// Whether `path` stays on this server, so the login can't be used as an open
+/// redirect. Browsers drop tabs and newlines from URLs and read `\` as `/`,
+/// so `/<tab>/evil.com` and `/\evil.com` would both lead to another host.
+fn is_local_path(path: &str) -> bool {
+    let mut chars = path.chars();
+    chars.next() == Some('/')
+        && !matches!(chars.next(), Some('/'))
+        && !path.chars().any(|c| c.is_control() || c == '\\')
+}
+
+fn cipher(secret: &str) -> Result<Aes256Gcm> {
+    let key = Sha256::new()
+        .chain_update(b"github-mcp-server oauth state\0")
+        .chain_update(secret.as_bytes())
+        .finalize();
+    Aes256Gcm::new_from_slice(&key).map_err(|e| AppError::Internal(format!("Invalid OAuth state key: {}", e)))
+}
+
+fn client_fingerprint(headers: &HeaderMap) -> String {
+    let user_agent = headers.get(header::USER_AGENT).and_then(|v| v.to_str().ok()).unwrap_or_default();
+    hex::encode(&Sha256::digest(user_agent.as_bytes())[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_local_path;
+
+    #[test]
+    fn accepts_only_paths_on_this_server() {
+        assert!(is_local_path("/"));
+        assert!(is_local_path("/dashboard?tab=tasks"));
+        assert!(!is_local_path("https://evil.com"));
+        assert!(!is_local_path("//evil.com"));
+        assert!(!is_local_path("/\\evil.com"));
+        assert!(!is_local_path("/\t/evil.com"));
+        assert!(!is_local_path("/\n/evil.com"));
+        assert!(!is_local_path(""));
+    }
+}
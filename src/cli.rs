@@ -0,0 +1,107 @@
+// Command-line interface. With no subcommand the binary serves, as it always
+// has; the others run one operational task and exit.
+
+use clap::{Parser, Subcommand};
+
+use crate::config::Config;
+use crate::{git, security, tls};
+
+type CliResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+#[derive(Parser)]
+#[command(name = "github-mcp-server", version, about = "Secure GitHub MCP server with workflow automation")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run the HTTP(S) server (the default)
+    Serve,
+    /// Apply pending database migrations and exit
+    Migrate,
+    /// Serve MCP over stdin and stdout, one JSON-RPC message per line
+    Stdio,
+    /// Validate configuration, keys, certificates and the database, then exit
+    CheckConfig,
+    /// Maintain stored GitHub tokens
+    Token {
+        #[command(subcommand)]
+        command: TokenCommand,
+    },
+    /// Write users, GitHub tokens and workflow history to an archive
+    Export {
+        path: String,
+        #[arg(long, env = "TRANSFER_KEY", hide_env_values = true)]
+        transfer_key: String,
+    },
+    /// Load an archive written by `export`
+    Import {
+        path: String,
+        #[arg(long, env = "TRANSFER_KEY", hide_env_values = true)]
+        transfer_key: String,
+    },
+    /// Manage a running server through its /admin API
+    #[command(disable_help_flag = true)]
+    Admin {
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TokenCommand {
+    /// Re-encrypt every stored token under TOKEN_ENCRYPTION_KEY
+    EncryptRotate,
+}
+
+/// `check-config`: everything startup would reject, reported before a deploy
+pub async fn check_config() -> CliResult<()> {
+    crate::secrets::init().await?;
+    let config = Config::load()?;
+    match &config.secrets {
+        Some(secrets) => println!("ok  environment, with secrets from {}", secrets.backend.name()),
+        None => println!("ok  environment"),
+    }
+
+    security::jwt::JwtKeys::from_config(&config)?;
+    println!("ok  session token keys ({})", config.security.jwt_algorithm);
+
+    security::proxies::TrustedProxies::from_config(&config.security)?;
+    match config.security.trusted_proxies.len() {
+        0 => println!("ok  no trusted proxies; forwarding headers are ignored"),
+        n => println!("ok  {} trusted proxies", n),
+    }
+
+    security::tokens::init(&config.security)?;
+    match config.security.token_encryption_key {
+        Some(_) => println!(
+            "ok  token encryption ({} retired keys)",
+            config.security.token_encryption_previous_keys.len()
+        ),
+        None => println!("ok  token encryption disabled; set TOKEN_ENCRYPTION_KEY to encrypt stored tokens"),
+    }
+
+    if let Some(tls_config) = &config.tls {
+        tls::check(tls_config)?;
+        println!("ok  TLS certificate {}", tls_config.cert_path);
+    }
+
+    git::init(&config.workflow.git_backend, git::Timeouts::from_config(&config.workflow))?;
+    println!("ok  git backend {}", config.workflow.git_backend);
+
+    let db = sqlx::SqlitePool::connect(&config.database_url).await?;
+    let applied: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_migrations WHERE success = 1")
+        .fetch_one(&db)
+        .await
+        .unwrap_or(0);
+    let pending = sqlx::migrate!("./migrations").iter().count().saturating_sub(applied as usize);
+    match pending {
+        0 => println!("ok  database {}", config.database_url),
+        n => println!("ok  database {} ({} migrations pending; run `migrate`)", config.database_url, n),
+    }
+
+    println!("Configuration is valid");
+    Ok(())
+}
@@ -2,14 +2,36 @@ use serde::{Deserialize, Serialize};
 use std::env;
 use thiserror::Error;
 
+use crate::github::host::GitHubHost;
+
+/// Read-only REST areas open to `github_rest` unless `REST_ALLOWED_PATHS` says otherwise
+const DEFAULT_REST_ALLOWED_PATHS: &str = "/repos/*/*/**,/orgs/*/**,/users/*/**,/rate_limit";
+
+/// Tools that run whole workflows or many GitHub calls, unless `EXPENSIVE_TOOLS` says otherwise
+const DEFAULT_EXPENSIVE_TOOLS: &str = "github_merge,github_scan_tasks,github_push,github_bulk_update_issues,github/merge,github/scan-tasks,github/push";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub host: String,
     pub port: u16,
+    /// HTTPS on `port`, when `TLS_CERT_PATH` and `TLS_KEY_PATH` are set
+    pub tls: Option<TlsConfig>,
+    /// State shared between replicas, when `REDIS_URL` is set
+    pub redis: Option<RedisConfig>,
+    /// Where secrets come from, when `SECRETS_BACKEND` is set
+    pub secrets: Option<SecretsConfig>,
     pub database_url: String,
     pub jwt_secret: String,
     pub github: GitHubConfig,
     pub security: SecurityConfig,
+    pub notifications: NotificationConfig,
+    pub workflow: WorkflowConfig,
+    /// Checkouts registered at startup from `REPOSITORIES`
+    pub repositories: Vec<RepositoryConfig>,
+    pub policy: PolicyConfig,
+    pub scheduler: SchedulerConfig,
+    /// Other instances whose tools are proxied, from `FEDERATION_PEERS`
+    pub federation: Vec<PeerConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,15 +39,262 @@ pub struct GitHubConfig {
     pub client_id: String,
     pub client_secret: String,
     pub redirect_uri: String,
-    pub api_base_url: String,
+    /// github.com or a GitHub Enterprise Server instance
+    pub host: GitHubHost,
+    pub project_owner: Option<String>,
+    /// Shared secret for verifying `/webhooks/github` deliveries
+    pub webhook_secret: Option<String>,
+    /// Limits on `github_graphql` documents
+    pub graphql_max_depth: usize,
+    pub graphql_max_nodes: u64,
+    /// Let `ADMIN_USERS` run mutations through `github_graphql`
+    pub graphql_mutations: bool,
+    /// Path patterns `github_rest` may GET; `*` is one segment, a trailing `**` the rest
+    pub rest_allowed_paths: Vec<String>,
+    /// Responses kept for conditional requests; 0 turns the cache off
+    pub cache_entries: usize,
+    /// Also keep cached responses in the database across restarts
+    pub cache_persist: bool,
+    /// Most times a GitHub request is sent before a retryable failure is returned
+    pub retry_max_attempts: u32,
+    /// Longest wait before a retry; failures asking for more are returned at once
+    pub retry_max_wait_seconds: u64,
+    /// Requests left in a rate-limit window below which requests are paced
+    pub rate_limit_reserve: u64,
+    /// Page size list calls request, up to GitHub's maximum of 100
+    pub per_page: u32,
+    /// Most items a paginated list call collects
+    pub max_list_items: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityConfig {
     pub rate_limit_requests_per_minute: u32,
+    /// Minutes a client IP's in-memory limiter is kept after its last request
+    pub rate_limit_idle_minutes: u64,
+    /// Most client IPs with an in-memory limiter; the least recently seen make way
+    pub rate_limit_max_entries: usize,
+    /// Addresses and CIDR ranges of reverse proxies whose forwarding headers are believed
+    pub trusted_proxies: Vec<String>,
+    /// Tool calls per minute for each signed-in user; 0 turns the limit off
+    pub user_rate_limit_per_minute: u32,
+    /// Calls per minute to `expensive_tools` for each signed-in user; 0 turns the limit off
+    pub expensive_tool_rate_limit_per_minute: u32,
+    /// Tools (names, optionally ending in `*`) counted against the expensive budget
+    pub expensive_tools: Vec<String>,
+    /// Seconds a WebSocket opened without a token has to send its `auth` message
+    pub ws_auth_timeout_seconds: u64,
+    /// WebSocket connections each user may hold open; 0 allows any number
+    pub ws_max_connections_per_user: usize,
+    /// Lifetime of a sign-in session, after which its refresh token stops working
     pub session_timeout_hours: u64,
+    /// Lifetime of each access token; clients refresh within the session
+    pub access_token_minutes: u64,
     pub max_token_age_days: u64,
     pub audit_log_enabled: bool,
+    /// Days audit log entries are kept; 0 keeps them forever
+    pub audit_retention_days: u64,
+    pub read_only: bool,
+    /// Refuse workflows on repositories an admin hasn't approved
+    pub repository_approval: bool,
+    /// Mutating tools (names, optionally ending in `*`) held for a human's approval
+    pub confirm_tools: Vec<String>,
+    /// `owner/name` globs of the only repositories the server may touch; empty allows all
+    pub allowed_repositories: Vec<String>,
+    /// GitHub logins allowed to use the /admin endpoints
+    pub admin_users: Vec<String>,
+    /// `iss` and `aud` set on session tokens and required when validating them
+    pub jwt_issuer: String,
+    pub jwt_audience: String,
+    /// HS256 signs with `JWT_SECRET`; RS256 and EdDSA with a PEM key pair
+    pub jwt_algorithm: String,
+    pub jwt_private_key_file: Option<String>,
+    /// Current public key first, then retired ones still accepted
+    pub jwt_public_key_files: Vec<String>,
+    /// Retired HS256 secrets still accepted for verification
+    pub jwt_previous_secrets: Vec<String>,
+    /// Passphrase GitHub tokens are encrypted under; unset stores them as they are
+    pub token_encryption_key: Option<String>,
+    /// Retired passphrases still accepted for decryption
+    pub token_encryption_previous_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    pub webhook_url: Option<String>,
+    pub reminder_days: i64,
+    pub reminder_interval_minutes: u64,
+    /// off, hourly or daily, per delivery channel
+    pub webhook_digest: String,
+    pub log_digest: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowConfig {
+    /// Commands run before merging; a repo's `.mcp-workflow.toml` takes precedence
+    pub merge_check_commands: Vec<String>,
+    pub merge_check_timeout_seconds: u64,
+    /// Fall back to default checks for the detected project type when none are configured
+    pub detect_default_checks: bool,
+    /// Refuse to merge PRs containing commits without a verified GPG/SSH signature
+    pub require_verified_commits: bool,
+    /// Per-branch scratch folder relative to the repo root; `{branch}` is substituted
+    pub work_folder_pattern: String,
+    /// `libgit2` (in-process) or `subprocess` (the git CLI)
+    pub git_backend: String,
+    /// Git commands are killed after this long; push, pull and clone get the network timeout
+    pub git_timeout_seconds: u64,
+    pub git_network_timeout_seconds: u64,
+    /// Where `github_wiki` keeps its clones of repository wikis
+    pub wiki_dir: String,
+    /// Directories `github_repo_create` can seed a new repository from
+    pub repo_templates_dir: String,
+    /// The only directory `github_repositories` can register checkouts from
+    pub checkouts_dir: String,
+    /// Deployment workflow dispatched by `github_promote`
+    pub promote_workflow: Option<String>,
+    /// Environments a tag is promoted through, in order
+    pub promote_environments: Vec<String>,
+    /// Largest Dependabot bump auto-merged: none, patch or minor
+    pub dependabot_auto_merge: String,
+    pub dependabot_merge_method: String,
+    /// Refuse to push commits that add lines matching a secret scan rule
+    pub secret_scan: bool,
+    /// How `github_sync_branch` brings a branch up to date: rebase or merge
+    pub sync_strategy: String,
+    /// Open a draft PR when a push finds the branch has none
+    pub auto_draft_pr: bool,
+    /// How `merge` merges: immediate, auto (GitHub auto-merge) or queue
+    pub merge_mode: String,
+    /// How often deferred merges are checked for having landed; 0 relies on webhooks
+    pub merge_poll_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerConfig {
+    /// Percentage of the GitHub rate limit kept free of normal background jobs
+    pub reserve_percent: u8,
+    /// Percentage kept free of low-priority jobs such as reminders
+    pub low_priority_reserve_percent: u8,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyConfig {
+    /// Topics every org repository must carry; `team-*` matches any `team-` topic
+    pub required_topics: Vec<String>,
+    pub require_description: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryConfig {
+    pub name: String,
+    pub path: String,
+    /// `owner/repo`; read from the checkout's origin remote when omitted
+    pub full_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// PEM certificate chain, leaf first
+    pub cert_path: String,
+    /// PEM private key (PKCS#8, RSA or EC)
+    pub key_path: String,
+    /// How often to check the files for a rotated certificate; 0 never does
+    pub reload_seconds: u64,
+    /// Plain HTTP port that redirects to HTTPS
+    pub redirect_port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedisConfig {
+    pub url: String,
+    /// Keep per-IP rate-limit counters in Redis (`RATE_LIMIT_BACKEND=redis`)
+    pub rate_limits: bool,
+    /// Keep `POST /mcp` sessions in Redis (`SESSION_BACKEND=redis`)
+    pub sessions: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretsConfig {
+    pub backend: SecretsBackend,
+    /// How often to fetch the secret again; 0 reads it once at startup
+    pub refresh_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SecretsBackend {
+    /// KV secret at `path`, e.g. `secret/data/github-mcp-server`
+    Vault { addr: String, token: String, path: String },
+    /// Secrets Manager secret name or ARN
+    Aws { secret_id: String },
+}
+
+impl SecretsBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Vault { .. } => "vault",
+            Self::Aws { .. } => "aws",
+        }
+    }
+
+    /// Cargo feature the backend is compiled in with
+    pub fn feature(&self) -> &'static str {
+        match self {
+            Self::Vault { .. } => "vault",
+            Self::Aws { .. } => "aws-secrets",
+        }
+    }
+}
+
+impl SecretsConfig {
+    /// Read on its own, since the secrets it points at are needed to load the rest
+    pub fn from_env() -> Result<Option<Self>, ConfigError> {
+        dotenvy::dotenv().ok();
+        let required = |name: &str| {
+            env::var(name)
+                .ok()
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| ConfigError::MissingEnvVar(name.to_string()))
+        };
+
+        let backend = match env::var("SECRETS_BACKEND").unwrap_or_default().as_str() {
+            "" | "env" => return Ok(None),
+            "vault" => SecretsBackend::Vault {
+                addr: required("VAULT_ADDR")?,
+                token: required("VAULT_TOKEN")?,
+                path: required("VAULT_SECRET_PATH")?,
+            },
+            "aws" => SecretsBackend::Aws { secret_id: required("AWS_SECRET_ID")? },
+            other => {
+                return Err(ConfigError::ParseError(format!(
+                    "Invalid SECRETS_BACKEND: {} (expected env, vault or aws)",
+                    other
+                )))
+            }
+        };
+
+        Ok(Some(Self {
+            backend,
+            refresh_seconds: env::var("SECRETS_REFRESH_SECONDS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .map_err(|e| ConfigError::ParseError(format!("Invalid secrets refresh interval: {}", e)))?,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerConfig {
+    /// Prefix of the peer's tools here, e.g. `ghes` for `ghes__github_search_code`
+    pub name: String,
+    /// The peer's `POST /mcp` endpoint
+    pub url: String,
+    /// Bearer token sent with every request to the peer
+    pub token: Option<String>,
+    /// Tool names to proxy; a trailing `*` matches a prefix
+    pub tools: Vec<String>,
+    /// Whether the peer's resources can be read through `federated://`
+    pub resources: bool,
 }
 
 #[derive(Error, Debug)]
@@ -47,6 +316,10 @@ impl Config {
                 .parse()
                 .map_err(|e| ConfigError::ParseError(format!("Invalid port: {}", e)))?,
             
+            tls: parse_tls()?,
+            redis: parse_redis()?,
+            secrets: SecretsConfig::from_env()?,
+            
             database_url: env::var("DATABASE_URL")
                 .unwrap_or_else(|_| "sqlite:./data/github-mcp-server.db".to_string()),
             
@@ -60,8 +333,59 @@ impl Config {
                     .map_err(|_| ConfigError::MissingEnvVar("GITHUB_CLIENT_SECRET".to_string()))?,
                 redirect_uri: env::var("GITHUB_REDIRECT_URI")
                     .unwrap_or_else(|_| "https://localhost:8443/auth/github/callback".to_string()),
-                api_base_url: env::var("GITHUB_API_BASE_URL")
-                    .unwrap_or_else(|_| "https://api.github.com".to_string()),
+                host: GitHubHost::new(
+                    env::var("GITHUB_HOST").ok().filter(|h| !h.is_empty()).as_deref(),
+                    env::var("GITHUB_API_BASE_URL").ok().filter(|u| !u.is_empty()).as_deref(),
+                ),
+                project_owner: env::var("GITHUB_PROJECT_OWNER").ok().filter(|o| !o.is_empty()),
+                webhook_secret: env::var("GITHUB_WEBHOOK_SECRET").ok().filter(|s| !s.is_empty()),
+                cache_entries: env::var("GITHUB_CACHE_ENTRIES")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid GitHub cache size: {}", e)))?,
+                cache_persist: env::var("GITHUB_CACHE_PERSIST")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid GitHub cache persistence setting: {}", e)))?,
+                retry_max_attempts: env::var("GITHUB_RETRY_MAX_ATTEMPTS")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid GitHub retry attempts: {}", e)))?,
+                retry_max_wait_seconds: env::var("GITHUB_RETRY_MAX_WAIT_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid GitHub retry wait: {}", e)))?,
+                rate_limit_reserve: env::var("GITHUB_RATE_LIMIT_RESERVE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid GitHub rate limit reserve: {}", e)))?,
+                per_page: env::var("GITHUB_PER_PAGE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid GitHub page size: {}", e)))?,
+                max_list_items: env::var("GITHUB_MAX_LIST_ITEMS")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid GitHub list item cap: {}", e)))?,
+                graphql_max_depth: env::var("GRAPHQL_MAX_DEPTH")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid GraphQL depth limit: {}", e)))?,
+                graphql_max_nodes: env::var("GRAPHQL_MAX_NODES")
+                    .unwrap_or_else(|_| "10000".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid GraphQL node limit: {}", e)))?,
+                graphql_mutations: env::var("GRAPHQL_MUTATIONS")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid GraphQL mutations setting: {}", e)))?,
+                rest_allowed_paths: env::var("REST_ALLOWED_PATHS")
+                    .unwrap_or_else(|_| DEFAULT_REST_ALLOWED_PATHS.to_string())
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(String::from)
+                    .collect(),
             },
             
             security: SecurityConfig {
@@ -69,10 +393,52 @@ impl Config {
                     .unwrap_or_else(|_| "60".to_string())
                     .parse()
                     .map_err(|e| ConfigError::ParseError(format!("Invalid rate limit: {}", e)))?,
+                rate_limit_idle_minutes: env::var("RATE_LIMIT_IDLE_MINUTES")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid rate limiter idle time: {}", e)))?,
+                rate_limit_max_entries: env::var("RATE_LIMIT_MAX_ENTRIES")
+                    .unwrap_or_else(|_| "10000".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid rate limiter cap: {}", e)))?,
+                trusted_proxies: env::var("TRUSTED_PROXIES")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(String::from)
+                    .collect(),
+                user_rate_limit_per_minute: env::var("USER_RATE_LIMIT_RPM")
+                    .unwrap_or_else(|_| "120".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid user rate limit: {}", e)))?,
+                expensive_tool_rate_limit_per_minute: env::var("EXPENSIVE_TOOL_RATE_LIMIT_RPM")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid expensive tool rate limit: {}", e)))?,
+                expensive_tools: env::var("EXPENSIVE_TOOLS")
+                    .unwrap_or_else(|_| DEFAULT_EXPENSIVE_TOOLS.to_string())
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect(),
+                ws_auth_timeout_seconds: env::var("WS_AUTH_TIMEOUT_SECONDS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid WebSocket auth timeout: {}", e)))?,
+                ws_max_connections_per_user: env::var("WS_MAX_CONNECTIONS_PER_USER")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid WebSocket connection limit: {}", e)))?,
                 session_timeout_hours: env::var("SESSION_TIMEOUT_HOURS")
                     .unwrap_or_else(|_| "24".to_string())
                     .parse()
                     .map_err(|e| ConfigError::ParseError(format!("Invalid session timeout: {}", e)))?,
+                access_token_minutes: env::var("ACCESS_TOKEN_MINUTES")
+                    .unwrap_or_else(|_| "15".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid access token lifetime: {}", e)))?,
                 max_token_age_days: env::var("MAX_TOKEN_AGE_DAYS")
                     .unwrap_or_else(|_| "30".to_string())
                     .parse()
@@ -81,9 +447,301 @@ impl Config {
                     .unwrap_or_else(|_| "true".to_string())
                     .parse()
                     .map_err(|e| ConfigError::ParseError(format!("Invalid audit log setting: {}", e)))?,
+                audit_retention_days: env::var("AUDIT_RETENTION_DAYS")
+                    .unwrap_or_else(|_| "90".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid audit retention: {}", e)))?,
+                read_only: env::var("READ_ONLY")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid read-only setting: {}", e)))?,
+                repository_approval: env::var("REPOSITORY_APPROVAL")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid repository approval setting: {}", e)))?,
+                confirm_tools: env::var("CONFIRM_TOOLS")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect(),
+                allowed_repositories: env::var("ALLOWED_REPOSITORIES")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(String::from)
+                    .collect(),
+                admin_users: env::var("ADMIN_USERS")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|u| !u.is_empty())
+                    .map(String::from)
+                    .collect(),
+                jwt_issuer: env::var("JWT_ISSUER").unwrap_or_else(|_| "github-mcp-server".to_string()),
+                jwt_audience: env::var("JWT_AUDIENCE").unwrap_or_else(|_| "github-mcp-server".to_string()),
+                jwt_algorithm: env::var("JWT_ALGORITHM").unwrap_or_else(|_| "HS256".to_string()),
+                jwt_private_key_file: env::var("JWT_PRIVATE_KEY_FILE").ok().filter(|p| !p.is_empty()),
+                jwt_public_key_files: env::var("JWT_PUBLIC_KEY_FILES")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(String::from)
+                    .collect(),
+                jwt_previous_secrets: env::var("JWT_PREVIOUS_SECRETS")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect(),
+                token_encryption_key: env::var("TOKEN_ENCRYPTION_KEY").ok().filter(|k| !k.is_empty()),
+                token_encryption_previous_keys: env::var("TOKEN_ENCRYPTION_PREVIOUS_KEYS")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect(),
+            },
+
+            notifications: NotificationConfig {
+                webhook_url: env::var("NOTIFY_WEBHOOK_URL").ok().filter(|u| !u.is_empty()),
+                reminder_days: env::var("REMINDER_DAYS")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid reminder days: {}", e)))?,
+                reminder_interval_minutes: env::var("REMINDER_INTERVAL_MINUTES")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid reminder interval: {}", e)))?,
+                webhook_digest: parse_digest_mode("NOTIFY_WEBHOOK_DIGEST")?,
+                log_digest: parse_digest_mode("NOTIFY_LOG_DIGEST")?,
+            },
+
+            workflow: WorkflowConfig {
+                merge_check_commands: env::var("MERGE_CHECK_COMMANDS")
+                    .unwrap_or_default()
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty())
+                    .map(String::from)
+                    .collect(),
+                merge_check_timeout_seconds: env::var("MERGE_CHECK_TIMEOUT_SECONDS")
+                    .unwrap_or_else(|_| "900".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid merge check timeout: {}", e)))?,
+                detect_default_checks: env::var("DETECT_DEFAULT_CHECKS")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid default checks flag: {}", e)))?,
+                require_verified_commits: env::var("REQUIRE_VERIFIED_COMMITS")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid verified commits requirement: {}", e)))?,
+                work_folder_pattern: env::var("WORK_FOLDER_PATTERN")
+                    .unwrap_or_else(|_| "work/{branch}".to_string()),
+                git_backend: env::var("GIT_BACKEND")
+                    .unwrap_or_else(|_| "libgit2".to_string()),
+                git_timeout_seconds: env::var("GIT_TIMEOUT_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid git timeout: {}", e)))?,
+                git_network_timeout_seconds: env::var("GIT_NETWORK_TIMEOUT_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid git network timeout: {}", e)))?,
+                wiki_dir: env::var("WIKI_DIR")
+                    .unwrap_or_else(|_| "./data/wikis".to_string()),
+                repo_templates_dir: env::var("REPO_TEMPLATES_DIR")
+                    .unwrap_or_else(|_| "./templates".to_string()),
+                checkouts_dir: env::var("CHECKOUTS_DIR")
+                    .unwrap_or_else(|_| "./data/checkouts".to_string()),
+                promote_workflow: env::var("PROMOTE_WORKFLOW").ok().filter(|w| !w.is_empty()),
+                promote_environments: env::var("PROMOTE_ENVIRONMENTS")
+                    .unwrap_or_else(|_| "staging,production".to_string())
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|e| !e.is_empty())
+                    .map(String::from)
+                    .collect(),
+                dependabot_auto_merge: env::var("DEPENDABOT_AUTO_MERGE")
+                    .unwrap_or_else(|_| "patch".to_string()),
+                dependabot_merge_method: env::var("DEPENDABOT_MERGE_METHOD")
+                    .unwrap_or_else(|_| "squash".to_string()),
+                secret_scan: env::var("SECRET_SCAN")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid secret scan flag: {}", e)))?,
+                sync_strategy: env::var("SYNC_STRATEGY")
+                    .unwrap_or_else(|_| "rebase".to_string()),
+                auto_draft_pr: env::var("AUTO_DRAFT_PR")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid auto draft PR flag: {}", e)))?,
+                merge_mode: parse_merge_mode()?,
+                merge_poll_seconds: env::var("MERGE_POLL_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid merge poll interval: {}", e)))?,
+            },
+
+            repositories: parse_repositories(&env::var("REPOSITORIES").unwrap_or_default())?,
+
+            federation: parse_peers(&env::var("FEDERATION_PEERS").unwrap_or_default())?,
+
+            scheduler: SchedulerConfig {
+                reserve_percent: env::var("SCHEDULER_RESERVE_PERCENT")
+                    .unwrap_or_else(|_| "20".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid scheduler reserve: {}", e)))?,
+                low_priority_reserve_percent: env::var("SCHEDULER_LOW_PRIORITY_RESERVE_PERCENT")
+                    .unwrap_or_else(|_| "50".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid low-priority scheduler reserve: {}", e)))?,
+            },
+
+            policy: PolicyConfig {
+                required_topics: env::var("REQUIRED_TOPICS")
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect(),
+                require_description: env::var("REQUIRE_REPO_DESCRIPTION")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid description requirement: {}", e)))?,
             },
         };
 
         Ok(config)
     }
-}
\ No newline at end of file
+}
+
+fn parse_digest_mode(var: &str) -> Result<String, ConfigError> {
+    let mode = env::var(var).unwrap_or_else(|_| "off".to_string()).to_lowercase();
+    match mode.as_str() {
+        "off" | "hourly" | "daily" => Ok(mode),
+        _ => Err(ConfigError::ParseError(format!("Invalid {}: {} (expected off, hourly or daily)", var, mode))),
+    }
+}
+
+fn parse_merge_mode() -> Result<String, ConfigError> {
+    let mode = env::var("MERGE_MODE").unwrap_or_else(|_| "immediate".to_string()).to_lowercase();
+    match mode.as_str() {
+        "immediate" | "auto" | "queue" => Ok(mode),
+        _ => Err(ConfigError::ParseError(format!("Invalid MERGE_MODE: {} (expected immediate, auto or queue)", mode))),
+    }
+}
+
+/// Parse `name=/path[@owner/repo]` entries separated by `;`
+fn parse_repositories(value: &str) -> Result<Vec<RepositoryConfig>, ConfigError> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (name, target) = entry
+                .split_once('=')
+                .ok_or_else(|| ConfigError::ParseError(format!("Invalid repository entry: {}", entry)))?;
+            let (path, full_name) = match target.rsplit_once('@') {
+                Some((path, full_name)) if full_name.contains('/') => (path, Some(full_name.trim().to_string())),
+                _ => (target, None),
+            };
+
+            Ok(RepositoryConfig {
+                name: name.trim().to_string(),
+                path: path.trim().to_string(),
+                full_name,
+            })
+        })
+        .collect()
+}
+
+/// TLS settings, when a certificate and key are both configured
+fn parse_tls() -> Result<Option<TlsConfig>, ConfigError> {
+    let cert_path = env::var("TLS_CERT_PATH").ok().filter(|p| !p.is_empty());
+    let key_path = env::var("TLS_KEY_PATH").ok().filter(|p| !p.is_empty());
+    let (cert_path, key_path) = match (cert_path, key_path) {
+        (Some(cert_path), Some(key_path)) => (cert_path, key_path),
+        (None, None) => return Ok(None),
+        (Some(_), None) => return Err(ConfigError::MissingEnvVar("TLS_KEY_PATH".to_string())),
+        (None, Some(_)) => return Err(ConfigError::MissingEnvVar("TLS_CERT_PATH".to_string())),
+    };
+
+    Ok(Some(TlsConfig {
+        cert_path,
+        key_path,
+        reload_seconds: env::var("TLS_RELOAD_SECONDS")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse()
+            .map_err(|e| ConfigError::ParseError(format!("Invalid TLS reload interval: {}", e)))?,
+        redirect_port: env::var("HTTP_REDIRECT_PORT")
+            .ok()
+            .filter(|p| !p.is_empty())
+            .map(|p| p.parse())
+            .transpose()
+            .map_err(|e| ConfigError::ParseError(format!("Invalid HTTP redirect port: {}", e)))?,
+    }))
+}
+
+fn parse_redis() -> Result<Option<RedisConfig>, ConfigError> {
+    let backend = |name: &str| -> Result<bool, ConfigError> {
+        match env::var(name).unwrap_or_else(|_| "memory".to_string()).as_str() {
+            "memory" => Ok(false),
+            "redis" => Ok(true),
+            other => Err(ConfigError::ParseError(format!("Invalid {}: {} (expected memory or redis)", name, other))),
+        }
+    };
+    let rate_limits = backend("RATE_LIMIT_BACKEND")?;
+    let sessions = backend("SESSION_BACKEND")?;
+
+    match env::var("REDIS_URL").ok().filter(|u| !u.is_empty()) {
+        Some(url) => Ok(Some(RedisConfig { url, rate_limits, sessions })),
+        None if rate_limits || sessions => Err(ConfigError::MissingEnvVar("REDIS_URL".to_string())),
+        None => Ok(None),
+    }
+}
+
+/// Parse `name=url` entries separated by `;`. Each peer's token, tools and
+/// resource access come from `FEDERATION_TOKEN_<NAME>`,
+/// `FEDERATION_TOOLS_<NAME>` and `FEDERATION_RESOURCES_<NAME>`.
+fn parse_peers(value: &str) -> Result<Vec<PeerConfig>, ConfigError> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (name, url) = entry
+                .split_once('=')
+                .ok_or_else(|| ConfigError::ParseError(format!("Invalid federation peer: {}", entry)))?;
+            let name = name.trim().to_lowercase();
+            if name.is_empty() || name.contains("__") || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_')) {
+                return Err(ConfigError::ParseError(format!("Invalid federation peer name: {}", name)));
+            }
+            let suffix = name.to_uppercase().replace('-', "_");
+
+            Ok(PeerConfig {
+                url: url.trim().to_string(),
+                token: env::var(format!("FEDERATION_TOKEN_{}", suffix)).ok().filter(|t| !t.is_empty()),
+                tools: env::var(format!("FEDERATION_TOOLS_{}", suffix))
+                    .unwrap_or_else(|_| "*".to_string())
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect(),
+                resources: env::var(format!("FEDERATION_RESOURCES_{}", suffix))
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .map_err(|e| ConfigError::ParseError(format!("Invalid resource access for peer {}: {}", name, e)))?,
+                name,
+            })
+        })
+        .collect()
+}
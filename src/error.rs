@@ -26,6 +26,9 @@ pub enum AppError {
     #[error("GitHub API error: {0}")]
     GitHubApi(String),
     
+    #[error("GitHub API error: {0}")]
+    GitHub(#[from] crate::github::error::GitHubError),
+    
     #[error("MCP protocol error: {0}")]
     McpProtocol(String),
     
@@ -38,9 +41,18 @@ pub enum AppError {
     #[error("Rate limit exceeded")]
     RateLimit,
     
+    #[error("Server is in read-only mode: {0} is not allowed")]
+    ReadOnly(String),
+    
+    #[error("Repository {0} is awaiting admin approval")]
+    ApprovalRequired(String),
+    
     #[error("Validation error: {0}")]
     Validation(String),
     
+    #[error("Timed out: {0}")]
+    Timeout(String),
+    
     #[error("Configuration error: {0}")]
     Config(#[from] crate::config::ConfigError),
     
@@ -51,6 +63,10 @@ pub enum AppError {
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, error_message) = match &self {
+            AppError::GitHub(e) => (
+                StatusCode::from_u16(e.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY),
+                "GitHub API error",
+            ),
             AppError::Database(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
             AppError::HttpClient(_) => (StatusCode::BAD_GATEWAY, "External service error"),
             AppError::Json(_) => (StatusCode::BAD_REQUEST, "Invalid JSON"),
@@ -61,7 +77,10 @@ impl IntoResponse for AppError {
             AppError::Authentication(_) => (StatusCode::UNAUTHORIZED, "Authentication failed"),
             AppError::Authorization(_) => (StatusCode::FORBIDDEN, "Access denied"),
             AppError::RateLimit => (StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded"),
+            AppError::ReadOnly(_) => (StatusCode::FORBIDDEN, "Read-only mode"),
+            AppError::ApprovalRequired(_) => (StatusCode::FORBIDDEN, "Approval required"),
             AppError::Validation(_) => (StatusCode::BAD_REQUEST, "Validation error"),
+            AppError::Timeout(_) => (StatusCode::GATEWAY_TIMEOUT, "Operation timed out"),
             AppError::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Configuration error"),
             AppError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error"),
         };
@@ -0,0 +1,416 @@
+use std::path::{Path, PathBuf};
+
+use git2::{build::CheckoutBuilder, BranchType, Diff, DiffFormat, DiffOptions, IndexAddOption, Patch, Repository, Status, StatusOptions};
+
+use crate::error::{AppError, Result};
+use super::{FileDiffStat, GitBackend, SubprocessBackend, SyncOutcome, SyncStrategy, Timeouts};
+
+/// In-process git via libgit2. Network operations (push/pull/clone/fetch) are
+/// delegated to the CLI so SSH config and credential helpers keep working, as
+/// are rebases and merges, which libgit2 can't leave stopped on conflicts the
+/// way git does. Commits made here don't run hooks or signing; use the
+/// subprocess backend if you rely on them.
+pub struct Libgit2Backend {
+    network: SubprocessBackend,
+}
+
+impl Libgit2Backend {
+    pub fn new(timeouts: Timeouts) -> Self {
+        Self { network: SubprocessBackend::new(timeouts) }
+    }
+
+    fn open(&self, dir: &Path) -> Result<Repository> {
+        Repository::discover(dir).map_err(|_| AppError::Internal("Not inside a git repository".to_string()))
+    }
+
+    fn commit(&self, repo: &Repository, message: &str, allow_empty: bool) -> Result<()> {
+        let mut index = repo.index().map_err(git_error)?;
+        let tree_id = index.write_tree().map_err(git_error)?;
+        let tree = repo.find_tree(tree_id).map_err(git_error)?;
+
+        let parent = match repo.head() {
+            Ok(head) => Some(head.peel_to_commit().map_err(git_error)?),
+            Err(_) => None, // unborn branch
+        };
+
+        if !allow_empty && parent.as_ref().map_or(false, |p| p.tree_id() == tree_id) {
+            return Err(AppError::Internal("Nothing to commit".to_string()));
+        }
+
+        let signature = repo
+            .signature()
+            .map_err(|e| AppError::Internal(format!("Git user.name/user.email not configured: {}", e)))?;
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+            .map_err(git_error)?;
+
+        Ok(())
+    }
+
+    /// The index (`staged`) or the working tree against HEAD; an unborn
+    /// branch diffs against the empty tree
+    fn diff_against_head<'r>(&self, repo: &'r Repository, staged: bool) -> Result<Diff<'r>> {
+        let head = match repo.head() {
+            Ok(head) => Some(head.peel_to_tree().map_err(git_error)?),
+            Err(_) => None,
+        };
+        let mut options = DiffOptions::new();
+        options.include_untracked(false);
+
+        if staged {
+            repo.diff_tree_to_index(head.as_ref(), None, Some(&mut options)).map_err(git_error)
+        } else {
+            repo.diff_tree_to_workdir_with_index(head.as_ref(), Some(&mut options)).map_err(git_error)
+        }
+    }
+}
+
+impl Default for Libgit2Backend {
+    fn default() -> Self {
+        Self::new(Timeouts::default())
+    }
+}
+
+impl GitBackend for Libgit2Backend {
+    fn name(&self) -> &'static str {
+        "libgit2"
+    }
+
+    fn repo_root(&self, dir: &Path) -> Result<PathBuf> {
+        let repo = self.open(dir)?;
+        repo.workdir()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| AppError::Internal("Repository has no working tree".to_string()))
+    }
+
+    fn current_branch(&self, dir: &Path) -> Result<String> {
+        let repo = self.open(dir)?;
+        let head = repo.find_reference("HEAD").map_err(git_error)?;
+
+        // Works for unborn branches too; a detached HEAD has no symbolic target
+        Ok(head
+            .symbolic_target()
+            .and_then(|target| target.strip_prefix("refs/heads/"))
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    fn main_branch(&self, dir: &Path) -> Result<String> {
+        let repo = self.open(dir)?;
+        let branch = repo
+            .find_reference("refs/remotes/origin/HEAD")
+            .ok()
+            .and_then(|r| r.symbolic_target().map(String::from))
+            .and_then(|target| target.strip_prefix("refs/remotes/origin/").map(String::from));
+
+        Ok(branch.unwrap_or_else(|| "main".to_string()))
+    }
+
+    fn status(&self, dir: &Path) -> Result<Vec<String>> {
+        let repo = self.open(dir)?;
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).include_ignored(false);
+
+        let statuses = repo.statuses(Some(&mut options)).map_err(git_error)?;
+        Ok(statuses
+            .iter()
+            .filter_map(|entry| {
+                let path = entry.path()?.to_string();
+                Some(format!("{} {}", porcelain_code(entry.status()), path))
+            })
+            .collect())
+    }
+
+    fn commit_all(&self, dir: &Path, message: &str) -> Result<()> {
+        let repo = self.open(dir)?;
+        let mut index = repo.index().map_err(git_error)?;
+        index.add_all(["*"], IndexAddOption::DEFAULT, None).map_err(git_error)?;
+        // add_all doesn't stage deletions
+        index.update_all(["*"], None).map_err(git_error)?;
+        index.write().map_err(git_error)?;
+
+        self.commit(&repo, message, false)
+    }
+
+    fn commit_staged(&self, dir: &Path, message: &str) -> Result<()> {
+        let repo = self.open(dir)?;
+        self.commit(&repo, message, false)
+    }
+
+    fn diff(&self, dir: &Path, staged: bool) -> Result<String> {
+        let repo = self.open(dir)?;
+        let diff = self.diff_against_head(&repo, staged)?;
+        patch_text(&diff)
+    }
+
+    fn diff_stats(&self, dir: &Path, staged: bool) -> Result<Vec<FileDiffStat>> {
+        let repo = self.open(dir)?;
+        let diff = self.diff_against_head(&repo, staged)?;
+
+        let mut stats = Vec::new();
+        for (index, delta) in diff.deltas().enumerate() {
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .map(|path| path.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let binary = delta.flags().is_binary();
+            let (additions, deletions) = match Patch::from_diff(&diff, index).map_err(git_error)? {
+                Some(patch) if !binary => {
+                    let (_, additions, deletions) = patch.line_stats().map_err(git_error)?;
+                    (additions, deletions)
+                }
+                _ => (0, 0),
+            };
+            stats.push(FileDiffStat { path, additions, deletions, binary });
+        }
+
+        Ok(stats)
+    }
+
+    fn outgoing_diff(&self, dir: &Path, branch: &str) -> Result<String> {
+        let repo = self.open(dir)?;
+        let head = repo.head().map_err(git_error)?.peel_to_commit().map_err(git_error)?;
+        let remote = [format!("refs/remotes/origin/{}", branch), "refs/remotes/origin/HEAD".to_string()]
+            .iter()
+            .find_map(|name| repo.revparse_single(name).ok()?.peel_to_commit().ok());
+
+        let base = match remote {
+            Some(remote) => {
+                let merge_base = repo.merge_base(remote.id(), head.id()).map_err(git_error)?;
+                Some(repo.find_commit(merge_base).map_err(git_error)?.tree().map_err(git_error)?)
+            }
+            None => None,
+        };
+        let head_tree = head.tree().map_err(git_error)?;
+        let diff = repo.diff_tree_to_tree(base.as_ref(), Some(&head_tree), None).map_err(git_error)?;
+        patch_text(&diff)
+    }
+
+    fn fetch(&self, dir: &Path) -> Result<()> {
+        self.network.fetch(dir)
+    }
+
+    fn fetch_remote(&self, dir: &Path, remote: &str) -> Result<()> {
+        self.network.fetch_remote(dir, remote)
+    }
+
+    fn ahead_behind(&self, dir: &Path, upstream: &str) -> Result<(usize, usize)> {
+        let repo = self.open(dir)?;
+        let head = repo.head().map_err(git_error)?.peel_to_commit().map_err(git_error)?;
+        let upstream = repo.revparse_single(upstream).map_err(git_error)?.peel_to_commit().map_err(git_error)?;
+        repo.graph_ahead_behind(head.id(), upstream.id()).map_err(git_error)
+    }
+
+    fn integrate(&self, dir: &Path, upstream: &str, strategy: SyncStrategy) -> Result<SyncOutcome> {
+        self.network.integrate(dir, upstream, strategy)
+    }
+
+    fn sync_in_progress(&self, dir: &Path) -> Result<Option<SyncStrategy>> {
+        self.network.sync_in_progress(dir)
+    }
+
+    fn conflicted_files(&self, dir: &Path) -> Result<Vec<String>> {
+        let repo = self.open(dir)?;
+        let index = repo.index().map_err(git_error)?;
+        let conflicts = index.conflicts().map_err(git_error)?;
+
+        let mut paths = Vec::new();
+        for conflict in conflicts {
+            let conflict = conflict.map_err(git_error)?;
+            let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+            if let Some(entry) = entry {
+                paths.push(String::from_utf8_lossy(&entry.path).into_owned());
+            }
+        }
+        Ok(paths)
+    }
+
+    fn continue_sync(&self, dir: &Path, strategy: SyncStrategy) -> Result<SyncOutcome> {
+        self.network.continue_sync(dir, strategy)
+    }
+
+    fn abort_sync(&self, dir: &Path, strategy: SyncStrategy) -> Result<()> {
+        self.network.abort_sync(dir, strategy)
+    }
+
+    fn commit_empty(&self, dir: &Path, message: &str) -> Result<()> {
+        let repo = self.open(dir)?;
+        self.commit(&repo, message, true)
+    }
+
+    fn head_message(&self, dir: &Path) -> Result<String> {
+        let repo = self.open(dir)?;
+        let commit = repo.head().map_err(git_error)?.peel_to_commit().map_err(git_error)?;
+        Ok(String::from_utf8_lossy(commit.message_bytes()).trim().to_string())
+    }
+
+    fn push(&self, dir: &Path, branch: &str) -> Result<()> {
+        self.network.push(dir, branch)
+    }
+
+    fn pull(&self, dir: &Path, branch: &str) -> Result<()> {
+        self.network.pull(dir, branch)
+    }
+
+    fn checkout(&self, dir: &Path, branch: &str) -> Result<()> {
+        let repo = self.open(dir)?;
+
+        // Like `git checkout <branch>`, create a tracking branch from origin if needed
+        let local = match repo.find_branch(branch, BranchType::Local) {
+            Ok(local) => local,
+            Err(_) => {
+                let remote = repo
+                    .find_branch(&format!("origin/{}", branch), BranchType::Remote)
+                    .map_err(|_| AppError::Internal(format!("Git checkout failed: no branch named {}", branch)))?;
+                let commit = remote.get().peel_to_commit().map_err(git_error)?;
+                let mut local = repo.branch(branch, &commit, false).map_err(git_error)?;
+                local
+                    .set_upstream(Some(&format!("origin/{}", branch)))
+                    .map_err(git_error)?;
+                local
+            }
+        };
+
+        let refname = local
+            .get()
+            .name()
+            .ok_or_else(|| AppError::Internal("Invalid branch name".to_string()))?
+            .to_string();
+        let target = local.get().peel(git2::ObjectType::Commit).map_err(git_error)?;
+
+        repo.checkout_tree(&target, Some(CheckoutBuilder::new().safe()))
+            .map_err(|e| AppError::Internal(format!("Git checkout failed: {}", e)))?;
+        repo.set_head(&refname).map_err(git_error)?;
+        Ok(())
+    }
+
+    fn create_branch(&self, dir: &Path, branch: &str) -> Result<()> {
+        let repo = self.open(dir)?;
+        let head = repo.head().map_err(git_error)?.peel_to_commit().map_err(git_error)?;
+        let created = repo
+            .branch(branch, &head, false)
+            .map_err(|e| AppError::Internal(format!("Git branch creation failed: {}", e)))?;
+
+        let refname = created
+            .get()
+            .name()
+            .ok_or_else(|| AppError::Internal("Invalid branch name".to_string()))?
+            .to_string();
+        repo.set_head(&refname).map_err(git_error)?;
+        Ok(())
+    }
+
+    fn delete_branch(&self, dir: &Path, branch: &str) -> Result<()> {
+        let repo = self.open(dir)?;
+        let mut local = repo.find_branch(branch, BranchType::Local).map_err(git_error)?;
+        if local.is_head() {
+            return Err(AppError::Internal(format!("Cannot delete the checked-out branch {}", branch)));
+        }
+
+        // Same safety as `git branch -d`: the branch must be merged into HEAD
+        let branch_oid = local.get().peel_to_commit().map_err(git_error)?.id();
+        let head_oid = repo.head().map_err(git_error)?.peel_to_commit().map_err(git_error)?.id();
+        let merged = branch_oid == head_oid || repo.graph_descendant_of(head_oid, branch_oid).map_err(git_error)?;
+        if !merged {
+            return Err(AppError::Internal(format!("The branch {} is not fully merged", branch)));
+        }
+
+        local.delete().map_err(git_error)
+    }
+
+    fn remote_url(&self, dir: &Path, remote: &str) -> Result<String> {
+        let repo = self.open(dir)?;
+        let remote = repo
+            .find_remote(remote)
+            .map_err(|_| AppError::Internal("Git remote command failed".to_string()))?;
+        remote
+            .url()
+            .map(String::from)
+            .ok_or_else(|| AppError::Internal("Remote URL is not valid UTF-8".to_string()))
+    }
+
+    fn set_remote(&self, dir: &Path, remote: &str, url: &str) -> Result<()> {
+        let repo = self.open(dir)?;
+        if repo.find_remote(remote).is_ok() {
+            repo.remote_set_url(remote, url).map_err(git_error)
+        } else {
+            repo.remote(remote, url).map(|_| ()).map_err(git_error)
+        }
+    }
+
+    fn clone_repository(&self, url: &str, dest: &Path) -> Result<()> {
+        self.network.clone_repository(url, dest)
+    }
+
+    fn has_tracked_files(&self, dir: &Path, path: &Path) -> Result<bool> {
+        let repo = self.open(dir)?;
+        let root = repo
+            .workdir()
+            .ok_or_else(|| AppError::Internal("Repository has no working tree".to_string()))?;
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let prefix = format!("{}/", relative.to_string_lossy().trim_end_matches('/'));
+
+        let index = repo.index().map_err(git_error)?;
+        let tracked = index.iter().any(|entry| entry.path.starts_with(prefix.as_bytes()));
+        Ok(tracked)
+    }
+}
+
+/// `diff` as a unified patch, as `git diff` prints it
+fn patch_text(diff: &Diff) -> Result<String> {
+    let mut patch = Vec::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        // Content lines come without their +/- marker
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin() as u8);
+        }
+        patch.extend_from_slice(line.content());
+        true
+    })
+    .map_err(git_error)?;
+
+    Ok(String::from_utf8_lossy(&patch).into_owned())
+}
+
+fn porcelain_code(status: Status) -> String {
+    if status.is_wt_new() && !status.intersects(Status::INDEX_NEW) {
+        return "??".to_string();
+    }
+    if status.is_conflicted() {
+        return "UU".to_string();
+    }
+
+    let index = if status.is_index_new() {
+        'A'
+    } else if status.is_index_modified() {
+        'M'
+    } else if status.is_index_deleted() {
+        'D'
+    } else if status.is_index_renamed() {
+        'R'
+    } else if status.is_index_typechange() {
+        'T'
+    } else {
+        ' '
+    };
+
+    let worktree = if status.is_wt_modified() {
+        'M'
+    } else if status.is_wt_deleted() {
+        'D'
+    } else if status.is_wt_renamed() {
+        'R'
+    } else if status.is_wt_typechange() {
+        'T'
+    } else {
+        ' '
+    };
+
+    format!("{}{}", index, worktree)
+}
+
+fn git_error(e: git2::Error) -> AppError {
+    AppError::Internal(format!("Git error: {}", e.message()))
+}
@@ -0,0 +1,219 @@
+// Local git operations used by the workflows.
+//
+// Everything goes through the `GitBackend` trait so the libgit2 backend and
+// the `git` CLI backend are interchangeable. Calls are blocking, so async code
+// should go through `run`, which moves them onto the blocking thread pool and
+// targets the checkout selected with `scope` (the process CWD by default).
+
+#[cfg(feature = "libgit2")]
+mod libgit2;
+mod subprocess;
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use tracing::info;
+
+use crate::config::WorkflowConfig;
+use crate::error::{AppError, Result};
+
+#[cfg(feature = "libgit2")]
+pub use libgit2::Libgit2Backend;
+pub use subprocess::SubprocessBackend;
+
+pub trait GitBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Working tree root of the repository containing `dir`
+    fn repo_root(&self, dir: &Path) -> Result<PathBuf>;
+    /// Checked-out branch name; empty when HEAD is detached
+    fn current_branch(&self, dir: &Path) -> Result<String>;
+    /// Default branch of `origin`, falling back to `main`
+    fn main_branch(&self, dir: &Path) -> Result<String>;
+    /// Changed paths in `git status --porcelain` format
+    fn status(&self, dir: &Path) -> Result<Vec<String>>;
+    /// Stage everything and commit
+    fn commit_all(&self, dir: &Path, message: &str) -> Result<()>;
+    /// Commit what's staged, leaving the rest of the working tree alone
+    fn commit_staged(&self, dir: &Path, message: &str) -> Result<()>;
+    /// Unified diff of the index against HEAD (`staged`), or of the working
+    /// tree against HEAD; untracked files aren't included
+    fn diff(&self, dir: &Path, staged: bool) -> Result<String>;
+    /// Per-file line counts for the same diff as `diff`
+    fn diff_stats(&self, dir: &Path, staged: bool) -> Result<Vec<FileDiffStat>>;
+    /// What pushing `branch` would send: the diff from where HEAD left
+    /// `origin/<branch>` (or, for a new branch, the remote's default branch)
+    /// to HEAD, as last fetched
+    fn outgoing_diff(&self, dir: &Path, branch: &str) -> Result<String>;
+    /// Update the remote-tracking branches from `origin`
+    fn fetch(&self, dir: &Path) -> Result<()>;
+    /// Update the remote-tracking branches from any remote
+    fn fetch_remote(&self, dir: &Path, remote: &str) -> Result<()>;
+    /// Commits HEAD has that `upstream` doesn't, and the other way round
+    fn ahead_behind(&self, dir: &Path, upstream: &str) -> Result<(usize, usize)>;
+    /// Rebase HEAD onto `upstream`, or merge `upstream` into it
+    fn integrate(&self, dir: &Path, upstream: &str, strategy: SyncStrategy) -> Result<SyncOutcome>;
+    /// The rebase or merge stopped part way through, if there is one
+    fn sync_in_progress(&self, dir: &Path) -> Result<Option<SyncStrategy>>;
+    /// Paths with unresolved conflicts
+    fn conflicted_files(&self, dir: &Path) -> Result<Vec<String>>;
+    /// Stage the conflicted files as resolved and carry on
+    fn continue_sync(&self, dir: &Path, strategy: SyncStrategy) -> Result<SyncOutcome>;
+    fn abort_sync(&self, dir: &Path, strategy: SyncStrategy) -> Result<()>;
+    fn commit_empty(&self, dir: &Path, message: &str) -> Result<()>;
+    /// Full message of the commit HEAD points at
+    fn head_message(&self, dir: &Path) -> Result<String>;
+    fn push(&self, dir: &Path, branch: &str) -> Result<()>;
+    fn pull(&self, dir: &Path, branch: &str) -> Result<()>;
+    fn checkout(&self, dir: &Path, branch: &str) -> Result<()>;
+    /// Create a branch from HEAD and check it out
+    fn create_branch(&self, dir: &Path, branch: &str) -> Result<()>;
+    /// Delete a local branch, refusing if it isn't fully merged
+    fn delete_branch(&self, dir: &Path, branch: &str) -> Result<()>;
+    fn remote_url(&self, dir: &Path, remote: &str) -> Result<String>;
+    /// Add `remote`, or point it at `url` if it already exists
+    fn set_remote(&self, dir: &Path, remote: &str, url: &str) -> Result<()>;
+    /// Clone `url` into `dest`, which must not exist yet
+    fn clone_repository(&self, url: &str, dest: &Path) -> Result<()>;
+    /// Whether any file under `path` is tracked
+    fn has_tracked_files(&self, dir: &Path, path: &Path) -> Result<bool>;
+}
+
+/// Lines added and removed in one file of a diff
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct FileDiffStat {
+    pub path: String,
+    pub additions: usize,
+    pub deletions: usize,
+    /// Binary files have no line counts
+    pub binary: bool,
+}
+
+/// How a branch takes in its base's new commits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStrategy {
+    Rebase,
+    Merge,
+}
+
+impl SyncStrategy {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "rebase" => Ok(Self::Rebase),
+            "merge" => Ok(Self::Merge),
+            other => Err(AppError::Validation(format!("Unknown sync strategy {}: expected rebase or merge", other))),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rebase => "rebase",
+            Self::Merge => "merge",
+        }
+    }
+}
+
+/// Where a rebase or merge got to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncOutcome {
+    Done,
+    /// Stopped with conflicts for the caller to resolve
+    Conflicted,
+}
+
+/// How long a git subprocess may run before it's killed
+#[derive(Debug, Clone, Copy)]
+pub struct Timeouts {
+    pub local: Duration,
+    /// Push, pull, clone and anything else that talks to the remote
+    pub network: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            local: Duration::from_secs(60),
+            network: Duration::from_secs(300),
+        }
+    }
+}
+
+impl Timeouts {
+    pub fn from_config(config: &WorkflowConfig) -> Self {
+        Self {
+            local: Duration::from_secs(config.git_timeout_seconds),
+            network: Duration::from_secs(config.git_network_timeout_seconds),
+        }
+    }
+}
+
+static BACKEND: OnceLock<Arc<dyn GitBackend>> = OnceLock::new();
+
+tokio::task_local! {
+    static WORKDIR: PathBuf;
+}
+
+/// Select the backend by name (`libgit2` or `subprocess`). Call once at startup;
+/// without it the default backend is used.
+pub fn init(name: &str, timeouts: Timeouts) -> Result<()> {
+    let backend: Arc<dyn GitBackend> = match name {
+        #[cfg(feature = "libgit2")]
+        "libgit2" => Arc::new(Libgit2Backend::new(timeouts)),
+        "subprocess" | "cli" => Arc::new(SubprocessBackend::new(timeouts)),
+        other => return Err(AppError::Validation(format!("Unsupported git backend: {}", other))),
+    };
+
+    info!("Using {} git backend", backend.name());
+    BACKEND
+        .set(backend)
+        .map_err(|_| AppError::Internal("Git backend already initialised".to_string()))
+}
+
+pub fn backend() -> Arc<dyn GitBackend> {
+    BACKEND.get_or_init(default_backend).clone()
+}
+
+#[cfg(feature = "libgit2")]
+fn default_backend() -> Arc<dyn GitBackend> {
+    Arc::new(Libgit2Backend::new(Timeouts::default()))
+}
+
+#[cfg(not(feature = "libgit2"))]
+fn default_backend() -> Arc<dyn GitBackend> {
+    Arc::new(SubprocessBackend::new(Timeouts::default()))
+}
+
+/// Run `future` with git operations targeting the checkout at `workdir`
+pub async fn scope<F: Future>(workdir: Option<PathBuf>, future: F) -> F::Output {
+    match workdir {
+        Some(workdir) => WORKDIR.scope(workdir, future).await,
+        None => future.await,
+    }
+}
+
+/// Checkout the current task operates on
+pub fn workdir() -> PathBuf {
+    WORKDIR
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| PathBuf::from("."))
+}
+
+/// Run a git operation on the blocking pool against the current checkout
+pub async fn run<T, F>(operation: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&dyn GitBackend, &Path) -> Result<T> + Send + 'static,
+{
+    let backend = backend();
+    let dir = workdir();
+    let cancel = crate::mcp::cancellation::current();
+    let span = tracing::Span::current();
+    tokio::task::spawn_blocking(move || {
+        span.in_scope(|| crate::mcp::cancellation::blocking(cancel, || operation(backend.as_ref(), &dir)))
+    })
+        .await
+        .map_err(|e| AppError::Internal(format!("Git task failed: {}", e)))?
+}
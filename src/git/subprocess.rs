@@ -0,0 +1,558 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+use tracing::{debug, warn};
+
+use crate::error::{AppError, Result};
+use crate::mcp::cancellation;
+use crate::process;
+use super::{FileDiffStat, GitBackend, SyncOutcome, SyncStrategy, Timeouts};
+
+/// git's empty tree, the base of the outgoing diff when the remote has nothing to compare with
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// How often a running git command checks whether its request was cancelled
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// How long each git command that cleans up after a killed one may take
+const RECOVER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Shells out to the `git` CLI; needs git on PATH but honours hooks,
+/// signing and credential helpers exactly as the user's git does
+pub struct SubprocessBackend {
+    timeouts: Timeouts,
+}
+
+impl SubprocessBackend {
+    pub fn new(timeouts: Timeouts) -> Self {
+        Self { timeouts }
+    }
+
+    fn git(&self, dir: &Path, args: &[&str], action: &str) -> Result<std::process::Output> {
+        self.run(dir, args, action, self.timeouts.local)
+    }
+
+    /// A command that talks to the remote, under the network timeout
+    fn git_remote(&self, dir: &Path, args: &[&str], action: &str) -> Result<std::process::Output> {
+        self.run(dir, args, action, self.timeouts.network)
+    }
+
+    fn git_dir(&self, dir: &Path) -> Result<PathBuf> {
+        let output = self.git(dir, &["rev-parse", "--git-dir"], "find the git directory")?;
+        if !output.status.success() {
+            return Err(AppError::Internal("Not inside a git repository".to_string()));
+        }
+
+        Ok(dir.join(String::from_utf8_lossy(&output.stdout).trim()))
+    }
+
+    /// What `diff` compares the working tree or index with: HEAD, or the
+    /// empty tree on an unborn branch
+    fn diff_base(&self, dir: &Path, staged: bool) -> Result<&'static str> {
+        if staged {
+            return Ok("--staged");
+        }
+        let output = self.git(dir, &["rev-parse", "--verify", "--quiet", "HEAD"], "find HEAD")?;
+        Ok(if output.status.success() { "HEAD" } else { EMPTY_TREE })
+    }
+
+    /// A rebase or merge that exited non-zero either stopped on conflicts,
+    /// leaving them for the caller, or failed outright
+    fn sync_outcome(&self, dir: &Path, output: Output, action: &str) -> Result<SyncOutcome> {
+        if output.status.success() {
+            return Ok(SyncOutcome::Done);
+        }
+        if self.sync_in_progress(dir)?.is_some() && !self.conflicted_files(dir)?.is_empty() {
+            return Ok(SyncOutcome::Conflicted);
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Err(AppError::Internal(format!("Git {} failed: {}", action, if stderr.trim().is_empty() { stdout } else { stderr })))
+    }
+
+    fn run(&self, dir: &Path, args: &[&str], action: &str, timeout: Duration) -> Result<std::process::Output> {
+        debug!("git {} (in {})", args.join(" "), dir.display());
+        let started = SystemTime::now();
+        // Nothing here can answer an editor, and GIT_EDITOR in the server's
+        // own environment would outrank `core.editor`
+        let mut command = Command::new("git");
+        command.args(args).current_dir(dir).env("GIT_EDITOR", "true");
+        let result = output(&mut command, action, timeout);
+        if interrupted(&result) {
+            recover(dir, started);
+        }
+        result
+    }
+}
+
+/// Like `Command::output`, but kills the child and everything it started
+/// when the request it serves is cancelled or `timeout` passes
+fn output(command: &mut Command, action: &str, timeout: Duration) -> Result<Output> {
+    supervise(command, action, timeout, true)
+}
+
+/// `output`, ignoring cancellation unless `cancellable`
+fn supervise(command: &mut Command, action: &str, timeout: Duration, cancellable: bool) -> Result<Output> {
+    let started = Instant::now();
+    let mut child = process::isolate(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::Internal(format!("Failed to {}: {}", action, e)))?;
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let status = loop {
+        let finished = child
+            .try_wait()
+            .map_err(|e| AppError::Internal(format!("Failed to {}: {}", action, e)))?;
+        if let Some(status) = finished {
+            break status;
+        }
+
+        let cancelled = cancellable && cancellation::is_cancelled();
+        if cancelled || started.elapsed() >= timeout {
+            process::kill_group(child.id());
+            let _ = child.kill();
+            let _ = child.wait();
+            if cancelled {
+                warn!("Killed git to {}: request cancelled", action);
+                return Err(AppError::Internal(format!("Cancelled while trying to {}", action)));
+            }
+            warn!("Killed git to {}: still running after {}s", action, timeout.as_secs());
+            return Err(AppError::Timeout(format!(
+                "git did not {} within {}s and was stopped",
+                action,
+                timeout.as_secs()
+            )));
+        }
+        std::thread::sleep(CANCEL_POLL_INTERVAL);
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Whether `output` killed the command rather than letting it finish
+fn interrupted(result: &Result<Output>) -> bool {
+    matches!(result, Err(AppError::Timeout(_))) || (result.is_err() && cancellation::is_cancelled())
+}
+
+/// Refuse a remote name git would read as an option or not accept at all
+fn check_remote(remote: &str) -> Result<()> {
+    let valid = !remote.is_empty()
+        && !remote.starts_with('-')
+        && remote.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::Validation(format!("Invalid remote name '{}'", remote)))
+    }
+}
+
+/// Undo what a killed command left half done in the checkout at `dir`: its
+/// index lock, and the merge of a pull killed part way through. Only state
+/// created after `started` is touched, as anything older isn't ours. It runs
+/// once the request is already cancelled, so only its own timeout stops it.
+fn recover(dir: &Path, started: SystemTime) {
+    let git = |args: &[&str], action: &str| {
+        supervise(Command::new("git").args(args).current_dir(dir).env("GIT_EDITOR", "true"), action, RECOVER_TIMEOUT, false)
+    };
+    let Ok(git_dir) = git(&["rev-parse", "--git-dir"], "find the git directory") else {
+        return;
+    };
+    let git_dir = dir.join(String::from_utf8_lossy(&git_dir.stdout).trim());
+    let ours = |path: &Path| {
+        std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map_or(false, |modified| modified >= started)
+    };
+
+    let lock = git_dir.join("index.lock");
+    if ours(&lock) {
+        warn!("Removing {} left by a killed git command", lock.display());
+        let _ = std::fs::remove_file(&lock);
+    }
+    if ours(&git_dir.join("MERGE_HEAD")) {
+        warn!("Aborting the merge left by a killed git command in {}", dir.display());
+        let _ = git(&["merge", "--abort"], "abort the merge");
+    }
+}
+
+/// Read a pipe to the end on its own thread so the child never blocks on a full pipe
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+impl GitBackend for SubprocessBackend {
+    fn name(&self) -> &'static str {
+        "subprocess"
+    }
+
+    fn repo_root(&self, dir: &Path) -> Result<PathBuf> {
+        let output = self.git(dir, &["rev-parse", "--show-toplevel"], "find repository root")?;
+        if !output.status.success() {
+            return Err(AppError::Internal("Not inside a git repository".to_string()));
+        }
+
+        Ok(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+    }
+
+    fn current_branch(&self, dir: &Path) -> Result<String> {
+        let output = self.git(dir, &["branch", "--show-current"], "get current branch")?;
+        if !output.status.success() {
+            return Err(AppError::Internal("Git command failed".to_string()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn main_branch(&self, dir: &Path) -> Result<String> {
+        let output = self.git_remote(dir, &["remote", "show", "origin"], "get main branch")?;
+        if !output.status.success() {
+            return Ok("main".to_string()); // Default fallback
+        }
+
+        let output_str = String::from_utf8_lossy(&output.stdout);
+        for line in output_str.lines() {
+            if line.contains("HEAD branch:") {
+                if let Some(branch) = line.split(':').nth(1) {
+                    return Ok(branch.trim().to_string());
+                }
+            }
+        }
+
+        Ok("main".to_string()) // Default fallback
+    }
+
+    fn status(&self, dir: &Path) -> Result<Vec<String>> {
+        let output = self.git(dir, &["status", "--porcelain"], "get git status")?;
+        if !output.status.success() {
+            return Err(AppError::Internal("Git status command failed".to_string()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.to_string())
+            .collect())
+    }
+
+    fn commit_all(&self, dir: &Path, message: &str) -> Result<()> {
+        let add_output = self.git(dir, &["add", "."], "add changes")?;
+        if !add_output.status.success() {
+            return Err(AppError::Internal("Git add command failed".to_string()));
+        }
+
+        let commit_output = self.git(dir, &["commit", "-m", message], "commit changes")?;
+        if !commit_output.status.success() {
+            return Err(AppError::Internal("Git commit command failed".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn commit_staged(&self, dir: &Path, message: &str) -> Result<()> {
+        let output = self.git(dir, &["commit", "-m", message], "commit staged changes")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git commit failed: {}", stderr)));
+        }
+
+        Ok(())
+    }
+
+    fn diff(&self, dir: &Path, staged: bool) -> Result<String> {
+        let against = self.diff_base(dir, staged)?;
+        let output = self.git(dir, &["diff", "--no-color", "--no-ext-diff", against], "diff changes")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git diff failed: {}", stderr)));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn diff_stats(&self, dir: &Path, staged: bool) -> Result<Vec<FileDiffStat>> {
+        let against = self.diff_base(dir, staged)?;
+        let output = self.git(dir, &["diff", "--numstat", "--no-renames", against], "count changed lines")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git diff failed: {}", stderr)));
+        }
+
+        // `<added>\t<deleted>\t<path>`, with `-` for both counts on binary files
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, '\t');
+                let (added, deleted, path) = (fields.next()?, fields.next()?, fields.next()?);
+                Some(FileDiffStat {
+                    path: path.to_string(),
+                    additions: added.parse().unwrap_or(0),
+                    deletions: deleted.parse().unwrap_or(0),
+                    binary: added == "-",
+                })
+            })
+            .collect())
+    }
+
+    fn outgoing_diff(&self, dir: &Path, branch: &str) -> Result<String> {
+        let upstream = format!("origin/{}", branch);
+        let mut base = None;
+        for candidate in [upstream.as_str(), "origin/HEAD"] {
+            let output = self.git(dir, &["rev-parse", "--verify", "--quiet", candidate], "find the remote branch")?;
+            if output.status.success() {
+                base = Some(format!("{}...HEAD", candidate));
+                break;
+            }
+        }
+
+        let args = match &base {
+            Some(range) => vec!["diff", "--no-color", "--no-ext-diff", range.as_str()],
+            None => vec!["diff", "--no-color", "--no-ext-diff", EMPTY_TREE, "HEAD"],
+        };
+        let output = self.git(dir, &args, "diff outgoing commits")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git diff failed: {}", stderr)));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn fetch(&self, dir: &Path) -> Result<()> {
+        self.fetch_remote(dir, "origin")
+    }
+
+    fn fetch_remote(&self, dir: &Path, remote: &str) -> Result<()> {
+        check_remote(remote)?;
+        let output = self.git_remote(dir, &["fetch", "--prune", remote], &format!("fetch from {}", remote))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git fetch failed: {}", stderr)));
+        }
+
+        Ok(())
+    }
+
+    fn ahead_behind(&self, dir: &Path, upstream: &str) -> Result<(usize, usize)> {
+        let range = format!("HEAD...{}", upstream);
+        let output = self.git(dir, &["rev-list", "--left-right", "--count", &range], "count commits")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git rev-list failed: {}", stderr)));
+        }
+
+        let counts = String::from_utf8_lossy(&output.stdout);
+        let mut counts = counts.split_whitespace().map(|n| n.parse().unwrap_or(0));
+        Ok((counts.next().unwrap_or(0), counts.next().unwrap_or(0)))
+    }
+
+    fn integrate(&self, dir: &Path, upstream: &str, strategy: SyncStrategy) -> Result<SyncOutcome> {
+        let (args, action) = match strategy {
+            SyncStrategy::Rebase => (vec!["rebase", upstream], "rebase"),
+            SyncStrategy::Merge => (vec!["merge", "--no-edit", upstream], "merge"),
+        };
+        let output = self.git(dir, &args, action)?;
+        self.sync_outcome(dir, output, action)
+    }
+
+    fn sync_in_progress(&self, dir: &Path) -> Result<Option<SyncStrategy>> {
+        let git_dir = self.git_dir(dir)?;
+        if git_dir.join("rebase-merge").exists() || git_dir.join("rebase-apply").exists() {
+            return Ok(Some(SyncStrategy::Rebase));
+        }
+        if git_dir.join("MERGE_HEAD").exists() {
+            return Ok(Some(SyncStrategy::Merge));
+        }
+
+        Ok(None)
+    }
+
+    fn conflicted_files(&self, dir: &Path) -> Result<Vec<String>> {
+        let output = self.git(dir, &["diff", "--name-only", "--diff-filter=U"], "list conflicted files")?;
+        if !output.status.success() {
+            return Err(AppError::Internal("Git diff command failed".to_string()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).lines().map(String::from).collect())
+    }
+
+    fn continue_sync(&self, dir: &Path, strategy: SyncStrategy) -> Result<SyncOutcome> {
+        let conflicted = self.conflicted_files(dir)?;
+        if !conflicted.is_empty() {
+            let mut args = vec!["add", "--"];
+            args.extend(conflicted.iter().map(String::as_str));
+            let output = self.git(dir, &args, "stage resolved files")?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(AppError::Internal(format!("Git add failed: {}", stderr)));
+            }
+        }
+
+        // No editor to wait for: keep the messages git prepared
+        let (args, action) = match strategy {
+            SyncStrategy::Rebase => (vec!["rebase", "--continue"], "rebase"),
+            SyncStrategy::Merge => (vec!["commit", "--no-edit"], "merge"),
+        };
+        let output = self.git(dir, &args, action)?;
+        self.sync_outcome(dir, output, action)
+    }
+
+    fn abort_sync(&self, dir: &Path, strategy: SyncStrategy) -> Result<()> {
+        let args = match strategy {
+            SyncStrategy::Rebase => ["rebase", "--abort"],
+            SyncStrategy::Merge => ["merge", "--abort"],
+        };
+        let output = self.git(dir, &args, "abort")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git {} failed: {}", args.join(" "), stderr)));
+        }
+
+        Ok(())
+    }
+
+    fn commit_empty(&self, dir: &Path, message: &str) -> Result<()> {
+        let output = self.git(dir, &["commit", "--allow-empty", "-m", message], "commit")?;
+        if !output.status.success() {
+            return Err(AppError::Internal("Git commit command failed".to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn head_message(&self, dir: &Path) -> Result<String> {
+        let output = self.git(dir, &["log", "-1", "--format=%B"], "read the last commit")?;
+        if !output.status.success() {
+            return Err(AppError::Internal("Git log command failed".to_string()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn push(&self, dir: &Path, branch: &str) -> Result<()> {
+        let output = self.git_remote(dir, &["push", "origin", branch], "push branch")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git push failed: {}", stderr)));
+        }
+
+        Ok(())
+    }
+
+    fn pull(&self, dir: &Path, branch: &str) -> Result<()> {
+        let output = self.git_remote(dir, &["pull", "origin", branch], "pull branch")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git pull failed: {}", stderr)));
+        }
+
+        Ok(())
+    }
+
+    fn checkout(&self, dir: &Path, branch: &str) -> Result<()> {
+        let output = self.git(dir, &["checkout", branch], "checkout branch")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git checkout failed: {}", stderr)));
+        }
+
+        Ok(())
+    }
+
+    fn create_branch(&self, dir: &Path, branch: &str) -> Result<()> {
+        let output = self.git(dir, &["checkout", "-b", branch], "create branch")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git branch creation failed: {}", stderr)));
+        }
+
+        Ok(())
+    }
+
+    fn delete_branch(&self, dir: &Path, branch: &str) -> Result<()> {
+        let output = self.git(dir, &["branch", "-d", branch], "delete branch")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git branch deletion failed: {}", stderr)));
+        }
+
+        Ok(())
+    }
+
+    fn remote_url(&self, dir: &Path, remote: &str) -> Result<String> {
+        let output = self.git(dir, &["remote", "get-url", remote], "get remote URL")?;
+        if !output.status.success() {
+            return Err(AppError::Internal("Git remote command failed".to_string()));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn set_remote(&self, dir: &Path, remote: &str, url: &str) -> Result<()> {
+        check_remote(remote)?;
+        let action = if self.remote_url(dir, remote).is_ok() { "set-url" } else { "add" };
+        let output = self.git(dir, &["remote", action, remote, url], "set remote")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git remote {} failed: {}", action, stderr)));
+        }
+
+        Ok(())
+    }
+
+    fn clone_repository(&self, url: &str, dest: &Path) -> Result<()> {
+        let parent = dest.parent().unwrap_or_else(|| Path::new("."));
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::Internal(format!("Failed to create {}: {}", parent.display(), e)))?;
+
+        debug!("git clone {} {}", url, dest.display());
+        let result = output(
+            Command::new("git").args(["clone", "--quiet", url]).arg(dest).current_dir(parent),
+            "clone repository",
+            self.timeouts.network,
+        );
+        if interrupted(&result) && dest.exists() {
+            // A partial clone would be mistaken for a usable checkout next time
+            warn!("Removing partial clone {}", dest.display());
+            let _ = std::fs::remove_dir_all(dest);
+        }
+        let output = result?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::Internal(format!("Git clone failed: {}", stderr)));
+        }
+
+        Ok(())
+    }
+
+    fn has_tracked_files(&self, dir: &Path, path: &Path) -> Result<bool> {
+        let root = self.repo_root(dir)?;
+        let output = output(
+            Command::new("git").arg("ls-files").arg("--").arg(path).current_dir(root),
+            "list tracked files",
+            self.timeouts.local,
+        )?;
+
+        if !output.status.success() {
+            return Err(AppError::Internal("Git ls-files command failed".to_string()));
+        }
+
+        Ok(!output.stdout.is_empty())
+    }
+}
@@ -0,0 +1,147 @@
+use serde::Serialize;
+
+use super::api::{GitHubCheckRun, GitHubCommitStatus};
+
+/// Lines of log kept from the end of a failing job
+pub const LOG_TAIL_LINES: usize = 80;
+
+/// The useful part of a failing job's log
+#[derive(Debug, Default, Serialize)]
+pub struct FailureExcerpt {
+    /// `##[error]` annotations, in order
+    pub errors: Vec<String>,
+    /// The last lines of the log, which usually hold the failing step's output
+    pub tail: Vec<String>,
+}
+
+/// Strip the ISO timestamp GitHub prefixes to every log line
+fn strip_timestamp(line: &str) -> &str {
+    match line.split_once(' ') {
+        Some((stamp, rest)) if stamp.len() >= 20 && stamp.ends_with('Z') && stamp.as_bytes()[4] == b'-' => rest,
+        _ => line,
+    }
+}
+
+pub fn failure_excerpt(log: &str, tail_lines: usize) -> FailureExcerpt {
+    let lines: Vec<&str> = log
+        .lines()
+        .map(strip_timestamp)
+        .filter(|l| !l.starts_with("##[endgroup]"))
+        .collect();
+
+    let errors = lines
+        .iter()
+        .filter_map(|l| l.strip_prefix("##[error]"))
+        .map(String::from)
+        .collect();
+
+    let start = lines.len().saturating_sub(tail_lines);
+    let tail = lines[start..]
+        .iter()
+        .map(|l| l.trim_start_matches("##[group]").to_string())
+        .collect();
+
+    FailureExcerpt { errors, tail }
+}
+
+/// Whether a job or run conclusion counts as a failure
+pub fn is_failure(conclusion: Option<&str>) -> bool {
+    matches!(conclusion, Some("failure" | "timed_out" | "startup_failure" | "action_required"))
+}
+
+/// A single status or check run, normalised for merge gating
+#[derive(Debug, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub state: String,
+    pub url: Option<String>,
+}
+
+/// CI state of a commit as seen by the merge gate
+#[derive(Debug, Serialize)]
+pub struct CheckGate {
+    pub sha: String,
+    /// passing, pending or failing
+    pub state: &'static str,
+    /// Check names required by branch protection; None when every check counts
+    pub required: Option<Vec<String>>,
+    pub passing: Vec<String>,
+    pub pending: Vec<CheckResult>,
+    pub failing: Vec<CheckResult>,
+    /// Required checks that haven't reported yet
+    pub missing: Vec<String>,
+}
+
+impl CheckGate {
+    pub fn is_passing(&self) -> bool {
+        self.state == "passing"
+    }
+}
+
+/// Combine commit statuses and check runs into one verdict, counting only
+/// `required` checks when branch protection lists them
+pub fn evaluate_checks(
+    sha: &str,
+    statuses: &[GitHubCommitStatus],
+    check_runs: &[GitHubCheckRun],
+    required: Option<Vec<String>>,
+) -> CheckGate {
+    // Required lists are empty when protection only enforces reviews
+    let required = required.filter(|r| !r.is_empty());
+    let counts = |name: &str| required.as_ref().map_or(true, |r| r.iter().any(|c| c == name));
+
+    let mut passing = Vec::new();
+    let mut pending = Vec::new();
+    let mut failing = Vec::new();
+
+    let statuses = statuses.iter().map(|s| {
+        let outcome = match s.state.as_str() {
+            "success" => Some(true),
+            "pending" => None,
+            _ => Some(false),
+        };
+        (&s.context, s.state.clone(), s.target_url.clone(), outcome)
+    });
+    let runs = check_runs.iter().map(|r| {
+        let outcome = match (r.status.as_str(), r.conclusion.as_deref()) {
+            ("completed", Some("success" | "neutral" | "skipped")) => Some(true),
+            ("completed", _) => Some(false),
+            _ => None,
+        };
+        let state = r.conclusion.clone().unwrap_or_else(|| r.status.clone());
+        (&r.name, state, r.html_url.clone(), outcome)
+    });
+
+    for (name, state, url, outcome) in statuses.chain(runs) {
+        if !counts(name) {
+            continue;
+        }
+        match outcome {
+            Some(true) => passing.push(name.clone()),
+            Some(false) => failing.push(CheckResult { name: name.clone(), state, url }),
+            None => pending.push(CheckResult { name: name.clone(), state, url }),
+        }
+    }
+
+    let reported = |name: &String| {
+        passing.contains(name)
+            || pending.iter().any(|c| &c.name == name)
+            || failing.iter().any(|c| &c.name == name)
+    };
+    let missing: Vec<String> = required
+        .iter()
+        .flatten()
+        .filter(|name| !reported(*name))
+        .cloned()
+        .collect();
+
+    let state = if !failing.is_empty() {
+        "failing"
+    } else if !pending.is_empty() || !missing.is_empty() {
+        "pending"
+    } else {
+        "passing"
+    };
+
+    CheckGate { sha: sha.to_string(), state, required, passing, pending, failing, missing }
+}
@@ -0,0 +1,185 @@
+// A repository's security alerts from Dependabot, code scanning and secret
+// scanning, in one shape so they can be triaged together.
+//
+// Severities are put on one scale: code scanning rules without a security
+// severity map `error`, `warning` and `note` to high, medium and low, and a
+// leaked secret always counts as critical. Each kind is dismissed with its
+// own reasons, which `dismiss_body` checks.
+
+use serde_json::{json, Value};
+
+use crate::error::{AppError, Result};
+
+/// Alerts listed per kind unless the caller asks for fewer
+pub const DEFAULT_LIMIT: usize = 50;
+pub const MAX_LIMIT: usize = 200;
+
+/// Alerts fetched per kind when the severity can only be filtered here
+pub const SCAN_LIMIT: usize = 1000;
+
+/// Lowest to highest
+pub const SEVERITIES: &[&str] = &["low", "medium", "high", "critical"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    Dependabot,
+    CodeScanning,
+    SecretScanning,
+}
+
+pub const ALL_KINDS: &[AlertKind] = &[AlertKind::Dependabot, AlertKind::CodeScanning, AlertKind::SecretScanning];
+
+impl AlertKind {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.replace('-', "_").as_str() {
+            "dependabot" => Ok(Self::Dependabot),
+            "code_scanning" => Ok(Self::CodeScanning),
+            "secret_scanning" => Ok(Self::SecretScanning),
+            other => Err(AppError::Validation(format!(
+                "Unknown alert kind {}: expected dependabot, code_scanning or secret_scanning",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dependabot => "dependabot",
+            Self::CodeScanning => "code_scanning",
+            Self::SecretScanning => "secret_scanning",
+        }
+    }
+
+    /// The alerts endpoint under `/repos/{owner}/{repo}/`
+    pub fn api_path(self) -> &'static str {
+        match self {
+            Self::Dependabot => "dependabot",
+            Self::CodeScanning => "code-scanning",
+            Self::SecretScanning => "secret-scanning",
+        }
+    }
+
+    /// The `state` query parameter for alerts that are `open`, `closed`
+    /// (each kind's own end states) or `all`
+    pub fn state_param(self, state: &str) -> Option<&'static str> {
+        match (state, self) {
+            ("open", _) => Some("open"),
+            ("closed", Self::Dependabot) => Some("auto_dismissed,dismissed,fixed"),
+            ("closed", Self::CodeScanning) => Some("closed"),
+            ("closed", Self::SecretScanning) => Some("resolved"),
+            _ => None,
+        }
+    }
+
+    /// The `severity` query parameter for alerts ranked `min_rank` and up, for
+    /// the kinds whose API filters on the common scale. Code scanning mixes
+    /// rule and security severities, and secrets are always critical.
+    pub fn severity_param(self, min_rank: usize) -> Option<String> {
+        match self {
+            Self::Dependabot if min_rank > 0 => Some(SEVERITIES[min_rank..].join(",")),
+            _ => None,
+        }
+    }
+
+    /// Reasons the API accepts when dismissing an alert of this kind
+    pub fn reasons(self) -> &'static [&'static str] {
+        match self {
+            Self::Dependabot => &["fix_started", "inaccurate", "no_bandwidth", "not_used", "tolerable_risk"],
+            Self::CodeScanning => &["false positive", "won't fix", "used in tests"],
+            Self::SecretScanning => &["false_positive", "wont_fix", "revoked", "used_in_tests"],
+        }
+    }
+
+    /// The PATCH body that dismisses an alert (or resolves a secret)
+    pub fn dismiss_body(self, reason: &str, comment: Option<&str>) -> Result<Value> {
+        // Accept `false_positive` for code scanning's `false positive` and so on
+        let wanted = reason.trim().to_lowercase().replace(['_', ' '], "").replace('\'', "");
+        let reason = self
+            .reasons()
+            .iter()
+            .find(|r| r.replace(['_', ' ', '\''], "") == wanted)
+            .ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Unknown {} dismissal reason '{}': expected one of {}",
+                    self.name(),
+                    reason,
+                    self.reasons().join(", ")
+                ))
+            })?;
+
+        Ok(match self {
+            Self::SecretScanning => json!({ "state": "resolved", "resolution": reason, "resolution_comment": comment }),
+            _ => json!({ "state": "dismissed", "dismissed_reason": reason, "dismissed_comment": comment }),
+        })
+    }
+}
+
+/// Position of `severity` in `SEVERITIES`
+pub fn severity_rank(severity: &str) -> Option<usize> {
+    SEVERITIES.iter().position(|s| s.eq_ignore_ascii_case(severity))
+}
+
+/// An alert's severity on the common scale
+pub fn severity(kind: AlertKind, alert: &Value) -> &'static str {
+    let raw = match kind {
+        AlertKind::Dependabot => alert.pointer("/security_advisory/severity").and_then(|s| s.as_str()),
+        AlertKind::CodeScanning => alert
+            .pointer("/rule/security_severity_level")
+            .and_then(|s| s.as_str())
+            .or_else(|| match alert.pointer("/rule/severity").and_then(|s| s.as_str()) {
+                Some("error") => Some("high"),
+                Some("warning") => Some("medium"),
+                Some("note") => Some("low"),
+                _ => None,
+            }),
+        AlertKind::SecretScanning => Some("critical"),
+    };
+    match raw.map(str::to_lowercase).as_deref() {
+        Some("critical") => "critical",
+        Some("high") => "high",
+        Some("medium" | "moderate") => "medium",
+        _ => "low",
+    }
+}
+
+pub fn summary(kind: AlertKind, alert: &Value) -> Value {
+    let text = |pointer: &str| alert.pointer(pointer).and_then(|v| v.as_str()).map(String::from);
+    let mut summary = json!({
+        "kind": kind.name(),
+        "number": alert["number"],
+        "state": alert["state"],
+        "severity": severity(kind, alert),
+        "url": alert["html_url"],
+        "created_at": alert["created_at"]
+    });
+
+    let details = match kind {
+        AlertKind::Dependabot => json!({
+            "package": text("/dependency/package/name"),
+            "ecosystem": text("/dependency/package/ecosystem"),
+            "manifest": text("/dependency/manifest_path"),
+            "advisory": text("/security_advisory/ghsa_id"),
+            "cve": text("/security_advisory/cve_id"),
+            "title": text("/security_advisory/summary"),
+            "vulnerable_versions": text("/security_vulnerability/vulnerable_version_range"),
+            "fixed_in": text("/security_vulnerability/first_patched_version/identifier")
+        }),
+        AlertKind::CodeScanning => json!({
+            "rule": text("/rule/id"),
+            "title": text("/rule/description"),
+            "tool": text("/tool/name"),
+            "path": text("/most_recent_instance/location/path"),
+            "line": alert.pointer("/most_recent_instance/location/start_line")
+        }),
+        // The secret itself is never passed on
+        AlertKind::SecretScanning => json!({
+            "secret_type": text("/secret_type"),
+            "title": text("/secret_type_display_name"),
+            "validity": text("/validity")
+        }),
+    };
+    if let (Some(summary), Value::Object(details)) = (summary.as_object_mut(), details) {
+        summary.extend(details);
+    }
+    summary
+}
@@ -0,0 +1,170 @@
+// Repositories the server may touch at all, from `ALLOWED_REPOSITORIES`.
+//
+// Patterns are `owner/name` globs where `*` matches any run of characters
+// within one segment, e.g. `myorg/*` or `myorg/api-*`. Workflow commands
+// against a repository outside the list are refused before they start, and
+// `GitHubClient` refuses any REST write to one, so a tool that reaches a
+// repository some other way still can't change it. With no patterns
+// configured every repository is allowed.
+//
+// GraphQL mutations name what they change by node ID, so they can't be
+// checked by path the way REST writes are. While the list is set, a mutation
+// is only sent by a command whose own target repository was allowed, or by
+// a background job scoped to an allowed repository, which rules out
+// `github_graphql` and other commands that span repositories.
+
+use std::cell::Cell;
+use std::future::Future;
+use std::sync::OnceLock;
+
+use crate::error::{AppError, Result};
+
+static PATTERNS: OnceLock<Vec<String>> = OnceLock::new();
+
+tokio::task_local! {
+    /// Whether the running command's target, or a background job's
+    /// repository, is allowed
+    static ALLOWED: Cell<bool>;
+}
+
+/// Set the patterns; call once at startup
+pub fn init(patterns: &[String]) {
+    let patterns = patterns.iter().map(|p| p.trim().to_lowercase()).filter(|p| !p.is_empty()).collect();
+    let _ = PATTERNS.set(patterns);
+}
+
+pub fn enabled() -> bool {
+    PATTERNS.get().map_or(false, |patterns| !patterns.is_empty())
+}
+
+/// Refuse `repository` (`owner/name`) unless a pattern matches it
+pub fn check(repository: &str) -> Result<()> {
+    let Some(patterns) = PATTERNS.get().filter(|patterns| !patterns.is_empty()) else {
+        return Ok(());
+    };
+
+    let repository = repository.trim().to_lowercase();
+    if patterns.iter().any(|pattern| glob_match(pattern, &repository)) {
+        return Ok(());
+    }
+    Err(AppError::Authorization(format!(
+        "{} is not in ALLOWED_REPOSITORIES ({})",
+        repository,
+        patterns.join(", ")
+    )))
+}
+
+/// Refuse a REST write to `path` (relative to the API root) when it
+/// addresses a repository outside the list. Forking only reads the source
+/// and writes the fork, which `GitHubClient::fork_repository` checks.
+pub fn check_api_path(path: &str) -> Result<()> {
+    let Some(rest) = path.trim_start_matches('/').strip_prefix("repos/") else {
+        return Ok(());
+    };
+    let mut segments = rest.split(['/', '?']);
+    match (segments.next(), segments.next(), segments.next(), segments.next()) {
+        (Some(_), Some(_), Some("forks"), None) => Ok(()),
+        (Some(owner), Some(name), ..) if !owner.is_empty() && !name.is_empty() => check(&format!("{}/{}", owner, name)),
+        _ => Ok(()),
+    }
+}
+
+/// Run a command's `future`, noting whether `allow_target` allows it its
+/// repository
+pub async fn scope<F: Future>(future: F) -> F::Output {
+    ALLOWED.scope(Cell::new(false), future).await
+}
+
+/// `check` the running command's own target, letting the command send
+/// GraphQL mutations once it's allowed
+pub fn allow_target(repository: &str) -> Result<()> {
+    check(repository)?;
+    let _ = ALLOWED.try_with(|allowed| allowed.set(true));
+    Ok(())
+}
+
+/// Run a background job's `future`, which acts on `repository` alone and may
+/// send GraphQL mutations when the list allows it
+pub async fn scope_to<F: Future>(repository: &str, future: F) -> F::Output {
+    ALLOWED.scope(Cell::new(check(repository).is_ok()), future).await
+}
+
+/// Refuse a GraphQL mutation unless the running command's target, or the
+/// background job's repository, is allowed
+pub fn check_mutation() -> Result<()> {
+    if !enabled() || ALLOWED.try_with(Cell::get).unwrap_or(false) {
+        return Ok(());
+    }
+    Err(AppError::Authorization(
+        "GraphQL mutations outside a repository in ALLOWED_REPOSITORIES are refused".to_string(),
+    ))
+}
+
+/// `*` matches any run of characters other than `/`, `?` any one of them
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let (pattern, text): (Vec<char>, Vec<char>) = (pattern.chars().collect(), text.chars().collect());
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was, and how much text it has taken so far
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == text[t] || (c == '?' && text[t] != '/') => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                // Let the `*` take one more character, but never a `/`
+                Some((star_p, star_t)) if text[star_t] != '/' => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                _ => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_match;
+
+    #[test]
+    fn matches_literal_repositories() {
+        assert!(glob_match("myorg/api", "myorg/api"));
+        assert!(!glob_match("myorg/api", "myorg/api2"));
+        assert!(!glob_match("myorg/api", "myorg/ap"));
+    }
+
+    #[test]
+    fn star_stays_within_a_segment() {
+        assert!(glob_match("myorg/*", "myorg/api"));
+        assert!(glob_match("myorg/api-*", "myorg/api-gateway"));
+        assert!(glob_match("myorg/api-*", "myorg/api-"));
+        assert!(glob_match("*/api", "anyone/api"));
+        assert!(!glob_match("myorg/*", "otherorg/api"));
+        assert!(!glob_match("*", "myorg/api"));
+        assert!(!glob_match("my*", "myorg/api"));
+    }
+
+    #[test]
+    fn star_backtracks() {
+        assert!(glob_match("myorg/*-service", "myorg/auth-api-service"));
+        assert!(glob_match("myorg/a*b*c", "myorg/aXbYbZc"));
+        assert!(!glob_match("myorg/*-service", "myorg/auth-api-services"));
+    }
+
+    #[test]
+    fn question_mark_is_one_character() {
+        assert!(glob_match("myorg/api?", "myorg/api2"));
+        assert!(!glob_match("myorg/api?", "myorg/api"));
+        assert!(!glob_match("myorg?api", "myorg/api"));
+    }
+}
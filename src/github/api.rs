@@ -1,10 +1,14 @@
-use reqwest::{Client, header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT}};
-use serde::{Deserialize, Serialize};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use reqwest::{Client, Method, RequestBuilder, Response, header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT}};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use tracing::{debug, error};
+use std::time::Duration;
+use tracing::{debug, error, warn};
 
 use crate::{AppState, error::{AppError, Result}};
+use super::error::GitHubError;
+use super::host::GitHubHost;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubUser {
@@ -20,15 +24,53 @@ pub struct GitHubRepository {
     pub id: u64,
     pub name: String,
     pub full_name: String,
+    pub html_url: String,
     pub owner: GitHubUser,
     pub default_branch: String,
     pub clone_url: String,
     pub ssh_url: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub homepage: Option<String>,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub archived: bool,
+    /// Whether GitHub deletes head branches once their PR merges; only
+    /// returned to callers with admin access
+    #[serde(default)]
+    pub delete_branch_on_merge: Option<bool>,
+    #[serde(default)]
+    pub fork: bool,
+    /// The repository this one was forked from; only returned for a single repository
+    #[serde(default)]
+    pub parent: Option<Box<GitHubRepository>>,
+    /// What the token's user may do in the repository
+    #[serde(default)]
+    pub permissions: Option<RepositoryPermissions>,
+    /// Merge methods the repository accepts; only returned for a single repository
+    #[serde(default)]
+    pub allow_merge_commit: Option<bool>,
+    #[serde(default)]
+    pub allow_squash_merge: Option<bool>,
+    #[serde(default)]
+    pub allow_rebase_merge: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RepositoryPermissions {
+    #[serde(default)]
+    pub admin: bool,
+    #[serde(default)]
+    pub push: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubIssue {
     pub id: u64,
+    #[serde(default)]
+    pub node_id: String,
     pub number: u64,
     pub title: String,
     pub body: Option<String>,
@@ -37,10 +79,59 @@ pub struct GitHubIssue {
     pub assignee: Option<GitHubUser>,
     pub user: GitHubUser,
     pub html_url: String,
+    #[serde(default)]
+    pub milestone: Option<GitHubMilestone>,
+    /// Present when the issue is really a pull request
+    #[serde(default)]
+    pub pull_request: Option<Value>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubMilestone {
+    pub number: u64,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub state: String,
+    pub due_on: Option<String>,
+    pub html_url: String,
+    /// Issues and pull requests, as GitHub counts them
+    #[serde(default)]
+    pub open_issues: u64,
+    #[serde(default)]
+    pub closed_issues: u64,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub closed_at: Option<String>,
+}
+
+/// A file of a commit built through the Git Data API
+#[derive(Debug, Clone)]
+pub struct TreeFile {
+    /// Relative to the repository root, with `/` separators
+    pub path: String,
+    pub content: Vec<u8>,
+    pub executable: bool,
+}
+
+/// Server-side filters for listing issues, as accepted by the issues API
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueFilter {
+    /// open (default), closed or all
+    pub state: Option<String>,
+    /// Issues must carry all of these labels
+    pub labels: Option<Vec<String>>,
+    pub assignee: Option<String>,
+    pub creator: Option<String>,
+    /// Milestone number, `*` for any or `none`
+    pub milestone: Option<String>,
+    /// Only issues updated at or after this ISO 8601 timestamp
+    pub since: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubLabel {
     pub id: u64,
@@ -52,6 +143,8 @@ pub struct GitHubLabel {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubPullRequest {
     pub id: u64,
+    #[serde(default)]
+    pub node_id: String,
     pub number: u64,
     pub title: String,
     pub body: Option<String>,
@@ -62,10 +155,51 @@ pub struct GitHubPullRequest {
     pub user: GitHubUser,
     pub html_url: String,
     pub mergeable: Option<bool>,
+    #[serde(default)]
+    pub merged_at: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<GitHubLabel>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCommit {
+    pub sha: String,
+    pub html_url: String,
+    pub commit: GitHubCommitDetail,
+    /// The linked GitHub account; null when the author email isn't linked
+    pub author: Option<GitHubUser>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCommitDetail {
+    pub message: String,
+    pub author: Option<GitHubCommitAuthor>,
+    pub verification: Option<GitHubVerification>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCommitAuthor {
+    pub name: String,
+    pub email: String,
+    pub date: String,
+}
+
+/// GPG/SSH signature verification as reported by GitHub
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubVerification {
+    pub verified: bool,
+    /// e.g. `valid`, `unsigned`, `unknown_key`, `bad_email`
+    pub reason: String,
+}
+
+impl GitHubCommit {
+    pub fn is_verified(&self) -> bool {
+        self.commit.verification.as_ref().map_or(false, |v| v.verified)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubBranch {
     pub label: String,
@@ -87,9 +221,26 @@ pub struct GitHubProjectContent {
     pub id: String,
     pub title: String,
     pub body: Option<String>,
-    pub url: String,
+    pub url: Option<String>,
     #[serde(rename = "type")]
-    pub content_type: String,
+    pub content_type: String, // "Issue", "PullRequest" or "DraftIssue"
+    pub number: Option<u64>,
+    pub state: Option<String>,
+    pub repository: Option<String>,
+    pub assignees: Vec<String>,
+    pub labels: Vec<String>,
+    /// Pull requests that will close an issue when merged
+    #[serde(default)]
+    pub linked_pull_requests: Vec<GitHubLinkedPullRequest>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubLinkedPullRequest {
+    pub number: u64,
+    pub url: String,
+    pub state: String,
+    #[serde(rename = "isDraft")]
+    pub is_draft: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -106,293 +257,3447 @@ pub struct GitHubProjectField {
     pub data_type: String,
 }
 
-pub struct GitHubClient {
-    client: Client,
-    base_url: String,
-    token: String,
+impl GitHubProjectItem {
+    /// Look up a field value by field name (case-insensitive)
+    pub fn field_value(&self, name: &str) -> Option<&Value> {
+        self.field_values
+            .as_ref()?
+            .iter()
+            .find(|fv| fv.field.name.eq_ignore_ascii_case(name))
+            .and_then(|fv| fv.value.as_ref())
+    }
 }
 
-impl GitHubClient {
-    pub fn new(token: String, base_url: Option<String>) -> Result<Self> {
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            AUTHORIZATION,
-            HeaderValue::from_str(&format!("Bearer {}", token))
-                .map_err(|e| AppError::Internal(format!("Invalid token format: {}", e)))?,
-        );
-        headers.insert(
-            USER_AGENT,
-            HeaderValue::from_static("github-mcp-server/1.0"),
-        );
-
-        let client = Client::builder()
-            .default_headers(headers)
-            .build()
-            .map_err(|e| AppError::HttpClient(e))?;
+// Raw GraphQL shapes for ProjectV2 items. These mirror the query in
+// `PROJECT_ITEMS_QUERY` and are converted into the public types above.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlPageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
 
-        Ok(Self {
-            client,
-            base_url: base_url.unwrap_or_else(|| "https://api.github.com".to_string()),
-            token,
-        })
-    }
+#[derive(Debug, Deserialize)]
+struct GraphQlConnection<T> {
+    #[serde(rename = "pageInfo")]
+    page_info: Option<GraphQlPageInfo>,
+    #[serde(default = "Vec::new")]
+    nodes: Vec<Option<T>>,
+}
 
-    pub async fn get_user(&self) -> Result<GitHubUser> {
-        let url = format!("{}/user", self.base_url);
-        debug!("Fetching GitHub user: {}", url);
+#[derive(Debug, Deserialize)]
+struct GraphQlLogin {
+    login: String,
+}
 
-        let response = self.client
-            .get(&url)
-            .send()
-            .await
-            .map_err(AppError::HttpClient)?;
+#[derive(Debug, Deserialize)]
+struct GraphQlName {
+    name: String,
+}
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            error!("GitHub API error: {} - {}", status, text);
-            return Err(AppError::GitHubApi(format!("Failed to get user: {} - {}", status, text)));
-        }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlRepository {
+    name_with_owner: String,
+}
 
-        let user = response.json::<GitHubUser>().await.map_err(AppError::HttpClient)?;
-        Ok(user)
-    }
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlProjectContent {
+    #[serde(rename = "__typename")]
+    typename: String,
+    id: Option<String>,
+    title: Option<String>,
+    body: Option<String>,
+    url: Option<String>,
+    number: Option<u64>,
+    state: Option<String>,
+    repository: Option<GraphQlRepository>,
+    assignees: Option<GraphQlConnection<GraphQlLogin>>,
+    labels: Option<GraphQlConnection<GraphQlName>>,
+    closed_by_pull_requests_references: Option<GraphQlConnection<GitHubLinkedPullRequest>>,
+}
 
-    pub async fn get_repository(&self, owner: &str, repo: &str) -> Result<GitHubRepository> {
-        let url = format!("{}/repos/{}/{}", self.base_url, owner, repo);
-        debug!("Fetching repository: {}", url);
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlProjectFieldValue {
+    #[serde(rename = "__typename")]
+    typename: String,
+    field: Option<GitHubProjectField>,
+    text: Option<String>,
+    name: Option<String>,
+    number: Option<f64>,
+    date: Option<String>,
+    title: Option<String>,
+    start_date: Option<String>,
+    duration: Option<u64>,
+}
 
-        let response = self.client
-            .get(&url)
-            .send()
-            .await
-            .map_err(AppError::HttpClient)?;
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlProjectItem {
+    id: String,
+    content: Option<GraphQlProjectContent>,
+    field_values: Option<GraphQlConnection<GraphQlProjectFieldValue>>,
+}
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(AppError::GitHubApi(format!("Failed to get repository: {} - {}", status, text)));
+impl From<GraphQlProjectContent> for GitHubProjectContent {
+    fn from(raw: GraphQlProjectContent) -> Self {
+        Self {
+            id: raw.id.unwrap_or_default(),
+            title: raw.title.unwrap_or_default(),
+            body: raw.body,
+            url: raw.url,
+            content_type: raw.typename,
+            number: raw.number,
+            state: raw.state,
+            repository: raw.repository.map(|r| r.name_with_owner),
+            assignees: raw.assignees
+                .map(|c| c.nodes.into_iter().flatten().map(|u| u.login).collect())
+                .unwrap_or_default(),
+            labels: raw.labels
+                .map(|c| c.nodes.into_iter().flatten().map(|l| l.name).collect())
+                .unwrap_or_default(),
+            linked_pull_requests: raw.closed_by_pull_requests_references
+                .map(|c| c.nodes.into_iter().flatten().collect())
+                .unwrap_or_default(),
         }
+    }
+}
 
-        let repository = response.json::<GitHubRepository>().await.map_err(AppError::HttpClient)?;
-        Ok(repository)
+impl GraphQlProjectFieldValue {
+    fn into_field_value(self) -> Option<GitHubProjectFieldValue> {
+        let field = self.field?;
+        let value = match self.typename.as_str() {
+            "ProjectV2ItemFieldTextValue" => self.text.map(Value::String),
+            "ProjectV2ItemFieldSingleSelectValue" => self.name.map(Value::String),
+            "ProjectV2ItemFieldNumberValue" => self.number.map(|n| serde_json::json!(n)),
+            "ProjectV2ItemFieldDateValue" => self.date.map(Value::String),
+            "ProjectV2ItemFieldIterationValue" => Some(serde_json::json!({
+                "title": self.title,
+                "startDate": self.start_date,
+                "duration": self.duration
+            })),
+            _ => None,
+        };
+        Some(GitHubProjectFieldValue { field, value })
     }
+}
 
-    pub async fn list_issues(&self, owner: &str, repo: &str, state: Option<&str>) -> Result<Vec<GitHubIssue>> {
-        let mut url = format!("{}/repos/{}/{}/issues", self.base_url, owner, repo);
-        if let Some(state) = state {
-            url.push_str(&format!("?state={}", state));
+impl From<GraphQlProjectItem> for GitHubProjectItem {
+    fn from(raw: GraphQlProjectItem) -> Self {
+        Self {
+            id: raw.id,
+            content: raw.content.map(GitHubProjectContent::from),
+            field_values: raw.field_values.map(|c| {
+                c.nodes
+                    .into_iter()
+                    .flatten()
+                    .filter_map(GraphQlProjectFieldValue::into_field_value)
+                    .collect()
+            }),
         }
-        
-        debug!("Fetching issues: {}", url);
-
-        let response = self.client
-            .get(&url)
-            .send()
-            .await
-            .map_err(AppError::HttpClient)?;
+    }
+}
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(AppError::GitHubApi(format!("Failed to list issues: {} - {}", status, text)));
+// `OWNER_ROOT` is substituted with `organization` or `user` depending on the
+// project owner, since GraphQL can't select a root field from a variable
+const PROJECT_ITEMS_QUERY: &str = r#"
+    query($owner: String!, $number: Int!, $cursor: String) {
+        OWNER_ROOT(login: $owner) {
+            projectV2(number: $number) {
+                items(first: 100, after: $cursor) {
+                    pageInfo {
+                        hasNextPage
+                        endCursor
+                    }
+                    nodes {
+                        id
+                        content {
+                            __typename
+                            ... on Issue {
+                                id
+                                number
+                                title
+                                body
+                                url
+                                state
+                                repository { nameWithOwner }
+                                assignees(first: 10) { nodes { login } }
+                                labels(first: 20) { nodes { name } }
+                                closedByPullRequestsReferences(first: 10) { nodes { number url state isDraft } }
+                            }
+                            ... on PullRequest {
+                                id
+                                number
+                                title
+                                body
+                                url
+                                state
+                                repository { nameWithOwner }
+                                assignees(first: 10) { nodes { login } }
+                                labels(first: 20) { nodes { name } }
+                            }
+                            ... on DraftIssue {
+                                id
+                                title
+                                body
+                                assignees(first: 10) { nodes { login } }
+                            }
+                        }
+                        fieldValues(first: 20) {
+                            nodes {
+                                __typename
+                                ... on ProjectV2ItemFieldTextValue {
+                                    text
+                                    field { ...FieldCommon }
+                                }
+                                ... on ProjectV2ItemFieldSingleSelectValue {
+                                    name
+                                    field { ...FieldCommon }
+                                }
+                                ... on ProjectV2ItemFieldNumberValue {
+                                    number
+                                    field { ...FieldCommon }
+                                }
+                                ... on ProjectV2ItemFieldDateValue {
+                                    date
+                                    field { ...FieldCommon }
+                                }
+                                ... on ProjectV2ItemFieldIterationValue {
+                                    title
+                                    startDate
+                                    duration
+                                    field { ...FieldCommon }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
         }
+    }
 
-        let issues = response.json::<Vec<GitHubIssue>>().await.map_err(AppError::HttpClient)?;
-        Ok(issues)
+    fragment FieldCommon on ProjectV2FieldCommon {
+        id
+        name
+        dataType
     }
+"#;
 
-    pub async fn create_issue(&self, owner: &str, repo: &str, title: &str, body: Option<&str>, labels: Option<Vec<&str>>) -> Result<GitHubIssue> {
-        let url = format!("{}/repos/{}/{}/issues", self.base_url, owner, repo);
-        debug!("Creating issue: {}", url);
+/// ProjectV2 metadata needed for write operations (field and option IDs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubProject {
+    pub id: String,
+    pub title: String,
+    pub fields: Vec<GitHubProjectFieldDefinition>,
+}
 
-        let mut payload = serde_json::json!({
-            "title": title
-        });
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubProjectFieldDefinition {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "dataType")]
+    pub data_type: String,
+    #[serde(default)]
+    pub options: Vec<GitHubProjectFieldOption>,
+    /// Current and upcoming iterations, for an iteration field
+    #[serde(default)]
+    pub iterations: Vec<GitHubProjectIteration>,
+}
 
-        if let Some(body) = body {
-            payload["body"] = serde_json::Value::String(body.to_string());
-        }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubProjectFieldOption {
+    pub id: String,
+    pub name: String,
+}
 
-        if let Some(labels) = labels {
-            payload["labels"] = serde_json::Value::Array(
-                labels.into_iter().map(|l| serde_json::Value::String(l.to_string())).collect()
-            );
-        }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubProjectIteration {
+    pub id: String,
+    pub title: String,
+    #[serde(rename = "startDate")]
+    pub start_date: chrono::NaiveDate,
+    /// Length in days
+    pub duration: i64,
+}
 
-        let response = self.client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await
-            .map_err(AppError::HttpClient)?;
+impl GitHubProject {
+    pub fn field(&self, name: &str) -> Option<&GitHubProjectFieldDefinition> {
+        self.fields.iter().find(|f| f.name.eq_ignore_ascii_case(name))
+    }
+}
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(AppError::GitHubApi(format!("Failed to create issue: {} - {}", status, text)));
+impl GitHubProjectFieldDefinition {
+    /// An iteration by title, or `current` / `next` relative to `today`
+    pub fn iteration(&self, name: &str, today: chrono::NaiveDate) -> Option<&GitHubProjectIteration> {
+        match name.to_lowercase().as_str() {
+            "current" | "@current" => self.iterations.iter().find(|i| {
+                i.start_date <= today && today < i.start_date + chrono::Duration::days(i.duration)
+            }),
+            "next" | "@next" => self.iterations.iter().filter(|i| i.start_date > today).min_by_key(|i| i.start_date),
+            _ => self.iterations.iter().find(|i| i.title.eq_ignore_ascii_case(name)),
         }
-
-        let issue = response.json::<GitHubIssue>().await.map_err(AppError::HttpClient)?;
-        Ok(issue)
     }
+}
 
-    pub async fn list_pull_requests(&self, owner: &str, repo: &str, state: Option<&str>) -> Result<Vec<GitHubPullRequest>> {
-        let mut url = format!("{}/repos/{}/{}/pulls", self.base_url, owner, repo);
-        if let Some(state) = state {
-            url.push_str(&format!("?state={}", state));
+const PROJECT_FIELDS_QUERY: &str = r#"
+    query($owner: String!, $number: Int!) {
+        OWNER_ROOT(login: $owner) {
+            projectV2(number: $number) {
+                id
+                title
+                fields(first: 50) {
+                    nodes {
+                        ... on ProjectV2FieldCommon {
+                            id
+                            name
+                            dataType
+                        }
+                        ... on ProjectV2SingleSelectField {
+                            options { id name }
+                        }
+                        ... on ProjectV2IterationField {
+                            configuration {
+                                iterations { id title startDate duration }
+                            }
+                        }
+                    }
+                }
+            }
         }
-        
-        debug!("Fetching pull requests: {}", url);
+    }
+"#;
 
-        let response = self.client
-            .get(&url)
-            .send()
-            .await
-            .map_err(AppError::HttpClient)?;
+const UPDATE_FIELD_VALUE_MUTATION: &str = r#"
+    mutation($project: ID!, $item: ID!, $field: ID!, $value: ProjectV2FieldValue!) {
+        updateProjectV2ItemFieldValue(input: {
+            projectId: $project,
+            itemId: $item,
+            fieldId: $field,
+            value: $value
+        }) {
+            projectV2Item { id }
+        }
+    }
+"#;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(AppError::GitHubApi(format!("Failed to list pull requests: {} - {}", status, text)));
+const CLEAR_FIELD_VALUE_MUTATION: &str = r#"
+    mutation($project: ID!, $item: ID!, $field: ID!) {
+        clearProjectV2ItemFieldValue(input: { projectId: $project, itemId: $item, fieldId: $field }) {
+            projectV2Item { id }
         }
+    }
+"#;
 
-        let prs = response.json::<Vec<GitHubPullRequest>>().await.map_err(AppError::HttpClient)?;
-        Ok(prs)
+const CONVERT_DRAFT_ISSUE_MUTATION: &str = r#"
+    mutation($item: ID!, $repository: ID!) {
+        convertProjectV2DraftIssueItemToIssue(input: { itemId: $item, repositoryId: $repository }) {
+            item {
+                id
+                content {
+                    ... on Issue { number url }
+                }
+            }
+        }
     }
+"#;
 
-    pub async fn create_pull_request(
-        &self,
-        owner: &str,
-        repo: &str,
-        title: &str,
-        head: &str,
-        base: &str,
-        body: Option<&str>,
-        draft: bool,
-    ) -> Result<GitHubPullRequest> {
-        let url = format!("{}/repos/{}/{}/pulls", self.base_url, owner, repo);
-        debug!("Creating pull request: {}", url);
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubDiscussionCategory {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+    /// Whether discussions in it can have a comment marked as the answer
+    #[serde(rename = "isAnswerable")]
+    pub is_answerable: bool,
+}
 
-        let mut payload = serde_json::json!({
-            "title": title,
-            "head": head,
-            "base": base,
-            "draft": draft
-        });
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubDiscussion {
+    pub id: String,
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    pub body: Option<String>,
+    /// None when the account was deleted
+    pub author: Option<String>,
+    pub category: GitHubDiscussionCategory,
+    pub created_at: String,
+    pub updated_at: String,
+    pub closed: bool,
+    /// None in categories that don't take answers
+    pub answered: Option<bool>,
+    pub comment_count: u64,
+}
 
-        if let Some(body) = body {
-            payload["body"] = serde_json::Value::String(body.to_string());
-        }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubDiscussionComment {
+    pub id: String,
+    pub body: String,
+    pub url: String,
+    pub author: Option<String>,
+    pub created_at: String,
+    pub is_answer: bool,
+    pub upvotes: u64,
+    pub replies: Vec<GitHubDiscussionComment>,
+    pub reply_count: u64,
+}
 
-        let response = self.client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await
-            .map_err(AppError::HttpClient)?;
+// Raw GraphQL shapes for discussions, mirroring `DISCUSSIONS_QUERY` and
+// `DISCUSSION_QUERY`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlCount {
+    total_count: u64,
+}
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(AppError::GitHubApi(format!("Failed to create pull request: {} - {}", status, text)));
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlDiscussion {
+    id: String,
+    number: u64,
+    title: String,
+    url: String,
+    body: Option<String>,
+    author: Option<GraphQlLogin>,
+    category: GitHubDiscussionCategory,
+    created_at: String,
+    updated_at: String,
+    closed: bool,
+    is_answered: Option<bool>,
+    comments: GraphQlCount,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlDiscussionComment {
+    id: String,
+    body: String,
+    url: String,
+    author: Option<GraphQlLogin>,
+    created_at: String,
+    #[serde(default)]
+    is_answer: bool,
+    #[serde(default)]
+    upvote_count: u64,
+    replies: Option<GraphQlDiscussionReplies>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlDiscussionReplies {
+    total_count: u64,
+    #[serde(default)]
+    nodes: Vec<Option<GraphQlDiscussionComment>>,
+}
+
+impl From<GraphQlDiscussion> for GitHubDiscussion {
+    fn from(raw: GraphQlDiscussion) -> Self {
+        Self {
+            id: raw.id,
+            number: raw.number,
+            title: raw.title,
+            url: raw.url,
+            body: raw.body,
+            author: raw.author.map(|a| a.login),
+            category: raw.category,
+            created_at: raw.created_at,
+            updated_at: raw.updated_at,
+            closed: raw.closed,
+            answered: raw.is_answered,
+            comment_count: raw.comments.total_count,
         }
+    }
+}
 
-        let pr = response.json::<GitHubPullRequest>().await.map_err(AppError::HttpClient)?;
-        Ok(pr)
+impl From<GraphQlDiscussionComment> for GitHubDiscussionComment {
+    fn from(raw: GraphQlDiscussionComment) -> Self {
+        let (replies, reply_count) = match raw.replies {
+            Some(replies) => (replies.nodes.into_iter().flatten().map(Self::from).collect(), replies.total_count),
+            None => (Vec::new(), 0),
+        };
+        Self {
+            id: raw.id,
+            body: raw.body,
+            url: raw.url,
+            author: raw.author.map(|a| a.login),
+            created_at: raw.created_at,
+            is_answer: raw.is_answer,
+            upvotes: raw.upvote_count,
+            replies,
+            reply_count,
+        }
     }
+}
 
-    pub async fn get_project_items(&self, project_number: &str) -> Result<Vec<GitHubProjectItem>> {
-        // Note: This is a simplified implementation
-        // In practice, you'd use the GraphQL API for GitHub Projects v2
-        let query = format!(r#"
-            query {{
-                organization(login: "your-org") {{
-                    projectV2(number: {}) {{
-                        items(first: 100) {{
-                            nodes {{
-                                id
-                                content {{
-                                    ... on Issue {{
-                                        id
-                                        title
-                                        body
-                                        url
-                                    }}
-                                    ... on PullRequest {{
-                                        id
-                                        title
-                                        body
-                                        url
-                                    }}
-                                }}
-                                fieldValues(first: 20) {{
-                                    nodes {{
-                                        ... on ProjectV2ItemFieldTextValue {{
-                                            field {{
-                                                ... on ProjectV2Field {{
-                                                    id
-                                                    name
-                                                    dataType
-                                                }}
-                                            }}
-                                            text
-                                        }}
-                                        ... on ProjectV2ItemFieldSingleSelectValue {{
-                                            field {{
-                                                ... on ProjectV2SingleSelectField {{
-                                                    id
-                                                    name
-                                                    dataType
-                                                }}
-                                            }}
-                                            name
-                                        }}
-                                    }}
-                                }}
-                            }}
-                        }}
-                    }}
-                }}
-            }}
-        "#, project_number);
-
-        let url = format!("{}/graphql", self.base_url);
-        let payload = serde_json::json!({ "query": query });
-
-        let response = self.client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await
-            .map_err(AppError::HttpClient)?;
+const DISCUSSION_FIELDS: &str = "id number title url body author { login } category { id name slug isAnswerable } createdAt updatedAt closed isAnswered comments { totalCount }";
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(AppError::GitHubApi(format!("Failed to get project items: {} - {}", status, text)));
+const DISCUSSIONS_QUERY: &str = r#"
+    query($owner: String!, $name: String!, $category: ID, $states: [DiscussionState!], $answered: Boolean, $cursor: String) {
+        repository(owner: $owner, name: $name) {
+            discussions(first: 50, after: $cursor, categoryId: $category, states: $states, answered: $answered, orderBy: { field: UPDATED_AT, direction: DESC }) {
+                pageInfo {
+                    hasNextPage
+                    endCursor
+                }
+                nodes { FIELDS }
+            }
         }
+    }
+"#;
 
-        // Parse GraphQL response and extract project items
-        let response_data: Value = response.json().await.map_err(AppError::HttpClient)?;
-        
-        // This is a simplified parsing - in practice you'd need more robust GraphQL response handling
-        let items = vec![]; // TODO: Parse actual GraphQL response
-        
-        Ok(items)
+// Comments come back oldest first; a thread longer than a page is cut short.
+// `thread` is aliased as FIELDS already selects the comment count.
+const DISCUSSION_QUERY: &str = r#"
+    query($owner: String!, $name: String!, $number: Int!) {
+        repository(owner: $owner, name: $name) {
+            discussion(number: $number) {
+                FIELDS
+                thread: comments(first: 100) {
+                    nodes {
+                        id body url createdAt isAnswer upvoteCount
+                        author { login }
+                        replies(first: 50) {
+                            totalCount
+                            nodes { id body url createdAt author { login } }
+                        }
+                    }
+                }
+            }
+        }
     }
+"#;
+
+/// Whether a project owner login is an organization or a personal account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProjectOwnerKind {
+    Organization,
+    User,
 }
 
-pub async fn get_github_client(state: AppState, user_id: Option<u64>) -> Result<GitHubClient> {
-    // Get GitHub token from database for the user
-    let token = if let Some(user_id) = user_id {
-        get_user_github_token(&state.db, user_id).await?
-    } else {
-        // For now, use a default token or return an error
-        return Err(AppError::Authentication("No GitHub token available".to_string()));
-    };
+impl ProjectOwnerKind {
+    fn graphql_root(self) -> &'static str {
+        match self {
+            ProjectOwnerKind::Organization => "organization",
+            ProjectOwnerKind::User => "user",
+        }
+    }
+}
+
+/// Reaction contents accepted by the GitHub reactions API
+pub const REACTION_CONTENTS: &[&str] = &["+1", "-1", "laugh", "confused", "heart", "hooray", "rocket", "eyes"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubReaction {
+    pub id: u64,
+    pub content: String,
+    pub user: Option<GitHubUser>,
+    pub created_at: String,
+}
+
+/// A file changed by a pull request; `patch` is absent for binary or very large files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubPullRequestFile {
+    pub filename: String,
+    pub status: String,
+    pub additions: u64,
+    pub deletions: u64,
+    pub changes: u64,
+    pub patch: Option<String>,
+    #[serde(default)]
+    pub previous_filename: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubWorkflowRun {
+    pub id: u64,
+    pub name: Option<String>,
+    pub workflow_id: u64,
+    pub run_number: u64,
+    #[serde(default)]
+    pub run_attempt: Option<u64>,
+    pub event: String,
+    pub head_branch: Option<String>,
+    pub head_sha: String,
+    /// queued, in_progress or completed
+    pub status: Option<String>,
+    /// success, failure, cancelled, skipped, ... once completed
+    pub conclusion: Option<String>,
+    pub html_url: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubJob {
+    pub id: u64,
+    pub run_id: u64,
+    pub name: String,
+    pub status: String,
+    pub conclusion: Option<String>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub html_url: Option<String>,
+    #[serde(default)]
+    pub steps: Vec<GitHubJobStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubJobStep {
+    pub number: u64,
+    pub name: String,
+    pub status: String,
+    pub conclusion: Option<String>,
+}
+
+/// A legacy commit status reported through the Statuses API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCommitStatus {
+    pub context: String,
+    /// error, failure, pending or success
+    pub state: String,
+    pub description: Option<String>,
+    pub target_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCombinedStatus {
+    pub sha: String,
+    pub state: String,
+    #[serde(default)]
+    pub statuses: Vec<GitHubCommitStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCheckRun {
+    pub id: u64,
+    pub name: String,
+    /// queued, in_progress or completed
+    pub status: String,
+    pub conclusion: Option<String>,
+    pub html_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtectionFlag {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtectionStatusChecks {
+    /// Branch must be up to date with the base before merging
+    pub strict: bool,
+    #[serde(default)]
+    pub contexts: Vec<String>,
+    /// Settings without a field here, such as the apps checks are pinned to
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtectionReviews {
+    #[serde(default)]
+    pub required_approving_review_count: u64,
+    #[serde(default)]
+    pub dismiss_stale_reviews: bool,
+    #[serde(default)]
+    pub require_code_owner_reviews: bool,
+    /// Settings without a field here, such as who may dismiss reviews
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, Value>,
+}
+
+/// Classic branch protection; absent sections are not enforced
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitHubBranchProtection {
+    #[serde(default)]
+    pub required_status_checks: Option<ProtectionStatusChecks>,
+    #[serde(default)]
+    pub required_pull_request_reviews: Option<ProtectionReviews>,
+    #[serde(default)]
+    pub enforce_admins: Option<ProtectionFlag>,
+    /// Users, teams and apps allowed to push, when pushes are restricted
+    #[serde(default)]
+    pub restrictions: Option<Value>,
+    #[serde(default)]
+    pub required_linear_history: Option<ProtectionFlag>,
+    #[serde(default)]
+    pub allow_force_pushes: Option<ProtectionFlag>,
+    #[serde(default)]
+    pub allow_deletions: Option<ProtectionFlag>,
+    #[serde(default)]
+    pub required_conversation_resolution: Option<ProtectionFlag>,
+    #[serde(default)]
+    pub lock_branch: Option<ProtectionFlag>,
+    /// Settings without a field here, such as `block_creations`
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubRelease {
+    pub id: u64,
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub body: Option<String>,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub html_url: String,
+    /// URI template for asset uploads, e.g. `.../assets{?name,label}`
+    pub upload_url: String,
+    pub created_at: String,
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub assets: Vec<GitHubReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubReleaseAsset {
+    pub id: u64,
+    pub name: String,
+    pub size: u64,
+    pub browser_download_url: String,
+}
+
+/// Fields for creating a release
+#[derive(Debug, Clone, Serialize)]
+pub struct NewRelease {
+    pub tag_name: String,
+    pub target_commitish: String,
+    pub name: String,
+    pub body: String,
+    pub draft: bool,
+    pub prerelease: bool,
+}
+
+/// A file from the Contents API; `content` is base64 and omitted for
+/// files over 1 MB
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubContent {
+    pub name: String,
+    pub path: String,
+    pub sha: String,
+    pub size: u64,
+    /// file, dir, symlink or submodule
+    #[serde(rename = "type")]
+    pub content_type: String,
+    #[serde(default)]
+    pub encoding: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+    pub html_url: Option<String>,
+}
+
+impl GitHubContent {
+    /// Raw bytes of a file fetched with its content inline
+    pub fn decoded(&self) -> Result<Vec<u8>> {
+        let content = match (&self.content, self.encoding.as_deref()) {
+            (Some(content), Some("base64")) => content,
+            _ => return Err(AppError::Validation(format!("{} has no inline content (files over 1 MB aren't returned)", self.path))),
+        };
+        let compact: String = content.chars().filter(|c| !c.is_whitespace()).collect();
+        BASE64
+            .decode(compact)
+            .map_err(|e| AppError::GitHubApi(format!("Invalid file content for {}: {}", self.path, e)))
+    }
+}
+
+/// Filters for listing workflow runs
+#[derive(Debug, Clone, Default)]
+pub struct WorkflowRunFilter {
+    /// Workflow file name or ID; lists every workflow when unset
+    pub workflow: Option<String>,
+    pub branch: Option<String>,
+    pub head_sha: Option<String>,
+    pub status: Option<String>,
+    pub event: Option<String>,
+    /// Date range such as `>=2024-01-01T00:00:00Z`
+    pub created: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubReview {
+    pub id: u64,
+    pub user: Option<GitHubUser>,
+    pub body: Option<String>,
+    /// APPROVED, CHANGES_REQUESTED, COMMENTED, DISMISSED or PENDING
+    pub state: String,
+    pub html_url: String,
+    pub commit_id: Option<String>,
+    pub submitted_at: Option<String>,
+}
+
+/// Review event to submit
+pub const REVIEW_EVENTS: &[&str] = &["APPROVE", "REQUEST_CHANGES", "COMMENT"];
+
+/// Credit types GitHub accepts on a security advisory
+pub const ADVISORY_CREDIT_TYPES: &[&str] = &[
+    "analyst", "finder", "reporter", "coordinator", "remediation_developer",
+    "remediation_reviewer", "remediation_verifier", "tool", "sponsor", "other",
+];
+
+pub const ADVISORY_SEVERITIES: &[&str] = &["critical", "high", "medium", "low"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdvisoryCredit {
+    pub login: String,
+    #[serde(rename = "type")]
+    pub credit_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvisoryPackage {
+    /// e.g. `rust`, `npm`, `pip`, `maven`, `go`
+    pub ecosystem: String,
+    pub name: Option<String>,
+}
+
+/// An affected package and the versions involved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvisoryVulnerability {
+    pub package: AdvisoryPackage,
+    /// e.g. `< 1.4.2`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vulnerable_version_range: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub patched_versions: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vulnerable_functions: Vec<String>,
+}
+
+/// Fields for a new draft repository security advisory
+#[derive(Debug, Clone, Serialize)]
+pub struct NewAdvisory {
+    pub summary: String,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cwe_ids: Vec<String>,
+    pub vulnerabilities: Vec<AdvisoryVulnerability>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub credits: Vec<AdvisoryCredit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubAdvisory {
+    pub ghsa_id: String,
+    pub cve_id: Option<String>,
+    pub html_url: String,
+    pub summary: String,
+    /// triage, draft, published or closed
+    pub state: String,
+    pub severity: Option<String>,
+    #[serde(default)]
+    pub credits: Vec<AdvisoryCredit>,
+}
+
+/// Longest secondary rate-limit or search-window wait a search sits out
+/// before giving up with an error
+const SEARCH_MAX_WAIT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GitHubSearchResults<T> {
+    pub total_count: u64,
+    pub incomplete_results: bool,
+    pub items: Vec<T>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubTextMatch {
+    pub fragment: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCodeResultRepository {
+    pub full_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubCodeResult {
+    pub name: String,
+    pub path: String,
+    pub sha: String,
+    pub html_url: String,
+    pub repository: GitHubCodeResultRepository,
+    /// Only returned with the text-match media type
+    #[serde(default)]
+    pub text_matches: Vec<GitHubTextMatch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubIssueResult {
+    #[serde(flatten)]
+    pub issue: GitHubIssue,
+    /// API URL of the issue's repository, e.g. https://api.github.com/repos/o/r
+    pub repository_url: String,
+    #[serde(default)]
+    pub comments: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubRepositoryResult {
+    pub full_name: String,
+    pub description: Option<String>,
+    pub html_url: String,
+    pub language: Option<String>,
+    pub stargazers_count: u64,
+    pub forks_count: u64,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default)]
+    pub archived: bool,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubNotificationSubject {
+    pub title: String,
+    /// API URL of the issue, pull request or release; null for CI activity
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Issue, PullRequest, CheckSuite, Release, Discussion, ...
+    #[serde(rename = "type")]
+    pub subject_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubNotificationRepository {
+    pub full_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubGistFile {
+    pub filename: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub size: u64,
+    pub raw_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubGist {
+    pub id: String,
+    pub html_url: String,
+    pub description: Option<String>,
+    pub public: bool,
+    pub files: HashMap<String, GitHubGistFile>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A thread in the authenticated user's notification inbox
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubNotification {
+    pub id: String,
+    pub unread: bool,
+    /// Why the user was notified: review_requested, mention, ci_activity, ...
+    pub reason: String,
+    pub updated_at: String,
+    pub subject: GitHubNotificationSubject,
+    pub repository: GitHubNotificationRepository,
+}
+
+/// A line-anchored comment on a pull request diff. `line` is the line in the
+/// file (not the diff position); set `start_line` for a multi-line range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewCommentInput {
+    pub path: String,
+    pub line: u64,
+    pub body: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub side: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<u64>,
+}
+
+/// Something that can be reacted to
+#[derive(Debug, Clone, Copy)]
+pub enum ReactionTarget {
+    Issue(u64),
+    IssueComment(u64),
+    ReviewComment(u64),
+}
+
+impl ReactionTarget {
+    fn path(&self) -> String {
+        match self {
+            ReactionTarget::Issue(number) => format!("issues/{}/reactions", number),
+            ReactionTarget::IssueComment(id) => format!("issues/comments/{}/reactions", id),
+            ReactionTarget::ReviewComment(id) => format!("pulls/comments/{}/reactions", id),
+        }
+    }
+}
+
+/// How long a rate-limited response asks callers to wait, from `Retry-After`
+/// or an exhausted window's reset time; None for other 403s
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    if let Some(seconds) = header("retry-after").and_then(|v| v.parse::<u64>().ok()) {
+        return Some(Duration::from_secs(seconds));
+    }
+    if header("x-ratelimit-remaining") == Some("0") {
+        let reset_at: i64 = header("x-ratelimit-reset")?.parse().ok()?;
+        return Some(Duration::from_secs((reset_at - chrono::Utc::now().timestamp()).max(1) as u64));
+    }
+    None
+}
+
+/// The `rel="next"` URL from a paginated response's `Link` header
+fn next_link(headers: &HeaderMap) -> Option<String> {
+    let link = headers.get(reqwest::header::LINK)?.to_str().ok()?;
+    link.split(',').find_map(|part| {
+        let (url, rel) = part.split_once(';')?;
+        rel.contains("rel=\"next\"")
+            .then(|| url.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+    })
+}
+
+/// Page size and item cap for list calls that follow pagination
+#[derive(Debug, Clone, Copy)]
+pub struct Pagination {
+    pub per_page: u32,
+    pub max_items: usize,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Self { per_page: 100, max_items: 1000 }
+    }
+}
+
+impl Pagination {
+    pub fn from_config(config: &crate::config::GitHubConfig) -> Self {
+        Self {
+            per_page: config.per_page.clamp(1, 100),
+            max_items: config.max_list_items.max(1),
+        }
+    }
+}
+
+pub struct GitHubClient {
+    client: Client,
+    base_url: String,
+    host: GitHubHost,
+    token: String,
+    pagination: Pagination,
+}
+
+impl GitHubClient {
+    pub fn new(token: String, host: &GitHubHost) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(|e| AppError::Internal(format!("Invalid token format: {}", e)))?,
+        );
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_static("github-mcp-server/1.0"),
+        );
+
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|e| AppError::HttpClient(e))?;
+
+        Ok(Self {
+            client,
+            base_url: host.api_url.clone(),
+            host: host.clone(),
+            token,
+            pagination: Pagination::default(),
+        })
+    }
+
+    pub fn with_pagination(mut self, pagination: Pagination) -> Self {
+        self.pagination = pagination;
+        self
+    }
+
+    /// `resource` under a key no other token's copy shares, for results kept in memory
+    pub fn cache_key(&self, resource: &str) -> String {
+        super::cache::key(&self.token, resource)
+    }
+
+    pub fn host(&self) -> &GitHubHost {
+        &self.host
+    }
+
+    /// Send a request, recording the rate-limit headers on the response.
+    /// Writes to repositories outside `ALLOWED_REPOSITORIES` are refused.
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let mut request = request.build().map_err(AppError::HttpClient)?;
+        if !matches!(*request.method(), Method::GET | Method::HEAD) {
+            if let Some(path) = request.url().as_str().strip_prefix(&self.base_url) {
+                super::allowlist::check_api_path(path)?;
+            }
+        }
+
+        let conditional = match *request.method() {
+            Method::GET => {
+                let key = super::cache::key(&self.token, request.url().as_str());
+                super::cache::Conditional::prepare(key, &mut request).await
+            }
+            _ => None,
+        };
+
+        if let Some(wait) = super::budget::pacing(&self.token, request.url().path()) {
+            debug!("Rate limit nearly exhausted; pacing {} by {}ms", request.url().path(), wait.as_millis());
+            tokio::time::sleep(wait).await;
+        }
+
+        let response = super::retry::execute(&self.client, request, |headers| {
+            super::budget::observe(&self.token, headers)
+        })
+        .await?;
+        match conditional {
+            Some(conditional) => conditional.complete(response).await,
+            None => Ok(response),
+        }
+    }
+
+    pub async fn get_user(&self) -> Result<GitHubUser> {
+        let url = format!("{}/user", self.base_url);
+        debug!("Fetching GitHub user: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to get user").await.into());
+        }
+
+        let user = response.json::<GitHubUser>().await.map_err(AppError::HttpClient)?;
+        Ok(user)
+    }
+
+    /// `files` maps each file name to `{"content": ...}`
+    pub async fn create_gist(&self, description: Option<&str>, public: bool, files: &Value) -> Result<GitHubGist> {
+        let url = format!("{}/gists", self.base_url);
+        debug!("Creating gist: {}", url);
+
+        let mut body = serde_json::json!({ "public": public, "files": files });
+        if let Some(description) = description {
+            body["description"] = Value::String(description.to_string());
+        }
+        let response = self.send(self.client.post(&url).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to create gist").await.into());
+        }
+
+        let gist = response.json::<GitHubGist>().await.map_err(AppError::HttpClient)?;
+        Ok(gist)
+    }
+
+    /// The authenticated user's gists, newest first
+    pub async fn list_gists(&self, max_items: usize) -> Result<Vec<GitHubGist>> {
+        self.paginate("/gists", &[], "gists", Some(max_items)).await
+    }
+
+    /// Notification threads, newest first: unread only unless `all`, and just
+    /// `repository`'s when given
+    pub async fn list_notifications(
+        &self,
+        repository: Option<(&str, &str)>,
+        all: bool,
+        participating: bool,
+        since: Option<&str>,
+        max_items: usize,
+    ) -> Result<Vec<GitHubNotification>> {
+        let path = match repository {
+            Some((owner, repo)) => format!("/repos/{}/{}/notifications", owner, repo),
+            None => "/notifications".to_string(),
+        };
+        let mut params = vec![("all", all.to_string()), ("participating", participating.to_string())];
+        if let Some(since) = since {
+            params.push(("since", since.to_string()));
+        }
+        self.paginate(&path, &params, "notifications", Some(max_items)).await
+    }
+
+    pub async fn get_notification(&self, thread_id: &str) -> Result<GitHubNotification> {
+        let url = format!("{}/notifications/threads/{}", self.base_url, thread_id);
+        debug!("Fetching notification: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to get notification").await.into());
+        }
+
+        let notification = response.json::<GitHubNotification>().await.map_err(AppError::HttpClient)?;
+        Ok(notification)
+    }
+
+    /// Mark one thread read, or done, which also drops it from the inbox
+    pub async fn mark_notification(&self, thread_id: &str, done: bool) -> Result<()> {
+        let url = format!("{}/notifications/threads/{}", self.base_url, thread_id);
+        debug!("Marking notification {}: {}", if done { "done" } else { "read" }, url);
+
+        let request = if done { self.client.delete(&url) } else { self.client.patch(&url) };
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to mark notification").await.into());
+        }
+
+        Ok(())
+    }
+
+    /// Mark every notification updated before `last_read_at` read, across the
+    /// inbox or in one repository. GitHub may finish this in the background.
+    pub async fn mark_all_notifications_read(&self, repository: Option<(&str, &str)>, last_read_at: &str) -> Result<()> {
+        let url = match repository {
+            Some((owner, repo)) => format!("{}/repos/{}/{}/notifications", self.base_url, owner, repo),
+            None => format!("{}/notifications", self.base_url),
+        };
+        debug!("Marking notifications read: {}", url);
+
+        let request = self.client
+            .put(&url)
+            .json(&serde_json::json!({ "last_read_at": last_read_at, "read": true }));
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to mark notifications read").await.into());
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_repository(&self, owner: &str, repo: &str) -> Result<GitHubRepository> {
+        let url = format!("{}/repos/{}/{}", self.base_url, owner, repo);
+        debug!("Fetching repository: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to get repository").await.into());
+        }
+
+        let repository = response.json::<GitHubRepository>().await.map_err(AppError::HttpClient)?;
+        Ok(repository)
+    }
+
+    pub async fn list_issues(&self, owner: &str, repo: &str, state: Option<&str>) -> Result<Vec<GitHubIssue>> {
+        let params: Vec<(&str, String)> = state.map(|state| ("state", state.to_string())).into_iter().collect();
+        self.paginate(&format!("/repos/{}/{}/issues", owner, repo), &params, "issues", None).await
+    }
+
+    /// Issues (not PRs) matching `filter`, up to `limit`
+    pub async fn list_issues_filtered(&self, owner: &str, repo: &str, filter: &IssueFilter, limit: usize) -> Result<Vec<GitHubIssue>> {
+        let url = format!("{}/repos/{}/{}/issues", self.base_url, owner, repo);
+
+        let mut query = vec![
+            ("state", filter.state.clone().unwrap_or_else(|| "open".to_string())),
+            ("per_page", "100".to_string()),
+        ];
+        if let Some(labels) = filter.labels.as_ref().filter(|l| !l.is_empty()) {
+            query.push(("labels", labels.join(",")));
+        }
+        for (name, value) in [
+            ("assignee", &filter.assignee),
+            ("creator", &filter.creator),
+            ("milestone", &filter.milestone),
+            ("since", &filter.since),
+        ] {
+            if let Some(value) = value {
+                query.push((name, value.trim_start_matches('@').to_string()));
+            }
+        }
+
+        let mut issues = Vec::new();
+        for page in 1.. {
+            debug!("Listing issues: {} (page {})", url, page);
+            let request = self.client
+                .get(&url)
+                .query(&query)
+                .query(&[("page", page)]);
+            let response = self.send(request).await?;
+
+            if !response.status().is_success() {
+                return Err(GitHubError::from_response(response, "Failed to list issues").await.into());
+            }
+
+            let batch = response.json::<Vec<GitHubIssue>>().await.map_err(AppError::HttpClient)?;
+            let done = batch.len() < 100;
+            issues.extend(batch.into_iter().filter(|i| i.pull_request.is_none()));
+            if done || issues.len() >= limit {
+                break;
+            }
+        }
+
+        issues.truncate(limit);
+        Ok(issues)
+    }
+
+    pub async fn list_milestones(&self, owner: &str, repo: &str, state: &str) -> Result<Vec<GitHubMilestone>> {
+        let params = [("state", state.to_string())];
+        self.paginate(&format!("/repos/{}/{}/milestones", owner, repo), &params, "milestones", None).await
+    }
+
+    pub async fn get_milestone(&self, owner: &str, repo: &str, number: u64) -> Result<GitHubMilestone> {
+        let url = format!("{}/repos/{}/{}/milestones/{}", self.base_url, owner, repo, number);
+        debug!("Getting milestone: {}", url);
+
+        let response = self.send(self.client.get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to get milestone").await.into());
+        }
+
+        let milestone = response.json::<GitHubMilestone>().await.map_err(AppError::HttpClient)?;
+        Ok(milestone)
+    }
+
+    pub async fn create_milestone(&self, owner: &str, repo: &str, milestone: &Value) -> Result<GitHubMilestone> {
+        let url = format!("{}/repos/{}/{}/milestones", self.base_url, owner, repo);
+        debug!("Creating milestone: {}", url);
+
+        let request = self.client
+            .post(&url)
+            .json(milestone);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to create milestone").await.into());
+        }
+
+        let milestone = response.json::<GitHubMilestone>().await.map_err(AppError::HttpClient)?;
+        Ok(milestone)
+    }
+
+    /// PATCH a milestone, e.g. `{"state": "closed"}`
+    pub async fn update_milestone(&self, owner: &str, repo: &str, number: u64, changes: &Value) -> Result<GitHubMilestone> {
+        let url = format!("{}/repos/{}/{}/milestones/{}", self.base_url, owner, repo, number);
+        debug!("Updating milestone: {}", url);
+
+        let request = self.client
+            .patch(&url)
+            .json(changes);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to update milestone").await.into());
+        }
+
+        let milestone = response.json::<GitHubMilestone>().await.map_err(AppError::HttpClient)?;
+        Ok(milestone)
+    }
+
+    pub async fn list_labels(&self, owner: &str, repo: &str) -> Result<Vec<GitHubLabel>> {
+        self.paginate(&format!("/repos/{}/{}/labels", owner, repo), &[], "labels", None).await
+    }
+
+    /// `label` holds `name`, `color` and optionally `description`
+    pub async fn create_label(&self, owner: &str, repo: &str, label: &Value) -> Result<GitHubLabel> {
+        let url = format!("{}/repos/{}/{}/labels", self.base_url, owner, repo);
+        debug!("Creating label: {}", url);
+
+        let request = self.client
+            .post(&url)
+            .json(label);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to create label").await.into());
+        }
+
+        let label = response.json::<GitHubLabel>().await.map_err(AppError::HttpClient)?;
+        Ok(label)
+    }
+
+    /// PATCH the label called `name`; `new_name` in `changes` renames it
+    pub async fn update_label(&self, owner: &str, repo: &str, name: &str, changes: &Value) -> Result<GitHubLabel> {
+        let url = self.label_url(owner, repo, name)?;
+        debug!("Updating label: {}", url);
+
+        let request = self.client
+            .patch(url)
+            .json(changes);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to update label").await.into());
+        }
+
+        let label = response.json::<GitHubLabel>().await.map_err(AppError::HttpClient)?;
+        Ok(label)
+    }
+
+    /// Delete a label from the repository and every issue carrying it; false
+    /// when there was no such label
+    pub async fn delete_label(&self, owner: &str, repo: &str, name: &str) -> Result<bool> {
+        let url = self.label_url(owner, repo, name)?;
+        debug!("Deleting label: {}", url);
+
+        let response = self.send(self.client.delete(url)).await?;
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status if status.is_success() => Ok(true),
+            _ => Err(GitHubError::from_response(response, "Failed to delete label").await.into()),
+        }
+    }
+
+    /// Label names may hold spaces and slashes, so they're added as an encoded segment
+    fn label_url(&self, owner: &str, repo: &str, name: &str) -> Result<reqwest::Url> {
+        let mut url = reqwest::Url::parse(&format!("{}/repos/{}/{}/labels", self.base_url, owner, repo))
+            .map_err(|e| AppError::Internal(format!("Invalid URL: {}", e)))?;
+        url.path_segments_mut()
+            .map_err(|_| AppError::Internal("Invalid base URL".to_string()))?
+            .push(name);
+        Ok(url)
+    }
+
+    pub async fn add_labels(&self, owner: &str, repo: &str, number: u64, labels: &[String]) -> Result<()> {
+        let url = format!("{}/repos/{}/{}/issues/{}/labels", self.base_url, owner, repo, number);
+        debug!("Adding labels: {}", url);
+
+        let request = self.client
+            .post(&url)
+            .json(&serde_json::json!({ "labels": labels }));
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to add labels").await.into());
+        }
+
+        Ok(())
+    }
+
+    /// Remove a label; a label that isn't on the issue is not an error
+    pub async fn remove_label(&self, owner: &str, repo: &str, number: u64, label: &str) -> Result<()> {
+        let mut url = reqwest::Url::parse(&format!("{}/repos/{}/{}/issues/{}/labels", self.base_url, owner, repo, number))
+            .map_err(|e| AppError::Internal(format!("Invalid URL: {}", e)))?;
+        url.path_segments_mut()
+            .map_err(|_| AppError::Internal("Invalid base URL".to_string()))?
+            .push(label);
+        debug!("Removing label: {}", url);
+
+        let request = self.client.delete(url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() && response.status() != reqwest::StatusCode::NOT_FOUND {
+            return Err(GitHubError::from_response(response, "Failed to remove label").await.into());
+        }
+
+        Ok(())
+    }
+
+    pub async fn create_issue(&self, owner: &str, repo: &str, title: &str, body: Option<&str>, labels: Option<Vec<&str>>) -> Result<GitHubIssue> {
+        let url = format!("{}/repos/{}/{}/issues", self.base_url, owner, repo);
+        debug!("Creating issue: {}", url);
+
+        let mut payload = serde_json::json!({
+            "title": title
+        });
+
+        if let Some(body) = body {
+            payload["body"] = serde_json::Value::String(body.to_string());
+        }
+
+        if let Some(labels) = labels {
+            payload["labels"] = serde_json::Value::Array(
+                labels.into_iter().map(|l| serde_json::Value::String(l.to_string())).collect()
+            );
+        }
+
+        let request = self.client
+            .post(&url)
+            .json(&payload);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to create issue").await.into());
+        }
+
+        let issue = response.json::<GitHubIssue>().await.map_err(AppError::HttpClient)?;
+        Ok(issue)
+    }
+
+    /// List commits reachable from `sha` (a branch name or SHA), newest first
+    pub async fn list_commits(&self, owner: &str, repo: &str, sha: Option<&str>, limit: u32) -> Result<Vec<GitHubCommit>> {
+        let url = format!("{}/repos/{}/{}/commits", self.base_url, owner, repo);
+        debug!("Listing commits: {}", url);
+
+        let mut query = vec![("per_page", limit.min(100).to_string())];
+        if let Some(sha) = sha {
+            query.push(("sha", sha.to_string()));
+        }
+
+        let request = self.client
+            .get(&url)
+            .query(&query);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to list commits").await.into());
+        }
+
+        let commits = response.json::<Vec<GitHubCommit>>().await.map_err(AppError::HttpClient)?;
+        Ok(commits)
+    }
+
+    pub async fn get_combined_status(&self, owner: &str, repo: &str, sha: &str) -> Result<GitHubCombinedStatus> {
+        let url = format!("{}/repos/{}/{}/commits/{}/status", self.base_url, owner, repo, sha);
+        debug!("Fetching combined status: {}", url);
+
+        let request = self.client
+            .get(&url)
+            .query(&[("per_page", "100")]);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to get combined status").await.into());
+        }
+
+        let combined = response.json::<GitHubCombinedStatus>().await.map_err(AppError::HttpClient)?;
+        Ok(combined)
+    }
+
+    /// Latest check run per check for a commit
+    pub async fn list_check_runs(&self, owner: &str, repo: &str, sha: &str) -> Result<Vec<GitHubCheckRun>> {
+        let url = format!("{}/repos/{}/{}/commits/{}/check-runs", self.base_url, owner, repo, sha);
+        debug!("Listing check runs: {}", url);
+
+        let request = self.client
+            .get(&url)
+            .query(&[("per_page", "100"), ("filter", "latest")]);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to list check runs").await.into());
+        }
+
+        let body = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        Ok(serde_json::from_value(body["check_runs"].clone())?)
+    }
+
+    /// Status check names branch protection requires; None when the branch
+    /// isn't protected or the token can't read its protection
+    pub async fn get_required_status_checks(&self, owner: &str, repo: &str, branch: &str) -> Result<Option<Vec<String>>> {
+        let url = format!(
+            "{}/repos/{}/{}/branches/{}/protection/required_status_checks",
+            self.base_url, owner, repo, branch
+        );
+        debug!("Fetching required status checks: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::FORBIDDEN => return Ok(None),
+            status if !status.is_success() => {
+                return Err(GitHubError::from_response(response, "Failed to get required status checks").await.into());
+            }
+            _ => {}
+        }
+
+        let body = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        let contexts = body["contexts"]
+            .as_array()
+            .map(|c| c.iter().filter_map(|v| v.as_str()).map(String::from).collect())
+            .unwrap_or_default();
+        Ok(Some(contexts))
+    }
+
+    /// Protection rules on `branch`; None when the branch is unprotected or
+    /// the token can't read its protection (reading requires admin access)
+    pub async fn get_branch_protection(&self, owner: &str, repo: &str, branch: &str) -> Result<Option<GitHubBranchProtection>> {
+        let url = format!("{}/repos/{}/{}/branches/{}/protection", self.base_url, owner, repo, branch);
+        debug!("Fetching branch protection: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::FORBIDDEN => return Ok(None),
+            status if !status.is_success() => {
+                return Err(GitHubError::from_response(response, "Failed to get branch protection").await.into());
+            }
+            _ => {}
+        }
+
+        let protection = response.json::<GitHubBranchProtection>().await.map_err(AppError::HttpClient)?;
+        Ok(Some(protection))
+    }
+
+    /// Whether `login` is an active member of `org`'s team `team`
+    pub async fn is_team_member(&self, org: &str, team: &str, login: &str) -> Result<bool> {
+        let url = format!("{}/orgs/{}/teams/{}/memberships/{}", self.base_url, org, team, login);
+        debug!("Checking team membership: {}", url);
+
+        let response = self.send(self.client.get(&url)).await?;
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status if status.is_success() => {
+                let membership = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+                Ok(membership["state"] == "active")
+            }
+            _ => Err(GitHubError::from_response(response, "Failed to check team membership").await.into()),
+        }
+    }
+
+    /// Replace the protection on `branch`; `body` must carry every required section
+    pub async fn update_branch_protection(&self, owner: &str, repo: &str, branch: &str, body: &Value) -> Result<GitHubBranchProtection> {
+        let url = format!("{}/repos/{}/{}/branches/{}/protection", self.base_url, owner, repo, branch);
+        debug!("Updating branch protection: {}", url);
+
+        let request = self.client.put(&url).json(body);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to update branch protection").await.into());
+        }
+
+        let protection = response.json::<GitHubBranchProtection>().await.map_err(AppError::HttpClient)?;
+        Ok(protection)
+    }
+
+    /// Commits on a pull request, oldest first (GitHub caps this at 250)
+    pub async fn list_pull_request_commits(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<GitHubCommit>> {
+        let path = format!("/repos/{}/{}/pulls/{}/commits", owner, repo, number);
+        self.paginate(&path, &[], "pull request commits", Some(250)).await
+    }
+
+    pub async fn get_issue(&self, owner: &str, repo: &str, number: u64) -> Result<GitHubIssue> {
+        let url = format!("{}/repos/{}/{}/issues/{}", self.base_url, owner, repo, number);
+        debug!("Fetching issue: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to get issue").await.into());
+        }
+
+        let issue = response.json::<GitHubIssue>().await.map_err(AppError::HttpClient)?;
+        Ok(issue)
+    }
+
+    pub async fn add_assignees(&self, owner: &str, repo: &str, number: u64, assignees: &[&str]) -> Result<GitHubIssue> {
+        let url = format!("{}/repos/{}/{}/issues/{}/assignees", self.base_url, owner, repo, number);
+        debug!("Adding assignees: {}", url);
+
+        let request = self.client
+            .post(&url)
+            .json(&serde_json::json!({ "assignees": assignees }));
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to add assignees").await.into());
+        }
+
+        let issue = response.json::<GitHubIssue>().await.map_err(AppError::HttpClient)?;
+        Ok(issue)
+    }
+
+    pub async fn create_issue_comment(&self, owner: &str, repo: &str, number: u64, body: &str) -> Result<Value> {
+        let url = format!("{}/repos/{}/{}/issues/{}/comments", self.base_url, owner, repo, number);
+        debug!("Creating issue comment: {}", url);
+
+        let request = self.client
+            .post(&url)
+            .json(&serde_json::json!({ "body": body }));
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to create comment").await.into());
+        }
+
+        let comment = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        Ok(comment)
+    }
+
+    pub async fn list_issue_comments(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<Value>> {
+        self.paginate(&format!("/repos/{}/{}/issues/{}/comments", owner, repo, number), &[], "comments", None).await
+    }
+
+    /// Timeline events of an issue or pull request, oldest first, reading at
+    /// most `max_pages` pages of 100, and whether more pages were left unread
+    pub async fn list_issue_timeline(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        max_pages: u32,
+    ) -> Result<(Vec<Value>, bool)> {
+        let mut next = Some(format!("/repos/{}/{}/issues/{}/timeline", owner, repo, number));
+        let mut events = Vec::new();
+        let mut pages = 0;
+
+        while pages < max_pages {
+            let Some(path) = next.take() else { break };
+            // Next-page URLs already carry the query
+            let params = if pages == 0 { vec![("per_page", "100".to_string())] } else { Vec::new() };
+            let (page, following) = self.get_json_page(&path, &params).await?;
+            if let Value::Array(page) = page {
+                events.extend(page);
+            }
+            next = following;
+            pages += 1;
+        }
+
+        Ok((events, next.is_some()))
+    }
+
+    pub async fn add_reaction(&self, owner: &str, repo: &str, target: ReactionTarget, content: &str) -> Result<GitHubReaction> {
+        let url = format!("{}/repos/{}/{}/{}", self.base_url, owner, repo, target.path());
+        debug!("Adding reaction: {}", url);
+
+        let request = self.client
+            .post(&url)
+            .json(&serde_json::json!({ "content": content }));
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to add reaction").await.into());
+        }
+
+        let reaction = response.json::<GitHubReaction>().await.map_err(AppError::HttpClient)?;
+        Ok(reaction)
+    }
+
+    pub async fn list_reactions(&self, owner: &str, repo: &str, target: ReactionTarget) -> Result<Vec<GitHubReaction>> {
+        self.paginate(&format!("/repos/{}/{}/{}", owner, repo, target.path()), &[], "reactions", None).await
+    }
+
+    /// PATCH an issue (or PR, via the issues API) with the given fields
+    pub async fn update_issue(&self, owner: &str, repo: &str, number: u64, changes: &Value) -> Result<GitHubIssue> {
+        let url = format!("{}/repos/{}/{}/issues/{}", self.base_url, owner, repo, number);
+        debug!("Updating issue: {}", url);
+
+        let request = self.client
+            .patch(&url)
+            .json(changes);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to update issue").await.into());
+        }
+
+        let issue = response.json::<GitHubIssue>().await.map_err(AppError::HttpClient)?;
+        Ok(issue)
+    }
+
+    pub async fn close_issue(&self, owner: &str, repo: &str, number: u64, state_reason: Option<&str>) -> Result<GitHubIssue> {
+        let url = format!("{}/repos/{}/{}/issues/{}", self.base_url, owner, repo, number);
+        debug!("Closing issue: {}", url);
+
+        let request = self.client
+            .patch(&url)
+            .json(&serde_json::json!({
+                "state": "closed",
+                "state_reason": state_reason.unwrap_or("completed")
+            }));
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to close issue").await.into());
+        }
+
+        let issue = response.json::<GitHubIssue>().await.map_err(AppError::HttpClient)?;
+        Ok(issue)
+    }
+
+    /// Lock an issue's conversation; `lock_reason` is one of off-topic, too heated, resolved or spam
+    pub async fn lock_issue(&self, owner: &str, repo: &str, number: u64, lock_reason: Option<&str>) -> Result<()> {
+        let url = format!("{}/repos/{}/{}/issues/{}/lock", self.base_url, owner, repo, number);
+        debug!("Locking issue: {}", url);
+
+        let payload = match lock_reason {
+            Some(reason) => serde_json::json!({ "lock_reason": reason }),
+            None => serde_json::json!({}),
+        };
+
+        let request = self.client
+            .put(&url)
+            .json(&payload);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to lock issue").await.into());
+        }
+
+        Ok(())
+    }
+
+    pub async fn list_pull_requests(&self, owner: &str, repo: &str, state: Option<&str>) -> Result<Vec<GitHubPullRequest>> {
+        let params: Vec<(&str, String)> = state.map(|state| ("state", state.to_string())).into_iter().collect();
+        self.paginate(&format!("/repos/{}/{}/pulls", owner, repo), &params, "pull requests", None).await
+    }
+
+    /// Pull requests whose head is `branch` in `owner`'s repository, newest first
+    pub async fn list_pull_requests_for_branch(&self, owner: &str, repo: &str, branch: &str) -> Result<Vec<GitHubPullRequest>> {
+        let params = [("head", format!("{}:{}", owner, branch)), ("state", "all".to_string())];
+        self.paginate(&format!("/repos/{}/{}/pulls", owner, repo), &params, "pull requests", None).await
+    }
+
+    /// Merge a pull request, provided its head is still `sha`, the commit that
+    /// was checked; GitHub refuses with 409 if more was pushed since
+    pub async fn merge_pull_request(&self, owner: &str, repo: &str, number: u64, merge_method: &str, sha: &str) -> Result<Value> {
+        let url = format!("{}/repos/{}/{}/pulls/{}/merge", self.base_url, owner, repo, number);
+        debug!("Merging pull request: {}", url);
+
+        let request = self.client
+            .put(&url)
+            .json(&serde_json::json!({ "merge_method": merge_method, "sha": sha }));
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to merge pull request").await.into());
+        }
+
+        let result = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        Ok(result)
+    }
+
+    pub async fn get_pull_request(&self, owner: &str, repo: &str, number: u64) -> Result<GitHubPullRequest> {
+        let url = format!("{}/repos/{}/{}/pulls/{}", self.base_url, owner, repo, number);
+        debug!("Fetching pull request: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to get pull request").await.into());
+        }
+
+        let pr = response.json::<GitHubPullRequest>().await.map_err(AppError::HttpClient)?;
+        Ok(pr)
+    }
+
+    /// The pull request as a unified diff
+    pub async fn get_pull_request_diff(&self, owner: &str, repo: &str, number: u64) -> Result<String> {
+        let url = format!("{}/repos/{}/{}/pulls/{}", self.base_url, owner, repo, number);
+        debug!("Fetching pull request diff: {}", url);
+
+        let request = self.client
+            .get(&url)
+            .header(reqwest::header::ACCEPT, "application/vnd.github.diff");
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to get pull request diff").await.into());
+        }
+
+        let diff = response.text().await.map_err(AppError::HttpClient)?;
+        Ok(diff)
+    }
+
+    /// Changed files with per-file patches (GitHub caps this at 3000 files)
+    pub async fn list_pull_request_files(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<GitHubPullRequestFile>> {
+        let path = format!("/repos/{}/{}/pulls/{}/files", owner, repo, number);
+        self.paginate(&path, &[], "pull request files", Some(3000)).await
+    }
+
+    pub async fn list_workflow_runs(
+        &self,
+        owner: &str,
+        repo: &str,
+        filter: &WorkflowRunFilter,
+        limit: u32,
+    ) -> Result<Vec<GitHubWorkflowRun>> {
+        let url = match &filter.workflow {
+            Some(workflow) => format!("{}/repos/{}/{}/actions/workflows/{}/runs", self.base_url, owner, repo, workflow),
+            None => format!("{}/repos/{}/{}/actions/runs", self.base_url, owner, repo),
+        };
+        debug!("Listing workflow runs: {}", url);
+
+        let mut query = vec![("per_page", limit.min(100).to_string())];
+        for (name, value) in [
+            ("branch", &filter.branch),
+            ("head_sha", &filter.head_sha),
+            ("status", &filter.status),
+            ("event", &filter.event),
+            ("created", &filter.created),
+        ] {
+            if let Some(value) = value {
+                query.push((name, value.clone()));
+            }
+        }
+
+        let request = self.client
+            .get(&url)
+            .query(&query);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to list workflow runs").await.into());
+        }
+
+        let body = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        Ok(serde_json::from_value(body["workflow_runs"].clone())?)
+    }
+
+    pub async fn get_workflow_run(&self, owner: &str, repo: &str, run_id: u64) -> Result<GitHubWorkflowRun> {
+        let url = format!("{}/repos/{}/{}/actions/runs/{}", self.base_url, owner, repo, run_id);
+        debug!("Fetching workflow run: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to get workflow run").await.into());
+        }
+
+        let run = response.json::<GitHubWorkflowRun>().await.map_err(AppError::HttpClient)?;
+        Ok(run)
+    }
+
+    /// Jobs from the run's latest attempt
+    pub async fn list_run_jobs(&self, owner: &str, repo: &str, run_id: u64) -> Result<Vec<GitHubJob>> {
+        let url = format!("{}/repos/{}/{}/actions/runs/{}/jobs", self.base_url, owner, repo, run_id);
+        debug!("Listing workflow jobs: {}", url);
+
+        let request = self.client
+            .get(&url)
+            .query(&[("per_page", "100"), ("filter", "latest")]);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to list workflow jobs").await.into());
+        }
+
+        let body = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        Ok(serde_json::from_value(body["jobs"].clone())?)
+    }
+
+    /// Plain-text log for a job; GitHub redirects to a short-lived download URL
+    pub async fn get_job_logs(&self, owner: &str, repo: &str, job_id: u64) -> Result<String> {
+        let url = format!("{}/repos/{}/{}/actions/jobs/{}/logs", self.base_url, owner, repo, job_id);
+        debug!("Fetching job logs: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to get job logs").await.into());
+        }
+
+        let logs = response.text().await.map_err(AppError::HttpClient)?;
+        Ok(logs)
+    }
+
+    /// Trigger a `workflow_dispatch` workflow on `git_ref`; GitHub doesn't return the run
+    pub async fn dispatch_workflow(&self, owner: &str, repo: &str, workflow: &str, git_ref: &str, inputs: &Value) -> Result<()> {
+        let url = format!("{}/repos/{}/{}/actions/workflows/{}/dispatches", self.base_url, owner, repo, workflow);
+        debug!("Dispatching workflow: {}", url);
+
+        let request = self.client
+            .post(&url)
+            .json(&serde_json::json!({ "ref": git_ref, "inputs": inputs }));
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to dispatch workflow").await.into());
+        }
+
+        Ok(())
+    }
+
+    /// Whether `tag` exists in the repository
+    pub async fn tag_exists(&self, owner: &str, repo: &str, tag: &str) -> Result<bool> {
+        let url = format!("{}/repos/{}/{}/git/ref/tags/{}", self.base_url, owner, repo, tag);
+        debug!("Checking tag: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            _ => {
+                Err(GitHubError::from_response(response, "Failed to check tag").await.into())
+            }
+        }
+    }
+
+    /// Point a new lightweight tag at `sha`
+    pub async fn create_tag_ref(&self, owner: &str, repo: &str, tag: &str, sha: &str) -> Result<()> {
+        let url = format!("{}/repos/{}/{}/git/refs", self.base_url, owner, repo);
+        debug!("Creating tag {}: {}", tag, url);
+
+        let request = self.client
+            .post(&url)
+            .json(&serde_json::json!({ "ref": format!("refs/tags/{}", tag), "sha": sha }));
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to create tag").await.into());
+        }
+
+        Ok(())
+    }
+
+    /// Delete a tag; false when it doesn't exist
+    pub async fn delete_tag_ref(&self, owner: &str, repo: &str, tag: &str) -> Result<bool> {
+        let url = self.ref_url(owner, repo, "tags", tag)?;
+        debug!("Deleting tag: {}", url);
+
+        let request = self.client.delete(url);
+        let response = self.send(request).await?;
+
+        match response.status() {
+            reqwest::StatusCode::UNPROCESSABLE_ENTITY | reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status if !status.is_success() => {
+                Err(GitHubError::from_response(response, "Failed to delete tag").await.into())
+            }
+            _ => Ok(true),
+        }
+    }
+
+    /// `git/refs/{kind}/{name}`, with each part of `name` percent-encoded
+    fn ref_url(&self, owner: &str, repo: &str, kind: &str, name: &str) -> Result<reqwest::Url> {
+        let invalid = |e: String| AppError::Internal(format!("Invalid GitHub API URL {}: {}", self.base_url, e));
+        let mut url = reqwest::Url::parse(&format!("{}/repos/{}/{}/git/refs/{}", self.base_url, owner, repo, kind))
+            .map_err(|e| invalid(e.to_string()))?;
+        url.path_segments_mut()
+            .map_err(|_| invalid("not a base URL".to_string()))?
+            .extend(name.split('/'));
+        Ok(url)
+    }
+
+    /// Commit a branch, tag or SHA currently points at
+    pub async fn get_commit(&self, owner: &str, repo: &str, git_ref: &str) -> Result<GitHubCommit> {
+        let url = format!("{}/repos/{}/{}/commits/{}", self.base_url, owner, repo, git_ref);
+        debug!("Fetching commit: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to get commit").await.into());
+        }
+
+        let commit = response.json::<GitHubCommit>().await.map_err(AppError::HttpClient)?;
+        Ok(commit)
+    }
+
+    /// Releases newest first, drafts included when the token can see them
+    pub async fn list_releases(&self, owner: &str, repo: &str, limit: u32) -> Result<Vec<GitHubRelease>> {
+        let url = format!("{}/repos/{}/{}/releases", self.base_url, owner, repo);
+        debug!("Listing releases: {}", url);
+
+        let request = self.client
+            .get(&url)
+            .query(&[("per_page", limit.min(100))]);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to list releases").await.into());
+        }
+
+        let releases = response.json::<Vec<GitHubRelease>>().await.map_err(AppError::HttpClient)?;
+        Ok(releases)
+    }
+
+    /// The most recent published, non-prerelease release, if any
+    pub async fn get_latest_release(&self, owner: &str, repo: &str) -> Result<Option<GitHubRelease>> {
+        let url = format!("{}/repos/{}/{}/releases/latest", self.base_url, owner, repo);
+        debug!("Fetching latest release: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND => Ok(None),
+            status if !status.is_success() => {
+                Err(GitHubError::from_response(response, "Failed to get latest release").await.into())
+            }
+            _ => Ok(Some(response.json::<GitHubRelease>().await.map_err(AppError::HttpClient)?)),
+        }
+    }
+
+    pub async fn create_release(&self, owner: &str, repo: &str, release: &NewRelease) -> Result<GitHubRelease> {
+        let url = format!("{}/repos/{}/{}/releases", self.base_url, owner, repo);
+        debug!("Creating release {}: {}", release.tag_name, url);
+
+        let request = self.client
+            .post(&url)
+            .json(release);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to create release").await.into());
+        }
+
+        let release = response.json::<GitHubRelease>().await.map_err(AppError::HttpClient)?;
+        Ok(release)
+    }
+
+    /// Upload `data` as a release asset named `name`
+    pub async fn upload_release_asset(
+        &self,
+        release: &GitHubRelease,
+        name: &str,
+        content_type: &str,
+        data: Vec<u8>,
+    ) -> Result<GitHubReleaseAsset> {
+        // Drop the `{?name,label}` template suffix
+        let url = release.upload_url.split('{').next().unwrap_or(&release.upload_url);
+        debug!("Uploading release asset {}: {}", name, url);
+
+        let request = self.client
+            .post(url)
+            .query(&[("name", name)])
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(data);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to upload release asset").await.into());
+        }
+
+        let asset = response.json::<GitHubReleaseAsset>().await.map_err(AppError::HttpClient)?;
+        Ok(asset)
+    }
+
+    /// Pull requests merged into `base` after `since` (RFC 3339), oldest first
+    pub async fn list_merged_pull_requests(&self, owner: &str, repo: &str, base: &str, since: Option<&str>) -> Result<Vec<GitHubPullRequest>> {
+        let url = format!("{}/repos/{}/{}/pulls", self.base_url, owner, repo);
+        let mut merged = Vec::new();
+
+        // Closed PRs come back most recently updated first, so stop once a
+        // page reaches PRs last touched before `since`
+        for page in 1..=10 {
+            debug!("Listing merged pull requests: {} (page {})", url, page);
+            let request = self.client
+                .get(&url)
+                .query(&[("state", "closed"), ("base", base), ("sort", "updated"), ("direction", "desc"), ("per_page", "100")])
+                .query(&[("page", page)]);
+            let response = self.send(request).await?;
+
+            if !response.status().is_success() {
+                return Err(GitHubError::from_response(response, "Failed to list pull requests").await.into());
+            }
+
+            let batch = response.json::<Vec<GitHubPullRequest>>().await.map_err(AppError::HttpClient)?;
+            let done = batch.len() < 100
+                || since.map_or(false, |since| batch.last().map_or(true, |pr| pr.updated_at.as_str() < since));
+            merged.extend(batch.into_iter().filter(|pr| match (&pr.merged_at, since) {
+                (Some(merged_at), Some(since)) => merged_at.as_str() > since,
+                (Some(_), None) => true,
+                (None, _) => false,
+            }));
+            if done {
+                break;
+            }
+        }
+
+        merged.sort_by(|a, b| a.merged_at.cmp(&b.merged_at));
+        Ok(merged)
+    }
+
+    pub async fn create_security_advisory(&self, owner: &str, repo: &str, advisory: &NewAdvisory) -> Result<GitHubAdvisory> {
+        let url = format!("{}/repos/{}/{}/security-advisories", self.base_url, owner, repo);
+        debug!("Creating draft security advisory: {}", url);
+
+        let request = self.client
+            .post(&url)
+            .json(advisory);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to create security advisory").await.into());
+        }
+
+        let advisory = response.json::<GitHubAdvisory>().await.map_err(AppError::HttpClient)?;
+        Ok(advisory)
+    }
+
+    pub async fn get_security_advisory(&self, owner: &str, repo: &str, ghsa_id: &str) -> Result<GitHubAdvisory> {
+        let url = format!("{}/repos/{}/{}/security-advisories/{}", self.base_url, owner, repo, ghsa_id);
+        debug!("Fetching security advisory: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to get security advisory").await.into());
+        }
+
+        let advisory = response.json::<GitHubAdvisory>().await.map_err(AppError::HttpClient)?;
+        Ok(advisory)
+    }
+
+    /// Replace the advisory's credits with `credits`
+    pub async fn set_advisory_credits(&self, owner: &str, repo: &str, ghsa_id: &str, credits: &[AdvisoryCredit]) -> Result<GitHubAdvisory> {
+        let url = format!("{}/repos/{}/{}/security-advisories/{}", self.base_url, owner, repo, ghsa_id);
+        debug!("Updating advisory credits: {}", url);
+
+        let request = self.client
+            .patch(&url)
+            .json(&serde_json::json!({ "credits": credits }));
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to update security advisory").await.into());
+        }
+
+        let advisory = response.json::<GitHubAdvisory>().await.map_err(AppError::HttpClient)?;
+        Ok(advisory)
+    }
+
+    /// Ask GitHub, as a CNA, to assign a CVE; the ID appears on the advisory once granted
+    pub async fn request_advisory_cve(&self, owner: &str, repo: &str, ghsa_id: &str) -> Result<()> {
+        let url = format!("{}/repos/{}/{}/security-advisories/{}/cve", self.base_url, owner, repo, ghsa_id);
+        debug!("Requesting CVE: {}", url);
+
+        let request = self.client.post(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to request CVE").await.into());
+        }
+
+        Ok(())
+    }
+
+    /// Commits reachable from `head` but not `base`, oldest first, up to `limit`
+    pub async fn compare_commits(&self, owner: &str, repo: &str, base: &str, head: &str, limit: usize) -> Result<Vec<GitHubCommit>> {
+        let url = format!("{}/repos/{}/{}/compare/{}...{}", self.base_url, owner, repo, base, head);
+        let mut commits = Vec::new();
+
+        for page in 1.. {
+            debug!("Comparing refs: {} (page {})", url, page);
+            let request = self.client
+                .get(&url)
+                .query(&[("per_page", 100), ("page", page)]);
+            let response = self.send(request).await?;
+
+            if !response.status().is_success() {
+                return Err(GitHubError::from_response(response, &format!("Failed to compare {}...{}", base, head)).await.into());
+            }
+
+            let body = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+            let batch: Vec<GitHubCommit> = serde_json::from_value(body["commits"].clone())?;
+            let done = batch.len() < 100;
+            commits.extend(batch);
+            if done || commits.len() >= limit {
+                break;
+            }
+        }
+
+        commits.truncate(limit);
+        Ok(commits)
+    }
+
+    /// Pull requests a commit belongs to, e.g. the PR it was merged through
+    pub async fn list_commit_pull_requests(&self, owner: &str, repo: &str, sha: &str) -> Result<Vec<GitHubPullRequest>> {
+        let url = format!("{}/repos/{}/{}/commits/{}/pulls", self.base_url, owner, repo, sha);
+        debug!("Listing pull requests for commit: {}", url);
+
+        let request = self.client.get(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to list commit pull requests").await.into());
+        }
+
+        let prs = response.json::<Vec<GitHubPullRequest>>().await.map_err(AppError::HttpClient)?;
+        Ok(prs)
+    }
+
+    /// One page of `/search/{kind}` (code, issues or repositories). Waits out
+    /// an exhausted search window or a secondary rate limit when it clears
+    /// within `SEARCH_MAX_WAIT`, otherwise fails with the time to retry after
+    pub async fn search<T: DeserializeOwned>(
+        &self,
+        kind: &str,
+        query: &str,
+        params: &[(&str, String)],
+        text_matches: bool,
+    ) -> Result<GitHubSearchResults<T>> {
+        let url = format!("{}/search/{}", self.base_url, kind);
+
+        // Code search has its own, smaller window
+        let resource = if kind == "code" { "code_search" } else { "search" };
+        if let Some(snapshot) = super::budget::get(&self.token, resource).filter(|s| s.remaining == 0) {
+            let wait = Duration::from_secs((snapshot.reset_at - chrono::Utc::now().timestamp()).max(1) as u64);
+            if wait > SEARCH_MAX_WAIT {
+                return Err(GitHubError::rate_limited(
+                    format!("{} rate limit exhausted; retry after {}s", resource, wait.as_secs()),
+                    Some(snapshot.reset_at),
+                ).into());
+            }
+            debug!("Waiting {}s for the {} rate limit window", wait.as_secs(), resource);
+            tokio::time::sleep(wait).await;
+        }
+
+        let mut retried = false;
+        loop {
+            debug!("Searching {}: {}", kind, query);
+            let mut request = self.client
+                .get(&url)
+                .query(&[("q", query)])
+                .query(params);
+            if text_matches {
+                request = request.header(reqwest::header::ACCEPT, "application/vnd.github.text-match+json");
+            }
+            let response = self.send(request).await?;
+            let status = response.status();
+
+            if status == reqwest::StatusCode::FORBIDDEN || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                if let Some(wait) = retry_after(response.headers()) {
+                    if !retried && wait <= SEARCH_MAX_WAIT {
+                        warn!("Search rate limited, retrying in {}s", wait.as_secs());
+                        tokio::time::sleep(wait).await;
+                        retried = true;
+                        continue;
+                    }
+                    return Err(GitHubError::rate_limited(
+                        format!("Search rate limited by GitHub; retry after {}s", wait.as_secs()),
+                        Some(chrono::Utc::now().timestamp() + wait.as_secs() as i64),
+                    ).into());
+                }
+            }
+
+            if !status.is_success() {
+                return Err(GitHubError::from_response(response, &format!("Failed to search {}", kind)).await.into());
+            }
+
+            let results = response.json::<GitHubSearchResults<T>>().await.map_err(AppError::HttpClient)?;
+            return Ok(results);
+        }
+    }
+
+    /// Every item of a list endpoint, following `Link` headers until the
+    /// last page or `max_items` (the configured cap when None). `path` is
+    /// relative to the API root; `what` names the items in errors.
+    pub async fn paginate<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &[(&str, String)],
+        what: &str,
+        max_items: Option<usize>,
+    ) -> Result<Vec<T>> {
+        let max_items = max_items.unwrap_or(self.pagination.max_items);
+        let per_page = (self.pagination.per_page as usize).min(max_items.max(1)).to_string();
+        let mut url = Some(format!("{}{}", self.base_url, path));
+        let mut items = Vec::new();
+        let mut first = true;
+
+        while let Some(page_url) = url.take() {
+            debug!("Listing {}: {}", what, page_url);
+            let mut request = self.client.get(&page_url);
+            // Next-page URLs already carry the query
+            if first {
+                request = request.query(params).query(&[("per_page", &per_page)]);
+                first = false;
+            }
+            let response = self.send(request).await?;
+
+            if !response.status().is_success() {
+                return Err(GitHubError::from_response(response, &format!("Failed to list {}", what)).await.into());
+            }
+
+            url = next_link(response.headers()).filter(|next| next.starts_with(&self.base_url));
+            items.extend(response.json::<Vec<T>>().await.map_err(AppError::HttpClient)?);
+            if items.len() >= max_items {
+                break;
+            }
+        }
+
+        items.truncate(max_items);
+        Ok(items)
+    }
+
+    /// Every node of the connection at `pointer` in a GraphQL query that
+    /// takes a `$cursor` variable, up to `max_items` (the configured cap
+    /// when None)
+    async fn paginate_graphql<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        mut variables: Value,
+        pointer: &str,
+        max_items: Option<usize>,
+    ) -> Result<Vec<T>> {
+        let max_items = max_items.unwrap_or(self.pagination.max_items);
+        let mut nodes = Vec::new();
+
+        loop {
+            debug!("Fetching {} (cursor: {})", pointer, variables["cursor"]);
+            let data = self.graphql(query, variables.clone()).await?;
+            let connection = data
+                .pointer(pointer)
+                .cloned()
+                .ok_or_else(|| AppError::GitHubApi(format!("GraphQL response has no {}", pointer)))?;
+
+            let page: GraphQlConnection<T> = serde_json::from_value(connection)?;
+            nodes.extend(page.nodes.into_iter().flatten());
+            if nodes.len() >= max_items {
+                break;
+            }
+
+            match page.page_info {
+                Some(GraphQlPageInfo { has_next_page: true, end_cursor: Some(next) }) => variables["cursor"] = Value::String(next),
+                _ => break,
+            }
+        }
+
+        nodes.truncate(max_items);
+        Ok(nodes)
+    }
+
+    /// One page of an arbitrary GET, where `path` is relative to the API root
+    /// or is a next-page URL from a previous call. Returns the parsed body and
+    /// the next page's URL.
+    pub async fn get_json_page(&self, path: &str, params: &[(&str, String)]) -> Result<(Value, Option<String>)> {
+        let url = self.page_url(path)?;
+        debug!("Fetching {}", url);
+
+        let request = self.client.get(url).query(params);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, &format!("Failed to get {}", path)).await.into());
+        }
+
+        let next = next_link(response.headers()).filter(|next| next.starts_with(&self.base_url));
+        let body = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        Ok((body, next))
+    }
+
+    fn page_url(&self, path: &str) -> Result<reqwest::Url> {
+        let url = if path.starts_with(&self.base_url) {
+            path.to_string()
+        } else if path.starts_with('/') {
+            format!("{}{}", self.base_url, path)
+        } else {
+            return Err(AppError::Validation(format!("{} is not a GitHub API path", path)));
+        };
+        reqwest::Url::parse(&url).map_err(|e| AppError::Validation(format!("{} is not a valid URL: {}", url, e)))
+    }
+
+    /// The API path `get_json_page` would request for `path`, as the URL
+    /// parser leaves it: dot segments, encoded or not, are resolved, so this
+    /// is what to check against REST_ALLOWED_PATHS
+    pub fn resolved_api_path(&self, path: &str) -> Result<String> {
+        let url = self.page_url(path)?;
+        let base = reqwest::Url::parse(&self.base_url)
+            .map_err(|e| AppError::Internal(format!("Invalid GitHub API URL {}: {}", self.base_url, e)))?;
+        let outside = || AppError::Validation(format!("{} is outside the GitHub API", path));
+        if url.origin() != base.origin() {
+            return Err(outside());
+        }
+        let resolved = url.path().strip_prefix(base.path().trim_end_matches('/')).ok_or_else(outside)?;
+        Ok(if resolved.is_empty() { "/".to_string() } else { resolved.to_string() })
+    }
+
+    /// A file at `git_ref` (default branch when None); directories are rejected
+    pub async fn get_file(&self, owner: &str, repo: &str, path: &str, git_ref: Option<&str>) -> Result<Option<GitHubContent>> {
+        let url = format!("{}/repos/{}/{}/contents/{}", self.base_url, owner, repo, path.trim_start_matches('/'));
+        debug!("Fetching file: {}", url);
+
+        let mut request = self.client.get(&url);
+        if let Some(git_ref) = git_ref {
+            request = request.query(&[("ref", git_ref)]);
+        }
+        let response = self.send(request).await?;
+
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND => return Ok(None),
+            status if !status.is_success() => {
+                return Err(GitHubError::from_response(response, "Failed to get file").await.into());
+            }
+            _ => {}
+        }
+
+        let body = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        if body.is_array() {
+            return Err(AppError::Validation(format!("{} is a directory", path)));
+        }
+        Ok(Some(serde_json::from_value(body)?))
+    }
+
+    /// Create or update a file in one commit; `sha` must be the current blob
+    /// SHA when updating. Returns the commit.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn put_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        message: &str,
+        content: &[u8],
+        sha: Option<&str>,
+        branch: Option<&str>,
+    ) -> Result<Value> {
+        let url = format!("{}/repos/{}/{}/contents/{}", self.base_url, owner, repo, path.trim_start_matches('/'));
+        debug!("Writing file: {}", url);
+
+        let mut payload = serde_json::json!({ "message": message, "content": BASE64.encode(content) });
+        if let Some(sha) = sha {
+            payload["sha"] = Value::String(sha.to_string());
+        }
+        if let Some(branch) = branch {
+            payload["branch"] = Value::String(branch.to_string());
+        }
+
+        let request = self.client
+            .put(&url)
+            .json(&payload);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to write file").await.into());
+        }
+
+        let mut body = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        Ok(body["commit"].take())
+    }
+
+    /// Delete a file in one commit; returns the commit
+    pub async fn delete_file(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+        message: &str,
+        sha: &str,
+        branch: Option<&str>,
+    ) -> Result<Value> {
+        let url = format!("{}/repos/{}/{}/contents/{}", self.base_url, owner, repo, path.trim_start_matches('/'));
+        debug!("Deleting file: {}", url);
+
+        let mut payload = serde_json::json!({ "message": message, "sha": sha });
+        if let Some(branch) = branch {
+            payload["branch"] = Value::String(branch.to_string());
+        }
+
+        let request = self.client
+            .delete(&url)
+            .json(&payload);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to delete file").await.into());
+        }
+
+        let mut body = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        Ok(body["commit"].take())
+    }
+
+    /// Create a repository in `org`, or for the authenticated user when None
+    pub async fn create_repository(&self, org: Option<&str>, settings: &Value) -> Result<GitHubRepository> {
+        let url = match org {
+            Some(org) => format!("{}/orgs/{}/repos", self.base_url, org),
+            None => format!("{}/user/repos", self.base_url),
+        };
+        debug!("Creating repository: {}", url);
+
+        let request = self.client
+            .post(&url)
+            .json(settings);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to create repository").await.into());
+        }
+
+        let repository = response.json::<GitHubRepository>().await.map_err(AppError::HttpClient)?;
+        Ok(repository)
+    }
+
+    /// Fork a repository into `organization`, or the authenticated user's
+    /// account. GitHub creates forks asynchronously, so the fork's contents
+    /// may take a moment to appear.
+    pub async fn fork_repository(
+        &self,
+        owner: &str,
+        repo: &str,
+        organization: Option<&str>,
+        name: Option<&str>,
+        default_branch_only: bool,
+    ) -> Result<GitHubRepository> {
+        // `send` leaves the forks endpoint alone; the fork is what gets written
+        if super::allowlist::enabled() {
+            let fork_owner = match organization {
+                Some(organization) => organization.to_string(),
+                None => self.get_user().await?.login,
+            };
+            super::allowlist::check(&format!("{}/{}", fork_owner, name.unwrap_or(repo)))?;
+        }
+        let url = format!("{}/repos/{}/{}/forks", self.base_url, owner, repo);
+        debug!("Forking repository: {}", url);
+
+        let mut body = serde_json::json!({ "default_branch_only": default_branch_only });
+        if let Some(organization) = organization {
+            body["organization"] = Value::String(organization.to_string());
+        }
+        if let Some(name) = name {
+            body["name"] = Value::String(name.to_string());
+        }
+        let response = self.send(self.client.post(&url).json(&body)).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to fork repository").await.into());
+        }
+
+        let repository = response.json::<GitHubRepository>().await.map_err(AppError::HttpClient)?;
+        Ok(repository)
+    }
+
+    /// Bring a fork's `branch` up to date with its upstream. Returns None when
+    /// the branches have diverged and GitHub can't merge them without conflicts.
+    pub async fn merge_upstream(&self, owner: &str, repo: &str, branch: &str) -> Result<Option<Value>> {
+        let url = format!("{}/repos/{}/{}/merge-upstream", self.base_url, owner, repo);
+        debug!("Merging upstream into {}: {}", branch, url);
+
+        let request = self.client
+            .post(&url)
+            .json(&serde_json::json!({ "branch": branch }));
+        let response = self.send(request).await?;
+
+        match response.status() {
+            reqwest::StatusCode::CONFLICT => Ok(None),
+            status if !status.is_success() => {
+                Err(GitHubError::from_response(response, "Failed to sync fork").await.into())
+            }
+            _ => Ok(Some(response.json::<Value>().await.map_err(AppError::HttpClient)?)),
+        }
+    }
+
+    /// Alerts under `/repos/{owner}/{repo}/{kind}/alerts`, where `kind` is
+    /// dependabot, code-scanning or secret-scanning; every state when None
+    pub async fn list_security_alerts(
+        &self,
+        owner: &str,
+        repo: &str,
+        kind: &str,
+        state: Option<&str>,
+        severity: Option<&str>,
+        max_items: usize,
+    ) -> Result<Vec<Value>> {
+        let mut params: Vec<(&str, String)> = state.map(|state| ("state", state.to_string())).into_iter().collect();
+        if let Some(severity) = severity {
+            params.push(("severity", severity.to_string()));
+        }
+        self.paginate(&format!("/repos/{}/{}/{}/alerts", owner, repo, kind), &params, "alerts", Some(max_items)).await
+    }
+
+    /// PATCH one alert, e.g. to dismiss it
+    pub async fn update_security_alert(&self, owner: &str, repo: &str, kind: &str, number: u64, changes: &Value) -> Result<Value> {
+        let url = format!("{}/repos/{}/{}/{}/alerts/{}", self.base_url, owner, repo, kind, number);
+        debug!("Updating alert: {}", url);
+
+        let request = self.client
+            .patch(&url)
+            .json(changes);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to update alert").await.into());
+        }
+
+        let alert = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        Ok(alert)
+    }
+
+    /// Turn on Dependabot alerts for vulnerable dependencies
+    pub async fn enable_vulnerability_alerts(&self, owner: &str, repo: &str) -> Result<()> {
+        let url = format!("{}/repos/{}/{}/vulnerability-alerts", self.base_url, owner, repo);
+        debug!("Enabling vulnerability alerts: {}", url);
+
+        let response = self.send(self.client.put(&url)).await?;
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to enable vulnerability alerts").await.into());
+        }
+
+        Ok(())
+    }
+
+    /// Upload `files` and commit them as a root commit (no parents); returns
+    /// its SHA. Nothing points at the commit until a ref is set to it.
+    pub async fn create_root_commit(&self, owner: &str, repo: &str, files: &[TreeFile], message: &str) -> Result<String> {
+        let git_url = format!("{}/repos/{}/{}/git", self.base_url, owner, repo);
+        debug!("Creating root commit of {} files: {}", files.len(), git_url);
+
+        let post = |path: &'static str, body: Value| {
+            let url = format!("{}/{}", git_url, path);
+            async move {
+                let response = self.send(self.client.post(&url).json(&body)).await?;
+                if !response.status().is_success() {
+                    return Err(GitHubError::from_response(response, &format!("Failed to create git {}", path)).await.into());
+                }
+                let body = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+                body["sha"]
+                    .as_str()
+                    .map(String::from)
+                    .ok_or_else(|| AppError::GitHubApi(format!("Creating a git {} returned no SHA", path)))
+            }
+        };
+
+        let mut tree = Vec::with_capacity(files.len());
+        for file in files {
+            let sha = post("blobs", serde_json::json!({ "content": BASE64.encode(&file.content), "encoding": "base64" })).await?;
+            tree.push(serde_json::json!({
+                "path": file.path,
+                "mode": if file.executable { "100755" } else { "100644" },
+                "type": "blob",
+                "sha": sha
+            }));
+        }
+        let tree = post("trees", serde_json::json!({ "tree": tree })).await?;
+        post("commits", serde_json::json!({ "message": message, "tree": tree, "parents": [] })).await
+    }
+
+    /// Point `branch` at `sha`, creating it if needed and moving it even when
+    /// that isn't a fast-forward
+    pub async fn set_branch(&self, owner: &str, repo: &str, branch: &str, sha: &str) -> Result<()> {
+        let url = format!("{}/repos/{}/{}/git/refs/heads/{}", self.base_url, owner, repo, branch);
+        debug!("Setting branch {}: {}", branch, url);
+
+        let request = self.client
+            .patch(&url)
+            .json(&serde_json::json!({ "sha": sha, "force": true }));
+        let response = self.send(request).await?;
+        match response.status() {
+            status if status.is_success() => return Ok(()),
+            // 422 "Reference does not exist"
+            reqwest::StatusCode::UNPROCESSABLE_ENTITY | reqwest::StatusCode::NOT_FOUND => {}
+            _ => return Err(GitHubError::from_response(response, "Failed to update branch").await.into()),
+        }
+
+        let url = format!("{}/repos/{}/{}/git/refs", self.base_url, owner, repo);
+        let request = self.client
+            .post(&url)
+            .json(&serde_json::json!({ "ref": format!("refs/heads/{}", branch), "sha": sha }));
+        let response = self.send(request).await?;
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to create branch").await.into());
+        }
+
+        Ok(())
+    }
+
+    /// Delete a branch on the remote; false if it was already gone
+    pub async fn delete_branch(&self, owner: &str, repo: &str, branch: &str) -> Result<bool> {
+        let url = self.ref_url(owner, repo, "heads", branch)?;
+        debug!("Deleting branch: {}", url);
+
+        let request = self.client.delete(url);
+        let response = self.send(request).await?;
+
+        match response.status() {
+            // 422 "Reference does not exist"
+            reqwest::StatusCode::UNPROCESSABLE_ENTITY | reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status if !status.is_success() => {
+                Err(GitHubError::from_response(response, "Failed to delete branch").await.into())
+            }
+            _ => Ok(true),
+        }
+    }
+
+    /// Re-run a workflow run, either entirely or only its failed jobs
+    pub async fn rerun_workflow_run(&self, owner: &str, repo: &str, run_id: u64, failed_only: bool) -> Result<()> {
+        let action = if failed_only { "rerun-failed-jobs" } else { "rerun" };
+        let url = format!("{}/repos/{}/{}/actions/runs/{}/{}", self.base_url, owner, repo, run_id, action);
+        debug!("Re-running workflow run: {}", url);
+
+        let request = self.client.post(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to re-run workflow run").await.into());
+        }
+
+        Ok(())
+    }
+
+    pub async fn cancel_workflow_run(&self, owner: &str, repo: &str, run_id: u64) -> Result<()> {
+        let url = format!("{}/repos/{}/{}/actions/runs/{}/cancel", self.base_url, owner, repo, run_id);
+        debug!("Cancelling workflow run: {}", url);
+
+        let request = self.client.post(&url);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to cancel workflow run").await.into());
+        }
+
+        Ok(())
+    }
+
+    pub async fn list_reviews(&self, owner: &str, repo: &str, number: u64) -> Result<Vec<GitHubReview>> {
+        self.paginate(&format!("/repos/{}/{}/pulls/{}/reviews", owner, repo, number), &[], "reviews", None).await
+    }
+
+    /// Submit a review with `event` (APPROVE, REQUEST_CHANGES or COMMENT) and
+    /// any inline comments in one go
+    pub async fn create_review(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        event: &str,
+        body: Option<&str>,
+        comments: &[ReviewCommentInput],
+    ) -> Result<GitHubReview> {
+        let url = format!("{}/repos/{}/{}/pulls/{}/reviews", self.base_url, owner, repo, number);
+        debug!("Submitting review: {}", url);
+
+        let mut payload = serde_json::json!({ "event": event });
+        if let Some(body) = body {
+            payload["body"] = Value::String(body.to_string());
+        }
+        if !comments.is_empty() {
+            payload["comments"] = serde_json::to_value(comments)?;
+        }
+
+        let request = self.client
+            .post(&url)
+            .json(&payload);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to submit review").await.into());
+        }
+
+        let review = response.json::<GitHubReview>().await.map_err(AppError::HttpClient)?;
+        Ok(review)
+    }
+
+    /// Add a single comment anchored to a line of the diff at `commit_id`
+    pub async fn create_review_comment(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        commit_id: &str,
+        comment: &ReviewCommentInput,
+    ) -> Result<Value> {
+        let url = format!("{}/repos/{}/{}/pulls/{}/comments", self.base_url, owner, repo, number);
+        debug!("Creating review comment: {}", url);
+
+        let mut payload = serde_json::to_value(comment)?;
+        payload["commit_id"] = Value::String(commit_id.to_string());
+
+        let request = self.client
+            .post(&url)
+            .json(&payload);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to create review comment").await.into());
+        }
+
+        let comment = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        Ok(comment)
+    }
+
+    pub async fn create_pull_request(
+        &self,
+        owner: &str,
+        repo: &str,
+        title: &str,
+        head: &str,
+        base: &str,
+        body: Option<&str>,
+        draft: bool,
+    ) -> Result<GitHubPullRequest> {
+        let url = format!("{}/repos/{}/{}/pulls", self.base_url, owner, repo);
+        debug!("Creating pull request: {}", url);
+
+        let mut payload = serde_json::json!({
+            "title": title,
+            "head": head,
+            "base": base,
+            "draft": draft
+        });
+
+        if let Some(body) = body {
+            payload["body"] = serde_json::Value::String(body.to_string());
+        }
+
+        let request = self.client
+            .post(&url)
+            .json(&payload);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to create pull request").await.into());
+        }
+
+        let pr = response.json::<GitHubPullRequest>().await.map_err(AppError::HttpClient)?;
+        Ok(pr)
+    }
+
+    pub async fn request_reviewers(
+        &self,
+        owner: &str,
+        repo: &str,
+        number: u64,
+        reviewers: &[String],
+        team_reviewers: &[String],
+    ) -> Result<GitHubPullRequest> {
+        let url = format!("{}/repos/{}/{}/pulls/{}/requested_reviewers", self.base_url, owner, repo, number);
+        debug!("Requesting reviewers: {}", url);
+
+        let request = self.client
+            .post(&url)
+            .json(&serde_json::json!({ "reviewers": reviewers, "team_reviewers": team_reviewers }));
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to request reviewers").await.into());
+        }
+
+        let pr = response.json::<GitHubPullRequest>().await.map_err(AppError::HttpClient)?;
+        Ok(pr)
+    }
+
+    pub async fn graphql(&self, query: &str, variables: Value) -> Result<Value> {
+        if super::graphql::is_mutation(query) {
+            super::allowlist::check_mutation()?;
+        }
+        let url = self.host.graphql_url();
+        let payload = serde_json::json!({ "query": query, "variables": variables });
+
+        let request = self.client
+            .post(&url)
+            .json(&payload);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "GraphQL request failed").await.into());
+        }
+
+        let mut body: Value = response.json().await.map_err(AppError::HttpClient)?;
+
+        // GraphQL reports most failures with a 200 and an `errors` array
+        if let Some(errors) = body.get("errors").and_then(|e| e.as_array()) {
+            if !errors.is_empty() {
+                let error = GitHubError::from_graphql(errors);
+                error!("GitHub GraphQL errors: {}", error);
+                return Err(error.into());
+            }
+        }
+
+        Ok(body.get_mut("data").map(Value::take).unwrap_or(Value::Null))
+    }
+
+    /// Merge the PR automatically once its requirements are met; `merge_method`
+    /// is merge, squash or rebase
+    pub async fn enable_auto_merge(&self, pull_request_id: &str, merge_method: &str) -> Result<()> {
+        self.graphql(
+            "mutation($pr: ID!, $method: PullRequestMergeMethod!) { enablePullRequestAutoMerge(input: { pullRequestId: $pr, mergeMethod: $method }) { pullRequest { number } } }",
+            serde_json::json!({ "pr": pull_request_id, "method": merge_method.to_uppercase() }),
+        ).await?;
+        Ok(())
+    }
+
+    /// Add the PR to its base branch's merge queue; returns its position
+    pub async fn enqueue_pull_request(&self, pull_request_id: &str) -> Result<Option<u64>> {
+        let data = self.graphql(
+            "mutation($pr: ID!) { enqueuePullRequest(input: { pullRequestId: $pr }) { mergeQueueEntry { position } } }",
+            serde_json::json!({ "pr": pull_request_id }),
+        ).await?;
+        Ok(data.pointer("/enqueuePullRequest/mergeQueueEntry/position").and_then(|p| p.as_u64()))
+    }
+
+    pub async fn get_project_owner_kind(&self, owner: &str) -> Result<ProjectOwnerKind> {
+        let data = self.graphql(
+            "query($login: String!) { repositoryOwner(login: $login) { __typename } }",
+            serde_json::json!({ "login": owner }),
+        ).await?;
+
+        match data.pointer("/repositoryOwner/__typename").and_then(|t| t.as_str()) {
+            Some("Organization") => Ok(ProjectOwnerKind::Organization),
+            Some("User") => Ok(ProjectOwnerKind::User),
+            _ => Err(GitHubError::not_found(format!("Project owner not found: {}", owner)).into()),
+        }
+    }
+
+    pub async fn get_project(&self, owner: &str, project_number: &str) -> Result<GitHubProject> {
+        let number: u64 = project_number
+            .parse()
+            .map_err(|_| AppError::Validation(format!("Invalid project number: {}", project_number)))?;
+
+        let root = self.get_project_owner_kind(owner).await?.graphql_root();
+        let data = self.graphql(
+            &PROJECT_FIELDS_QUERY.replace("OWNER_ROOT", root),
+            serde_json::json!({ "owner": owner, "number": number }),
+        ).await?;
+
+        let project = data
+            .pointer(&format!("/{}/projectV2", root))
+            .filter(|p| !p.is_null())
+            .ok_or_else(|| GitHubError::not_found(format!("Project {}/{} not found", owner, number)))?;
+
+        let fields = project
+            .pointer("/fields/nodes")
+            .and_then(|n| n.as_array())
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .filter_map(|n| {
+                        // Iterations are nested under the field's configuration
+                        let mut n = n.clone();
+                        if let Some(iterations) = n.pointer("/configuration/iterations").cloned() {
+                            n["iterations"] = iterations;
+                        }
+                        serde_json::from_value::<GitHubProjectFieldDefinition>(n).ok()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(GitHubProject {
+            id: project["id"].as_str().unwrap_or_default().to_string(),
+            title: project["title"].as_str().unwrap_or_default().to_string(),
+            fields,
+        })
+    }
+
+    /// Set a single-select field (e.g. Status) on a project item by option name
+    pub async fn set_project_item_option(
+        &self,
+        project: &GitHubProject,
+        item_id: &str,
+        field_name: &str,
+        option_name: &str,
+    ) -> Result<()> {
+        let field = project
+            .field(field_name)
+            .ok_or_else(|| AppError::Validation(format!("Project has no {} field", field_name)))?;
+        let option = field
+            .options
+            .iter()
+            .find(|o| o.name.eq_ignore_ascii_case(option_name))
+            .ok_or_else(|| AppError::Validation(format!("{} field has no option \"{}\"", field_name, option_name)))?;
+
+        self.graphql(
+            UPDATE_FIELD_VALUE_MUTATION,
+            serde_json::json!({
+                "project": project.id,
+                "item": item_id,
+                "field": field.id,
+                "value": { "singleSelectOptionId": option.id }
+            }),
+        ).await?;
+
+        Ok(())
+    }
+
+    /// Set any field a project item can hold by name: an option or iteration
+    /// title, a `YYYY-MM-DD` date, a number or text. `null` clears the field.
+    /// Returns the value as set, e.g. the option's own spelling.
+    pub async fn set_project_item_field(
+        &self,
+        project: &GitHubProject,
+        item_id: &str,
+        field_name: &str,
+        value: &Value,
+    ) -> Result<Value> {
+        let field = project
+            .field(field_name)
+            .ok_or_else(|| AppError::Validation(format!("Project has no {} field", field_name)))?;
+
+        if value.is_null() {
+            self.graphql(
+                CLEAR_FIELD_VALUE_MUTATION,
+                serde_json::json!({ "project": project.id, "item": item_id, "field": field.id }),
+            ).await?;
+            return Ok(Value::Null);
+        }
+
+        let text = || {
+            value.as_str().ok_or_else(|| AppError::Validation(format!("{} takes a string", field.name)))
+        };
+        let (input, set) = match field.data_type.as_str() {
+            "SINGLE_SELECT" => {
+                let name = text()?;
+                let option = field
+                    .options
+                    .iter()
+                    .find(|o| o.name.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| AppError::Validation(format!("{} field has no option \"{}\"", field.name, name)))?;
+                (serde_json::json!({ "singleSelectOptionId": option.id }), Value::from(option.name.clone()))
+            }
+            "ITERATION" => {
+                let name = text()?;
+                let iteration = field
+                    .iteration(name, chrono::Utc::now().date_naive())
+                    .ok_or_else(|| AppError::Validation(format!("{} field has no iteration \"{}\"", field.name, name)))?;
+                (serde_json::json!({ "iterationId": iteration.id }), Value::from(iteration.title.clone()))
+            }
+            "DATE" => {
+                let date = text()?;
+                chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .map_err(|_| AppError::Validation(format!("{} takes a YYYY-MM-DD date, not \"{}\"", field.name, date)))?;
+                (serde_json::json!({ "date": date }), Value::from(date))
+            }
+            "NUMBER" => {
+                let number = value
+                    .as_f64()
+                    .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+                    .ok_or_else(|| AppError::Validation(format!("{} takes a number", field.name)))?;
+                (serde_json::json!({ "number": number }), Value::from(number))
+            }
+            "TEXT" => {
+                let text = text()?;
+                (serde_json::json!({ "text": text }), Value::from(text))
+            }
+            other => {
+                return Err(AppError::Validation(format!(
+                    "{} is a {} field, which can't be set on a project item",
+                    field.name,
+                    other.to_lowercase().replace('_', " ")
+                )));
+            }
+        };
+
+        self.graphql(
+            UPDATE_FIELD_VALUE_MUTATION,
+            serde_json::json!({ "project": project.id, "item": item_id, "field": field.id, "value": input }),
+        ).await?;
+
+        Ok(set)
+    }
+
+    /// Archive a project item, or restore an archived one
+    pub async fn archive_project_item(&self, project_id: &str, item_id: &str, archived: bool) -> Result<()> {
+        let mutation = if archived { "archiveProjectV2Item" } else { "unarchiveProjectV2Item" };
+        self.graphql(
+            &format!(
+                "mutation($project: ID!, $item: ID!) {{ {}(input: {{ projectId: $project, itemId: $item }}) {{ item {{ id }} }} }}",
+                mutation
+            ),
+            serde_json::json!({ "project": project_id, "item": item_id }),
+        ).await?;
+
+        Ok(())
+    }
+
+    /// Create a project owned by `owner` and linked to a repository (by node
+    /// ID); returns its number and URL
+    pub async fn create_project(&self, owner: &str, title: &str, repository_id: &str) -> Result<(u64, String)> {
+        let data = self.graphql(
+            "query($login: String!) { repositoryOwner(login: $login) { id } }",
+            serde_json::json!({ "login": owner }),
+        ).await?;
+        let owner_id = data
+            .pointer("/repositoryOwner/id")
+            .and_then(|id| id.as_str())
+            .ok_or_else(|| GitHubError::not_found(format!("Project owner not found: {}", owner)))?;
+
+        let data = self.graphql(
+            "mutation($owner: ID!, $title: String!, $repository: ID!) { createProjectV2(input: { ownerId: $owner, title: $title, repositoryId: $repository }) { projectV2 { number url } } }",
+            serde_json::json!({ "owner": owner_id, "title": title, "repository": repository_id }),
+        ).await?;
+
+        let project = data
+            .pointer("/createProjectV2/projectV2")
+            .ok_or_else(|| AppError::GitHubApi("Project creation returned no project".to_string()))?;
+        Ok((
+            project["number"].as_u64().unwrap_or_default(),
+            project["url"].as_str().unwrap_or_default().to_string(),
+        ))
+    }
+
+    /// Put an issue or pull request (by node ID) on a project; returns the item ID.
+    /// Adding one that's already there returns its existing item.
+    pub async fn add_project_item(&self, project_id: &str, content_id: &str) -> Result<String> {
+        let data = self.graphql(
+            "mutation($project: ID!, $content: ID!) { addProjectV2ItemById(input: { projectId: $project, contentId: $content }) { item { id } } }",
+            serde_json::json!({ "project": project_id, "content": content_id }),
+        ).await?;
+
+        data.pointer("/addProjectV2ItemById/item/id")
+            .and_then(|id| id.as_str())
+            .map(String::from)
+            .ok_or_else(|| AppError::GitHubApi("Adding to the project returned no item".to_string()))
+    }
+
+    /// The item an issue or pull request (by node ID) already has on a
+    /// project, found from the content's side so the project's size doesn't matter
+    pub async fn find_project_item(&self, project_id: &str, content_id: &str) -> Result<Option<String>> {
+        let data = self.graphql(
+            "query($content: ID!) { node(id: $content) { ... on Issue { projectItems(first: 100, includeArchived: true) { nodes { id project { id } } } } ... on PullRequest { projectItems(first: 100, includeArchived: true) { nodes { id project { id } } } } } }",
+            serde_json::json!({ "content": content_id }),
+        ).await?;
+
+        Ok(data
+            .pointer("/node/projectItems/nodes")
+            .and_then(|nodes| nodes.as_array())
+            .into_iter()
+            .flatten()
+            .find(|item| item.pointer("/project/id").and_then(|id| id.as_str()) == Some(project_id))
+            .and_then(|item| item["id"].as_str())
+            .map(String::from))
+    }
+
+    /// The node ID of the project a project item is on, and the `owner/name`
+    /// of the repository holding its issue or pull request; None for drafts
+    pub async fn get_project_item_location(&self, item_id: &str) -> Result<(String, Option<String>)> {
+        let data = self.graphql(
+            "query($item: ID!) { node(id: $item) { ... on ProjectV2Item { project { id } content { ... on Issue { repository { nameWithOwner } } ... on PullRequest { repository { nameWithOwner } } } } } }",
+            serde_json::json!({ "item": item_id }),
+        ).await?;
+
+        let project_id = data
+            .pointer("/node/project/id")
+            .and_then(|id| id.as_str())
+            .ok_or_else(|| AppError::Validation(format!("No project item {}", item_id)))?;
+        let repository = data
+            .pointer("/node/content/repository/nameWithOwner")
+            .and_then(|name| name.as_str())
+            .map(String::from);
+        Ok((project_id.to_string(), repository))
+    }
+
+    /// A repository's discussion categories and its node ID, which creating a
+    /// discussion needs
+    pub async fn list_discussion_categories(&self, owner: &str, repo: &str) -> Result<(String, Vec<GitHubDiscussionCategory>)> {
+        let data = self.graphql(
+            "query($owner: String!, $name: String!) { repository(owner: $owner, name: $name) { id hasDiscussionsEnabled discussionCategories(first: 50) { nodes { id name slug isAnswerable } } } }",
+            serde_json::json!({ "owner": owner, "name": repo }),
+        ).await?;
+        let repository = data
+            .get("repository")
+            .filter(|r| !r.is_null())
+            .ok_or_else(|| GitHubError::not_found(format!("Repository not found: {}/{}", owner, repo)))?;
+        if repository["hasDiscussionsEnabled"] == Value::Bool(false) {
+            return Err(AppError::Validation(format!("Discussions aren't enabled on {}/{}", owner, repo)));
+        }
+
+        let categories = serde_json::from_value(repository["discussionCategories"]["nodes"].clone())?;
+        Ok((repository["id"].as_str().unwrap_or_default().to_string(), categories))
+    }
+
+    /// Discussions, most recently updated first. `states` is OPEN and/or CLOSED.
+    pub async fn list_discussions(
+        &self,
+        owner: &str,
+        repo: &str,
+        category_id: Option<&str>,
+        states: Option<&[&str]>,
+        answered: Option<bool>,
+        max_items: usize,
+    ) -> Result<Vec<GitHubDiscussion>> {
+        let discussions: Vec<GraphQlDiscussion> = self.paginate_graphql(
+            &DISCUSSIONS_QUERY.replace("FIELDS", DISCUSSION_FIELDS),
+            serde_json::json!({
+                "owner": owner,
+                "name": repo,
+                "category": category_id,
+                "states": states,
+                "answered": answered,
+                "cursor": null
+            }),
+            "/repository/discussions",
+            Some(max_items),
+        ).await?;
+        Ok(discussions.into_iter().map(GitHubDiscussion::from).collect())
+    }
+
+    /// A discussion with its comments and their replies
+    pub async fn get_discussion(&self, owner: &str, repo: &str, number: u64) -> Result<(GitHubDiscussion, Vec<GitHubDiscussionComment>)> {
+        let mut data = self.graphql(
+            &DISCUSSION_QUERY.replace("FIELDS", DISCUSSION_FIELDS),
+            serde_json::json!({ "owner": owner, "name": repo, "number": number }),
+        ).await?;
+        let mut raw = data
+            .pointer_mut("/repository/discussion")
+            .filter(|d| !d.is_null())
+            .map(Value::take)
+            .ok_or_else(|| GitHubError::not_found(format!("Discussion #{} not found in {}/{}", number, owner, repo)))?;
+
+        let comments: Vec<Option<GraphQlDiscussionComment>> = serde_json::from_value(raw["thread"]["nodes"].take())?;
+        let discussion: GraphQlDiscussion = serde_json::from_value(raw)?;
+        Ok((
+            discussion.into(),
+            comments.into_iter().flatten().map(GitHubDiscussionComment::from).collect(),
+        ))
+    }
+
+    /// Start a discussion; returns its number and URL
+    pub async fn create_discussion(
+        &self,
+        repository_id: &str,
+        category_id: &str,
+        title: &str,
+        body: &str,
+    ) -> Result<(u64, String)> {
+        let data = self.graphql(
+            "mutation($repository: ID!, $category: ID!, $title: String!, $body: String!) { createDiscussion(input: { repositoryId: $repository, categoryId: $category, title: $title, body: $body }) { discussion { number url } } }",
+            serde_json::json!({ "repository": repository_id, "category": category_id, "title": title, "body": body }),
+        ).await?;
+
+        let discussion = data
+            .pointer("/createDiscussion/discussion")
+            .ok_or_else(|| AppError::GitHubApi("Creating the discussion returned no discussion".to_string()))?;
+        Ok((
+            discussion["number"].as_u64().unwrap_or_default(),
+            discussion["url"].as_str().unwrap_or_default().to_string(),
+        ))
+    }
+
+    /// Comment on a discussion, or reply to one of its top-level comments;
+    /// returns the new comment's ID and URL
+    pub async fn add_discussion_comment(&self, discussion_id: &str, body: &str, reply_to: Option<&str>) -> Result<(String, String)> {
+        let data = self.graphql(
+            "mutation($discussion: ID!, $body: String!, $replyTo: ID) { addDiscussionComment(input: { discussionId: $discussion, body: $body, replyToId: $replyTo }) { comment { id url } } }",
+            serde_json::json!({ "discussion": discussion_id, "body": body, "replyTo": reply_to }),
+        ).await?;
+
+        let comment = data
+            .pointer("/addDiscussionComment/comment")
+            .ok_or_else(|| AppError::GitHubApi("Commenting returned no comment".to_string()))?;
+        Ok((
+            comment["id"].as_str().unwrap_or_default().to_string(),
+            comment["url"].as_str().unwrap_or_default().to_string(),
+        ))
+    }
+
+    /// The repository (owner/name) a discussion comment belongs to
+    pub async fn discussion_comment_repository(&self, comment_id: &str) -> Result<String> {
+        let data = self.graphql(
+            "query($id: ID!) { node(id: $id) { ... on DiscussionComment { discussion { repository { nameWithOwner } } } } }",
+            serde_json::json!({ "id": comment_id }),
+        ).await?;
+
+        data.pointer("/node/discussion/repository/nameWithOwner")
+            .and_then(|name| name.as_str())
+            .map(String::from)
+            .ok_or_else(|| GitHubError::not_found(format!("Discussion comment not found: {}", comment_id)).into())
+    }
+
+    /// Mark a comment as its discussion's answer, or take the mark back
+    pub async fn mark_discussion_answer(&self, comment_id: &str, answer: bool) -> Result<String> {
+        let mutation = if answer { "markDiscussionCommentAsAnswer" } else { "unmarkDiscussionCommentAsAnswer" };
+        let data = self.graphql(
+            &format!("mutation($id: ID!) {{ {}(input: {{ id: $id }}) {{ discussion {{ url }} }} }}", mutation),
+            serde_json::json!({ "id": comment_id }),
+        ).await?;
+
+        Ok(data
+            .pointer(&format!("/{}/discussion/url", mutation))
+            .and_then(|url| url.as_str())
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    /// PATCH repository settings such as description and homepage
+    pub async fn update_repository(&self, owner: &str, repo: &str, changes: &Value) -> Result<GitHubRepository> {
+        let url = format!("{}/repos/{}/{}", self.base_url, owner, repo);
+        debug!("Updating repository: {}", url);
+
+        let request = self.client
+            .patch(&url)
+            .json(changes);
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to update repository").await.into());
+        }
+
+        let repository = response.json::<GitHubRepository>().await.map_err(AppError::HttpClient)?;
+        Ok(repository)
+    }
+
+    /// Replace the repository's topics with `names`
+    pub async fn replace_topics(&self, owner: &str, repo: &str, names: &[String]) -> Result<Vec<String>> {
+        let url = format!("{}/repos/{}/{}/topics", self.base_url, owner, repo);
+        debug!("Replacing topics: {}", url);
+
+        let request = self.client
+            .put(&url)
+            .json(&serde_json::json!({ "names": names }));
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(GitHubError::from_response(response, "Failed to update topics").await.into());
+        }
+
+        let body = response.json::<Value>().await.map_err(AppError::HttpClient)?;
+        Ok(serde_json::from_value(body["names"].clone()).unwrap_or_default())
+    }
+
+    pub async fn list_org_repositories(&self, org: &str) -> Result<Vec<GitHubRepository>> {
+        self.paginate(&format!("/orgs/{}/repos", org), &[], "repositories", None).await
+    }
+
+    /// GraphQL node ID of a repository, needed by mutations that target it
+    pub async fn get_repository_node_id(&self, owner: &str, repo: &str) -> Result<String> {
+        let data = self.graphql(
+            "query($owner: String!, $name: String!) { repository(owner: $owner, name: $name) { id } }",
+            serde_json::json!({ "owner": owner, "name": repo }),
+        ).await?;
+
+        data.pointer("/repository/id")
+            .and_then(|id| id.as_str())
+            .map(String::from)
+            .ok_or_else(|| GitHubError::not_found(format!("Repository not found: {}/{}", owner, repo)).into())
+    }
+
+    /// Convert a draft project item into an issue in `repository_id`. The
+    /// project item keeps its ID and field values; returns the issue number.
+    pub async fn convert_draft_issue(&self, item_id: &str, repository_id: &str) -> Result<u64> {
+        let data = self.graphql(
+            CONVERT_DRAFT_ISSUE_MUTATION,
+            serde_json::json!({ "item": item_id, "repository": repository_id }),
+        ).await?;
+
+        data.pointer("/convertProjectV2DraftIssueItemToIssue/item/content/number")
+            .and_then(|n| n.as_u64())
+            .ok_or_else(|| AppError::GitHubApi("Draft conversion returned no issue".to_string()))
+    }
+
+    pub async fn get_project_items(&self, owner: &str, project_number: &str) -> Result<Vec<GitHubProjectItem>> {
+        let number: u64 = project_number
+            .parse()
+            .map_err(|_| AppError::Validation(format!("Invalid project number: {}", project_number)))?;
+
+        let root = self.get_project_owner_kind(owner).await?.graphql_root();
+        let query = PROJECT_ITEMS_QUERY.replace("OWNER_ROOT", root);
+        let items_pointer = format!("/{}/projectV2/items", root);
+
+        let items: Vec<GraphQlProjectItem> = self
+            .paginate_graphql(
+                &query,
+                serde_json::json!({ "owner": owner, "number": number, "cursor": null }),
+                &items_pointer,
+                None,
+            )
+            .await?;
+        Ok(items.into_iter().map(GitHubProjectItem::from).collect())
+    }
+}
+
+pub async fn get_github_client(state: AppState, user_id: Option<u64>) -> Result<GitHubClient> {
+    // Get GitHub token from database for the user
+    let token = if let Some(user_id) = user_id {
+        get_user_github_token(&state.db, user_id).await?
+    } else {
+        // For now, use a default token or return an error
+        return Err(AppError::Authentication("No GitHub token available".to_string()));
+    };
+
+    Ok(GitHubClient::new(token, &state.config.github.host)?
+        .with_pagination(Pagination::from_config(&state.config.github)))
+}
+
+/// Client acting as a specific GitHub user, for background jobs with no request context
+pub async fn get_github_client_for_login(state: &AppState, login: &str) -> Result<GitHubClient> {
+    let row = sqlx::query!(
+        "SELECT encrypted_token FROM github_tokens WHERE username = ? AND expires_at > datetime('now')",
+        login
+    )
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or_else(|| AppError::Authentication(format!("No valid GitHub token found for {}", login)))?;
 
-    GitHubClient::new(token, Some(state.config.github.api_base_url.clone()))
+    let token = decrypt_token(&row.encrypted_token)?;
+    Ok(GitHubClient::new(token, &state.config.github.host)?
+        .with_pagination(Pagination::from_config(&state.config.github)))
 }
 
 async fn get_user_github_token(db: &sqlx::SqlitePool, user_id: u64) -> Result<String> {
@@ -405,7 +3710,6 @@ async fn get_user_github_token(db: &sqlx::SqlitePool, user_id: u64) -> Result<St
 
     match row {
         Some(row) => {
-            // TODO: Decrypt the token
             let token = decrypt_token(&row.encrypted_token)?;
             Ok(token)
         }
@@ -413,8 +3717,6 @@ async fn get_user_github_token(db: &sqlx::SqlitePool, user_id: u64) -> Result<St
     }
 }
 
-fn decrypt_token(encrypted_token: &str) -> Result<String> {
-    // TODO: Implement proper token decryption
-    // For now, assume the token is stored in plain text (NOT SECURE - implement proper encryption)
-    Ok(encrypted_token.to_string())
+pub(crate) fn decrypt_token(encrypted_token: &str) -> Result<String> {
+    crate::security::tokens::decrypt(encrypted_token)
 }
\ No newline at end of file
@@ -0,0 +1,142 @@
+// `github://projects/{number}/board`: a project laid out as its board.
+//
+// Items are grouped into one column per Status option, in the project's
+// order, with a "No Status" column for the rest. Each card carries what a
+// board shows: assignees, priority, iteration and the PRs linked to an
+// issue. Boards are kept for `TTL` so an agent or the web UI re-reading one
+// doesn't cost a fresh page of GraphQL each time; writes through
+// `github_project_update_item` drop the kept copy. A board is only served
+// back to the token it was fetched with, as projects can be private.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use super::api::{GitHubClient, GitHubProject, GitHubProjectItem};
+
+/// How long a board is served from memory
+pub const TTL: Duration = Duration::from_secs(60);
+
+const NO_STATUS: &str = "No Status";
+
+static BOARDS: OnceLock<Mutex<HashMap<String, (Value, Instant)>>> = OnceLock::new();
+
+fn boards() -> &'static Mutex<HashMap<String, (Value, Instant)>> {
+    BOARDS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn project(owner: &str, number: &str) -> String {
+    format!("board:{}/{}", owner.to_lowercase(), number)
+}
+
+fn key(client: &GitHubClient, owner: &str, number: &str) -> String {
+    client.cache_key(&project(owner, number))
+}
+
+/// `github://projects/{number}/board`, optionally with `?owner=login`.
+/// Returns the project number and owner.
+pub fn parse_uri(uri: &str) -> Option<(String, Option<String>)> {
+    let (path, query) = match uri.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (uri, None),
+    };
+    let number = path.strip_prefix("github://projects/")?.strip_suffix("/board")?;
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let owner = query
+        .into_iter()
+        .flat_map(|q| q.split('&'))
+        .find_map(|pair| pair.strip_prefix("owner="))
+        .filter(|owner| !owner.is_empty())
+        .map(String::from);
+    Some((number.to_string(), owner))
+}
+
+/// A board kept less than `TTL` ago
+pub fn cached(client: &GitHubClient, owner: &str, number: &str) -> Option<Value> {
+    let mut boards = boards().lock().unwrap();
+    let key = key(client, owner, number);
+    match boards.get(&key) {
+        Some((board, at)) if at.elapsed() < TTL => Some(board.clone()),
+        Some(_) => {
+            boards.remove(&key);
+            None
+        }
+        None => None,
+    }
+}
+
+pub fn store(client: &GitHubClient, owner: &str, number: &str, board: &Value) {
+    let mut boards = boards().lock().unwrap();
+    boards.retain(|_, (_, at)| at.elapsed() < TTL);
+    boards.insert(key(client, owner, number), (board.clone(), Instant::now()));
+}
+
+/// Forget every kept copy of a board after the project changed
+pub fn invalidate(owner: &str, number: &str) {
+    let suffix = format!(":{}", project(owner, number));
+    boards().lock().unwrap().retain(|key, _| !key.ends_with(&suffix));
+}
+
+/// The project's items as columns of cards
+pub fn shape(owner: &str, number: &str, project: &GitHubProject, items: &[GitHubProjectItem]) -> Value {
+    let mut names: Vec<String> = project
+        .field("Status")
+        .map(|field| field.options.iter().map(|o| o.name.clone()).collect())
+        .unwrap_or_default();
+    let mut cards: HashMap<String, Vec<Value>> = HashMap::new();
+
+    for item in items {
+        let status = item
+            .field_value("Status")
+            .and_then(|s| s.as_str())
+            .map(String::from)
+            .unwrap_or_else(|| NO_STATUS.to_string());
+        // A status renamed since the field was read still gets a column
+        if status != NO_STATUS && !names.contains(&status) {
+            names.push(status.clone());
+        }
+        cards.entry(status).or_default().push(card(item));
+    }
+    if cards.contains_key(NO_STATUS) {
+        names.push(NO_STATUS.to_string());
+    }
+
+    let columns: Vec<Value> = names
+        .iter()
+        .map(|name| {
+            let cards = cards.remove(name).unwrap_or_default();
+            json!({ "status": name, "count": cards.len(), "items": cards })
+        })
+        .collect();
+
+    json!({
+        "project": { "title": project.title, "owner": owner, "number": number },
+        "columns": columns,
+        "total_count": items.len(),
+        "generated_at": chrono::Utc::now().to_rfc3339(),
+        "ttl_seconds": TTL.as_secs()
+    })
+}
+
+fn card(item: &GitHubProjectItem) -> Value {
+    let content = item.content.as_ref();
+    json!({
+        "item_id": item.id,
+        "type": content.map(|c| c.content_type.as_str()),
+        "title": content.map(|c| c.title.as_str()),
+        "number": content.and_then(|c| c.number),
+        "repository": content.and_then(|c| c.repository.as_deref()),
+        "url": content.and_then(|c| c.url.as_deref()),
+        "state": content.and_then(|c| c.state.as_deref()),
+        "assignees": content.map(|c| c.assignees.as_slice()).unwrap_or_default(),
+        "labels": content.map(|c| c.labels.as_slice()).unwrap_or_default(),
+        "priority": item.field_value("Priority"),
+        "iteration": item.field_value("Iteration").and_then(|i| i.get("title")),
+        "linked_pull_requests": content.map(|c| c.linked_pull_requests.as_slice()).unwrap_or_default()
+    })
+}
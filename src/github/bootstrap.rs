@@ -0,0 +1,122 @@
+// What `github_repo_create` puts in a new repository.
+//
+// Templates are directories under `REPO_TEMPLATES_DIR`; every file in one
+// becomes part of the repository's initial commit, keeping its executable
+// bit. `.git` directories and symlinks are skipped. The standard labels are
+// applied the same way `github_label_sync` applies a label set.
+
+use std::path::{Component, Path, PathBuf};
+
+use super::api::TreeFile;
+use super::labels::LabelSpec;
+use crate::error::{AppError, Result};
+
+/// Most files a template may hold, and their combined size
+const MAX_TEMPLATE_FILES: usize = 1000;
+const MAX_TEMPLATE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Merge methods a new repository allows unless told otherwise
+pub const DEFAULT_MERGE_METHODS: &[&str] = &["squash"];
+
+const STANDARD_LABELS: &[(&str, &str, &str)] = &[
+    ("bug", "d73a4a", "Something isn't working"),
+    ("enhancement", "a2eeef", "New feature or request"),
+    ("documentation", "0075ca", "Improvements or additions to documentation"),
+    ("chore", "cfd3d7", "Maintenance with no user-facing change"),
+    ("good first issue", "7057ff", "Good for newcomers"),
+    ("help wanted", "008672", "Extra attention is needed"),
+    ("question", "d876e3", "Further information is requested"),
+    ("duplicate", "cfd3d7", "This issue or pull request already exists"),
+    ("wontfix", "ffffff", "This will not be worked on"),
+    ("priority: high", "b60205", "Needs attention soon"),
+    ("priority: low", "c2e0c6", "Can wait"),
+];
+
+pub fn standard_labels() -> Vec<LabelSpec> {
+    STANDARD_LABELS
+        .iter()
+        .map(|(name, color, description)| LabelSpec {
+            name: name.to_string(),
+            color: color.to_string(),
+            description: Some(description.to_string()),
+            aliases: Vec::new(),
+        })
+        .collect()
+}
+
+/// The template called `name` under `templates_dir`; names can't leave it
+pub fn template_dir(templates_dir: &str, name: &str) -> Result<PathBuf> {
+    let relative = Path::new(name);
+    if name.is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(AppError::Validation(format!("Invalid template name '{}'", name)));
+    }
+
+    let dir = Path::new(templates_dir).join(relative);
+    if !dir.is_dir() {
+        return Err(AppError::Validation(format!("Template '{}' not found in {}", name, templates_dir)));
+    }
+    Ok(dir)
+}
+
+/// Every file under `dir`, sorted by path
+pub fn read_template(dir: &Path) -> Result<Vec<TreeFile>> {
+    let mut files = Vec::new();
+    let mut total = 0;
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let entries = std::fs::read_dir(&current)
+            .map_err(|e| AppError::Internal(format!("Failed to read {}: {}", current.display(), e)))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| AppError::Internal(format!("Failed to read template: {}", e)))?;
+            let file_type = entry.file_type().map_err(|e| AppError::Internal(format!("Failed to read template: {}", e)))?;
+            let path = entry.path();
+
+            if file_type.is_dir() {
+                if entry.file_name() != ".git" {
+                    pending.push(path);
+                }
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+
+            let metadata = entry.metadata().map_err(|e| AppError::Internal(format!("Failed to read template: {}", e)))?;
+            total += metadata.len();
+            if files.len() >= MAX_TEMPLATE_FILES || total > MAX_TEMPLATE_BYTES {
+                return Err(AppError::Validation(format!(
+                    "Template {} is too large: at most {} files and {} MB",
+                    dir.display(),
+                    MAX_TEMPLATE_FILES,
+                    MAX_TEMPLATE_BYTES / (1024 * 1024)
+                )));
+            }
+
+            let relative = path
+                .strip_prefix(dir)
+                .map_err(|_| AppError::Internal(format!("{} is outside the template", path.display())))?;
+            let content = std::fs::read(&path)
+                .map_err(|e| AppError::Internal(format!("Failed to read {}: {}", path.display(), e)))?;
+            files.push(TreeFile {
+                path: relative.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"),
+                content,
+                executable: is_executable(&metadata),
+            });
+        }
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &std::fs::Metadata) -> bool {
+    false
+}
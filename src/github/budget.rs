@@ -0,0 +1,174 @@
+// Process-wide view of the GitHub API rate limits, fed from the
+// `X-RateLimit-*` headers on every response the client sees.
+//
+// Limits are tracked per token and per resource (`core`, `graphql`, ...), so
+// background jobs can check the headroom before spending it. Once a window
+// drops below `GITHUB_RATE_LIMIT_RESERVE` requests, the client paces what is
+// left over the time until it resets instead of running into the limit.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+pub const URI: &str = "github://rate-limit";
+
+/// Longest a request is held back for pacing; a window further from its
+/// reset than this is left for GitHub to refuse
+const MAX_PACING: Duration = Duration::from_secs(60);
+
+static RESERVE: OnceLock<u64> = OnceLock::new();
+
+/// Set the request count below which requests are paced; call once at startup
+pub fn init(reserve: u64) {
+    let _ = RESERVE.set(reserve);
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RateSnapshot {
+    /// Short fingerprint of the token, never the token itself
+    pub token: String,
+    pub resource: String,
+    pub limit: u64,
+    pub remaining: u64,
+    /// Unix timestamp at which the window resets
+    pub reset_at: i64,
+    pub observed_at: String,
+}
+
+impl RateSnapshot {
+    pub fn remaining_fraction(&self) -> f64 {
+        if self.limit == 0 {
+            return 1.0;
+        }
+        self.remaining as f64 / self.limit as f64
+    }
+
+    /// Whether the window has reset since this was observed
+    pub fn is_stale(&self) -> bool {
+        chrono::Utc::now().timestamp() >= self.reset_at
+    }
+}
+
+static SNAPSHOTS: OnceLock<Mutex<HashMap<(String, String), RateSnapshot>>> = OnceLock::new();
+
+fn snapshots() -> &'static Mutex<HashMap<(String, String), RateSnapshot>> {
+    SNAPSHOTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub fn token_fingerprint(token: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    token.hash(&mut hasher);
+    format!("{:08x}", hasher.finish() as u32)
+}
+
+/// Record the rate-limit headers from a response; responses without them are ignored
+pub fn observe(token: &str, headers: &HeaderMap) {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let (Some(limit), Some(remaining), Some(reset_at)) = (
+        header("x-ratelimit-limit").and_then(|v| v.parse().ok()),
+        header("x-ratelimit-remaining").and_then(|v| v.parse().ok()),
+        header("x-ratelimit-reset").and_then(|v| v.parse().ok()),
+    ) else {
+        return;
+    };
+    let resource = header("x-ratelimit-resource").unwrap_or("core").to_string();
+    let token = token_fingerprint(token);
+
+    let snapshot = RateSnapshot {
+        token: token.clone(),
+        resource: resource.clone(),
+        limit,
+        remaining,
+        reset_at,
+        observed_at: chrono::Utc::now().to_rfc3339(),
+    };
+
+    if let Ok(mut snapshots) = snapshots().lock() {
+        snapshots.insert((token, resource), snapshot);
+    }
+}
+
+/// Current snapshots, dropping windows that have already reset
+pub fn all() -> Vec<RateSnapshot> {
+    let Ok(mut snapshots) = snapshots().lock() else {
+        return Vec::new();
+    };
+    snapshots.retain(|_, s| !s.is_stale());
+    let mut all: Vec<RateSnapshot> = snapshots.values().cloned().collect();
+    all.sort_by(|a, b| a.remaining_fraction().total_cmp(&b.remaining_fraction()));
+    all
+}
+
+/// Live snapshot for one token and resource, if a response has reported it
+pub fn get(token: &str, resource: &str) -> Option<RateSnapshot> {
+    let snapshots = snapshots().lock().ok()?;
+    snapshots
+        .get(&(token_fingerprint(token), resource.to_string()))
+        .filter(|s| !s.is_stale())
+        .cloned()
+}
+
+/// The most constrained live window across all tokens and resources
+pub fn tightest() -> Option<RateSnapshot> {
+    all().into_iter().next()
+}
+
+/// The rate-limit resource an API path counts against
+pub fn resource_for(path: &str) -> &'static str {
+    let path = path.trim_start_matches('/');
+    if path.starts_with("graphql") {
+        "graphql"
+    } else if path.starts_with("search/code") {
+        "code_search"
+    } else if path.starts_with("search/") {
+        "search"
+    } else {
+        "core"
+    }
+}
+
+/// How long to hold back a request to `path` so the requests left in its
+/// window last until the window resets; None when there is headroom
+pub fn pacing(token: &str, path: &str) -> Option<Duration> {
+    let reserve = *RESERVE.get()?;
+    let snapshot = get(token, resource_for(path)).filter(|s| s.remaining < reserve)?;
+    let until_reset = Duration::from_secs((snapshot.reset_at - chrono::Utc::now().timestamp()).max(1) as u64);
+
+    if snapshot.remaining == 0 {
+        return (until_reset <= MAX_PACING).then_some(until_reset);
+    }
+    Some((until_reset / (snapshot.remaining as u32 + 1)).min(MAX_PACING))
+}
+
+/// The lowest remaining count per resource across tokens
+pub fn remaining_by_resource() -> HashMap<String, u64> {
+    let mut lowest: HashMap<String, u64> = HashMap::new();
+    for snapshot in all() {
+        let remaining = lowest.entry(snapshot.resource).or_insert(snapshot.remaining);
+        *remaining = (*remaining).min(snapshot.remaining);
+    }
+    lowest
+}
+
+/// `github://rate-limit`: every live window, most constrained first
+pub fn resource() -> Value {
+    let windows = all();
+    let mut resources: Vec<Value> = remaining_by_resource()
+        .into_iter()
+        .map(|(resource, remaining)| json!({ "resource": resource, "remaining": remaining }))
+        .collect();
+    resources.sort_by(|a, b| a["resource"].as_str().cmp(&b["resource"].as_str()));
+
+    json!({
+        "reserve": RESERVE.get(),
+        "resources": resources,
+        "windows": windows,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })
+}
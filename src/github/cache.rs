@@ -0,0 +1,268 @@
+// Conditional-request cache for GitHub REST GETs.
+//
+// A response carrying an `ETag` or `Last-Modified` is kept, per token and URL,
+// and the next GET of that URL sends `If-None-Match` / `If-Modified-Since`.
+// GitHub answers an unchanged resource with a bodyless 304 that doesn't count
+// against the rate limit, and the client serves the kept body instead.
+// Entries live in an in-memory LRU of `GITHUB_CACHE_ENTRIES`, and with
+// `GITHUB_CACHE_PERSIST` also in SQLite, so they survive restarts. Stored
+// entries not rewritten for a week are deleted.
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Request, Response, StatusCode};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tracing::{debug, info, warn, Instrument};
+
+use crate::{AppState, error::{AppError, Result}, scheduler::Priority};
+
+/// Bodies larger than this are passed through uncached
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Stored responses not rewritten for this long are deleted
+const STORED_RETENTION: &str = "-7 days";
+
+/// How often expired stored responses are deleted
+const PRUNE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Headers kept with a body and replayed on a hit; `link` carries pagination
+const KEPT_HEADERS: &[&str] = &["content-type", "link", "etag", "last-modified"];
+
+#[derive(Clone)]
+struct Entry {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    headers: BTreeMap<String, String>,
+    body: Vec<u8>,
+}
+
+struct Cache {
+    capacity: usize,
+    db: Option<sqlx::SqlitePool>,
+    /// Entries with the tick at which each was last used
+    entries: Mutex<(HashMap<String, (Entry, u64)>, u64)>,
+}
+
+static CACHE: OnceLock<Cache> = OnceLock::new();
+
+/// Keep up to `capacity` entries in memory, and in `db` when given; call once
+/// at startup. Without it, or with a capacity of 0, nothing is cached.
+pub fn init(capacity: usize, db: Option<sqlx::SqlitePool>) {
+    let _ = CACHE.set(Cache { capacity, db, entries: Mutex::new((HashMap::new(), 0)) });
+}
+
+fn cache() -> Option<&'static Cache> {
+    CACHE.get().filter(|cache| cache.capacity > 0)
+}
+
+/// Responses differ per user, so the key includes a digest of the token
+pub fn key(token: &str, url: &str) -> String {
+    let digest = hex::encode(Sha256::digest(token.as_bytes()));
+    format!("{}:{}", &digest[..16], url)
+}
+
+/// A cached response for `key`, by which `request` is made conditional
+pub struct Conditional {
+    key: String,
+    entry: Option<Entry>,
+}
+
+impl Conditional {
+    /// Look `request` up and add the validators of any cached entry
+    pub async fn prepare(key: String, request: &mut Request) -> Option<Self> {
+        let cache = cache()?;
+        let entry = match cache.get_memory(&key) {
+            Some(entry) => Some(entry),
+            None => cache.get_stored(&key).await,
+        };
+
+        if let Some(entry) = &entry {
+            let headers = request.headers_mut();
+            if let Some(etag) = entry.etag.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers.insert(IF_NONE_MATCH, etag);
+            }
+            if let Some(modified) = entry.last_modified.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers.insert(IF_MODIFIED_SINCE, modified);
+            }
+        }
+        Some(Self { key, entry })
+    }
+
+    /// The cached body on a 304; otherwise `response`, kept for next time
+    /// when it can be revalidated
+    pub async fn complete(self, response: Response) -> Result<Response> {
+        let Some(cache) = cache() else {
+            return Ok(response);
+        };
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(entry) = self.entry {
+                debug!("Serving {} from the ETag cache", self.key);
+                cache.touch(&self.key, &entry);
+                return rebuild(StatusCode::OK, &entry);
+            }
+            return Ok(response);
+        }
+
+        let header = |name: HeaderName| response.headers().get(name).and_then(|v| v.to_str().ok()).map(String::from);
+        let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+        if response.status() != StatusCode::OK || (etag.is_none() && last_modified.is_none()) {
+            return Ok(response);
+        }
+        if response.content_length().map_or(false, |length| length as usize > MAX_BODY_BYTES) {
+            return Ok(response);
+        }
+
+        let headers = kept_headers(response.headers());
+        let body = response.bytes().await.map_err(AppError::HttpClient)?.to_vec();
+        let entry = Entry { etag, last_modified, headers, body };
+        let served = rebuild(StatusCode::OK, &entry);
+        if entry.body.len() <= MAX_BODY_BYTES {
+            cache.store(self.key, entry);
+        }
+        served
+    }
+}
+
+fn kept_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    KEPT_HEADERS
+        .iter()
+        .filter_map(|name| Some((name.to_string(), headers.get(*name)?.to_str().ok()?.to_string())))
+        .collect()
+}
+
+fn rebuild(status: StatusCode, entry: &Entry) -> Result<Response> {
+    let mut builder = http::Response::builder().status(status.as_u16());
+    for (name, value) in &entry.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    let response = builder
+        .body(entry.body.clone())
+        .map_err(|e| AppError::Internal(format!("Failed to rebuild cached response: {}", e)))?;
+    Ok(Response::from(response))
+}
+
+impl Cache {
+    fn get_memory(&self, key: &str) -> Option<Entry> {
+        let mut guard = self.entries.lock().ok()?;
+        let (entries, tick) = &mut *guard;
+        *tick += 1;
+        let (entry, used) = entries.get_mut(key)?;
+        *used = *tick;
+        Some(entry.clone())
+    }
+
+    async fn get_stored(&self, key: &str) -> Option<Entry> {
+        let db = self.db.as_ref()?;
+        let row = sqlx::query!(
+            "SELECT etag, last_modified, headers, body FROM github_response_cache WHERE cache_key = ?",
+            key
+        )
+        .fetch_optional(db)
+        .await
+        .map_err(|e| warn!("Failed to read the response cache: {}", e))
+        .ok()??;
+
+        let entry = Entry {
+            etag: row.etag,
+            last_modified: row.last_modified,
+            headers: serde_json::from_str(&row.headers).unwrap_or_default(),
+            body: row.body,
+        };
+        self.remember(key.to_string(), entry.clone());
+        Some(entry)
+    }
+
+    fn touch(&self, key: &str, entry: &Entry) {
+        if self.get_memory(key).is_none() {
+            self.remember(key.to_string(), entry.clone());
+        }
+    }
+
+    /// Keep `entry` in memory, evicting the least recently used beyond capacity
+    fn remember(&self, key: String, entry: Entry) {
+        let Ok(mut guard) = self.entries.lock() else {
+            return;
+        };
+        let (entries, tick) = &mut *guard;
+        *tick += 1;
+        entries.insert(key, (entry, *tick));
+        while entries.len() > self.capacity {
+            let oldest = entries.iter().min_by_key(|(_, (_, used))| *used).map(|(key, _)| key.clone());
+            match oldest {
+                Some(oldest) => entries.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+
+    fn store(&self, key: String, entry: Entry) {
+        self.remember(key.clone(), entry.clone());
+
+        let Some(db) = self.db.clone() else {
+            return;
+        };
+        // Written in the background so responses aren't held up by the database
+        tokio::spawn(
+            async move {
+                let headers = serde_json::to_string(&entry.headers).unwrap_or_default();
+                let result = sqlx::query!(
+                    r#"
+                    INSERT INTO github_response_cache (cache_key, etag, last_modified, headers, body, updated_at)
+                    VALUES (?, ?, ?, ?, ?, datetime('now'))
+                    ON CONFLICT(cache_key) DO UPDATE SET
+                        etag = excluded.etag,
+                        last_modified = excluded.last_modified,
+                        headers = excluded.headers,
+                        body = excluded.body,
+                        updated_at = excluded.updated_at
+                    "#,
+                    key,
+                    entry.etag,
+                    entry.last_modified,
+                    headers,
+                    entry.body
+                )
+                .execute(&db)
+                .await;
+                if let Err(e) = result {
+                    warn!("Failed to persist a cached response: {}", e);
+                }
+            }
+            .instrument(tracing::Span::current()),
+        );
+    }
+}
+
+/// Delete stored responses older than the retention; returns how many went
+pub async fn prune(db: &sqlx::SqlitePool) -> Result<u64> {
+    let result = sqlx::query!(
+        "DELETE FROM github_response_cache WHERE updated_at < datetime('now', ?)",
+        STORED_RETENTION
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Periodically delete expired stored responses when they're persisted
+pub fn spawn_pruning(state: AppState) {
+    if !state.config.github.cache_persist {
+        return;
+    }
+
+    let scheduler = state.scheduler.clone();
+    scheduler.spawn("github_cache_pruning", Priority::Low, PRUNE_INTERVAL, move || {
+        let state = state.clone();
+        async move {
+            let removed = prune(&state.db).await?;
+            if removed > 0 {
+                info!("Pruned {} stored GitHub responses", removed);
+            }
+            Ok(())
+        }
+    });
+}
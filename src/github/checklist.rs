@@ -0,0 +1,92 @@
+use serde::Serialize;
+
+use crate::error::{AppError, Result};
+
+/// A Markdown task-list entry (`- [ ] text`) from an issue or PR body
+#[derive(Debug, Clone, Serialize)]
+pub struct ChecklistItem {
+    /// 1-based position among the checklist items in the body
+    pub index: usize,
+    pub text: String,
+    pub checked: bool,
+    #[serde(skip)]
+    line: usize,
+}
+
+/// Extract task-list items, ignoring anything inside fenced code blocks
+pub fn parse_checklist(body: &str) -> Vec<ChecklistItem> {
+    let mut items = Vec::new();
+    let mut in_fence = false;
+
+    for (line_number, line) in body.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        if let Some((checked, text)) = parse_task_line(trimmed) {
+            items.push(ChecklistItem {
+                index: items.len() + 1,
+                text: text.to_string(),
+                checked,
+                line: line_number,
+            });
+        }
+    }
+
+    items
+}
+
+fn parse_task_line(line: &str) -> Option<(bool, &str)> {
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))?
+        .trim_start();
+
+    let checked = match rest.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    Some((checked, rest[3..].trim()))
+}
+
+/// Set the checked state of the selected items and return the patched body.
+/// Items are selected by index or by case-insensitive text match; everything
+/// else in the body is left byte-for-byte as it was.
+pub fn set_checked(body: &str, indices: &[usize], text: Option<&str>, checked: bool) -> Result<(String, Vec<ChecklistItem>)> {
+    let items = parse_checklist(body);
+    let text = text.map(str::to_lowercase);
+
+    let selected: Vec<ChecklistItem> = items
+        .into_iter()
+        .filter(|item| {
+            indices.contains(&item.index)
+                || text.as_deref().map_or(false, |t| item.text.to_lowercase().contains(t))
+        })
+        .collect();
+
+    if selected.is_empty() {
+        return Err(AppError::Validation("No checklist items matched".to_string()));
+    }
+
+    let marker = if checked { "[x]" } else { "[ ]" };
+    let mut lines: Vec<String> = body.split('\n').map(String::from).collect();
+    for item in &selected {
+        let line = &mut lines[item.line];
+        if let Some(start) = line.find('[') {
+            line.replace_range(start..start + 3, marker);
+        }
+    }
+
+    let updated = selected
+        .into_iter()
+        .map(|item| ChecklistItem { checked, ..item })
+        .collect();
+    Ok((lines.join("\n"), updated))
+}
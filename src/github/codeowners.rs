@@ -0,0 +1,175 @@
+// Who owns the paths a pull request changes, according to CODEOWNERS.
+//
+// Patterns follow GitHub's subset of gitignore syntax: a leading `/` or an
+// inner `/` anchors a pattern at the root, a trailing `/` matches only
+// directories, `*` and `?` stay within one path segment and `**` crosses
+// them. The last pattern matching a path decides its owners, and a pattern
+// listed without owners leaves the paths it matches unowned.
+
+use regex::Regex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Where GitHub looks for the file, in its order
+pub const LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+struct Rule {
+    regex: Regex,
+    owners: Vec<String>,
+}
+
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+/// The owners of a set of changed paths, split the way the API wants them
+#[derive(Debug, Default, Serialize)]
+pub struct Ownership {
+    /// Each owner with the changed paths it owns
+    pub owners: BTreeMap<String, Vec<String>>,
+    /// Changed paths no rule gives an owner
+    pub unowned: Vec<String>,
+    /// User logins, without the `@`
+    pub users: Vec<String>,
+    /// Teams as `org/team`, without the `@`
+    pub teams: Vec<String>,
+    /// Owners given by email, which can't be requested through the API
+    pub unresolved: Vec<String>,
+}
+
+impl CodeOwners {
+    /// Parse a CODEOWNERS file; lines whose pattern isn't understood are skipped
+    pub fn parse(text: &str) -> Self {
+        let rules = text
+            .lines()
+            .map(|line| line.split_once(" #").map_or(line, |(rule, _)| rule).trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let regex = pattern_regex(parts.next()?)?;
+                Some(Rule { regex, owners: parts.map(String::from).collect() })
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    /// Owners of `path`, from the last rule that matches it
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.regex.is_match(path))
+            .map_or(&[], |rule| rule.owners.as_slice())
+    }
+
+    pub fn ownership<'a>(&self, paths: impl IntoIterator<Item = &'a str>) -> Ownership {
+        let mut ownership = Ownership::default();
+        for path in paths {
+            let owners = self.owners_of(path);
+            if owners.is_empty() {
+                ownership.unowned.push(path.to_string());
+            }
+            for owner in owners {
+                ownership.owners.entry(owner.clone()).or_default().push(path.to_string());
+            }
+        }
+
+        for owner in ownership.owners.keys() {
+            let (list, name) = match owner.strip_prefix('@') {
+                Some(handle) if handle.contains('/') => (&mut ownership.teams, handle.to_string()),
+                Some(handle) => (&mut ownership.users, handle.to_string()),
+                None => (&mut ownership.unresolved, owner.clone()),
+            };
+            list.push(name);
+        }
+        ownership
+    }
+}
+
+/// A CODEOWNERS pattern as a regex over repository-relative file paths
+fn pattern_regex(pattern: &str) -> Option<Regex> {
+    let directory = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.starts_with('/') || trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut rest = trimmed;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("**/") {
+            regex.push_str("(?:.*/)?");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("**") {
+            regex.push_str(".*");
+            rest = after;
+        } else {
+            let c = rest.chars().next()?;
+            match c {
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                c => regex.push_str(&regex::escape(&c.to_string())),
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    // A pattern naming a directory owns everything beneath it. One whose last
+    // segment has a wildcard matches within that segment only, so `docs/*`
+    // owns the files in docs but not those in its subdirectories.
+    let last = trimmed.rsplit('/').next().unwrap_or(trimmed);
+    regex.push_str(if directory {
+        "/.*$"
+    } else if last.contains(['*', '?']) && last != "**" {
+        "$"
+    } else {
+        "(?:/.*)?$"
+    });
+
+    Regex::new(&regex).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn anchors_patterns_with_a_slash() {
+        let owners = CodeOwners::parse("*.rs @rust\n/build/ @ci\ndocs/* @docs\napps/**/test.js @qa\n");
+        assert_eq!(owners.owners_of("src/deep/lib.rs"), ["@rust"]);
+        assert_eq!(owners.owners_of("build/out/bin"), ["@ci"]);
+        assert!(owners.owners_of("src/build/out").is_empty());
+        assert_eq!(owners.owners_of("apps/test.js"), ["@qa"]);
+        assert_eq!(owners.owners_of("apps/web/unit/test.js"), ["@qa"]);
+    }
+
+    #[test]
+    fn wildcards_stay_in_their_segment() {
+        let owners = CodeOwners::parse("docs/* @docs\ndocs/api @api\n");
+        assert_eq!(owners.owners_of("docs/index.md"), ["@docs"]);
+        assert!(owners.owners_of("docs/guides/setup.md").is_empty());
+        // Without a wildcard a pattern also owns what's beneath it
+        assert_eq!(owners.owners_of("docs/api/v1.md"), ["@api"]);
+    }
+
+    #[test]
+    fn last_match_wins_and_no_owners_unowns() {
+        let owners = CodeOwners::parse("# comment\n* @everyone\n/vendor/ # upstream code\nsrc/ @core @octo-org/reviewers # inline\n");
+        assert_eq!(owners.owners_of("README.md"), ["@everyone"]);
+        assert!(owners.owners_of("vendor/lib.c").is_empty());
+        assert_eq!(owners.owners_of("src/main.rs"), ["@core", "@octo-org/reviewers"]);
+    }
+
+    #[test]
+    fn splits_owners_into_users_teams_and_emails() {
+        let owners = CodeOwners::parse("* @octocat @octo-org/reviewers dev@example.com\n/docs/ \n");
+        let ownership = owners.ownership(["src/lib.rs", "docs/index.md"]);
+        assert_eq!(ownership.users, ["octocat"]);
+        assert_eq!(ownership.teams, ["octo-org/reviewers"]);
+        assert_eq!(ownership.unresolved, ["dev@example.com"]);
+        assert_eq!(ownership.unowned, ["docs/index.md"]);
+        assert_eq!(ownership.owners["@octocat"], ["src/lib.rs"]);
+    }
+}
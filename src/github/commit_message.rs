@@ -0,0 +1,146 @@
+// What an agent needs to write a commit message for a diff.
+//
+// The server doesn't write the message itself. It reports which files
+// changed and by how much, hands over the diff (cut at a size an agent can
+// read), and guesses a Conventional Commits type and scope from the paths for
+// the agent to confirm or replace.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::git::FileDiffStat;
+
+/// Conventional Commits types, with when to use each
+pub const COMMIT_TYPES: &[(&str, &str)] = &[
+    ("feat", "a new feature"),
+    ("fix", "a bug fix"),
+    ("docs", "documentation only"),
+    ("style", "formatting, no code change"),
+    ("refactor", "a code change that neither fixes a bug nor adds a feature"),
+    ("perf", "a performance improvement"),
+    ("test", "adding or correcting tests"),
+    ("build", "the build system or dependencies"),
+    ("ci", "CI configuration"),
+    ("chore", "anything else that doesn't touch source or tests"),
+];
+
+#[derive(Debug, Serialize)]
+pub struct DiffSummary {
+    pub files_changed: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    /// A type guessed from the paths; `None` means the diff has to be read
+    pub suggested_type: Option<&'static str>,
+    /// The directory every changed file shares under `src/`, when there is one
+    pub suggested_scope: Option<String>,
+}
+
+pub fn summarize(files: &[FileDiffStat]) -> DiffSummary {
+    DiffSummary {
+        files_changed: files.len(),
+        additions: files.iter().map(|f| f.additions).sum(),
+        deletions: files.iter().map(|f| f.deletions).sum(),
+        suggested_type: suggest_type(files),
+        suggested_scope: suggest_scope(files),
+    }
+}
+
+fn suggest_type(files: &[FileDiffStat]) -> Option<&'static str> {
+    let all = |matches: fn(&str) -> bool| !files.is_empty() && files.iter().all(|f| matches(&f.path));
+
+    if all(|p| p.ends_with(".md") || p.starts_with("docs/")) {
+        Some("docs")
+    } else if all(|p| p.starts_with(".github/workflows/") || p.starts_with(".gitlab-ci") || p.starts_with(".circleci/")) {
+        Some("ci")
+    } else if all(|p| p.starts_with("tests/") || p.contains("/tests/") || p.contains("_test.") || p.contains(".test.")) {
+        Some("test")
+    } else if all(|p| {
+        let name = p.rsplit('/').next().unwrap_or(p);
+        matches!(name, "Cargo.toml" | "Cargo.lock" | "package.json" | "package-lock.json" | "go.mod" | "go.sum" | "Dockerfile")
+    }) {
+        Some("build")
+    } else {
+        None
+    }
+}
+
+fn suggest_scope(files: &[FileDiffStat]) -> Option<String> {
+    let scope = |path: &str| {
+        let mut parts = path.strip_prefix("src/")?.split('/');
+        let first = parts.next()?;
+        // A file directly under src/ is its own scope, without the extension
+        Some(match parts.next() {
+            Some(_) => first.to_string(),
+            None => first.rsplit_once('.').map_or(first, |(stem, _)| stem).to_string(),
+        })
+    };
+
+    let first = scope(&files.first()?.path)?;
+    files.iter().all(|f| scope(&f.path).as_deref() == Some(first.as_str())).then_some(first)
+}
+
+/// The template and rules the agent fills in, with the guesses pre-filled
+pub fn template(summary: &DiffSummary) -> Value {
+    let kind = summary.suggested_type.unwrap_or("<type>");
+    let scope = summary.suggested_scope.as_deref().map(|s| format!("({})", s)).unwrap_or_default();
+
+    json!({
+        "format": format!("{}{}: <summary>\n\n<body>\n\n<footer>", kind, scope),
+        "types": COMMIT_TYPES.iter().map(|(name, meaning)| json!({ "type": name, "meaning": meaning })).collect::<Vec<_>>(),
+        "rules": [
+            "Summary in the imperative mood, at most 72 characters, no trailing period",
+            "Scope is optional: the area of the code the change touches",
+            "Body explains what changed and why, wrapped at 72 characters",
+            "Mark breaking changes with `!` after the type/scope and a `BREAKING CHANGE:` footer",
+            "Reference issues in the footer, e.g. `Closes #123`"
+        ]
+    })
+}
+
+/// `diff` cut to at most `max_bytes` on a line boundary, and whether it was cut
+pub fn truncate(diff: String, max_bytes: usize) -> (String, bool) {
+    if diff.len() <= max_bytes {
+        return (diff, false);
+    }
+
+    let mut end = max_bytes;
+    while !diff.is_char_boundary(end) {
+        end -= 1;
+    }
+    let end = diff[..end].rfind('\n').map_or(end, |newline| newline + 1);
+    (diff[..end].to_string(), true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(path: &str, additions: usize, deletions: usize) -> FileDiffStat {
+        FileDiffStat { path: path.to_string(), additions, deletions, binary: false }
+    }
+
+    #[test]
+    fn summarizes_and_guesses_from_paths() {
+        let summary = summarize(&[stat("README.md", 3, 1), stat("docs/setup.md", 10, 0)]);
+        assert_eq!((summary.files_changed, summary.additions, summary.deletions), (2, 13, 1));
+        assert_eq!(summary.suggested_type, Some("docs"));
+        assert_eq!(summary.suggested_scope, None);
+
+        let summary = summarize(&[stat("src/github/api.rs", 1, 1), stat("src/github/mod.rs", 2, 0)]);
+        assert_eq!(summary.suggested_type, None);
+        assert_eq!(summary.suggested_scope.as_deref(), Some("github"));
+
+        assert_eq!(summarize(&[stat("src/config.rs", 1, 0)]).suggested_scope.as_deref(), Some("config"));
+        assert_eq!(summarize(&[stat("Cargo.toml", 1, 0), stat("Cargo.lock", 9, 9)]).suggested_type, Some("build"));
+        assert_eq!(summarize(&[]).suggested_type, None);
+    }
+
+    #[test]
+    fn truncates_on_a_line_boundary() {
+        let diff = "+first\n+second\n+third\n".to_string();
+        assert_eq!(truncate(diff.clone(), 100), (diff.clone(), false));
+        assert_eq!(truncate(diff.clone(), 10), ("+first\n".to_string(), true));
+        // A cut inside a multi-byte character backs off to its start
+        assert_eq!(truncate("é".repeat(4), 3), ("é".to_string(), true));
+    }
+}
@@ -0,0 +1,99 @@
+use serde::Serialize;
+
+/// Author login of Dependabot pull requests
+pub const DEPENDABOT_LOGIN: &str = "dependabot[bot]";
+
+/// Size of a version bump, smallest first so policies compare with `<=`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+    /// Versions couldn't be parsed, e.g. grouped or git-ref updates
+    Unknown,
+}
+
+impl Bump {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Bump::Patch => "patch",
+            Bump::Minor => "minor",
+            Bump::Major => "major",
+            Bump::Unknown => "unknown",
+        }
+    }
+}
+
+/// A dependency update parsed from a Dependabot PR title
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyUpdate {
+    pub dependency: String,
+    pub from: String,
+    pub to: String,
+    pub bump: Bump,
+}
+
+/// Largest bump auto-merged under a policy of `none`, `patch` or `minor`
+pub fn max_auto_merge_bump(policy: &str) -> Option<Bump> {
+    match policy.to_lowercase().as_str() {
+        "patch" => Some(Bump::Patch),
+        "minor" => Some(Bump::Minor),
+        _ => None,
+    }
+}
+
+/// Parse titles such as "Bump serde from 1.0.1 to 1.0.2 in /api" or
+/// "chore(deps): update tokio requirement from ~1.2 to ~1.3"
+pub fn parse_update(title: &str) -> Option<DependencyUpdate> {
+    let lower = title.to_ascii_lowercase();
+    let start = ["bump ", "update "]
+        .iter()
+        .find_map(|verb| lower.find(verb).map(|i| i + verb.len()))?;
+    let from_at = lower[start..].find(" from ")? + start;
+    let to_at = lower[from_at..].find(" to ")? + from_at;
+
+    let dependency = title[start..from_at].trim_end_matches(" requirement").trim();
+    let from = title[from_at + 6..to_at].trim();
+    let to = title[to_at + 4..].split_whitespace().next()?;
+
+    if dependency.is_empty() || from.is_empty() {
+        return None;
+    }
+
+    Some(DependencyUpdate {
+        dependency: dependency.to_string(),
+        from: from.to_string(),
+        to: to.to_string(),
+        bump: classify(from, to),
+    })
+}
+
+/// Compare two versions; a change at or before the first non-zero
+/// component is breaking, as Cargo and npm treat 0.x releases
+pub fn classify(from: &str, to: &str) -> Bump {
+    let (Some(from), Some(to)) = (version_parts(from), version_parts(to)) else {
+        return Bump::Unknown;
+    };
+
+    let first_non_zero = from.iter().position(|&p| p != 0).unwrap_or(2);
+    match (0..3).find(|&i| from[i] != to[i]) {
+        None => Bump::Patch,
+        Some(i) if i <= first_non_zero => Bump::Major,
+        Some(1) => Bump::Minor,
+        Some(_) => Bump::Patch,
+    }
+}
+
+/// `[major, minor, patch]`, ignoring range operators, a `v` prefix and
+/// pre-release or build suffixes
+fn version_parts(version: &str) -> Option<[u64; 3]> {
+    let version = version.trim_start_matches(|c: char| !c.is_ascii_digit());
+    let core = version.split(['-', '+']).next()?;
+
+    let mut parts = [0u64; 3];
+    for (i, part) in core.split('.').take(3).enumerate() {
+        parts[i] = part.parse().ok()?;
+    }
+    (!core.is_empty()).then_some(parts)
+}
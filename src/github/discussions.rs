@@ -0,0 +1,77 @@
+// GitHub Discussions, which have no REST API and are read and written
+// through GraphQL.
+//
+// Categories are named by their display name or slug. Only comments in a
+// category that takes answers (Q&A by default) can be marked as the answer.
+
+use serde_json::{json, Value};
+
+use super::api::{GitHubDiscussion, GitHubDiscussionCategory, GitHubDiscussionComment};
+use crate::error::{AppError, Result};
+
+/// Discussions listed per call unless the caller asks for fewer
+pub const DEFAULT_LIMIT: usize = 20;
+pub const MAX_LIMIT: usize = 100;
+
+/// The category called `name`, by display name or slug
+pub fn find_category<'a>(categories: &'a [GitHubDiscussionCategory], name: &str) -> Result<&'a GitHubDiscussionCategory> {
+    let name = name.trim();
+    categories
+        .iter()
+        .find(|c| c.name.eq_ignore_ascii_case(name) || c.slug.eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            let known: Vec<&str> = categories.iter().map(|c| c.name.as_str()).collect();
+            AppError::Validation(format!("Unknown discussion category '{}': expected one of {}", name, known.join(", ")))
+        })
+}
+
+/// OPEN and/or CLOSED for `open`, `closed` or `all`
+pub fn states(state: &str) -> Result<Option<&'static [&'static str]>> {
+    match state {
+        "open" => Ok(Some(&["OPEN"])),
+        "closed" => Ok(Some(&["CLOSED"])),
+        "all" => Ok(None),
+        other => Err(AppError::Validation(format!("Unknown state {}: expected open, closed or all", other))),
+    }
+}
+
+pub fn category(category: &GitHubDiscussionCategory) -> Value {
+    json!({
+        "name": category.name,
+        "slug": category.slug,
+        "answerable": category.is_answerable
+    })
+}
+
+pub fn summary(discussion: &GitHubDiscussion) -> Value {
+    json!({
+        "number": discussion.number,
+        "title": discussion.title,
+        "url": discussion.url,
+        "author": discussion.author,
+        "category": discussion.category.name,
+        "state": if discussion.closed { "closed" } else { "open" },
+        "answered": discussion.answered,
+        "comments": discussion.comment_count,
+        "created_at": discussion.created_at,
+        "updated_at": discussion.updated_at
+    })
+}
+
+pub fn comment(comment: &GitHubDiscussionComment) -> Value {
+    let mut value = json!({
+        "id": comment.id,
+        "author": comment.author,
+        "body": comment.body,
+        "url": comment.url,
+        "created_at": comment.created_at,
+        "is_answer": comment.is_answer,
+        "upvotes": comment.upvotes
+    });
+    // Replies can't be replied to, so they carry no thread of their own
+    if comment.reply_count > 0 || !comment.replies.is_empty() {
+        value["replies"] = json!(comment.replies.iter().map(self::comment).collect::<Vec<_>>());
+        value["reply_count"] = json!(comment.reply_count);
+    }
+    value
+}
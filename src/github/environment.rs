@@ -0,0 +1,191 @@
+use serde::Serialize;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+/// How long a `--version` probe may take before the tool is reported missing
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Marker files identifying a project type, checked at the repository root
+pub const PROJECT_MARKERS: &[(&str, &str)] = &[
+    ("Cargo.toml", "rust"),
+    ("package.json", "node"),
+    ("pyproject.toml", "python"),
+    ("requirements.txt", "python"),
+    ("setup.py", "python"),
+    ("go.mod", "go"),
+    ("pom.xml", "java"),
+    ("build.gradle", "java"),
+    ("build.gradle.kts", "java"),
+    ("Gemfile", "ruby"),
+    ("composer.json", "php"),
+];
+
+/// Checks run before merging when neither the workflow file nor
+/// `MERGE_CHECK_COMMANDS` lists any, per detected project type
+pub const DEFAULT_CHECKS: &[(&str, &[&str])] = &[
+    ("rust", &["cargo clippy --all-targets -- -D warnings", "cargo test"]),
+    ("node", &["npm run --if-present lint", "npm test"]),
+    ("python", &["python3 -m pytest"]),
+    ("go", &["go vet ./...", "go test ./..."]),
+];
+
+/// Version commands per toolchain; git is always probed
+const TOOLCHAINS: &[(&str, &[(&str, &[&str])])] = &[
+    ("rust", &[("rustc", &["--version"]), ("cargo", &["--version"])]),
+    ("node", &[("node", &["--version"]), ("npm", &["--version"])]),
+    ("python", &[("python3", &["--version"]), ("pip3", &["--version"])]),
+    ("go", &[("go", &["version"])]),
+    ("java", &[("java", &["-version"]), ("mvn", &["--version"]), ("gradle", &["--version"])]),
+    ("ruby", &[("ruby", &["--version"]), ("bundle", &["--version"])]),
+    ("php", &[("php", &["--version"]), ("composer", &["--version"])]),
+];
+
+#[derive(Debug, Serialize)]
+pub struct ToolVersion {
+    pub tool: String,
+    /// First line of the version output; None when the tool isn't installed
+    pub version: Option<String>,
+}
+
+/// Whether a pre-merge command's program can be found
+#[derive(Debug, Serialize)]
+pub struct CheckReadiness {
+    pub command: String,
+    pub program: String,
+    pub available: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnvironmentSnapshot {
+    pub project_types: Vec<&'static str>,
+    pub toolchains: Vec<ToolVersion>,
+    /// Free space on the repository's filesystem, when `df` is available
+    pub disk_available_bytes: Option<u64>,
+    pub merge_checks: Vec<CheckReadiness>,
+    /// workflow_file, config, detected or none
+    pub merge_checks_source: &'static str,
+    /// Every merge check's program is on PATH
+    pub merge_checks_runnable: bool,
+}
+
+/// Project types whose marker files exist in `root`, without duplicates
+pub fn detect_project_types(root: &Path) -> Vec<&'static str> {
+    let mut types = Vec::new();
+    for (marker, project_type) in PROJECT_MARKERS {
+        if root.join(marker).exists() && !types.contains(project_type) {
+            types.push(*project_type);
+        }
+    }
+    types
+}
+
+/// Default pipeline for the project types found in `root`, in marker order
+pub fn default_checks(root: &Path) -> Vec<String> {
+    detect_project_types(root)
+        .into_iter()
+        .filter_map(|t| DEFAULT_CHECKS.iter().find(|(project_type, _)| *project_type == t))
+        .flat_map(|(_, checks)| checks.iter().map(|c| c.to_string()))
+        .collect()
+}
+
+pub async fn snapshot(root: &Path, merge_checks: &[String], merge_checks_source: &'static str) -> EnvironmentSnapshot {
+    let project_types = detect_project_types(root);
+
+    let mut probes: Vec<(&str, &[&str])> = vec![("git", &["--version"])];
+    for (project_type, tools) in TOOLCHAINS {
+        if project_types.contains(project_type) {
+            probes.extend(tools.iter().copied());
+        }
+    }
+
+    let mut set = JoinSet::new();
+    for (index, (tool, args)) in probes.iter().enumerate() {
+        let (tool, args) = (tool.to_string(), args.iter().map(|a| a.to_string()).collect::<Vec<_>>());
+        set.spawn(async move { (index, tool.clone(), probe_version(&tool, &args).await) });
+    }
+    let mut toolchains = Vec::with_capacity(probes.len());
+    while let Some(Ok(probe)) = set.join_next().await {
+        toolchains.push(probe);
+    }
+    toolchains.sort_by_key(|(index, _, _)| *index);
+
+    let merge_checks: Vec<CheckReadiness> = merge_checks
+        .iter()
+        .map(|command| {
+            let program = command_program(command);
+            CheckReadiness {
+                command: command.clone(),
+                available: program_on_path(&program),
+                program,
+            }
+        })
+        .collect();
+
+    EnvironmentSnapshot {
+        project_types,
+        toolchains: toolchains
+            .into_iter()
+            .map(|(_, tool, version)| ToolVersion { tool, version })
+            .collect(),
+        disk_available_bytes: disk_available(root).await,
+        merge_checks_runnable: merge_checks.iter().all(|c| c.available),
+        merge_checks,
+        merge_checks_source,
+    }
+}
+
+async fn probe_version(tool: &str, args: &[String]) -> Option<String> {
+    let output = tokio::process::Command::new(tool)
+        .args(args)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(PROBE_TIMEOUT, output).await.ok()?.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // Some tools (java) print their version to stderr
+    let text = if output.stdout.is_empty() { output.stderr } else { output.stdout };
+    String::from_utf8_lossy(&text)
+        .lines()
+        .next()
+        .map(|l| l.trim().to_string())
+}
+
+/// The program a shell command runs, skipping leading `VAR=value` assignments
+fn command_program(command: &str) -> String {
+    command
+        .split_whitespace()
+        .find(|word| !word.contains('='))
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn program_on_path(program: &str) -> bool {
+    if program.is_empty() {
+        return false;
+    }
+    if program.contains('/') {
+        return Path::new(program).exists();
+    }
+    std::env::var_os("PATH").map_or(false, |path| {
+        std::env::split_paths(&path).any(|dir| dir.join(program).is_file())
+    })
+}
+
+async fn disk_available(root: &Path) -> Option<u64> {
+    let output = tokio::process::Command::new("df")
+        .arg("-Pk")
+        .arg(root)
+        .stdin(Stdio::null())
+        .output();
+    let output = tokio::time::timeout(PROBE_TIMEOUT, output).await.ok()?.ok()?;
+
+    // POSIX format: header, then "fs blocks used available capacity mount"
+    let text = String::from_utf8_lossy(&output.stdout);
+    let kib: u64 = text.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()?;
+    Some(kib * 1024)
+}
@@ -0,0 +1,148 @@
+// GitHub's error responses, sorted by what a caller can do about them.
+//
+// GitHub explains a failure in a JSON body (`message`, `errors`,
+// `documentation_url`) and in headers: the rate-limit window, and on 403/404
+// the scopes the endpoint accepts next to those the token has. Each kind maps
+// to its own MCP error code, with the details in the error's `data`, so an
+// agent can wait out a rate limit or ask for a scope rather than parse text.
+
+use reqwest::header::HeaderMap;
+use reqwest::{Response, StatusCode};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum GitHubError {
+    /// The resource doesn't exist, or the token can't see it
+    #[error("{message}")]
+    NotFound { message: String },
+
+    /// Unix timestamp at which the window resets, when GitHub gave one
+    #[error("{message}")]
+    RateLimited { message: String, reset_at: Option<i64> },
+
+    /// Scopes the endpoint accepts, next to those the token has
+    #[error("{message}")]
+    Forbidden { message: String, required_scopes: Vec<String>, token_scopes: Vec<String> },
+
+    /// GitHub's per-field `errors`, e.g. `{"resource": "Issue", "field": "title", "code": "missing_field"}`
+    #[error("{message}")]
+    ValidationFailed { message: String, errors: Vec<Value> },
+
+    #[error("{message}")]
+    Failed { status: u16, message: String },
+}
+
+impl GitHubError {
+    /// The error a failed `response` describes; `context` says what was attempted
+    pub async fn from_response(response: Response, context: &str) -> Self {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let text = response.text().await.unwrap_or_default();
+        Self::from_parts(status, &headers, &text, context)
+    }
+
+    pub fn from_parts(status: StatusCode, headers: &HeaderMap, text: &str, context: &str) -> Self {
+        let body: Value = serde_json::from_str(text).unwrap_or(Value::Null);
+        let detail = body["message"].as_str().unwrap_or(text);
+        let message = format!("{}: {} - {}", context, status, detail);
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let scopes = |name: &str| -> Vec<String> {
+            header(name)
+                .map(|v| v.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from).collect())
+                .unwrap_or_default()
+        };
+
+        let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+            || header("x-ratelimit-remaining") == Some("0")
+            || header("retry-after").is_some()
+            || detail.to_lowercase().contains("rate limit");
+        if matches!(status, StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS) && rate_limited {
+            let reset_at = header("retry-after")
+                .and_then(|v| v.parse::<i64>().ok())
+                .map(|seconds| chrono::Utc::now().timestamp() + seconds)
+                .or_else(|| header("x-ratelimit-reset").and_then(|v| v.parse().ok()));
+            return Self::RateLimited { message, reset_at };
+        }
+
+        match status {
+            StatusCode::NOT_FOUND => Self::NotFound { message },
+            StatusCode::FORBIDDEN | StatusCode::UNAUTHORIZED => Self::Forbidden {
+                message,
+                required_scopes: scopes("x-accepted-oauth-scopes"),
+                token_scopes: scopes("x-oauth-scopes"),
+            },
+            StatusCode::UNPROCESSABLE_ENTITY => Self::ValidationFailed {
+                message,
+                errors: body["errors"].as_array().cloned().unwrap_or_default(),
+            },
+            _ => Self::Failed { status: status.as_u16(), message },
+        }
+    }
+
+    /// The error a GraphQL `errors` array describes, typed by its first entry
+    pub fn from_graphql(errors: &[Value]) -> Self {
+        let messages: Vec<&str> = errors.iter().filter_map(|e| e["message"].as_str()).collect();
+        let message = format!("GraphQL error: {}", messages.join("; "));
+
+        match errors.first().and_then(|e| e["type"].as_str()) {
+            Some("NOT_FOUND") => Self::NotFound { message },
+            Some("RATE_LIMITED") => Self::RateLimited { message, reset_at: None },
+            Some("FORBIDDEN" | "INSUFFICIENT_SCOPES") => Self::Forbidden {
+                message,
+                required_scopes: Vec::new(),
+                token_scopes: Vec::new(),
+            },
+            _ => Self::Failed { status: 200, message },
+        }
+    }
+
+    pub fn not_found(message: String) -> Self {
+        Self::NotFound { message }
+    }
+
+    /// A rate limit that was hit before a request was sent
+    pub fn rate_limited(message: String, reset_at: Option<i64>) -> Self {
+        Self::RateLimited { message, reset_at }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::NotFound { .. } => "not_found",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Forbidden { .. } => "forbidden",
+            Self::ValidationFailed { .. } => "validation_failed",
+            Self::Failed { .. } => "failed",
+        }
+    }
+
+    /// The HTTP status this error is reported with
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::NotFound { .. } => StatusCode::NOT_FOUND,
+            Self::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::Forbidden { .. } => StatusCode::FORBIDDEN,
+            Self::ValidationFailed { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::Failed { .. } => StatusCode::BAD_GATEWAY,
+        }
+    }
+
+    /// Details for the MCP error's `data`
+    pub fn data(&self) -> Value {
+        let mut data = match self {
+            Self::NotFound { .. } => json!({}),
+            Self::RateLimited { reset_at, .. } => json!({
+                "reset_at": reset_at,
+                "retry_after_seconds": reset_at.map(|at| (at - chrono::Utc::now().timestamp()).max(0))
+            }),
+            Self::Forbidden { required_scopes, token_scopes, .. } => json!({
+                "required_scopes": required_scopes,
+                "token_scopes": token_scopes
+            }),
+            Self::ValidationFailed { errors, .. } => json!({ "errors": errors }),
+            Self::Failed { status, .. } => json!({ "status": status }),
+        };
+        data["type"] = json!(self.kind());
+        data
+    }
+}
@@ -0,0 +1,73 @@
+// Gists for sharing command output, test logs and snippets.
+//
+// Gists are secret unless asked otherwise: unlisted, but readable by anyone
+// with the link. Content is checked for credentials before it's published,
+// with the same built-in rules as a push.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::api::GitHubGist;
+use crate::error::{AppError, Result};
+
+/// Most a single gist may hold; GitHub truncates larger files when read back
+const MAX_GIST_BYTES: usize = 10 * 1024 * 1024;
+
+/// Gists listed per call unless the caller asks for fewer
+pub const DEFAULT_LIMIT: usize = 30;
+pub const MAX_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GistFile {
+    /// File name, whose extension sets the highlighting, e.g. `test-output.log`
+    pub filename: String,
+    pub content: String,
+}
+
+/// The `files` object the API takes, after checking names and sizes
+pub fn files_payload(files: &[GistFile]) -> Result<Value> {
+    if files.is_empty() {
+        return Err(AppError::Validation("A gist needs at least one file".to_string()));
+    }
+
+    let mut payload = Map::new();
+    let mut total = 0;
+    for file in files {
+        let name = file.filename.trim();
+        if name.is_empty() || name.contains('/') {
+            return Err(AppError::Validation(format!("Invalid gist file name '{}'", file.filename)));
+        }
+        // GitHub drops empty files, which would leave a gist with fewer than asked
+        if file.content.trim().is_empty() {
+            return Err(AppError::Validation(format!("Gist file {} is empty", name)));
+        }
+        if payload.keys().any(|existing| existing.eq_ignore_ascii_case(name)) {
+            return Err(AppError::Validation(format!("Gist file {} appears more than once", name)));
+        }
+        total += file.content.len();
+        payload.insert(name.to_string(), json!({ "content": file.content }));
+    }
+
+    if total > MAX_GIST_BYTES {
+        return Err(AppError::Validation(format!(
+            "Gist content is {} bytes, over the {} MB limit",
+            total,
+            MAX_GIST_BYTES / (1024 * 1024)
+        )));
+    }
+    Ok(Value::Object(payload))
+}
+
+pub fn summary(gist: &GitHubGist) -> Value {
+    let mut files: Vec<&String> = gist.files.keys().collect();
+    files.sort();
+    json!({
+        "id": gist.id,
+        "url": gist.html_url,
+        "description": gist.description,
+        "visibility": if gist.public { "public" } else { "secret" },
+        "files": files,
+        "created_at": gist.created_at,
+        "updated_at": gist.updated_at
+    })
+}
@@ -0,0 +1,405 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Assumed page size for a connection whose `first`/`last` can't be resolved,
+/// matching GitHub's own maximum
+const UNKNOWN_PAGE_SIZE: u64 = 100;
+
+/// Most named fragment spreads expanded in one document, so fragments that
+/// spread each other many times over can't make measuring it expensive
+const MAX_FRAGMENT_SPREADS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OperationKind {
+    Query,
+    Mutation,
+    Subscription,
+}
+
+/// What a document asks for, as far as can be told without the schema
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryShape {
+    pub operation: OperationKind,
+    /// Deepest selection set nesting, counting the operation's own braces
+    pub depth: usize,
+    /// Upper bound on nodes returned, multiplying nested `first`/`last` page
+    /// sizes the way GitHub's node limit does. Named fragments are expanded
+    /// where they are spread.
+    pub nodes: u64,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Punct(char),
+    /// `...`, before a fragment's name or an inline fragment
+    Spread,
+    Name(String),
+    Number(String),
+    Str,
+}
+
+fn tokenize(document: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = document.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() || c == ',' || c == '\u{feff}' => {}
+            '#' => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            '"' => {
+                let block = chars.next_if_eq(&'"').is_some();
+                if block && chars.next_if_eq(&'"').is_none() {
+                    // `""` is an empty string
+                    tokens.push(Token::Str);
+                    continue;
+                }
+                let mut quotes = 0;
+                let mut closed = false;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                            quotes = 0;
+                        }
+                        '"' if !block => {
+                            closed = true;
+                            break;
+                        }
+                        '"' => {
+                            quotes += 1;
+                            if quotes == 3 {
+                                closed = true;
+                                break;
+                            }
+                        }
+                        _ => quotes = 0,
+                    }
+                }
+                if !closed {
+                    return Err("unterminated string".to_string());
+                }
+                tokens.push(Token::Str);
+            }
+            '{' | '}' | '(' | ')' | '[' | ']' | ':' | '$' | '@' | '!' | '=' | '|' | '&' => {
+                tokens.push(Token::Punct(c));
+            }
+            '.' => {
+                if chars.next_if_eq(&'.').is_none() || chars.next_if_eq(&'.').is_none() {
+                    return Err("unexpected '.'".to_string());
+                }
+                tokens.push(Token::Spread);
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut name = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                    name.push(c);
+                }
+                tokens.push(Token::Name(name));
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut number = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-')) {
+                    number.push(c);
+                }
+                tokens.push(Token::Number(number));
+            }
+            c => return Err(format!("unexpected character '{}'", c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Classify and measure `document`, resolving `$variable` page sizes from
+/// `variables`. Fails on documents too malformed to measure, and on
+/// fragments that are unknown or spread themselves.
+pub fn analyze(document: &str, variables: &Value) -> Result<QueryShape, String> {
+    let tokens = tokenize(document)?;
+
+    let mut operation = None;
+    // Selection sets of the operations, and of fragments by name
+    let mut operations = Vec::new();
+    let mut fragments = HashMap::new();
+    let mut parens = 0usize;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        match &tokens[i] {
+            Token::Name(name) if parens == 0 && name == "fragment" => {
+                let Some(Token::Name(fragment)) = tokens.get(i + 1) else {
+                    return Err("fragment without a name".to_string());
+                };
+                // Skipping any object values in the fragment's directive arguments
+                let mut nested = 0usize;
+                let start = (i..tokens.len())
+                    .find(|&j| match tokens[j] {
+                        Token::Punct('(') => {
+                            nested += 1;
+                            false
+                        }
+                        Token::Punct(')') => {
+                            nested = nested.saturating_sub(1);
+                            false
+                        }
+                        Token::Punct('{') => nested == 0,
+                        _ => false,
+                    })
+                    .ok_or_else(|| format!("fragment {} has no selection set", fragment))?;
+                let end = closing_brace(&tokens, start)?;
+                if fragments.insert(fragment.as_str(), &tokens[start..=end]).is_some() {
+                    return Err(format!("fragment {} is defined twice", fragment));
+                }
+                i = end;
+            }
+            Token::Name(name) if parens == 0 => {
+                let kind = match name.as_str() {
+                    "mutation" => Some(OperationKind::Mutation),
+                    "subscription" => Some(OperationKind::Subscription),
+                    "query" => Some(OperationKind::Query),
+                    _ => None,
+                };
+                // The most privileged operation in the document decides
+                if let Some(kind) = kind {
+                    operation = Some(match (operation, kind) {
+                        (Some(OperationKind::Subscription), _) | (_, OperationKind::Subscription) => {
+                            OperationKind::Subscription
+                        }
+                        (Some(OperationKind::Mutation), _) | (_, OperationKind::Mutation) => OperationKind::Mutation,
+                        _ => OperationKind::Query,
+                    });
+                }
+            }
+            Token::Punct('(') => parens += 1,
+            Token::Punct(')') => parens = parens.checked_sub(1).ok_or("unbalanced parentheses")?,
+            Token::Punct('{') if parens == 0 => {
+                if operation.is_none() {
+                    // Anonymous `{ ... }` shorthand
+                    operation = Some(OperationKind::Query);
+                }
+                let end = closing_brace(&tokens, i)?;
+                operations.push(&tokens[i..=end]);
+                i = end;
+            }
+            Token::Punct('}') if parens == 0 => return Err("unbalanced braces".to_string()),
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if parens != 0 {
+        return Err("unbalanced braces or parentheses".to_string());
+    }
+    let operation = operation.ok_or("no operation found")?;
+
+    let mut measure = Measure { fragments, variables, depth: 0, nodes: 0, expanding: Vec::new(), spreads: 0 };
+    for selection in operations {
+        measure.selection(selection, 0, 1)?;
+    }
+
+    Ok(QueryShape { operation, depth: measure.depth, nodes: measure.nodes })
+}
+
+/// Index of the `}` closing the `{` at `open`
+fn closing_brace(tokens: &[Token], open: usize) -> Result<usize, String> {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token {
+            Token::Punct('{') => depth += 1,
+            Token::Punct('}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    Err("unbalanced braces".to_string())
+}
+
+struct Measure<'a> {
+    fragments: HashMap<&'a str, &'a [Token]>,
+    variables: &'a Value,
+    depth: usize,
+    nodes: u64,
+    /// Fragments being expanded, innermost last
+    expanding: Vec<&'a str>,
+    spreads: usize,
+}
+
+impl<'a> Measure<'a> {
+    /// Measure the selection set `set` (braces included), nested in `depth`
+    /// others whose fields each stand for up to `multiplier` nodes
+    fn selection(&mut self, set: &'a [Token], depth: usize, multiplier: u64) -> Result<(), String> {
+        let depth = depth + 1;
+        self.depth = self.depth.max(depth);
+        self.fields(&set[1..set.len() - 1], depth, multiplier)
+    }
+
+    fn fields(&mut self, tokens: &'a [Token], depth: usize, multiplier: u64) -> Result<(), String> {
+        let mut parens = 0usize;
+        // Page size from the arguments of the field whose selection set opens next
+        let mut pending: Option<u64> = None;
+
+        let mut i = 0;
+        while i < tokens.len() {
+            match &tokens[i] {
+                Token::Name(name) if parens > 0 && (name == "first" || name == "last") => {
+                    if tokens.get(i + 1) == Some(&Token::Punct(':')) {
+                        let size = match (tokens.get(i + 2), tokens.get(i + 3)) {
+                            (Some(Token::Number(n)), _) => n.parse().unwrap_or(UNKNOWN_PAGE_SIZE),
+                            (Some(Token::Punct('$')), Some(Token::Name(var))) => {
+                                self.variables[var.as_str()].as_u64().unwrap_or(UNKNOWN_PAGE_SIZE)
+                            }
+                            _ => UNKNOWN_PAGE_SIZE,
+                        };
+                        pending = Some(pending.map_or(size, |p| p.max(size)));
+                    }
+                }
+                // A new field outside an argument list discards the previous field's arguments
+                Token::Name(_) if parens == 0 => pending = None,
+                Token::Spread if parens == 0 => {
+                    pending = None;
+                    // `... on Type { }` and `... { }` are inline and measured as
+                    // they come; `...Name` is replaced by the fragment's fields
+                    if let Some(Token::Name(name)) = tokens.get(i + 1) {
+                        if name != "on" {
+                            self.spread(name, depth, multiplier)?;
+                            i += 1;
+                        }
+                    }
+                }
+                Token::Punct('(') => parens += 1,
+                Token::Punct(')') => parens = parens.checked_sub(1).ok_or("unbalanced parentheses")?,
+                Token::Punct('{') if parens == 0 => {
+                    let child = match pending.take() {
+                        Some(size) => {
+                            let child = multiplier.saturating_mul(size);
+                            self.nodes = self.nodes.saturating_add(child);
+                            child
+                        }
+                        None => multiplier,
+                    };
+                    let end = closing_brace(tokens, i)?;
+                    self.selection(&tokens[i..=end], depth, child)?;
+                    i = end;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+
+        Ok(())
+    }
+
+    fn spread(&mut self, name: &str, depth: usize, multiplier: u64) -> Result<(), String> {
+        if self.expanding.contains(&name) {
+            return Err(format!("fragment {} spreads itself", name));
+        }
+        self.spreads += 1;
+        if self.spreads > MAX_FRAGMENT_SPREADS {
+            return Err(format!("more than {} fragment spreads", MAX_FRAGMENT_SPREADS));
+        }
+        let (&name, &set) = self
+            .fragments
+            .get_key_value(name)
+            .ok_or_else(|| format!("unknown fragment {}", name))?;
+
+        self.expanding.push(name);
+        self.fields(&set[1..set.len() - 1], depth, multiplier)?;
+        self.expanding.pop();
+        Ok(())
+    }
+}
+
+/// Whether `document` contains a mutation; unparseable documents count as one
+/// so read-only mode refuses them
+pub fn is_mutation(document: &str) -> bool {
+    analyze(document, &Value::Null).map_or(true, |shape| shape.operation != OperationKind::Query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn shape(document: &str) -> QueryShape {
+        analyze(document, &Value::Null).unwrap()
+    }
+
+    #[test]
+    fn classifies_operations() {
+        assert_eq!(shape("{ viewer { login } }").operation, OperationKind::Query);
+        assert_eq!(shape("query Q { viewer { login } }").operation, OperationKind::Query);
+        assert_eq!(shape("mutation { addStar(input: {starrableId: \"x\"}) { clientMutationId } }").operation, OperationKind::Mutation);
+        assert_eq!(shape("query A { viewer { login } } mutation B { x { y } }").operation, OperationKind::Mutation);
+        assert_eq!(shape("query($f: IssueFilters = {states: [OPEN]}) { viewer { login } }").operation, OperationKind::Query);
+        assert!(is_mutation("{ viewer { login "));
+        assert!(!is_mutation("query { viewer { login } }"));
+    }
+
+    #[test]
+    fn measures_depth_and_nodes() {
+        let document = "query($n: Int) { viewer { repositories(first: 50) { nodes { issues(last: $n) { nodes { title } } } } } }";
+        let shape = analyze(document, &json!({ "n": 10 })).unwrap();
+        assert_eq!(shape.depth, 6);
+        assert_eq!(shape.nodes, 50 + 500);
+    }
+
+    #[test]
+    fn expands_fragment_spreads() {
+        let inline = shape("{ viewer { repositories(first: 100) { nodes { issues(first: 100) { nodes { title } } } } } }");
+        let spread = shape(
+            "{ viewer { ...Repos } }
+             fragment Repos on User { repositories(first: 100) { nodes { ...Issues } } }
+             fragment Issues on Repository { issues(first: 100) { nodes { title } } }",
+        );
+        assert_eq!(spread.depth, inline.depth);
+        assert_eq!(spread.nodes, inline.nodes);
+    }
+
+    #[test]
+    fn measures_inline_fragments_inside_named_ones() {
+        let shape = shape(
+            "{ node(id: \"x\") { ...Item } }
+             fragment Item on Node { ... on Repository { issues(first: 100) { nodes { comments(first: 100) { nodes { body } } } } } }",
+        );
+        assert_eq!(shape.nodes, 100 + 10_000);
+        assert_eq!(shape.depth, 7);
+    }
+
+    #[test]
+    fn fragments_are_not_operations() {
+        assert_eq!(shape("fragment F on User { login } query { viewer { ...F } }").operation, OperationKind::Query);
+        assert!(analyze("fragment F on User { login }", &Value::Null).is_err());
+    }
+
+    #[test]
+    fn rejects_fragment_cycles() {
+        let cycle = "{ viewer { ...A } } fragment A on User { ...B } fragment B on User { ...A }";
+        assert!(analyze(cycle, &Value::Null).unwrap_err().contains("spreads itself"));
+        let own = "{ viewer { ...A } } fragment A on User { login ...A }";
+        assert!(analyze(own, &Value::Null).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_and_duplicate_fragments() {
+        assert!(analyze("{ viewer { ...Missing } }", &Value::Null).is_err());
+        assert!(analyze("{ viewer { ...A } } fragment A on User { login } fragment A on User { id }", &Value::Null).is_err());
+    }
+
+    #[test]
+    fn caps_fragment_expansion() {
+        // Each level spreads the next twice: 2^12 expansions if left unchecked
+        let mut document = "{ viewer { ...F0 } }".to_string();
+        for level in 0..12 {
+            document.push_str(&format!(" fragment F{} on User {{ ...F{} ...F{} }}", level, level + 1, level + 1));
+        }
+        document.push_str(" fragment F12 on User { login }");
+        assert!(analyze(&document, &Value::Null).unwrap_err().contains("fragment spreads"));
+    }
+}
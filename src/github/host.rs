@@ -0,0 +1,78 @@
+// Where a GitHub instance serves its web pages, APIs and OAuth endpoints.
+//
+// github.com puts the REST and GraphQL APIs on api.github.com. GitHub
+// Enterprise Server serves everything from its own host, with the APIs under
+// `/api/v3` and `/api/graphql`.
+
+use serde::{Deserialize, Serialize};
+
+const GITHUB_COM: &str = "https://github.com";
+const GITHUB_COM_API: &str = "https://api.github.com";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitHubHost {
+    /// e.g. `https://github.com` or `https://ghes.example.com`
+    pub web_url: String,
+    /// REST API root, e.g. `https://api.github.com` or `https://ghes.example.com/api/v3`
+    pub api_url: String,
+}
+
+impl Default for GitHubHost {
+    fn default() -> Self {
+        Self {
+            web_url: GITHUB_COM.to_string(),
+            api_url: GITHUB_COM_API.to_string(),
+        }
+    }
+}
+
+impl GitHubHost {
+    /// From `GITHUB_HOST` (a hostname or URL) and `GITHUB_API_BASE_URL`,
+    /// either of which is derived from the other when unset
+    pub fn new(host: Option<&str>, api_url: Option<&str>) -> Self {
+        let host = host.map(|h| match h.contains("://") {
+            true => h.trim_end_matches('/').to_string(),
+            false => format!("https://{}", h.trim_end_matches('/')),
+        });
+        let api_url = api_url.map(|u| u.trim_end_matches('/').to_string());
+
+        match (host, api_url) {
+            (Some(web_url), Some(api_url)) => Self { web_url, api_url },
+            (Some(web_url), None) => {
+                let api_url = match web_url.as_str() {
+                    GITHUB_COM => GITHUB_COM_API.to_string(),
+                    _ => format!("{}/api/v3", web_url),
+                };
+                Self { web_url, api_url }
+            }
+            (None, Some(api_url)) => {
+                let web_url = match api_url.strip_suffix("/api/v3") {
+                    Some(enterprise) => enterprise.to_string(),
+                    None => api_url.replace("://api.", "://"),
+                };
+                Self { web_url, api_url }
+            }
+            (None, None) => Self::default(),
+        }
+    }
+
+    pub fn graphql_url(&self) -> String {
+        match self.api_url.strip_suffix("/api/v3") {
+            Some(enterprise) => format!("{}/api/graphql", enterprise),
+            None => format!("{}/graphql", self.api_url),
+        }
+    }
+
+    pub fn authorize_url(&self) -> String {
+        format!("{}/login/oauth/authorize", self.web_url)
+    }
+
+    pub fn token_url(&self) -> String {
+        format!("{}/login/oauth/access_token", self.web_url)
+    }
+
+    /// Web page at `path`, e.g. `owner/repo/issues/1`
+    pub fn web(&self, path: &str) -> String {
+        format!("{}/{}", self.web_url, path.trim_start_matches('/'))
+    }
+}
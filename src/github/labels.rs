@@ -0,0 +1,156 @@
+// Declarative label sets, and the changes that bring a repository in line.
+//
+// A set comes from the tool call, from `[labels]` in `.mcp-workflow.toml`,
+// or from a YAML file (`.github/labels.yml` by default) listing
+//
+//     - name: bug
+//       color: d73a4a
+//       description: Something isn't working
+//       aliases: [defect]
+//
+// A label whose name or alias matches an existing one (ignoring case) is
+// updated in place, so renaming keeps it on the issues that carry it.
+// With `prune`, labels outside the set are deleted.
+
+use serde::{Deserialize, Serialize};
+
+use super::api::GitHubLabel;
+use crate::error::{AppError, Result};
+
+/// Where the label set is read from unless configured otherwise
+pub const LABELS_FILE: &str = ".github/labels.yml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelSpec {
+    pub name: String,
+    /// Six hex digits, with or without `#`
+    pub color: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Earlier names, renamed to `name`
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LabelChange {
+    /// create, update or delete
+    pub action: &'static str,
+    pub name: String,
+    /// The existing label's name, when it differs from `name`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// What an update changes, e.g. `color d73a4a -> b60205`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<String>,
+}
+
+/// A color as the API wants it: six lowercase hex digits without `#`
+pub fn normalize_color(color: &str) -> Option<String> {
+    let color = color.trim().trim_start_matches('#').to_lowercase();
+    (color.len() == 6 && color.chars().all(|c| c.is_ascii_hexdigit())).then_some(color)
+}
+
+/// A YAML label set: a list of labels, or a mapping with a `labels` list
+pub fn parse_yaml(text: &str) -> Result<Vec<LabelSpec>> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum LabelFile {
+        List(Vec<LabelSpec>),
+        Wrapped { labels: Vec<LabelSpec> },
+    }
+
+    let file: LabelFile = serde_yaml::from_str(text)
+        .map_err(|e| AppError::Validation(format!("Invalid label file: {}", e)))?;
+    Ok(match file {
+        LabelFile::List(labels) | LabelFile::Wrapped { labels } => labels,
+    })
+}
+
+/// Check a set, normalizing colors; names and aliases must be unique
+pub fn validate(labels: &mut [LabelSpec]) -> Result<()> {
+    let mut seen: Vec<String> = Vec::new();
+    for label in labels.iter_mut() {
+        label.name = label.name.trim().to_string();
+        if label.name.is_empty() {
+            return Err(AppError::Validation("Label names cannot be empty".to_string()));
+        }
+        label.color = normalize_color(&label.color).ok_or_else(|| {
+            AppError::Validation(format!("Label {} has an invalid color '{}': use six hex digits", label.name, label.color))
+        })?;
+        for name in std::iter::once(&label.name).chain(&label.aliases) {
+            let key = name.to_lowercase();
+            if seen.contains(&key) {
+                return Err(AppError::Validation(format!("Label {} appears more than once in the set", name)));
+            }
+            seen.push(key);
+        }
+    }
+    Ok(())
+}
+
+/// What to create, update and delete so `existing` matches `wanted`
+pub fn plan(wanted: &[LabelSpec], existing: &[GitHubLabel], prune: bool) -> Vec<LabelChange> {
+    let mut changes = Vec::new();
+    let mut matched: Vec<&str> = Vec::new();
+
+    for label in wanted {
+        // The label itself first, then any alias
+        let current = existing
+            .iter()
+            .find(|e| e.name.eq_ignore_ascii_case(&label.name))
+            .or_else(|| existing.iter().find(|e| label.aliases.iter().any(|a| e.name.eq_ignore_ascii_case(a))));
+
+        let Some(current) = current else {
+            changes.push(LabelChange {
+                action: "create",
+                name: label.name.clone(),
+                from: None,
+                color: Some(label.color.clone()),
+                description: label.description.clone(),
+                changes: Vec::new(),
+            });
+            continue;
+        };
+        matched.push(&current.name);
+
+        let mut differences = Vec::new();
+        if current.name != label.name {
+            differences.push(format!("name {} -> {}", current.name, label.name));
+        }
+        if !current.color.eq_ignore_ascii_case(&label.color) {
+            differences.push(format!("color {} -> {}", current.color, label.color));
+        }
+        if label.description.is_some() && current.description != label.description {
+            differences.push("description".to_string());
+        }
+        if !differences.is_empty() {
+            changes.push(LabelChange {
+                action: "update",
+                name: label.name.clone(),
+                from: (current.name != label.name).then(|| current.name.clone()),
+                color: Some(label.color.clone()),
+                description: label.description.clone(),
+                changes: differences,
+            });
+        }
+    }
+
+    if prune {
+        for label in existing.iter().filter(|e| !matched.contains(&e.name.as_str())) {
+            changes.push(LabelChange {
+                action: "delete",
+                name: label.name.clone(),
+                from: None,
+                color: None,
+                description: None,
+                changes: Vec::new(),
+            });
+        }
+    }
+    changes
+}
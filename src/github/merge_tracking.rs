@@ -0,0 +1,199 @@
+// Merges left to GitHub: auto-merge and merge queues.
+//
+// `merge` with `mode: auto` or `queue` records the PR here. A `pull_request`
+// webhook closing it, or the poll every `MERGE_POLL_SECONDS` for servers
+// without webhooks, marks it finished and notifies whoever asked. A merge
+// that lands also completes the task linked to the branch; one taken out of
+// the queue, or whose auto-merge was turned off, is reported as cancelled.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::json;
+use tracing::{debug, info, warn};
+
+use crate::{AppState, error::Result, scheduler::Priority, webhooks::WebhookEvent};
+use super::api::get_github_client_for_login;
+
+/// Start tracking a PR; asking again for the same PR starts over
+pub async fn track(
+    db: &sqlx::SqlitePool,
+    repository: &str,
+    pull_request: u64,
+    branch: &str,
+    mode: &str,
+    requested_by: &str,
+) -> Result<()> {
+    let number = pull_request as i64;
+    sqlx::query!(
+        r#"
+        INSERT INTO pending_merges (repository, pull_request_number, branch, mode, requested_by)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT(repository, pull_request_number) DO UPDATE SET
+            branch = excluded.branch,
+            mode = excluded.mode,
+            requested_by = excluded.requested_by,
+            status = 'pending',
+            created_at = CURRENT_TIMESTAMP,
+            finished_at = NULL
+        "#,
+        repository,
+        number,
+        branch,
+        mode,
+        requested_by
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Mark a tracked PR finished and tell its requester. Does nothing for a PR
+/// that isn't being tracked, or was already reported.
+async fn finish(state: &AppState, repository: &str, pull_request: u64, status: &str, reason: Option<&str>) -> Result<()> {
+    let number = pull_request as i64;
+    let Some(pending) = sqlx::query!(
+        "SELECT branch, mode, requested_by FROM pending_merges WHERE repository = ? AND pull_request_number = ? AND status = 'pending'",
+        repository,
+        number
+    )
+    .fetch_optional(&state.db)
+    .await?
+    else {
+        return Ok(());
+    };
+
+    let updated = sqlx::query!(
+        r#"
+        UPDATE pending_merges SET status = ?, finished_at = CURRENT_TIMESTAMP
+        WHERE repository = ? AND pull_request_number = ? AND status = 'pending'
+        "#,
+        status,
+        repository,
+        number
+    )
+    .execute(&state.db)
+    .await?
+    .rows_affected();
+    // The webhook and the poll can both see the same merge
+    if updated == 0 {
+        return Ok(());
+    }
+
+    let mut task = None;
+    if status == "merged" {
+        match get_github_client_for_login(state, &pending.requested_by).await {
+            Ok(client) => {
+                let complete = super::workflows::complete_linked_task(state, &client, repository, &pending.branch, None, Some(pull_request));
+                match super::allowlist::scope_to(repository, complete).await {
+                    Ok(completed) => task = completed,
+                    Err(e) => warn!("Failed to complete the task linked to {}: {}", pending.branch, e),
+                }
+            }
+            Err(e) => debug!("Not completing the task for {}: {}", pending.branch, e),
+        }
+    }
+
+    let text = match status {
+        "merged" => format!("🎉 {}#{} has merged", repository, pull_request),
+        "closed" => format!("❌ {}#{} was closed without merging", repository, pull_request),
+        _ => format!(
+            "⚠️ {}#{} won't merge on its own any more: {}",
+            repository,
+            pull_request,
+            reason.unwrap_or("the deferred merge was cancelled")
+        ),
+    };
+    info!("{}", text);
+    state
+        .notifier
+        .notify(
+            Some(&pending.requested_by),
+            "deferred_merge",
+            &text,
+            json!({
+                "repository": repository,
+                "pull_request": pull_request,
+                "branch": pending.branch,
+                "mode": pending.mode,
+                "status": status,
+                "reason": reason,
+                "task": task
+            }),
+        )
+        .await
+}
+
+/// Webhook handler for `pull_request` deliveries about tracked PRs
+pub async fn on_webhook(state: AppState, event: Arc<WebhookEvent>) -> Result<()> {
+    let WebhookEvent::PullRequest(event) = event.as_ref() else {
+        return Ok(());
+    };
+    let repository = &event.repository.full_name;
+
+    match event.action.as_str() {
+        "closed" if event.pull_request.merged_at.is_some() => finish(&state, repository, event.number, "merged", None).await,
+        "closed" => finish(&state, repository, event.number, "closed", None).await,
+        "dequeued" => finish(&state, repository, event.number, "cancelled", Some("it was removed from the merge queue")).await,
+        "auto_merge_disabled" => finish(&state, repository, event.number, "cancelled", Some("auto-merge was disabled")).await,
+        _ => Ok(()),
+    }
+}
+
+/// Check every tracked PR once, for servers that don't receive webhooks
+async fn poll(state: &AppState) -> Result<()> {
+    let pending = sqlx::query!(
+        "SELECT repository, pull_request_number, requested_by FROM pending_merges WHERE status = 'pending'"
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    for merge in pending {
+        let Some((owner, name)) = merge.repository.split_once('/') else {
+            continue;
+        };
+        let client = match get_github_client_for_login(state, &merge.requested_by).await {
+            Ok(client) => client,
+            Err(e) => {
+                debug!("Can't check {}#{}: {}", merge.repository, merge.pull_request_number, e);
+                continue;
+            }
+        };
+
+        let number = merge.pull_request_number as u64;
+        // One PR that can't be read or reported leaves the others to check
+        let pr = match client.get_pull_request(owner, name, number).await {
+            Ok(pr) => pr,
+            Err(e) => {
+                warn!("Can't check {}#{}: {}", merge.repository, number, e);
+                continue;
+            }
+        };
+        let finished = match (pr.merged_at.is_some(), pr.state.as_str()) {
+            (true, _) => finish(state, &merge.repository, number, "merged", None).await,
+            (false, "closed") => finish(state, &merge.repository, number, "closed", None).await,
+            _ => Ok(()),
+        };
+        if let Err(e) = finished {
+            warn!("Failed to finish {}#{}: {}", merge.repository, number, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Periodically check deferred merges; disabled when the interval is zero
+pub fn spawn_polling(state: AppState) {
+    let interval = state.config.workflow.merge_poll_seconds;
+    if interval == 0 {
+        info!("Deferred merge polling disabled; relying on pull_request webhooks");
+        return;
+    }
+
+    let scheduler = state.scheduler.clone();
+    scheduler.spawn("deferred_merge_polling", Priority::Low, Duration::from_secs(interval), move || {
+        let state = state.clone();
+        async move { poll(&state).await }
+    });
+}
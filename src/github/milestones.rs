@@ -0,0 +1,71 @@
+// How far along a milestone is, and whether it will make its due date.
+//
+// Progress counts the milestone's issues and pull requests as GitHub does.
+// The projection assumes work keeps closing at the rate it has since the
+// milestone was created; `slip_days` is how far past the due date that
+// lands, or how overdue an unfinished milestone already is.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::{json, Value};
+
+use super::api::GitHubMilestone;
+
+/// A `YYYY-MM-DD` date or a full timestamp, as the API wants `due_on`
+pub fn due_on(value: &str) -> Option<String> {
+    let value = value.trim();
+    if NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok() {
+        return Some(format!("{}T00:00:00Z", value));
+    }
+    DateTime::parse_from_rfc3339(value).ok().map(|_| value.to_string())
+}
+
+pub fn progress(milestone: &GitHubMilestone, now: DateTime<Utc>) -> Value {
+    let open = milestone.open_issues;
+    let closed = milestone.closed_issues;
+    let total = open + closed;
+    let percent = if total == 0 { 0.0 } else { (closed as f64 * 1000.0 / total as f64).round() / 10.0 };
+
+    let parse = |at: &Option<String>| {
+        at.as_deref()
+            .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+            .map(|at| at.with_timezone(&Utc))
+    };
+    let due = parse(&milestone.due_on);
+    let created = parse(&milestone.created_at);
+
+    // Closed items per day since the milestone was created
+    let rate = created
+        .map(|created| (now - created).num_hours() as f64 / 24.0)
+        .filter(|days| *days >= 1.0)
+        .map(|days| closed as f64 / days);
+    let projected = match rate {
+        _ if open == 0 => None,
+        Some(rate) if rate > 0.0 => Some(now + chrono::Duration::hours((open as f64 / rate * 24.0).ceil() as i64)),
+        _ => None,
+    };
+
+    let (schedule, slip_days) = match due {
+        _ if open == 0 => ("done", None),
+        None => ("no due date", None),
+        Some(due) if due < now => ("overdue", Some((now - due).num_days().max(1))),
+        Some(due) => match projected {
+            Some(projected) if projected > due => ("at risk", Some((projected - due).num_days().max(1))),
+            Some(_) => ("on track", None),
+            // Nothing closed yet, so there's no rate to project from
+            None => ("not started", None),
+        },
+    };
+
+    json!({
+        "open": open,
+        "closed": closed,
+        "total": total,
+        "percent_complete": percent,
+        "due_on": milestone.due_on,
+        "days_left": due.filter(|_| open > 0).map(|due| (due - now).num_days()),
+        "closed_per_day": rate.map(|r| (r * 100.0).round() / 100.0),
+        "projected_completion": projected.map(|p| p.date_naive().to_string()),
+        "schedule": schedule,
+        "slip_days": slip_days
+    })
+}
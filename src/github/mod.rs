@@ -1,31 +1,78 @@
+pub mod actions;
+pub mod alerts;
+pub mod allowlist;
 pub mod api;
+pub mod board;
+pub mod bootstrap;
+pub mod budget;
+pub mod cache;
+pub mod checklist;
+pub mod codeowners;
+pub mod commit_message;
+pub mod dependabot;
+pub mod discussions;
+pub mod environment;
+pub mod error;
+pub mod gists;
+pub mod graphql;
+pub mod host;
+pub mod labels;
+pub mod merge_tracking;
+pub mod milestones;
+pub mod notifications;
+pub mod policy;
+pub mod protection;
+pub mod pull_request;
+pub mod release;
+pub mod reminders;
+pub mod search;
+pub mod sync;
+pub mod repo_config;
+pub mod rest;
+pub mod retry;
+pub mod tasks;
+pub mod timeline;
+pub mod triage;
+pub mod wiki;
+pub mod work_folder;
 pub mod workflows;
 
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     Json,
 };
-use serde_json::Value;
+use serde_json::{json, Value};
+use tracing::{warn, Instrument};
 
-use crate::{AppState, error::Result, mcp::protocol::GitHubCommand};
+use crate::{AppState, error::{AppError, Result}, mcp::protocol::{CommandTarget, GitHubCommand}};
 
 pub async fn handle_push(State(state): State<AppState>) -> Result<Json<Value>> {
     let command = GitHubCommand::Push {
         branch: None,
         message: None,
         ready_for_review: None,
+        draft_pr: None,
+        request_reviewers: None,
+        repo: None,
     };
-    let result = execute_workflow_command(state, command).await?;
+    let result = execute_as_tool(state, "github_push", json!({}), command).await?;
     Ok(Json(result))
 }
 
 pub async fn handle_scan_tasks(State(state): State<AppState>) -> Result<Json<Value>> {
     let command = GitHubCommand::ScanTasks {
         project_number: None,
+        owner: None,
         filter_type: None,
         status: None,
+        assignee: None,
+        label: None,
+        projects: None,
+        all_projects: None,
+        filter: None,
+        repo: None,
     };
-    let result = execute_workflow_command(state, command).await?;
+    let result = execute_as_tool(state, "github_scan_tasks", json!({}), command).await?;
     Ok(Json(result))
 }
 
@@ -34,13 +81,71 @@ pub async fn handle_merge(State(state): State<AppState>) -> Result<Json<Value>>
         branch: None,
         delete_branch: Some(true),
         cleanup_work_folder: None,
+        ignore_checks: None,
+        mode: None,
+        repo: None,
     };
-    let result = execute_workflow_command(state, command).await?;
+    let result = execute_as_tool(state, "github_merge", json!({ "delete_branch": true }), command).await?;
     Ok(Json(result))
 }
 
+/// Run a REST route's command as a call to `tool` with `arguments` would be:
+/// refused while the tool is disabled, and held for approval under
+/// CONFIRM_TOOLS, after which the call is what runs
+async fn execute_as_tool(state: AppState, tool: &str, arguments: Value, command: GitHubCommand) -> Result<Value> {
+    if state.tools.is_disabled(tool) {
+        return Err(AppError::Authorization(format!("Tool {} is disabled by an administrator", tool)));
+    }
+    if command.is_mutating() && crate::operations::requires_confirmation(&state, tool) {
+        return crate::operations::hold(&state, tool, &arguments).await;
+    }
+    execute_workflow_command(state, command).await
+}
+
+#[derive(serde::Deserialize)]
+pub struct BoardQuery {
+    owner: Option<String>,
+}
+
+pub async fn handle_project_board(
+    State(state): State<AppState>,
+    Path(number): Path<String>,
+    Query(query): Query<BoardQuery>,
+) -> Result<Json<Value>> {
+    let board = get_project_board(state, number, query.owner).await?;
+    Ok(Json(board))
+}
+
 pub async fn execute_workflow_command(state: AppState, command: GitHubCommand) -> Result<Value> {
-    workflows::execute_command(state, command).await
+    if state.config.security.read_only && command.is_mutating() {
+        return Err(AppError::ReadOnly(command.name().to_string()));
+    }
+    // Background jobs hold off while tool calls are using the rate limit
+    let _interactive = state.scheduler.interactive();
+
+    let name = command.name();
+    let repo = match command.target() {
+        CommandTarget::Repository(repository) => repository.to_string(),
+        CommandTarget::Checkout => "checkout".to_string(),
+        CommandTarget::Unscoped => "*".to_string(),
+    };
+    let span = tracing::info_span!("workflow", command = name, repo = %repo);
+
+    let started = std::time::Instant::now();
+    let result = workflows::execute_command(state.clone(), command).instrument(span).await;
+    let duration_ms = started.elapsed().as_millis() as i64;
+
+    // Workflows report some failures as an error status rather than Err
+    let success = match &result {
+        Ok(value) => value.get("status").and_then(|s| s.as_str()) != Some("error"),
+        Err(_) => false,
+    };
+    state.metrics.record_tool_call(name, success);
+    if let Err(e) = crate::history::record(&state.db, name, success, duration_ms).await {
+        warn!("Failed to record {} in command history: {}", name, e);
+    }
+
+    result
 }
 
 pub async fn get_workflow_status(state: AppState) -> Result<Value> {
@@ -49,4 +154,8 @@ pub async fn get_workflow_status(state: AppState) -> Result<Value> {
 
 pub async fn get_project_tasks(state: AppState) -> Result<Value> {
     workflows::get_tasks(state).await
+}
+
+pub async fn get_project_board(state: AppState, project_number: String, owner: Option<String>) -> Result<Value> {
+    execute_workflow_command(state, GitHubCommand::GetProjectBoard { project_number, owner }).await
 }
\ No newline at end of file
@@ -0,0 +1,85 @@
+// The authenticated user's notification inbox.
+//
+// By default only what usually needs acting on is listed: review requests,
+// mentions (of the user or one of their teams) and CI activity, which GitHub
+// sends for failed workflow runs. Subjects link to the API; `item` turns
+// that into the page a person would open.
+
+use serde_json::{json, Value};
+
+use super::api::GitHubNotification;
+use super::host::GitHubHost;
+
+/// Notifications listed per call unless the caller asks for fewer
+pub const DEFAULT_LIMIT: usize = 50;
+pub const MAX_LIMIT: usize = 200;
+
+/// Unread threads marked one at a time when the allowlist rules out marking
+/// the whole inbox at once
+pub const MARK_ALL_LIMIT: usize = 1000;
+
+/// Reasons listed when the caller doesn't pass any
+pub const ACTIONABLE_REASONS: &[&str] = &["review_requested", "mention", "team_mention", "ci_activity"];
+
+/// What a notification asks of the user, grouping GitHub's reasons
+pub fn category(reason: &str) -> &'static str {
+    match reason {
+        "review_requested" => "review request",
+        "mention" | "team_mention" => "mention",
+        "ci_activity" => "ci",
+        "assign" => "assigned",
+        "security_alert" => "security",
+        _ => "activity",
+    }
+}
+
+/// Whether `reason` is one of `wanted`; `mention` also covers `team_mention`
+pub fn wanted(reason: &str, wanted: &[String]) -> bool {
+    wanted.is_empty()
+        || wanted
+            .iter()
+            .any(|w| w == reason || (w == "mention" && reason == "team_mention"))
+}
+
+/// The web page for an API subject URL such as
+/// `https://api.github.com/repos/o/r/pulls/5`. Releases are addressed by an
+/// ID no page uses, so they link to the release list.
+pub fn html_url(host: &GitHubHost, api_url: &str) -> Option<String> {
+    let (_, path) = api_url.split_once("/repos/")?;
+    let mut segments = path.splitn(4, '/');
+    let (owner, repo, kind, rest) = (segments.next()?, segments.next()?, segments.next()?, segments.next()?);
+    let page = match kind {
+        "pulls" => format!("pull/{}", rest),
+        "issues" => format!("issues/{}", rest),
+        "commits" => format!("commit/{}", rest),
+        "releases" => "releases".to_string(),
+        _ => return None,
+    };
+    Some(host.web(&format!("{}/{}/{}", owner, repo, page)))
+}
+
+pub fn item(host: &GitHubHost, notification: &GitHubNotification) -> Value {
+    let subject = &notification.subject;
+    let number = subject
+        .url
+        .as_deref()
+        .filter(|_| matches!(subject.subject_type.as_str(), "Issue" | "PullRequest"))
+        .and_then(|url| url.rsplit('/').next())
+        .and_then(|n| n.parse::<u64>().ok());
+    json!({
+        "thread_id": notification.id,
+        "repository": notification.repository.full_name,
+        "reason": notification.reason,
+        "category": category(&notification.reason),
+        "type": subject.subject_type,
+        "title": subject.title,
+        "number": number,
+        "url": subject
+            .url
+            .as_deref()
+            .and_then(|url| html_url(host, url))
+            .unwrap_or_else(|| host.web(&notification.repository.full_name)),
+        "unread": notification.unread,
+        "updated_at": notification.updated_at
+    })
+}
@@ -0,0 +1,49 @@
+use serde::Serialize;
+
+use super::api::GitHubRepository;
+
+/// Result of checking one repository against the org's repository policy
+#[derive(Debug, Serialize)]
+pub struct PolicyReport {
+    pub repository: String,
+    pub compliant: bool,
+    pub missing_topics: Vec<String>,
+    pub missing_description: bool,
+}
+
+/// Required topic rules that `topics` doesn't satisfy. A rule ending in `*`
+/// (e.g. `team-*`) is satisfied by any topic with that prefix.
+pub fn missing_topics(topics: &[String], required: &[String]) -> Vec<String> {
+    required
+        .iter()
+        .filter(|rule| {
+            let satisfied = match rule.strip_suffix('*') {
+                Some(prefix) => topics.iter().any(|t| t.starts_with(prefix) && t.len() > prefix.len()),
+                None => topics.iter().any(|t| t == *rule),
+            };
+            !satisfied
+        })
+        .cloned()
+        .collect()
+}
+
+pub fn check_repository(repository: &GitHubRepository, required_topics: &[String], require_description: bool) -> PolicyReport {
+    let missing_topics = missing_topics(&repository.topics, required_topics);
+    let missing_description = require_description
+        && repository.description.as_deref().map_or(true, |d| d.trim().is_empty());
+
+    PolicyReport {
+        repository: repository.full_name.clone(),
+        compliant: missing_topics.is_empty() && !missing_description,
+        missing_topics,
+        missing_description,
+    }
+}
+
+/// GitHub topics are lowercase letters, digits and hyphens, at most 50 characters
+pub fn is_valid_topic(topic: &str) -> bool {
+    !topic.is_empty()
+        && topic.len() <= 50
+        && !topic.starts_with('-')
+        && topic.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use super::api::{GitHubBranchProtection, GitHubReview, ProtectionFlag};
+
+/// Protection settings to change; unset fields keep their current value
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProtectionUpdate {
+    /// 0 removes the pull request review requirement
+    pub required_approving_review_count: Option<u64>,
+    pub require_code_owner_reviews: Option<bool>,
+    pub dismiss_stale_reviews: Option<bool>,
+    /// An empty list removes the status check requirement
+    pub required_status_checks: Option<Vec<String>>,
+    pub strict_status_checks: Option<bool>,
+    pub enforce_admins: Option<bool>,
+    pub required_linear_history: Option<bool>,
+    pub allow_force_pushes: Option<bool>,
+    pub allow_deletions: Option<bool>,
+    pub required_conversation_resolution: Option<bool>,
+}
+
+impl ProtectionUpdate {
+    pub fn is_empty(&self) -> bool {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|v| v.as_object().map(|o| o.values().all(Value::is_null)))
+            .unwrap_or(true)
+    }
+}
+
+fn enabled(flag: &Option<ProtectionFlag>) -> bool {
+    flag.as_ref().map_or(false, |f| f.enabled)
+}
+
+/// Who a push would come from, as far as branch protection is concerned
+#[derive(Debug, Clone, Default)]
+pub struct Pusher {
+    pub login: String,
+    /// Has admin access to the repository
+    pub admin: bool,
+    /// Slugs of the teams allowed to push (see `restricted_teams`) the pusher is in
+    pub teams: Vec<String>,
+}
+
+/// Flattened view of the rules, for tool output
+pub fn summary(protection: &GitHubBranchProtection) -> Value {
+    let reviews = protection.required_pull_request_reviews.as_ref();
+    let checks = protection.required_status_checks.as_ref();
+    json!({
+        "required_approving_review_count": reviews.map(|r| r.required_approving_review_count),
+        "require_code_owner_reviews": reviews.map_or(false, |r| r.require_code_owner_reviews),
+        "dismiss_stale_reviews": reviews.map_or(false, |r| r.dismiss_stale_reviews),
+        "required_status_checks": checks.map(|c| c.contexts.clone()),
+        "strict_status_checks": checks.map_or(false, |c| c.strict),
+        "enforce_admins": enabled(&protection.enforce_admins),
+        "required_linear_history": enabled(&protection.required_linear_history),
+        "allow_force_pushes": enabled(&protection.allow_force_pushes),
+        "allow_deletions": enabled(&protection.allow_deletions),
+        "required_conversation_resolution": enabled(&protection.required_conversation_resolution),
+        "lock_branch": enabled(&protection.lock_branch),
+        "push_restricted": protection.restrictions.is_some()
+    })
+}
+
+/// Why a direct push to the branch by `pusher` would be rejected; empty when
+/// it wouldn't
+pub fn push_blockers(protection: &GitHubBranchProtection, pusher: &Pusher) -> Vec<String> {
+    // Without `enforce_admins` the rules don't apply to repository admins
+    if pusher.admin && !enabled(&protection.enforce_admins) {
+        return Vec::new();
+    }
+
+    let mut blockers = Vec::new();
+    if enabled(&protection.lock_branch) {
+        blockers.push("the branch is locked and read-only".to_string());
+    }
+    if let Some(reviews) = &protection.required_pull_request_reviews {
+        blockers.push(format!(
+            "changes must go through a pull request with {} approving review(s)",
+            reviews.required_approving_review_count
+        ));
+    }
+    if let Some(checks) = protection.required_status_checks.as_ref().filter(|c| !c.contexts.is_empty()) {
+        blockers.push(format!("commits must pass required status checks: {}", checks.contexts.join(", ")));
+    }
+    if let Some(restrictions) = &protection.restrictions {
+        let listed = restrictions["users"]
+            .as_array()
+            .map_or(false, |users| users.iter().any(|u| u["login"].as_str().map_or(false, |l| l.eq_ignore_ascii_case(&pusher.login))));
+        if !listed && pusher.teams.is_empty() {
+            blockers.push(format!("only the listed users, teams and apps may push, and {} isn't one of them", pusher.login));
+        }
+    }
+    blockers
+}
+
+/// Slugs of the teams allowed to push, when pushes are restricted
+pub fn restricted_teams(protection: &GitHubBranchProtection) -> Vec<String> {
+    protection
+        .restrictions
+        .as_ref()
+        .and_then(|restrictions| restrictions["teams"].as_array())
+        .map(|teams| teams.iter().filter_map(|t| t["slug"].as_str()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// Reviewers whose latest approving or blocking review is an approval
+pub fn approvals(reviews: &[GitHubReview]) -> usize {
+    let mut latest: HashMap<&str, &str> = HashMap::new();
+    for review in reviews {
+        // Comments don't change a reviewer's verdict
+        if let (Some(user), "APPROVED" | "CHANGES_REQUESTED" | "DISMISSED") = (&review.user, review.state.as_str()) {
+            latest.insert(user.login.as_str(), review.state.as_str());
+        }
+    }
+    latest.values().filter(|state| **state == "APPROVED").count()
+}
+
+/// Full PUT body applying `update` over `current`, preserving every rule
+/// the update doesn't mention, including those it has no field for
+pub fn put_body(current: Option<&GitHubBranchProtection>, update: &ProtectionUpdate) -> Value {
+    let default = GitHubBranchProtection::default();
+    let current = current.unwrap_or(&default);
+
+    let current_checks = current.required_status_checks.as_ref();
+    let contexts = update
+        .required_status_checks
+        .clone()
+        .or_else(|| current_checks.map(|c| c.contexts.clone()));
+    let strict = update
+        .strict_status_checks
+        .or_else(|| current_checks.map(|c| c.strict))
+        .unwrap_or(false);
+    let required_status_checks = match (contexts, strict) {
+        // An explicit empty list clears the requirement
+        _ if update.required_status_checks.as_ref().map_or(false, Vec::is_empty) => Value::Null,
+        (None, false) => Value::Null,
+        (contexts, strict) => match current_checks.and_then(|c| c.other.get("checks")) {
+            // Unchanged checks stay pinned to the apps that must report them
+            Some(checks) if update.required_status_checks.is_none() => json!({ "strict": strict, "checks": checks }),
+            _ => json!({ "strict": strict, "contexts": contexts.unwrap_or_default() }),
+        },
+    };
+
+    let current_reviews = current.required_pull_request_reviews.as_ref();
+    let review_count = update
+        .required_approving_review_count
+        .or_else(|| current_reviews.map(|r| r.required_approving_review_count));
+    let reviews_touched = update.require_code_owner_reviews.is_some() || update.dismiss_stale_reviews.is_some();
+    let required_pull_request_reviews = match review_count {
+        Some(0) => Value::Null,
+        None if !reviews_touched => Value::Null,
+        count => {
+            let mut reviews = json!({
+                "required_approving_review_count": count.unwrap_or(1),
+                "dismiss_stale_reviews": update
+                    .dismiss_stale_reviews
+                    .unwrap_or_else(|| current_reviews.map_or(false, |r| r.dismiss_stale_reviews)),
+                "require_code_owner_reviews": update
+                    .require_code_owner_reviews
+                    .unwrap_or_else(|| current_reviews.map_or(false, |r| r.require_code_owner_reviews))
+            });
+            if let Some(other) = current_reviews.map(|r| &r.other) {
+                if let Some(last_push) = other.get("require_last_push_approval") {
+                    reviews["require_last_push_approval"] = last_push.clone();
+                }
+                for key in ["dismissal_restrictions", "bypass_pull_request_allowances"] {
+                    if let Some(allowed) = other.get(key) {
+                        reviews[key] = restriction_names(allowed);
+                    }
+                }
+            }
+            reviews
+        }
+    };
+
+    let mut body = json!({
+        "required_status_checks": required_status_checks,
+        "enforce_admins": update.enforce_admins.unwrap_or_else(|| enabled(&current.enforce_admins)),
+        "required_pull_request_reviews": required_pull_request_reviews,
+        "restrictions": current.restrictions.as_ref().map(restriction_names),
+        "required_linear_history": update
+            .required_linear_history
+            .unwrap_or_else(|| enabled(&current.required_linear_history)),
+        "allow_force_pushes": update.allow_force_pushes.unwrap_or_else(|| enabled(&current.allow_force_pushes)),
+        "allow_deletions": update.allow_deletions.unwrap_or_else(|| enabled(&current.allow_deletions)),
+        "required_conversation_resolution": update
+            .required_conversation_resolution
+            .unwrap_or_else(|| enabled(&current.required_conversation_resolution)),
+        "lock_branch": enabled(&current.lock_branch)
+    });
+    for key in ["block_creations", "allow_fork_syncing"] {
+        if let Some(flag) = current.other.get(key).and_then(|flag| flag["enabled"].as_bool()) {
+            body[key] = json!(flag);
+        }
+    }
+    body
+}
+
+/// GET returns restriction objects; PUT takes logins and slugs
+fn restriction_names(restrictions: &Value) -> Value {
+    let names = |key: &str, field: &str| -> Vec<String> {
+        restrictions[key]
+            .as_array()
+            .map(|items| items.iter().filter_map(|i| i[field].as_str()).map(String::from).collect())
+            .unwrap_or_default()
+    };
+    json!({
+        "users": names("users", "login"),
+        "teams": names("teams", "slug"),
+        "apps": names("apps", "slug")
+    })
+}
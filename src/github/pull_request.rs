@@ -0,0 +1,138 @@
+// The body, labels and reviewers of the pull requests the workflows open.
+//
+// The body starts from the repository's pull request template when it has
+// one, with the summary written under its first summary-like heading, and
+// ends with `Closes #N` for the task the branch was started for. Labels and
+// reviewers come from the `[[pull_request.rules]]` in `.mcp-workflow.toml`
+// whose branch prefixes and changed paths match.
+
+use serde::Serialize;
+use std::path::Path;
+
+use super::repo_config::PullRequestRule;
+
+/// Where GitHub looks for a pull request template, in its order
+pub const TEMPLATE_PATHS: &[&str] = &[
+    ".github/PULL_REQUEST_TEMPLATE.md",
+    ".github/pull_request_template.md",
+    "PULL_REQUEST_TEMPLATE.md",
+    "pull_request_template.md",
+    "docs/PULL_REQUEST_TEMPLATE.md",
+    "docs/pull_request_template.md",
+];
+
+/// Headings the summary is written under, matched case-insensitively
+const SUMMARY_HEADINGS: &[&str] = &["summary", "description", "what", "changes"];
+
+/// What a workflow needs to open a pull request
+pub struct NewPullRequest<'a> {
+    pub branch: &'a str,
+    pub base: &'a str,
+    pub title: &'a str,
+    /// Written into the template; may be empty
+    pub summary: &'a str,
+    /// Issue the PR closes
+    pub issue_number: Option<u64>,
+    pub draft: bool,
+}
+
+/// Labels and reviewers the matching rules ask for
+#[derive(Debug, Default, Serialize)]
+pub struct RuleActions {
+    pub labels: Vec<String>,
+    pub reviewers: Vec<String>,
+    pub team_reviewers: Vec<String>,
+}
+
+/// The repository's pull request template, if it has one
+pub fn load_template(root: &Path) -> Option<String> {
+    TEMPLATE_PATHS
+        .iter()
+        .find_map(|path| std::fs::read_to_string(root.join(path)).ok())
+        .filter(|template| !template.trim().is_empty())
+}
+
+/// The PR body: the template (or a plain summary section) with the summary
+/// filled in, and a `Closes #N` trailer unless the body already links the issue
+pub fn body(template: Option<&str>, summary: &str, issue_number: Option<u64>) -> String {
+    let summary = summary.trim();
+    let mut body = match template {
+        Some(template) => fill_template(template, summary),
+        None if summary.is_empty() => "## Summary\n\n_Describe the change._".to_string(),
+        None => format!("## Summary\n\n{}", summary),
+    };
+
+    if let Some(number) = issue_number {
+        if !links_issue(&body, number) {
+            body = format!("{}\n\nCloses #{}", body.trim_end(), number);
+        }
+    }
+    body
+}
+
+fn fill_template(template: &str, summary: &str) -> String {
+    if summary.is_empty() {
+        return template.to_string();
+    }
+
+    let heading = template.lines().position(|line| {
+        let line = line.trim_start();
+        line.starts_with('#') && {
+            let title = line.trim_start_matches('#').trim().to_lowercase();
+            SUMMARY_HEADINGS.iter().any(|h| title.starts_with(h))
+        }
+    });
+
+    match heading {
+        Some(index) => {
+            let mut lines: Vec<&str> = template.lines().collect();
+            lines.splice(index + 1..index + 1, ["", summary]);
+            lines.join("\n")
+        }
+        None => format!("{}\n\n{}", summary, template),
+    }
+}
+
+/// Whether `body` already closes `number` with one of GitHub's keywords
+fn links_issue(body: &str, number: u64) -> bool {
+    let body = body.to_lowercase();
+    let reference = format!("#{}", number);
+    ["close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved"]
+        .iter()
+        .any(|keyword| {
+            let needle = format!("{} {}", keyword, reference);
+            body.match_indices(&needle).any(|(start, _)| {
+                let after = body[start + needle.len()..].chars().next();
+                !after.is_some_and(|c| c.is_ascii_digit())
+            })
+        })
+}
+
+/// Whether any rule looks at the changed paths, which costs an API call to list
+pub fn needs_paths(rules: &[PullRequestRule]) -> bool {
+    rules.iter().any(|rule| !rule.paths.is_empty())
+}
+
+/// Everything the rules matching `branch` and `paths` ask for, deduplicated
+pub fn matching(rules: &[PullRequestRule], branch: &str, paths: &[String]) -> RuleActions {
+    let mut actions = RuleActions::default();
+    let add = |into: &mut Vec<String>, from: &[String]| {
+        for value in from {
+            if !into.contains(value) {
+                into.push(value.clone());
+            }
+        }
+    };
+
+    for rule in rules {
+        let branch_matches = rule.branches.is_empty() || rule.branches.iter().any(|prefix| branch.starts_with(prefix.as_str()));
+        let paths_match = rule.paths.is_empty()
+            || paths.iter().any(|path| rule.paths.iter().any(|prefix| path.starts_with(prefix.as_str())));
+        if branch_matches && paths_match {
+            add(&mut actions.labels, &rule.labels);
+            add(&mut actions.reviewers, &rule.reviewers);
+            add(&mut actions.team_reviewers, &rule.team_reviewers);
+        }
+    }
+    actions
+}
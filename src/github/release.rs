@@ -0,0 +1,84 @@
+use super::api::GitHubPullRequest;
+
+/// Markdown release notes: an optional intro, one line per merged PR and a
+/// compare link against the previous tag
+pub fn release_notes(prs: &[GitHubPullRequest], intro: Option<&str>, compare_url: Option<&str>) -> String {
+    let mut notes = String::new();
+
+    if let Some(intro) = intro.map(str::trim).filter(|i| !i.is_empty()) {
+        notes.push_str(intro);
+        notes.push_str("\n\n");
+    }
+
+    notes.push_str("## What's Changed\n\n");
+    if prs.is_empty() {
+        notes.push_str("No pull requests merged since the previous release.\n");
+    }
+    for pr in prs {
+        notes.push_str(&format!("- {} by @{} in #{}\n", pr.title.trim(), pr.user.login, pr.number));
+    }
+
+    if let Some(url) = compare_url {
+        notes.push_str(&format!("\n**Full Changelog**: {}\n", url));
+    }
+
+    notes
+}
+
+/// Changelog sections in output order, with the labels and conventional
+/// commit prefixes that place a PR in each
+pub const CHANGELOG_SECTIONS: &[(&str, &[&str])] = &[
+    ("Features", &["feature", "enhancement", "feat"]),
+    ("Bug Fixes", &["bug", "fix", "bugfix"]),
+    ("Chores", &["chore", "dependencies", "ci", "build", "refactor", "docs", "documentation", "test"]),
+];
+
+/// Heading for PRs that match no section
+pub const OTHER_SECTION: &str = "Other Changes";
+
+/// Section for a PR, from its labels first and then a `feat:`/`fix(x):` style title prefix
+pub fn changelog_section(pr: &GitHubPullRequest) -> &'static str {
+    let labels: Vec<String> = pr
+        .labels
+        .iter()
+        .map(|l| l.name.to_lowercase().trim_start_matches("type:").trim_start_matches("type/").trim().to_string())
+        .collect();
+    for (section, keys) in CHANGELOG_SECTIONS {
+        if labels.iter().any(|l| keys.contains(&l.as_str())) {
+            return section;
+        }
+    }
+
+    let prefix = pr
+        .title
+        .split_once(':')
+        .map(|(p, _)| p.split('(').next().unwrap_or(p).trim_end_matches('!').trim().to_lowercase());
+    CHANGELOG_SECTIONS
+        .iter()
+        .find(|(_, keys)| prefix.as_deref().map_or(false, |p| keys.contains(&p)))
+        .map_or(OTHER_SECTION, |(section, _)| section)
+}
+
+/// Markdown changelog entry headed `heading`, PRs grouped by section
+pub fn changelog(heading: &str, prs: &[GitHubPullRequest]) -> String {
+    let mut markdown = format!("## {}\n", heading);
+
+    let sections = CHANGELOG_SECTIONS.iter().map(|(s, _)| *s).chain([OTHER_SECTION]);
+    for section in sections {
+        let entries: Vec<&GitHubPullRequest> = prs.iter().filter(|pr| changelog_section(pr) == section).collect();
+        if entries.is_empty() {
+            continue;
+        }
+
+        markdown.push_str(&format!("\n### {}\n\n", section));
+        for pr in entries {
+            markdown.push_str(&format!("- {} (#{}) @{}\n", pr.title.trim(), pr.number, pr.user.login));
+        }
+    }
+
+    if prs.is_empty() {
+        markdown.push_str("\nNo merged pull requests.\n");
+    }
+
+    markdown
+}
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde_json::json;
+use tracing::{debug, info, warn};
+
+use crate::{AppState, error::Result, scheduler::Priority};
+use super::api::get_github_client_for_login;
+use super::tasks;
+
+/// Periodically remind assignees about claimed tasks whose due date is
+/// within `REMINDER_DAYS`. Runs as a low-priority job, so it waits while the
+/// rate limit is needed for tool calls. Disabled when the interval is zero.
+pub fn spawn_reminder_scheduler(state: AppState) {
+    let interval_minutes = state.config.notifications.reminder_interval_minutes;
+    if interval_minutes == 0 {
+        info!("Task reminders disabled");
+        return;
+    }
+
+    let scheduler = state.scheduler.clone();
+    scheduler.spawn(
+        "task_reminders",
+        Priority::Low,
+        Duration::from_secs(interval_minutes * 60),
+        move || {
+            let state = state.clone();
+            async move { send_due_reminders(&state).await }
+        },
+    );
+}
+
+async fn send_due_reminders(state: &AppState) -> Result<()> {
+    let today = chrono::Utc::now().date_naive();
+    let today_str = today.to_string();
+
+    let links = sqlx::query!(
+        r#"
+        SELECT id as "id!", repository, issue_number, project_item_id,
+               project_owner as "project_owner!", project_number as "project_number!", assignee as "assignee!"
+        FROM task_links
+        WHERE status = 'in_progress'
+          AND project_owner IS NOT NULL AND project_number IS NOT NULL AND assignee IS NOT NULL
+          AND (last_reminded_on IS NULL OR last_reminded_on < ?)
+        "#,
+        today_str
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    // Fetch each project once per assignee, since items are read with their token
+    let mut groups: HashMap<(String, String, String), Vec<_>> = HashMap::new();
+    for link in links {
+        let key = (link.assignee.clone(), link.project_owner.clone(), link.project_number.clone());
+        groups.entry(key).or_default().push(link);
+    }
+
+    let mut sent = 0;
+    for ((assignee, project_owner, project_number), links) in groups {
+        let client = match get_github_client_for_login(state, &assignee).await {
+            Ok(client) => client,
+            Err(e) => {
+                debug!("Skipping reminders for {}: {}", assignee, e);
+                continue;
+            }
+        };
+        // A project or reminder that fails is logged and retried next run; the
+        // rest of the run goes ahead
+        let items = match client.get_project_items(&project_owner, &project_number).await {
+            Ok(items) => items,
+            Err(e) => {
+                warn!("Skipping reminders for {} in project {}/{}: {}", assignee, project_owner, project_number, e);
+                continue;
+            }
+        };
+
+        for link in links {
+            let issue_number = link.issue_number as u64;
+            let item = items.iter().find(|item| {
+                link.project_item_id.as_deref() == Some(item.id.as_str())
+                    || item.content.as_ref().map_or(false, |c| {
+                        c.number == Some(issue_number) && c.repository.as_deref() == Some(link.repository.as_str())
+                    })
+            });
+            let Some(due) = item.and_then(tasks::task_due_date) else {
+                continue;
+            };
+
+            let due_str = due.to_string();
+            if let Err(e) = sqlx::query!("UPDATE task_links SET due_date = ? WHERE id = ?", due_str, link.id)
+                .execute(&state.db)
+                .await
+            {
+                warn!("Failed to record the due date of {}#{}: {}", link.repository, issue_number, e);
+            }
+
+            let days_left = (due - today).num_days();
+            if days_left > state.config.notifications.reminder_days {
+                continue;
+            }
+
+            let when = match days_left {
+                d if d < 0 => format!("was due {} ({} days overdue)", due, -d),
+                0 => "is due today".to_string(),
+                1 => "is due tomorrow".to_string(),
+                d => format!("is due {} (in {} days)", due, d),
+            };
+            let title = item.and_then(|i| i.content.as_ref()).map(|c| c.title.as_str()).unwrap_or_default();
+            let text = format!("⏰ @{}: {}#{} \"{}\" {}", assignee, link.repository, issue_number, title, when);
+
+            let notified = state
+                .notifier
+                .notify(Some(&assignee), "task_due", &text, json!({
+                    "assignee": assignee,
+                    "repository": link.repository,
+                    "issue_number": issue_number,
+                    "url": state.config.github.host.web(&format!("{}/issues/{}", link.repository, issue_number)),
+                    "due_date": due_str,
+                    "overdue": days_left < 0
+                }))
+                .await;
+            if let Err(e) = notified {
+                warn!("Failed to remind {} about {}#{}: {}", assignee, link.repository, issue_number, e);
+                continue;
+            }
+
+            if let Err(e) = sqlx::query!("UPDATE task_links SET last_reminded_on = ? WHERE id = ?", today_str, link.id)
+                .execute(&state.db)
+                .await
+            {
+                warn!("Failed to record the reminder for {}#{}: {}", link.repository, issue_number, e);
+            }
+            sent += 1;
+        }
+    }
+
+    if sent > 0 {
+        info!("Sent {} task due-date reminders", sent);
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use serde::Deserialize;
+use std::path::Path;
+
+use crate::error::{AppError, Result};
+
+/// Per-repository workflow settings, read from `.mcp-workflow.toml` at the repo root
+pub const WORKFLOW_FILE: &str = ".mcp-workflow.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct WorkflowFile {
+    pub merge: MergeSettings,
+    pub work_folder: WorkFolderSettings,
+    pub promote: PromoteSettings,
+    pub dependabot: DependabotSettings,
+    pub secret_scan: SecretScanSettings,
+    pub sync: SyncSettings,
+    pub pull_request: PullRequestSettings,
+    pub labels: LabelSettings,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct MergeSettings {
+    /// Commands that must pass before a merge, e.g. `["cargo test", "npm test"]`
+    pub checks: Option<Vec<String>>,
+    pub timeout_seconds: Option<u64>,
+    /// Require every commit on the PR to have a verified signature; can't
+    /// waive `REQUIRE_VERIFIED_COMMITS`
+    pub require_verified_commits: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct WorkFolderSettings {
+    /// Per-branch scratch folder, e.g. `work/{branch}`
+    pub pattern: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PromoteSettings {
+    /// `workflow_dispatch` workflow that deploys a tag, e.g. `deploy.yml`
+    pub workflow: Option<String>,
+    /// Environments in promotion order, e.g. `["staging", "production"]`
+    pub environments: Option<Vec<String>>,
+    /// Ref the workflow runs on; defaults to the tag itself
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
+    /// Workflow inputs; `{tag}` and `{environment}` are substituted
+    pub inputs: Option<std::collections::BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DependabotSettings {
+    /// Largest bump approved and auto-merged: `none`, `patch` or `minor`
+    pub auto_merge: Option<String>,
+    /// merge, squash or rebase
+    pub merge_method: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SecretScanSettings {
+    /// Set to false to push this repository without scanning
+    pub enabled: Option<bool>,
+    /// Patterns checked alongside the built-in rules
+    pub rules: Vec<SecretScanRule>,
+    /// Built-in rules to skip, by name, e.g. `["slack_token"]`
+    pub disable: Vec<String>,
+    /// Path prefixes never scanned, e.g. `["tests/fixtures/"]`
+    pub allow_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SecretScanRule {
+    pub name: String,
+    /// A regular expression matched against each added line
+    pub pattern: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SyncSettings {
+    /// How feature branches catch up with main: `rebase` or `merge`
+    pub strategy: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PullRequestSettings {
+    /// Applied to every pull request the workflows open, in order
+    pub rules: Vec<PullRequestRule>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct PullRequestRule {
+    /// Branch name prefixes, e.g. `["fix/"]`; empty matches any branch
+    pub branches: Vec<String>,
+    /// Changed path prefixes, e.g. `["src/auth/"]`; empty matches any change
+    pub paths: Vec<String>,
+    pub labels: Vec<String>,
+    /// Users asked to review
+    pub reviewers: Vec<String>,
+    /// Team slugs asked to review
+    pub team_reviewers: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LabelSettings {
+    /// YAML label set in the repository; defaults to `.github/labels.yml`
+    pub file: Option<String>,
+    /// Labels declared here instead of in a file
+    pub set: Vec<super::labels::LabelSpec>,
+    /// Delete labels that aren't in the set
+    pub prune: Option<bool>,
+}
+
+/// Load the workflow file from `dir`; a missing file yields the defaults
+pub fn load_workflow_file(dir: &Path) -> Result<WorkflowFile> {
+    let path = dir.join(WORKFLOW_FILE);
+    if !path.exists() {
+        return Ok(WorkflowFile::default());
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::Internal(format!("Failed to read {}: {}", WORKFLOW_FILE, e)))?;
+    parse_workflow_file(&contents)
+}
+
+/// Parse the workflow file's contents, e.g. as read from another branch
+pub fn parse_workflow_file(contents: &str) -> Result<WorkflowFile> {
+    toml::from_str(contents)
+        .map_err(|e| AppError::Validation(format!("Invalid {}: {}", WORKFLOW_FILE, e)))
+}
@@ -0,0 +1,120 @@
+use serde_json::Value;
+
+pub const DEFAULT_MAX_PAGES: u32 = 1;
+pub const MAX_PAGES: u32 = 10;
+
+/// Check that `path` is a plain API path: rooted, without a host, query,
+/// fragment, empty or relative segments, or percent-encoding, which could
+/// hide any of those. Returns it without a trailing slash.
+pub fn normalize_path(path: &str) -> Result<String, String> {
+    let path = path.trim();
+    if !path.starts_with('/') || path.starts_with("//") {
+        return Err(format!("'{}' must be a path such as /repos/owner/name/pulls", path));
+    }
+    if path.contains(['?', '#', '\\', '%']) {
+        return Err(format!("'{}' may not contain a query, fragment or percent-encoding; pass query parameters separately", path));
+    }
+    let trimmed = path.trim_end_matches('/');
+    if trimmed.split('/').skip(1).any(|s| s.is_empty() || s == ".." || s == ".") {
+        return Err(format!("'{}' may not contain empty or relative segments", path));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Whether `path` matches one of `patterns`, where `*` matches a single
+/// segment and a trailing `**` matches any remainder, including none
+pub fn path_allowed(path: &str, patterns: &[String]) -> bool {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    patterns.iter().any(|pattern| {
+        let pattern: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+        matches(&segments, &pattern)
+    })
+}
+
+fn matches(segments: &[&str], pattern: &[&str]) -> bool {
+    match (pattern.first(), segments.first()) {
+        (Some(&"**"), _) => pattern.len() == 1,
+        (Some(&"*"), Some(_)) => matches(&segments[1..], &pattern[1..]),
+        (Some(expected), Some(actual)) if expected.eq_ignore_ascii_case(actual) => {
+            matches(&segments[1..], &pattern[1..])
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Append the items of `page` to `combined`. List endpoints return either a
+/// bare array or an object wrapping one array (`workflow_runs`, `items`...);
+/// anything else isn't paginated and is kept as the first page.
+pub fn merge_page(combined: &mut Option<Value>, page: Value) {
+    let Some(existing) = combined else {
+        *combined = Some(page);
+        return;
+    };
+    match (existing, page) {
+        (Value::Array(items), Value::Array(more)) => items.extend(more),
+        (Value::Object(existing), Value::Object(mut page)) => {
+            let Some(key) = existing.iter().find(|(_, v)| v.is_array()).map(|(k, _)| k.clone()) else {
+                return;
+            };
+            if let (Some(Value::Array(items)), Some(Value::Array(more))) = (existing.get_mut(&key), page.remove(&key)) {
+                items.extend(more);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Number of items in a merged response, when it is a list
+pub fn item_count(value: &Value) -> Option<usize> {
+    match value {
+        Value::Array(items) => Some(items.len()),
+        Value::Object(fields) => fields.values().find_map(|v| v.as_array()).map(Vec::len),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_plain_paths() {
+        assert_eq!(normalize_path(" /repos/o/r/pulls/ ").as_deref(), Ok("/repos/o/r/pulls"));
+        assert_eq!(normalize_path("/user").as_deref(), Ok("/user"));
+    }
+
+    #[test]
+    fn rejects_relative_segments() {
+        assert!(normalize_path("/repos/o/r/../../orgs/x").is_err());
+        assert!(normalize_path("/repos/o/./r").is_err());
+        assert!(normalize_path("/repos/o/r/..").is_err());
+    }
+
+    #[test]
+    fn rejects_encoded_relative_segments() {
+        assert!(normalize_path("/repos/o/r/%2e%2e/%2e%2e/orgs/x").is_err());
+        assert!(normalize_path("/repos/o/r/%2E%2E/x").is_err());
+        assert!(normalize_path("/repos/o/r/.%2e/x").is_err());
+        assert!(normalize_path("/repos/o%2fr/pulls").is_err());
+    }
+
+    #[test]
+    fn rejects_hosts_queries_and_empty_segments() {
+        assert!(normalize_path("https://evil.example/repos/o/r").is_err());
+        assert!(normalize_path("//evil.example/repos").is_err());
+        assert!(normalize_path("/repos/o/r/pulls?state=all").is_err());
+        assert!(normalize_path("/repos//o/r").is_err());
+        assert!(normalize_path("repos/o/r").is_err());
+    }
+
+    #[test]
+    fn matches_allowed_paths() {
+        let allowed = vec!["/repos/*/*/pulls/**".to_string(), "/user".to_string()];
+        assert!(path_allowed("/repos/o/r/pulls", &allowed));
+        assert!(path_allowed("/repos/o/r/pulls/1/files", &allowed));
+        assert!(path_allowed("/user", &allowed));
+        assert!(!path_allowed("/repos/o/r/issues", &allowed));
+        assert!(!path_allowed("/user/repos", &allowed));
+    }
+}
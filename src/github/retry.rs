@@ -0,0 +1,198 @@
+// Retrying GitHub requests that failed for reasons that pass.
+//
+// Rate-limited requests (429, or 403 from the primary or a secondary limit)
+// are retried after the wait GitHub asks for: `Retry-After`, else until
+// `X-RateLimit-Reset`, else a minute. 5xx responses to idempotent requests are
+// retried with exponential backoff. Every wait gets jitter so clients sharing a
+// token don't retry in lockstep. A request is sent at most
+// `GITHUB_RETRY_MAX_ATTEMPTS` times, and a wait longer than
+// `GITHUB_RETRY_MAX_WAIT_SECONDS` returns the failure instead of blocking.
+
+use prometheus::{IntCounterVec, Opts};
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, Method, Request, Response, StatusCode};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::error::{AppError, Result};
+
+/// First backoff after a 5xx, doubled for each later attempt
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+
+/// GitHub's advice for a secondary limit that names no wait
+const SECONDARY_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy)]
+pub struct Policy {
+    pub max_attempts: u32,
+    pub max_wait: Duration,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self { max_attempts: 3, max_wait: Duration::from_secs(60) }
+    }
+}
+
+static POLICY: OnceLock<Policy> = OnceLock::new();
+static RETRIES: OnceLock<IntCounterVec> = OnceLock::new();
+
+/// Set the policy; call once at startup
+pub fn init(policy: Policy) {
+    let _ = POLICY.set(policy);
+}
+
+fn policy() -> Policy {
+    POLICY.get().copied().unwrap_or_default()
+}
+
+/// `github_api_retries_total`, by reason, for registration with the metrics registry
+pub fn metric() -> IntCounterVec {
+    RETRIES
+        .get_or_init(|| {
+            IntCounterVec::new(
+                Opts::new("github_api_retries_total", "GitHub API requests retried, by reason"),
+                &["reason"],
+            )
+            .expect("valid retry metric")
+        })
+        .clone()
+}
+
+/// Send `request`, retrying under the policy; `observe` sees every response's headers
+pub async fn execute(client: &Client, mut request: Request, observe: impl Fn(&HeaderMap)) -> Result<Response> {
+    let policy = policy();
+    let idempotent = matches!(*request.method(), Method::GET | Method::HEAD | Method::PUT | Method::DELETE);
+    let mut attempt = 1;
+
+    loop {
+        // Streaming bodies can't be cloned, and so can't be retried
+        let next = if attempt < policy.max_attempts { request.try_clone() } else { None };
+        let (method, url) = (request.method().clone(), request.url().path().to_string());
+
+        let response = client.execute(request).await.map_err(AppError::HttpClient)?;
+        observe(response.headers());
+        let Some(next) = next else {
+            return Ok(response);
+        };
+
+        let (response, reason, wait, jitter) = match classify(response, idempotent, attempt).await? {
+            Classified::Done(response) => return Ok(response),
+            Classified::Retry { response, reason, wait, jitter } => (response, reason, wait, jitter),
+        };
+        // Jitter can't push a wait GitHub allows over the limit
+        if wait > policy.max_wait {
+            warn!(
+                "GitHub {} {} hit a {}; the {}s wait exceeds GITHUB_RETRY_MAX_WAIT_SECONDS, not retrying",
+                method,
+                url,
+                reason,
+                wait.as_secs()
+            );
+            return Ok(response);
+        }
+
+        let wait = wait + jitter;
+        metric().with_label_values(&[reason]).inc();
+        warn!(
+            "GitHub {} {} returned {} ({}); retrying in {}ms (attempt {} of {})",
+            method,
+            url,
+            response.status(),
+            reason,
+            wait.as_millis(),
+            attempt + 1,
+            policy.max_attempts
+        );
+        drop(response);
+        tokio::time::sleep(wait).await;
+        request = next;
+        attempt += 1;
+    }
+}
+
+enum Classified {
+    Done(Response),
+    /// Wait `wait`, then up to `jitter` more
+    Retry { response: Response, reason: &'static str, wait: Duration, jitter: Duration },
+}
+
+async fn classify(response: Response, idempotent: bool, attempt: u32) -> Result<Classified> {
+    let status = response.status();
+    let headers = response.headers();
+
+    if status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = retry_after(headers);
+        let exhausted = headers
+            .get("x-ratelimit-remaining")
+            .and_then(|v| v.to_str().ok())
+            .map_or(false, |v| v == "0");
+        let reset_wait = until_reset(headers);
+
+        if let Some(wait) = retry_after {
+            return Ok(retry(response, "secondary_rate_limit", wait));
+        }
+        if exhausted {
+            let wait = reset_wait.unwrap_or(SECONDARY_LIMIT_WAIT);
+            return Ok(retry(response, "rate_limit", wait));
+        }
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Ok(retry(response, "secondary_rate_limit", SECONDARY_LIMIT_WAIT));
+        }
+
+        // A 403 is a secondary limit only if the message says so, which means
+        // reading the body and handing the caller a copy
+        let (response, body) = buffer(response).await?;
+        let body = String::from_utf8_lossy(&body).to_lowercase();
+        if body.contains("secondary rate limit") || body.contains("abuse detection") {
+            return Ok(retry(response, "secondary_rate_limit", SECONDARY_LIMIT_WAIT));
+        }
+        return Ok(Classified::Done(response));
+    }
+
+    if status.is_server_error() && idempotent {
+        let backoff = BASE_BACKOFF * 2u32.saturating_pow(attempt - 1);
+        // Full jitter over the upper half of the backoff
+        let jitter = backoff.mul_f64(rand::thread_rng().gen_range(0.0..0.5));
+        return Ok(Classified::Retry { response, reason: "server_error", wait: backoff / 2, jitter });
+    }
+
+    Ok(Classified::Done(response))
+}
+
+/// Retry after `wait` plus up to a second of jitter
+fn retry(response: Response, reason: &'static str, wait: Duration) -> Classified {
+    let jitter = Duration::from_millis(rand::thread_rng().gen_range(0..1000));
+    Classified::Retry { response, reason, wait, jitter }
+}
+
+/// `Retry-After` as seconds or an HTTP date
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().unwrap_or_default())
+}
+
+fn until_reset(headers: &HeaderMap) -> Option<Duration> {
+    let reset_at: i64 = headers.get("x-ratelimit-reset")?.to_str().ok()?.parse().ok()?;
+    let seconds = (reset_at - chrono::Utc::now().timestamp()).max(0);
+    Some(Duration::from_secs(seconds as u64))
+}
+
+/// Read the body, returning an equivalent response along with it
+async fn buffer(response: Response) -> Result<(Response, Vec<u8>)> {
+    let mut builder = http::Response::builder().status(response.status().as_u16());
+    for (name, value) in response.headers() {
+        builder = builder.header(name.as_str(), value.as_bytes());
+    }
+    let body = response.bytes().await.map_err(AppError::HttpClient)?.to_vec();
+    let rebuilt = builder
+        .body(body.clone())
+        .map_err(|e| AppError::Internal(format!("Failed to rebuild GitHub response: {}", e)))?;
+    Ok((Response::from(rebuilt), body))
+}
@@ -0,0 +1,96 @@
+use serde_json::{json, Value};
+
+use super::api::{GitHubCodeResult, GitHubIssueResult, GitHubRepositoryResult};
+
+pub const DEFAULT_PER_PAGE: u32 = 10;
+pub const MAX_PER_PAGE: u32 = 50;
+
+/// GitHub only serves the first 1000 results of any search
+const MAX_RESULTS: u64 = 1000;
+
+/// Longest text kept per field so a page of hits fits in a model's context
+const MAX_FRAGMENT_CHARS: usize = 300;
+const MAX_BODY_CHARS: usize = 500;
+const MAX_DESCRIPTION_CHARS: usize = 200;
+
+/// Add a `repo:` qualifier for `repository` unless the query already scopes itself
+pub fn scoped_query(query: &str, repository: Option<&str>) -> String {
+    let query = query.trim();
+    match repository {
+        Some(repo) if !query.split_whitespace().any(|t| t.starts_with("repo:")) => {
+            format!("{} repo:{}", query, repo)
+        }
+        _ => query.to_string(),
+    }
+}
+
+/// Query parameters for one page, clamping `per_page` to `MAX_PER_PAGE`
+pub fn page_params(page: u32, per_page: u32, sort: Option<&str>, order: Option<&str>) -> Vec<(&'static str, String)> {
+    let mut params = vec![
+        ("page", page.max(1).to_string()),
+        ("per_page", per_page.clamp(1, MAX_PER_PAGE).to_string()),
+    ];
+    if let Some(sort) = sort {
+        params.push(("sort", sort.to_string()));
+    }
+    if let Some(order) = order {
+        params.push(("order", order.to_string()));
+    }
+    params
+}
+
+/// The page after `page`, while it is within the reachable results
+pub fn next_page(total_count: u64, page: u32, per_page: u32) -> Option<u32> {
+    let seen = page.max(1) as u64 * per_page.clamp(1, MAX_PER_PAGE) as u64;
+    (seen < total_count.min(MAX_RESULTS)).then_some(page.max(1) + 1)
+}
+
+pub fn code_hit(hit: &GitHubCodeResult) -> Value {
+    json!({
+        "repository": hit.repository.full_name,
+        "path": hit.path,
+        "sha": hit.sha,
+        "url": hit.html_url,
+        "fragments": hit.text_matches.iter().map(|m| clip(&m.fragment, MAX_FRAGMENT_CHARS)).collect::<Vec<_>>()
+    })
+}
+
+pub fn issue_hit(hit: &GitHubIssueResult) -> Value {
+    let issue = &hit.issue;
+    json!({
+        "repository": hit.repository_url.split("/repos/").nth(1).unwrap_or(&hit.repository_url),
+        "number": issue.number,
+        "title": issue.title,
+        "state": issue.state,
+        "is_pull_request": issue.pull_request.is_some(),
+        "author": issue.user.login,
+        "labels": issue.labels.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(),
+        "comments": hit.comments,
+        "updated_at": issue.updated_at,
+        "url": issue.html_url,
+        "body": issue.body.as_deref().map(|b| clip(b, MAX_BODY_CHARS))
+    })
+}
+
+pub fn repository_hit(hit: &GitHubRepositoryResult) -> Value {
+    json!({
+        "repository": hit.full_name,
+        "description": hit.description.as_deref().map(|d| clip(d, MAX_DESCRIPTION_CHARS)),
+        "language": hit.language,
+        "stars": hit.stargazers_count,
+        "forks": hit.forks_count,
+        "topics": hit.topics,
+        "archived": hit.archived,
+        "updated_at": hit.updated_at,
+        "url": hit.html_url
+    })
+}
+
+/// Cut `text` to `max_chars` characters, marking the cut with an ellipsis
+fn clip(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
@@ -0,0 +1,123 @@
+// Conflicts left by a rebase or merge, in a form an agent can resolve.
+//
+// Each conflicted file is read from the working tree and every
+// `<<<<<<<` ... `>>>>>>>` block becomes a hunk holding both sides (and the
+// common ancestor, with `merge.conflictStyle = diff3`). The agent edits the
+// file so no markers remain and calls `github_sync_branch` with `continue`.
+
+use serde::Serialize;
+use std::path::Path;
+
+/// Hunks returned per file; the rest are counted but left out
+const MAX_HUNKS_PER_FILE: usize = 20;
+
+#[derive(Debug, Serialize)]
+pub struct ConflictedFile {
+    pub path: String,
+    /// Whether the file can't be shown as text, so must be resolved whole
+    pub binary: bool,
+    pub hunks: Vec<ConflictHunk>,
+    pub total_hunks: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConflictHunk {
+    /// Line of the `<<<<<<<` marker, from 1
+    pub line: usize,
+    /// Text after the marker, e.g. `HEAD` or a commit being replayed
+    pub ours_label: String,
+    pub ours: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    pub theirs_label: String,
+    pub theirs: String,
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Ours,
+    Base,
+    Theirs,
+}
+
+/// The conflicts in each of `paths`, relative to `root`
+pub fn describe(root: &Path, paths: &[String]) -> Vec<ConflictedFile> {
+    paths
+        .iter()
+        .map(|path| match std::fs::read(root.join(path)) {
+            Ok(bytes) => match String::from_utf8(bytes) {
+                Ok(text) => {
+                    let hunks = parse_hunks(&text);
+                    let total_hunks = hunks.len();
+                    ConflictedFile {
+                        path: path.clone(),
+                        binary: false,
+                        hunks: hunks.into_iter().take(MAX_HUNKS_PER_FILE).collect(),
+                        total_hunks,
+                    }
+                }
+                Err(_) => ConflictedFile { path: path.clone(), binary: true, hunks: Vec::new(), total_hunks: 0 },
+            },
+            // Deleted on one side: there is nothing to show but the path
+            Err(_) => ConflictedFile { path: path.clone(), binary: false, hunks: Vec::new(), total_hunks: 0 },
+        })
+        .collect()
+}
+
+/// Conflicted paths that still contain conflict markers
+pub fn unresolved(root: &Path, paths: &[String]) -> Vec<String> {
+    paths
+        .iter()
+        .filter(|path| {
+            std::fs::read_to_string(root.join(path)).is_ok_and(|text| !parse_hunks(&text).is_empty())
+        })
+        .cloned()
+        .collect()
+}
+
+fn parse_hunks(text: &str) -> Vec<ConflictHunk> {
+    let mut hunks = Vec::new();
+    let mut current: Option<(ConflictHunk, Side)> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        if let Some(label) = line.strip_prefix("<<<<<<<") {
+            current = Some((
+                ConflictHunk {
+                    line: index + 1,
+                    ours_label: label.trim().to_string(),
+                    ours: String::new(),
+                    base: None,
+                    theirs_label: String::new(),
+                    theirs: String::new(),
+                },
+                Side::Ours,
+            ));
+            continue;
+        }
+        let Some((hunk, side)) = current.as_mut() else {
+            continue;
+        };
+
+        if line.starts_with("|||||||") {
+            hunk.base = Some(String::new());
+            *side = Side::Base;
+        } else if line == "=======" {
+            *side = Side::Theirs;
+        } else if let Some(label) = line.strip_prefix(">>>>>>>") {
+            hunk.theirs_label = label.trim().to_string();
+            if let Some((hunk, _)) = current.take() {
+                hunks.push(hunk);
+            }
+        } else {
+            let target = match side {
+                Side::Ours => &mut hunk.ours,
+                Side::Base => hunk.base.get_or_insert_with(String::new),
+                Side::Theirs => &mut hunk.theirs,
+            };
+            target.push_str(line);
+            target.push('\n');
+        }
+    }
+
+    hunks
+}
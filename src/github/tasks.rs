@@ -0,0 +1,220 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::api::GitHubProjectItem;
+
+/// Task types recognised from labels or a "Type" project field
+pub const TASK_TYPES: &[&str] = &["bug", "feature", "enhancement", "documentation", "refactor", "test", "chore"];
+
+/// Priority buckets used when presenting tasks, highest first
+pub const PRIORITIES: &[&str] = &["critical", "high", "medium", "low"];
+
+/// Field names checked, in order, for a task's due date
+pub const DUE_DATE_FIELDS: &[&str] = &["Due Date", "Due", "Deadline", "Target Date"];
+
+/// Tasks due within this many days are flagged as due soon
+pub const DUE_SOON_DAYS: i64 = 3;
+
+/// A project to scan, identified by owner login and project number
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectRef {
+    pub owner: String,
+    pub project_number: String,
+}
+
+/// Server-side filters applied to project items in scan-tasks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskFilter {
+    pub task_type: Option<String>,
+    pub status: Option<String>,
+    pub assignee: Option<String>,
+    pub label: Option<String>,
+}
+
+impl TaskFilter {
+    pub fn is_empty(&self) -> bool {
+        self.task_type.is_none() && self.status.is_none() && self.assignee.is_none() && self.label.is_none()
+    }
+
+    pub fn matches(&self, item: &GitHubProjectItem) -> bool {
+        if let Some(wanted) = &self.task_type {
+            if task_type(item).as_deref() != Some(wanted.to_lowercase().as_str()) {
+                return false;
+            }
+        }
+
+        if let Some(wanted) = &self.status {
+            match task_status(item) {
+                Some(status) if status.eq_ignore_ascii_case(wanted) => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(wanted) = &self.assignee {
+            let wanted = wanted.trim_start_matches('@');
+            let assigned = item.content.as_ref().map_or(false, |c| {
+                c.assignees.iter().any(|a| a.eq_ignore_ascii_case(wanted))
+            });
+            if !assigned {
+                return false;
+            }
+        }
+
+        if let Some(wanted) = &self.label {
+            let labelled = item.content.as_ref().map_or(false, |c| {
+                c.labels.iter().any(|l| l.eq_ignore_ascii_case(wanted))
+            });
+            if !labelled {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Task type from the "Type" field, falling back to labels such as
+/// `bug`, `type: bug` or `type/bug`
+pub fn task_type(item: &GitHubProjectItem) -> Option<String> {
+    if let Some(value) = item.field_value("Type").and_then(|v| v.as_str()) {
+        return Some(value.to_lowercase());
+    }
+
+    let labels = &item.content.as_ref()?.labels;
+    labels.iter().find_map(|label| {
+        let name = strip_label_prefix(label, "type").to_lowercase();
+        TASK_TYPES.contains(&name.as_str()).then_some(name)
+    })
+}
+
+pub fn task_status(item: &GitHubProjectItem) -> Option<String> {
+    item.field_value("Status").and_then(|v| v.as_str()).map(String::from)
+}
+
+/// Priority bucket from the "Priority" field or a `priority: x` label;
+/// P0-P3 style values are mapped onto critical..low
+pub fn task_priority(item: &GitHubProjectItem) -> Option<&'static str> {
+    let raw = item
+        .field_value("Priority")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .or_else(|| {
+            item.content.as_ref()?.labels.iter().find_map(|label| {
+                let stripped = strip_label_prefix(label, "priority");
+                (stripped.len() != label.len()).then(|| stripped.to_string())
+            })
+        })?;
+
+    let raw = raw.to_lowercase();
+    let raw = raw.trim_start_matches(|c: char| !c.is_alphanumeric());
+    match raw {
+        r if r.starts_with("critical") || r.starts_with("urgent") || r == "p0" => Some("critical"),
+        r if r.starts_with("high") || r == "p1" => Some("high"),
+        r if r.starts_with("medium") || r.starts_with("normal") || r == "p2" => Some("medium"),
+        r if r.starts_with("low") || r == "p3" || r == "p4" => Some("low"),
+        _ => None,
+    }
+}
+
+pub fn task_due_date(item: &GitHubProjectItem) -> Option<NaiveDate> {
+    DUE_DATE_FIELDS.iter().find_map(|name| {
+        let value = item.field_value(name)?.as_str()?;
+        // Date fields come back as YYYY-MM-DD, occasionally with a time part
+        NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+    })
+}
+
+/// Due-date bucket relative to `today`: overdue, today, this_week, later or no_due_date
+pub fn due_bucket(due: Option<NaiveDate>, today: NaiveDate) -> &'static str {
+    match due {
+        None => "no_due_date",
+        Some(d) if d < today => "overdue",
+        Some(d) if d == today => "today",
+        Some(d) if d <= today + chrono::Duration::days(7) => "this_week",
+        Some(_) => "later",
+    }
+}
+
+/// Due today or within the next `DUE_SOON_DAYS` days
+pub fn is_due_soon(due: NaiveDate, today: NaiveDate) -> bool {
+    due >= today && due <= today + chrono::Duration::days(DUE_SOON_DAYS)
+}
+
+fn strip_label_prefix<'a>(label: &'a str, prefix: &str) -> &'a str {
+    let lower = label.to_lowercase();
+    if lower.starts_with(prefix) {
+        let rest = &label[prefix.len()..];
+        let trimmed = rest.trim_start_matches([':', '/', '-', ' ']);
+        if trimmed.len() != rest.len() {
+            return trimmed;
+        }
+    }
+    label
+}
+
+/// Compact, agent-friendly summary of a project item
+pub fn task_summary(item: &GitHubProjectItem) -> Value {
+    let content = item.content.as_ref();
+    let today = chrono::Utc::now().date_naive();
+    let due_date = task_due_date(item);
+    json!({
+        "id": item.id,
+        "number": content.and_then(|c| c.number),
+        "title": content.map(|c| c.title.as_str()),
+        "url": content.and_then(|c| c.url.as_deref()),
+        "content_type": content.map(|c| c.content_type.as_str()),
+        "repository": content.and_then(|c| c.repository.as_deref()),
+        "type": task_type(item),
+        "status": task_status(item),
+        "priority": task_priority(item),
+        "due_date": due_date.map(|d| d.to_string()),
+        "overdue": due_date.map_or(false, |d| d < today),
+        "due_soon": due_date.map_or(false, |d| is_due_soon(d, today)),
+        "assignees": content.map(|c| c.assignees.clone()).unwrap_or_default(),
+        "labels": content.map(|c| c.labels.clone()).unwrap_or_default()
+    })
+}
+
+pub fn organize_by_priority(tasks: &[GitHubProjectItem]) -> Value {
+    let summaries: Vec<Value> = tasks.iter().map(task_summary).collect();
+    organize_summaries_by_priority(&summaries)
+}
+
+/// Group already-summarised tasks into priority buckets
+pub fn organize_summaries_by_priority(summaries: &[Value]) -> Value {
+    let mut buckets = serde_json::Map::new();
+    for priority in PRIORITIES {
+        buckets.insert(priority.to_string(), json!([]));
+    }
+    buckets.insert("unprioritized".to_string(), json!([]));
+
+    for summary in summaries {
+        let bucket = summary["priority"].as_str().unwrap_or("unprioritized");
+        if let Some(Value::Array(items)) = buckets.get_mut(bucket) {
+            items.push(summary.clone());
+        }
+    }
+
+    buckets.insert("total".to_string(), json!(summaries.len()));
+    Value::Object(buckets)
+}
+
+/// Group already-summarised tasks into due-date buckets
+pub fn organize_by_due_date(summaries: &[Value], today: NaiveDate) -> Value {
+    let mut buckets = serde_json::Map::new();
+    for bucket in ["overdue", "today", "this_week", "later", "no_due_date"] {
+        buckets.insert(bucket.to_string(), json!([]));
+    }
+
+    for summary in summaries {
+        let due = summary["due_date"]
+            .as_str()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        if let Some(Value::Array(items)) = buckets.get_mut(due_bucket(due, today)) {
+            items.push(summary.clone());
+        }
+    }
+
+    Value::Object(buckets)
+}
@@ -0,0 +1,95 @@
+use serde_json::{json, Value};
+
+/// Pages of 100 events read per timeline
+pub const MAX_PAGES: u32 = 10;
+
+/// Comment and review bodies are cut to this many characters
+const BODY_PREVIEW_CHARS: usize = 500;
+
+/// `github://issue/{number}/timeline` (the current repository) or
+/// `github://repos/{owner}/{name}/issues/{number}/timeline`, optionally with
+/// `?events=assigned,cross-referenced`. Returns the repository, number and
+/// event filter.
+pub fn parse_uri(uri: &str) -> Option<(Option<String>, u64, Option<Vec<String>>)> {
+    let (path, query) = match uri.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (uri, None),
+    };
+    let path = path.strip_prefix("github://")?.strip_suffix("/timeline")?;
+
+    let (repository, number) = match path.split('/').collect::<Vec<_>>().as_slice() {
+        ["issue" | "pr", number] => (None, number.parse().ok()?),
+        ["repos", owner, name, "issues" | "pulls", number] => (Some(format!("{}/{}", owner, name)), number.parse().ok()?),
+        _ => return None,
+    };
+
+    let events = query
+        .into_iter()
+        .flat_map(|q| q.split('&'))
+        .find_map(|pair| pair.strip_prefix("events="))
+        .map(|list| list.split(',').filter(|e| !e.is_empty()).map(String::from).collect());
+    Some((repository, number, events))
+}
+
+/// Common fields of a raw timeline event plus the details that matter for
+/// its type; GitHub's payloads differ per event and are mostly noise
+pub fn summarize(event: &Value) -> Value {
+    let kind = event["event"].as_str().unwrap_or("unknown");
+    let actor = event["actor"]["login"]
+        .as_str()
+        .or_else(|| event["user"]["login"].as_str())
+        .or_else(|| event["author"]["name"].as_str());
+    let at = event["created_at"]
+        .as_str()
+        .or_else(|| event["submitted_at"].as_str())
+        .or_else(|| event["author"]["date"].as_str());
+
+    let details = match kind {
+        "cross-referenced" => {
+            let issue = &event["source"]["issue"];
+            json!({
+                "repository": issue["repository"]["full_name"],
+                "number": issue["number"],
+                "title": issue["title"],
+                "state": issue["state"],
+                "pull_request": !issue["pull_request"].is_null()
+            })
+        }
+        "assigned" | "unassigned" => json!({ "assignee": event["assignee"]["login"] }),
+        "review_requested" | "review_request_removed" => json!({
+            "reviewer": event["requested_reviewer"]["login"],
+            "team": event["requested_team"]["slug"]
+        }),
+        "deployed" => json!({
+            "app": event["performed_via_github_app"]["slug"],
+            "environment": event["deployment"]["environment"]
+        }),
+        "labeled" | "unlabeled" => json!({ "label": event["label"]["name"] }),
+        "milestoned" | "demilestoned" => json!({ "milestone": event["milestone"]["title"] }),
+        "renamed" => json!({ "from": event["rename"]["from"], "to": event["rename"]["to"] }),
+        "committed" => json!({
+            "sha": event["sha"],
+            "message": event["message"].as_str().and_then(|m| m.lines().next())
+        }),
+        "reviewed" => json!({ "state": event["state"], "body": preview(&event["body"]) }),
+        "commented" => json!({ "body": preview(&event["body"]), "url": event["html_url"] }),
+        "closed" | "merged" | "referenced" | "reopened" => json!({ "commit": event["commit_id"] }),
+        _ => json!({}),
+    };
+
+    json!({
+        "event": kind,
+        "actor": actor,
+        "created_at": at,
+        "details": details
+    })
+}
+
+fn preview(body: &Value) -> Option<String> {
+    let body = body.as_str()?;
+    if body.chars().count() <= BODY_PREVIEW_CHARS {
+        return Some(body.to_string());
+    }
+    let cut: String = body.chars().take(BODY_PREVIEW_CHARS).collect();
+    Some(format!("{}…", cut))
+}
@@ -0,0 +1,45 @@
+// Open issues nobody has sorted yet, and the agent's decisions about them.
+//
+// An issue is untriaged when it has no labels, or when a project board is
+// in use and the issue isn't on it. `github_triage` first lists them with
+// their full bodies and the labels, milestones and priorities there are to
+// choose from; the agent classifies each one and calls it again with a
+// decision per issue, which is applied.
+
+use serde::{Deserialize, Serialize};
+
+use super::api::GitHubIssue;
+
+/// Issues listed per call unless the caller asks for fewer
+pub const DEFAULT_LIMIT: usize = 30;
+pub const MAX_LIMIT: usize = 100;
+
+/// What to do with one issue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriageDecision {
+    pub number: u64,
+    /// Added to the labels the issue already has
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// An option of the project's Priority field
+    #[serde(default)]
+    pub priority: Option<String>,
+    /// Milestone number or title
+    #[serde(default)]
+    pub milestone: Option<String>,
+    /// Put the issue on the project board; defaults to true when a project is in use
+    #[serde(default)]
+    pub add_to_project: Option<bool>,
+}
+
+/// Why an issue counts as untriaged; empty when it doesn't
+pub fn untriaged_because(issue: &GitHubIssue, on_project: Option<bool>) -> Vec<&'static str> {
+    let mut reasons = Vec::new();
+    if issue.labels.is_empty() {
+        reasons.push("no labels");
+    }
+    if on_project == Some(false) {
+        reasons.push("not on the project");
+    }
+    reasons
+}
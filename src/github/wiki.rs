@@ -0,0 +1,260 @@
+// Repository wikis, edited through a local clone of `owner/name.wiki.git`.
+//
+// GitHub has no API for wiki pages, so each wiki is cloned under `WIKI_DIR`
+// and fetched before every operation. A page is a markup file at the top of
+// the wiki repository, titled by its file name with hyphens for spaces.
+// Clones and pushes use the server's git credentials, like `push` does.
+//
+// Each clone has two locks. `remote` is held while git talks to GitHub and
+// `files` while the working tree is read or changed, so a slow fetch or push
+// doesn't hold up reads of the clone, and no lock is shared between wikis.
+// Where both are needed `remote` is taken first.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+use tracing::{info, warn};
+
+use crate::error::{AppError, Result};
+use crate::git::{GitBackend, SyncOutcome, SyncStrategy};
+use super::host::GitHubHost;
+
+/// Markup formats GitHub renders as wiki pages
+const PAGE_EXTENSIONS: &[&str] = &[
+    "md", "markdown", "mediawiki", "wiki", "textile", "rdoc", "org", "creole", "rst", "asciidoc", "adoc", "pod",
+];
+
+#[derive(Default)]
+struct CheckoutLocks {
+    remote: Mutex<()>,
+    files: Mutex<()>,
+}
+
+static CHECKOUT_LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<CheckoutLocks>>>> = OnceLock::new();
+
+fn locks(checkout: &Path) -> Arc<CheckoutLocks> {
+    CHECKOUT_LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(checkout.to_path_buf())
+        .or_default()
+        .clone()
+}
+
+fn hold(lock: &Mutex<()>) -> MutexGuard<'_, ()> {
+    lock.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[derive(Debug, Serialize)]
+pub struct WikiPage {
+    pub title: String,
+    pub file: String,
+}
+
+/// What a write or delete did
+#[derive(Debug, Serialize)]
+pub struct WikiChange {
+    pub page: String,
+    pub file: String,
+    pub created: bool,
+    /// False when the content was already identical, so nothing was pushed
+    pub changed: bool,
+}
+
+/// Clone URL of the wiki on the configured GitHub host
+pub fn clone_url(host: &GitHubHost, owner: &str, repo: &str) -> String {
+    host.web(&format!("{}/{}.wiki.git", owner, repo))
+}
+
+/// Local clone for `owner/repo` under `base`
+pub fn checkout_path(base: &Path, owner: &str, repo: &str) -> Result<PathBuf> {
+    let safe = |part: &str| {
+        !part.is_empty()
+            && !part.starts_with('.')
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    if !safe(owner) || !safe(repo) {
+        return Err(AppError::Validation(format!("Invalid repository: {}/{}", owner, repo)));
+    }
+
+    Ok(base.join(owner.to_lowercase()).join(format!("{}.wiki", repo.to_lowercase())))
+}
+
+/// Bring the clone at `checkout` up to date, cloning it first if needed, and
+/// run `operation` on its files
+pub fn with_checkout<T>(
+    git: &dyn GitBackend,
+    url: &str,
+    checkout: &Path,
+    operation: impl FnOnce(&dyn GitBackend, &Path) -> Result<T>,
+) -> Result<T> {
+    let locks = locks(checkout);
+    {
+        let _remote = hold(&locks.remote);
+        refresh(git, url, checkout)?;
+    }
+
+    let files = hold(&locks.files);
+    let branch = git.current_branch(checkout)?;
+    let upstream = format!("origin/{}", branch);
+    let merged = git.integrate(checkout, &upstream, SyncStrategy::Merge);
+    if !matches!(merged, Ok(SyncOutcome::Done)) {
+        // An edit committed here but not yet pushed conflicts with GitHub's
+        // copy; its push will be rejected, so start over from GitHub's copy
+        let _ = git.abort_sync(checkout, SyncStrategy::Merge);
+        drop(files);
+        discard(&locks, checkout)?;
+        return Err(match merged {
+            Err(e) => e,
+            Ok(_) => AppError::GitHubApi(format!("The wiki at {} changed while it was being edited; try again", url)),
+        });
+    }
+
+    operation(git, checkout)
+}
+
+/// Run `operation` as [`with_checkout`] does, then push what it committed
+pub fn edit(
+    git: &dyn GitBackend,
+    url: &str,
+    checkout: &Path,
+    operation: impl FnOnce(&dyn GitBackend, &Path) -> Result<WikiChange>,
+) -> Result<WikiChange> {
+    let change = with_checkout(git, url, checkout, operation)?;
+    if !change.changed {
+        return Ok(change);
+    }
+
+    let locks = locks(checkout);
+    let remote = hold(&locks.remote);
+    let pushed = git.current_branch(checkout).and_then(|branch| git.push(checkout, &branch));
+    if let Err(e) = pushed {
+        // Don't leave an unpushed edit behind to conflict with the next fetch
+        drop(remote);
+        discard(&locks, checkout)?;
+        return Err(e);
+    }
+
+    Ok(change)
+}
+
+/// Fetch GitHub's copy into the clone, or clone it if there's none yet
+fn refresh(git: &dyn GitBackend, url: &str, checkout: &Path) -> Result<()> {
+    if checkout.join(".git").exists() {
+        return git.fetch(checkout);
+    }
+
+    info!("Cloning wiki {} into {}", url, checkout.display());
+    git.clone_repository(url, checkout).map_err(|e| {
+        AppError::GitHubApi(format!(
+            "Could not clone {}. The wiki must be enabled and have at least one page created on GitHub: {}",
+            url, e
+        ))
+    })
+}
+
+/// Remove the clone so the next operation clones GitHub's copy afresh
+fn discard(locks: &CheckoutLocks, checkout: &Path) -> Result<()> {
+    let _remote = hold(&locks.remote);
+    let _files = hold(&locks.files);
+    warn!("Discarding wiki clone {}", checkout.display());
+    remove_checkout(checkout)
+}
+
+pub fn list_pages(checkout: &Path) -> Result<Vec<WikiPage>> {
+    let entries = std::fs::read_dir(checkout)
+        .map_err(|e| AppError::Internal(format!("Failed to read {}: {}", checkout.display(), e)))?;
+
+    let mut pages: Vec<WikiPage> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map_or(false, |t| t.is_file()))
+        .filter_map(|entry| {
+            let path = entry.path();
+            let extension = path.extension()?.to_str()?;
+            if !PAGE_EXTENSIONS.contains(&extension) {
+                return None;
+            }
+            Some(WikiPage {
+                title: path.file_stem()?.to_str()?.replace('-', " "),
+                file: entry.file_name().to_string_lossy().to_string(),
+            })
+        })
+        .collect();
+    pages.sort_by(|a, b| a.title.to_lowercase().cmp(&b.title.to_lowercase()));
+    Ok(pages)
+}
+
+/// File for page `title`, whatever markup it's written in
+fn find_page(checkout: &Path, title: &str) -> Result<Option<String>> {
+    let stem = page_stem(title)?;
+    Ok(list_pages(checkout)?
+        .into_iter()
+        .find(|page| page.file.rsplit_once('.').map_or(false, |(s, _)| s.eq_ignore_ascii_case(&stem)))
+        .map(|page| page.file))
+}
+
+/// File name stem GitHub uses for a page title
+fn page_stem(title: &str) -> Result<String> {
+    let stem = title.trim().replace(' ', "-");
+    if stem.is_empty() || stem.starts_with('.') || stem.contains(['/', '\\']) {
+        return Err(AppError::Validation(format!("Invalid wiki page title: {}", title)));
+    }
+    Ok(stem)
+}
+
+/// Page file and content
+pub fn read_page(checkout: &Path, title: &str) -> Result<(String, String)> {
+    let file = find_page(checkout, title)?
+        .ok_or_else(|| AppError::Validation(format!("Wiki page not found: {}", title)))?;
+    let content = std::fs::read_to_string(checkout.join(&file))
+        .map_err(|e| AppError::Internal(format!("Failed to read wiki page {}: {}", file, e)))?;
+    Ok((file, content))
+}
+
+/// Create or replace page `title` (new pages are Markdown) and commit it
+pub fn write_page(git: &dyn GitBackend, checkout: &Path, title: &str, content: &str, message: &str) -> Result<WikiChange> {
+    let stem = page_stem(title)?;
+    let existing = find_page(checkout, title)?;
+    let created = existing.is_none();
+    let file = existing.unwrap_or_else(|| format!("{}.md", stem));
+
+    std::fs::write(checkout.join(&file), content)
+        .map_err(|e| AppError::Internal(format!("Failed to write wiki page {}: {}", file, e)))?;
+    let changed = commit(git, checkout, message)?;
+
+    Ok(WikiChange { page: title.to_string(), file, created, changed })
+}
+
+pub fn delete_page(git: &dyn GitBackend, checkout: &Path, title: &str, message: &str) -> Result<WikiChange> {
+    let file = find_page(checkout, title)?
+        .ok_or_else(|| AppError::Validation(format!("Wiki page not found: {}", title)))?;
+
+    std::fs::remove_file(checkout.join(&file))
+        .map_err(|e| AppError::Internal(format!("Failed to delete wiki page {}: {}", file, e)))?;
+    commit(git, checkout, message)?;
+
+    Ok(WikiChange { page: title.to_string(), file, created: false, changed: true })
+}
+
+/// Commit any changes; false when there were none
+fn commit(git: &dyn GitBackend, checkout: &Path, message: &str) -> Result<bool> {
+    if git.status(checkout)?.is_empty() {
+        return Ok(false);
+    }
+
+    if let Err(e) = git.commit_all(checkout, message) {
+        // Don't leave a half-made edit behind in the working tree
+        remove_checkout(checkout)?;
+        return Err(e);
+    }
+
+    Ok(true)
+}
+
+fn remove_checkout(checkout: &Path) -> Result<()> {
+    std::fs::remove_dir_all(checkout)
+        .map_err(|e| AppError::Internal(format!("Failed to remove {}: {}", checkout.display(), e)))
+}
@@ -0,0 +1,112 @@
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::{AppError, Result};
+
+/// Paths listed in a cleanup report before it is truncated
+const MAX_REPORTED_PATHS: usize = 200;
+
+/// What a work-folder cleanup removed, or would remove on a dry run
+#[derive(Debug, Serialize)]
+pub struct CleanupReport {
+    pub folder: String,
+    pub exists: bool,
+    pub dry_run: bool,
+    pub removed: bool,
+    pub paths: Vec<String>,
+    pub truncated: bool,
+    pub total_bytes: u64,
+}
+
+/// Resolve the work folder for `branch` from a pattern such as `work/{branch}`,
+/// refusing anything that could point outside the repository
+pub fn work_folder_path(root: &Path, pattern: &str, branch: &str) -> Result<PathBuf> {
+    if !pattern.contains("{branch}") {
+        return Err(AppError::Validation("Work folder pattern must contain {branch}".to_string()));
+    }
+
+    let relative = PathBuf::from(pattern.replace("{branch}", branch));
+    let safe = relative.components().all(|c| matches!(c, Component::Normal(_)));
+    if !safe || relative.as_os_str().is_empty() {
+        return Err(AppError::Validation(format!("Unsafe work folder path: {}", relative.display())));
+    }
+
+    Ok(root.join(relative))
+}
+
+/// Remove the branch's work folder, or just report its contents when `dry_run` is set.
+/// Folders containing files tracked by git are never removed.
+pub fn cleanup(root: &Path, pattern: &str, branch: &str, dry_run: bool) -> Result<CleanupReport> {
+    let folder = work_folder_path(root, pattern, branch)?;
+    let display = folder.strip_prefix(root).unwrap_or(&folder).display().to_string();
+
+    let mut report = CleanupReport {
+        folder: display,
+        exists: false,
+        dry_run,
+        removed: false,
+        paths: Vec::new(),
+        truncated: false,
+        total_bytes: 0,
+    };
+
+    let metadata = match std::fs::symlink_metadata(&folder) {
+        Ok(metadata) => metadata,
+        Err(_) => return Ok(report),
+    };
+    report.exists = true;
+
+    // A symlinked work folder is unlinked, never followed
+    if metadata.file_type().is_symlink() {
+        report.paths.push(report.folder.clone());
+        if !dry_run {
+            std::fs::remove_file(&folder)
+                .map_err(|e| AppError::Internal(format!("Failed to remove {}: {}", report.folder, e)))?;
+            report.removed = true;
+        }
+        return Ok(report);
+    }
+
+    if crate::git::backend().has_tracked_files(root, &folder)? {
+        return Err(AppError::Validation(format!(
+            "{} contains files tracked by git; refusing to delete it",
+            report.folder
+        )));
+    }
+
+    collect_paths(root, &folder, &mut report)?;
+
+    if !dry_run {
+        std::fs::remove_dir_all(&folder)
+            .map_err(|e| AppError::Internal(format!("Failed to remove {}: {}", report.folder, e)))?;
+        report.removed = true;
+    }
+
+    Ok(report)
+}
+
+fn collect_paths(root: &Path, dir: &Path, report: &mut CleanupReport) -> Result<()> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| AppError::Internal(format!("Failed to read {}: {}", dir.display(), e)))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+            continue;
+        };
+
+        if metadata.is_dir() {
+            collect_paths(root, &path, report)?;
+            continue;
+        }
+
+        report.total_bytes += metadata.len();
+        if report.paths.len() < MAX_REPORTED_PATHS {
+            report.paths.push(path.strip_prefix(root).unwrap_or(&path).display().to_string());
+        } else {
+            report.truncated = true;
+        }
+    }
+
+    Ok(())
+}
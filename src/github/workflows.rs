@@ -1,34 +1,376 @@
 use serde_json::{json, Value};
-use std::process::Command;
-use tracing::{debug, info, warn, error};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::{sync::Semaphore, task::JoinSet};
+use tracing::{debug, info, warn};
 
-use crate::{AppState, error::{AppError, Result}, mcp::protocol::GitHubCommand};
-use super::api::{get_github_client, GitHubClient};
+use crate::{AppState, approvals, error::{AppError, Result}, git, mcp::{caller, progress, protocol::{CommandTarget, GitHubCommand}}, permissions, process, security::secret_scan};
+use super::api::{
+    get_github_client, AdvisoryCredit, GitHubBranchProtection, GitHubClient, GitHubCodeResult, GitHubIssueResult, GitHubRepository, GitHubRepositoryResult,
+    IssueFilter, WorkflowRunFilter, ReactionTarget, ReviewCommentInput,
+    ADVISORY_CREDIT_TYPES, ADVISORY_SEVERITIES, REACTION_CONTENTS, REVIEW_EVENTS,
+};
+use super::{actions, alerts, allowlist, board, bootstrap, checklist, codeowners, commit_message, dependabot, discussions, environment, gists, graphql, labels, merge_tracking, milestones, notifications, policy, protection, pull_request, release, rest, search, sync, timeline, triage, wiki, work_folder};
+use super::tasks::{self, ProjectRef, TaskFilter};
+
+/// Upper bound on concurrent project fetches in a multi-project scan
+const MAX_CONCURRENT_PROJECT_SCANS: usize = 4;
+
+/// Lines of output kept per merge check for the result payload
+const CHECK_OUTPUT_TAIL_LINES: usize = 40;
+
+/// Diff handed to an agent writing a commit message, unless it asks for more
+const COMMIT_DIFF_MAX_BYTES: usize = 60_000;
 
 pub async fn execute_command(state: AppState, command: GitHubCommand) -> Result<Value> {
+    // Commands naming a registered repository run against that checkout
+    let workdir = match command.repo() {
+        Some(name) => Some(PathBuf::from(crate::repositories::get(&state.db, name).await?.path)),
+        None => None,
+    };
+
+    let run = git::scope(workdir, async move {
+        authorize_command(&state, &command).await?;
+        dispatch_command(state, command).await
+    });
+    allowlist::scope(run).await
+}
+
+/// Apply `ALLOWED_REPOSITORIES`, the repository allow-list and the server's
+/// repository permissions to `command`
+async fn authorize_command(state: &AppState, command: &GitHubCommand) -> Result<()> {
+    // Skip resolving the checkout's remote when there's no policy to apply
+    let approval = state.config.security.repository_approval;
+    if !approval && !allowlist::enabled() && !permissions::enabled(&state.db).await? {
+        return Ok(());
+    }
+
+    let repository = match command.target() {
+        CommandTarget::Repository(repository) => repository.to_string(),
+        CommandTarget::Checkout => {
+            let (owner, name) = get_remote_repository().await?;
+            format!("{}/{}", owner, name)
+        }
+        CommandTarget::Unscoped => "*".to_string(),
+    };
+    authorize_repository(state, &repository, command.name(), command.operation()).await
+}
+
+/// The checks `authorize_command` applies, for one repository; also used by
+/// commands that reach several repositories and by federated tools
+pub(crate) async fn authorize_repository(state: &AppState, repository: &str, name: &str, operation: permissions::Operation) -> Result<()> {
+    // Cross-repository calls have no single repository to approve
+    if repository != "*" {
+        allowlist::allow_target(repository)?;
+        approvals::require(state, repository, name).await?;
+    }
+    permissions::authorize(state, repository, operation).await
+}
+
+async fn dispatch_command(state: AppState, command: GitHubCommand) -> Result<Value> {
     match command {
-        GitHubCommand::Push { branch, message, ready_for_review } => {
-            execute_push_workflow(state, branch, message, ready_for_review).await
+        GitHubCommand::Push { branch, message, ready_for_review, draft_pr, request_reviewers, .. } => {
+            execute_push_workflow(state, branch, message, ready_for_review, draft_pr, request_reviewers).await
+        }
+        GitHubCommand::ScanTasks {
+            project_number, owner, filter_type, status, assignee, label, projects, all_projects, filter, ..
+        } => {
+            let saved = match filter {
+                Some(name) => crate::preferences::find_filter(&state, &name).await?,
+                None => Default::default(),
+            };
+            let project_number = project_number.or(saved.project_number);
+            let owner = owner.or(saved.owner);
+            let projects = projects.or(saved.projects);
+            let all_projects = all_projects.or(saved.all_projects);
+            let filter = TaskFilter {
+                task_type: filter_type.or(saved.filter_type),
+                status: status.or(saved.status),
+                assignee: assignee.or(saved.assignee),
+                label: label.or(saved.label),
+            };
+            if projects.is_some() || all_projects == Some(true) {
+                execute_multi_project_scan(state, projects, filter).await
+            } else {
+                execute_scan_tasks_workflow(state, project_number, owner, filter).await
+            }
+        }
+        GitHubCommand::Merge { branch, delete_branch, cleanup_work_folder, ignore_checks, mode, .. } => {
+            execute_merge_workflow(state, branch, delete_branch, cleanup_work_folder, ignore_checks, mode).await
+        }
+        GitHubCommand::StartTask { issue_number, project_number, owner, open_draft_pr } => {
+            execute_start_task_workflow(state, issue_number, project_number, owner, open_draft_pr).await
+        }
+        GitHubCommand::PromoteDraft { item_id, title, repository, project_number, owner, assignee } => {
+            execute_promote_draft_workflow(state, item_id, title, repository, project_number, owner, assignee).await
+        }
+        GitHubCommand::GetChecklist { number, repository } => {
+            execute_get_checklist(state, number, repository).await
+        }
+        GitHubCommand::UpdateChecklist { number, repository, items, text, checked } => {
+            execute_update_checklist(state, number, repository, items, text, checked).await
+        }
+        GitHubCommand::AddReaction { repository, number, comment_id, review_comment, content } => {
+            execute_add_reaction(state, repository, number, comment_id, review_comment, content).await
+        }
+        GitHubCommand::GetReactions { repository, number, comment_id, review_comment, own_comments } => {
+            execute_get_reactions(state, repository, number, comment_id, review_comment, own_comments).await
+        }
+        GitHubCommand::CreateIssue { repository, title, body, labels, assignees } => {
+            execute_create_issue(state, repository, title, body, labels, assignees).await
+        }
+        GitHubCommand::UpdateIssue { repository, number, title, body, state: issue_state, labels, assignees } => {
+            execute_update_issue(state, repository, number, title, body, issue_state, labels, assignees).await
+        }
+        GitHubCommand::CommentIssue { repository, number, body } => {
+            execute_comment_issue(state, repository, number, body).await
+        }
+        GitHubCommand::CloseIssue { repository, number, reason, comment, lock, lock_reason } => {
+            execute_close_issue(state, repository, number, reason, comment, lock, lock_reason).await
+        }
+        GitHubCommand::BulkUpdateIssues {
+            repository, filter, numbers, add_labels, remove_labels, milestone, close, close_reason, comment, confirm, limit,
+        } => {
+            let changes = BulkIssueChanges {
+                add_labels: add_labels.unwrap_or_default(),
+                remove_labels: remove_labels.unwrap_or_default(),
+                milestone,
+                close: close.unwrap_or(false),
+                close_reason,
+                comment,
+            };
+            execute_bulk_update_issues(state, repository, filter.unwrap_or_default(), numbers, changes, confirm, limit).await
+        }
+        GitHubCommand::ListLabels { repository } => {
+            execute_list_labels(state, repository).await
+        }
+        GitHubCommand::CreateLabel { repository, name, color, description } => {
+            execute_create_label(state, repository, name, color, description).await
+        }
+        GitHubCommand::UpdateLabel { repository, name, new_name, color, description } => {
+            execute_update_label(state, repository, name, new_name, color, description).await
+        }
+        GitHubCommand::DeleteLabel { repository, name } => {
+            execute_delete_label(state, repository, name).await
+        }
+        GitHubCommand::SyncLabels { repositories, labels, file, source, prune, confirm } => {
+            execute_sync_labels(state, repositories, labels, file, source, prune, confirm).await
+        }
+        GitHubCommand::CreateRepository {
+            name, owner, description, private, default_branch, merge_methods, delete_branch_on_merge,
+            vulnerability_alerts, template, labels, project,
+        } => {
+            execute_create_repository(
+                state, name, owner, description, private, default_branch, merge_methods, delete_branch_on_merge,
+                vulnerability_alerts, template, labels, project,
+            )
+            .await
+        }
+        GitHubCommand::ListDiscussions { repository, category, state: discussion_state, answered, limit } => {
+            execute_list_discussions(state, repository, category, discussion_state, answered, limit).await
+        }
+        GitHubCommand::GetDiscussion { repository, number } => {
+            execute_get_discussion(state, repository, number).await
+        }
+        GitHubCommand::CreateDiscussion { repository, category, title, body } => {
+            execute_create_discussion(state, repository, category, title, body).await
+        }
+        GitHubCommand::ReplyDiscussion { repository, number, body, reply_to } => {
+            execute_reply_discussion(state, repository, number, body, reply_to).await
+        }
+        GitHubCommand::AnswerDiscussion { repository, comment_id, unmark } => {
+            execute_answer_discussion(state, repository, comment_id, unmark).await
+        }
+        GitHubCommand::ListSecurityAlerts { repository, kinds, severity, state: alert_state, limit } => {
+            execute_list_security_alerts(state, repository, kinds, severity, alert_state, limit).await
+        }
+        GitHubCommand::DismissSecurityAlert { repository, kind, number, reason, comment } => {
+            execute_dismiss_security_alert(state, repository, kind, number, reason, comment).await
+        }
+        GitHubCommand::CreateGist { description, public, files } => {
+            execute_create_gist(state, description, public, files).await
+        }
+        GitHubCommand::ListGists { limit } => execute_list_gists(state, limit).await,
+        GitHubCommand::ListNotifications { repository, reasons, all, participating, since, limit } => {
+            execute_list_notifications(state, repository, reasons, all, participating, since, limit).await
+        }
+        GitHubCommand::MarkNotificationsRead { thread_ids, all, repository, before, done } => {
+            execute_mark_notifications_read(state, thread_ids, all, repository, before, done).await
+        }
+        GitHubCommand::ListMilestones { repository, state: milestone_state } => {
+            execute_list_milestones(state, repository, milestone_state).await
+        }
+        GitHubCommand::CreateMilestone { repository, title, description, due_on } => {
+            execute_create_milestone(state, repository, title, description, due_on).await
+        }
+        GitHubCommand::CloseMilestone { repository, milestone } => {
+            execute_close_milestone(state, repository, milestone).await
+        }
+        GitHubCommand::AssignMilestone { repository, milestone, numbers } => {
+            execute_assign_milestone(state, repository, milestone, numbers).await
+        }
+        GitHubCommand::MilestoneProgress { repository, milestone } => {
+            execute_milestone_progress(state, repository, milestone).await
+        }
+        GitHubCommand::ListWorkflowRuns { repository, branch, pull_request, status, limit } => {
+            execute_list_workflow_runs(state, repository, branch, pull_request, status, limit).await
+        }
+        GitHubCommand::GetWorkflowRun { repository, run_id, logs } => {
+            execute_get_workflow_run(state, repository, run_id, logs).await
+        }
+        GitHubCommand::RerunWorkflowRun { repository, run_id, failed_only } => {
+            execute_rerun_workflow_run(state, repository, run_id, failed_only).await
+        }
+        GitHubCommand::CancelWorkflowRun { repository, run_id } => {
+            execute_cancel_workflow_run(state, repository, run_id).await
+        }
+        GitHubCommand::Promote { tag, environment, wait, .. } => {
+            execute_promote_workflow(state, tag, environment, wait).await
+        }
+        GitHubCommand::ManageDependabot { confirm, .. } => {
+            execute_manage_dependabot(state, confirm).await
+        }
+        GitHubCommand::CreateRelease { tag, name, notes, draft, prerelease, assets, .. } => {
+            execute_create_release(state, tag, name, notes, draft, prerelease, assets).await
+        }
+        GitHubCommand::ListReleases { repository, limit } => {
+            execute_list_releases(state, repository, limit).await
+        }
+        GitHubCommand::Changelog { repository, from, to, heading } => {
+            execute_changelog(state, repository, from, to, heading).await
+        }
+        GitHubCommand::ReadFile { repository, path, git_ref } => {
+            execute_read_file(state, repository, path, git_ref).await
+        }
+        GitHubCommand::WriteFile { repository, path, content, message, branch, sha } => {
+            execute_write_file(state, repository, path, content, message, branch, sha).await
+        }
+        GitHubCommand::DeleteFile { repository, path, message, branch, sha } => {
+            execute_delete_file(state, repository, path, message, branch, sha).await
+        }
+        GitHubCommand::Wiki { repository, action, page, content, message } => {
+            execute_wiki(state, repository, action, page, content, message).await
         }
-        GitHubCommand::ScanTasks { project_number, filter_type, status } => {
-            execute_scan_tasks_workflow(state, project_number, filter_type, status).await
+        GitHubCommand::GetBranchProtection { repository, branch } => {
+            execute_get_branch_protection(state, repository, branch).await
         }
-        GitHubCommand::Merge { branch, delete_branch, cleanup_work_folder } => {
-            execute_merge_workflow(state, branch, delete_branch, cleanup_work_folder).await
+        GitHubCommand::UpdateBranchProtection { repository, branch, settings } => {
+            execute_update_branch_protection(state, repository, branch, settings).await
+        }
+        GitHubCommand::SearchCode { query, repository, page, per_page } => {
+            execute_search_code(state, query, repository, page, per_page).await
+        }
+        GitHubCommand::SearchIssues { query, repository, sort, order, page, per_page } => {
+            execute_search_issues(state, query, repository, sort, order, page, per_page).await
+        }
+        GitHubCommand::SearchRepos { query, sort, order, page, per_page } => {
+            execute_search_repos(state, query, sort, order, page, per_page).await
+        }
+        GitHubCommand::Graphql { query, variables } => execute_graphql(state, query, variables).await,
+        GitHubCommand::RestGet { path, params, max_pages } => execute_rest_get(state, path, params, max_pages).await,
+        GitHubCommand::DraftAdvisory {
+            repository, ghsa_id, summary, description, severity, cwe_ids, vulnerabilities, credits, request_cve,
+        } => {
+            let draft = AdvisoryDraft { summary, description, severity, cwe_ids, vulnerabilities };
+            execute_draft_advisory(state, repository, ghsa_id, draft, credits.unwrap_or_default(), request_cve).await
+        }
+        GitHubCommand::GetPullRequestDiff { repository, number, per_file, files, max_bytes } => {
+            execute_get_pull_request_diff(state, repository, number, per_file, files, max_bytes).await
+        }
+        GitHubCommand::GetTimeline { repository, number, events } => {
+            execute_get_timeline(state, repository, number, events).await
+        }
+        GitHubCommand::SubmitReview { repository, number, event, body, comments } => {
+            execute_submit_review(state, repository, number, event, body, comments).await
+        }
+        GitHubCommand::AddReviewComment { repository, number, comment } => {
+            execute_add_review_comment(state, repository, number, comment).await
+        }
+        GitHubCommand::ListReviews { repository, number } => {
+            execute_list_reviews(state, repository, number).await
+        }
+        GitHubCommand::ListCommits { repository, branch, pull_request, limit } => {
+            execute_list_commits(state, repository, branch, pull_request, limit).await
+        }
+        GitHubCommand::GetRepoMetadata { repository } => {
+            execute_get_repo_metadata(state, repository).await
+        }
+        GitHubCommand::UpdateRepoMetadata { repository, description, homepage, topics, add_topics, remove_topics } => {
+            execute_update_repo_metadata(state, repository, description, homepage, topics, add_topics, remove_topics).await
+        }
+        GitHubCommand::CheckRepoPolicy { org, repository } => {
+            execute_check_repo_policy(state, org, repository).await
+        }
+        GitHubCommand::CleanupWorkFolder { branch, dry_run } => {
+            execute_cleanup_work_folder(state, branch, dry_run).await
+        }
+        GitHubCommand::CompleteTask { issue_number, branch, pull_request } => {
+            execute_complete_task_workflow(state, issue_number, branch, pull_request).await
+        }
+        GitHubCommand::SuggestCommitMessage { staged, message, max_diff_bytes, .. } => {
+            execute_suggest_commit_message(staged, message, max_diff_bytes).await
+        }
+        GitHubCommand::RequestReviewers { repository, pull_request, reviewers, dry_run } => {
+            execute_request_reviewers(state, repository, pull_request, reviewers, dry_run).await
+        }
+        GitHubCommand::Triage { repository, project_number, owner, decisions, limit } => {
+            execute_triage(state, repository, project_number, owner, decisions, limit).await
+        }
+        GitHubCommand::ProjectUpdateItem { project_number, owner, repository, number, item_id, fields, archive } => {
+            execute_project_update_item(state, project_number, owner, repository, number, item_id, fields, archive).await
+        }
+        GitHubCommand::GetProjectBoard { project_number, owner } => get_board(state, project_number, owner).await,
+        GitHubCommand::SyncBranch { action, strategy, base, .. } => {
+            execute_sync_branch(state, action, strategy, base).await
+        }
+        GitHubCommand::ForkRepository { repository, organization, name, default_branch_only, remotes, .. } => {
+            execute_fork_repository(state, repository, organization, name, default_branch_only, remotes).await
+        }
+        GitHubCommand::AddUpstreamRemote { upstream, remote, .. } => {
+            execute_add_upstream_remote(state, upstream, remote).await
+        }
+        GitHubCommand::SyncFork { repository, branch, method, strategy, .. } => {
+            execute_sync_fork(state, repository, branch, method, strategy).await
         }
     }
 }
 
 pub async fn get_status(state: AppState) -> Result<Value> {
-    let current_branch = get_current_branch()?;
-    let git_status = get_git_status()?;
+    let current_branch = get_current_branch().await?;
+    let git_status = get_git_status().await?;
     let has_uncommitted_changes = !git_status.is_empty();
     
-    // Check for existing PR
-    let pr_info = if let Ok(github_client) = get_github_client(state.clone(), None).await {
-        get_pr_for_branch(&github_client, &current_branch).await.ok()
+    // Check for existing PR, with the signature status of its commits
+    let (pr_info, commit_verification) = if let Ok(github_client) = get_github_client(state.clone(), caller::user_id()).await {
+        match get_pr_for_branch(&github_client, &current_branch).await {
+            Ok(pr) => {
+                let verification = match get_remote_repository().await {
+                    Ok((repo_owner, repo_name)) => github_client
+                        .list_pull_request_commits(&repo_owner, &repo_name, pr.number)
+                        .await
+                        .ok()
+                        .map(|commits| verification_summary(&commits)),
+                    Err(_) => None,
+                };
+                (Some(pr), verification)
+            }
+            Err(_) => (None, None),
+        }
     } else {
-        None
+        (None, None)
+    };
+
+    // Toolchains and disk space, so agents can tell whether the merge checks can run here
+    let environment = match get_repo_root().await {
+        Ok(root) => {
+            let (checks, source) = merge_check_commands(&state, &root).unwrap_or((Vec::new(), "none"));
+            Some(environment::snapshot(&root, &checks, source).await)
+        }
+        Err(_) => None,
     };
 
     Ok(json!({
@@ -36,6 +378,8 @@ pub async fn get_status(state: AppState) -> Result<Value> {
         "has_uncommitted_changes": has_uncommitted_changes,
         "git_status": git_status,
         "pull_request": pr_info,
+        "commit_verification": commit_verification,
+        "environment": environment,
         "timestamp": chrono::Utc::now().to_rfc3339()
     }))
 }
@@ -43,12 +387,14 @@ pub async fn get_status(state: AppState) -> Result<Value> {
 pub async fn get_tasks(state: AppState) -> Result<Value> {
     // Try to get project number from TODO.md or environment
     let project_number = detect_project_number().await?;
+    let owner = resolve_project_owner(&state, None).await?;
     
-    if let Ok(github_client) = get_github_client(state, None).await {
-        let tasks = github_client.get_project_items(&project_number).await?;
+    if let Ok(github_client) = get_github_client(state, caller::user_id()).await {
+        let tasks = github_client.get_project_items(&owner, &project_number).await?;
         
         Ok(json!({
             "project_number": project_number,
+            "owner": owner,
             "tasks": tasks,
             "total_count": tasks.len(),
             "timestamp": chrono::Utc::now().to_rfc3339()
@@ -58,17 +404,57 @@ pub async fn get_tasks(state: AppState) -> Result<Value> {
     }
 }
 
+async fn get_board(state: AppState, project_number: String, owner: Option<String>) -> Result<Value> {
+    let owner = resolve_project_owner(&state, owner).await?;
+    // Kept boards are per token, so the caller's client comes first
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    if let Some(board) = board::cached(&github_client, &owner, &project_number) {
+        return Ok(board);
+    }
+
+    let (project, items) = tokio::try_join!(
+        github_client.get_project(&owner, &project_number),
+        github_client.get_project_items(&owner, &project_number)
+    )?;
+
+    let shaped = board::shape(&owner, &project_number, &project, &items);
+    board::store(&github_client, &owner, &project_number, &shaped);
+    Ok(shaped)
+}
+
 async fn execute_push_workflow(
     state: AppState,
     branch: Option<String>,
     message: Option<String>,
     ready_for_review: Option<bool>,
+    draft_pr: Option<bool>,
+    request_reviewers: Option<bool>,
 ) -> Result<Value> {
     info!("Executing push workflow");
 
     // Get current branch or use provided branch
-    let current_branch = branch.unwrap_or_else(|| get_current_branch().unwrap_or_else(|_| "main".to_string()));
-    let main_branch = get_main_branch().unwrap_or_else(|_| "main".to_string());
+    let current_branch = match branch {
+        Some(branch) => branch,
+        None => get_current_branch().await.unwrap_or_else(|_| "main".to_string()),
+    };
+    let main_branch = get_main_branch().await.unwrap_or_else(|_| "main".to_string());
+
+    // Report protection rules up front instead of a rejected push from git
+    if let Some((protection, pusher)) = current_branch_protection(&state, &current_branch).await {
+        let blockers = protection::push_blockers(&protection, &pusher);
+        if !blockers.is_empty() {
+            return Ok(json!({
+                "status": "blocked",
+                "message": format!("🔒 Direct pushes to {} are blocked by branch protection", current_branch),
+                "branch": current_branch,
+                "blockers": blockers,
+                "protection": protection::summary(&protection),
+                "suggestion": "Push to a feature branch and open a pull request instead"
+            }));
+        }
+    }
 
     // Check if we're on main branch
     if current_branch == main_branch {
@@ -84,11 +470,12 @@ async fn execute_push_workflow(
     // Commit changes if message provided
     if let Some(commit_message) = message {
         info!("Committing changes with message: {}", commit_message);
-        commit_changes(&commit_message)?;
+        progress::report("📝 Committing changes");
+        commit_changes(&commit_message).await?;
     }
 
     // Check for uncommitted changes
-    let git_status = get_git_status()?;
+    let git_status = get_git_status().await?;
     if !git_status.is_empty() {
         return Ok(json!({
             "status": "error",
@@ -97,12 +484,17 @@ async fn execute_push_workflow(
         }));
     }
 
+    if let Some(blocked) = secret_scan_block(&state, &current_branch).await? {
+        return Ok(blocked);
+    }
+
     // Push to remote
     info!("Pushing branch: {}", current_branch);
-    push_branch(&current_branch)?;
+    progress::report(&format!("⬆️ Pushing {}", current_branch));
+    push_branch(&current_branch).await?;
 
     // Check if PR exists and update
-    if let Ok(github_client) = get_github_client(state, None).await {
+    if let Ok(github_client) = get_github_client(state.clone(), caller::user_id()).await {
         if let Ok(pr) = get_pr_for_branch(&github_client, &current_branch).await {
             info!("Found existing PR: #{}", pr.number);
             
@@ -124,9 +516,48 @@ async fn execute_push_workflow(
                 result["pull_request"]["ready_for_review"] = json!(true);
                 result["message"] = json!("🎉 Pushed and marked PR as ready for review!");
             }
+            if request_reviewers == Some(true) {
+                result["reviewers"] = push_review_request(&github_client, &pr).await;
+            }
 
             return Ok(result);
         }
+
+        if draft_pr.unwrap_or(state.config.workflow.auto_draft_pr) {
+            progress::report("📬 Opening a draft pull request");
+            match open_draft_pr(&state, &github_client, &current_branch, &main_branch).await {
+                Ok((pr, issue_number, applied)) => {
+                    let mut result = json!({
+                        "status": "success",
+                        "message": format!("✅ Pushed {} and opened draft PR #{}", current_branch, pr.number),
+                        "branch": current_branch,
+                        "pull_request": {
+                            "number": pr.number,
+                            "url": pr.html_url,
+                            "title": pr.title,
+                            "draft": pr.draft,
+                            "created": true,
+                            "closes_issue": issue_number,
+                            "applied": applied
+                        }
+                    });
+                    if request_reviewers == Some(true) {
+                        result["reviewers"] = push_review_request(&github_client, &pr).await;
+                    }
+                    return Ok(result);
+                }
+                Err(e) => {
+                    warn!("Failed to open a draft PR for {}: {}", current_branch, e);
+                    return Ok(json!({
+                        "status": "success",
+                        "message": format!("✅ Pushed to feature branch: {}", current_branch),
+                        "branch": current_branch,
+                        "pull_request_error": e.to_string(),
+                        "suggestion": "The branch was pushed but the draft PR couldn't be opened; create it manually"
+                    }));
+                }
+            }
+        }
     }
 
     Ok(json!({
@@ -137,11 +568,144 @@ async fn execute_push_workflow(
     }))
 }
 
+/// Open a draft PR for a freshly pushed branch. The title comes from the task
+/// the branch was started for, else the latest commit; a linked task also
+/// puts `Closes #N` in the body. Returns the PR, the linked issue, and the
+/// labels and reviewers applied.
+async fn open_draft_pr(
+    state: &AppState,
+    github_client: &GitHubClient,
+    branch: &str,
+    main_branch: &str,
+) -> Result<(super::api::GitHubPullRequest, Option<u64>, pull_request::RuleActions)> {
+    let (repo_owner, repo_name) = get_remote_repository().await?;
+    let repository = format!("{}/{}", repo_owner, repo_name);
+
+    let link = sqlx::query!(
+        "SELECT issue_number FROM task_links WHERE repository = ? AND branch = ?",
+        repository,
+        branch
+    )
+    .fetch_optional(&state.db)
+    .await?;
+    let issue_number = link.map(|l| l.issue_number as u64);
+
+    let commit_message = git::run(|g, dir| g.head_message(dir)).await?;
+    let (subject, commit_body) = match commit_message.split_once('\n') {
+        Some((subject, body)) => (subject.trim().to_string(), body.trim().to_string()),
+        None => (commit_message.trim().to_string(), String::new()),
+    };
+    let title = match issue_number {
+        Some(number) => github_client.get_issue(&repo_owner, &repo_name, number).await?.title,
+        None if !subject.is_empty() => subject,
+        None => branch.to_string(),
+    };
+
+    let (pr, applied) = open_pull_request(github_client, &repo_owner, &repo_name, pull_request::NewPullRequest {
+        branch,
+        base: main_branch,
+        title: &title,
+        summary: &commit_body,
+        issue_number,
+        draft: true,
+    }).await?;
+
+    if issue_number.is_some() {
+        let pr_number = pr.number as i64;
+        sqlx::query!(
+            "UPDATE task_links SET pull_request_number = ? WHERE repository = ? AND branch = ?",
+            pr_number,
+            repository,
+            branch
+        )
+        .execute(&state.db)
+        .await?;
+    }
+
+    Ok((pr, issue_number, applied))
+}
+
+/// Request the code owners' review of a pushed branch's PR; a failure is
+/// reported in the result rather than failing a push that happened
+async fn push_review_request(github_client: &GitHubClient, pr: &super::api::GitHubPullRequest) -> Value {
+    let requested = match get_remote_repository().await {
+        Ok((repo_owner, repo_name)) => {
+            progress::report("👀 Requesting reviews from code owners");
+            request_codeowner_reviews(github_client, &repo_owner, &repo_name, pr, &[], false).await
+        }
+        Err(e) => Err(e),
+    };
+    requested.unwrap_or_else(|e| {
+        warn!("Failed to request reviewers on PR #{}: {}", pr.number, e);
+        json!({ "error": e.to_string() })
+    })
+}
+
+/// Open a PR with the repository's template filled in, then apply the labels
+/// and reviewers its `[[pull_request.rules]]` ask for. Failing to apply them,
+/// or to parse the workflow file they're in, is logged rather than failing
+/// the PR.
+async fn open_pull_request(
+    github_client: &GitHubClient,
+    repo_owner: &str,
+    repo_name: &str,
+    new: pull_request::NewPullRequest<'_>,
+) -> Result<(super::api::GitHubPullRequest, pull_request::RuleActions)> {
+    let root = get_repo_root().await?;
+    let rules = match super::repo_config::load_workflow_file(&root) {
+        Ok(file) => file.pull_request.rules,
+        Err(e) => {
+            warn!("Opening the PR without pull request rules: {}", e);
+            Vec::new()
+        }
+    };
+    let template = pull_request::load_template(&root);
+    let body = pull_request::body(template.as_deref(), new.summary, new.issue_number);
+
+    let pr = github_client
+        .create_pull_request(repo_owner, repo_name, new.title, new.branch, new.base, Some(&body), new.draft)
+        .await?;
+    info!("Opened PR #{} for {}", pr.number, new.branch);
+
+    let paths = match pull_request::needs_paths(&rules) {
+        true => match github_client.list_pull_request_files(repo_owner, repo_name, pr.number).await {
+            Ok(files) => files.into_iter().map(|f| f.filename).collect(),
+            Err(e) => {
+                warn!("Failed to list the files in PR #{}: {}", pr.number, e);
+                Vec::new()
+            }
+        },
+        false => Vec::new(),
+    };
+    let mut applied = pull_request::matching(&rules, new.branch, &paths);
+
+    if !applied.labels.is_empty() {
+        if let Err(e) = github_client.add_labels(repo_owner, repo_name, pr.number, &applied.labels).await {
+            warn!("Failed to label PR #{}: {}", pr.number, e);
+            applied.labels.clear();
+        }
+    }
+    // GitHub refuses to ask a PR's author to review it
+    applied.reviewers.retain(|reviewer| !reviewer.eq_ignore_ascii_case(&pr.user.login));
+    if !applied.reviewers.is_empty() || !applied.team_reviewers.is_empty() {
+        if let Err(e) = github_client
+            .request_reviewers(repo_owner, repo_name, pr.number, &applied.reviewers, &applied.team_reviewers)
+            .await
+        {
+            warn!("Failed to request reviewers on PR #{}: {}", pr.number, e);
+            applied.reviewers.clear();
+            applied.team_reviewers.clear();
+        }
+    }
+
+    Ok((pr, applied))
+}
+
 async fn execute_scan_tasks_workflow(
     state: AppState,
     project_number: Option<String>,
-    filter_type: Option<String>,
-    status: Option<String>,
+    owner: Option<String>,
+    filter: TaskFilter,
 ) -> Result<Value> {
     info!("Executing scan tasks workflow");
 
@@ -151,28 +715,26 @@ async fn execute_scan_tasks_workflow(
     } else {
         detect_project_number().await?
     };
+    let owner = resolve_project_owner(&state, owner).await?;
 
-    if let Ok(github_client) = get_github_client(state, None).await {
-        let mut tasks = github_client.get_project_items(&project_num).await?;
+    if let Ok(github_client) = get_github_client(state, caller::user_id()).await {
+        let mut tasks = github_client.get_project_items(&owner, &project_num).await?;
 
         // Apply filters
-        if let Some(task_type) = filter_type {
-            // TODO: Filter tasks by type
-            info!("Filtering tasks by type: {}", task_type);
-        }
-
-        if let Some(task_status) = status {
-            // TODO: Filter tasks by status
-            info!("Filtering tasks by status: {}", task_status);
+        if !filter.is_empty() {
+            info!("Filtering tasks: {:?}", filter);
+            tasks.retain(|task| filter.matches(task));
         }
 
         // Organize tasks by priority and type
-        let organized_tasks = organize_tasks_by_priority(tasks);
+        let organized_tasks = tasks::organize_by_priority(&tasks);
 
         Ok(json!({
             "status": "success",
             "project_number": project_num,
+            "owner": owner,
             "tasks": organized_tasks,
+            "filters": filter,
             "message": "📋 GitHub Project Tasks Available",
             "instructions": "Select a task number to start working on it"
         }))
@@ -181,59 +743,263 @@ async fn execute_scan_tasks_workflow(
     }
 }
 
+/// Scan several projects concurrently and build a cross-project
+/// "my plate today" summary grouped by priority and due date
+async fn execute_multi_project_scan(
+    state: AppState,
+    projects: Option<Vec<ProjectRef>>,
+    mut filter: TaskFilter,
+) -> Result<Value> {
+    info!("Executing multi-project scan tasks workflow");
+
+    let github_client = Arc::new(
+        get_github_client(state.clone(), caller::user_id())
+            .await
+            .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?,
+    );
+
+    let login = github_client.get_user().await?.login;
+    let projects = match projects {
+        Some(projects) => projects,
+        None => crate::preferences::load(&state.db, &login).await?.scan_projects,
+    };
+
+    if projects.is_empty() {
+        return Err(AppError::Validation(
+            "No projects to scan. Pass projects or save scan_projects with github_preferences".to_string(),
+        ));
+    }
+
+    // "My plate" defaults to the caller's own tasks
+    if filter.assignee.is_none() {
+        filter.assignee = Some(login);
+    }
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_PROJECT_SCANS));
+    let mut scans = JoinSet::new();
+    for project in projects {
+        let client = github_client.clone();
+        let semaphore = semaphore.clone();
+        scans.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let result = client.get_project_items(&project.owner, &project.project_number).await;
+            (project, result)
+        });
+    }
+
+    let mut summaries = Vec::new();
+    let mut project_reports = Vec::new();
+    while let Some(joined) = scans.join_next().await {
+        let (project, result) = joined.map_err(|e| AppError::Internal(format!("Project scan task failed: {}", e)))?;
+        match result {
+            Ok(items) => {
+                let matching: Vec<_> = items.iter().filter(|item| filter.matches(item)).collect();
+                for item in &matching {
+                    let mut summary = tasks::task_summary(item);
+                    summary["project"] = json!(project);
+                    summaries.push(summary);
+                }
+                project_reports.push(json!({
+                    "owner": project.owner,
+                    "project_number": project.project_number,
+                    "matching_tasks": matching.len()
+                }));
+            }
+            Err(e) => {
+                warn!("Failed to scan project {}/{}: {}", project.owner, project.project_number, e);
+                project_reports.push(json!({
+                    "owner": project.owner,
+                    "project_number": project.project_number,
+                    "error": e.to_string()
+                }));
+            }
+        }
+    }
+
+    let today = chrono::Utc::now().date_naive();
+    Ok(json!({
+        "status": "success",
+        "projects": project_reports,
+        "by_priority": tasks::organize_summaries_by_priority(&summaries),
+        "by_due_date": tasks::organize_by_due_date(&summaries, today),
+        "total": summaries.len(),
+        "filters": filter,
+        "message": "📋 Your plate today across all projects",
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }))
+}
+
 async fn execute_merge_workflow(
     state: AppState,
     branch: Option<String>,
     delete_branch: Option<bool>,
     cleanup_work_folder: Option<bool>,
+    ignore_checks: Option<bool>,
+    mode: Option<String>,
 ) -> Result<Value> {
     info!("Executing merge workflow");
 
-    let current_branch = branch.unwrap_or_else(|| get_current_branch().unwrap_or_else(|_| "main".to_string()));
-    let main_branch = get_main_branch().unwrap_or_else(|_| "main".to_string());
+    let mode = mode.unwrap_or_else(|| state.config.workflow.merge_mode.clone());
+    if !matches!(mode.as_str(), "immediate" | "auto" | "queue") {
+        return Err(AppError::Validation(format!("Unknown merge mode {}: expected immediate, auto or queue", mode)));
+    }
+    // Auto-merge and merge queues wait for CI and reviews themselves
+    let deferred = mode != "immediate";
+
+    let current_branch = match branch {
+        Some(branch) => branch,
+        None => get_current_branch().await.unwrap_or_else(|_| "main".to_string()),
+    };
+    let main_branch = get_main_branch().await.unwrap_or_else(|_| "main".to_string());
 
     if current_branch == main_branch {
         return Err(AppError::Validation("Already on main branch. Switch to feature branch first.".to_string()));
     }
 
     // Ensure all changes are committed
-    let git_status = get_git_status()?;
+    let git_status = get_git_status().await?;
     if !git_status.is_empty() {
         info!("Committing final changes");
-        commit_changes(&format!("Final changes for {}", current_branch))?;
+        progress::report("📝 Committing final changes");
+        commit_changes(&format!("Final changes for {}", current_branch)).await?;
+    }
+
+    if let Some(blocked) = secret_scan_block(&state, &current_branch).await? {
+        return Ok(blocked);
     }
 
     // Push final changes
-    push_branch(&current_branch)?;
+    progress::report(&format!("⬆️ Pushing {}", current_branch));
+    push_branch(&current_branch).await?;
 
-    if let Ok(github_client) = get_github_client(state.clone(), None).await {
+    if let Ok(github_client) = get_github_client(state.clone(), caller::user_id()).await {
         // Get PR for current branch
         let pr = get_pr_for_branch(&github_client, &current_branch).await?;
         
-        // TODO: Run tests here
         info!("🧪 Running final checks...");
-        
-        // TODO: Merge PR via GitHub API
+        progress::report("🧪 Running pre-merge checks");
+        let (checks_passed, checks) = run_merge_checks(&state).await?;
+        if !checks_passed {
+            return Ok(json!({
+                "status": "error",
+                "message": "❌ Pre-merge checks failed. Merge aborted.",
+                "pull_request": {
+                    "number": pr.number,
+                    "url": pr.html_url
+                },
+                "checks": checks
+            }));
+        }
+
+        let (repo_owner, repo_name) = get_remote_repository().await?;
+        progress::report(&format!("⏳ Checking CI for #{}", pr.number));
+        let ci = pull_request_checks(&github_client, &repo_owner, &repo_name, &pr).await?;
+        if !ci.is_passing() && !deferred {
+            if !ignore_checks.unwrap_or(false) {
+                return Ok(json!({
+                    "status": "error",
+                    "message": format!(
+                        "❌ CI checks are {} on {}. Merge aborted; pass ignore_checks to merge anyway.",
+                        ci.state,
+                        &ci.sha[..ci.sha.len().min(7)]
+                    ),
+                    "pull_request": {
+                        "number": pr.number,
+                        "url": pr.html_url
+                    },
+                    "ci": ci,
+                    "checks": checks
+                }));
+            }
+            warn!("Merging PR #{} with {} CI checks (ignore_checks set)", pr.number, ci.state);
+        }
+
+        if verified_commits_required(&state).await? {
+            let commits = github_client.list_pull_request_commits(&repo_owner, &repo_name, pr.number).await?;
+            let unverified: Vec<Value> = commits.iter().filter(|c| !c.is_verified()).map(commit_summary).collect();
+            if !unverified.is_empty() {
+                return Ok(json!({
+                    "status": "error",
+                    "message": format!("❌ {} commit(s) on this branch are not signed and verified. Merge aborted.", unverified.len()),
+                    "pull_request": {
+                        "number": pr.number,
+                        "url": pr.html_url
+                    },
+                    "unverified_commits": unverified,
+                    "checks": checks
+                }));
+            }
+        }
+
+        if deferred {
+            return defer_merge(&state, &github_client, &repo_owner, &repo_name, &pr, &current_branch, &mode, checks).await;
+        }
+
+        // Unmet review requirements otherwise surface as GitHub's generic 405
+        let base = &pr.base.ref_name;
+        if let Some(rules) = github_client.get_branch_protection(&repo_owner, &repo_name, base).await? {
+            let required = rules
+                .required_pull_request_reviews
+                .as_ref()
+                .map_or(0, |r| r.required_approving_review_count as usize);
+            if required > 0 {
+                let reviews = github_client.list_reviews(&repo_owner, &repo_name, pr.number).await?;
+                let approved = protection::approvals(&reviews);
+                if approved < required {
+                    return Ok(json!({
+                        "status": "error",
+                        "message": format!(
+                            "❌ {} requires {} approving review(s); PR #{} has {}. Merge aborted.",
+                            base, required, pr.number, approved
+                        ),
+                        "pull_request": {
+                            "number": pr.number,
+                            "url": pr.html_url
+                        },
+                        "protection": protection::summary(&rules),
+                        "checks": checks
+                    }));
+                }
+            }
+        }
+
         info!("🔀 Merging PR #{}", pr.number);
+        progress::report(&format!("🔀 Merging #{}", pr.number));
+        github_client.merge_pull_request(&repo_owner, &repo_name, pr.number, "merge", &pr.head.sha).await?;
+
+        // Update the linked project task; failures here don't undo the merge
+        let repository = format!("{}/{}", repo_owner, repo_name);
+        let task = match complete_linked_task(&state, &github_client, &repository, &current_branch, None, Some(pr.number)).await {
+            Ok(task) => task,
+            Err(e) => {
+                warn!("Failed to complete linked task for {}: {}", current_branch, e);
+                Some(json!({ "error": e.to_string() }))
+            }
+        };
         
         // Switch back to main and pull
-        checkout_branch(&main_branch)?;
-        pull_branch(&main_branch)?;
+        progress::report(&format!("🔄 Updating {}", main_branch));
+        checkout_branch(&main_branch).await?;
+        pull_branch(&main_branch).await?;
 
-        // Clean up work folder if requested
-        let work_folder_cleaned = if cleanup_work_folder.unwrap_or(false) {
-            // TODO: Implement work folder cleanup
-            true
-        } else {
-            false
+        // Clean up the work folder if requested; otherwise report what's there so the user can decide
+        let work_folder = match cleanup_branch_work_folder(&state, &current_branch, !cleanup_work_folder.unwrap_or(false)).await {
+            Ok(report) => json!(report),
+            Err(e) => {
+                warn!("Work folder cleanup for {} failed: {}", current_branch, e);
+                json!({ "error": e.to_string() })
+            }
         };
+        let work_folder_cleaned = work_folder["removed"].as_bool().unwrap_or(false);
 
         // Delete branch if requested
-        let branch_deleted = if delete_branch.unwrap_or(true) {
-            delete_local_branch(&current_branch)?;
-            true
+        let (branch_deleted, remote_branch) = if delete_branch.unwrap_or(true) {
+            delete_local_branch(&current_branch).await?;
+            progress::report(&format!("🧹 Deleting {} on GitHub", current_branch));
+            let remote = delete_merged_remote_branch(&github_client, &repo_owner, &repo_name, &pr).await;
+            (true, remote)
         } else {
-            false
+            (false, json!({ "deleted": false, "reason": "delete_branch is false" }))
         };
 
         Ok(json!({
@@ -246,7 +1012,13 @@ async fn execute_merge_workflow(
             },
             "current_branch": main_branch,
             "branch_deleted": branch_deleted,
+            "remote_branch": remote_branch,
             "work_folder_cleaned": work_folder_cleaned,
+            "work_folder": work_folder,
+            "checks": checks,
+            "ci": ci,
+            "checks_ignored": !ci.is_passing(),
+            "task": task,
             "timestamp": chrono::Utc::now().to_rfc3339()
         }))
     } else {
@@ -254,144 +1026,5155 @@ async fn execute_merge_workflow(
     }
 }
 
-// Git utility functions
-fn get_current_branch() -> Result<String> {
-    let output = Command::new("git")
-        .args(["branch", "--show-current"])
-        .output()
-        .map_err(|e| AppError::Internal(format!("Failed to get current branch: {}", e)))?;
+async fn execute_start_task_workflow(
+    state: AppState,
+    issue_number: u64,
+    project_number: Option<String>,
+    owner: Option<String>,
+    open_draft_pr: Option<bool>,
+) -> Result<Value> {
+    info!("Executing start task workflow for #{}", issue_number);
 
-    if !output.status.success() {
-        return Err(AppError::Internal("Git command failed".to_string()));
-    }
+    let (repo_owner, repo_name) = get_remote_repository().await?;
+    let main_branch = get_main_branch().await.unwrap_or_else(|_| "main".to_string());
 
-    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    Ok(branch)
-}
+    let git_status = get_git_status().await?;
+    if !git_status.is_empty() {
+        return Ok(json!({
+            "status": "error",
+            "message": "⚠️ Uncommitted changes detected. Commit or stash them before starting a new task.",
+            "uncommitted_changes": git_status
+        }));
+    }
 
-fn get_main_branch() -> Result<String> {
-    let output = Command::new("git")
-        .args(["remote", "show", "origin"])
-        .output()
-        .map_err(|e| AppError::Internal(format!("Failed to get main branch: {}", e)))?;
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
 
-    if !output.status.success() {
-        return Ok("main".to_string()); // Default fallback
+    let issue = github_client.get_issue(&repo_owner, &repo_name, issue_number).await?;
+    if issue.state != "open" {
+        return Err(AppError::Validation(format!("Issue #{} is {}", issue_number, issue.state)));
     }
 
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    for line in output_str.lines() {
-        if line.contains("HEAD branch:") {
-            if let Some(branch) = line.split(':').nth(1) {
-                return Ok(branch.trim().to_string());
+    // Claim the issue for the caller
+    let login = caller::require()?.login;
+    github_client.add_assignees(&repo_owner, &repo_name, issue_number, &[login.as_str()]).await?;
+
+    // Move the linked project item to In Progress, if the issue is on the board
+    let project_owner = resolve_project_owner(&state, owner).await?;
+    let project_number = match project_number {
+        Some(number) => Some(number),
+        None => detect_project_number().await.ok(),
+    };
+    let repository = format!("{}/{}", repo_owner, repo_name);
+
+    let mut project_item_id = None;
+    let mut project_status = None;
+    if let Some(number) = &project_number {
+        match move_task_status(&github_client, &project_owner, number, &repository, issue_number, "In Progress").await {
+            Ok(item_id) => {
+                project_status = item_id.as_ref().map(|_| "In Progress");
+                project_item_id = item_id;
             }
+            Err(e) => warn!("Failed to update project status for #{}: {}", issue_number, e),
         }
     }
 
-    Ok("main".to_string()) // Default fallback
-}
-
-fn get_git_status() -> Result<Vec<String>> {
-    let output = Command::new("git")
-        .args(["status", "--porcelain"])
-        .output()
-        .map_err(|e| AppError::Internal(format!("Failed to get git status: {}", e)))?;
+    // Create and check out the working branch
+    let branch = task_branch_name(&issue);
+    checkout_branch(&main_branch).await?;
+    pull_branch(&main_branch).await?;
+    create_branch(&branch).await?;
 
-    if !output.status.success() {
-        return Err(AppError::Internal("Git status command failed".to_string()));
+    let mut pull_request = None;
+    if open_draft_pr.unwrap_or(false) {
+        // GitHub refuses PRs without commits, so seed the branch with an empty one
+        commit_empty(&format!("Start work on #{}", issue_number)).await?;
+        push_branch(&branch).await?;
+        let (pr, applied) = open_pull_request(&github_client, &repo_owner, &repo_name, pull_request::NewPullRequest {
+            branch: &branch,
+            base: &main_branch,
+            title: &issue.title,
+            summary: "",
+            issue_number: Some(issue_number),
+            draft: true,
+        }).await?;
+        pull_request = Some(json!({
+            "number": pr.number,
+            "url": pr.html_url,
+            "draft": pr.draft,
+            "applied": applied
+        }));
     }
 
-    let status_lines: Vec<String> = String::from_utf8_lossy(&output.stdout)
-        .lines()
-        .map(|line| line.to_string())
-        .collect();
+    let pr_number = pull_request.as_ref().and_then(|pr| pr["number"].as_i64());
+    let issue_number_db = issue_number as i64;
+    sqlx::query!(
+        r#"
+        INSERT INTO task_links
+        (repository, branch, issue_number, project_owner, project_number, project_item_id, assignee, status, pull_request_number)
+        VALUES (?, ?, ?, ?, ?, ?, ?, 'in_progress', ?)
+        ON CONFLICT(repository, branch) DO UPDATE SET
+            issue_number = excluded.issue_number,
+            project_item_id = excluded.project_item_id,
+            status = 'in_progress',
+            pull_request_number = excluded.pull_request_number,
+            started_at = datetime('now'),
+            completed_at = NULL
+        "#,
+        repository,
+        branch,
+        issue_number_db,
+        project_owner,
+        project_number,
+        project_item_id,
+        login,
+        pr_number
+    )
+    .execute(&state.db)
+    .await?;
 
-    Ok(status_lines)
+    Ok(json!({
+        "status": "success",
+        "message": format!("🚀 Started work on #{}: {}", issue_number, issue.title),
+        "issue": {
+            "number": issue.number,
+            "title": issue.title,
+            "url": issue.html_url
+        },
+        "assignee": login,
+        "branch": branch,
+        "project_status": project_status,
+        "pull_request": pull_request,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }))
 }
 
-fn commit_changes(message: &str) -> Result<()> {
-    // Add all changes
-    let add_output = Command::new("git")
-        .args(["add", "."])
-        .output()
-        .map_err(|e| AppError::Internal(format!("Failed to add changes: {}", e)))?;
+/// Turn a planning-stage draft item into a real issue so it can be started
+async fn execute_promote_draft_workflow(
+    state: AppState,
+    item_id: Option<String>,
+    title: Option<String>,
+    repository: Option<String>,
+    project_number: Option<String>,
+    owner: Option<String>,
+    assignee: Option<String>,
+) -> Result<Value> {
+    info!("Executing promote draft workflow");
 
-    if !add_output.status.success() {
-        return Err(AppError::Internal("Git add command failed".to_string()));
+    if item_id.is_none() && title.is_none() {
+        return Err(AppError::Validation("Pass item_id or title to identify the draft".to_string()));
     }
 
-    // Commit changes
-    let commit_output = Command::new("git")
-        .args(["commit", "-m", message])
-        .output()
-        .map_err(|e| AppError::Internal(format!("Failed to commit changes: {}", e)))?;
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
 
-    if !commit_output.status.success() {
-        return Err(AppError::Internal("Git commit command failed".to_string()));
-    }
+    let project_owner = resolve_project_owner(&state, owner).await?;
+    let project_number = match project_number {
+        Some(number) => number,
+        None => detect_project_number().await?,
+    };
 
-    Ok(())
-}
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let items = github_client.get_project_items(&project_owner, &project_number).await?;
+    let drafts: Vec<_> = items
+        .iter()
+        .filter(|item| item.content.as_ref().map_or(false, |c| c.content_type == "DraftIssue"))
+        .filter(|item| match (&item_id, &title) {
+            (Some(id), _) => &item.id == id,
+            (None, Some(title)) => item.content.as_ref().map_or(false, |c| c.title.eq_ignore_ascii_case(title)),
+            (None, None) => false,
+        })
+        .collect();
 
-fn push_branch(branch: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args(["push", "origin", branch])
-        .output()
-        .map_err(|e| AppError::Internal(format!("Failed to push branch: {}", e)))?;
+    let draft = match drafts.as_slice() {
+        [draft] => *draft,
+        [] => return Err(AppError::Validation("No matching draft item found in the project".to_string())),
+        _ => return Err(AppError::Validation("Several drafts match that title; pass item_id instead".to_string())),
+    };
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(AppError::Internal(format!("Git push failed: {}", stderr)));
-    }
+    let repository_id = github_client.get_repository_node_id(&repo_owner, &repo_name).await?;
+    let issue_number = github_client.convert_draft_issue(&draft.id, &repository_id).await?;
+    info!("Promoted draft {} to {}/{}#{}", draft.id, repo_owner, repo_name, issue_number);
 
-    Ok(())
+    // Draft assignees don't always carry over, so assign explicitly
+    let assignees = match assignee {
+        Some(assignee) => vec![assignee.trim_start_matches('@').to_string()],
+        None => {
+            let existing = draft.content.as_ref().map(|c| c.assignees.clone()).unwrap_or_default();
+            if existing.is_empty() {
+                vec![github_client.get_user().await?.login]
+            } else {
+                existing
+            }
+        }
+    };
+    let assignee_refs: Vec<&str> = assignees.iter().map(String::as_str).collect();
+    let issue = github_client.add_assignees(&repo_owner, &repo_name, issue_number, &assignee_refs).await?;
+
+    // The item keeps its ID, so its field values (status, priority, dates) carry over
+    Ok(json!({
+        "status": "success",
+        "message": format!("📝 Draft promoted to issue #{}", issue_number),
+        "issue": {
+            "number": issue.number,
+            "url": issue.html_url,
+            "title": issue.title
+        },
+        "project_item_id": draft.id,
+        "assignees": assignees,
+        "fields": tasks::task_summary(draft),
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }))
 }
 
-fn pull_branch(branch: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args(["pull", "origin", branch])
-        .output()
-        .map_err(|e| AppError::Internal(format!("Failed to pull branch: {}", e)))?;
+async fn execute_get_checklist(state: AppState, number: u64, repository: Option<String>) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(AppError::Internal(format!("Git pull failed: {}", stderr)));
-    }
+    // The issues API serves PRs too, so one lookup covers both
+    let issue = github_client.get_issue(&repo_owner, &repo_name, number).await?;
+    let items = checklist::parse_checklist(issue.body.as_deref().unwrap_or_default());
+    let done = items.iter().filter(|i| i.checked).count();
 
-    Ok(())
+    Ok(json!({
+        "number": number,
+        "title": issue.title,
+        "url": issue.html_url,
+        "items": items,
+        "completed": done,
+        "total": items.len(),
+        "all_done": !items.is_empty() && done == items.len()
+    }))
 }
 
-fn checkout_branch(branch: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args(["checkout", branch])
-        .output()
-        .map_err(|e| AppError::Internal(format!("Failed to checkout branch: {}", e)))?;
+async fn execute_update_checklist(
+    state: AppState,
+    number: u64,
+    repository: Option<String>,
+    items: Option<Vec<usize>>,
+    text: Option<String>,
+    checked: Option<bool>,
+) -> Result<Value> {
+    let indices = items.unwrap_or_default();
+    if indices.is_empty() && text.is_none() {
+        return Err(AppError::Validation("Pass items or text to select checklist entries".to_string()));
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(AppError::Internal(format!("Git checkout failed: {}", stderr)));
+    // Patch the freshest body so concurrent edits elsewhere aren't clobbered
+    let issue = github_client.get_issue(&repo_owner, &repo_name, number).await?;
+    let body = issue.body.unwrap_or_default();
+    let (patched, updated) = checklist::set_checked(&body, &indices, text.as_deref(), checked.unwrap_or(true))?;
+
+    if patched != body {
+        github_client.update_issue(&repo_owner, &repo_name, number, &json!({ "body": patched })).await?;
     }
 
-    Ok(())
+    let all = checklist::parse_checklist(&patched);
+    let done = all.iter().filter(|i| i.checked).count();
+    Ok(json!({
+        "status": "success",
+        "number": number,
+        "updated": updated,
+        "completed": done,
+        "total": all.len(),
+        "all_done": done == all.len()
+    }))
 }
 
-fn delete_local_branch(branch: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args(["branch", "-d", branch])
-        .output()
-        .map_err(|e| AppError::Internal(format!("Failed to delete branch: {}", e)))?;
+fn issue_summary(issue: &super::api::GitHubIssue) -> Value {
+    json!({
+        "number": issue.number,
+        "title": issue.title,
+        "state": issue.state,
+        "labels": issue.labels.iter().map(|l| l.name.clone()).collect::<Vec<_>>(),
+        "url": issue.html_url
+    })
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        warn!("Failed to delete branch {}: {}", branch, stderr);
+async fn execute_create_issue(
+    state: AppState,
+    repository: Option<String>,
+    title: String,
+    body: Option<String>,
+    labels: Option<Vec<String>>,
+    assignees: Option<Vec<String>>,
+) -> Result<Value> {
+    if title.trim().is_empty() {
+        return Err(AppError::Validation("Issue title cannot be empty".to_string()));
     }
 
-    Ok(())
-}
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let labels: Option<Vec<&str>> = labels.as_ref().map(|l| l.iter().map(String::as_str).collect());
+    let mut issue = github_client
+        .create_issue(&repo_owner, &repo_name, title.trim(), body.as_deref(), labels)
+        .await?;
+
+    if let Some(assignees) = assignees.filter(|a| !a.is_empty()) {
+        let assignees: Vec<&str> = assignees.iter().map(|a| a.trim_start_matches('@')).collect();
+        issue = github_client.add_assignees(&repo_owner, &repo_name, issue.number, &assignees).await?;
+    }
+
+    info!("Created issue #{} in {}/{}", issue.number, repo_owner, repo_name);
+    Ok(json!({
+        "status": "success",
+        "message": format!("📝 Created issue #{}", issue.number),
+        "issue": issue_summary(&issue)
+    }))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn execute_update_issue(
+    state: AppState,
+    repository: Option<String>,
+    number: u64,
+    title: Option<String>,
+    body: Option<String>,
+    issue_state: Option<String>,
+    labels: Option<Vec<String>>,
+    assignees: Option<Vec<String>>,
+) -> Result<Value> {
+    let mut changes = serde_json::Map::new();
+    if let Some(title) = title {
+        if title.trim().is_empty() {
+            return Err(AppError::Validation("Issue title cannot be empty".to_string()));
+        }
+        changes.insert("title".to_string(), json!(title.trim()));
+    }
+    if let Some(body) = body {
+        changes.insert("body".to_string(), json!(body));
+    }
+    if let Some(issue_state) = issue_state {
+        if issue_state != "open" && issue_state != "closed" {
+            return Err(AppError::Validation(format!("Invalid issue state '{}': use open or closed", issue_state)));
+        }
+        changes.insert("state".to_string(), json!(issue_state));
+    }
+    if let Some(labels) = labels {
+        changes.insert("labels".to_string(), json!(labels));
+    }
+    if let Some(assignees) = assignees {
+        let assignees: Vec<&str> = assignees.iter().map(|a| a.trim_start_matches('@')).collect();
+        changes.insert("assignees".to_string(), json!(assignees));
+    }
+    if changes.is_empty() {
+        return Err(AppError::Validation("Pass at least one of title, body, state, labels or assignees".to_string()));
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let issue = github_client
+        .update_issue(&repo_owner, &repo_name, number, &Value::Object(changes))
+        .await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("✏️ Updated issue #{}", number),
+        "issue": issue_summary(&issue)
+    }))
+}
+
+async fn execute_comment_issue(state: AppState, repository: Option<String>, number: u64, body: String) -> Result<Value> {
+    if body.trim().is_empty() {
+        return Err(AppError::Validation("Comment body cannot be empty".to_string()));
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let comment = github_client.create_issue_comment(&repo_owner, &repo_name, number, &body).await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("💬 Commented on #{}", number),
+        "comment": {
+            "id": comment["id"],
+            "url": comment["html_url"]
+        }
+    }))
+}
+
+async fn execute_close_issue(
+    state: AppState,
+    repository: Option<String>,
+    number: u64,
+    reason: Option<String>,
+    comment: Option<String>,
+    lock: Option<bool>,
+    lock_reason: Option<String>,
+) -> Result<Value> {
+    let reason = reason.unwrap_or_else(|| "completed".to_string());
+    if reason != "completed" && reason != "not_planned" {
+        return Err(AppError::Validation(format!("Invalid close reason '{}': use completed or not_planned", reason)));
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    if let Some(comment) = comment.filter(|c| !c.trim().is_empty()) {
+        github_client.create_issue_comment(&repo_owner, &repo_name, number, &comment).await?;
+    }
+
+    let issue = github_client.close_issue(&repo_owner, &repo_name, number, Some(&reason)).await?;
+
+    let locked = lock.unwrap_or(false) || lock_reason.is_some();
+    if locked {
+        github_client.lock_issue(&repo_owner, &repo_name, number, lock_reason.as_deref()).await?;
+    }
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("✅ Closed issue #{} as {}", number, reason),
+        "issue": issue_summary(&issue),
+        "locked": locked
+    }))
+}
+
+/// Upper bound on issues touched by one bulk update
+const BULK_UPDATE_MAX_ISSUES: usize = 500;
+
+struct BulkIssueChanges {
+    add_labels: Vec<String>,
+    remove_labels: Vec<String>,
+    milestone: Option<String>,
+    close: bool,
+    close_reason: Option<String>,
+    comment: Option<String>,
+}
+
+impl BulkIssueChanges {
+    fn is_empty(&self) -> bool {
+        self.add_labels.is_empty()
+            && self.remove_labels.is_empty()
+            && self.milestone.is_none()
+            && !self.close
+            && self.comment.is_none()
+    }
+
+    /// What would change on `issue`; empty when it's already in the target state
+    fn plan(&self, issue: &super::api::GitHubIssue, milestone: &Option<Option<u64>>) -> Vec<String> {
+        let has_label = |name: &str| issue.labels.iter().any(|l| l.name.eq_ignore_ascii_case(name));
+        let mut plan = Vec::new();
+
+        for label in self.add_labels.iter().filter(|l| !has_label(l)) {
+            plan.push(format!("add label {}", label));
+        }
+        for label in self.remove_labels.iter().filter(|l| has_label(l)) {
+            plan.push(format!("remove label {}", label));
+        }
+        if let Some(target) = milestone {
+            if issue.milestone.as_ref().map(|m| m.number) != *target {
+                plan.push(match target {
+                    Some(number) => format!("set milestone #{}", number),
+                    None => "clear milestone".to_string(),
+                });
+            }
+        }
+        if let Some(comment) = &self.comment {
+            plan.push(format!("comment \"{}\"", comment.lines().next().unwrap_or_default()));
+        }
+        if self.close && issue.state != "closed" {
+            plan.push(format!("close as {}", self.close_reason.as_deref().unwrap_or("completed")));
+        }
+
+        plan
+    }
+}
+
+/// Resolve a milestone given as a number, a title or `none`
+async fn resolve_milestone(
+    github_client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    milestone: Option<&str>,
+) -> Result<Option<Option<u64>>> {
+    let Some(milestone) = milestone else {
+        return Ok(None);
+    };
+    if milestone.eq_ignore_ascii_case("none") {
+        return Ok(Some(None));
+    }
+    if let Ok(number) = milestone.trim_start_matches('#').parse::<u64>() {
+        return Ok(Some(Some(number)));
+    }
+
+    let milestones = github_client.list_milestones(owner, repo, "all").await?;
+    milestones
+        .iter()
+        .find(|m| m.title.eq_ignore_ascii_case(milestone))
+        .map(|m| Some(Some(m.number)))
+        .ok_or_else(|| AppError::Validation(format!("Milestone '{}' not found", milestone)))
+}
+
+fn label_summary(label: &super::api::GitHubLabel) -> Value {
+    json!({
+        "name": label.name,
+        "color": label.color,
+        "description": label.description
+    })
+}
+
+async fn execute_list_labels(state: AppState, repository: Option<String>) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let mut list = github_client.list_labels(&repo_owner, &repo_name).await?;
+    list.sort_by_key(|l| l.name.to_lowercase());
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("🏷️ {} label(s) in {}/{}", list.len(), repo_owner, repo_name),
+        "labels": list.iter().map(label_summary).collect::<Vec<_>>()
+    }))
+}
+
+async fn execute_create_label(
+    state: AppState,
+    repository: Option<String>,
+    name: String,
+    color: String,
+    description: Option<String>,
+) -> Result<Value> {
+    let mut spec = [labels::LabelSpec { name, color, description, aliases: Vec::new() }];
+    labels::validate(&mut spec)?;
+    let [spec] = spec;
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let mut payload = json!({ "name": spec.name, "color": spec.color });
+    if let Some(description) = spec.description {
+        payload["description"] = Value::String(description);
+    }
+    let label = github_client.create_label(&repo_owner, &repo_name, &payload).await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("🏷️ Created label {}", label.name),
+        "label": label_summary(&label)
+    }))
+}
+
+async fn execute_update_label(
+    state: AppState,
+    repository: Option<String>,
+    name: String,
+    new_name: Option<String>,
+    color: Option<String>,
+    description: Option<String>,
+) -> Result<Value> {
+    let mut changes = serde_json::Map::new();
+    if let Some(new_name) = new_name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()) {
+        changes.insert("new_name".to_string(), Value::String(new_name));
+    }
+    if let Some(color) = color {
+        let normalized = labels::normalize_color(&color)
+            .ok_or_else(|| AppError::Validation(format!("Invalid color '{}': use six hex digits", color)))?;
+        changes.insert("color".to_string(), Value::String(normalized));
+    }
+    if let Some(description) = description {
+        changes.insert("description".to_string(), Value::String(description));
+    }
+    if changes.is_empty() {
+        return Err(AppError::Validation("Pass at least one of new_name, color or description".to_string()));
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let label = github_client.update_label(&repo_owner, &repo_name, &name, &Value::Object(changes)).await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("🏷️ Updated label {}", label.name),
+        "label": label_summary(&label)
+    }))
+}
+
+async fn execute_delete_label(state: AppState, repository: Option<String>, name: String) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    if !github_client.delete_label(&repo_owner, &repo_name, &name).await? {
+        return Err(super::error::GitHubError::not_found(format!("Label {} not found in {}/{}", name, repo_owner, repo_name)).into());
+    }
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("🗑️ Deleted label {}", name)
+    }))
+}
+
+/// The label set to sync and where it came from: the arguments, the
+/// workflow file's `[labels]`, or a YAML file in the checkout or in `source`
+async fn load_label_set(
+    state: &AppState,
+    github_client: &GitHubClient,
+    labels: Option<Vec<labels::LabelSpec>>,
+    file: Option<String>,
+    source: Option<String>,
+    settings: &super::repo_config::LabelSettings,
+) -> Result<(Vec<labels::LabelSpec>, String)> {
+    if let Some(labels) = labels {
+        return Ok((labels, "arguments".to_string()));
+    }
+    if file.is_none() && source.is_none() && !settings.set.is_empty() {
+        return Ok((settings.set.clone(), super::repo_config::WORKFLOW_FILE.to_string()));
+    }
+
+    let path = file
+        .or_else(|| settings.file.clone())
+        .unwrap_or_else(|| labels::LABELS_FILE.to_string());
+    if path.is_empty() || !Path::new(&path).components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(AppError::Validation(format!("Invalid label file '{}': give a path inside the repository", path)));
+    }
+    let (text, origin) = match source {
+        Some(source) => {
+            let (owner, name) = resolve_repository(Some(source.clone())).await?;
+            authorize_repository(state, &format!("{}/{}", owner, name), "sync_labels", permissions::Operation::Read).await?;
+            let content = github_client
+                .get_file(&owner, &name, &path, None)
+                .await?
+                .ok_or_else(|| super::error::GitHubError::not_found(format!("{} not found in {}", path, source)))?;
+            let text = String::from_utf8(content.decoded()?)
+                .map_err(|_| AppError::Validation(format!("{} is not UTF-8", path)))?;
+            (text, format!("{}:{}", source, path))
+        }
+        None => {
+            let not_found = || AppError::Validation(format!("No label set found: {} doesn't exist. Pass labels, file or source", path));
+            let root = tokio::fs::canonicalize(get_repo_root().await?)
+                .await
+                .map_err(|e| AppError::Internal(format!("Cannot resolve the repository root: {}", e)))?;
+            // A symlink in the checkout could still lead out of it
+            let file = tokio::fs::canonicalize(root.join(&path)).await.map_err(|_| not_found())?;
+            if !file.starts_with(&root) {
+                return Err(AppError::Validation(format!("Label file {} is outside the repository", path)));
+            }
+            let text = tokio::fs::read_to_string(&file).await.map_err(|_| not_found())?;
+            (text, path)
+        }
+    };
+    Ok((labels::parse_yaml(&text)?, origin))
+}
+
+/// Make each planned change; returns the ones that failed
+async fn apply_label_changes(
+    github_client: &GitHubClient,
+    owner: &str,
+    name: &str,
+    changes: &[labels::LabelChange],
+) -> Vec<Value> {
+    let mut errors = Vec::new();
+    for change in changes {
+        let current = change.from.as_deref().unwrap_or(&change.name);
+        let applied = match change.action {
+            "create" => {
+                let mut payload = json!({ "name": change.name, "color": change.color });
+                if let Some(description) = &change.description {
+                    payload["description"] = Value::String(description.clone());
+                }
+                github_client.create_label(owner, name, &payload).await.map(|_| ())
+            }
+            "update" => {
+                let mut payload = json!({ "new_name": change.name, "color": change.color });
+                if let Some(description) = &change.description {
+                    payload["description"] = Value::String(description.clone());
+                }
+                github_client.update_label(owner, name, current, &payload).await.map(|_| ())
+            }
+            _ => github_client.delete_label(owner, name, &change.name).await.map(|_| ()),
+        };
+        if let Err(e) = applied {
+            warn!("Failed to {} label {} in {}/{}: {}", change.action, change.name, owner, name, e);
+            errors.push(json!({ "action": change.action, "name": change.name, "error": e.to_string() }));
+        }
+    }
+    errors
+}
+
+async fn execute_sync_labels(
+    state: AppState,
+    repositories: Option<Vec<String>>,
+    labels: Option<Vec<labels::LabelSpec>>,
+    file: Option<String>,
+    source: Option<String>,
+    prune: Option<bool>,
+    confirm: Option<bool>,
+) -> Result<Value> {
+    let confirm = confirm.unwrap_or(false);
+    let settings = match get_repo_root().await {
+        Ok(root) => super::repo_config::load_workflow_file(&root)?.labels,
+        Err(_) => Default::default(),
+    };
+    let prune = prune.or(settings.prune).unwrap_or(false);
+
+    let repositories = match repositories.filter(|r| !r.is_empty()) {
+        Some(repositories) => repositories,
+        None => {
+            let (owner, name) = get_remote_repository().await?;
+            vec![format!("{}/{}", owner, name)]
+        }
+    };
+    // A single repository was authorized with the command itself
+    if repositories.len() > 1 {
+        let operation = if confirm { permissions::Operation::Push } else { permissions::Operation::Read };
+        for repository in &repositories {
+            authorize_repository(&state, repository, "sync_labels", operation).await?;
+        }
+    }
+
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let (mut wanted, origin) = load_label_set(&state, &github_client, labels, file, source, &settings).await?;
+    labels::validate(&mut wanted)?;
+    if wanted.is_empty() && prune {
+        return Err(AppError::Validation("The label set is empty; pruning would delete every label".to_string()));
+    }
+
+    let mut results = Vec::new();
+    let mut total = 0;
+    let mut failed = 0;
+    for repository in &repositories {
+        let (owner, name) = resolve_repository(Some(repository.clone())).await?;
+        let existing = match github_client.list_labels(&owner, &name).await {
+            Ok(existing) => existing,
+            Err(e) => {
+                failed += 1;
+                results.push(json!({ "repository": repository, "status": "failed", "error": e.to_string() }));
+                continue;
+            }
+        };
+
+        let changes = labels::plan(&wanted, &existing, prune);
+        total += changes.len();
+        if !confirm || changes.is_empty() {
+            results.push(json!({ "repository": repository, "status": "planned", "changes": changes }));
+            continue;
+        }
+
+        let errors = apply_label_changes(&github_client, &owner, &name, &changes).await;
+        progress::report(&format!("🏷️ {}: {} change(s)", repository, changes.len() - errors.len()));
+
+        if !errors.is_empty() {
+            failed += 1;
+        }
+        results.push(json!({
+            "repository": repository,
+            "status": if errors.is_empty() { "updated" } else { "partial" },
+            "changes": changes,
+            "errors": errors
+        }));
+    }
+
+    let message = if confirm {
+        format!("🏷️ Synced labels from {} to {} repositories, {} with failures", origin, repositories.len(), failed)
+    } else {
+        format!("🏷️ {} label change(s) needed across {} repositories; re-run with confirm: true to apply", total, repositories.len())
+    };
+    Ok(json!({
+        "status": match (confirm, failed) {
+            (false, _) => "preview",
+            (true, 0) => "success",
+            (true, _) => "partial",
+        },
+        "message": message,
+        "source": origin,
+        "prune": prune,
+        "labels": wanted.len(),
+        "results": results
+    }))
+}
+
+async fn execute_list_discussions(
+    state: AppState,
+    repository: Option<String>,
+    category: Option<String>,
+    discussion_state: Option<String>,
+    answered: Option<bool>,
+    limit: Option<usize>,
+) -> Result<Value> {
+    let limit = limit.unwrap_or(discussions::DEFAULT_LIMIT).clamp(1, discussions::MAX_LIMIT);
+    let states = discussions::states(discussion_state.as_deref().unwrap_or("open"))?;
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let (_, categories) = github_client.list_discussion_categories(&repo_owner, &repo_name).await?;
+    let category = category
+        .as_deref()
+        .map(|name| discussions::find_category(&categories, name))
+        .transpose()?;
+
+    let found = github_client
+        .list_discussions(&repo_owner, &repo_name, category.map(|c| c.id.as_str()), states, answered, limit)
+        .await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("💬 {} discussion(s) in {}/{}", found.len(), repo_owner, repo_name),
+        "categories": categories.iter().map(discussions::category).collect::<Vec<_>>(),
+        "discussions": found.iter().map(discussions::summary).collect::<Vec<_>>()
+    }))
+}
+
+async fn execute_get_discussion(state: AppState, repository: Option<String>, number: u64) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let (discussion, comments) = github_client.get_discussion(&repo_owner, &repo_name, number).await?;
+
+    let mut summary = discussions::summary(&discussion);
+    summary["body"] = json!(discussion.body);
+    summary["answerable"] = json!(discussion.category.is_answerable);
+    Ok(json!({
+        "status": "success",
+        "discussion": summary,
+        "comments": comments.iter().map(discussions::comment).collect::<Vec<_>>(),
+        "comments_truncated": (comments.len() as u64) < discussion.comment_count
+    }))
+}
+
+async fn execute_create_discussion(
+    state: AppState,
+    repository: Option<String>,
+    category: String,
+    title: String,
+    body: String,
+) -> Result<Value> {
+    if title.trim().is_empty() {
+        return Err(AppError::Validation("Discussion title cannot be empty".to_string()));
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let (repository_id, categories) = github_client.list_discussion_categories(&repo_owner, &repo_name).await?;
+    let category = discussions::find_category(&categories, &category)?;
+
+    let (number, url) = github_client
+        .create_discussion(&repository_id, &category.id, title.trim(), &body)
+        .await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("💬 Started discussion #{} in {}", number, category.name),
+        "discussion": { "number": number, "url": url, "title": title.trim(), "category": category.name }
+    }))
+}
+
+async fn execute_reply_discussion(
+    state: AppState,
+    repository: Option<String>,
+    number: u64,
+    body: String,
+    reply_to: Option<String>,
+) -> Result<Value> {
+    if body.trim().is_empty() {
+        return Err(AppError::Validation("Reply body cannot be empty".to_string()));
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let (discussion, comments) = github_client.get_discussion(&repo_owner, &repo_name, number).await?;
+    // GitHub only nests one level deep, so a reply must go under a top-level comment
+    if let Some(reply_to) = &reply_to {
+        if comments.iter().any(|c| c.replies.iter().any(|r| &r.id == reply_to)) {
+            return Err(AppError::Validation(
+                "That comment is itself a reply; reply to the top-level comment it's under".to_string(),
+            ));
+        }
+    }
+
+    let (id, url) = github_client
+        .add_discussion_comment(&discussion.id, &body, reply_to.as_deref())
+        .await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("💬 Replied to discussion #{}", number),
+        "comment": { "id": id, "url": url, "reply_to": reply_to }
+    }))
+}
+
+async fn execute_answer_discussion(
+    state: AppState,
+    repository: Option<String>,
+    comment_id: String,
+    unmark: Option<bool>,
+) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    // The command was authorized for this repository, so the comment must be in it
+    let full_name = format!("{}/{}", repo_owner, repo_name);
+    let belongs_to = github_client.discussion_comment_repository(&comment_id).await?;
+    if !belongs_to.eq_ignore_ascii_case(&full_name) {
+        return Err(AppError::Validation(format!("Comment {} is in {}, not {}", comment_id, belongs_to, full_name)));
+    }
+
+    let answer = !unmark.unwrap_or(false);
+    let url = github_client.mark_discussion_answer(&comment_id, answer).await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": if answer { "✅ Marked the comment as the answer" } else { "↩️ Unmarked the answer" },
+        "comment_id": comment_id,
+        "discussion_url": url
+    }))
+}
+
+/// Dependabot, code scanning and secret scanning alerts, most severe first
+async fn execute_list_security_alerts(
+    state: AppState,
+    repository: Option<String>,
+    kinds: Option<Vec<String>>,
+    severity: Option<String>,
+    alert_state: Option<String>,
+    limit: Option<usize>,
+) -> Result<Value> {
+    let limit = limit.unwrap_or(alerts::DEFAULT_LIMIT).clamp(1, alerts::MAX_LIMIT);
+    let kinds = match kinds.filter(|kinds| !kinds.is_empty()) {
+        Some(kinds) => kinds.iter().map(|k| alerts::AlertKind::parse(k)).collect::<Result<Vec<_>>>()?,
+        None => alerts::ALL_KINDS.to_vec(),
+    };
+    let min_rank = match &severity {
+        Some(severity) => alerts::severity_rank(severity).ok_or_else(|| {
+            AppError::Validation(format!("Unknown severity {}: expected {}", severity, alerts::SEVERITIES.join(", ")))
+        })?,
+        None => 0,
+    };
+    let alert_state = alert_state.unwrap_or_else(|| "open".to_string());
+    if !matches!(alert_state.as_str(), "open" | "closed" | "all") {
+        return Err(AppError::Validation(format!("Unknown state {}: expected open, closed or all", alert_state)));
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let mut found = Vec::new();
+    let mut unavailable = Vec::new();
+    for kind in kinds {
+        progress::report(&format!("🛡️ Fetching {} alerts", kind.name()));
+        // Code scanning severities are filtered below, over more alerts
+        let severity_param = kind.severity_param(min_rank);
+        let max_items = if kind == alerts::AlertKind::CodeScanning && min_rank > 0 { alerts::SCAN_LIMIT } else { limit };
+        let fetched = github_client
+            .list_security_alerts(
+                &repo_owner,
+                &repo_name,
+                kind.api_path(),
+                kind.state_param(&alert_state),
+                severity_param.as_deref(),
+                max_items,
+            )
+            .await;
+        match fetched {
+            Ok(list) => {
+                found.extend(
+                    list.iter()
+                        .filter(|alert| alert_state != "closed" || alert["state"] != "open")
+                        .filter(|alert| alerts::severity_rank(alerts::severity(kind, alert)).unwrap_or(0) >= min_rank)
+                        .take(limit)
+                        .map(|alert| alerts::summary(kind, alert)),
+                );
+            }
+            // The feature is off for the repository, or the token can't read it
+            Err(e) => {
+                warn!("Couldn't list {} alerts for {}/{}: {}", kind.name(), repo_owner, repo_name, e);
+                unavailable.push(json!({ "kind": kind.name(), "error": e.to_string() }));
+            }
+        }
+    }
+
+    let rank = |alert: &Value| alert["severity"].as_str().and_then(alerts::severity_rank).unwrap_or(0);
+    found.sort_by_key(|alert| std::cmp::Reverse(rank(alert)));
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for alert in &found {
+        *counts.entry(alerts::SEVERITIES[rank(alert)]).or_default() += 1;
+    }
+
+    Ok(json!({
+        "status": if unavailable.is_empty() { "success" } else { "partial" },
+        "message": format!("🛡️ {} {} alert(s) in {}/{}", found.len(), alert_state, repo_owner, repo_name),
+        "counts": counts,
+        "alerts": found,
+        "unavailable": unavailable
+    }))
+}
+
+async fn execute_dismiss_security_alert(
+    state: AppState,
+    repository: Option<String>,
+    kind: String,
+    number: u64,
+    reason: String,
+    comment: Option<String>,
+) -> Result<Value> {
+    let kind = alerts::AlertKind::parse(&kind)?;
+    let body = kind.dismiss_body(&reason, comment.as_deref())?;
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let alert = github_client
+        .update_security_alert(&repo_owner, &repo_name, kind.api_path(), number, &body)
+        .await?;
+
+    info!("Dismissed {} alert #{} in {}/{}", kind.name(), number, repo_owner, repo_name);
+    Ok(json!({
+        "status": "success",
+        "message": format!("🛡️ Dismissed {} alert #{}", kind.name(), number),
+        "alert": alerts::summary(kind, &alert)
+    }))
+}
+
+/// Publish files as a gist, refusing content that looks like a credential
+async fn execute_create_gist(
+    state: AppState,
+    description: Option<String>,
+    public: Option<bool>,
+    files: Vec<gists::GistFile>,
+) -> Result<Value> {
+    let payload = gists::files_payload(&files)?;
+    let public = public.unwrap_or(false);
+
+    // Unlike a push there's no later chance to catch a secret, so gists are
+    // scanned whatever SECRET_SCAN says
+    let scanner = secret_scan::Scanner::new(&Default::default())?;
+    let findings: Vec<_> = files
+        .iter()
+        .flat_map(|file| scanner.scan_text(file.filename.trim(), &file.content))
+        .collect();
+    if !findings.is_empty() {
+        warn!("Refusing to publish a gist: {} possible secrets", findings.len());
+        return Ok(json!({
+            "status": "blocked",
+            "message": format!("🔑 Gist not created: {} possible secrets in its files", findings.len()),
+            "findings": findings,
+            "suggestion": format!(
+                "Remove or mask the secrets and try again. For a false positive, add `{}` to the line.",
+                secret_scan::ALLOW_MARKER
+            )
+        }));
+    }
+
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let gist = github_client.create_gist(description.as_deref(), public, &payload).await?;
+
+    info!("Created {} gist {}", if public { "public" } else { "secret" }, gist.id);
+    Ok(json!({
+        "status": "success",
+        "message": format!("🔗 Published {} gist: {}", if public { "public" } else { "secret" }, gist.html_url),
+        "url": gist.html_url,
+        "gist": gists::summary(&gist)
+    }))
+}
+
+async fn execute_list_gists(state: AppState, limit: Option<usize>) -> Result<Value> {
+    let limit = limit.unwrap_or(gists::DEFAULT_LIMIT).clamp(1, gists::MAX_LIMIT);
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let found = github_client.list_gists(limit).await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("🔗 {} gist(s)", found.len()),
+        "gists": found.iter().map(gists::summary).collect::<Vec<_>>()
+    }))
+}
+
+/// A timestamp the notifications API accepts
+fn notification_time(value: &str) -> Result<String> {
+    chrono::DateTime::parse_from_rfc3339(value.trim())
+        .map(|at| at.with_timezone(&chrono::Utc).to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        .map_err(|_| AppError::Validation(format!("Invalid timestamp '{}': use ISO 8601, e.g. 2024-05-01T09:00:00Z", value)))
+}
+
+/// The inbox, narrowed to the reasons that need acting on unless told otherwise
+async fn execute_list_notifications(
+    state: AppState,
+    repository: Option<String>,
+    reasons: Option<Vec<String>>,
+    all: Option<bool>,
+    participating: Option<bool>,
+    since: Option<String>,
+    limit: Option<usize>,
+) -> Result<Value> {
+    let limit = limit.unwrap_or(notifications::DEFAULT_LIMIT).clamp(1, notifications::MAX_LIMIT);
+    let reasons = reasons.unwrap_or_else(|| notifications::ACTIONABLE_REASONS.iter().map(|r| r.to_string()).collect());
+    let since = since.as_deref().map(notification_time).transpose()?;
+    let repository = match repository {
+        Some(repository) => Some(resolve_repository(Some(repository)).await?),
+        None => None,
+    };
+
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    // Filtering by reason happens here, so fetch a full page to fill `limit`
+    let threads = github_client
+        .list_notifications(
+            repository.as_ref().map(|(owner, name)| (owner.as_str(), name.as_str())),
+            all.unwrap_or(false),
+            participating.unwrap_or(false),
+            since.as_deref(),
+            notifications::MAX_LIMIT,
+        )
+        .await?;
+
+    // Threads from repositories outside ALLOWED_REPOSITORIES aren't shown
+    let (visible, hidden): (Vec<_>, Vec<_>) = threads
+        .iter()
+        .filter(|t| notifications::wanted(&t.reason, &reasons))
+        .partition(|t| allowlist::check(&t.repository.full_name).is_ok());
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for thread in &visible {
+        *counts.entry(notifications::category(&thread.reason)).or_default() += 1;
+    }
+    let items: Vec<Value> = visible.iter().take(limit).map(|t| notifications::item(github_client.host(), t)).collect();
+
+    Ok(json!({
+        "status": "success",
+        "message": match visible.len() {
+            0 => "📭 Nothing needs attention".to_string(),
+            n => format!("📬 {} notification(s) need attention", n),
+        },
+        "total_count": visible.len(),
+        "counts": counts,
+        "reasons": reasons,
+        "hidden_by_allowlist": hidden.len(),
+        "truncated": visible.len() > limit,
+        "notifications": items
+    }))
+}
+
+async fn execute_mark_notifications_read(
+    state: AppState,
+    thread_ids: Option<Vec<String>>,
+    all: Option<bool>,
+    repository: Option<String>,
+    before: Option<String>,
+    done: Option<bool>,
+) -> Result<Value> {
+    let thread_ids = thread_ids.unwrap_or_default();
+    let all = all.unwrap_or(false);
+    if thread_ids.is_empty() == !all {
+        return Err(AppError::Validation("Pass either thread_ids or all: true".to_string()));
+    }
+
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let mut hidden = None;
+    let thread_ids = if all {
+        let last_read_at = match before {
+            Some(before) => notification_time(&before)?,
+            None => chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        };
+        let repository = match repository {
+            Some(repository) => Some(resolve_repository(Some(repository)).await?),
+            None => None,
+        };
+        // Marking the whole inbox would also mark threads from repositories
+        // outside ALLOWED_REPOSITORIES, so the allowed ones are marked one by one
+        if repository.is_none() && allowlist::enabled() {
+            let cutoff = chrono::DateTime::parse_from_rfc3339(&last_read_at)
+                .map_err(|e| AppError::Internal(format!("Invalid cutoff {}: {}", last_read_at, e)))?;
+            let threads = github_client
+                .list_notifications(None, false, false, None, notifications::MARK_ALL_LIMIT)
+                .await?;
+            let (visible, refused): (Vec<_>, Vec<_>) = threads
+                .into_iter()
+                .filter(|t| chrono::DateTime::parse_from_rfc3339(&t.updated_at).map_or(false, |at| at < cutoff))
+                .partition(|t| allowlist::check(&t.repository.full_name).is_ok());
+            hidden = Some(refused.len());
+            visible.into_iter().map(|t| t.id).collect()
+        } else {
+            return mark_all_notifications_read(&github_client, repository, last_read_at).await;
+        }
+    } else {
+        thread_ids
+    };
+
+    // Read-all only marks threads read
+    let done = !all && done.unwrap_or(false);
+    let mut marked = Vec::new();
+    let mut errors = Vec::new();
+    for thread_id in &thread_ids {
+        let outcome = async {
+            // Threads gathered for read-all were already checked
+            if !all && allowlist::enabled() {
+                let thread = github_client.get_notification(thread_id).await?;
+                allowlist::check(&thread.repository.full_name)?;
+            }
+            github_client.mark_notification(thread_id, done).await
+        }
+        .await;
+        match outcome {
+            Ok(()) => marked.push(thread_id.clone()),
+            Err(e) => {
+                warn!("Failed to mark notification {}: {}", thread_id, e);
+                errors.push(json!({ "thread_id": thread_id, "error": e.to_string() }));
+            }
+        }
+    }
+
+    Ok(json!({
+        "status": if errors.is_empty() { "success" } else { "partial" },
+        "message": format!("✅ Marked {} of {} notification(s) {}", marked.len(), thread_ids.len(), if done { "done" } else { "read" }),
+        "marked": marked,
+        "hidden_by_allowlist": hidden,
+        "errors": errors
+    }))
+}
+
+/// Mark everything in the inbox, or in `repository`, updated before `last_read_at` read
+async fn mark_all_notifications_read(
+    github_client: &GitHubClient,
+    repository: Option<(String, String)>,
+    last_read_at: String,
+) -> Result<Value> {
+    github_client
+        .mark_all_notifications_read(
+            repository.as_ref().map(|(owner, name)| (owner.as_str(), name.as_str())),
+            &last_read_at,
+        )
+        .await?;
+
+    let scope = match &repository {
+        Some((owner, name)) => format!("{}/{}", owner, name),
+        None => "the inbox".to_string(),
+    };
+    Ok(json!({
+        "status": "success",
+        "message": format!("✅ Marked everything in {} before {} read", scope, last_read_at),
+        "repository": repository.map(|(owner, name)| format!("{}/{}", owner, name)),
+        "before": last_read_at
+    }))
+}
+
+/// Open issues listed by `milestone_progress`
+const MILESTONE_REMAINING_LIMIT: usize = 50;
+
+fn milestone_summary(milestone: &super::api::GitHubMilestone) -> Value {
+    json!({
+        "number": milestone.number,
+        "title": milestone.title,
+        "description": milestone.description,
+        "state": milestone.state,
+        "due_on": milestone.due_on,
+        "url": milestone.html_url,
+        "progress": milestones::progress(milestone, chrono::Utc::now())
+    })
+}
+
+/// A milestone given by number or title, which must exist
+async fn find_milestone(
+    github_client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    milestone: &str,
+) -> Result<super::api::GitHubMilestone> {
+    match resolve_milestone(github_client, owner, repo, Some(milestone)).await? {
+        Some(Some(number)) => github_client.get_milestone(owner, repo, number).await,
+        _ => Err(AppError::Validation("Name a milestone by number or title".to_string())),
+    }
+}
+
+async fn execute_list_milestones(state: AppState, repository: Option<String>, milestone_state: Option<String>) -> Result<Value> {
+    let milestone_state = milestone_state.unwrap_or_else(|| "open".to_string());
+    if !matches!(milestone_state.as_str(), "open" | "closed" | "all") {
+        return Err(AppError::Validation(format!("Invalid state '{}': use open, closed or all", milestone_state)));
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let mut list = github_client.list_milestones(&repo_owner, &repo_name, &milestone_state).await?;
+    // Soonest due first; those without a due date last
+    list.sort_by(|a, b| match (&a.due_on, &b.due_on) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.number.cmp(&b.number),
+    });
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("🏁 {} {} milestone(s) in {}/{}", list.len(), milestone_state, repo_owner, repo_name),
+        "milestones": list.iter().map(milestone_summary).collect::<Vec<_>>()
+    }))
+}
+
+async fn execute_create_milestone(
+    state: AppState,
+    repository: Option<String>,
+    title: String,
+    description: Option<String>,
+    due_on: Option<String>,
+) -> Result<Value> {
+    if title.trim().is_empty() {
+        return Err(AppError::Validation("Milestone title cannot be empty".to_string()));
+    }
+    let mut payload = json!({ "title": title.trim() });
+    if let Some(description) = description {
+        payload["description"] = Value::String(description);
+    }
+    if let Some(due_on) = due_on {
+        let due = milestones::due_on(&due_on)
+            .ok_or_else(|| AppError::Validation(format!("Invalid due_on '{}': use YYYY-MM-DD", due_on)))?;
+        payload["due_on"] = Value::String(due);
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let milestone = github_client.create_milestone(&repo_owner, &repo_name, &payload).await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("🏁 Created milestone {} (#{})", milestone.title, milestone.number),
+        "milestone": milestone_summary(&milestone)
+    }))
+}
+
+async fn execute_close_milestone(state: AppState, repository: Option<String>, milestone: String) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let milestone = find_milestone(&github_client, &repo_owner, &repo_name, &milestone).await?;
+    let closed = github_client
+        .update_milestone(&repo_owner, &repo_name, milestone.number, &json!({ "state": "closed" }))
+        .await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": if closed.open_issues > 0 {
+            format!("⚠️ Closed milestone {} with {} open item(s) still in it", closed.title, closed.open_issues)
+        } else {
+            format!("✅ Closed milestone {}", closed.title)
+        },
+        "milestone": milestone_summary(&closed)
+    }))
+}
+
+async fn execute_assign_milestone(
+    state: AppState,
+    repository: Option<String>,
+    milestone: String,
+    numbers: Vec<u64>,
+) -> Result<Value> {
+    if numbers.is_empty() {
+        return Err(AppError::Validation("numbers must not be empty".to_string()));
+    }
+    if numbers.len() > BULK_UPDATE_MAX_ISSUES {
+        return Err(AppError::Validation(format!("At most {} issues can be assigned at once", BULK_UPDATE_MAX_ISSUES)));
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let (target, title) = match resolve_milestone(&github_client, &repo_owner, &repo_name, Some(&milestone)).await? {
+        Some(Some(number)) => {
+            let milestone = github_client.get_milestone(&repo_owner, &repo_name, number).await?;
+            (Some(milestone.number), milestone.title)
+        }
+        _ => (None, "no milestone".to_string()),
+    };
+
+    let mut results = Vec::new();
+    let mut failed = 0;
+    for number in &numbers {
+        match github_client.update_issue(&repo_owner, &repo_name, *number, &json!({ "milestone": target })).await {
+            Ok(issue) => results.push(json!({ "number": issue.number, "title": issue.title, "status": "updated" })),
+            Err(e) => {
+                failed += 1;
+                warn!("Failed to set the milestone of #{}: {}", number, e);
+                results.push(json!({ "number": number, "status": "failed", "error": e.to_string() }));
+            }
+        }
+    }
+
+    Ok(json!({
+        "status": if failed == 0 { "success" } else { "partial" },
+        "message": format!("🏁 Moved {} item(s) to {}, {} failed", numbers.len() - failed, title, failed),
+        "milestone": target,
+        "results": results
+    }))
+}
+
+async fn execute_milestone_progress(state: AppState, repository: Option<String>, milestone: String) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let milestone = find_milestone(&github_client, &repo_owner, &repo_name, &milestone).await?;
+    let filter = IssueFilter {
+        state: Some("open".to_string()),
+        milestone: Some(milestone.number.to_string()),
+        ..Default::default()
+    };
+    let remaining = github_client
+        .list_issues_filtered(&repo_owner, &repo_name, &filter, MILESTONE_REMAINING_LIMIT)
+        .await?;
+
+    let progress = milestones::progress(&milestone, chrono::Utc::now());
+    let message = match progress["slip_days"].as_i64() {
+        Some(days) if progress["schedule"] == "overdue" => {
+            format!("⚠️ {} is {} day(s) overdue at {}%", milestone.title, days, progress["percent_complete"])
+        }
+        Some(days) => format!(
+            "⚠️ {} is {}% done and projected to slip {} day(s)",
+            milestone.title, progress["percent_complete"], days
+        ),
+        None => format!(
+            "🏁 {} is {}% done ({})",
+            milestone.title,
+            progress["percent_complete"],
+            progress["schedule"].as_str().unwrap_or_default()
+        ),
+    };
+
+    Ok(json!({
+        "status": "success",
+        "message": message,
+        "milestone": {
+            "number": milestone.number,
+            "title": milestone.title,
+            "state": milestone.state,
+            "url": milestone.html_url,
+            "created_at": milestone.created_at,
+            "closed_at": milestone.closed_at
+        },
+        "progress": progress,
+        "remaining_issues": remaining.iter().map(issue_summary).collect::<Vec<_>>(),
+        "remaining_truncated": milestone.open_issues as usize > remaining.len() && remaining.len() >= MILESTONE_REMAINING_LIMIT
+    }))
+}
+
+async fn execute_bulk_update_issues(
+    state: AppState,
+    repository: Option<String>,
+    filter: IssueFilter,
+    numbers: Option<Vec<u64>>,
+    changes: BulkIssueChanges,
+    confirm: Option<bool>,
+    limit: Option<usize>,
+) -> Result<Value> {
+    if changes.is_empty() {
+        return Err(AppError::Validation(
+            "Pass at least one of add_labels, remove_labels, milestone, close or comment".to_string(),
+        ));
+    }
+    if let Some(reason) = &changes.close_reason {
+        if reason != "completed" && reason != "not_planned" {
+            return Err(AppError::Validation(format!("Invalid close reason '{}': use completed or not_planned", reason)));
+        }
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let milestone = resolve_milestone(&github_client, &repo_owner, &repo_name, changes.milestone.as_deref()).await?;
+    let limit = limit.unwrap_or(100).min(BULK_UPDATE_MAX_ISSUES);
+
+    let mut issues = github_client.list_issues_filtered(&repo_owner, &repo_name, &filter, limit).await?;
+    if let Some(numbers) = &numbers {
+        issues.retain(|i| numbers.contains(&i.number));
+    }
+
+    let planned: Vec<(&super::api::GitHubIssue, Vec<String>)> = issues
+        .iter()
+        .map(|issue| (issue, changes.plan(issue, &milestone)))
+        .collect();
+
+    // Changes are only applied to numbers taken from a preview, so a filter
+    // alone never changes issues nobody has seen
+    if confirm != Some(true) || numbers.is_none() {
+        let to_change: Vec<u64> = planned.iter().filter(|(_, p)| !p.is_empty()).map(|(i, _)| i.number).collect();
+        return Ok(json!({
+            "status": "preview",
+            "message": format!(
+                "🔍 {} issue(s) match, {} would change. Re-run with confirm: true and these numbers to apply.",
+                issues.len(),
+                to_change.len()
+            ),
+            "filter": filter,
+            "matched": issues.len(),
+            "numbers": to_change,
+            "issues": planned.iter().map(|(issue, plan)| json!({
+                "number": issue.number,
+                "title": issue.title,
+                "url": issue.html_url,
+                "changes": plan
+            })).collect::<Vec<_>>()
+        }));
+    }
+
+    let mut results = Vec::new();
+    let (mut updated, mut skipped, mut failed) = (0, 0, 0);
+    for (issue, plan) in &planned {
+        if plan.is_empty() {
+            skipped += 1;
+            results.push(json!({ "number": issue.number, "status": "skipped" }));
+            continue;
+        }
+
+        match apply_bulk_changes(&github_client, &repo_owner, &repo_name, issue, &changes, &milestone).await {
+            Ok(()) => {
+                updated += 1;
+                progress::report(&format!("✅ #{} {}", issue.number, plan.join(", ")));
+                results.push(json!({ "number": issue.number, "status": "updated", "changes": plan }));
+            }
+            Err(e) => {
+                failed += 1;
+                warn!("Bulk update of #{} failed: {}", issue.number, e);
+                progress::report(&format!("❌ #{} {}", issue.number, e));
+                results.push(json!({ "number": issue.number, "status": "failed", "error": e.to_string() }));
+            }
+        }
+    }
+
+    Ok(json!({
+        "status": if failed == 0 { "success" } else { "partial" },
+        "message": format!("📦 Updated {} issue(s), skipped {}, failed {}", updated, skipped, failed),
+        "updated": updated,
+        "skipped": skipped,
+        "failed": failed,
+        "results": results
+    }))
+}
+
+async fn apply_bulk_changes(
+    github_client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    issue: &super::api::GitHubIssue,
+    changes: &BulkIssueChanges,
+    milestone: &Option<Option<u64>>,
+) -> Result<()> {
+    if !changes.add_labels.is_empty() {
+        github_client.add_labels(owner, repo, issue.number, &changes.add_labels).await?;
+    }
+    for label in &changes.remove_labels {
+        github_client.remove_label(owner, repo, issue.number, label).await?;
+    }
+    if let Some(target) = milestone {
+        if issue.milestone.as_ref().map(|m| m.number) != *target {
+            github_client
+                .update_issue(owner, repo, issue.number, &json!({ "milestone": target }))
+                .await?;
+        }
+    }
+    if let Some(comment) = &changes.comment {
+        github_client.create_issue_comment(owner, repo, issue.number, comment).await?;
+    }
+    if changes.close && issue.state != "closed" {
+        github_client
+            .close_issue(owner, repo, issue.number, changes.close_reason.as_deref())
+            .await?;
+    }
+    Ok(())
+}
+
+/// List open issues nobody has triaged, or apply the agent's decisions about them
+async fn execute_triage(
+    state: AppState,
+    repository: Option<String>,
+    project_number: Option<String>,
+    owner: Option<String>,
+    decisions: Option<Vec<triage::TriageDecision>>,
+    limit: Option<usize>,
+) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let repository = format!("{}/{}", repo_owner, repo_name);
+    let project_number = match project_number {
+        Some(number) => Some(number),
+        None => detect_project_number().await.ok(),
+    };
+    let project_owner = match &project_number {
+        Some(_) => Some(resolve_project_owner(&state, owner).await?),
+        None => None,
+    };
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    // Issue number to project item, for the issues of this repository on the board
+    let project = match (&project_owner, &project_number) {
+        (Some(owner), Some(number)) => {
+            let project = github_client.get_project(owner, number).await?;
+            let items: HashMap<u64, String> = github_client
+                .get_project_items(owner, number)
+                .await?
+                .into_iter()
+                .filter_map(|item| {
+                    let content = item.content.as_ref()?;
+                    let number = content.number.filter(|_| content.repository.as_deref() == Some(repository.as_str()))?;
+                    Some((number, item.id))
+                })
+                .collect();
+            Some((project, items))
+        }
+        _ => None,
+    };
+
+    let Some(decisions) = decisions else {
+        let limit = limit.unwrap_or(triage::DEFAULT_LIMIT).min(triage::MAX_LIMIT);
+        let filter = IssueFilter { state: Some("open".to_string()), ..Default::default() };
+        // Read past the limit, since pull requests and triaged issues are
+        // dropped, and one more to tell whether older issues went unchecked
+        let scan = triage::MAX_LIMIT * 3;
+        let mut issues = github_client.list_issues_filtered(&repo_owner, &repo_name, &filter, scan + 1).await?;
+        let scanned_all = issues.len() <= scan;
+        issues.truncate(scan);
+        let mut untriaged: Vec<Value> = issues
+            .iter()
+            .filter(|issue| issue.pull_request.is_none())
+            .filter_map(|issue| {
+                let on_project = project.as_ref().map(|(_, items)| items.contains_key(&issue.number));
+                let reasons = triage::untriaged_because(issue, on_project);
+                (!reasons.is_empty()).then(|| json!({
+                    "number": issue.number,
+                    "title": issue.title,
+                    "body": issue.body,
+                    "author": issue.user.login,
+                    "created_at": issue.created_at,
+                    "url": issue.html_url,
+                    "labels": issue.labels.iter().map(|l| &l.name).collect::<Vec<_>>(),
+                    "milestone": issue.milestone.as_ref().map(|m| &m.title),
+                    "on_project": on_project,
+                    "untriaged_because": reasons
+                }))
+            })
+            .collect();
+        let truncated = !scanned_all || untriaged.len() > limit;
+        untriaged.truncate(limit);
+        let mut message = format!("🗂️ {} untriaged issue(s) in {}", untriaged.len(), repository);
+        if !scanned_all {
+            message.push_str(&format!("; only the newest {} open issues were checked", scan));
+        }
+
+        let labels = github_client.list_labels(&repo_owner, &repo_name).await?;
+        let milestones = github_client.list_milestones(&repo_owner, &repo_name, "open").await?;
+        let priorities: Vec<&str> = project
+            .as_ref()
+            .and_then(|(project, _)| project.field("Priority"))
+            .map(|field| field.options.iter().map(|o| o.name.as_str()).collect())
+            .unwrap_or_default();
+
+        return Ok(json!({
+            "status": "success",
+            "message": message,
+            "repository": repository,
+            "project": project.as_ref().map(|(p, _)| json!({ "title": p.title, "owner": project_owner, "number": project_number })),
+            "issues": untriaged,
+            "truncated": truncated,
+            "choices": {
+                "labels": labels.iter().map(|l| json!({ "name": l.name, "description": l.description })).collect::<Vec<_>>(),
+                "milestones": milestones.iter().map(|m| json!({ "number": m.number, "title": m.title, "due_on": m.due_on })).collect::<Vec<_>>(),
+                "priorities": priorities
+            },
+            "next_step": "Classify each issue, then call github_triage again with decisions: [{number, labels, priority, milestone, add_to_project}]"
+        }));
+    };
+
+    if decisions.is_empty() {
+        return Err(AppError::Validation("decisions must not be empty".to_string()));
+    }
+
+    let mut results = Vec::new();
+    let mut failed = 0;
+    for decision in &decisions {
+        match apply_triage_decision(&github_client, &repo_owner, &repo_name, project.as_ref(), decision).await {
+            Ok(changes) => {
+                progress::report(&format!("✅ #{} {}", decision.number, changes.join(", ")));
+                results.push(json!({ "number": decision.number, "status": "updated", "changes": changes }));
+            }
+            Err(e) => {
+                failed += 1;
+                warn!("Triage of #{} failed: {}", decision.number, e);
+                progress::report(&format!("❌ #{} {}", decision.number, e));
+                results.push(json!({ "number": decision.number, "status": "failed", "error": e.to_string() }));
+            }
+        }
+    }
+
+    if let (Some(owner), Some(number)) = (&project_owner, &project_number) {
+        board::invalidate(owner, number);
+    }
+
+    Ok(json!({
+        "status": if failed == 0 { "success" } else { "partial" },
+        "message": format!("🗂️ Triaged {} issue(s), {} failed", decisions.len() - failed, failed),
+        "repository": repository,
+        "results": results
+    }))
+}
+
+/// Apply one triage decision; returns what changed
+async fn apply_triage_decision(
+    github_client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    project: Option<&(super::api::GitHubProject, HashMap<u64, String>)>,
+    decision: &triage::TriageDecision,
+) -> Result<Vec<String>> {
+    let issue = github_client.get_issue(owner, repo, decision.number).await?;
+    let mut changes = Vec::new();
+
+    let new_labels: Vec<String> = decision
+        .labels
+        .iter()
+        .filter(|label| !issue.labels.iter().any(|l| l.name.eq_ignore_ascii_case(label)))
+        .cloned()
+        .collect();
+    if !new_labels.is_empty() {
+        github_client.add_labels(owner, repo, issue.number, &new_labels).await?;
+        changes.push(format!("labels +{}", new_labels.join(", +")));
+    }
+
+    if let Some(Some(target)) = resolve_milestone(github_client, owner, repo, decision.milestone.as_deref()).await? {
+        if issue.milestone.as_ref().map(|m| m.number) != Some(target) {
+            github_client.update_issue(owner, repo, issue.number, &json!({ "milestone": target })).await?;
+            changes.push(format!("milestone {}", decision.milestone.as_deref().unwrap_or_default()));
+        }
+    }
+
+    let Some((project, items)) = project else {
+        if decision.priority.is_some() {
+            return Err(AppError::Validation("Setting a priority needs a project; pass project_number".to_string()));
+        }
+        return Ok(changes);
+    };
+    let item_id = match items.get(&issue.number) {
+        Some(id) => Some(id.clone()),
+        None if decision.add_to_project.unwrap_or(true) => {
+            let id = github_client.add_project_item(&project.id, &issue.node_id).await?;
+            changes.push(format!("added to {}", project.title));
+            Some(id)
+        }
+        None => None,
+    };
+    if let Some(priority) = &decision.priority {
+        let item_id = item_id.ok_or_else(|| {
+            AppError::Validation(format!("#{} isn't on the project, so it can't have a priority", issue.number))
+        })?;
+        github_client.set_project_item_option(project, &item_id, "Priority", priority).await?;
+        changes.push(format!("priority {}", priority));
+    }
+
+    Ok(changes)
+}
+
+/// Add an issue or PR to a project, set its fields and archive or restore it
+#[allow(clippy::too_many_arguments)]
+async fn execute_project_update_item(
+    state: AppState,
+    project_number: Option<String>,
+    owner: Option<String>,
+    repository: Option<String>,
+    number: Option<u64>,
+    item_id: Option<String>,
+    fields: Option<serde_json::Map<String, Value>>,
+    archive: Option<bool>,
+) -> Result<Value> {
+    let fields = fields.unwrap_or_default();
+    let project_number = match project_number {
+        Some(number) => number,
+        None => detect_project_number().await.map_err(|_| {
+            AppError::Validation("No project found. Please specify project_number".to_string())
+        })?,
+    };
+    let project_owner = resolve_project_owner(&state, owner).await?;
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let project = github_client.get_project(&project_owner, &project_number).await?;
+
+    let mut added = false;
+    let (item_id, content) = match (item_id, number) {
+        // The item's issue can be in any repository, which is checked here
+        // rather than the checkout's
+        (Some(item_id), _) => {
+            let (project_id, repository) = github_client.get_project_item_location(&item_id).await?;
+            if project_id != project.id {
+                return Err(AppError::Validation(format!("Item {} isn't on {}", item_id, project.title)));
+            }
+            if let Some(repository) = &repository {
+                authorize_repository(&state, repository, "project_update_item", permissions::Operation::Push).await?;
+            }
+            (item_id, None)
+        }
+        (None, Some(number)) => {
+            let (repo_owner, repo_name) = resolve_repository(repository).await?;
+            let repository = format!("{}/{}", repo_owner, repo_name);
+            let issue = github_client.get_issue(&repo_owner, &repo_name, number).await?;
+            let node_id = match issue.pull_request {
+                Some(_) => github_client.get_pull_request(&repo_owner, &repo_name, number).await?.node_id,
+                None => issue.node_id,
+            };
+            let item_id = match github_client.find_project_item(&project.id, &node_id).await? {
+                Some(item_id) => item_id,
+                None => {
+                    added = true;
+                    github_client.add_project_item(&project.id, &node_id).await?
+                }
+            };
+            (item_id, Some(format!("{}#{}", repository, number)))
+        }
+        (None, None) => {
+            return Err(AppError::Validation("Pass the issue or PR number, or item_id".to_string()));
+        }
+    };
+
+    if !added && fields.is_empty() && archive.is_none() {
+        return Err(AppError::Validation("Nothing to update: pass fields or archive".to_string()));
+    }
+
+    let mut set = serde_json::Map::new();
+    let mut failed = serde_json::Map::new();
+    for (name, value) in &fields {
+        match github_client.set_project_item_field(&project, &item_id, name, value).await {
+            Ok(value) => {
+                set.insert(name.clone(), value);
+            }
+            Err(e) => {
+                warn!("Failed to set {} on project item {}: {}", name, item_id, e);
+                failed.insert(name.clone(), Value::from(e.to_string()));
+            }
+        }
+    }
+
+    if let Some(archived) = archive {
+        github_client.archive_project_item(&project.id, &item_id, archived).await?;
+    }
+    board::invalidate(&project_owner, &project_number);
+
+    let subject = content.clone().unwrap_or_else(|| format!("item {}", item_id));
+    let mut changes = Vec::new();
+    if added {
+        changes.push(format!("added to {}", project.title));
+    }
+    if !set.is_empty() {
+        changes.push(format!("set {}", set.keys().cloned().collect::<Vec<_>>().join(", ")));
+    }
+    match archive {
+        Some(true) => changes.push("archived".to_string()),
+        Some(false) => changes.push("restored".to_string()),
+        None => {}
+    }
+
+    Ok(json!({
+        "status": if failed.is_empty() { "success" } else { "partial" },
+        "message": if changes.is_empty() {
+            format!("❌ Nothing changed on {}", subject)
+        } else {
+            format!("📋 {}: {}", subject, changes.join(", "))
+        },
+        "project": { "title": project.title, "owner": project_owner, "number": project_number },
+        "item_id": item_id,
+        "content": content,
+        "added": added,
+        "fields": set,
+        "failed": failed,
+        "archived": archive
+    }))
+}
+
+fn workflow_run_summary(run: &super::api::GitHubWorkflowRun) -> Value {
+    json!({
+        "id": run.id,
+        "name": run.name,
+        "run_number": run.run_number,
+        "attempt": run.run_attempt,
+        "event": run.event,
+        "branch": run.head_branch,
+        "head_sha": run.head_sha,
+        "status": run.status,
+        "conclusion": run.conclusion,
+        "failed": actions::is_failure(run.conclusion.as_deref()),
+        "url": run.html_url,
+        "updated_at": run.updated_at
+    })
+}
+
+async fn execute_list_workflow_runs(
+    state: AppState,
+    repository: Option<String>,
+    branch: Option<String>,
+    pull_request: Option<u64>,
+    status: Option<String>,
+    limit: Option<u32>,
+) -> Result<Value> {
+    let explicit_repository = repository.is_some();
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let mut filter = WorkflowRunFilter { status, ..Default::default() };
+    match pull_request {
+        Some(number) => {
+            let pr = github_client.get_pull_request(&repo_owner, &repo_name, number).await?;
+            filter.head_sha = Some(pr.head.sha);
+        }
+        // The local branch only means something for the local checkout
+        None if branch.is_none() && !explicit_repository => filter.branch = get_current_branch().await.ok(),
+        None => filter.branch = branch,
+    }
+
+    let runs = github_client
+        .list_workflow_runs(&repo_owner, &repo_name, &filter, limit.unwrap_or(20))
+        .await?;
+    let failed = runs.iter().filter(|r| actions::is_failure(r.conclusion.as_deref())).count();
+    let in_progress = runs.iter().filter(|r| r.status.as_deref() != Some("completed")).count();
+
+    Ok(json!({
+        "repository": format!("{}/{}", repo_owner, repo_name),
+        "branch": filter.branch,
+        "head_sha": filter.head_sha,
+        "runs": runs.iter().map(workflow_run_summary).collect::<Vec<_>>(),
+        "failed": failed,
+        "in_progress": in_progress
+    }))
+}
+
+async fn execute_get_workflow_run(state: AppState, repository: Option<String>, run_id: u64, logs: Option<bool>) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let run = github_client.get_workflow_run(&repo_owner, &repo_name, run_id).await?;
+    let jobs = github_client.list_run_jobs(&repo_owner, &repo_name, run_id).await?;
+
+    let mut job_summaries = Vec::new();
+    for job in &jobs {
+        let failed = actions::is_failure(job.conclusion.as_deref());
+        let failed_steps: Vec<&str> = job
+            .steps
+            .iter()
+            .filter(|s| actions::is_failure(s.conclusion.as_deref()))
+            .map(|s| s.name.as_str())
+            .collect();
+
+        // Logs are only worth fetching for the jobs that broke
+        let log = if failed && logs.unwrap_or(true) {
+            match github_client.get_job_logs(&repo_owner, &repo_name, job.id).await {
+                Ok(text) => json!(actions::failure_excerpt(&text, actions::LOG_TAIL_LINES)),
+                Err(e) => json!({ "error": e.to_string() }),
+            }
+        } else {
+            Value::Null
+        };
+
+        job_summaries.push(json!({
+            "id": job.id,
+            "name": job.name,
+            "status": job.status,
+            "conclusion": job.conclusion,
+            "failed": failed,
+            "failed_steps": failed_steps,
+            "started_at": job.started_at,
+            "completed_at": job.completed_at,
+            "url": job.html_url,
+            "log": log
+        }));
+    }
+
+    Ok(json!({
+        "run": workflow_run_summary(&run),
+        "jobs": job_summaries,
+        "failed_jobs": jobs.iter().filter(|j| actions::is_failure(j.conclusion.as_deref())).count()
+    }))
+}
+
+async fn execute_rerun_workflow_run(
+    state: AppState,
+    repository: Option<String>,
+    run_id: u64,
+    failed_only: Option<bool>,
+) -> Result<Value> {
+    let failed_only = failed_only.unwrap_or(true);
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    github_client.rerun_workflow_run(&repo_owner, &repo_name, run_id, failed_only).await?;
+    info!("Re-running workflow run {} (failed only: {})", run_id, failed_only);
+
+    Ok(json!({
+        "status": "success",
+        "message": if failed_only {
+            format!("🔁 Re-running failed jobs of run {}", run_id)
+        } else {
+            format!("🔁 Re-running run {}", run_id)
+        },
+        "run_id": run_id,
+        "failed_only": failed_only
+    }))
+}
+
+async fn execute_cancel_workflow_run(state: AppState, repository: Option<String>, run_id: u64) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    github_client.cancel_workflow_run(&repo_owner, &repo_name, run_id).await?;
+    info!("Cancelled workflow run {}", run_id);
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("⏹️ Cancelling run {}", run_id),
+        "run_id": run_id
+    }))
+}
+
+/// How long to look for the run a dispatch created
+const DISPATCH_LOOKUP_ATTEMPTS: u32 = 10;
+const DISPATCH_LOOKUP_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Upper bound on waiting for a deployment run to finish
+const PROMOTE_WAIT_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+const PROMOTE_POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+async fn execute_promote_workflow(
+    state: AppState,
+    tag: String,
+    environment: Option<String>,
+    wait: Option<bool>,
+) -> Result<Value> {
+    info!("Executing promote workflow for {}", tag);
+
+    let root = get_repo_root().await?;
+    let settings = super::repo_config::load_workflow_file(&root)?.promote;
+    let workflow = settings
+        .workflow
+        .or_else(|| state.config.workflow.promote_workflow.clone())
+        .ok_or_else(|| AppError::Validation(
+            "No deployment workflow configured: set [promote] workflow in .mcp-workflow.toml or PROMOTE_WORKFLOW".to_string(),
+        ))?;
+    let environments = settings
+        .environments
+        .unwrap_or_else(|| state.config.workflow.promote_environments.clone());
+
+    let (repo_owner, repo_name) = get_remote_repository().await?;
+    let repository = format!("{}/{}", repo_owner, repo_name);
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    if !github_client.tag_exists(&repo_owner, &repo_name, &tag).await? {
+        return Err(AppError::Validation(format!("Tag {} not found in {}", tag, repository)));
+    }
+
+    let history = promotion_history(&state, &repository, &tag).await?;
+    let environment = match environment {
+        Some(environment) => {
+            if !environments.contains(&environment) {
+                return Err(AppError::Validation(format!(
+                    "Unknown environment '{}': expected one of {}",
+                    environment,
+                    environments.join(", ")
+                )));
+            }
+            environment
+        }
+        None => {
+            // The furthest environment this tag has reached without failing
+            let reached = history
+                .iter()
+                .filter(|p| !actions::is_failure(p["conclusion"].as_str()) && p["conclusion"] != "cancelled")
+                .filter_map(|p| environments.iter().position(|e| p["environment"] == e.as_str()))
+                .max();
+            let next = reached.map_or(0, |i| i + 1);
+            match environments.get(next) {
+                Some(environment) => environment.clone(),
+                None => {
+                    return Ok(json!({
+                        "status": "error",
+                        "message": format!("⚠️ {} has already been promoted to {}", tag, environments.last().map(String::as_str).unwrap_or("every environment")),
+                        "history": history
+                    }));
+                }
+            }
+        }
+    };
+
+    let inputs: serde_json::Map<String, Value> = settings
+        .inputs
+        .unwrap_or_else(|| [("tag".to_string(), "{tag}".to_string()), ("environment".to_string(), "{environment}".to_string())].into())
+        .into_iter()
+        .map(|(name, value)| (name, json!(value.replace("{tag}", &tag).replace("{environment}", &environment))))
+        .collect();
+    let git_ref = settings.git_ref.unwrap_or_else(|| tag.clone());
+
+    let dispatched_at = chrono::Utc::now() - chrono::Duration::seconds(5);
+    github_client
+        .dispatch_workflow(&repo_owner, &repo_name, &workflow, &git_ref, &Value::Object(inputs.clone()))
+        .await?;
+    progress::report(&format!("🚀 Dispatched {} for {} → {}", workflow, tag, environment));
+
+    // The dispatch API doesn't return the run, so look for the one it created
+    let filter = WorkflowRunFilter {
+        workflow: Some(workflow.clone()),
+        event: Some("workflow_dispatch".to_string()),
+        created: Some(format!(">={}", dispatched_at.format("%Y-%m-%dT%H:%M:%SZ"))),
+        ..Default::default()
+    };
+    let mut run = None;
+    for _ in 0..DISPATCH_LOOKUP_ATTEMPTS {
+        tokio::time::sleep(DISPATCH_LOOKUP_INTERVAL).await;
+        let runs = github_client.list_workflow_runs(&repo_owner, &repo_name, &filter, 10).await?;
+        // Runs are listed newest first
+        if let Some(found) = runs.into_iter().next() {
+            run = Some(found);
+            break;
+        }
+    }
+
+    let user = github_client.get_user().await?;
+    let mut record = json!({
+        "tag": tag,
+        "environment": environment,
+        "workflow": workflow,
+        "ref": git_ref,
+        "inputs": inputs,
+        "run_id": run.as_ref().map(|r| r.id),
+        "run_url": run.as_ref().map(|r| r.html_url.clone()),
+        "status": run.as_ref().and_then(|r| r.status.clone()).unwrap_or_else(|| "dispatched".to_string()),
+        "conclusion": run.as_ref().and_then(|r| r.conclusion.clone()),
+        "promoted_by": user.login,
+        "promoted_at": chrono::Utc::now().to_rfc3339()
+    });
+    let record_id = record_workflow_state(&state, &user, &repository, &tag, "promote", &record).await?;
+
+    if let (Some(mut current), true) = (run.clone(), wait.unwrap_or(false)) {
+        let started = std::time::Instant::now();
+        while current.status.as_deref() != Some("completed") && started.elapsed() < PROMOTE_WAIT_TIMEOUT {
+            tokio::time::sleep(PROMOTE_POLL_INTERVAL).await;
+            current = github_client.get_workflow_run(&repo_owner, &repo_name, current.id).await?;
+            progress::report(&format!("⏳ Run {}: {}", current.id, current.status.as_deref().unwrap_or("unknown")));
+        }
+        record["status"] = json!(current.status);
+        record["conclusion"] = json!(current.conclusion);
+        update_workflow_state(&state, record_id, &record).await?;
+        run = Some(current);
+    }
+
+    let failed = actions::is_failure(record["conclusion"].as_str());
+    Ok(json!({
+        "status": if failed { "error" } else { "success" },
+        "message": match (&run, record["conclusion"].as_str()) {
+            (_, Some("success")) => format!("🎉 {} promoted to {}", tag, environment),
+            (_, Some(conclusion)) => format!("❌ Promotion of {} to {} finished with {}", tag, environment, conclusion),
+            (Some(_), None) => format!("🚀 Promoting {} to {}; deployment run in progress", tag, environment),
+            (None, None) => format!("🚀 Dispatched promotion of {} to {}; the run hasn't appeared yet", tag, environment),
+        },
+        "promotion": record,
+        "run": run.as_ref().map(workflow_run_summary),
+        "environments": environments,
+        "previous_promotions": history
+    }))
+}
+
+/// Recorded promotions of `tag`, newest first
+async fn promotion_history(state: &AppState, repository: &str, tag: &str) -> Result<Vec<Value>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT state FROM workflow_states
+        WHERE repository = ? AND branch = ? AND workflow_type = 'promote'
+        ORDER BY id DESC
+        "#,
+        repository,
+        tag
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| serde_json::from_str(&row.state).ok())
+        .collect())
+}
+
+/// Append an entry to the workflow history, returning its ID
+async fn record_workflow_state(
+    state: &AppState,
+    user: &super::api::GitHubUser,
+    repository: &str,
+    branch: &str,
+    workflow_type: &str,
+    details: &Value,
+) -> Result<i64> {
+    let github_id = user.id as i64;
+    let details = serde_json::to_string(details)?;
+
+    // History rows reference the user, who may never have been stored locally
+    sqlx::query!(
+        r#"
+        INSERT INTO users (github_id, username, name, email, avatar_url)
+        VALUES (?, ?, ?, ?, ?)
+        ON CONFLICT(github_id) DO UPDATE SET
+            username = excluded.username,
+            updated_at = datetime('now')
+        "#,
+        github_id,
+        user.login,
+        user.name,
+        user.email,
+        user.avatar_url
+    )
+    .execute(&state.db)
+    .await?;
+
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO workflow_states (user_id, repository, branch, workflow_type, state)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+        github_id,
+        repository,
+        branch,
+        workflow_type,
+        details
+    )
+    .execute(&state.db)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+async fn update_workflow_state(state: &AppState, id: i64, details: &Value) -> Result<()> {
+    let details = serde_json::to_string(details)?;
+    sqlx::query!(
+        "UPDATE workflow_states SET state = ?, updated_at = datetime('now') WHERE id = ?",
+        details,
+        id
+    )
+    .execute(&state.db)
+    .await?;
+
+    Ok(())
+}
+
+/// Default size limit for diffs returned to clients
+const DEFAULT_DIFF_MAX_BYTES: usize = 100_000;
+
+/// Cut `text` to at most `max_bytes`, at a line boundary where possible
+fn truncate_diff(text: &str, max_bytes: usize) -> (&str, bool) {
+    if text.len() <= max_bytes {
+        return (text, false);
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let end = text[..end].rfind('\n').map_or(end, |i| i + 1);
+    (&text[..end], true)
+}
+
+/// Sort open Dependabot PRs into ones policy lets through automatically and
+/// ones a human should look at; with `confirm`, approve and auto-merge the former
+async fn execute_manage_dependabot(state: AppState, confirm: Option<bool>) -> Result<Value> {
+    let root = get_repo_root().await?;
+    let settings = super::repo_config::load_workflow_file(&root)?.dependabot;
+    let policy = settings.auto_merge.unwrap_or_else(|| state.config.workflow.dependabot_auto_merge.clone());
+    let merge_method = settings.merge_method.unwrap_or_else(|| state.config.workflow.dependabot_merge_method.clone());
+    let max_bump = dependabot::max_auto_merge_bump(&policy);
+
+    let (repo_owner, repo_name) = get_remote_repository().await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let prs: Vec<_> = github_client
+        .list_pull_requests(&repo_owner, &repo_name, Some("open"))
+        .await?
+        .into_iter()
+        .filter(|pr| pr.user.login == dependabot::DEPENDABOT_LOGIN)
+        .collect();
+
+    let mut auto_merge = Vec::new();
+    let mut needs_review = Vec::new();
+    for pr in &prs {
+        let update = dependabot::parse_update(&pr.title);
+        let bump = update.as_ref().map_or(dependabot::Bump::Unknown, |u| u.bump);
+        let ci = pull_request_checks(&github_client, &repo_owner, &repo_name, pr).await?;
+
+        let mut summary = json!({
+            "number": pr.number,
+            "title": pr.title,
+            "url": pr.html_url,
+            "update": update,
+            "bump": bump,
+            "ci": ci.state
+        });
+
+        let within_policy = max_bump.map_or(false, |max| bump <= max);
+        let reason = if !within_policy {
+            Some(format!("{} update outside the '{}' auto-merge policy", bump.as_str(), policy))
+        } else if ci.state == "failing" {
+            Some("CI is failing".to_string())
+        } else if pr.draft {
+            Some("PR is a draft".to_string())
+        } else {
+            None
+        };
+
+        match reason {
+            Some(reason) => {
+                summary["reason"] = json!(reason);
+                needs_review.push(summary);
+            }
+            None => auto_merge.push((pr, summary)),
+        }
+    }
+
+    if confirm != Some(true) {
+        return Ok(json!({
+            "status": "preview",
+            "message": format!(
+                "🔍 {} Dependabot PR(s): {} eligible for auto-merge, {} need review. Re-run with confirm: true to approve and auto-merge.",
+                prs.len(),
+                auto_merge.len(),
+                needs_review.len()
+            ),
+            "policy": policy,
+            "merge_method": merge_method,
+            "auto_merge": auto_merge.into_iter().map(|(_, s)| s).collect::<Vec<_>>(),
+            "needs_review": needs_review
+        }));
+    }
+
+    let mut results = Vec::new();
+    let mut failed = 0;
+    for (pr, mut summary) in auto_merge {
+        let approval = format!(
+            "Auto-approved: {} update within the '{}' Dependabot policy.",
+            summary["bump"].as_str().unwrap_or("unknown"),
+            policy
+        );
+        let outcome = match github_client.create_review(&repo_owner, &repo_name, pr.number, "APPROVE", Some(&approval), &[]).await {
+            Ok(_) => github_client.enable_auto_merge(&pr.node_id, &merge_method).await,
+            Err(e) => Err(e),
+        };
+
+        match outcome {
+            Ok(()) => {
+                progress::report(&format!("✅ #{} approved, auto-merge enabled", pr.number));
+                summary["status"] = json!("auto_merge_enabled");
+            }
+            Err(e) => {
+                failed += 1;
+                warn!("Dependabot auto-merge of #{} failed: {}", pr.number, e);
+                progress::report(&format!("❌ #{} {}", pr.number, e));
+                summary["status"] = json!("failed");
+                summary["error"] = json!(e.to_string());
+            }
+        }
+        results.push(summary);
+    }
+
+    Ok(json!({
+        "status": if failed == 0 { "success" } else { "partial" },
+        "message": format!(
+            "🤖 Auto-merge enabled on {} Dependabot PR(s), {} failed; {} need review",
+            results.len() - failed,
+            failed,
+            needs_review.len()
+        ),
+        "policy": policy,
+        "merge_method": merge_method,
+        "auto_merge": results,
+        "needs_review": needs_review,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }))
+}
+
+fn release_summary(release: &super::api::GitHubRelease) -> Value {
+    json!({
+        "id": release.id,
+        "tag": release.tag_name,
+        "name": release.name,
+        "draft": release.draft,
+        "prerelease": release.prerelease,
+        "url": release.html_url,
+        "created_at": release.created_at,
+        "published_at": release.published_at,
+        "assets": release.assets.iter().map(|a| json!({
+            "name": a.name,
+            "size": a.size,
+            "url": a.browser_download_url
+        })).collect::<Vec<_>>()
+    })
+}
+
+async fn execute_list_releases(state: AppState, repository: Option<String>, limit: Option<u32>) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let releases = github_client.list_releases(&repo_owner, &repo_name, limit.unwrap_or(10)).await?;
+
+    Ok(json!({
+        "repository": format!("{}/{}", repo_owner, repo_name),
+        "releases": releases.iter().map(release_summary).collect::<Vec<_>>()
+    }))
+}
+
+/// Tag the default branch's HEAD, generate notes from the PRs merged since
+/// the latest release and create the release, uploading any assets
+async fn execute_create_release(
+    state: AppState,
+    tag: String,
+    name: Option<String>,
+    notes: Option<String>,
+    draft: Option<bool>,
+    prerelease: Option<bool>,
+    assets: Option<Vec<String>>,
+) -> Result<Value> {
+    let (repo_owner, repo_name) = get_remote_repository().await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    if github_client.tag_exists(&repo_owner, &repo_name, &tag).await? {
+        return Err(AppError::Validation(format!("Tag {} already exists", tag)));
+    }
+
+    // Read assets up front so a missing file doesn't leave a half-made release.
+    // They must be files in the checkout, so nothing else the server can read
+    // is published.
+    let root = tokio::fs::canonicalize(get_repo_root().await?)
+        .await
+        .map_err(|e| AppError::Internal(format!("Cannot resolve the repository root: {}", e)))?;
+    let mut files = Vec::new();
+    for asset in assets.unwrap_or_default() {
+        let path = tokio::fs::canonicalize(root.join(&asset))
+            .await
+            .map_err(|e| AppError::Validation(format!("Cannot read asset {}: {}", asset, e)))?;
+        if !path.starts_with(&root) {
+            return Err(AppError::Validation(format!("Asset {} is outside the repository", asset)));
+        }
+        let data = tokio::fs::read(&path)
+            .await
+            .map_err(|e| AppError::Validation(format!("Cannot read asset {}: {}", asset, e)))?;
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or(asset);
+        files.push((file_name, data));
+    }
+
+    let repository = github_client.get_repository(&repo_owner, &repo_name).await?;
+    let head = github_client.get_commit(&repo_owner, &repo_name, &repository.default_branch).await?;
+    let previous = github_client.get_latest_release(&repo_owner, &repo_name).await?;
+
+    let merged = github_client
+        .list_merged_pull_requests(
+            &repo_owner,
+            &repo_name,
+            &repository.default_branch,
+            previous.as_ref().map(|r| r.published_at.as_deref().unwrap_or(&r.created_at)),
+        )
+        .await?;
+    let compare_url = previous.as_ref().map(|p| {
+        github_client.host().web(&format!("{}/{}/compare/{}...{}", repo_owner, repo_name, p.tag_name, tag))
+    });
+    let body = release::release_notes(&merged, notes.as_deref(), compare_url.as_deref());
+
+    info!("Tagging {} at {} ({})", tag, head.sha, repository.default_branch);
+    github_client.create_tag_ref(&repo_owner, &repo_name, &tag, &head.sha).await?;
+
+    let release = github_client.create_release(&repo_owner, &repo_name, &super::api::NewRelease {
+        tag_name: tag.clone(),
+        target_commitish: head.sha.clone(),
+        name: name.unwrap_or_else(|| tag.clone()),
+        body,
+        draft: draft.unwrap_or(true),
+        prerelease: prerelease.unwrap_or(false),
+    }).await;
+    let created = match release {
+        Ok(created) => created,
+        Err(e) => {
+            // Without its release the tag would block the next attempt
+            if let Err(cleanup) = github_client.delete_tag_ref(&repo_owner, &repo_name, &tag).await {
+                warn!("Failed to delete tag {} after its release failed: {}", tag, cleanup);
+            }
+            return Err(e);
+        }
+    };
+
+    let mut uploads = Vec::new();
+    for (file_name, data) in files {
+        progress::report(&format!("⬆️ Uploading {}", file_name));
+        match github_client.upload_release_asset(&created, &file_name, "application/octet-stream", data).await {
+            Ok(asset) => uploads.push(json!({ "name": asset.name, "status": "uploaded", "url": asset.browser_download_url })),
+            Err(e) => {
+                warn!("Uploading {} to {} failed: {}", file_name, tag, e);
+                uploads.push(json!({ "name": file_name, "status": "failed", "error": e.to_string() }));
+            }
+        }
+    }
+
+    Ok(json!({
+        "status": "success",
+        "message": format!(
+            "🏷️ {} release {} created from {} with {} merged PR(s)",
+            if created.draft { "Draft" } else { "Published" },
+            tag,
+            &head.sha[..head.sha.len().min(7)],
+            merged.len()
+        ),
+        "release": release_summary(&created),
+        "previous_tag": previous.map(|p| p.tag_name),
+        "commit": head.sha,
+        "pull_requests": merged.iter().map(|pr| json!({
+            "number": pr.number,
+            "title": pr.title,
+            "author": pr.user.login
+        })).collect::<Vec<_>>(),
+        "assets": uploads,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }))
+}
+
+/// Commits looked at per changelog; each costs one PR-association request
+const CHANGELOG_MAX_COMMITS: usize = 250;
+
+/// Markdown changelog for the PRs merged between two refs, found through the
+/// PR associated with each commit in the range
+async fn execute_changelog(
+    state: AppState,
+    repository: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    heading: Option<String>,
+) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let to = match to {
+        Some(to) => to,
+        None => github_client.get_repository(&repo_owner, &repo_name).await?.default_branch,
+    };
+    let from = match from {
+        Some(from) => from,
+        None => github_client
+            .get_latest_release(&repo_owner, &repo_name)
+            .await?
+            .map(|r| r.tag_name)
+            .ok_or_else(|| AppError::Validation("No previous release found; pass `from`".to_string()))?,
+    };
+
+    let commits = github_client
+        .compare_commits(&repo_owner, &repo_name, &from, &to, CHANGELOG_MAX_COMMITS)
+        .await?;
+
+    let mut seen = HashSet::new();
+    let mut prs = Vec::new();
+    for commit in &commits {
+        for pr in github_client.list_commit_pull_requests(&repo_owner, &repo_name, &commit.sha).await? {
+            if pr.merged_at.is_some() && seen.insert(pr.number) {
+                prs.push(pr);
+            }
+        }
+    }
+    prs.sort_by(|a, b| a.merged_at.cmp(&b.merged_at));
+
+    let heading = heading.unwrap_or_else(|| format!("{} ({})", to, chrono::Utc::now().format("%Y-%m-%d")));
+    let markdown = release::changelog(&heading, &prs);
+
+    Ok(json!({
+        "repository": format!("{}/{}", repo_owner, repo_name),
+        "from": from,
+        "to": to,
+        "commits": commits.len(),
+        "truncated": commits.len() >= CHANGELOG_MAX_COMMITS,
+        "pull_requests": prs.iter().map(|pr| json!({
+            "number": pr.number,
+            "title": pr.title,
+            "author": pr.user.login,
+            "section": release::changelog_section(pr),
+            "merged_at": pr.merged_at
+        })).collect::<Vec<_>>(),
+        "markdown": markdown
+    }))
+}
+
+fn file_commit_summary(commit: &Value) -> Value {
+    json!({
+        "sha": commit["sha"],
+        "url": commit["html_url"],
+        "message": commit["message"]
+    })
+}
+
+async fn execute_read_file(
+    state: AppState,
+    repository: Option<String>,
+    path: String,
+    git_ref: Option<String>,
+) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let file = github_client
+        .get_file(&repo_owner, &repo_name, &path, git_ref.as_deref())
+        .await?
+        .ok_or_else(|| super::error::GitHubError::not_found(format!("{} not found in {}/{}", path, repo_owner, repo_name)))?;
+    let bytes = file.decoded()?;
+
+    // Binary files come back base64-encoded rather than mangled
+    let (content, encoding) = match String::from_utf8(bytes) {
+        Ok(text) => (text, "utf-8"),
+        Err(_) => (file.content.clone().unwrap_or_default().replace('\n', ""), "base64"),
+    };
+
+    Ok(json!({
+        "repository": format!("{}/{}", repo_owner, repo_name),
+        "path": file.path,
+        "ref": git_ref,
+        "sha": file.sha,
+        "size": file.size,
+        "encoding": encoding,
+        "content": content,
+        "url": file.html_url
+    }))
+}
+
+async fn execute_write_file(
+    state: AppState,
+    repository: Option<String>,
+    path: String,
+    content: String,
+    message: String,
+    branch: Option<String>,
+    sha: Option<String>,
+) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    // Without a base SHA the edit applies to whatever is on the branch now
+    let current = github_client.get_file(&repo_owner, &repo_name, &path, branch.as_deref()).await?;
+    let base_sha = match (sha, &current) {
+        (Some(sha), _) => Some(sha),
+        (None, Some(file)) => Some(file.sha.clone()),
+        (None, None) => None,
+    };
+    if current.as_ref().map_or(false, |f| f.content_type != "file") {
+        return Err(AppError::Validation(format!("{} is not a regular file", path)));
+    }
+
+    let commit = github_client
+        .put_file(&repo_owner, &repo_name, &path, &message, content.as_bytes(), base_sha.as_deref(), branch.as_deref())
+        .await?;
+    let created = current.is_none();
+    info!("{} {} in {}/{}", if created { "Created" } else { "Updated" }, path, repo_owner, repo_name);
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("📝 {} {}", if created { "Created" } else { "Updated" }, path),
+        "path": path,
+        "branch": branch,
+        "created": created,
+        "commit": file_commit_summary(&commit)
+    }))
+}
+
+async fn execute_delete_file(
+    state: AppState,
+    repository: Option<String>,
+    path: String,
+    message: String,
+    branch: Option<String>,
+    sha: Option<String>,
+) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let sha = match sha {
+        Some(sha) => sha,
+        None => github_client
+            .get_file(&repo_owner, &repo_name, &path, branch.as_deref())
+            .await?
+            .map(|f| f.sha)
+            .ok_or_else(|| super::error::GitHubError::not_found(format!("{} not found in {}/{}", path, repo_owner, repo_name)))?,
+    };
+
+    let commit = github_client
+        .delete_file(&repo_owner, &repo_name, &path, &message, &sha, branch.as_deref())
+        .await?;
+    info!("Deleted {} in {}/{}", path, repo_owner, repo_name);
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("🗑️ Deleted {}", path),
+        "path": path,
+        "branch": branch,
+        "commit": file_commit_summary(&commit)
+    }))
+}
+
+async fn execute_wiki(
+    state: AppState,
+    repository: Option<String>,
+    action: Option<String>,
+    page: Option<String>,
+    content: Option<String>,
+    message: Option<String>,
+) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let full_name = format!("{}/{}", repo_owner, repo_name);
+    let checkout = wiki::checkout_path(Path::new(&state.config.workflow.wiki_dir), &repo_owner, &repo_name)?;
+    let url = wiki::clone_url(&state.config.github.host, &repo_owner, &repo_name);
+
+    let action = action.unwrap_or_else(|| if page.is_some() { "read" } else { "list" }.to_string());
+    let required = |value: Option<String>, name: &str| {
+        value.ok_or_else(|| AppError::Validation(format!("{} is required to {} a wiki page", name, action)))
+    };
+
+    match action.as_str() {
+        "list" => {
+            let pages = git::run(move |g, _| wiki::with_checkout(g, &url, &checkout, |_, dir| wiki::list_pages(dir))).await?;
+            Ok(json!({
+                "status": "success",
+                "repository": full_name,
+                "count": pages.len(),
+                "pages": pages
+            }))
+        }
+        "read" => {
+            let page = required(page, "page")?;
+            let title = page.clone();
+            let (file, content) =
+                git::run(move |g, _| wiki::with_checkout(g, &url, &checkout, |_, dir| wiki::read_page(dir, &title))).await?;
+            Ok(json!({
+                "status": "success",
+                "repository": full_name,
+                "page": page,
+                "file": file,
+                "content": content
+            }))
+        }
+        "write" | "delete" => {
+            let page = required(page, "page")?;
+            let verb = if action == "write" { "Update" } else { "Delete" };
+            let message = message.unwrap_or_else(|| format!("{} {}", verb, page));
+            let change = if action == "write" {
+                let content = required(content, "content")?;
+                git::run(move |g, _| {
+                    wiki::edit(g, &url, &checkout, |g, dir| wiki::write_page(g, dir, &page, &content, &message))
+                })
+                .await?
+            } else {
+                git::run(move |g, _| wiki::edit(g, &url, &checkout, |g, dir| wiki::delete_page(g, dir, &page, &message))).await?
+            };
+            info!("Wiki {} {} in {}: {}", action, change.page, full_name, if change.changed { "pushed" } else { "unchanged" });
+
+            Ok(json!({
+                "status": "success",
+                "message": match (action.as_str(), change.created, change.changed) {
+                    ("delete", _, _) => format!("🗑️ Deleted wiki page {}", change.page),
+                    (_, _, false) => format!("Wiki page {} is already up to date", change.page),
+                    (_, true, _) => format!("📝 Created wiki page {}", change.page),
+                    _ => format!("📝 Updated wiki page {}", change.page),
+                },
+                "repository": full_name,
+                "change": change
+            }))
+        }
+        other => Err(AppError::Validation(format!("Unknown wiki action: {}", other))),
+    }
+}
+
+/// Content fields for a new advisory, as passed to `github_draft_advisory`
+struct AdvisoryDraft {
+    summary: Option<String>,
+    description: Option<String>,
+    severity: Option<String>,
+    cwe_ids: Option<Vec<String>>,
+    vulnerabilities: Option<Vec<super::api::AdvisoryVulnerability>>,
+}
+
+fn advisory_summary(advisory: &super::api::GitHubAdvisory) -> Value {
+    json!({
+        "ghsa_id": advisory.ghsa_id,
+        "cve_id": advisory.cve_id,
+        "summary": advisory.summary,
+        "state": advisory.state,
+        "severity": advisory.severity,
+        "credits": advisory.credits,
+        "url": advisory.html_url
+    })
+}
+
+/// Draft a new security advisory, or add credits to an existing one, then
+/// optionally request a CVE for it
+async fn execute_draft_advisory(
+    state: AppState,
+    repository: Option<String>,
+    ghsa_id: Option<String>,
+    draft: AdvisoryDraft,
+    credits: Vec<AdvisoryCredit>,
+    request_cve: Option<bool>,
+) -> Result<Value> {
+    for credit in &credits {
+        if !ADVISORY_CREDIT_TYPES.contains(&credit.credit_type.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Invalid credit type '{}': use one of {}",
+                credit.credit_type,
+                ADVISORY_CREDIT_TYPES.join(", ")
+            )));
+        }
+    }
+    if let Some(severity) = &draft.severity {
+        if !ADVISORY_SEVERITIES.contains(&severity.as_str()) {
+            return Err(AppError::Validation(format!("Invalid severity '{}': use critical, high, medium or low", severity)));
+        }
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let (mut advisory, created) = match ghsa_id {
+        Some(ghsa_id) => {
+            let mut advisory = github_client.get_security_advisory(&repo_owner, &repo_name, &ghsa_id).await?;
+            let mut merged = advisory.credits.clone();
+            for credit in credits {
+                if !merged.contains(&credit) {
+                    merged.push(credit);
+                }
+            }
+            if merged.len() != advisory.credits.len() {
+                advisory = github_client.set_advisory_credits(&repo_owner, &repo_name, &ghsa_id, &merged).await?;
+            }
+            (advisory, false)
+        }
+        None => {
+            let (Some(summary), Some(description)) = (draft.summary, draft.description) else {
+                return Err(AppError::Validation("A new advisory needs a summary and description".to_string()));
+            };
+            let vulnerabilities = draft.vulnerabilities.unwrap_or_default();
+            if vulnerabilities.is_empty() {
+                return Err(AppError::Validation(
+                    "A new advisory needs at least one affected package in vulnerabilities".to_string(),
+                ));
+            }
+
+            let advisory = github_client.create_security_advisory(&repo_owner, &repo_name, &super::api::NewAdvisory {
+                summary,
+                description,
+                severity: draft.severity,
+                cwe_ids: draft.cwe_ids.unwrap_or_default(),
+                vulnerabilities,
+                credits,
+            }).await?;
+            info!("Drafted security advisory {} in {}/{}", advisory.ghsa_id, repo_owner, repo_name);
+            (advisory, true)
+        }
+    };
+
+    // An advisory that already has a CVE is left alone
+    let mut cve_requested = false;
+    if request_cve == Some(true) && advisory.cve_id.is_none() {
+        github_client.request_advisory_cve(&repo_owner, &repo_name, &advisory.ghsa_id).await?;
+        advisory = github_client.get_security_advisory(&repo_owner, &repo_name, &advisory.ghsa_id).await?;
+        cve_requested = true;
+    }
+
+    Ok(json!({
+        "status": "success",
+        "message": format!(
+            "🔒 {} {}{}",
+            if created { "Drafted advisory" } else { "Updated advisory" },
+            advisory.ghsa_id,
+            if cve_requested { ", CVE requested" } else { "" }
+        ),
+        "advisory": advisory_summary(&advisory),
+        "cve_requested": cve_requested
+    }))
+}
+
+/// Protection on `branch` of the current repository, when the token can read
+/// it, with who the token pushes as
+async fn current_branch_protection(state: &AppState, branch: &str) -> Option<(GitHubBranchProtection, protection::Pusher)> {
+    let github_client = get_github_client(state.clone(), caller::user_id()).await.ok()?;
+    let (repo_owner, repo_name) = get_remote_repository().await.ok()?;
+    let protection = match github_client.get_branch_protection(&repo_owner, &repo_name, branch).await {
+        Ok(protection) => protection?,
+        Err(e) => {
+            warn!("Failed to read protection for {}: {}", branch, e);
+            return None;
+        }
+    };
+    let pusher = match protection_pusher(&github_client, &repo_owner, &repo_name, &protection).await {
+        Ok(pusher) => pusher,
+        Err(e) => {
+            // Without knowing who pushes, report every rule
+            warn!("Failed to work out who pushes to {}: {}", branch, e);
+            protection::Pusher::default()
+        }
+    };
+    Some((protection, pusher))
+}
+
+/// Who `github_client` pushes as, with what branch protection lets through:
+/// admin access and membership of the teams allowed to push
+async fn protection_pusher(
+    github_client: &GitHubClient,
+    repo_owner: &str,
+    repo_name: &str,
+    rules: &GitHubBranchProtection,
+) -> Result<protection::Pusher> {
+    let login = github_client.get_user().await?.login;
+    let admin = github_client
+        .get_repository(repo_owner, repo_name)
+        .await?
+        .permissions
+        .map_or(false, |permissions| permissions.admin);
+    let mut teams = Vec::new();
+    for team in protection::restricted_teams(rules) {
+        if github_client.is_team_member(repo_owner, &team, &login).await? {
+            teams.push(team);
+        }
+    }
+    Ok(protection::Pusher { login, admin, teams })
+}
+
+async fn execute_get_branch_protection(state: AppState, repository: Option<String>, branch: Option<String>) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let branch = match branch {
+        Some(branch) => branch,
+        None => github_client.get_repository(&repo_owner, &repo_name).await?.default_branch,
+    };
+    let rules = github_client.get_branch_protection(&repo_owner, &repo_name, &branch).await?;
+    let push_blockers = match &rules {
+        Some(rules) => {
+            let pusher = protection_pusher(&github_client, &repo_owner, &repo_name, rules).await?;
+            protection::push_blockers(rules, &pusher)
+        }
+        None => Vec::new(),
+    };
+
+    Ok(json!({
+        "repository": format!("{}/{}", repo_owner, repo_name),
+        "branch": branch,
+        "protected": rules.is_some(),
+        "protection": rules.as_ref().map(protection::summary),
+        "push_blockers": push_blockers,
+        "message": match &rules {
+            Some(_) => format!("🔒 {} is protected", branch),
+            None => format!("🔓 {} is unprotected, or its protection isn't readable without admin access", branch),
+        }
+    }))
+}
+
+/// Apply `settings` over the branch's current protection, keeping every rule
+/// the update doesn't mention
+async fn execute_update_branch_protection(
+    state: AppState,
+    repository: Option<String>,
+    branch: Option<String>,
+    settings: protection::ProtectionUpdate,
+) -> Result<Value> {
+    if settings.is_empty() {
+        return Err(AppError::Validation("No protection settings to change".to_string()));
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let branch = match branch {
+        Some(branch) => branch,
+        None => github_client.get_repository(&repo_owner, &repo_name).await?.default_branch,
+    };
+    let current = github_client.get_branch_protection(&repo_owner, &repo_name, &branch).await?;
+    let body = protection::put_body(current.as_ref(), &settings);
+    let updated = github_client.update_branch_protection(&repo_owner, &repo_name, &branch, &body).await?;
+
+    info!("Updated branch protection on {}/{}:{}", repo_owner, repo_name, branch);
+    Ok(json!({
+        "status": "success",
+        "message": format!("🔒 Updated protection on {}", branch),
+        "repository": format!("{}/{}", repo_owner, repo_name),
+        "branch": branch,
+        "previous": current.as_ref().map(protection::summary),
+        "protection": protection::summary(&updated)
+    }))
+}
+
+async fn execute_search_code(
+    state: AppState,
+    query: String,
+    repository: Option<String>,
+    page: Option<u32>,
+    per_page: Option<u32>,
+) -> Result<Value> {
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let query = search::scoped_query(&query, repository.as_deref());
+    let (page, per_page) = (page.unwrap_or(1), per_page.unwrap_or(search::DEFAULT_PER_PAGE));
+    let results = github_client
+        .search::<GitHubCodeResult>("code", &query, &search::page_params(page, per_page, None, None), true)
+        .await?;
+
+    let items = results.items.iter().map(search::code_hit).collect();
+    Ok(search_response(&query, page, per_page, results.total_count, results.incomplete_results, items))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn execute_search_issues(
+    state: AppState,
+    query: String,
+    repository: Option<String>,
+    sort: Option<String>,
+    order: Option<String>,
+    page: Option<u32>,
+    per_page: Option<u32>,
+) -> Result<Value> {
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let query = search::scoped_query(&query, repository.as_deref());
+    let (page, per_page) = (page.unwrap_or(1), per_page.unwrap_or(search::DEFAULT_PER_PAGE));
+    let params = search::page_params(page, per_page, sort.as_deref(), order.as_deref());
+    let results = github_client.search::<GitHubIssueResult>("issues", &query, &params, false).await?;
+
+    let items = results.items.iter().map(search::issue_hit).collect();
+    Ok(search_response(&query, page, per_page, results.total_count, results.incomplete_results, items))
+}
+
+async fn execute_search_repos(
+    state: AppState,
+    query: String,
+    sort: Option<String>,
+    order: Option<String>,
+    page: Option<u32>,
+    per_page: Option<u32>,
+) -> Result<Value> {
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let (page, per_page) = (page.unwrap_or(1), per_page.unwrap_or(search::DEFAULT_PER_PAGE));
+    let params = search::page_params(page, per_page, sort.as_deref(), order.as_deref());
+    let results = github_client.search::<GitHubRepositoryResult>("repositories", &query, &params, false).await?;
+
+    let items = results.items.iter().map(search::repository_hit).collect();
+    Ok(search_response(&query, page, per_page, results.total_count, results.incomplete_results, items))
+}
+
+async fn execute_graphql(state: AppState, query: String, variables: Option<Value>) -> Result<Value> {
+    let variables = variables.unwrap_or_else(|| json!({}));
+    if !variables.is_object() {
+        return Err(AppError::Validation("GraphQL variables must be an object".to_string()));
+    }
+
+    let shape = graphql::analyze(&query, &variables)
+        .map_err(|e| AppError::Validation(format!("Invalid GraphQL document: {}", e)))?;
+    let limits = &state.config.github;
+    if shape.depth > limits.graphql_max_depth {
+        return Err(AppError::Validation(format!(
+            "GraphQL query nests {} levels deep, over the limit of {}",
+            shape.depth, limits.graphql_max_depth
+        )));
+    }
+    if shape.nodes > limits.graphql_max_nodes {
+        return Err(AppError::Validation(format!(
+            "GraphQL query may return {} nodes, over the limit of {}; request smaller pages",
+            shape.nodes, limits.graphql_max_nodes
+        )));
+    }
+
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    match shape.operation {
+        graphql::OperationKind::Query => {}
+        graphql::OperationKind::Subscription => {
+            return Err(AppError::Validation("GraphQL subscriptions are not supported".to_string()));
+        }
+        graphql::OperationKind::Mutation => {
+            if !limits.graphql_mutations {
+                return Err(AppError::Authorization(
+                    "GraphQL mutations are disabled; set GRAPHQL_MUTATIONS=true to allow them for administrators".to_string(),
+                ));
+            }
+            let login = github_client.get_user().await?.login;
+            if !state.config.security.admin_users.iter().any(|u| u.eq_ignore_ascii_case(&login)) {
+                return Err(AppError::Authorization(format!("{} may not run GraphQL mutations", login)));
+            }
+            info!("Running GraphQL mutation for {}", login);
+        }
+    }
+
+    let data = github_client.graphql(&query, variables).await?;
+
+    Ok(json!({
+        "status": "success",
+        "operation": shape.operation,
+        "depth": shape.depth,
+        "estimated_nodes": shape.nodes,
+        "data": data
+    }))
+}
+
+async fn execute_rest_get(
+    state: AppState,
+    path: String,
+    params: Option<HashMap<String, String>>,
+    max_pages: Option<u32>,
+) -> Result<Value> {
+    let path = rest::normalize_path(&path).map_err(AppError::Validation)?;
+    let allowed = state.config.github.rest_allowed_paths.clone();
+
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    // Checked as each request will actually be sent, next pages included
+    let check = |path: &str| -> Result<()> {
+        let resolved = github_client.resolved_api_path(path)?;
+        if !rest::path_allowed(&resolved, &allowed) {
+            return Err(AppError::Authorization(format!(
+                "{} is not in REST_ALLOWED_PATHS ({})",
+                resolved,
+                allowed.join(", ")
+            )));
+        }
+        Ok(())
+    };
+
+    let params: Vec<(&str, String)> = params
+        .iter()
+        .flatten()
+        .map(|(k, v)| (k.as_str(), v.clone()))
+        .collect();
+    let max_pages = max_pages.unwrap_or(rest::DEFAULT_MAX_PAGES).clamp(1, rest::MAX_PAGES);
+
+    let mut combined = None;
+    let mut pages = 0;
+    check(&path)?;
+    let (first, mut next) = github_client.get_json_page(&path, &params).await?;
+    rest::merge_page(&mut combined, first);
+    pages += 1;
+    while pages < max_pages {
+        let Some(url) = next.take() else { break };
+        check(&url)?;
+        let (page, following) = github_client.get_json_page(&url, &[]).await?;
+        rest::merge_page(&mut combined, page);
+        next = following;
+        pages += 1;
+    }
+    let data = combined.unwrap_or(Value::Null);
+
+    Ok(json!({
+        "status": "success",
+        "path": path,
+        "pages": pages,
+        "items": rest::item_count(&data),
+        "next_page_url": next,
+        "data": data
+    }))
+}
+
+fn search_response(query: &str, page: u32, per_page: u32, total_count: u64, incomplete: bool, items: Vec<Value>) -> Value {
+    json!({
+        "query": query,
+        "total_count": total_count,
+        "incomplete_results": incomplete,
+        "page": page.max(1),
+        "per_page": per_page.clamp(1, search::MAX_PER_PAGE),
+        "next_page": search::next_page(total_count, page, per_page),
+        "items": items
+    })
+}
+
+async fn execute_get_timeline(
+    state: AppState,
+    repository: Option<String>,
+    number: u64,
+    events: Option<Vec<String>>,
+) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let (raw, truncated) = github_client
+        .list_issue_timeline(&repo_owner, &repo_name, number, timeline::MAX_PAGES)
+        .await?;
+    let timeline: Vec<Value> = raw
+        .iter()
+        .filter(|event| {
+            events.as_ref().map_or(true, |wanted| {
+                event["event"].as_str().map_or(false, |kind| wanted.iter().any(|w| w == kind))
+            })
+        })
+        .map(timeline::summarize)
+        .collect();
+
+    Ok(json!({
+        "repository": format!("{}/{}", repo_owner, repo_name),
+        "number": number,
+        "count": timeline.len(),
+        "truncated": truncated,
+        "events": timeline
+    }))
+}
+
+async fn execute_get_pull_request_diff(
+    state: AppState,
+    repository: Option<String>,
+    number: u64,
+    per_file: Option<bool>,
+    files: Option<Vec<String>>,
+    max_bytes: Option<usize>,
+) -> Result<Value> {
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_DIFF_MAX_BYTES);
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    if per_file != Some(true) && files.is_none() {
+        let diff = github_client.get_pull_request_diff(&repo_owner, &repo_name, number).await?;
+        let (text, truncated) = truncate_diff(&diff, max_bytes);
+        return Ok(json!({
+            "number": number,
+            "diff": text,
+            "bytes": diff.len(),
+            "truncated": truncated
+        }));
+    }
+
+    let mut changed = github_client.list_pull_request_files(&repo_owner, &repo_name, number).await?;
+    if let Some(wanted) = &files {
+        changed.retain(|f| wanted.iter().any(|w| w == &f.filename));
+    }
+
+    // Share the budget across files in order; later files lose their patch first
+    let mut remaining = max_bytes;
+    let mut truncated = false;
+    let entries: Vec<Value> = changed
+        .iter()
+        .map(|file| {
+            let patch = file.patch.as_deref().map(|patch| {
+                let (text, cut) = truncate_diff(patch, remaining);
+                remaining -= text.len();
+                truncated |= cut;
+                (text, cut)
+            });
+            json!({
+                "filename": file.filename,
+                "previous_filename": file.previous_filename,
+                "status": file.status,
+                "additions": file.additions,
+                "deletions": file.deletions,
+                "patch": patch.map(|(text, _)| text),
+                "patch_truncated": patch.map_or(false, |(_, cut)| cut),
+                "binary_or_too_large": file.patch.is_none()
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "number": number,
+        "files": entries,
+        "file_count": changed.len(),
+        "additions": changed.iter().map(|f| f.additions).sum::<u64>(),
+        "deletions": changed.iter().map(|f| f.deletions).sum::<u64>(),
+        "truncated": truncated
+    }))
+}
+
+fn review_summary(review: &super::api::GitHubReview) -> Value {
+    json!({
+        "id": review.id,
+        "reviewer": review.user.as_ref().map(|u| u.login.clone()),
+        "state": review.state,
+        "body": review.body,
+        "commit_id": review.commit_id,
+        "submitted_at": review.submitted_at,
+        "url": review.html_url
+    })
+}
+
+fn validate_review_comment(comment: &ReviewCommentInput) -> Result<()> {
+    if comment.path.trim().is_empty() || comment.body.trim().is_empty() {
+        return Err(AppError::Validation("Review comments need a path and a body".to_string()));
+    }
+    if let Some(side) = &comment.side {
+        if side != "LEFT" && side != "RIGHT" {
+            return Err(AppError::Validation(format!("Invalid side '{}': use LEFT or RIGHT", side)));
+        }
+    }
+    if comment.start_line.map_or(false, |start| start >= comment.line) {
+        return Err(AppError::Validation("start_line must be before line".to_string()));
+    }
+    Ok(())
+}
+
+async fn execute_submit_review(
+    state: AppState,
+    repository: Option<String>,
+    number: u64,
+    event: String,
+    body: Option<String>,
+    comments: Option<Vec<ReviewCommentInput>>,
+) -> Result<Value> {
+    let event = event.to_uppercase();
+    if !REVIEW_EVENTS.contains(&event.as_str()) {
+        return Err(AppError::Validation(format!(
+            "Invalid review event '{}': use one of {}",
+            event,
+            REVIEW_EVENTS.join(", ")
+        )));
+    }
+    // GitHub rejects change requests and plain comments without a body
+    if event != "APPROVE" && body.as_deref().map_or(true, |b| b.trim().is_empty()) {
+        return Err(AppError::Validation(format!("A {} review needs a body", event)));
+    }
+    let comments = comments.unwrap_or_default();
+    for comment in &comments {
+        validate_review_comment(comment)?;
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let review = github_client
+        .create_review(&repo_owner, &repo_name, number, &event, body.as_deref(), &comments)
+        .await?;
+
+    let message = match event.as_str() {
+        "APPROVE" => format!("✅ Approved #{}", number),
+        "REQUEST_CHANGES" => format!("🔁 Requested changes on #{}", number),
+        _ => format!("💬 Reviewed #{}", number),
+    };
+
+    Ok(json!({
+        "status": "success",
+        "message": message,
+        "review": review_summary(&review),
+        "inline_comments": comments.len()
+    }))
+}
+
+async fn execute_add_review_comment(
+    state: AppState,
+    repository: Option<String>,
+    number: u64,
+    comment: ReviewCommentInput,
+) -> Result<Value> {
+    validate_review_comment(&comment)?;
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    // Anchor to the PR's current head so the line numbers match what the reviewer sees
+    let pr = github_client.get_pull_request(&repo_owner, &repo_name, number).await?;
+    let created = github_client
+        .create_review_comment(&repo_owner, &repo_name, number, &pr.head.sha, &comment)
+        .await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("💬 Commented on {}:{} in #{}", comment.path, comment.line, number),
+        "comment": {
+            "id": created["id"],
+            "url": created["html_url"],
+            "commit_id": pr.head.sha
+        }
+    }))
+}
+
+async fn execute_list_reviews(state: AppState, repository: Option<String>, number: u64) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let reviews = github_client.list_reviews(&repo_owner, &repo_name, number).await?;
+
+    // A reviewer's latest approval or change request is what counts
+    let mut latest: HashMap<String, &str> = HashMap::new();
+    for review in &reviews {
+        if let Some(user) = &review.user {
+            if review.state == "APPROVED" || review.state == "CHANGES_REQUESTED" || review.state == "DISMISSED" {
+                latest.insert(user.login.clone(), review.state.as_str());
+            }
+        }
+    }
+    let approved_by: Vec<&String> = latest.iter().filter(|(_, s)| **s == "APPROVED").map(|(u, _)| u).collect();
+    let changes_requested_by: Vec<&String> = latest.iter().filter(|(_, s)| **s == "CHANGES_REQUESTED").map(|(u, _)| u).collect();
+
+    Ok(json!({
+        "number": number,
+        "reviews": reviews.iter().map(review_summary).collect::<Vec<_>>(),
+        "approved_by": approved_by,
+        "changes_requested_by": changes_requested_by
+    }))
+}
+
+fn commit_summary(commit: &super::api::GitHubCommit) -> Value {
+    let author = commit.commit.author.as_ref();
+    json!({
+        "sha": commit.sha,
+        "message": commit.commit.message.lines().next().unwrap_or_default(),
+        "author": commit.author.as_ref().map(|a| a.login.clone()).or_else(|| author.map(|a| a.name.clone())),
+        "date": author.map(|a| a.date.clone()),
+        "verified": commit.is_verified(),
+        "verification_reason": commit.commit.verification.as_ref().map(|v| v.reason.clone()),
+        "url": commit.html_url
+    })
+}
+
+fn verification_summary(commits: &[super::api::GitHubCommit]) -> Value {
+    let verified = commits.iter().filter(|c| c.is_verified()).count();
+    json!({
+        "verified": verified,
+        "total": commits.len(),
+        "all_verified": verified == commits.len(),
+        "unverified": commits.iter().filter(|c| !c.is_verified()).map(|c| c.sha.clone()).collect::<Vec<_>>()
+    })
+}
+
+async fn execute_list_commits(
+    state: AppState,
+    repository: Option<String>,
+    branch: Option<String>,
+    pull_request: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let commits = match pull_request {
+        Some(number) => github_client.list_pull_request_commits(&repo_owner, &repo_name, number).await?,
+        None => github_client
+            .list_commits(&repo_owner, &repo_name, branch.as_deref(), limit.unwrap_or(30))
+            .await?,
+    };
+
+    Ok(json!({
+        "repository": format!("{}/{}", repo_owner, repo_name),
+        "branch": branch,
+        "pull_request": pull_request,
+        "commits": commits.iter().map(commit_summary).collect::<Vec<_>>(),
+        "verification": verification_summary(&commits)
+    }))
+}
+
+fn repo_metadata(repository: &super::api::GitHubRepository) -> Value {
+    json!({
+        "repository": repository.full_name,
+        "description": repository.description,
+        "homepage": repository.homepage,
+        "topics": repository.topics,
+        "default_branch": repository.default_branch,
+        "archived": repository.archived
+    })
+}
+
+async fn execute_get_repo_metadata(state: AppState, repository: Option<String>) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let repository = github_client.get_repository(&repo_owner, &repo_name).await?;
+    let report = policy::check_repository(
+        &repository,
+        &state.config.policy.required_topics,
+        state.config.policy.require_description,
+    );
+
+    let mut result = repo_metadata(&repository);
+    result["policy"] = json!(report);
+    Ok(result)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn execute_create_repository(
+    state: AppState,
+    name: String,
+    owner: Option<String>,
+    description: Option<String>,
+    private: Option<bool>,
+    default_branch: Option<String>,
+    merge_methods: Option<Vec<String>>,
+    delete_branch_on_merge: Option<bool>,
+    vulnerability_alerts: Option<bool>,
+    template: Option<String>,
+    labels: Option<bool>,
+    project: Option<bool>,
+) -> Result<Value> {
+    let name = name.trim().to_string();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+        return Err(AppError::Validation(format!(
+            "Invalid repository name '{}': use letters, digits, '-', '_' and '.'",
+            name
+        )));
+    }
+    let merge_methods = merge_methods
+        .unwrap_or_else(|| bootstrap::DEFAULT_MERGE_METHODS.iter().map(|m| m.to_string()).collect());
+    if merge_methods.is_empty() {
+        return Err(AppError::Validation("Allow at least one merge method".to_string()));
+    }
+    if let Some(unknown) = merge_methods.iter().find(|m| !matches!(m.as_str(), "merge" | "squash" | "rebase")) {
+        return Err(AppError::Validation(format!(
+            "Unknown merge method '{}': use merge, squash or rebase",
+            unknown
+        )));
+    }
+    // Read the template before anything is created, so a bad one stops here
+    let files = match &template {
+        Some(template) => {
+            let (templates_dir, name) = (state.config.workflow.repo_templates_dir.clone(), template.clone());
+            let files = tokio::task::spawn_blocking(move || {
+                bootstrap::read_template(&bootstrap::template_dir(&templates_dir, &name)?)
+            })
+            .await
+            .map_err(|e| AppError::Internal(format!("Reading the template failed: {}", e)))??;
+            if files.is_empty() {
+                return Err(AppError::Validation(format!("Template '{}' has no files", template)));
+            }
+            Some(files)
+        }
+        None => None,
+    };
+
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    // Naming the user's own account creates a personal repository; only
+    // organizations have an /orgs endpoint
+    let login = github_client.get_user().await?.login;
+    let organization = owner.filter(|owner| !owner.eq_ignore_ascii_case(&login));
+    let repo_owner = organization.clone().unwrap_or(login);
+    let full_name = format!("{}/{}", repo_owner, name);
+    authorize_repository(&state, &full_name, "create_repository", permissions::Operation::Admin).await?;
+
+    progress::report(&format!("📦 Creating {}", full_name));
+    let mut settings = json!({
+        "name": name,
+        "private": private.unwrap_or(true),
+        "auto_init": true,
+        "allow_merge_commit": merge_methods.iter().any(|m| m == "merge"),
+        "allow_squash_merge": merge_methods.iter().any(|m| m == "squash"),
+        "allow_rebase_merge": merge_methods.iter().any(|m| m == "rebase"),
+        "delete_branch_on_merge": delete_branch_on_merge.unwrap_or(true)
+    });
+    if let Some(description) = &description {
+        settings["description"] = Value::String(description.clone());
+    }
+    let repository = github_client.create_repository(organization.as_deref(), &settings).await?;
+    let repo_owner = repository.owner.login.clone();
+
+    // Everything after creation is best effort: the repository exists either
+    // way, and each step reports how it went
+    let mut steps = Vec::new();
+    let mut failed = 0;
+    let mut step = |what: &str, outcome: Result<Value>| {
+        match outcome {
+            Ok(detail) => steps.push(json!({ "step": what, "status": "done", "detail": detail })),
+            Err(e) => {
+                warn!("Failed to {} for {}: {}", what, full_name, e);
+                failed += 1;
+                steps.push(json!({ "step": what, "status": "failed", "error": e.to_string() }));
+            }
+        }
+    };
+
+    let initial_branch = repository.default_branch.clone();
+    let branch = default_branch.unwrap_or_else(|| initial_branch.clone());
+    let mut current_branch = initial_branch.clone();
+
+    if let Some(files) = &files {
+        progress::report(&format!("📄 Committing template {}", template.as_deref().unwrap_or_default()));
+        let outcome = async {
+            let sha = github_client
+                .create_root_commit(&repo_owner, &name, files, "Initial commit")
+                .await?;
+            github_client.set_branch(&repo_owner, &name, &branch, &sha).await?;
+            Ok::<_, AppError>(json!({ "template": template, "files": files.len(), "sha": sha }))
+        }
+        .await;
+        if outcome.is_ok() {
+            current_branch = branch.clone();
+        }
+        step("commit template", outcome);
+    } else if branch != initial_branch {
+        let outcome = async {
+            let head = github_client.get_commit(&repo_owner, &name, &initial_branch).await?;
+            github_client.set_branch(&repo_owner, &name, &branch, &head.sha).await?;
+            Ok::<_, AppError>(json!({ "branch": branch }))
+        }
+        .await;
+        if outcome.is_ok() {
+            current_branch = branch.clone();
+        }
+        step("create default branch", outcome);
+    }
+
+    if current_branch != initial_branch {
+        let outcome = async {
+            github_client
+                .update_repository(&repo_owner, &name, &json!({ "default_branch": current_branch }))
+                .await?;
+            github_client.delete_branch(&repo_owner, &name, &initial_branch).await?;
+            Ok::<_, AppError>(json!({ "default_branch": current_branch, "removed": initial_branch }))
+        }
+        .await;
+        if outcome.is_err() {
+            current_branch = initial_branch.clone();
+        }
+        step("set default branch", outcome);
+    }
+
+    if vulnerability_alerts.unwrap_or(true) {
+        let outcome = github_client
+            .enable_vulnerability_alerts(&repo_owner, &name)
+            .await
+            .map(|_| json!({ "enabled": true }));
+        step("enable vulnerability alerts", outcome);
+    }
+
+    if labels.unwrap_or(true) {
+        progress::report("🏷️ Applying the standard labels");
+        let outcome = async {
+            let existing = github_client.list_labels(&repo_owner, &name).await?;
+            let changes = labels::plan(&bootstrap::standard_labels(), &existing, false);
+            let errors = apply_label_changes(&github_client, &repo_owner, &name, &changes).await;
+            if !errors.is_empty() {
+                return Err(AppError::GitHubApi(format!("{} of {} label changes failed", errors.len(), changes.len())));
+            }
+            Ok::<_, AppError>(json!({ "changes": changes.len() }))
+        }
+        .await;
+        step("apply labels", outcome);
+    }
+
+    if project.unwrap_or(false) {
+        progress::report("📋 Creating a project board");
+        let outcome = async {
+            let repository_id = github_client.get_repository_node_id(&repo_owner, &name).await?;
+            let (number, url) = github_client.create_project(&repo_owner, &name, &repository_id).await?;
+            Ok::<_, AppError>(json!({ "number": number, "url": url }))
+        }
+        .await;
+        step("create project", outcome);
+    }
+
+    let message = if failed == 0 {
+        format!("📦 Created {}", full_name)
+    } else {
+        format!("📦 Created {}, but {} setup step(s) failed", full_name, failed)
+    };
+    Ok(json!({
+        "status": if failed == 0 { "success" } else { "partial" },
+        "message": message,
+        "repository": {
+            "full_name": repository.full_name,
+            "url": repository.html_url,
+            "default_branch": current_branch,
+            "private": private.unwrap_or(true),
+            "clone_url": repository.clone_url
+        },
+        "steps": steps
+    }))
+}
+
+async fn execute_update_repo_metadata(
+    state: AppState,
+    repository: Option<String>,
+    description: Option<String>,
+    homepage: Option<String>,
+    topics: Option<Vec<String>>,
+    add_topics: Option<Vec<String>>,
+    remove_topics: Option<Vec<String>>,
+) -> Result<Value> {
+    if description.is_none() && homepage.is_none() && topics.is_none() && add_topics.is_none() && remove_topics.is_none() {
+        return Err(AppError::Validation(
+            "Pass description, homepage, topics, add_topics or remove_topics".to_string(),
+        ));
+    }
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let mut repository = github_client.get_repository(&repo_owner, &repo_name).await?;
+
+    let mut changes = serde_json::Map::new();
+    if let Some(description) = description {
+        changes.insert("description".to_string(), json!(description));
+    }
+    if let Some(homepage) = homepage {
+        changes.insert("homepage".to_string(), json!(homepage));
+    }
+    if !changes.is_empty() {
+        let topics = repository.topics.clone();
+        repository = github_client
+            .update_repository(&repo_owner, &repo_name, &Value::Object(changes))
+            .await?;
+        // The PATCH response doesn't always echo topics
+        if repository.topics.is_empty() {
+            repository.topics = topics;
+        }
+    }
+
+    if topics.is_some() || add_topics.is_some() || remove_topics.is_some() {
+        let mut names: Vec<String> = topics.unwrap_or_else(|| repository.topics.clone());
+        names.extend(add_topics.unwrap_or_default());
+        let remove = remove_topics.unwrap_or_default();
+        let mut names: Vec<String> = names
+            .into_iter()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !remove.iter().any(|r| r.eq_ignore_ascii_case(t)))
+            .collect();
+        names.sort();
+        names.dedup();
+
+        if let Some(bad) = names.iter().find(|t| !policy::is_valid_topic(t)) {
+            return Err(AppError::Validation(format!(
+                "Invalid topic '{}': use lowercase letters, numbers and hyphens (max 50 characters)",
+                bad
+            )));
+        }
+
+        info!("Setting topics on {}/{}: {:?}", repo_owner, repo_name, names);
+        repository.topics = github_client.replace_topics(&repo_owner, &repo_name, &names).await?;
+    }
+
+    Ok(json!({
+        "status": "success",
+        "metadata": repo_metadata(&repository)
+    }))
+}
+
+async fn execute_check_repo_policy(state: AppState, org: Option<String>, repository: Option<String>) -> Result<Value> {
+    let required_topics = &state.config.policy.required_topics;
+    let require_description = state.config.policy.require_description;
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let repositories = match org {
+        Some(org) => github_client
+            .list_org_repositories(&org)
+            .await?
+            .into_iter()
+            .filter(|r| !r.archived)
+            .collect(),
+        None => {
+            let (repo_owner, repo_name) = resolve_repository(repository).await?;
+            vec![github_client.get_repository(&repo_owner, &repo_name).await?]
+        }
+    };
+
+    let reports: Vec<policy::PolicyReport> = repositories
+        .iter()
+        .map(|r| policy::check_repository(r, required_topics, require_description))
+        .collect();
+    let non_compliant: Vec<&policy::PolicyReport> = reports.iter().filter(|r| !r.compliant).collect();
+
+    Ok(json!({
+        "required_topics": required_topics,
+        "require_description": require_description,
+        "checked": reports.len(),
+        "compliant": reports.len() - non_compliant.len(),
+        "non_compliant": non_compliant
+    }))
+}
+
+async fn execute_add_reaction(
+    state: AppState,
+    repository: Option<String>,
+    number: Option<u64>,
+    comment_id: Option<u64>,
+    review_comment: Option<bool>,
+    content: Option<String>,
+) -> Result<Value> {
+    let target = reaction_target(number, comment_id, review_comment)?;
+    let content = match content.as_deref().map(str::to_lowercase).as_deref() {
+        None | Some("thumbsup") | Some("+1") => "+1".to_string(),
+        Some("thumbsdown") | Some("-1") => "-1".to_string(),
+        Some(other) if REACTION_CONTENTS.contains(&other) => other.to_string(),
+        Some(other) => {
+            return Err(AppError::Validation(format!(
+                "Unknown reaction \"{}\"; expected one of {}",
+                other,
+                REACTION_CONTENTS.join(", ")
+            )))
+        }
+    };
+
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let reaction = github_client.add_reaction(&repo_owner, &repo_name, target, &content).await?;
+
+    Ok(json!({
+        "status": "success",
+        "reaction": reaction,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }))
+}
+
+async fn execute_get_reactions(
+    state: AppState,
+    repository: Option<String>,
+    number: Option<u64>,
+    comment_id: Option<u64>,
+    review_comment: Option<bool>,
+    own_comments: Option<bool>,
+) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    // Feedback on the caller's own comments, using the reaction rollups GitHub includes
+    if own_comments.unwrap_or(false) {
+        let number = number.ok_or_else(|| AppError::Validation("own_comments requires number".to_string()))?;
+        let login = github_client.get_user().await?.login;
+        let comments = github_client.list_issue_comments(&repo_owner, &repo_name, number).await?;
+
+        let feedback: Vec<Value> = comments
+            .iter()
+            .filter(|c| c.pointer("/user/login").and_then(|l| l.as_str()) == Some(login.as_str()))
+            .map(|c| {
+                let mut counts = serde_json::Map::new();
+                for content in REACTION_CONTENTS {
+                    let count = c.pointer(&format!("/reactions/{}", content)).and_then(|n| n.as_u64()).unwrap_or(0);
+                    if count > 0 {
+                        counts.insert(content.to_string(), json!(count));
+                    }
+                }
+                json!({
+                    "comment_id": c["id"],
+                    "url": c["html_url"],
+                    "created_at": c["created_at"],
+                    "reactions": counts
+                })
+            })
+            .collect();
+
+        return Ok(json!({ "number": number, "login": login, "comments": feedback }));
+    }
+
+    let target = reaction_target(number, comment_id, review_comment)?;
+    let reactions = github_client.list_reactions(&repo_owner, &repo_name, target).await?;
+
+    let mut counts = serde_json::Map::new();
+    for reaction in &reactions {
+        let count = counts.entry(reaction.content.clone()).or_insert(json!(0));
+        *count = json!(count.as_u64().unwrap_or(0) + 1);
+    }
+
+    Ok(json!({
+        "total": reactions.len(),
+        "counts": counts,
+        "reactions": reactions
+            .iter()
+            .map(|r| json!({
+                "content": r.content,
+                "user": r.user.as_ref().map(|u| u.login.as_str()),
+                "created_at": r.created_at
+            }))
+            .collect::<Vec<_>>()
+    }))
+}
+
+fn reaction_target(number: Option<u64>, comment_id: Option<u64>, review_comment: Option<bool>) -> Result<ReactionTarget> {
+    match (comment_id, number) {
+        (Some(id), _) if review_comment.unwrap_or(false) => Ok(ReactionTarget::ReviewComment(id)),
+        (Some(id), _) => Ok(ReactionTarget::IssueComment(id)),
+        (None, Some(number)) => Ok(ReactionTarget::Issue(number)),
+        (None, None) => Err(AppError::Validation("Pass number or comment_id".to_string())),
+    }
+}
+
+async fn execute_cleanup_work_folder(state: AppState, branch: Option<String>, dry_run: Option<bool>) -> Result<Value> {
+    let branch = match branch {
+        Some(branch) => branch,
+        None => get_current_branch().await?,
+    };
+    let dry_run = dry_run.unwrap_or(true);
+
+    let report = cleanup_branch_work_folder(&state, &branch, dry_run).await?;
+    let message = match (report.exists, dry_run) {
+        (false, _) => format!("No work folder at {}", report.folder),
+        (true, true) => format!("Would remove {} ({} files)", report.folder, report.paths.len()),
+        (true, false) => format!("🧹 Removed {}", report.folder),
+    };
+
+    Ok(json!({
+        "status": "success",
+        "message": message,
+        "work_folder": report,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }))
+}
+
+/// The response refusing a push of `branch` when the commits it would send
+/// add something that looks like a credential
+async fn secret_scan_block(state: &AppState, branch: &str) -> Result<Option<Value>> {
+    if !state.config.workflow.secret_scan {
+        return Ok(None);
+    }
+    let settings = main_branch_secret_scan_settings(state).await?;
+    if settings.enabled == Some(false) {
+        return Ok(None);
+    }
+    let scanner = secret_scan::Scanner::new(&settings)?;
+
+    progress::report("🔑 Scanning outgoing commits for secrets");
+    let target = branch.to_string();
+    let diff = git::run(move |g, dir| g.outgoing_diff(dir, &target)).await?;
+    let findings = scanner.scan_diff(&diff);
+    if findings.is_empty() {
+        return Ok(None);
+    }
+
+    warn!("Refusing to push {}: {} possible secrets in outgoing commits", branch, findings.len());
+    Ok(Some(json!({
+        "status": "blocked",
+        "message": format!("🔑 Push blocked: {} possible secrets in the commits being pushed", findings.len()),
+        "branch": branch,
+        "findings": findings,
+        "suggestion": format!(
+            "Remove the secrets from the commits that add them (amend or rebase) and rotate them. \
+             For a false positive, add `{}` to the line or adjust [secret_scan] in {}.",
+            secret_scan::ALLOW_MARKER,
+            super::repo_config::WORKFLOW_FILE
+        )
+    })))
+}
+
+/// `[secret_scan]` from the workflow file on the remote's main branch. The
+/// commits being pushed can't be trusted to say how they're scanned, or
+/// they could turn the scan off or allow their own paths.
+async fn main_branch_secret_scan_settings(state: &AppState) -> Result<super::repo_config::SecretScanSettings> {
+    let (repo_owner, repo_name) = get_remote_repository().await?;
+    let main_branch = git::run(|g, dir| g.main_branch(dir)).await?;
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let file = github_client
+        .get_file(&repo_owner, &repo_name, super::repo_config::WORKFLOW_FILE, Some(&main_branch))
+        .await?;
+    match file {
+        Some(file) => {
+            let contents = String::from_utf8_lossy(&file.decoded()?).into_owned();
+            Ok(super::repo_config::parse_workflow_file(&contents)?.secret_scan)
+        }
+        None => Ok(Default::default()),
+    }
+}
+
+/// Describe the staged (or whole working tree) diff for an agent to write a
+/// commit message from, or commit it with `message` once written
+async fn execute_suggest_commit_message(
+    staged: Option<bool>,
+    message: Option<String>,
+    max_diff_bytes: Option<usize>,
+) -> Result<Value> {
+    let status = get_git_status().await?;
+    // Porcelain status puts the index state first; `??` is untracked
+    let anything_staged = status.iter().any(|line| !matches!(line.chars().next(), Some(' ' | '?') | None));
+    let untracked: Vec<String> = status.iter().filter_map(|line| line.strip_prefix("?? ").map(String::from)).collect();
+    let staged = staged.unwrap_or(anything_staged);
+    let scope = if staged { "staged" } else { "working_tree" };
+
+    let files = git::run(move |g, dir| g.diff_stats(dir, staged)).await?;
+    // Committing the working tree also adds untracked files
+    if files.is_empty() && (staged || untracked.is_empty()) {
+        return Ok(json!({
+            "status": "error",
+            "message": match staged {
+                true => "Nothing is staged. Stage changes, or pass staged: false to use the whole working tree.",
+                false => "No changes to commit",
+            },
+            "scope": scope
+        }));
+    }
+    let summary = commit_message::summarize(&files);
+
+    if let Some(message) = message {
+        let message = message.trim().to_string();
+        if message.is_empty() {
+            return Err(AppError::Validation("message must not be empty".to_string()));
+        }
+        progress::report("📝 Committing changes");
+        if staged {
+            let commit = message.clone();
+            git::run(move |g, dir| g.commit_staged(dir, &commit)).await?;
+        } else {
+            commit_changes(&message).await?;
+        }
+        return Ok(json!({
+            "status": "success",
+            "message": format!("✅ Committed {} files", summary.files_changed + if staged { 0 } else { untracked.len() }),
+            "committed": true,
+            "scope": scope,
+            "commit_message": message,
+            "summary": summary,
+            "files": files
+        }));
+    }
+
+    let diff = git::run(move |g, dir| g.diff(dir, staged)).await?;
+    let (diff, diff_truncated) = commit_message::truncate(diff, max_diff_bytes.unwrap_or(COMMIT_DIFF_MAX_BYTES));
+    Ok(json!({
+        "status": "success",
+        "message": format!(
+            "{} files changed, +{} -{}",
+            summary.files_changed, summary.additions, summary.deletions
+        ),
+        "committed": false,
+        "scope": scope,
+        "template": commit_message::template(&summary),
+        "summary": summary,
+        "files": files,
+        // Not in the diff, but committed with the working tree
+        "untracked_files": untracked,
+        "diff": diff,
+        "diff_truncated": diff_truncated,
+        "next_step": "Write a message following the template, then call github_suggest_commit_message again with the same staged value and `message` to commit"
+    }))
+}
+
+/// Ask the code owners of a PR's changed paths to review it
+async fn execute_request_reviewers(
+    state: AppState,
+    repository: Option<String>,
+    pull_request: Option<u64>,
+    reviewers: Option<Vec<String>>,
+    dry_run: Option<bool>,
+) -> Result<Value> {
+    let (repo_owner, repo_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let pr = match pull_request {
+        Some(number) => github_client.get_pull_request(&repo_owner, &repo_name, number).await?,
+        None => get_pr_for_branch(&github_client, &get_current_branch().await?).await?,
+    };
+    let dry_run = dry_run.unwrap_or(false);
+    let mut result = request_codeowner_reviews(
+        &github_client,
+        &repo_owner,
+        &repo_name,
+        &pr,
+        &reviewers.unwrap_or_default(),
+        dry_run,
+    )
+    .await?;
+
+    let requested = result["reviewers"].as_array().map_or(0, Vec::len) + result["team_reviewers"].as_array().map_or(0, Vec::len);
+    result["status"] = json!("success");
+    result["message"] = json!(match (requested, dry_run) {
+        (0, _) => format!("No reviewers to request on PR #{}", pr.number),
+        (n, true) => format!("Would request {} reviewers on PR #{}", n, pr.number),
+        (n, false) => format!("👀 Requested {} reviewers on PR #{}", n, pr.number),
+    });
+    Ok(result)
+}
+
+/// Work out who owns the paths `pr` changes from the CODEOWNERS on its base
+/// branch and, unless `dry_run`, request their review along with `extra`
+/// (users, or teams as `org/team`)
+async fn request_codeowner_reviews(
+    github_client: &GitHubClient,
+    repo_owner: &str,
+    repo_name: &str,
+    pr: &super::api::GitHubPullRequest,
+    extra: &[String],
+    dry_run: bool,
+) -> Result<Value> {
+    let mut codeowners_file = None;
+    for location in codeowners::LOCATIONS {
+        if let Some(file) = github_client.get_file(repo_owner, repo_name, location, Some(&pr.base.ref_name)).await? {
+            codeowners_file = Some((location, String::from_utf8_lossy(&file.decoded()?).into_owned()));
+            break;
+        }
+    }
+
+    let files = github_client.list_pull_request_files(repo_owner, repo_name, pr.number).await?;
+    let ownership = match &codeowners_file {
+        Some((_, text)) => codeowners::CodeOwners::parse(text).ownership(files.iter().map(|f| f.filename.as_str())),
+        None => codeowners::Ownership::default(),
+    };
+
+    let mut reviewers = ownership.users.clone();
+    let mut team_reviewers: Vec<String> = Vec::new();
+    let mut unresolved = ownership.unresolved.clone();
+    let extra = extra.iter().map(|name| name.trim_start_matches('@'));
+    for name in ownership.teams.iter().map(String::as_str).chain(extra) {
+        let (list, name) = match name.split_once('/') {
+            // Only teams of the organization that owns the repository can review
+            Some((org, team)) if org.eq_ignore_ascii_case(repo_owner) => (&mut team_reviewers, team),
+            Some(_) => (&mut unresolved, name),
+            None => (&mut reviewers, name),
+        };
+        if !list.iter().any(|existing| existing.eq_ignore_ascii_case(name)) {
+            list.push(name.to_string());
+        }
+    }
+    // GitHub refuses to ask a PR's author to review it
+    reviewers.retain(|reviewer| !reviewer.eq_ignore_ascii_case(&pr.user.login));
+
+    let requested = !dry_run && !(reviewers.is_empty() && team_reviewers.is_empty());
+    if requested {
+        github_client
+            .request_reviewers(repo_owner, repo_name, pr.number, &reviewers, &team_reviewers)
+            .await?;
+        info!("Requested {} reviewers and {} teams on PR #{}", reviewers.len(), team_reviewers.len(), pr.number);
+    }
+
+    Ok(json!({
+        "pull_request": {
+            "number": pr.number,
+            "url": pr.html_url
+        },
+        "codeowners_file": codeowners_file.map(|(location, _)| location),
+        "owners": ownership.owners,
+        "unowned_paths": ownership.unowned,
+        // Email owners and other organizations' teams have to be asked by hand
+        "unresolved_owners": unresolved,
+        "reviewers": reviewers,
+        "team_reviewers": team_reviewers,
+        "requested": requested,
+        "dry_run": dry_run
+    }))
+}
+
+/// Bring the current branch up to date with `base` (the main branch by
+/// default), or continue or abort a sync that stopped on conflicts
+async fn execute_sync_branch(
+    state: AppState,
+    action: Option<String>,
+    strategy: Option<String>,
+    base: Option<String>,
+) -> Result<Value> {
+    let root = get_repo_root().await?;
+    let in_progress = git::run(|g, dir| g.sync_in_progress(dir)).await?;
+    let branch = get_current_branch().await.unwrap_or_else(|_| "HEAD".to_string());
+
+    match action.as_deref().unwrap_or("sync") {
+        "sync" => {}
+        "continue" => {
+            let Some(strategy) = in_progress else {
+                return Err(AppError::Validation("No rebase or merge is in progress".to_string()));
+            };
+            let conflicted = git::run(|g, dir| g.conflicted_files(dir)).await?;
+            let unresolved = sync::unresolved(&root, &conflicted);
+            if !unresolved.is_empty() {
+                return Ok(json!({
+                    "status": "conflict",
+                    "message": format!("{} files still contain conflict markers", unresolved.len()),
+                    "branch": branch,
+                    "strategy": strategy,
+                    "unresolved_files": unresolved,
+                    "conflicts": sync::describe(&root, &unresolved),
+                    "next_step": "Remove every conflict marker, then call github_sync_branch with action: continue"
+                }));
+            }
+
+            progress::report("🔀 Continuing");
+            let outcome = git::run(move |g, dir| g.continue_sync(dir, strategy)).await?;
+            return sync_result(&root, &branch, strategy, outcome).await;
+        }
+        "abort" => {
+            let Some(strategy) = in_progress else {
+                return Err(AppError::Validation("No rebase or merge is in progress".to_string()));
+            };
+            git::run(move |g, dir| g.abort_sync(dir, strategy)).await?;
+            return Ok(json!({
+                "status": "success",
+                "message": format!("↩️ Aborted the {}; {} is back where it started", strategy.name(), branch),
+                "branch": branch,
+                "strategy": strategy
+            }));
+        }
+        other => {
+            return Err(AppError::Validation(format!("Unknown action {}: expected sync, continue or abort", other)));
+        }
+    }
+
+    if let Some(strategy) = in_progress {
+        return Err(AppError::Validation(format!(
+            "A {} is already in progress; resolve it with action: continue, or abort it",
+            strategy.name()
+        )));
+    }
+    let strategy = match strategy {
+        Some(strategy) => strategy,
+        None => super::repo_config::load_workflow_file(&root)?
+            .sync
+            .strategy
+            .unwrap_or_else(|| state.config.workflow.sync_strategy.clone()),
+    };
+    let strategy = git::SyncStrategy::parse(&strategy)?;
+    let base = match base {
+        Some(base) => base,
+        None => get_main_branch().await.unwrap_or_else(|_| "main".to_string()),
+    };
+    if branch == base {
+        return Err(AppError::Validation(format!("{} is the base branch; check out a feature branch to sync", branch)));
+    }
+
+    progress::report("📥 Fetching from origin");
+    git::run(|g, dir| g.fetch(dir)).await?;
+    let upstream = format!("origin/{}", base);
+    let target = upstream.clone();
+    let (ahead, behind) = git::run(move |g, dir| g.ahead_behind(dir, &target)).await?;
+    if behind == 0 {
+        return Ok(json!({
+            "status": "up_to_date",
+            "message": format!("✅ {} already contains every commit on {}", branch, upstream),
+            "branch": branch,
+            "base": upstream,
+            "ahead": ahead,
+            "behind": 0
+        }));
+    }
+
+    if !get_git_status().await?.is_empty() {
+        return Err(AppError::Validation(
+            "The working tree has uncommitted changes; commit or stash them before syncing".to_string(),
+        ));
+    }
+
+    progress::report(&format!("🔀 Syncing with {} ({} commits behind)", upstream, behind));
+    info!("Syncing {} with {} by {}", branch, upstream, strategy.name());
+    let target = upstream.clone();
+    let outcome = git::run(move |g, dir| g.integrate(dir, &target, strategy)).await?;
+    let mut result = sync_result(&root, &branch, strategy, outcome).await?;
+    result["base"] = json!(upstream);
+    result["behind"] = json!(behind);
+    Ok(result)
+}
+
+/// The response once a rebase or merge has finished or stopped on conflicts
+async fn sync_result(root: &Path, branch: &str, strategy: git::SyncStrategy, outcome: git::SyncOutcome) -> Result<Value> {
+    if outcome == git::SyncOutcome::Conflicted {
+        let conflicted = git::run(|g, dir| g.conflicted_files(dir)).await?;
+        return Ok(json!({
+            "status": "conflict",
+            "message": format!("⚠️ The {} stopped on conflicts in {} files", strategy.name(), conflicted.len()),
+            "branch": branch,
+            "strategy": strategy,
+            "conflicted_files": conflicted,
+            "conflicts": sync::describe(root, &conflicted),
+            "next_step": "Edit each file to resolve its hunks and remove the markers, then call github_sync_branch with action: continue (or abort to give up)"
+        }));
+    }
+
+    // A rebase rewrites commits the remote may already have
+    let force_push_required = strategy == git::SyncStrategy::Rebase;
+    Ok(json!({
+        "status": "success",
+        "message": format!("✅ {} is up to date", branch),
+        "branch": branch,
+        "strategy": strategy,
+        "force_push_required": force_push_required,
+        "next_step": match force_push_required {
+            true => "Push with --force-with-lease if the branch was already pushed",
+            false => "Push the merge commit",
+        }
+    }))
+}
+
+/// Remote name `add_upstream_remote` and `sync_fork` use for the original
+const UPSTREAM_REMOTE: &str = "upstream";
+
+/// The URL to reach `repository` by: SSH when `origin` uses it, else HTTPS
+fn remote_url_for(origin: &str, repository: &GitHubRepository) -> String {
+    if origin.starts_with("git@") || origin.starts_with("ssh://") {
+        repository.ssh_url.clone()
+    } else {
+        repository.clone_url.clone()
+    }
+}
+
+fn same_repository(a: &(String, String), b: &str) -> bool {
+    format!("{}/{}", a.0, a.1).eq_ignore_ascii_case(b)
+}
+
+/// Fork a repository, optionally re-pointing the checkout's remotes so
+/// `origin` is the fork and `upstream` the original
+async fn execute_fork_repository(
+    state: AppState,
+    repository: Option<String>,
+    organization: Option<String>,
+    name: Option<String>,
+    default_branch_only: Option<bool>,
+    remotes: Option<bool>,
+) -> Result<Value> {
+    let (source_owner, source_name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let fork_owner = match &organization {
+        Some(organization) => organization.clone(),
+        None => github_client.get_user().await?.login,
+    };
+    let fork_name = name.clone().unwrap_or_else(|| source_name.clone());
+    authorize_repository(&state, &format!("{}/{}", fork_owner, fork_name), "fork_repository", permissions::Operation::Push).await?;
+
+    progress::report(&format!("🍴 Forking {}/{}", source_owner, source_name));
+    // An existing fork is returned as it is
+    let fork = github_client
+        .fork_repository(&source_owner, &source_name, organization.as_deref(), name.as_deref(), default_branch_only.unwrap_or(true))
+        .await?;
+    let source = format!("{}/{}", source_owner, source_name);
+
+    let mut result = json!({
+        "status": "success",
+        "message": format!("🍴 Forked {} to {}", source, fork.full_name),
+        "fork": {
+            "full_name": fork.full_name,
+            "url": fork.html_url,
+            "clone_url": fork.clone_url,
+            "ssh_url": fork.ssh_url,
+            "default_branch": fork.default_branch
+        },
+        "upstream": source
+    });
+    if !remotes.unwrap_or(false) {
+        return Ok(result);
+    }
+
+    let origin = git::run(|g, dir| g.remote_url(dir, "origin")).await?;
+    let checkout = parse_github_remote(&origin)
+        .ok_or_else(|| AppError::Internal(format!("Unrecognised GitHub remote: {}", origin)))?;
+    let upstream_url = if same_repository(&checkout, &source) {
+        origin.clone()
+    } else if same_repository(&checkout, &fork.full_name) {
+        remote_url_for(&origin, &github_client.get_repository(&source_owner, &source_name).await?)
+    } else {
+        return Err(AppError::Validation(format!(
+            "The checkout's origin is {}/{}, neither {} nor its fork",
+            checkout.0, checkout.1, source
+        )));
+    };
+    let fork_url = remote_url_for(&origin, &fork);
+
+    progress::report("🔗 Pointing origin at the fork");
+    let (upstream, fork_remote) = (upstream_url.clone(), fork_url.clone());
+    git::run(move |g, dir| {
+        g.set_remote(dir, UPSTREAM_REMOTE, &upstream)?;
+        g.set_remote(dir, "origin", &fork_remote)
+    })
+    .await?;
+    // GitHub may still be copying the repository
+    let fetched = git::run(|g, dir| g.fetch(dir)).await;
+    if let Err(e) = &fetched {
+        warn!("Couldn't fetch the new fork {}: {}", fork.full_name, e);
+    }
+
+    result["remotes"] = json!({
+        "origin": fork_url,
+        "upstream": upstream_url,
+        "fetched": fetched.is_ok()
+    });
+    Ok(result)
+}
+
+/// Add the remote the checkout's fork was made from, and fetch it
+async fn execute_add_upstream_remote(state: AppState, upstream: Option<String>, remote: Option<String>) -> Result<Value> {
+    let remote = remote.unwrap_or_else(|| UPSTREAM_REMOTE.to_string());
+    if remote == "origin" {
+        return Err(AppError::Validation("origin is the fork; choose another remote name".to_string()));
+    }
+
+    let origin = git::run(|g, dir| g.remote_url(dir, "origin")).await?;
+    let github_client = get_github_client(state, caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let upstream = match upstream {
+        Some(upstream) => {
+            let (owner, name) = resolve_repository(Some(upstream)).await?;
+            github_client.get_repository(&owner, &name).await?
+        }
+        None => {
+            let (owner, name) = get_remote_repository().await?;
+            let fork = github_client.get_repository(&owner, &name).await?;
+            match fork.parent {
+                Some(parent) => *parent,
+                None => {
+                    return Err(AppError::Validation(format!("{}/{} isn't a fork; pass upstream", owner, name)));
+                }
+            }
+        }
+    };
+
+    let url = remote_url_for(&origin, &upstream);
+    progress::report(&format!("🔗 Adding {} for {}", remote, upstream.full_name));
+    let (name, target) = (remote.clone(), url.clone());
+    git::run(move |g, dir| {
+        g.set_remote(dir, &name, &target)?;
+        g.fetch_remote(dir, &name)
+    })
+    .await?;
+
+    Ok(json!({
+        "status": "success",
+        "message": format!("🔗 {} now points at {} and is fetched", remote, upstream.full_name),
+        "remote": remote,
+        "upstream": upstream.full_name,
+        "url": url,
+        "default_branch": upstream.default_branch
+    }))
+}
+
+/// Bring a fork's branch up to date with its upstream, on GitHub or in the
+/// checkout
+async fn execute_sync_fork(
+    state: AppState,
+    repository: Option<String>,
+    branch: Option<String>,
+    method: Option<String>,
+    strategy: Option<String>,
+) -> Result<Value> {
+    let (owner, name) = resolve_repository(repository).await?;
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+    let fork = github_client.get_repository(&owner, &name).await?;
+    let Some(parent) = fork.parent.as_deref() else {
+        return Err(AppError::Validation(format!("{} isn't a fork", fork.full_name)));
+    };
+    let branch = branch.unwrap_or_else(|| fork.default_branch.clone());
+
+    match method.as_deref().unwrap_or("api") {
+        "api" => {}
+        "local" => return sync_fork_locally(&state, &fork, parent, branch, strategy).await,
+        other => return Err(AppError::Validation(format!("Unknown method {}: expected api or local", other))),
+    }
+
+    progress::report(&format!("🔄 Syncing {} with {}", fork.full_name, parent.full_name));
+    let Some(merged) = github_client.merge_upstream(&owner, &name, &branch).await? else {
+        return Ok(json!({
+            "status": "conflict",
+            "message": format!("⚠️ {} on {} has diverged from {} and can't be synced on GitHub", branch, fork.full_name, parent.full_name),
+            "fork": fork.full_name,
+            "upstream": parent.full_name,
+            "branch": branch,
+            "next_step": "Call github_sync_fork with method: local to rebase or merge in the checkout and resolve the conflicts there"
+        }));
+    };
+
+    let merge_type = merged["merge_type"].as_str().unwrap_or("none");
+    Ok(json!({
+        "status": if merge_type == "none" { "up_to_date" } else { "success" },
+        "message": format!("🔄 {}", merged["message"].as_str().unwrap_or("Synced with upstream")),
+        "fork": fork.full_name,
+        "upstream": parent.full_name,
+        "branch": branch,
+        "merge_type": merge_type
+    }))
+}
+
+/// Fetch the upstream, rebase or merge it into `branch` and push to the fork
+async fn sync_fork_locally(
+    state: &AppState,
+    fork: &GitHubRepository,
+    parent: &GitHubRepository,
+    branch: String,
+    strategy: Option<String>,
+) -> Result<Value> {
+    let root = get_repo_root().await?;
+    let checkout = get_remote_repository().await?;
+    if !same_repository(&checkout, &fork.full_name) {
+        return Err(AppError::Validation(format!(
+            "The checkout's origin is {}/{}, not {}",
+            checkout.0, checkout.1, fork.full_name
+        )));
+    }
+    if let Some(strategy) = git::run(|g, dir| g.sync_in_progress(dir)).await? {
+        return Err(AppError::Validation(format!(
+            "A {} is already in progress; finish it with github_sync_branch first",
+            strategy.name()
+        )));
+    }
+    let strategy = match strategy {
+        Some(strategy) => strategy,
+        None => super::repo_config::load_workflow_file(&root)?
+            .sync
+            .strategy
+            .unwrap_or_else(|| state.config.workflow.sync_strategy.clone()),
+    };
+    let strategy = git::SyncStrategy::parse(&strategy)?;
+
+    // Add the upstream remote if the checkout doesn't have it yet
+    match git::run(|g, dir| g.remote_url(dir, UPSTREAM_REMOTE)).await {
+        Ok(url) => {
+            let points_at = parse_github_remote(&url);
+            if !points_at.as_ref().is_some_and(|remote| same_repository(remote, &parent.full_name)) {
+                return Err(AppError::Validation(format!(
+                    "The {} remote is {}, not {}; fix it with github_add_upstream",
+                    UPSTREAM_REMOTE, url, parent.full_name
+                )));
+            }
+        }
+        Err(_) => {
+            let origin = git::run(|g, dir| g.remote_url(dir, "origin")).await?;
+            let url = remote_url_for(&origin, parent);
+            git::run(move |g, dir| g.set_remote(dir, UPSTREAM_REMOTE, &url)).await?;
+        }
+    }
+
+    progress::report(&format!("📥 Fetching from {}", UPSTREAM_REMOTE));
+    git::run(|g, dir| g.fetch_remote(dir, UPSTREAM_REMOTE)).await?;
+
+    if !get_git_status().await?.is_empty() {
+        return Err(AppError::Validation(
+            "The working tree has uncommitted changes; commit or stash them before syncing".to_string(),
+        ));
+    }
+    if get_current_branch().await? != branch {
+        let target = branch.clone();
+        git::run(move |g, dir| g.checkout(dir, &target)).await?;
+    }
+
+    let upstream = format!("{}/{}", UPSTREAM_REMOTE, branch);
+    let target = upstream.clone();
+    let (ahead, behind) = git::run(move |g, dir| g.ahead_behind(dir, &target)).await?;
+    if behind == 0 {
+        return Ok(json!({
+            "status": "up_to_date",
+            "message": format!("✅ {} already contains every commit on {}", branch, upstream),
+            "fork": fork.full_name,
+            "upstream": parent.full_name,
+            "branch": branch,
+            "ahead": ahead,
+            "behind": 0
+        }));
+    }
+
+    progress::report(&format!("🔀 Syncing {} with {} ({} commits behind)", branch, upstream, behind));
+    info!("Syncing fork {} {} with {} by {}", fork.full_name, branch, upstream, strategy.name());
+    let target = upstream.clone();
+    let outcome = git::run(move |g, dir| g.integrate(dir, &target, strategy)).await?;
+    let mut result = sync_result(&root, &branch, strategy, outcome).await?;
+    result["fork"] = json!(fork.full_name);
+    result["upstream"] = json!(parent.full_name);
+    result["behind"] = json!(behind);
+    if outcome == git::SyncOutcome::Conflicted {
+        return Ok(result);
+    }
+
+    progress::report(&format!("⬆️ Pushing {} to {}", branch, fork.full_name));
+    match push_branch(&branch).await {
+        Ok(()) => {
+            result["message"] = json!(format!("✅ {} on {} is up to date with {}", branch, fork.full_name, parent.full_name));
+            result["pushed"] = json!(true);
+            if let Some(result) = result.as_object_mut() {
+                result.remove("next_step");
+                result.remove("force_push_required");
+            }
+        }
+        // A rebase over commits only the fork had can't fast-forward
+        Err(e) => {
+            warn!("Failed to push synced {}: {}", branch, e);
+            result["status"] = json!("partial");
+            result["message"] = json!(format!("⚠️ {} is synced locally, but pushing it to {} failed", branch, fork.full_name));
+            result["pushed"] = json!(false);
+            result["error"] = json!(e.to_string());
+            result["next_step"] = json!("Push with --force-with-lease if the fork's own commits were rebased");
+        }
+    }
+    Ok(result)
+}
+
+async fn cleanup_branch_work_folder(state: &AppState, branch: &str, dry_run: bool) -> Result<work_folder::CleanupReport> {
+    let root = get_repo_root().await?;
+    let file = super::repo_config::load_workflow_file(&root)?;
+    let pattern = file
+        .work_folder
+        .pattern
+        .unwrap_or_else(|| state.config.workflow.work_folder_pattern.clone());
+
+    let branch = branch.to_string();
+    tokio::task::spawn_blocking(move || work_folder::cleanup(&root, &pattern, &branch, dry_run))
+        .await
+        .map_err(|e| AppError::Internal(format!("Work folder cleanup failed: {}", e)))?
+}
+
+async fn execute_complete_task_workflow(
+    state: AppState,
+    issue_number: Option<u64>,
+    branch: Option<String>,
+    pull_request: Option<u64>,
+) -> Result<Value> {
+    info!("Executing complete task workflow");
+
+    let (repo_owner, repo_name) = get_remote_repository().await?;
+    let repository = format!("{}/{}", repo_owner, repo_name);
+    let branch = match branch {
+        Some(branch) => branch,
+        None => get_current_branch().await?,
+    };
+
+    let github_client = get_github_client(state.clone(), caller::user_id())
+        .await
+        .map_err(|_| AppError::Authentication("GitHub client not available".to_string()))?;
+
+    let task = complete_linked_task(&state, &github_client, &repository, &branch, issue_number, pull_request)
+        .await?
+        .ok_or_else(|| {
+            AppError::Validation(format!("No task linked to branch {}. Pass issue_number explicitly.", branch))
+        })?;
+
+    Ok(json!({
+        "status": "success",
+        "message": "✅ Task completed",
+        "task": task,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    }))
+}
+
+/// A merge method the repository accepts, preferring a merge commit as
+/// immediate merges make; settings that aren't returned count as allowed
+fn allowed_merge_method(repository: &GitHubRepository) -> &'static str {
+    let allowed = |setting: Option<bool>| setting.unwrap_or(true);
+    if allowed(repository.allow_merge_commit) {
+        "merge"
+    } else if allowed(repository.allow_squash_merge) {
+        "squash"
+    } else {
+        "rebase"
+    }
+}
+
+/// Enable auto-merge on `pr`, or add it to the merge queue, and track it so
+/// the requester hears when it lands. The branch stays checked out.
+#[allow(clippy::too_many_arguments)]
+async fn defer_merge(
+    state: &AppState,
+    github_client: &GitHubClient,
+    repo_owner: &str,
+    repo_name: &str,
+    pr: &super::api::GitHubPullRequest,
+    branch: &str,
+    mode: &str,
+    checks: Vec<Value>,
+) -> Result<Value> {
+    let (message, queue_position) = if mode == "queue" {
+        progress::report(&format!("🚦 Adding #{} to the merge queue", pr.number));
+        let position = github_client.enqueue_pull_request(&pr.node_id).await?;
+        (format!("🚦 PR #{} is in the merge queue", pr.number), position)
+    } else {
+        progress::report(&format!("⏳ Enabling auto-merge on #{}", pr.number));
+        let repository = github_client.get_repository(repo_owner, repo_name).await?;
+        github_client.enable_auto_merge(&pr.node_id, allowed_merge_method(&repository)).await?;
+        (format!("⏳ Auto-merge enabled on PR #{}; it merges once checks and reviews pass", pr.number), None)
+    };
+
+    let requested_by = github_client.get_user().await?.login;
+    let repository = format!("{}/{}", repo_owner, repo_name);
+    merge_tracking::track(&state.db, &repository, pr.number, branch, mode, &requested_by).await?;
+    info!("Deferred merge of {}#{} ({})", repository, pr.number, mode);
+
+    Ok(json!({
+        "status": "pending",
+        "message": message,
+        "mode": mode,
+        "pull_request": {
+            "number": pr.number,
+            "url": pr.html_url,
+            "title": pr.title
+        },
+        "queue_position": queue_position,
+        "checks": checks,
+        "tracking": "You'll get a notification when the merge lands. The branch stays checked out until then; switch to main and delete it afterwards."
+    }))
+}
+
+/// Move a task to Done, close its issue with a comment pointing at the PR,
+/// and mark the local task link completed. Returns `None` when there's no
+/// task to complete.
+pub(crate) async fn complete_linked_task(
+    state: &AppState,
+    github_client: &GitHubClient,
+    repository: &str,
+    branch: &str,
+    issue_number: Option<u64>,
+    pull_request: Option<u64>,
+) -> Result<Option<Value>> {
+    let link = sqlx::query!(
+        r#"
+        SELECT issue_number, project_owner, project_number, pull_request_number
+        FROM task_links
+        WHERE repository = ? AND branch = ?
+        "#,
+        repository,
+        branch
+    )
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some(issue_number) = issue_number.or(link.as_ref().map(|l| l.issue_number as u64)) else {
+        return Ok(None);
+    };
+    let pull_request = pull_request.or(link.as_ref().and_then(|l| l.pull_request_number.map(|n| n as u64)));
+
+    let (repo_owner, repo_name) = repository
+        .split_once('/')
+        .ok_or_else(|| AppError::Validation(format!("Invalid repository: {}", repository)))?;
+
+    // Move the project item to Done
+    let project_owner = link.as_ref().and_then(|l| l.project_owner.clone());
+    let project_number = link.as_ref().and_then(|l| l.project_number.clone());
+    let mut project_status = None;
+    if let (Some(owner), Some(number)) = (&project_owner, &project_number) {
+        match move_task_status(github_client, owner, number, repository, issue_number, "Done").await {
+            Ok(Some(_)) => project_status = Some("Done"),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to move #{} to Done: {}", issue_number, e),
+        }
+    }
+
+    // Close the issue, referencing the PR that resolved it
+    let comment = match pull_request {
+        Some(pr) => format!("✅ Completed in #{}", pr),
+        None => "✅ Completed".to_string(),
+    };
+    let issue = github_client.get_issue(repo_owner, repo_name, issue_number).await?;
+    let issue_closed = if issue.state == "open" {
+        github_client.create_issue_comment(repo_owner, repo_name, issue_number, &comment).await?;
+        github_client.close_issue(repo_owner, repo_name, issue_number, Some("completed")).await?;
+        true
+    } else {
+        false
+    };
+
+    let issue_number_db = issue_number as i64;
+    let pull_request_db = pull_request.map(|n| n as i64);
+    sqlx::query!(
+        r#"
+        INSERT INTO task_links (repository, branch, issue_number, status, pull_request_number, completed_at)
+        VALUES (?, ?, ?, 'completed', ?, datetime('now'))
+        ON CONFLICT(repository, branch) DO UPDATE SET
+            status = 'completed',
+            pull_request_number = COALESCE(excluded.pull_request_number, task_links.pull_request_number),
+            completed_at = excluded.completed_at
+        "#,
+        repository,
+        branch,
+        issue_number_db,
+        pull_request_db
+    )
+    .execute(&state.db)
+    .await?;
+
+    Ok(Some(json!({
+        "issue_number": issue_number,
+        "issue_closed": issue_closed,
+        "project_status": project_status,
+        "pull_request": pull_request
+    })))
+}
+
+/// Find the project item for an issue and set its Status; returns the item ID
+/// or `None` when the issue isn't on the project
+async fn move_task_status(
+    github_client: &GitHubClient,
+    project_owner: &str,
+    project_number: &str,
+    repository: &str,
+    issue_number: u64,
+    status: &str,
+) -> Result<Option<String>> {
+    let items = github_client.get_project_items(project_owner, project_number).await?;
+    let item = items.iter().find(|item| {
+        item.content.as_ref().map_or(false, |c| {
+            c.number == Some(issue_number) && c.repository.as_deref() == Some(repository)
+        })
+    });
+
+    let Some(item) = item else {
+        return Ok(None);
+    };
+
+    let project = github_client.get_project(project_owner, project_number).await?;
+    github_client.set_project_item_option(&project, &item.id, "Status", status).await?;
+    Ok(Some(item.id.clone()))
+}
+
+/// Branch name like `feature/123-short-title`, prefixed by task type
+fn task_branch_name(issue: &super::api::GitHubIssue) -> String {
+    let prefix = issue
+        .labels
+        .iter()
+        .find_map(|l| match l.name.to_lowercase().as_str() {
+            "bug" | "type: bug" | "type/bug" => Some("fix"),
+            "documentation" | "docs" => Some("docs"),
+            "chore" => Some("chore"),
+            _ => None,
+        })
+        .unwrap_or("feature");
+
+    let mut slug = String::new();
+    for c in issue.title.to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+        if slug.len() >= 40 {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+
+    crate::security::sanitize_branch_name(&format!("{}/{}-{}", prefix, issue.number, slug))
+}
+
+/// Whether merges must only contain verified commits. `.mcp-workflow.toml`
+/// comes from the branch being merged, so it can require them when
+/// `REQUIRE_VERIFIED_COMMITS` doesn't, but not waive them when it does.
+async fn verified_commits_required(state: &AppState) -> Result<bool> {
+    if state.config.workflow.require_verified_commits {
+        return Ok(true);
+    }
+    let root = get_repo_root().await?;
+    let file = super::repo_config::load_workflow_file(&root)?;
+    Ok(file.merge.require_verified_commits.unwrap_or(false))
+}
+
+// Git utility functions
+/// Run the configured pre-merge commands in the repository root, streaming
+/// their output as progress notifications. Stops at the first failure.
+async fn run_merge_checks(state: &AppState) -> Result<(bool, Vec<Value>)> {
+    let root = get_repo_root().await?;
+    let file = super::repo_config::load_workflow_file(&root)?;
+    let (commands, source) = merge_check_commands(state, &root)?;
+    debug!("Using {} merge checks from {}", commands.len(), source);
+    let timeout = Duration::from_secs(
+        file.merge.timeout_seconds.unwrap_or(state.config.workflow.merge_check_timeout_seconds),
+    );
+
+    let mut results = Vec::new();
+    for command in commands {
+        info!("Running merge check: {}", command);
+        progress::report(&format!("▶ {}", command));
+        let started = std::time::Instant::now();
+
+        let (passed, exit_code, output) = match tokio::time::timeout(timeout, run_check_command(&root, &command)).await {
+            Ok(result) => result?,
+            Err(_) => (false, None, VecDeque::from([format!("Timed out after {}s", timeout.as_secs())])),
+        };
+
+        progress::report(&format!("{} {}", if passed { "✅" } else { "❌" }, command));
+        results.push(json!({
+            "command": command,
+            "passed": passed,
+            "exit_code": exit_code,
+            "duration_ms": started.elapsed().as_millis() as u64,
+            "output": output.into_iter().collect::<Vec<_>>().join("\n")
+        }));
+
+        if !passed {
+            return Ok((false, results));
+        }
+    }
+
+    Ok((true, results))
+}
+
+/// Pre-merge commands and where they came from: the repo's workflow file,
+/// then `MERGE_CHECK_COMMANDS`, then defaults for the detected project type.
+/// An explicit `checks = []` in the workflow file disables checks.
+fn merge_check_commands(state: &AppState, root: &Path) -> Result<(Vec<String>, &'static str)> {
+    let file = super::repo_config::load_workflow_file(root)?;
+    if let Some(checks) = file.merge.checks {
+        return Ok((checks, "workflow_file"));
+    }
+
+    let configured = &state.config.workflow.merge_check_commands;
+    if !configured.is_empty() {
+        return Ok((configured.clone(), "config"));
+    }
+
+    if state.config.workflow.detect_default_checks {
+        let detected = environment::default_checks(root);
+        if !detected.is_empty() {
+            return Ok((detected, "detected"));
+        }
+    }
+
+    Ok((Vec::new(), "none"))
+}
+
+/// Run one check through the shell with stderr folded into stdout; returns
+/// success, exit code and the tail of the output. The shell leads its own
+/// process group, which is killed once the shell exits, or whole if this
+/// future is dropped on timeout or cancellation, so test runners and servers
+/// it started don't outlive it.
+async fn run_check_command(root: &Path, command: &str) -> Result<(bool, Option<i32>, VecDeque<String>)> {
+    let mut shell = std::process::Command::new("sh");
+    shell
+        .arg("-c")
+        .arg(format!("exec 2>&1\n{}", command))
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped());
+    process::isolate(&mut shell);
+    let mut child = tokio::process::Command::from(shell)
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| AppError::Internal(format!("Failed to run {}: {}", command, e)))?;
+    let pid = child.id();
+    let mut group = process::GroupGuard::new(pid);
+
+    let mut tail = VecDeque::with_capacity(CHECK_OUTPUT_TAIL_LINES);
+    let mut keep = |line: String| {
+        progress::report(&line);
+        if tail.len() == CHECK_OUTPUT_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    };
+    let mut lines = child.stdout.take().map(|stdout| BufReader::new(stdout).lines());
+
+    // Something the check left in the background can hold the output open,
+    // so the shell exiting ends the check, not the end of the output
+    let status = loop {
+        let Some(reader) = lines.as_mut() else {
+            break child.wait().await;
+        };
+        tokio::select! {
+            line = reader.next_line() => match line {
+                Ok(Some(line)) => keep(line),
+                _ => lines = None,
+            },
+            status = child.wait() => break status,
+        }
+    }
+    .map_err(|e| AppError::Internal(format!("Failed to wait for {}: {}", command, e)))?;
+    if let Some(pid) = pid {
+        process::kill_group(pid);
+    }
+    group.disarm();
+    // What was written before the group was killed
+    if let Some(mut reader) = lines {
+        while let Ok(Some(line)) = reader.next_line().await {
+            keep(line);
+        }
+    }
+    drop(keep);
+
+    Ok((status.success(), status.code(), tail))
+}
+
+/// `owner/name` argument, or the current repository's remote when omitted
+async fn resolve_repository(repository: Option<String>) -> Result<(String, String)> {
+    match repository {
+        Some(repository) => repository
+            .split_once('/')
+            .map(|(owner, name)| (owner.to_string(), name.to_string()))
+            .ok_or_else(|| AppError::Validation(format!("Invalid repository: {}", repository))),
+        None => get_remote_repository().await,
+    }
+}
+
+async fn get_repo_root() -> Result<PathBuf> {
+    git::run(|g, dir| g.repo_root(dir)).await
+}
+
+async fn get_current_branch() -> Result<String> {
+    git::run(|g, dir| g.current_branch(dir)).await
+}
+
+async fn get_main_branch() -> Result<String> {
+    git::run(|g, dir| g.main_branch(dir)).await
+}
+
+async fn get_git_status() -> Result<Vec<String>> {
+    git::run(|g, dir| g.status(dir)).await
+}
+
+async fn commit_changes(message: &str) -> Result<()> {
+    let message = message.to_string();
+    git::run(move |g, dir| g.commit_all(dir, &message)).await
+}
+
+async fn push_branch(branch: &str) -> Result<()> {
+    let branch = branch.to_string();
+    git::run(move |g, dir| g.push(dir, &branch)).await
+}
+
+async fn pull_branch(branch: &str) -> Result<()> {
+    let branch = branch.to_string();
+    git::run(move |g, dir| g.pull(dir, &branch)).await
+}
+
+async fn checkout_branch(branch: &str) -> Result<()> {
+    let branch = branch.to_string();
+    git::run(move |g, dir| g.checkout(dir, &branch)).await
+}
+
+async fn create_branch(branch: &str) -> Result<()> {
+    let branch = branch.to_string();
+    git::run(move |g, dir| g.create_branch(dir, &branch)).await
+}
+
+async fn commit_empty(message: &str) -> Result<()> {
+    let message = message.to_string();
+    git::run(move |g, dir| g.commit_empty(dir, &message)).await
+}
+
+/// Delete a merged PR's head branch on GitHub, unless the repository does
+/// that itself, the branch is protected or default, or it lives in a fork.
+/// Failures are reported, not raised, since the merge already happened.
+async fn delete_merged_remote_branch(
+    github_client: &GitHubClient,
+    repo_owner: &str,
+    repo_name: &str,
+    pr: &super::api::GitHubPullRequest,
+) -> Value {
+    let branch = &pr.head.ref_name;
+    let skipped = |reason: String| json!({ "branch": branch, "deleted": false, "reason": reason });
+
+    if !pr.head.repo.full_name.eq_ignore_ascii_case(&format!("{}/{}", repo_owner, repo_name)) {
+        return skipped(format!("the branch lives in {}", pr.head.repo.full_name));
+    }
+    match github_client.get_repository(repo_owner, repo_name).await {
+        Ok(repository) if repository.delete_branch_on_merge == Some(true) => {
+            return skipped("GitHub deletes head branches on merge in this repository".to_string());
+        }
+        Ok(repository) if &repository.default_branch == branch => {
+            return skipped("it is the default branch".to_string());
+        }
+        Ok(_) => {}
+        Err(e) => return skipped(format!("couldn't read the repository settings: {}", e)),
+    }
+    match github_client.get_branch_protection(repo_owner, repo_name, branch).await {
+        Ok(Some(protection)) if !protection.allow_deletions.as_ref().is_some_and(|flag| flag.enabled) => {
+            return skipped("the branch is protected".to_string());
+        }
+        Ok(_) => {}
+        Err(e) => return skipped(format!("couldn't read its protection: {}", e)),
+    }
+
+    match github_client.delete_branch(repo_owner, repo_name, branch).await {
+        Ok(true) => json!({ "branch": branch, "deleted": true }),
+        Ok(false) => skipped("it was already deleted".to_string()),
+        Err(e) => {
+            warn!("Failed to delete {} on GitHub: {}", branch, e);
+            skipped(e.to_string())
+        }
+    }
+}
+
+async fn delete_local_branch(branch: &str) -> Result<()> {
+    let owned = branch.to_string();
+    if let Err(e) = git::run(move |g, dir| g.delete_branch(dir, &owned)).await {
+        warn!("Failed to delete branch {}: {}", branch, e);
+    }
+
+    Ok(())
+}
 
 async fn detect_project_number() -> Result<String> {
     // Try to read project number from TODO.md
-    if let Ok(todo_content) = tokio::fs::read_to_string("TODO.md").await {
+    if let Ok(todo_content) = tokio::fs::read_to_string(git::workdir().join("TODO.md")).await {
         for line in todo_content.lines() {
             if line.contains("Project Number:") || line.contains("GitHub Project:") {
                 // Extract project number from line
@@ -410,6 +6193,44 @@ async fn detect_project_number() -> Result<String> {
     Err(AppError::Validation("No GitHub Project number found. Please specify project_number or add it to TODO.md".to_string()))
 }
 
+/// Resolve which org/user owns the project: explicit argument, then config,
+/// then the owner of the current repository's `origin` remote
+async fn resolve_project_owner(state: &AppState, owner: Option<String>) -> Result<String> {
+    if let Some(owner) = owner.filter(|o| !o.is_empty()) {
+        return Ok(owner);
+    }
+
+    if let Some(owner) = &state.config.github.project_owner {
+        return Ok(owner.clone());
+    }
+
+    let (owner, _) = get_remote_repository().await.map_err(|_| {
+        AppError::Validation("No project owner found. Please specify owner or set GITHUB_PROJECT_OWNER".to_string())
+    })?;
+    Ok(owner)
+}
+
+pub(crate) async fn get_remote_repository() -> Result<(String, String)> {
+    let url = git::run(|g, dir| g.remote_url(dir, "origin")).await?;
+    parse_github_remote(&url)
+        .ok_or_else(|| AppError::Internal(format!("Unrecognised GitHub remote: {}", url)))
+}
+
+fn parse_github_remote(url: &str) -> Option<(String, String)> {
+    // Handles https://host/owner/repo(.git), git@host:owner/repo(.git) and ssh://git@host/owner/repo
+    let path = if let Some(rest) = url.strip_prefix("git@") {
+        rest.split_once(':')?.1
+    } else {
+        let without_scheme = url.split_once("://")?.1;
+        without_scheme.split_once('/')?.1
+    };
+
+    let mut parts = path.trim_end_matches('/').trim_end_matches(".git").splitn(2, '/');
+    let owner = parts.next().filter(|p| !p.is_empty())?;
+    let repo = parts.next().filter(|p| !p.is_empty() && !p.contains('/'))?;
+    Some((owner.to_string(), repo.to_string()))
+}
+
 fn extract_number_from_line(line: &str) -> Option<String> {
     // Simple regex-like extraction for project numbers
     for word in line.split_whitespace() {
@@ -420,19 +6241,32 @@ fn extract_number_from_line(line: &str) -> Option<String> {
     None
 }
 
-async fn get_pr_for_branch(github_client: &GitHubClient, branch: &str) -> Result<super::api::GitHubPullRequest> {
-    // TODO: Implement PR lookup by branch name
-    // This would require parsing the repository from git remote
-    Err(AppError::Internal("PR lookup not implemented yet".to_string()))
+/// CI verdict for a pull request's head commit, limited to the checks the
+/// base branch's protection requires when it lists any
+async fn pull_request_checks(
+    github_client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    pr: &super::api::GitHubPullRequest,
+) -> Result<actions::CheckGate> {
+    let sha = &pr.head.sha;
+    let combined = github_client.get_combined_status(owner, repo, sha).await?;
+    let check_runs = github_client.list_check_runs(owner, repo, sha).await?;
+    let required = github_client.get_required_status_checks(owner, repo, &pr.base.ref_name).await?;
+    Ok(actions::evaluate_checks(sha, &combined.statuses, &check_runs, required))
 }
 
-fn organize_tasks_by_priority(tasks: Vec<super::api::GitHubProjectItem>) -> Value {
-    // TODO: Implement task organization by priority and type
-    json!({
-        "critical": [],
-        "high": [],
-        "medium": [],
-        "low": [],
-        "total": tasks.len()
-    })
-}
\ No newline at end of file
+async fn get_pr_for_branch(github_client: &GitHubClient, branch: &str) -> Result<super::api::GitHubPullRequest> {
+    let (owner, repo) = get_remote_repository().await?;
+    let prs = github_client.list_pull_requests_for_branch(&owner, &repo, branch).await?;
+
+    // Prefer the open PR; otherwise fall back to the most recent one
+    let mut prs = prs.into_iter();
+    let first = prs.next();
+    first
+        .clone()
+        .filter(|pr| pr.state == "open")
+        .or_else(|| prs.find(|pr| pr.state == "open"))
+        .or(first)
+        .ok_or_else(|| super::error::GitHubError::not_found(format!("No pull request found for branch {}", branch)).into())
+}
@@ -0,0 +1,82 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::error::Result;
+
+/// Days of command history behind the success ratios and percentiles
+pub const STATS_WINDOW_DAYS: i64 = 7;
+
+/// Success ratio and duration percentiles for one command over the stats window
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandStats {
+    pub command: String,
+    pub runs: u64,
+    pub succeeded: u64,
+    pub p50_ms: i64,
+    pub p95_ms: i64,
+}
+
+impl CommandStats {
+    pub fn success_ratio(&self) -> f64 {
+        if self.runs == 0 {
+            return 0.0;
+        }
+        self.succeeded as f64 / self.runs as f64
+    }
+}
+
+pub async fn record(db: &sqlx::SqlitePool, command: &str, success: bool, duration_ms: i64) -> Result<()> {
+    sqlx::query!(
+        "INSERT INTO command_runs (command, success, duration_ms) VALUES (?, ?, ?)",
+        command,
+        success,
+        duration_ms
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn stats(db: &sqlx::SqlitePool) -> Result<Vec<CommandStats>> {
+    let window = format!("-{} days", STATS_WINDOW_DAYS);
+    let rows = sqlx::query!(
+        r#"
+        SELECT command, success, duration_ms FROM command_runs
+        WHERE created_at >= datetime('now', ?)
+        ORDER BY command, duration_ms
+        "#,
+        window
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut grouped: BTreeMap<String, (u64, Vec<i64>)> = BTreeMap::new();
+    for row in rows {
+        let (succeeded, durations) = grouped.entry(row.command).or_default();
+        if row.success {
+            *succeeded += 1;
+        }
+        durations.push(row.duration_ms);
+    }
+
+    Ok(grouped
+        .into_iter()
+        .map(|(command, (succeeded, durations))| CommandStats {
+            command,
+            runs: durations.len() as u64,
+            succeeded,
+            p50_ms: percentile(&durations, 50),
+            p95_ms: percentile(&durations, 95),
+        })
+        .collect())
+}
+
+/// Nearest-rank percentile of already sorted values
+fn percentile(sorted: &[i64], pct: usize) -> i64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
@@ -0,0 +1,72 @@
+// Log output and per-request context.
+//
+// `LOG_FORMAT=json` writes one JSON object per line for log aggregation;
+// anything else keeps the human-readable format. Every HTTP request runs in a
+// `request` span carrying its `request_id` and, with a valid session token,
+// the `user`, so each line logged while handling it can be traced back. The
+// ID is taken from an incoming `X-Request-Id` when it looks like one and is
+// returned in the response's `X-Request-Id` either way.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderName, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+
+use crate::AppState;
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Install the global subscriber; `RUST_LOG` sets the levels. `stderr` keeps
+/// stdout free for a protocol, as in stdio mode.
+pub fn init(stderr: bool) {
+    let writer = if stderr {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(writer);
+    match std::env::var("LOG_FORMAT").as_deref() {
+        Ok("json") => builder.json().flatten_event(true).init(),
+        _ => builder.init(),
+    }
+}
+
+fn incoming_id(req: &Request<Body>) -> Option<String> {
+    let id = req.headers().get(&REQUEST_ID_HEADER)?.to_str().ok()?;
+    let valid = !id.is_empty()
+        && id.len() <= 128
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valid.then(|| id.to_string())
+}
+
+pub async fn request_context(State(state): State<AppState>, req: Request<Body>, next: Next) -> Response {
+    let request_id = incoming_id(&req).unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let user = req
+        .headers()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .and_then(|token| state.jwt.validate(token).ok())
+        .map(|claims| claims.username);
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %req.method(),
+        path = %req.uri().path(),
+        user = user.as_deref().unwrap_or("-"),
+    );
+
+    let mut response = next.run(req).instrument(span).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
@@ -2,11 +2,11 @@ use axum::{
     extract::State,
     http::StatusCode,
     response::Json,
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 use serde_json::{json, Value};
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 use tokio::net::TcpListener;
 use tower_http::{
     cors::CorsLayer,
@@ -19,17 +19,42 @@ use tracing::{info, warn};
 use prometheus::{Counter, Histogram, Gauge, Registry, Encoder, TextEncoder};
 use std::sync::Mutex;
 
+mod admin;
+mod approvals;
+mod audit;
 mod auth;
+mod cli;
 mod config;
 mod error;
+mod git;
 mod github;
+mod history;
+mod logging;
 mod mcp;
 mod security;
 mod metrics;
+mod notifier;
+mod operations;
+mod permissions;
+mod preferences;
+mod process;
+mod repositories;
+mod scheduler;
+mod secrets;
+mod setup;
+mod store;
+mod tls;
+mod transfer;
+mod users;
+mod webhooks;
 
+use clap::Parser;
+use cli::{Cli, Command, TokenCommand};
 use config::Config;
 use error::AppError;
 use metrics::Metrics;
+use notifier::Notifier;
+use scheduler::Scheduler;
 
 type AppState = Arc<AppStateInner>;
 
@@ -38,45 +63,175 @@ struct AppStateInner {
     config: Config,
     db: sqlx::SqlitePool,
     metrics: Arc<Metrics>,
+    /// Shared with other replicas, when `REDIS_URL` is set
+    redis: Option<store::RedisStore>,
+    notifier: Notifier,
+    scheduler: Scheduler,
+    webhooks: webhooks::Dispatcher,
+    events: mcp::events::EventHub,
+    jwt: security::jwt::JwtKeys,
+    ip_limiters: security::IpLimiters,
+    /// Reverse proxies whose `X-Forwarded-For` gives the client's address
+    proxies: security::proxies::TrustedProxies,
+    user_limits: security::user_limits::UserLimits,
+    sessions: mcp::session::Sessions,
+    /// Authenticated WebSocket connections, per user
+    connections: mcp::connections::Connections,
+    tools: Arc<mcp::tools::ToolRegistry>,
+    federation: mcp::federation::Federation,
+    started_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
+    let command = Cli::parse().command.unwrap_or(Command::Serve);
 
-    info!("Starting GitHub MCP Server");
+    // Initialize tracing; stdio mode keeps stdout for the protocol
+    logging::init(matches!(command, Command::Stdio));
 
-    // Load configuration
-    let config = Config::load()?;
+    let config = match command {
+        // `admin ...` talks to a running server, so it needs no config or database
+        Command::Admin { args } => return admin::cli::run(&args).await,
+        Command::CheckConfig => return cli::check_config().await,
+        _ => {
+            secrets::init().await?;
+            Config::load()?
+        }
+    };
     info!("Configuration loaded successfully");
+    security::tokens::init(&config.security)?;
 
     // Initialize database
     let db = sqlx::SqlitePool::connect(&config.database_url).await?;
     sqlx::migrate!("./migrations").run(&db).await?;
     info!("Database initialized and migrations applied");
 
+    match command {
+        Command::Serve => serve(config, db).await,
+        Command::Stdio => {
+            let state = build_state(config, db).await?;
+            mcp::stdio::serve(state).await?;
+            Ok(())
+        }
+        Command::Migrate => Ok(()),
+        Command::Token { command: TokenCommand::EncryptRotate } => {
+            let count = security::tokens::rotate(&db).await?;
+            println!("Re-encrypted {} stored GitHub tokens", count);
+            Ok(())
+        }
+        Command::Export { path, transfer_key } => Ok(transfer::export_state(&db, &path, &transfer_key).await?),
+        Command::Import { path, transfer_key } => Ok(transfer::import_state(&db, &path, &transfer_key).await?),
+        // Handled before loading the configuration
+        Command::Admin { .. } | Command::CheckConfig => Ok(()),
+    }
+}
+
+/// Set up the process-wide clients and everything handlers share
+async fn build_state(config: Config, db: sqlx::SqlitePool) -> Result<AppState, Box<dyn std::error::Error>> {
+    git::init(&config.workflow.git_backend, git::Timeouts::from_config(&config.workflow))?;
+    github::allowlist::init(&config.security.allowed_repositories);
+    github::retry::init(github::retry::Policy {
+        max_attempts: config.github.retry_max_attempts.max(1),
+        max_wait: std::time::Duration::from_secs(config.github.retry_max_wait_seconds),
+    });
+    github::budget::init(config.github.rate_limit_reserve);
+    github::cache::init(config.github.cache_entries, config.github.cache_persist.then(|| db.clone()));
+    repositories::sync_from_config(&db, &config.repositories).await?;
+
     // Initialize metrics
     let metrics = Arc::new(Metrics::new().expect("Failed to create metrics"));
     info!("Metrics initialized");
 
+    let redis = match &config.redis {
+        Some(redis_config) => {
+            let redis = store::RedisStore::connect(&redis_config.url).await?;
+            info!(
+                "Connected to Redis (rate limits: {}, sessions: {})",
+                if redis_config.rate_limits { "shared" } else { "local" },
+                if redis_config.sessions { "shared" } else { "local" }
+            );
+            Some(redis)
+        }
+        None => None,
+    };
+
     // Create application state
+    let notifier = Notifier::new(&config.notifications, db.clone());
+
+    // Internal handlers for verified GitHub webhook deliveries
+    let mut webhook_handlers = webhooks::Dispatcher::default();
+    webhook_handlers.register("log_ping", &["ping"], webhooks::log_ping);
+    webhook_handlers.register(
+        "mcp_notifications",
+        &["pull_request_review", "workflow_run", "issues"],
+        mcp::events::forward_webhook,
+    );
+    webhook_handlers.register("deferred_merges", &["pull_request"], github::merge_tracking::on_webhook);
+
+    // Built-in tools plus those proxied from federation peers
+    let federation = mcp::federation::Federation::new(&config.federation);
+    let mut tools = mcp::tools::ToolRegistry::builtin();
+    federation.register_tools(&mut tools).await;
+
     let state = Arc::new(AppStateInner { 
         config: config.clone(), 
         db,
-        metrics: metrics.clone(),
+        metrics,
+        redis: redis.clone(),
+        notifier,
+        scheduler: Scheduler::new(&config.scheduler),
+        webhooks: webhook_handlers,
+        events: mcp::events::EventHub::new(),
+        jwt: security::jwt::JwtKeys::from_config(&config)?,
+        ip_limiters: security::IpLimiters::new(&config.security),
+        proxies: security::proxies::TrustedProxies::from_config(&config.security)?,
+        user_limits: security::user_limits::UserLimits::new(
+            &config.security,
+            config.redis.as_ref().filter(|redis_config| redis_config.rate_limits).and(redis.clone()),
+        ),
+        sessions: mcp::session::Sessions::new(
+            config.redis.as_ref().filter(|redis_config| redis_config.sessions).and(redis),
+        ),
+        connections: mcp::connections::Connections::new(config.security.ws_max_connections_per_user),
+        tools: Arc::new(tools),
+        federation,
+        started_at: chrono::Utc::now(),
     });
 
+    users::load_reauth(&state).await?;
+    Ok(state)
+}
+
+async fn serve(config: Config, db: sqlx::SqlitePool) -> Result<(), Box<dyn std::error::Error>> {
+    info!("Starting GitHub MCP Server");
+    let state = build_state(config.clone(), db).await?;
+    setup::init(&state).await?;
+
+    // Background due-date reminders for claimed tasks
+    github::reminders::spawn_reminder_scheduler(state.clone());
+    github::merge_tracking::spawn_polling(state.clone());
+    notifier::spawn_digest_scheduler(state.clone());
+    audit::spawn_pruning(state.clone());
+    auth::sessions::spawn_pruning(state.clone());
+    github::cache::spawn_pruning(state.clone());
+    security::spawn_limiter_sweep(state.clone());
+    secrets::spawn_refresh(state.clone());
+
     // Build application router
     let app = create_router(state);
 
     // Start server
     let listener = TcpListener::bind(&format!("{}:{}", config.host, config.port)).await?;
-    info!("Server listening on {}:{}", config.host, config.port);
-
-    axum::serve(listener, app).await?;
+    match &config.tls {
+        Some(tls_config) => {
+            info!("Server listening on https://{}:{}", config.host, config.port);
+            tls::serve(listener, app, tls_config).await?;
+        }
+        None => {
+            info!("Server listening on {}:{}", config.host, config.port);
+            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
+        }
+    }
 
     Ok(())
 }
@@ -93,24 +248,57 @@ fn create_router(state: AppState) -> Router {
         .route("/auth/github", get(auth::github_oauth_start))
         .route("/auth/github/callback", get(auth::github_oauth_callback))
         .route("/auth/token/refresh", post(auth::refresh_token))
+        .route("/auth/logout", post(auth::logout))
+        
+        // Onboarding wizard
+        .route("/setup/status", get(setup::setup_status))
+        .route("/setup/step", post(setup::setup_step))
+        
+        // Administration
+        .route("/admin/overview", get(admin::overview))
+        .route("/admin/permissions", get(admin::list_permissions).put(admin::save_permission))
+        .route("/admin/permissions/:id", delete(admin::delete_permission))
+        .route("/admin/approvals", get(admin::list_approvals))
+        .route("/admin/approvals/:owner/:name", put(admin::approve_repository).delete(admin::remove_approval))
+        .route("/admin/audit", get(admin::list_audit))
+        .route("/admin/users", get(admin::list_users))
+        .route("/admin/users/:user_id", get(admin::get_user))
+        .route("/admin/users/:user_id/token", delete(admin::revoke_user_token))
+        .route("/admin/users/:user_id/reauth", post(admin::force_reauth))
+        .route("/admin/sessions", get(admin::list_sessions))
+        .route("/admin/jobs", get(admin::list_jobs))
+        .route("/admin/jobs/:name/cancel", post(admin::cancel_job))
+        .route("/admin/tools", get(admin::list_tools))
+        .route("/admin/tools/:name", put(admin::update_tool))
+        .route("/admin/operations", get(admin::list_operations))
+        .route("/admin/operations/:id/approve", post(admin::approve_operation))
+        .route("/admin/operations/:id/reject", post(admin::reject_operation))
+        
+        // GitHub webhook deliveries (HMAC-verified)
+        .route("/webhooks/github", post(webhooks::receive))
         
         // MCP protocol endpoints
-        .route("/mcp", post(mcp::handle_mcp_request))
+        .route("/mcp", post(mcp::handle_mcp_request).delete(mcp::close_session))
         .route("/mcp/ws", get(mcp::websocket_handler))
         
         // GitHub workflow endpoints
         .route("/github/push", post(github::handle_push))
         .route("/github/scan-tasks", post(github::handle_scan_tasks))
         .route("/github/merge", post(github::handle_merge))
+        .route("/github/projects/:number/board", get(github::handle_project_board))
         
         // Static file serving for web interface
         .nest_service("/", ServeDir::new("web"))
         
         // Middleware
+        .layer(axum::middleware::from_fn_with_state(state.clone(), auth::sessions::require_session))
         .layer(TraceLayer::new_for_http())
         .layer(CorsLayer::permissive())
-        .layer(security::rate_limiting_layer())
+        .layer(security::rate_limiting_layer(&state))
         .layer(security::security_headers_layer())
+        .layer(axum::middleware::from_fn_with_state(state.clone(), security::audit_log_middleware))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), metrics::track_http_requests))
+        .layer(axum::middleware::from_fn_with_state(state.clone(), logging::request_context))
         
         // Application state
         .with_state(state)
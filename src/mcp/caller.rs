@@ -0,0 +1,66 @@
+// Who an MCP request is being served for.
+//
+// Every request runs with the caller of its session installed, taken from the
+// session token it was made with. Tools and workflows read it to act with the
+// caller's own GitHub token and to check the caller's permissions, without it
+// being passed through every function in between.
+
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+use crate::config::Config;
+use crate::error::{AppError, Result};
+use crate::security::{jwt::ADMIN_ROLE, JwtClaims};
+
+tokio::task_local! {
+    static CALLER: Caller;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Caller {
+    /// GitHub user id, the token's `sub`
+    pub user_id: u64,
+    pub login: String,
+    /// Holds the admin role and is still listed in `ADMIN_USERS`
+    pub admin: bool,
+}
+
+impl Caller {
+    pub fn from_claims(config: &Config, claims: &JwtClaims) -> Self {
+        let listed = config.security.admin_users.iter().any(|u| u.eq_ignore_ascii_case(&claims.username));
+        Self {
+            user_id: claims.user_id,
+            login: claims.username.clone(),
+            admin: listed && claims.role.as_deref() == Some(ADMIN_ROLE),
+        }
+    }
+
+    /// The token `sub` this caller is known by, e.g. for per-user limits
+    pub fn sub(&self) -> String {
+        self.user_id.to_string()
+    }
+}
+
+/// Run `future` with `caller` available to `current`
+pub async fn scope<F: Future>(caller: Option<Caller>, future: F) -> F::Output {
+    match caller {
+        Some(caller) => CALLER.scope(caller, future).await,
+        None => future.await,
+    }
+}
+
+/// The caller of the request being served; None outside a request or for an
+/// unauthenticated one
+pub fn current() -> Option<Caller> {
+    CALLER.try_with(Caller::clone).ok()
+}
+
+/// The caller's GitHub user id, for `get_github_client`
+pub fn user_id() -> Option<u64> {
+    CALLER.try_with(|caller| caller.user_id).ok()
+}
+
+/// The caller, or an authentication error for requests made without a session token
+pub fn require() -> Result<Caller> {
+    current().ok_or_else(|| AppError::Authentication("Request has no authenticated caller".to_string()))
+}
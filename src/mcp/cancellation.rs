@@ -0,0 +1,146 @@
+// Cancellation of in-flight MCP requests.
+//
+// Each request a WebSocket connection is serving is registered with its task's
+// abort handle and a flag. `notifications/cancelled` aborts the task, which
+// drops (and so kills) any tokio child processes it was waiting on, and sets
+// the flag, which git commands running on the blocking pool poll so they can
+// kill their subprocess as well.
+
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::task::AbortHandle;
+use tracing::Instrument;
+
+tokio::task_local! {
+    static CURRENT: CancelFlag;
+}
+
+thread_local! {
+    static BLOCKING: RefCell<Option<CancelFlag>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default)]
+pub struct CancelFlag(Arc<AtomicBool>);
+
+impl CancelFlag {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Cancel when the returned guard is dropped, e.g. with the HTTP request
+    /// whose client disconnected
+    pub fn cancel_on_drop(&self, task: Option<AbortHandle>) -> CancelOnDrop {
+        CancelOnDrop { flag: self.clone(), task }
+    }
+}
+
+pub struct CancelOnDrop {
+    flag: CancelFlag,
+    task: Option<AbortHandle>,
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.flag.cancel();
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
+
+/// Run `future` with `flag` available to `current` and `is_cancelled`
+pub async fn scope<F: Future>(flag: CancelFlag, future: F) -> F::Output {
+    CURRENT.scope(flag, future).await
+}
+
+/// Flag of the request the current task is serving
+pub fn current() -> Option<CancelFlag> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+/// Run blocking `operation` with `flag` visible to `is_cancelled` on this
+/// thread; task-locals don't follow work onto the blocking pool
+pub fn blocking<T>(flag: Option<CancelFlag>, operation: impl FnOnce() -> T) -> T {
+    BLOCKING.with(|current| *current.borrow_mut() = flag);
+    // Cleared even if `operation` panics, so the pool thread doesn't carry
+    // the flag into whatever it runs next
+    let _reset = ResetBlocking;
+    operation()
+}
+
+struct ResetBlocking;
+
+impl Drop for ResetBlocking {
+    fn drop(&mut self) {
+        BLOCKING.with(|current| *current.borrow_mut() = None);
+    }
+}
+
+/// Whether the client cancelled the request being served
+pub fn is_cancelled() -> bool {
+    current()
+        .or_else(|| BLOCKING.with(|current| current.borrow().clone()))
+        .map_or(false, |flag| flag.is_cancelled())
+}
+
+/// Requests a connection is serving, by JSON-RPC id
+#[derive(Clone, Default)]
+pub struct InFlight {
+    requests: Arc<Mutex<HashMap<String, (AbortHandle, CancelFlag)>>>,
+}
+
+impl InFlight {
+    /// Spawn `future` for request `id`, cancellable until it completes
+    pub fn spawn<F>(&self, id: &Value, future: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let key = id.to_string();
+        let flag = CancelFlag::default();
+        let requests = self.requests.clone();
+        let task_key = key.clone();
+        let task_flag = flag.clone();
+
+        // Hold the lock while spawning so a request that finishes at once
+        // can't deregister before it is registered
+        let mut registered = self.requests.lock().unwrap_or_else(|e| e.into_inner());
+        let task = tokio::spawn(
+            async move {
+                scope(task_flag, future).await;
+                requests.lock().unwrap_or_else(|e| e.into_inner()).remove(&task_key);
+            }
+            .instrument(tracing::Span::current()),
+        );
+        registered.insert(key, (task.abort_handle(), flag));
+    }
+
+    /// Stop request `id`; false when it isn't running
+    pub fn cancel(&self, id: &Value) -> bool {
+        let entry = self.requests.lock().unwrap_or_else(|e| e.into_inner()).remove(&id.to_string());
+        match entry {
+            Some((task, flag)) => {
+                flag.cancel();
+                task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stop everything, e.g. when the connection closes
+    pub fn cancel_all(&self) {
+        let requests: Vec<_> = self.requests.lock().unwrap_or_else(|e| e.into_inner()).drain().collect();
+        for (_, (task, flag)) in requests {
+            flag.cancel();
+            task.abort();
+        }
+    }
+}
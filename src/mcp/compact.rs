@@ -0,0 +1,75 @@
+// Compact tool results, for clients that ask for them when initializing with
+// `capabilities.experimental.compactResults`. Nulls are dropped, common long
+// keys are shortened as listed in `KEYS`, and top-level fields that only
+// echo the call's arguments or say `"status": "success"` are left out.
+// Errors, whole results or single entries, are passed on unchanged.
+
+use serde_json::{Map, Value};
+
+/// Long key and its short form, also served as `github://compact/keys`.
+/// A key is left long where the object already has its short form.
+pub const KEYS: &[(&str, &str)] = &[
+    ("created_at", "created"),
+    ("updated_at", "updated"),
+    ("closed_at", "closed"),
+    ("merged_at", "merged"),
+    ("html_url", "url"),
+    ("description", "desc"),
+    ("repository", "repo"),
+    ("pull_request", "pr"),
+    ("issue_number", "issue"),
+    ("project_number", "project"),
+    ("assignees", "assigned"),
+    ("message", "msg"),
+];
+
+/// The key mapping as a JSON object
+pub fn key_map() -> Value {
+    Value::Object(KEYS.iter().map(|(long, short)| (long.to_string(), Value::from(*short))).collect())
+}
+
+/// Compact `result` of a call made with `arguments`
+pub fn apply(result: Value, arguments: &Value) -> Value {
+    let result = match result {
+        Value::Object(fields) if is_error(&fields) => return Value::Object(fields),
+        Value::Object(mut fields) => {
+            fields.retain(|key, value| {
+                let echo = arguments.get(key).map_or(false, |argument| argument == value);
+                let success = key == "status" && value == "success";
+                !echo && !success
+            });
+            Value::Object(fields)
+        }
+        other => other,
+    };
+    shrink(result)
+}
+
+/// A failed call or entry, e.g. `{"status": "error", ...}` or
+/// `{"number": 12, "status": "failed", "error": "..."}`
+fn is_error(fields: &Map<String, Value>) -> bool {
+    fields.contains_key("error") || matches!(fields.get("status").and_then(Value::as_str), Some("error" | "failed"))
+}
+
+fn shrink(value: Value) -> Value {
+    match value {
+        Value::Object(fields) if is_error(&fields) => Value::Object(fields),
+        Value::Object(fields) => {
+            let present: Vec<String> = fields.keys().cloned().collect();
+            let compact: Map<String, Value> = fields
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| {
+                    let key = match KEYS.iter().find(|(long, _)| *long == key) {
+                        Some((_, short)) if !present.iter().any(|k| k == short) => short.to_string(),
+                        _ => key,
+                    };
+                    (key, shrink(value))
+                })
+                .collect();
+            Value::Object(compact)
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(shrink).collect()),
+        other => other,
+    }
+}
@@ -0,0 +1,130 @@
+// Who each WebSocket connection belongs to, and how many each user has open.
+//
+// A connection is counted against its user from the moment it authenticates
+// until its `Connection` is dropped, so a socket that errors out frees its
+// slot as surely as one closed cleanly. Each also remembers the sign-in
+// session it authenticated with, so revoking the session can close it.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+use super::caller::Caller;
+
+#[derive(Clone)]
+pub struct Connections {
+    /// Open connections per user `sub`
+    open: Arc<Mutex<HashMap<String, usize>>>,
+    /// Session of each open connection, by connection id
+    sessions: Arc<Mutex<HashMap<u64, SessionLink>>>,
+    next_id: Arc<AtomicU64>,
+    /// 0 allows any number
+    max_per_user: usize,
+}
+
+struct SessionLink {
+    jti: String,
+    user_id: u64,
+    revoked: Arc<Notify>,
+}
+
+/// An authenticated connection, counted until dropped
+pub struct Connection {
+    /// Who the token the connection authenticated with was issued to
+    pub caller: Caller,
+    /// `sub` the connection is counted under
+    user: String,
+    id: u64,
+    revoked: Arc<Notify>,
+    open: Arc<Mutex<HashMap<String, usize>>>,
+    sessions: Arc<Mutex<HashMap<u64, SessionLink>>>,
+}
+
+impl Connections {
+    pub fn new(max_per_user: usize) -> Self {
+        Self {
+            open: Arc::default(),
+            sessions: Arc::default(),
+            next_id: Arc::default(),
+            max_per_user,
+        }
+    }
+
+    /// Count a connection for `caller`, opened with session `jti`; None when
+    /// they already have as many open as allowed
+    pub fn acquire(&self, caller: Caller, jti: &str) -> Option<Connection> {
+        let mut open = self.open.lock().ok()?;
+        let count = open.entry(caller.sub()).or_default();
+        if self.max_per_user > 0 && *count >= self.max_per_user {
+            return None;
+        }
+        *count += 1;
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let revoked = Arc::new(Notify::new());
+        if let Ok(mut sessions) = self.sessions.lock() {
+            let link = SessionLink { jti: jti.to_string(), user_id: caller.user_id, revoked: revoked.clone() };
+            sessions.insert(id, link);
+        }
+
+        Some(Connection {
+            user: caller.sub(),
+            caller,
+            id,
+            revoked,
+            open: self.open.clone(),
+            sessions: self.sessions.clone(),
+        })
+    }
+
+    /// Close the connections opened with session `jti`
+    pub fn close_session(&self, jti: &str) {
+        self.close(|link| link.jti == jti);
+    }
+
+    /// Close every connection `user_id` has open
+    pub fn close_user(&self, user_id: u64) {
+        self.close(|link| link.user_id == user_id);
+    }
+
+    fn close(&self, matches: impl Fn(&SessionLink) -> bool) {
+        if let Ok(sessions) = self.sessions.lock() {
+            for link in sessions.values().filter(|link| matches(link)) {
+                link.revoked.notify_one();
+            }
+        }
+    }
+
+    pub fn max_per_user(&self) -> usize {
+        self.max_per_user
+    }
+
+    /// Open connections across all users
+    pub fn total(&self) -> usize {
+        self.open.lock().map(|open| open.values().sum()).unwrap_or(0)
+    }
+}
+
+impl Connection {
+    /// Resolves once the connection's session has been revoked
+    pub async fn revoked(&self) {
+        self.revoked.notified().await
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.remove(&self.id);
+        }
+        if let Ok(mut open) = self.open.lock() {
+            if let Some(count) = open.get_mut(&self.user) {
+                *count -= 1;
+                if *count == 0 {
+                    open.remove(&self.user);
+                }
+            }
+        }
+    }
+}
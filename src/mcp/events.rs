@@ -0,0 +1,139 @@
+// Server-initiated notifications for connected WebSocket clients.
+//
+// Webhook deliveries that an agent may want to react to (reviews, finished
+// CI runs, assignments) are turned into `notifications/github/*` messages
+// and broadcast to every open connection, so clients don't have to poll.
+// Each connection only passes on events from repositories its user could
+// read through the server's tools, see `Access`.
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use super::caller::{self, Caller};
+use super::protocol::methods;
+use crate::{AppState, error::Result, github::{allowlist, api::get_github_client}, permissions, webhooks::WebhookEvent};
+
+/// Messages a slow client may fall behind by before it starts missing some
+const EVENT_BUFFER: usize = 256;
+
+/// How long a connection remembers whether its user can read a repository
+const ACCESS_TTL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Clone)]
+pub struct EventHub {
+    sender: broadcast::Sender<Value>,
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER);
+        Self { sender }
+    }
+
+    /// Send a notification to every connected client; dropped when none are connected
+    pub fn publish(&self, method: &str, params: Value) {
+        let _ = self.sender.send(json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params
+        }));
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Value> {
+        self.sender.subscribe()
+    }
+}
+
+/// Which repositories a connection's user may receive events from
+pub struct Access {
+    caller: Caller,
+    known: HashMap<String, (bool, Instant)>,
+}
+
+impl Access {
+    pub fn new(caller: Caller) -> Self {
+        Self { caller, known: HashMap::new() }
+    }
+
+    /// Whether the user may see events from `repository`: it must be in
+    /// `ALLOWED_REPOSITORIES`, readable under the server's permissions and
+    /// readable with their own GitHub token
+    pub async fn allows(&mut self, state: &AppState, repository: &str) -> bool {
+        let repository = repository.to_lowercase();
+        if let Some((allowed, checked)) = self.known.get(&repository) {
+            if checked.elapsed() < ACCESS_TTL {
+                return *allowed;
+            }
+        }
+
+        let allowed = self.check(state, &repository).await;
+        self.known.retain(|_, (_, checked)| checked.elapsed() < ACCESS_TTL);
+        self.known.insert(repository, (allowed, Instant::now()));
+        allowed
+    }
+
+    async fn check(&self, state: &AppState, repository: &str) -> bool {
+        let Some((owner, name)) = repository.split_once('/') else {
+            return false;
+        };
+        if allowlist::check(repository).is_err() {
+            return false;
+        }
+        let authorize = permissions::authorize(state, repository, permissions::Operation::Read);
+        if caller::scope(Some(self.caller.clone()), authorize).await.is_err() {
+            return false;
+        }
+        match get_github_client(state.clone(), Some(self.caller.user_id)).await {
+            Ok(client) => client.get_repository(owner, name).await.is_ok(),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Webhook handler publishing the events agents react to
+pub async fn forward_webhook(state: AppState, event: Arc<WebhookEvent>) -> Result<()> {
+    let (method, params) = match event.as_ref() {
+        WebhookEvent::PullRequestReview(e) if e.action == "submitted" => (
+            methods::NOTIFICATIONS_REVIEW_SUBMITTED,
+            json!({
+                "repository": e.repository.full_name,
+                "pull_request": e.pull_request.number,
+                "pull_request_title": e.pull_request.title,
+                "reviewer": e.review.user.as_ref().map(|u| &u.login),
+                "state": e.review.state.to_uppercase(),
+                "body": e.review.body,
+                "url": e.review.html_url
+            }),
+        ),
+        WebhookEvent::WorkflowRun(e) if e.action == "completed" => (
+            methods::NOTIFICATIONS_CI_FINISHED,
+            json!({
+                "repository": e.repository.full_name,
+                "run_id": e.workflow_run.id,
+                "workflow": e.workflow_run.name,
+                "branch": e.workflow_run.head_branch,
+                "sha": e.workflow_run.head_sha,
+                "conclusion": e.workflow_run.conclusion,
+                "url": e.workflow_run.html_url
+            }),
+        ),
+        WebhookEvent::Issues(e) if e.action == "assigned" => (
+            methods::NOTIFICATIONS_ISSUE_ASSIGNED,
+            json!({
+                "repository": e.repository.full_name,
+                "issue": e.issue.number,
+                "title": e.issue.title,
+                "assignee": e.assignee.as_ref().map(|u| &u.login),
+                "assigned_by": e.sender.as_ref().map(|u| &u.login),
+                "url": e.issue.html_url
+            }),
+        ),
+        _ => return Ok(()),
+    };
+
+    state.events.publish(method, params);
+    Ok(())
+}
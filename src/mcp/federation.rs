@@ -0,0 +1,272 @@
+// Tools and resources proxied from other instances of this server.
+//
+// Each peer in `FEDERATION_PEERS` is asked for its tools at startup, and the
+// selected ones are registered here as `{peer}__{tool}`. Calls are forwarded
+// to the peer's `POST /mcp` with its bearer token, in an MCP session opened on
+// first use and reopened when the peer forgets it. Resources are read through
+// `federated://{peer}/{uri}`.
+//
+// The peer only sees the operator's token, so a call is first held to this
+// server's own rules for its caller: the allow-lists and permissions apply to
+// the `repository` argument, and a call naming none is treated as reaching
+// any repository.
+
+use futures_util::future::BoxFuture;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::{AppState, config::PeerConfig, error::{AppError, Result}, github::{allowlist, workflows}, permissions};
+use super::caller;
+use super::protocol::{methods, McpTool, MCP_VERSION};
+use super::session::SESSION_HEADER;
+use super::tools::{Tool, ToolRegistry};
+
+/// Between the peer name and its tool name
+const SEPARATOR: &str = "__";
+
+pub const URI_PREFIX: &str = "federated://";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest a peer may take to answer, long enough for a merge running its checks
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+struct Peer {
+    name: String,
+    url: String,
+    token: Option<String>,
+    tools: Vec<String>,
+    resources: bool,
+    client: Client,
+    /// `Mcp-Session-Id` of the open session
+    session: Mutex<Option<String>>,
+}
+
+impl Peer {
+    fn offers_tool(&self, tool: &str) -> bool {
+        self.tools.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => tool.starts_with(prefix),
+            None => pattern == tool,
+        })
+    }
+
+    async fn post(&self, session: Option<&str>, message: &Value) -> Result<reqwest::Response> {
+        let mut request = self.client.post(&self.url).json(message);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(session) = session {
+            request = request.header(SESSION_HEADER, session);
+        }
+        Ok(request.send().await?)
+    }
+
+    /// The JSON-RPC result, or the peer's error
+    async fn result(&self, response: reqwest::Response) -> Result<Value> {
+        let body: Value = response.error_for_status()?.json().await?;
+        if let Some(error) = body.get("error") {
+            return Err(AppError::McpProtocol(format!(
+                "Federation peer {}: {}",
+                self.name,
+                error["message"].as_str().unwrap_or("unknown error")
+            )));
+        }
+        Ok(body.get("result").cloned().unwrap_or(Value::Null))
+    }
+
+    async fn open_session(&self) -> Result<String> {
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": methods::INITIALIZE,
+            "params": {
+                "protocolVersion": MCP_VERSION,
+                "capabilities": {},
+                "clientInfo": { "name": "github-mcp-server", "version": env!("CARGO_PKG_VERSION") }
+            }
+        });
+        let response = self.post(None, &initialize).await?;
+        let session = response.headers().get(SESSION_HEADER).and_then(|v| v.to_str().ok()).map(String::from);
+        self.result(response).await?;
+        let session = session.ok_or_else(|| {
+            AppError::McpProtocol(format!("Federation peer {} did not start a session", self.name))
+        })?;
+
+        let initialized = json!({ "jsonrpc": "2.0", "method": methods::NOTIFICATIONS_INITIALIZED });
+        self.post(Some(&session), &initialized).await?.error_for_status()?;
+        Ok(session)
+    }
+
+    async fn session(&self) -> Result<String> {
+        let mut session = self.session.lock().await;
+        if let Some(id) = session.as_ref() {
+            return Ok(id.clone());
+        }
+        let id = self.open_session().await?;
+        *session = Some(id.clone());
+        Ok(id)
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let message = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+
+        let session = self.session().await?;
+        let mut response = self.post(Some(&session), &message).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            // The peer restarted or expired the session; start another once
+            {
+                let mut current = self.session.lock().await;
+                if current.as_deref() == Some(session.as_str()) {
+                    *current = None;
+                }
+            }
+            let session = self.session().await?;
+            response = self.post(Some(&session), &message).await?;
+        }
+        self.result(response).await
+    }
+}
+
+/// A peer's tool, registered under the peer's prefix
+struct RemoteTool {
+    peer: Arc<Peer>,
+    name: String,
+    remote_name: String,
+    description: String,
+    input_schema: Value,
+}
+
+impl Tool for RemoteTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn input_schema(&self) -> Value {
+        self.input_schema.clone()
+    }
+
+    fn execute(&self, state: AppState, arguments: Value) -> BoxFuture<'static, Result<Value>> {
+        let peer = self.peer.clone();
+        let name = self.name.clone();
+        let repository = arguments.get("repository").and_then(Value::as_str).map(str::to_string);
+        let params = json!({ "name": self.remote_name, "arguments": arguments });
+        Box::pin(async move {
+            caller::require()?;
+            let repository = match repository {
+                Some(repository) => repository,
+                None if allowlist::enabled() => {
+                    return Err(AppError::Authorization(format!(
+                        "{} names no repository, and ALLOWED_REPOSITORIES limits the server to some",
+                        name
+                    )))
+                }
+                None => "*".to_string(),
+            };
+            // What the peer's tool does isn't known here, so it counts as a write
+            workflows::authorize_repository(&state, &repository, &name, permissions::Operation::Push).await?;
+            peer.request(methods::TOOLS_CALL, params).await
+        })
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Federation {
+    peers: Arc<HashMap<String, Arc<Peer>>>,
+}
+
+impl Federation {
+    pub fn new(config: &[PeerConfig]) -> Self {
+        let client = Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+
+        let peers = config
+            .iter()
+            .map(|peer| {
+                let peer = Peer {
+                    name: peer.name.clone(),
+                    url: peer.url.clone(),
+                    token: peer.token.clone(),
+                    tools: peer.tools.clone(),
+                    resources: peer.resources,
+                    client: client.clone(),
+                    session: Mutex::new(None),
+                };
+                (peer.name.clone(), Arc::new(peer))
+            })
+            .collect();
+
+        Self { peers: Arc::new(peers) }
+    }
+
+    /// Register the selected tools of every peer. A peer that can't be
+    /// reached is skipped, and its tools stay unavailable until a restart.
+    pub async fn register_tools(&self, registry: &mut ToolRegistry) {
+        for peer in self.peers.values() {
+            let tools = match peer.request(methods::TOOLS_LIST, json!({})).await {
+                Ok(result) => serde_json::from_value::<Vec<McpTool>>(result["tools"].clone()),
+                Err(e) => {
+                    warn!("Skipping federation peer {}: {}", peer.name, e);
+                    continue;
+                }
+            };
+            let tools = match tools {
+                Ok(tools) => tools,
+                Err(e) => {
+                    warn!("Skipping federation peer {}: invalid tools/list: {}", peer.name, e);
+                    continue;
+                }
+            };
+
+            let mut registered = 0;
+            // Tools the peer itself proxies are left out, so two instances
+            // federated with each other don't re-export each other's tools
+            for tool in tools.into_iter().filter(|t| !t.name.contains(SEPARATOR) && peer.offers_tool(&t.name)) {
+                registry.register(RemoteTool {
+                    peer: peer.clone(),
+                    name: format!("{}{}{}", peer.name, SEPARATOR, tool.name),
+                    description: format!("[{}] {}", peer.name, tool.description),
+                    input_schema: tool.input_schema,
+                    remote_name: tool.name,
+                });
+                registered += 1;
+            }
+            info!("Registered {} tools from federation peer {}", registered, peer.name);
+        }
+    }
+
+    /// Read `federated://{peer}/{uri}` from the peer, with the contents'
+    /// URIs rewritten to the federated form
+    pub async fn read_resource(&self, uri: &str) -> Result<Value> {
+        let (name, remote_uri) = uri
+            .strip_prefix(URI_PREFIX)
+            .and_then(|rest| rest.split_once('/'))
+            .ok_or_else(|| AppError::Validation(format!("Invalid federated resource: {}", uri)))?;
+        let peer = self
+            .peers
+            .get(name)
+            .filter(|peer| peer.resources)
+            .ok_or_else(|| AppError::Validation(format!("No federation peer {} serving resources", name)))?;
+
+        let mut result = peer.request(methods::RESOURCES_READ, json!({ "uri": remote_uri })).await?;
+        if let Some(contents) = result["contents"].as_array_mut() {
+            for content in contents {
+                if let Some(inner) = content["uri"].as_str() {
+                    content["uri"] = json!(format!("{}{}/{}", URI_PREFIX, name, inner));
+                }
+            }
+        }
+        Ok(result)
+    }
+}
@@ -1,85 +1,250 @@
-use axum::extract::ws::{Message, WebSocket};
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket};
+use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
 use serde_json::{json, Value};
-use tracing::{debug, error, info};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc::{self, UnboundedSender}, oneshot};
+use tracing::{debug, error, info, warn, Instrument};
 
 use crate::{AppState, error::{AppError, Result}};
+use super::caller::{self, Caller};
+use super::cancellation::InFlight;
+use super::connections::Connection;
+use super::events::Access;
+use super::progress::{self, ProgressReporter};
 use super::protocol::{
-    McpRequest, McpResponse, McpTool, McpResource, ServerCapabilities,
-    methods, error_codes, GitHubCommand, MCP_VERSION
+    McpRequest, McpResponse, McpResource, McpResourceTemplate, ServerCapabilities,
+    methods, error_codes, GitHubCommand, MCP_VERSION, SUPPORTED_PROTOCOL_VERSIONS
 };
+use super::session::Session;
 
-pub async fn handle_request(state: AppState, request: McpRequest) -> Result<serde_json::Value> {
+/// Handle one message in `session` for `caller`, who the message's own token
+/// was issued to; None for notifications, which get no response
+pub async fn handle_request(
+    state: AppState,
+    session: Session,
+    caller: Option<Caller>,
+    request: McpRequest,
+) -> Result<Option<Value>> {
     debug!("Handling MCP request: method={}", request.method);
 
-    let response = match request.method.as_str() {
-        methods::INITIALIZE => handle_initialize(&request).await?,
-        methods::TOOLS_LIST => handle_tools_list(&request).await?,
-        methods::TOOLS_CALL => handle_tools_call(state, &request).await?,
-        methods::RESOURCES_LIST => handle_resources_list(&request).await?,
-        methods::RESOURCES_READ => handle_resources_read(state, &request).await?,
-        methods::GITHUB_PUSH => handle_github_push(state, &request).await?,
-        methods::GITHUB_SCAN_TASKS => handle_github_scan_tasks(state, &request).await?,
-        methods::GITHUB_MERGE => handle_github_merge(state, &request).await?,
-        _ => McpResponse::error(
+    if let Err(message) = session.admit(&request) {
+        if request.id.is_none() {
+            warn!("Ignoring notification {}: {}", request.method, message);
+            return Ok(None);
+        }
+        let response = McpResponse::error(request.id, error_codes::INVALID_REQUEST, message, None);
+        return Ok(Some(serde_json::to_value(response)?));
+    }
+    // `admit` has already acted on notifications/initialized; others are ignored
+    if request.method.starts_with("notifications/") {
+        return Ok(None);
+    }
+
+    let metrics = state.metrics.clone();
+    let method = method_label(&request.method);
+    let started = std::time::Instant::now();
+
+    let response = match user_limit_exceeded(&state, caller.as_ref(), &request).await {
+        Some(response) => Ok(response),
+        // Tools and workflows act as the caller, see `caller`
+        None => caller::scope(caller, async {
+            match request.method.as_str() {
+                methods::INITIALIZE => handle_initialize(&session, &request).await,
+                methods::TOOLS_LIST => handle_tools_list(state, &request).await,
+                methods::TOOLS_CALL => handle_tools_call(state, &session, &request).await,
+                methods::RESOURCES_LIST => handle_resources_list(&request).await,
+                methods::RESOURCES_READ => handle_resources_read(state, &request).await,
+                methods::RESOURCES_TEMPLATES_LIST => handle_resource_templates_list(&request).await,
+                methods::GITHUB_PUSH => handle_legacy_method(state, &session, &request, "github_push").await,
+                methods::GITHUB_SCAN_TASKS => handle_legacy_method(state, &session, &request, "github_scan_tasks").await,
+                methods::GITHUB_MERGE => handle_legacy_method(state, &session, &request, "github_merge").await,
+                _ => Ok(McpResponse::error(
+                    request.id.clone(),
+                    error_codes::METHOD_NOT_FOUND,
+                    format!("Method not found: {}", request.method),
+                    None,
+                )),
+            }
+        })
+        .await,
+    };
+
+    // The client can act on this one (ask an admin), so it gets its own code
+    let response = match response {
+        Err(AppError::ApprovalRequired(repository)) => Ok(McpResponse::error(
             request.id,
-            error_codes::METHOD_NOT_FOUND,
-            format!("Method not found: {}", request.method),
-            None,
-        ),
+            error_codes::APPROVAL_REQUIRED,
+            AppError::ApprovalRequired(repository.clone()).to_string(),
+            Some(json!({ "repository": repository, "approval": "pending" })),
+        )),
+        response => response,
     };
 
-    Ok(serde_json::to_value(response)?)
+    let failed = response.as_ref().map_or(true, |response| response.error.is_some());
+    metrics.record_mcp_command(method, if failed { "error" } else { "success" }, started.elapsed().as_secs_f64());
+
+    Ok(Some(serde_json::to_value(response?)?))
+}
+
+/// RATE_LIMIT_ERROR for a tool call or workflow method over its caller's budget
+async fn user_limit_exceeded(state: &AppState, caller: Option<&Caller>, request: &McpRequest) -> Option<McpResponse> {
+    let tool = match request.method.as_str() {
+        methods::TOOLS_CALL => request.params.as_ref()?.get("name")?.as_str()?,
+        method @ (methods::GITHUB_PUSH | methods::GITHUB_SCAN_TASKS | methods::GITHUB_MERGE) => method,
+        _ => return None,
+    };
+    let user = caller?.sub();
+    let exceeded = state.user_limits.check(&user, tool).await.err()?;
+
+    warn!("User {} is over the {} tool budget calling {}", user, exceeded.class.as_str(), tool);
+    Some(McpResponse::error(
+        request.id.clone(),
+        error_codes::RATE_LIMIT_ERROR,
+        exceeded.message(tool),
+        Some(exceeded.data()),
+    ))
+}
+
+/// The method as a metric label; unknown methods share one so clients can't
+/// create series at will
+fn method_label(method: &str) -> &'static str {
+    [
+        methods::INITIALIZE,
+        methods::TOOLS_LIST,
+        methods::TOOLS_CALL,
+        methods::RESOURCES_LIST,
+        methods::RESOURCES_READ,
+        methods::RESOURCES_TEMPLATES_LIST,
+        methods::GITHUB_PUSH,
+        methods::GITHUB_SCAN_TASKS,
+        methods::GITHUB_MERGE,
+    ]
+    .into_iter()
+    .find(|known| *known == method)
+    .unwrap_or("unknown")
+}
+
+/// Run one entry of a JSON-RPC batch, reporting progress through `outgoing`.
+/// Notifications get no response, and failures become error responses so
+/// the rest of the batch still answers.
+pub async fn handle_batch_entry(
+    state: AppState,
+    session: Session,
+    caller: Option<Caller>,
+    entry: Value,
+    outgoing: UnboundedSender<Value>,
+) -> Option<Value> {
+    let request = match serde_json::from_value::<McpRequest>(entry) {
+        Ok(request) => request,
+        Err(e) => {
+            let response = McpResponse::error(None, error_codes::INVALID_REQUEST, format!("Invalid request: {}", e), None);
+            return serde_json::to_value(response).ok();
+        }
+    };
+    if request.method == methods::INITIALIZE {
+        let message = "initialize must not be part of a batch".to_string();
+        return serde_json::to_value(McpResponse::error(request.id, error_codes::INVALID_REQUEST, message, None)).ok();
+    }
+
+    let id = request.id.clone();
+    let reporter = ProgressReporter::from_params(request.params.as_ref(), &outgoing);
+    let response = match progress::scope(reporter, handle_request(state, session, caller, request)).await {
+        Ok(response) => response,
+        Err(e) => {
+            error!("Error handling batched MCP request: {}", e);
+            serde_json::to_value(McpResponse::from_error(id.clone(), &e)).ok()
+        }
+    };
+    id.and(response)
+}
+
+/// Run a JSON-RPC batch concurrently. Responses come back as one array in the
+/// batch's order; None when every entry was a notification.
+pub async fn handle_batch(
+    state: AppState,
+    session: Session,
+    caller: Option<Caller>,
+    batch: Vec<Value>,
+    outgoing: UnboundedSender<Value>,
+) -> Option<Value> {
+    if batch.is_empty() {
+        let response = McpResponse::error(None, error_codes::INVALID_REQUEST, "Empty batch".to_string(), None);
+        return serde_json::to_value(response).ok();
+    }
+
+    let entries = batch
+        .into_iter()
+        .map(|entry| handle_batch_entry(state.clone(), session.clone(), caller.clone(), entry, outgoing.clone()));
+    let responses: Vec<Value> = futures_util::future::join_all(entries).await.into_iter().flatten().collect();
+    (!responses.is_empty()).then_some(Value::Array(responses))
 }
 
-pub async fn handle_websocket(socket: WebSocket, state: AppState) {
+/// `connection` is set when the upgrade request carried a valid token.
+/// Otherwise the first message must be `{"method": "auth", "params":
+/// {"token": "..."}}`, sent within `WS_AUTH_TIMEOUT_SECONDS`, or the socket is
+/// closed.
+pub async fn handle_websocket(socket: WebSocket, state: AppState, connection: Option<Connection>) {
     let (mut sender, mut receiver) = socket.split();
+
+    let connection = match connection {
+        Some(connection) => connection,
+        None => {
+            let timeout = Duration::from_secs(state.config.security.ws_auth_timeout_seconds);
+            let authenticated = match tokio::time::timeout(timeout, await_auth(&state, &mut sender, &mut receiver)).await {
+                Ok(authenticated) => authenticated,
+                Err(_) => Err("Authentication timed out".to_string()),
+            };
+            match authenticated {
+                Ok(connection) => connection,
+                Err(reason) => {
+                    debug!("Closing unauthenticated WebSocket: {}", reason);
+                    let _ = sender
+                        .send(Message::Close(Some(CloseFrame { code: close_code::POLICY, reason: reason.into() })))
+                        .await;
+                    return;
+                }
+            }
+        }
+    };
     
-    info!("WebSocket connection established");
+    info!("WebSocket connection established for {}", connection.caller.login);
 
-    while let Some(msg) = receiver.next().await {
+    // Responses and progress notifications share one writer so they stay ordered
+    let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel::<Value>();
+    let writer = tokio::spawn(async move {
+        while let Some(message) = outgoing_rx.recv().await {
+            if let Ok(text) = serde_json::to_string(&message) {
+                if sender.send(Message::Text(text)).await.is_err() {
+                    error!("Failed to send WebSocket response");
+                    break;
+                }
+            }
+        }
+        sender
+    });
+
+    // Webhook-driven notifications go through the same writer
+    let forwarder = forward_events(&state, &outgoing, Some(connection.caller.clone()));
+
+    let in_flight = InFlight::default();
+    let session = Session::default();
+    let caller = Some(connection.caller.clone());
+    let mut revoked = false;
+    loop {
+        let msg = tokio::select! {
+            msg = receiver.next() => msg,
+            () = connection.revoked() => {
+                info!("Closing WebSocket for {}: its session was revoked", connection.caller.login);
+                revoked = true;
+                break;
+            }
+        };
+        let Some(msg) = msg else { break };
         match msg {
             Ok(Message::Text(text)) => {
                 debug!("Received WebSocket message: {}", text);
-                
-                match serde_json::from_str::<McpRequest>(&text) {
-                    Ok(request) => {
-                        match handle_request(state.clone(), request).await {
-                            Ok(response) => {
-                                if let Ok(response_text) = serde_json::to_string(&response) {
-                                    if sender.send(Message::Text(response_text)).await.is_err() {
-                                        error!("Failed to send WebSocket response");
-                                        break;
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                error!("Error handling WebSocket request: {}", e);
-                                let error_response = McpResponse::error(
-                                    None,
-                                    error_codes::INTERNAL_ERROR,
-                                    e.to_string(),
-                                    None,
-                                );
-                                if let Ok(error_text) = serde_json::to_string(&error_response) {
-                                    let _ = sender.send(Message::Text(error_text)).await;
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to parse WebSocket message: {}", e);
-                        let error_response = McpResponse::error(
-                            None,
-                            error_codes::PARSE_ERROR,
-                            "Invalid JSON".to_string(),
-                            None,
-                        );
-                        if let Ok(error_text) = serde_json::to_string(&error_response) {
-                            let _ = sender.send(Message::Text(error_text)).await;
-                        }
-                    }
-                }
+                handle_message(&state, &session, &caller, &text, &outgoing, &in_flight).await;
             }
             Ok(Message::Close(_)) => {
                 info!("WebSocket connection closed");
@@ -92,11 +257,279 @@ pub async fn handle_websocket(socket: WebSocket, state: AppState) {
             _ => {}
         }
     }
+
+    in_flight.cancel_all();
+    forwarder.abort();
+    drop(outgoing);
+    if let Ok(mut sender) = writer.await {
+        if revoked {
+            let reason = "Session has ended; sign in again";
+            let _ = sender.send(Message::Close(Some(CloseFrame { code: close_code::POLICY, reason: reason.into() }))).await;
+        }
+    }
+}
+
+/// Wait for the `auth` message that must come first on a connection opened
+/// without a token, answering it either way; Err says why the socket closes
+async fn await_auth(
+    state: &AppState,
+    sender: &mut SplitSink<WebSocket, Message>,
+    receiver: &mut SplitStream<WebSocket>,
+) -> std::result::Result<Connection, String> {
+    let text = loop {
+        match receiver.next().await {
+            Some(Ok(Message::Text(text))) => break text,
+            Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return Err("Closed before authenticating".to_string()),
+            Some(Ok(_)) => continue,
+        }
+    };
+
+    let request = serde_json::from_str::<McpRequest>(&text).ok();
+    let id = request.as_ref().and_then(|r| r.id.clone());
+    let outcome = match request {
+        Some(request) if request.method == methods::AUTH => {
+            match request.params.as_ref().and_then(|p| p.get("token")).and_then(|t| t.as_str()) {
+                Some(token) => super::connect(state, token).await.map_err(|e| e.to_string()),
+                None => Err("params.token is required".to_string()),
+            }
+        }
+        _ => Err("The first message must be auth".to_string()),
+    };
+
+    let response = match &outcome {
+        Ok(connection) => McpResponse::success(id, json!({ "user": connection.caller.login })),
+        Err(message) => McpResponse::error(id, error_codes::AUTHENTICATION_ERROR, message.clone(), None),
+    };
+    if let Ok(text) = serde_json::to_string(&response) {
+        let _ = sender.send(Message::Text(text)).await;
+    }
+    outcome
+}
+
+/// Send event notifications to a connection's `outgoing` until it closes.
+/// With a `caller`, only events from repositories they can read are sent.
+pub fn forward_events(state: &AppState, outgoing: &UnboundedSender<Value>, caller: Option<Caller>) -> tokio::task::JoinHandle<()> {
+    let mut events = state.events.subscribe();
+    let outgoing = outgoing.clone();
+    let state = state.clone();
+    let mut access = caller.map(Access::new);
+    tokio::spawn(
+        async move {
+            loop {
+                match events.recv().await {
+                    Ok(notification) => {
+                        if let Some(access) = &mut access {
+                            let repository = notification.pointer("/params/repository").and_then(|r| r.as_str());
+                            match repository {
+                                Some(repository) if access.allows(&state, repository).await => {}
+                                _ => continue,
+                            }
+                        }
+                        if outgoing.send(notification).is_err() {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Client missed {} event notifications", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+        .instrument(tracing::Span::current()),
+    )
+}
+
+/// Handle one text message on a long-lived connection (WebSocket or stdio).
+/// Notifications run in order; requests and batches are spawned on
+/// `in_flight` so a later cancellation can reach them, and every response
+/// goes out through `outgoing`. Everything runs as `caller`, the
+/// connection's user.
+pub async fn handle_message(
+    state: &AppState,
+    session: &Session,
+    caller: &Option<Caller>,
+    text: &str,
+    outgoing: &UnboundedSender<Value>,
+    in_flight: &InFlight,
+) {
+    let message = match serde_json::from_str::<Value>(text) {
+        Ok(message) => message,
+        Err(e) => {
+            error!("Failed to parse message: {}", e);
+            let error_response = McpResponse::error(
+                None,
+                error_codes::PARSE_ERROR,
+                "Invalid JSON".to_string(),
+                None,
+            );
+            if let Ok(error_value) = serde_json::to_value(&error_response) {
+                let _ = outgoing.send(error_value);
+            }
+            return;
+        }
+    };
+
+    if let Value::Array(batch) = message {
+        spawn_batch(state, session, caller, batch, outgoing, in_flight);
+        return;
+    }
+
+    match serde_json::from_value::<McpRequest>(message) {
+        Ok(request) if request.method == methods::NOTIFICATIONS_CANCELLED => {
+            let id = request.params.as_ref().and_then(|p| p.get("requestId")).cloned().unwrap_or_default();
+            cancel_request(in_flight, &id);
+        }
+        Ok(request) if request.method.starts_with("notifications/") => {
+            // Handled in order, so a request sent right after
+            // notifications/initialized finds the session ready
+            if let Ok(Some(response)) = handle_request(state.clone(), session.clone(), caller.clone(), request).await {
+                let _ = outgoing.send(response);
+            }
+        }
+        Ok(request) => {
+            let reporter = ProgressReporter::from_params(request.params.as_ref(), outgoing);
+            let id = request.id.clone().unwrap_or_default();
+            let (state, session, caller) = (state.clone(), session.clone(), caller.clone());
+            let outgoing = outgoing.clone();
+            in_flight.spawn(&id, async move {
+                match progress::scope(reporter, handle_request(state, session, caller, request)).await {
+                    Ok(Some(response)) => {
+                        let _ = outgoing.send(response);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        error!("Error handling request: {}", e);
+                        let error_response = McpResponse::from_error(None, &e);
+                        if let Ok(error_value) = serde_json::to_value(&error_response) {
+                            let _ = outgoing.send(error_value);
+                        }
+                    }
+                }
+            });
+        }
+        Err(e) => {
+            error!("Invalid request: {}", e);
+            let error_response = McpResponse::error(
+                None,
+                error_codes::INVALID_REQUEST,
+                format!("Invalid request: {}", e),
+                None,
+            );
+            if let Ok(error_value) = serde_json::to_value(&error_response) {
+                let _ = outgoing.send(error_value);
+            }
+        }
+    }
+}
+
+fn cancel_request(in_flight: &InFlight, id: &Value) {
+    if in_flight.cancel(id) {
+        info!("Cancelled request {}", id);
+    } else {
+        debug!("Cancellation for unknown or finished request {}", id);
+    }
+}
+
+/// Each entry of a batch runs as its own request, so it can be
+/// cancelled by its id, including by a `notifications/cancelled` in the same
+/// batch; the responses are sent together once all finish.
+fn spawn_batch(
+    state: &AppState,
+    session: &Session,
+    caller: &Option<Caller>,
+    batch: Vec<Value>,
+    outgoing: &UnboundedSender<Value>,
+    in_flight: &InFlight,
+) {
+    if batch.is_empty() {
+        let response = McpResponse::error(None, error_codes::INVALID_REQUEST, "Empty batch".to_string(), None);
+        if let Ok(value) = serde_json::to_value(&response) {
+            let _ = outgoing.send(value);
+        }
+        return;
+    }
+
+    let mut cancellations = Vec::new();
+    let mut starts = Vec::new();
+    let entries: Vec<_> = batch
+        .into_iter()
+        .filter_map(|entry| {
+            if entry.get("method").and_then(Value::as_str) == Some(methods::NOTIFICATIONS_CANCELLED) {
+                cancellations.push(entry.pointer("/params/requestId").cloned().unwrap_or_default());
+                return None;
+            }
+
+            let (done, result) = oneshot::channel();
+            let (start, started) = oneshot::channel::<()>();
+            let id = entry.get("id").cloned().unwrap_or_default();
+            let (state, session, caller, outgoing) = (state.clone(), session.clone(), caller.clone(), outgoing.clone());
+            in_flight.spawn(&id, async move {
+                if started.await.is_ok() {
+                    let _ = done.send(handle_batch_entry(state, session, caller, entry, outgoing).await);
+                }
+            });
+            starts.push(start);
+            Some(result)
+        })
+        .collect();
+
+    // Every entry is registered before any runs, so a cancellation anywhere
+    // in the batch reaches the others as well as earlier requests
+    for id in cancellations {
+        cancel_request(in_flight, &id);
+    }
+    for start in starts {
+        let _ = start.send(());
+    }
+
+    let outgoing = outgoing.clone();
+    tokio::spawn(async move {
+        // Cancelled entries drop their sender and are left out, like notifications
+        let responses: Vec<Value> = futures_util::future::join_all(entries)
+            .await
+            .into_iter()
+            .filter_map(|response| response.ok().flatten())
+            .collect();
+        if !responses.is_empty() {
+            let _ = outgoing.send(Value::Array(responses));
+        }
+    });
 }
 
-async fn handle_initialize(request: &McpRequest) -> Result<McpResponse> {
+async fn handle_initialize(session: &Session, request: &McpRequest) -> Result<McpResponse> {
+    let params = request.params.as_ref();
+    let requested = params.and_then(|p| p.get("protocolVersion")).and_then(|v| v.as_str());
+    let has_capabilities = params.and_then(|p| p.get("capabilities")).map_or(false, Value::is_object);
+    let client = params.and_then(|p| p.pointer("/clientInfo/name")).and_then(|v| v.as_str());
+    let (Some(requested), true, Some(client)) = (requested, has_capabilities, client) else {
+        // Let the client retry with a well-formed initialize
+        session.reset();
+        return Ok(McpResponse::error(
+            request.id.clone(),
+            error_codes::INVALID_PARAMS,
+            "initialize requires protocolVersion, capabilities and clientInfo.name".to_string(),
+            None,
+        ));
+    };
+
+    // A version we don't speak gets our latest; the client decides whether to continue
+    let version = SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .copied()
+        .find(|v| *v == requested)
+        .unwrap_or(MCP_VERSION);
+    info!("MCP client {} initializing with protocol {} (requested {})", client, version, requested);
+
+    let compact = params
+        .and_then(|p| p.pointer("/capabilities/experimental/compactResults"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    session.set_compact(compact);
+    session.set_client(client, version);
+
     let result = json!({
-        "protocolVersion": MCP_VERSION,
+        "protocolVersion": version,
         "capabilities": ServerCapabilities::default(),
         "serverInfo": {
             "name": "github-mcp-server",
@@ -107,79 +540,12 @@ async fn handle_initialize(request: &McpRequest) -> Result<McpResponse> {
     Ok(McpResponse::success(request.id.clone(), result))
 }
 
-async fn handle_tools_list(request: &McpRequest) -> Result<McpResponse> {
-    let tools = vec![
-        McpTool {
-            name: "github_push".to_string(),
-            description: "Intelligent git push with PR management and workflow automation".to_string(),
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "branch": {
-                        "type": "string",
-                        "description": "Branch to push (defaults to current branch)"
-                    },
-                    "message": {
-                        "type": "string",
-                        "description": "Optional commit message if changes need to be committed"
-                    },
-                    "ready_for_review": {
-                        "type": "boolean",
-                        "description": "Mark PR as ready for review after push"
-                    }
-                }
-            }),
-        },
-        McpTool {
-            name: "github_scan_tasks".to_string(),
-            description: "Scan GitHub Projects for tasks and present organized by type/priority".to_string(),
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "project_number": {
-                        "type": "string",
-                        "description": "GitHub Project number (optional, will auto-detect from TODO.md)"
-                    },
-                    "filter_type": {
-                        "type": "string",
-                        "enum": ["bug", "feature", "enhancement", "documentation", "refactor", "test", "chore"],
-                        "description": "Filter tasks by type"
-                    },
-                    "status": {
-                        "type": "string",
-                        "description": "Filter tasks by status (In Progress, To Do, etc.)"
-                    }
-                }
-            }),
-        },
-        McpTool {
-            name: "github_merge".to_string(),
-            description: "Complete merge workflow with tests, cleanup, and project updates".to_string(),
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "branch": {
-                        "type": "string",
-                        "description": "Branch to merge (defaults to current branch)"
-                    },
-                    "delete_branch": {
-                        "type": "boolean",
-                        "description": "Delete branch after merge (default: true)"
-                    },
-                    "cleanup_work_folder": {
-                        "type": "boolean",
-                        "description": "Clean up work folder after merge (default: ask user)"
-                    }
-                }
-            }),
-        },
-    ];
-
-    let result = json!({ "tools": tools });
+async fn handle_tools_list(state: AppState, request: &McpRequest) -> Result<McpResponse> {
+    let result = json!({ "tools": state.tools.definitions() });
     Ok(McpResponse::success(request.id.clone(), result))
 }
 
-async fn handle_tools_call(state: AppState, request: &McpRequest) -> Result<McpResponse> {
+async fn handle_tools_call(state: AppState, session: &Session, request: &McpRequest) -> Result<McpResponse> {
     let params = request.params.as_ref().ok_or_else(|| {
         AppError::McpProtocol("Missing parameters for tools/call".to_string())
     })?;
@@ -188,47 +554,61 @@ async fn handle_tools_call(state: AppState, request: &McpRequest) -> Result<McpR
         AppError::McpProtocol("Missing tool name".to_string())
     })?;
 
-    let arguments = params.get("arguments").unwrap_or(&json!({}));
+    let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
 
-    let result = match tool_name {
-        "github_push" => {
-            let command = serde_json::from_value::<GitHubCommand>(json!({
-                "Push": {
-                    "branch": arguments.get("branch"),
-                    "message": arguments.get("message"),
-                    "ready_for_review": arguments.get("ready_for_review")
-                }
-            }))?;
-            crate::github::execute_workflow_command(state, command).await?
-        }
-        "github_scan_tasks" => {
-            let command = serde_json::from_value::<GitHubCommand>(json!({
-                "ScanTasks": {
-                    "project_number": arguments.get("project_number"),
-                    "filter_type": arguments.get("filter_type"),
-                    "status": arguments.get("status")
-                }
-            }))?;
-            crate::github::execute_workflow_command(state, command).await?
-        }
-        "github_merge" => {
-            let command = serde_json::from_value::<GitHubCommand>(json!({
-                "Merge": {
-                    "branch": arguments.get("branch"),
-                    "delete_branch": arguments.get("delete_branch"),
-                    "cleanup_work_folder": arguments.get("cleanup_work_folder")
-                }
-            }))?;
-            crate::github::execute_workflow_command(state, command).await?
-        }
-        _ => {
-            return Ok(McpResponse::error(
-                request.id.clone(),
-                error_codes::METHOD_NOT_FOUND,
-                format!("Unknown tool: {}", tool_name),
-                None,
-            ));
-        }
+    if state.tools.is_disabled(tool_name) {
+        return Ok(McpResponse::error(
+            request.id.clone(),
+            error_codes::METHOD_NOT_FOUND,
+            format!("Tool {} is disabled by an administrator", tool_name),
+            None,
+        ));
+    }
+
+    let Some(tool) = state.tools.get(tool_name) else {
+        return Ok(McpResponse::error(
+            request.id.clone(),
+            error_codes::METHOD_NOT_FOUND,
+            format!("Unknown tool: {}", tool_name),
+            None,
+        ));
+    };
+
+    if state.config.security.read_only && !tool.read_only() {
+        return Ok(McpResponse::error(
+            request.id.clone(),
+            error_codes::READ_ONLY_ERROR,
+            AppError::ReadOnly(tool_name.to_string()).to_string(),
+            None,
+        ));
+    }
+
+    if let Err(errors) = state.tools.validate(tool_name, &arguments) {
+        let message = format!(
+            "Invalid arguments for {}: {} at {}",
+            tool_name,
+            errors[0].message,
+            if errors[0].pointer.is_empty() { "/" } else { &errors[0].pointer }
+        );
+        return Ok(McpResponse::error(
+            request.id.clone(),
+            error_codes::INVALID_PARAMS,
+            message,
+            Some(json!({ "errors": errors })),
+        ));
+    }
+
+    // Read-only tools never wait for approval, whatever CONFIRM_TOOLS says
+    if tool.mutates(&arguments) && crate::operations::requires_confirmation(&state, tool_name) {
+        let result = crate::operations::hold(&state, tool_name, &arguments).await?;
+        return Ok(McpResponse::success(request.id.clone(), result));
+    }
+
+    let echo = session.compact().then(|| arguments.clone());
+    let result = tool.execute(state, arguments).instrument(tracing::info_span!("tool", tool = %tool_name)).await?;
+    let result = match echo {
+        Some(arguments) => super::compact::apply(result, &arguments),
+        None => result,
     };
 
     Ok(McpResponse::success(request.id.clone(), result))
@@ -248,21 +628,160 @@ async fn handle_resources_list(request: &McpRequest) -> Result<McpResponse> {
             description: Some("GitHub Project tasks with current status".to_string()),
             mime_type: Some("application/json".to_string()),
         },
+        McpResource {
+            uri: super::server_info::URI.to_string(),
+            name: "Server Info".to_string(),
+            description: Some("Server version, enabled features, configured profiles and supported protocol versions".to_string()),
+            mime_type: Some("application/json".to_string()),
+        },
+        McpResource {
+            uri: crate::github::budget::URI.to_string(),
+            name: "Rate Limits".to_string(),
+            description: Some("Remaining GitHub API requests per resource and when each window resets".to_string()),
+            mime_type: Some("application/json".to_string()),
+        },
+        McpResource {
+            uri: "github://compact/keys".to_string(),
+            name: "Compact Result Keys".to_string(),
+            description: Some("Long keys and the short forms used in compact tool results".to_string()),
+            mime_type: Some("application/json".to_string()),
+        },
     ];
 
     let result = json!({ "resources": resources });
     Ok(McpResponse::success(request.id.clone(), result))
 }
 
+async fn handle_resource_templates_list(request: &McpRequest) -> Result<McpResponse> {
+    let templates = vec![
+        McpResourceTemplate {
+            uri_template: "github://pr/{number}/diff".to_string(),
+            name: "Pull Request Diff".to_string(),
+            description: Some("Unified diff of a pull request in the current repository".to_string()),
+            mime_type: Some("text/x-diff".to_string()),
+        },
+        McpResourceTemplate {
+            uri_template: "github://issue/{number}/timeline".to_string(),
+            name: "Issue Timeline".to_string(),
+            description: Some(
+                "History of an issue or pull request in the current repository: cross-references, assignments, \
+                 review requests, deployments and more. Add ?events=assigned,cross-referenced to filter"
+                    .to_string(),
+            ),
+            mime_type: Some("application/json".to_string()),
+        },
+        McpResourceTemplate {
+            uri_template: "github://repos/{owner}/{repo}/issues/{number}/timeline".to_string(),
+            name: "Repository Issue Timeline".to_string(),
+            description: Some("History of an issue or pull request in any repository".to_string()),
+            mime_type: Some("application/json".to_string()),
+        },
+        McpResourceTemplate {
+            uri_template: "github://projects/{number}/board".to_string(),
+            name: "Project Board".to_string(),
+            description: Some(
+                "A project as its board: one column per Status with each item's assignees, priority, iteration \
+                 and linked PRs. Add ?owner=login for another owner's project. Kept for a minute"
+                    .to_string(),
+            ),
+            mime_type: Some("application/json".to_string()),
+        },
+        McpResourceTemplate {
+            uri_template: "federated://{peer}/{uri}".to_string(),
+            name: "Federated Resource".to_string(),
+            description: Some("A resource of a federation peer, e.g. federated://ghes/github://projects/tasks".to_string()),
+            mime_type: None,
+        },
+    ];
+
+    let result = json!({ "resourceTemplates": templates });
+    Ok(McpResponse::success(request.id.clone(), result))
+}
+
+/// `github://pr/{number}/diff` -> number
+fn parse_pr_diff_uri(uri: &str) -> Option<u64> {
+    uri.strip_prefix("github://pr/")?
+        .strip_suffix("/diff")?
+        .parse()
+        .ok()
+}
+
+/// Most URIs one `resources/read` may ask for through `uris`
+const MAX_BATCH_URIS: usize = 20;
+
 async fn handle_resources_read(state: AppState, request: &McpRequest) -> Result<McpResponse> {
     let params = request.params.as_ref().ok_or_else(|| {
         AppError::McpProtocol("Missing parameters for resources/read".to_string())
     })?;
 
+    // `uris` reads several resources in one round trip, concurrently; one
+    // that fails is reported in `errors` without failing the others
+    if let Some(uris) = params.get("uris") {
+        let uris: Vec<String> = serde_json::from_value(uris.clone()).map_err(|_| {
+            AppError::McpProtocol("uris for resources/read must be an array of strings".to_string())
+        })?;
+        if uris.is_empty() || uris.len() > MAX_BATCH_URIS {
+            return Err(AppError::McpProtocol(format!(
+                "resources/read takes 1 to {} uris, got {}",
+                MAX_BATCH_URIS,
+                uris.len()
+            )));
+        }
+
+        let reads = uris.iter().map(|uri| read_resource(state.clone(), uri));
+        let mut contents = Vec::new();
+        let mut errors = Vec::new();
+        for (uri, read) in uris.iter().zip(futures_util::future::join_all(reads).await) {
+            match read {
+                Ok(Some(result)) => contents.extend(result["contents"].as_array().cloned().unwrap_or_default()),
+                Ok(None) => errors.push(json!({ "uri": uri, "message": format!("Unknown resource: {}", uri) })),
+                Err(e) => errors.push(json!({ "uri": uri, "message": e.to_string() })),
+            }
+        }
+        return Ok(McpResponse::success(request.id.clone(), json!({ "contents": contents, "errors": errors })));
+    }
+
     let uri = params.get("uri").and_then(|v| v.as_str()).ok_or_else(|| {
         AppError::McpProtocol("Missing URI for resources/read".to_string())
     })?;
 
+    match read_resource(state, uri).await? {
+        Some(result) => Ok(McpResponse::success(request.id.clone(), result)),
+        None => Ok(McpResponse::error(
+            request.id.clone(),
+            error_codes::METHOD_NOT_FOUND,
+            format!("Unknown resource: {}", uri),
+            None,
+        )),
+    }
+}
+
+/// The `resources/read` result for `uri`, or `None` for an unknown resource
+async fn read_resource(state: AppState, uri: &str) -> Result<Option<Value>> {
+    // Diffs are served as text rather than wrapped in JSON
+    if let Some(number) = parse_pr_diff_uri(uri) {
+        let command = GitHubCommand::GetPullRequestDiff {
+            repository: None,
+            number,
+            per_file: None,
+            files: None,
+            max_bytes: None,
+        };
+        let content = crate::github::execute_workflow_command(state, command).await?;
+        return Ok(Some(json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": "text/x-diff",
+                "text": content["diff"].as_str().unwrap_or_default()
+            }]
+        })));
+    }
+
+    // Served by the peer as is
+    if uri.starts_with(super::federation::URI_PREFIX) {
+        return state.federation.read_resource(uri).await.map(Some);
+    }
+
     let content = match uri {
         "github://workflow/status" => {
             crate::github::get_workflow_status(state).await?
@@ -270,62 +789,45 @@ async fn handle_resources_read(state: AppState, request: &McpRequest) -> Result<
         "github://projects/tasks" => {
             crate::github::get_project_tasks(state).await?
         }
-        _ => {
-            return Ok(McpResponse::error(
-                request.id.clone(),
-                error_codes::METHOD_NOT_FOUND,
-                format!("Unknown resource: {}", uri),
-                None,
-            ));
-        }
+        "github://compact/keys" => super::compact::key_map(),
+        super::server_info::URI => super::server_info::info(&state),
+        crate::github::budget::URI => crate::github::budget::resource(),
+        _ => match crate::github::timeline::parse_uri(uri) {
+            Some((repository, number, events)) => {
+                let command = GitHubCommand::GetTimeline { repository, number, events };
+                crate::github::execute_workflow_command(state, command).await?
+            }
+            None => match crate::github::board::parse_uri(uri) {
+                Some((number, owner)) => crate::github::get_project_board(state, number, owner).await?,
+                None => return Ok(None),
+            },
+        },
     };
 
-    let result = json!({
+    Ok(Some(json!({
         "contents": [{
             "uri": uri,
             "mimeType": "application/json",
             "text": serde_json::to_string_pretty(&content)?
         }]
-    });
-
-    Ok(McpResponse::success(request.id.clone(), result))
+    })))
 }
 
-async fn handle_github_push(state: AppState, request: &McpRequest) -> Result<McpResponse> {
-    let params = request.params.as_ref().unwrap_or(&json!({}));
-    
-    let command = GitHubCommand::Push {
-        branch: params.get("branch").and_then(|v| v.as_str()).map(String::from),
-        message: params.get("message").and_then(|v| v.as_str()).map(String::from),
-        ready_for_review: params.get("ready_for_review").and_then(|v| v.as_bool()),
-    };
-
-    let result = crate::github::execute_workflow_command(state, command).await?;
-    Ok(McpResponse::success(request.id.clone(), result))
-}
+/// The legacy `github/*` methods are calls to the tool each stands for, so
+/// they're validated, disabled and held for approval like any tools/call
+async fn handle_legacy_method(state: AppState, session: &Session, request: &McpRequest, tool: &str) -> Result<McpResponse> {
+    let mut arguments = request.params.clone().filter(Value::is_object).unwrap_or_else(|| json!({}));
+    let meta = arguments.as_object_mut().and_then(|arguments| arguments.remove("_meta"));
+    let mut params = json!({ "name": tool, "arguments": arguments });
+    if let Some(meta) = meta {
+        params["_meta"] = meta;
+    }
 
-async fn handle_github_scan_tasks(state: AppState, request: &McpRequest) -> Result<McpResponse> {
-    let params = request.params.as_ref().unwrap_or(&json!({}));
-    
-    let command = GitHubCommand::ScanTasks {
-        project_number: params.get("project_number").and_then(|v| v.as_str()).map(String::from),
-        filter_type: params.get("filter_type").and_then(|v| v.as_str()).map(String::from),
-        status: params.get("status").and_then(|v| v.as_str()).map(String::from),
+    let call = McpRequest {
+        jsonrpc: request.jsonrpc.clone(),
+        id: request.id.clone(),
+        method: methods::TOOLS_CALL.to_string(),
+        params: Some(params),
     };
-
-    let result = crate::github::execute_workflow_command(state, command).await?;
-    Ok(McpResponse::success(request.id.clone(), result))
+    handle_tools_call(state, session, &call).await
 }
-
-async fn handle_github_merge(state: AppState, request: &McpRequest) -> Result<McpResponse> {
-    let params = request.params.as_ref().unwrap_or(&json!({}));
-    
-    let command = GitHubCommand::Merge {
-        branch: params.get("branch").and_then(|v| v.as_str()).map(String::from),
-        delete_branch: params.get("delete_branch").and_then(|v| v.as_bool()),
-        cleanup_work_folder: params.get("cleanup_work_folder").and_then(|v| v.as_bool()),
-    };
-
-    let result = crate::github::execute_workflow_command(state, command).await?;
-    Ok(McpResponse::success(request.id.clone(), result))
-}
\ No newline at end of file
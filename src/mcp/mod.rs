@@ -1,26 +1,264 @@
 pub mod protocol;
+pub mod caller;
+pub mod cancellation;
+pub mod compact;
+pub mod connections;
+pub mod events;
+pub mod federation;
 pub mod handlers;
+pub mod progress;
+pub mod schema;
+pub mod server_info;
+pub mod session;
+pub mod stdio;
+pub mod tools;
 
 use axum::{
-    extract::{State, WebSocketUpgrade},
-    response::Response,
+    extract::{Query, State, WebSocketUpgrade},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{sse::{Event, Sse}, IntoResponse, Response},
     Json,
 };
+use futures_util::StreamExt;
+use serde::Deserialize;
 use serde_json::Value;
+use std::convert::Infallible;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::{error, Instrument};
 
-use crate::{AppState, error::Result};
-use protocol::McpRequest;
+use crate::{AppState, error::{AppError, Result}, security::JwtClaims};
+use caller::Caller;
+use cancellation::{CancelFlag, CancelOnDrop};
+use progress::ProgressReporter;
+use protocol::{error_codes, methods, McpRequest, McpResponse};
+use session::Session;
 
+/// `POST /mcp`. A client that accepts `text/event-stream` and sends a
+/// progress token gets its progress notifications as server-sent events,
+/// followed by the response; anything else gets a plain JSON response.
+/// A JSON array is a JSON-RPC batch and is answered with an array.
+/// `initialize` starts a session whose `Mcp-Session-Id` every later request
+/// must send.
 pub async fn handle_mcp_request(
     State(state): State<AppState>,
-    Json(request): Json<McpRequest>,
-) -> Result<Json<Value>> {
-    handlers::handle_request(state, request).await
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> Result<Response> {
+    let streams = headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .map_or(false, |accept| accept.contains("text/event-stream"));
+
+    let mut created = None;
+    let mut session_id = None;
+    let session = match headers.get(session::SESSION_HEADER).and_then(|v| v.to_str().ok()) {
+        Some(id) => match state.sessions.get(id).await? {
+            Some(session) => {
+                session_id = Some(id.to_string());
+                session
+            }
+            // Expired or from before a restart: the client must initialize again
+            None => return Ok(StatusCode::NOT_FOUND.into_response()),
+        },
+        None if body.get("method").and_then(|m| m.as_str()) == Some(methods::INITIALIZE) => {
+            let (id, session) = state.sessions.create().await?;
+            session_id = Some(id.clone());
+            created = Some(id);
+            session
+        }
+        // Never initialized, so everything but initialize is refused
+        None => Session::default(),
+    };
+    // The session is shared by every request that names it; who's calling
+    // comes from this request's own token, as validated by `require_session`
+    let caller = caller::current();
+    let sessions = state.sessions.clone();
+
+    let mut response = match body {
+        Value::Array(batch) => handle_batch_request(state, session.clone(), caller, streams, batch).await?,
+        body => {
+            let request = match serde_json::from_value::<McpRequest>(body) {
+                Ok(request) => request,
+                Err(e) => {
+                    let response = McpResponse::error(None, error_codes::INVALID_REQUEST, format!("Invalid request: {}", e), None);
+                    return Ok(Json(response).into_response());
+                }
+            };
+            handle_single_request(state, session.clone(), caller, streams, request).await?
+        }
+    };
+    // Phases only change on initialize and notifications/initialized, which
+    // are answered by the time a response exists, streamed or not
+    if let Some(id) = &session_id {
+        sessions.save(id, &session).await?;
+    }
+
+    if let Some(id) = created.and_then(|id| HeaderValue::from_str(&id).ok()) {
+        response.headers_mut().insert(session::SESSION_HEADER, id);
+    }
+    Ok(response)
+}
+
+/// `DELETE /mcp`: end the session named by `Mcp-Session-Id`
+pub async fn close_session(State(state): State<AppState>, headers: HeaderMap) -> Result<StatusCode> {
+    Ok(match headers.get(session::SESSION_HEADER).and_then(|v| v.to_str().ok()) {
+        Some(id) if state.sessions.remove(id).await? => StatusCode::OK,
+        Some(_) => StatusCode::NOT_FOUND,
+        None => StatusCode::BAD_REQUEST,
+    })
+}
+
+async fn handle_single_request(
+    state: AppState,
+    session: Session,
+    caller: Option<Caller>,
+    streams: bool,
+    request: McpRequest,
+) -> Result<Response> {
+    let (outgoing, outgoing_rx) = mpsc::unbounded_channel();
+    let reporter = ProgressReporter::from_params(request.params.as_ref(), &outgoing);
+    let flag = CancelFlag::default();
+    if !streams || reporter.is_none() {
+        // Dropped with this future if the client disconnects
+        let _cancel = flag.cancel_on_drop(None);
+        let id = request.id.clone();
+        let response = match cancellation::scope(flag.clone(), handlers::handle_request(state, session, caller, request)).await {
+            Ok(response) => response,
+            // Notifications have no response to carry the error
+            Err(e) if id.is_none() => return Err(e),
+            Err(e) => serde_json::to_value(McpResponse::from_error(id, &e)).ok(),
+        };
+        return Ok(match response {
+            Some(response) => Json(response).into_response(),
+            None => StatusCode::ACCEPTED.into_response(),
+        });
+    }
+
+    let id = request.id.clone();
+    let task_flag = flag.clone();
+    let task = tokio::spawn(
+        async move {
+            let handled = cancellation::scope(task_flag, handlers::handle_request(state, session, caller, request));
+            let response = match progress::scope(reporter, handled).await {
+                Ok(response) => response,
+                Err(e) => {
+                    error!("Error handling streamed MCP request: {}", e);
+                    serde_json::to_value(McpResponse::from_error(id, &e)).ok()
+                }
+            };
+            // Dropping the last sender after the response ends the stream
+            if let Some(response) = response {
+                let _ = outgoing.send(response);
+            }
+        }
+        .instrument(tracing::Span::current()),
+    );
+
+    Ok(event_stream(outgoing_rx, flag.cancel_on_drop(Some(task.abort_handle()))))
 }
 
+/// Entries run concurrently under one cancellation flag. With `streams`,
+/// progress for any entry is sent as it happens and the array of responses
+/// comes last.
+async fn handle_batch_request(
+    state: AppState,
+    session: Session,
+    caller: Option<Caller>,
+    streams: bool,
+    batch: Vec<Value>,
+) -> Result<Response> {
+    let (outgoing, outgoing_rx) = mpsc::unbounded_channel();
+    let flag = CancelFlag::default();
+    if !streams {
+        let _cancel = flag.cancel_on_drop(None);
+        let responses = cancellation::scope(flag.clone(), handlers::handle_batch(state, session, caller, batch, outgoing)).await;
+        // A batch of only notifications gets no body
+        return Ok(match responses {
+            Some(responses) => Json(responses).into_response(),
+            None => StatusCode::ACCEPTED.into_response(),
+        });
+    }
+
+    let task_flag = flag.clone();
+    let task_outgoing = outgoing.clone();
+    let task = tokio::spawn(
+        async move {
+            let responses =
+                cancellation::scope(task_flag, handlers::handle_batch(state, session, caller, batch, task_outgoing.clone()))
+                    .await;
+            if let Some(responses) = responses {
+                let _ = task_outgoing.send(responses);
+            }
+        }
+        .instrument(tracing::Span::current()),
+    );
+    drop(outgoing);
+
+    Ok(event_stream(outgoing_rx, flag.cancel_on_drop(Some(task.abort_handle()))))
+}
+
+/// Server-sent events carrying every message sent to `outgoing` until the
+/// last sender is dropped. The stream owns `cancel`, so a client closing it
+/// cancels the work behind it.
+fn event_stream(outgoing: mpsc::UnboundedReceiver<Value>, cancel: CancelOnDrop) -> Response {
+    let events = UnboundedReceiverStream::new(outgoing).map(move |message| {
+        let _ = &cancel;
+        Ok::<_, Infallible>(Event::default().event("message").data(message.to_string()))
+    });
+    Sse::new(events).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebSocketQuery {
+    /// For clients that can't set headers on the upgrade request
+    token: Option<String>,
+}
+
+/// `GET /mcp/ws`. The session token comes from `Authorization`, the `token`
+/// query parameter or, failing both, an `auth` message sent first on the
+/// socket (see `handlers::handle_websocket`). A token given on the upgrade
+/// request is checked before upgrading, so a bad one gets a plain 401.
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
-) -> Response {
-    ws.on_upgrade(|socket| handlers::handle_websocket(socket, state))
-}
\ No newline at end of file
+    Query(query): Query<WebSocketQuery>,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(String::from)
+        .or(query.token);
+
+    let connection = match token {
+        Some(token) => Some(connect(&state, &token).await?),
+        None => None,
+    };
+    Ok(ws.on_upgrade(|socket| handlers::handle_websocket(socket, state, connection)))
+}
+
+/// Authenticate a WebSocket connection with `token` and count it against
+/// the user's connection limit
+pub(crate) async fn connect(state: &AppState, token: &str) -> Result<connections::Connection> {
+    let claims = authenticate(state, token).await?;
+    state.connections.acquire(Caller::from_claims(&state.config, &claims), &claims.jti).ok_or_else(|| {
+        AppError::Authorization(format!(
+            "{} already has {} WebSocket connections open",
+            claims.username,
+            state.connections.max_per_user()
+        ))
+    })
+}
+
+/// Claims of a valid token whose sign-in session is still open. Tokens in
+/// `Authorization` are checked by `require_session` already; this covers
+/// those that arrive some other way.
+async fn authenticate(state: &AppState, token: &str) -> Result<JwtClaims> {
+    let claims = state.jwt.validate(token)?;
+    if !crate::auth::sessions::is_active(&state.db, &claims.jti).await? {
+        return Err(AppError::Authentication("Session has ended; sign in again".to_string()));
+    }
+    Ok(claims)
+}
@@ -0,0 +1,60 @@
+// MCP progress notifications for long-running tools.
+//
+// A reporter is installed for the duration of a request when the client sent
+// `params._meta.progressToken` over a transport that can stream (WebSocket).
+// Workflow code calls `report` without knowing whether anyone is listening.
+
+use serde_json::{json, Value};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::protocol::methods;
+
+tokio::task_local! {
+    static REPORTER: ProgressReporter;
+}
+
+#[derive(Clone)]
+pub struct ProgressReporter {
+    token: Value,
+    sender: UnboundedSender<Value>,
+    progress: Arc<AtomicU64>,
+}
+
+impl ProgressReporter {
+    pub fn new(token: Value, sender: UnboundedSender<Value>) -> Self {
+        Self { token, sender, progress: Arc::new(AtomicU64::new(0)) }
+    }
+
+    /// Build a reporter if the request params carry a progress token
+    pub fn from_params(params: Option<&Value>, sender: &UnboundedSender<Value>) -> Option<Self> {
+        let token = params?.pointer("/_meta/progressToken")?.clone();
+        Some(Self::new(token, sender.clone()))
+    }
+}
+
+/// Run `future` with `reporter` available to `report`
+pub async fn scope<F: Future>(reporter: Option<ProgressReporter>, future: F) -> F::Output {
+    match reporter {
+        Some(reporter) => REPORTER.scope(reporter, future).await,
+        None => future.await,
+    }
+}
+
+/// Send a `notifications/progress` message; a no-op when the client didn't ask for progress
+pub fn report(message: &str) {
+    let _ = REPORTER.try_with(|reporter| {
+        let progress = reporter.progress.fetch_add(1, Ordering::Relaxed) + 1;
+        let _ = reporter.sender.send(json!({
+            "jsonrpc": "2.0",
+            "method": methods::NOTIFICATIONS_PROGRESS,
+            "params": {
+                "progressToken": reporter.token,
+                "progress": progress,
+                "message": message
+            }
+        }));
+    });
+}
@@ -2,8 +2,19 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
-/// MCP Protocol Version
-pub const MCP_VERSION: &str = "2024-11-05";
+use crate::github::api::{AdvisoryCredit, AdvisoryVulnerability, IssueFilter, ReviewCommentInput};
+use crate::github::gists::GistFile;
+use crate::github::labels::LabelSpec;
+use crate::github::protection::ProtectionUpdate;
+use crate::github::tasks::ProjectRef;
+use crate::github::triage::TriageDecision;
+use crate::permissions::Operation;
+
+/// MCP Protocol Version: the latest this server speaks
+pub const MCP_VERSION: &str = "2025-03-26";
+
+/// Versions `initialize` accepts, newest first
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &[MCP_VERSION, "2024-11-05"];
 
 /// MCP Request message
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,17 +72,768 @@ pub enum GitHubCommand {
         branch: Option<String>,
         message: Option<String>,
         ready_for_review: Option<bool>,
+        draft_pr: Option<bool>,      // open a draft PR when the branch has none; defaults to AUTO_DRAFT_PR
+        request_reviewers: Option<bool>, // ask the CODEOWNERS of the changed paths to review the PR
+        repo: Option<String>,        // registered repository name; defaults to the server's CWD
     },
     ScanTasks {
         project_number: Option<String>,
+        owner: Option<String>,       // org or user login owning the project
         filter_type: Option<String>, // "bug", "feature", "enhancement"
         status: Option<String>,      // "In Progress", "To Do", etc.
+        assignee: Option<String>,    // GitHub login
+        label: Option<String>,
+        projects: Option<Vec<ProjectRef>>, // scan several projects at once
+        all_projects: Option<bool>,        // scan every project in the user's preferences
+        filter: Option<String>,            // saved filter from the user's preferences
+        repo: Option<String>,
     },
     Merge {
         branch: Option<String>,
         delete_branch: Option<bool>,
         cleanup_work_folder: Option<bool>,
+        ignore_checks: Option<bool>,       // merge even if CI checks are pending or failing
+        mode: Option<String>,              // immediate, auto (GitHub auto-merge) or queue (merge queue); defaults to MERGE_MODE
+        repo: Option<String>,
+    },
+    StartTask {
+        issue_number: u64,
+        project_number: Option<String>,
+        owner: Option<String>,
+        open_draft_pr: Option<bool>,
+    },
+    PromoteDraft {
+        item_id: Option<String>,         // project item ID of the draft
+        title: Option<String>,           // or match the draft by title
+        repository: Option<String>,      // owner/name, defaults to current repository
+        project_number: Option<String>,
+        owner: Option<String>,
+        assignee: Option<String>,        // defaults to the draft's assignees, then the caller
+    },
+    GetChecklist {
+        number: u64,                     // issue or PR number
+        repository: Option<String>,      // owner/name, defaults to current repository
+    },
+    UpdateChecklist {
+        number: u64,
+        repository: Option<String>,
+        items: Option<Vec<usize>>,       // 1-based checklist item indices
+        text: Option<String>,            // or items whose text contains this
+        checked: Option<bool>,           // defaults to true
+    },
+    AddReaction {
+        repository: Option<String>,
+        number: Option<u64>,             // react to an issue or PR
+        comment_id: Option<u64>,         // or to a comment
+        review_comment: Option<bool>,    // comment_id is a PR review comment
+        content: Option<String>,         // defaults to +1
+    },
+    GetReactions {
+        repository: Option<String>,
+        number: Option<u64>,
+        comment_id: Option<u64>,
+        review_comment: Option<bool>,
+        own_comments: Option<bool>,      // summarise reactions on your comments on `number`
+    },
+    CreateIssue {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        title: String,
+        body: Option<String>,
+        labels: Option<Vec<String>>,
+        assignees: Option<Vec<String>>,
+    },
+    UpdateIssue {
+        repository: Option<String>,
+        number: u64,
+        title: Option<String>,
+        body: Option<String>,
+        state: Option<String>,           // open or closed
+        labels: Option<Vec<String>>,     // replaces all labels
+        assignees: Option<Vec<String>>,  // replaces all assignees
+    },
+    CommentIssue {
+        repository: Option<String>,
+        number: u64,
+        body: String,
+    },
+    CloseIssue {
+        repository: Option<String>,
+        number: u64,
+        reason: Option<String>,          // completed (default) or not_planned
+        comment: Option<String>,         // posted before closing
+        lock: Option<bool>,
+        lock_reason: Option<String>,
+    },
+    BulkUpdateIssues {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        filter: Option<IssueFilter>,
+        numbers: Option<Vec<u64>>,       // restrict to these issues, e.g. those shown in a preview
+        add_labels: Option<Vec<String>>,
+        remove_labels: Option<Vec<String>>,
+        milestone: Option<String>,       // number or title; "none" clears it
+        close: Option<bool>,
+        close_reason: Option<String>,    // completed (default) or not_planned
+        comment: Option<String>,
+        confirm: Option<bool>,           // false/absent: preview only
+        limit: Option<usize>,
+    },
+    ListLabels {
+        repository: Option<String>,      // owner/name, defaults to current repository
+    },
+    CreateLabel {
+        repository: Option<String>,
+        name: String,
+        color: String,                   // six hex digits
+        description: Option<String>,
+    },
+    UpdateLabel {
+        repository: Option<String>,
+        name: String,                    // current name
+        new_name: Option<String>,
+        color: Option<String>,
+        description: Option<String>,
+    },
+    DeleteLabel {
+        repository: Option<String>,
+        name: String,
+    },
+    SyncLabels {
+        repositories: Option<Vec<String>>, // owner/name each; defaults to the current repository
+        labels: Option<Vec<LabelSpec>>,  // the set itself, instead of reading it
+        file: Option<String>,            // YAML set in the repository; defaults to the configured file or .github/labels.yml
+        source: Option<String>,          // owner/name to read `file` from; defaults to the local checkout
+        prune: Option<bool>,             // delete labels not in the set
+        confirm: Option<bool>,           // false/absent: preview only
+    },
+    ListMilestones {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        state: Option<String>,           // open (default), closed or all
+    },
+    CreateMilestone {
+        repository: Option<String>,
+        title: String,
+        description: Option<String>,
+        due_on: Option<String>,          // YYYY-MM-DD or an ISO 8601 timestamp
+    },
+    CloseMilestone {
+        repository: Option<String>,
+        milestone: String,               // number or title
+    },
+    AssignMilestone {
+        repository: Option<String>,
+        milestone: String,               // number or title; "none" removes issues from their milestone
+        numbers: Vec<u64>,               // issues or PRs
+    },
+    MilestoneProgress {
+        repository: Option<String>,
+        milestone: String,               // number or title
+    },
+    ListWorkflowRuns {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        branch: Option<String>,          // defaults to the current branch
+        pull_request: Option<u64>,       // runs for the PR's head commit instead
+        status: Option<String>,          // e.g. failure, in_progress, completed
+        limit: Option<u32>,
+    },
+    GetWorkflowRun {
+        repository: Option<String>,
+        run_id: u64,
+        logs: Option<bool>,              // include failing step logs (default true)
+    },
+    RerunWorkflowRun {
+        repository: Option<String>,
+        run_id: u64,
+        failed_only: Option<bool>,       // default true
+    },
+    CancelWorkflowRun {
+        repository: Option<String>,
+        run_id: u64,
+    },
+    Promote {
+        tag: String,
+        environment: Option<String>,     // defaults to the environment after the tag's last promotion
+        wait: Option<bool>,              // wait for the deployment run to finish
+        repo: Option<String>,            // registered repository name
+    },
+    ManageDependabot {
+        confirm: Option<bool>,           // approve and auto-merge; otherwise only report the plan
+        repo: Option<String>,            // registered repository name
+    },
+    CreateRelease {
+        tag: String,                     // new tag, created on the default branch's HEAD
+        name: Option<String>,            // defaults to the tag
+        notes: Option<String>,           // intro placed above the generated notes
+        draft: Option<bool>,             // default true
+        prerelease: Option<bool>,
+        assets: Option<Vec<String>>,     // files to upload, relative to the repository root
+        repo: Option<String>,            // registered repository name
+    },
+    ListReleases {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        limit: Option<u32>,
+    },
+    DraftAdvisory {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        ghsa_id: Option<String>,         // update this advisory instead of drafting a new one
+        summary: Option<String>,
+        description: Option<String>,
+        severity: Option<String>,        // critical, high, medium or low
+        cwe_ids: Option<Vec<String>>,
+        vulnerabilities: Option<Vec<AdvisoryVulnerability>>,
+        credits: Option<Vec<AdvisoryCredit>>, // added to any existing credits
+        request_cve: Option<bool>,
+    },
+    Changelog {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        from: Option<String>,            // tag or ref; defaults to the latest release's tag
+        to: Option<String>,              // tag or ref; defaults to the default branch
+        heading: Option<String>,
+    },
+    ReadFile {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        path: String,
+        #[serde(rename = "ref")]
+        git_ref: Option<String>,         // branch, tag or SHA; defaults to the default branch
+    },
+    WriteFile {
+        repository: Option<String>,
+        path: String,
+        content: String,
+        message: String,
+        branch: Option<String>,          // defaults to the default branch
+        sha: Option<String>,             // blob SHA the edit is based on; rejects the write if the file moved on
+    },
+    DeleteFile {
+        repository: Option<String>,
+        path: String,
+        message: String,
+        branch: Option<String>,
+        sha: Option<String>,
+    },
+    Wiki {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        action: Option<String>,          // list, read, write or delete; read with a page, list without
+        page: Option<String>,            // page title, e.g. "Deploy Runbook"
+        content: Option<String>,         // full new page content for write
+        message: Option<String>,         // commit message for write and delete
+    },
+    GetBranchProtection {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        branch: Option<String>,          // defaults to the default branch
+    },
+    UpdateBranchProtection {
+        repository: Option<String>,
+        branch: Option<String>,
+        settings: ProtectionUpdate,      // rules to change; the rest are kept
+    },
+    SearchCode {
+        query: String,                   // GitHub code search syntax
+        repository: Option<String>,      // owner/name to scope the search to
+        page: Option<u32>,
+        per_page: Option<u32>,           // default 10, max 50
+    },
+    SearchIssues {
+        query: String,                   // GitHub issue search syntax, e.g. "is:pr is:open label:bug"
+        repository: Option<String>,
+        sort: Option<String>,            // comments, reactions, created or updated
+        order: Option<String>,           // asc or desc
+        page: Option<u32>,
+        per_page: Option<u32>,
+    },
+    SearchRepos {
+        query: String,                   // GitHub repository search syntax
+        sort: Option<String>,            // stars, forks, help-wanted-issues or updated
+        order: Option<String>,
+        page: Option<u32>,
+        per_page: Option<u32>,
+    },
+    Graphql {
+        query: String,                   // GraphQL document
+        variables: Option<Value>,
+    },
+    RestGet {
+        path: String,                    // API path, e.g. /repos/owner/name/pulls
+        params: Option<HashMap<String, String>>,
+        max_pages: Option<u32>,          // follow Link rel="next" up to this many pages
+    },
+    GetPullRequestDiff {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        number: u64,
+        per_file: Option<bool>,          // split into per-file patches
+        files: Option<Vec<String>>,      // only these paths (implies per_file)
+        max_bytes: Option<usize>,        // truncate beyond this size
+    },
+    GetTimeline {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        number: u64,                     // issue or pull request
+        events: Option<Vec<String>>,     // only these event types, e.g. ["assigned", "cross-referenced"]
+    },
+    SubmitReview {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        number: u64,
+        event: String,                   // APPROVE, REQUEST_CHANGES or COMMENT
+        body: Option<String>,
+        comments: Option<Vec<ReviewCommentInput>>,
+    },
+    AddReviewComment {
+        repository: Option<String>,
+        number: u64,
+        comment: ReviewCommentInput,
+    },
+    ListReviews {
+        repository: Option<String>,
+        number: u64,
+    },
+    ListCommits {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        branch: Option<String>,          // branch or SHA to list from (defaults to the default branch)
+        pull_request: Option<u64>,       // list the PR's commits instead
+        limit: Option<u32>,
+    },
+    GetRepoMetadata {
+        repository: Option<String>,      // owner/name, defaults to current repository
+    },
+    UpdateRepoMetadata {
+        repository: Option<String>,
+        description: Option<String>,
+        homepage: Option<String>,
+        topics: Option<Vec<String>>,     // replaces all topics
+        add_topics: Option<Vec<String>>,
+        remove_topics: Option<Vec<String>>,
+    },
+    CreateRepository {
+        name: String,
+        owner: Option<String>,           // organization; defaults to the authenticated user
+        description: Option<String>,
+        private: Option<bool>,           // default true
+        default_branch: Option<String>,  // default main
+        merge_methods: Option<Vec<String>>, // merge, squash and/or rebase; default squash
+        delete_branch_on_merge: Option<bool>, // default true
+        vulnerability_alerts: Option<bool>,   // default true
+        template: Option<String>,        // directory under REPO_TEMPLATES_DIR for the initial commit
+        labels: Option<bool>,            // apply the standard label set
+        project: Option<bool>,           // create a project board linked to the repository
+    },
+    ForkRepository {
+        repository: Option<String>,      // owner/name to fork, defaults to current repository
+        organization: Option<String>,    // defaults to the authenticated user
+        name: Option<String>,            // defaults to the repository's name
+        default_branch_only: Option<bool>, // default true
+        remotes: Option<bool>,           // point origin at the fork and upstream at the original
+        repo: Option<String>,
     },
+    AddUpstreamRemote {
+        upstream: Option<String>,        // owner/name, defaults to the fork's parent
+        remote: Option<String>,          // default upstream
+        repo: Option<String>,
+    },
+    SyncFork {
+        repository: Option<String>,      // the fork, defaults to current repository
+        branch: Option<String>,          // defaults to the fork's default branch
+        method: Option<String>,          // api (default) or local
+        strategy: Option<String>,        // local only: rebase or merge
+        repo: Option<String>,
+    },
+    ListDiscussions {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        category: Option<String>,        // name or slug
+        state: Option<String>,           // open (default), closed or all
+        answered: Option<bool>,
+        limit: Option<usize>,
+    },
+    GetDiscussion {
+        repository: Option<String>,
+        number: u64,
+    },
+    CreateDiscussion {
+        repository: Option<String>,
+        category: String,
+        title: String,
+        body: String,
+    },
+    ReplyDiscussion {
+        repository: Option<String>,
+        number: u64,
+        body: String,
+        reply_to: Option<String>,        // ID of a top-level comment to reply under
+    },
+    AnswerDiscussion {
+        repository: Option<String>,
+        comment_id: String,
+        unmark: Option<bool>,
+    },
+    ListSecurityAlerts {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        kinds: Option<Vec<String>>,      // dependabot, code_scanning, secret_scanning; defaults to all
+        severity: Option<String>,        // minimum: low, medium, high or critical
+        state: Option<String>,           // open (default), closed or all
+        limit: Option<usize>,            // per kind
+    },
+    DismissSecurityAlert {
+        repository: Option<String>,
+        kind: String,
+        number: u64,
+        reason: String,                  // one of the kind's dismissal reasons
+        comment: Option<String>,
+    },
+    CreateGist {
+        description: Option<String>,
+        public: Option<bool>,            // default false: secret, visible only with the link
+        files: Vec<GistFile>,
+    },
+    ListGists {
+        limit: Option<usize>,
+    },
+    ListNotifications {
+        repository: Option<String>,      // owner/name; defaults to the whole inbox
+        reasons: Option<Vec<String>>,    // defaults to review requests, mentions and CI; [] for every reason
+        all: Option<bool>,               // include read notifications
+        participating: Option<bool>,     // only threads the user takes part in
+        since: Option<String>,           // ISO 8601 timestamp
+        limit: Option<usize>,
+    },
+    MarkNotificationsRead {
+        thread_ids: Option<Vec<String>>,
+        all: Option<bool>,               // everything in the inbox, or in `repository`
+        repository: Option<String>,
+        before: Option<String>,          // with all: only notifications updated before this; defaults to now
+        done: Option<bool>,              // with thread_ids: also remove them from the inbox
+    },
+    CheckRepoPolicy {
+        org: Option<String>,             // check every non-archived repo in the org
+        repository: Option<String>,      // or a single owner/name (defaults to current repository)
+    },
+    CleanupWorkFolder {
+        branch: Option<String>,          // defaults to current branch
+        dry_run: Option<bool>,           // defaults to true
+    },
+    CompleteTask {
+        issue_number: Option<u64>,       // defaults to the task linked to the branch
+        branch: Option<String>,
+        pull_request: Option<u64>,
+    },
+    SuggestCommitMessage {
+        staged: Option<bool>,            // defaults to staged changes if any, else the working tree
+        message: Option<String>,         // commit with this message instead of suggesting
+        max_diff_bytes: Option<usize>,
+        repo: Option<String>,
+    },
+    RequestReviewers {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        pull_request: Option<u64>,       // defaults to the current branch's PR
+        reviewers: Option<Vec<String>>,  // requested as well as the code owners; `org/team` for a team
+        dry_run: Option<bool>,           // only suggest
+    },
+    Triage {
+        repository: Option<String>,      // owner/name, defaults to current repository
+        project_number: Option<String>,  // board issues should be on; defaults to the detected project
+        owner: Option<String>,           // project owner
+        decisions: Option<Vec<TriageDecision>>, // absent: list untriaged issues
+        limit: Option<usize>,
+    },
+    ProjectUpdateItem {
+        project_number: Option<String>,  // defaults to the detected project
+        owner: Option<String>,           // project owner
+        repository: Option<String>,      // owner/name of `number`, defaults to current repository
+        number: Option<u64>,             // issue or PR; added to the project when it isn't there
+        item_id: Option<String>,         // project item, instead of `number`
+        fields: Option<serde_json::Map<String, Value>>, // field name to value; null clears
+        archive: Option<bool>,           // true archives, false restores
+    },
+    GetProjectBoard {
+        project_number: String,
+        owner: Option<String>,           // project owner, defaults to the configured one
+    },
+    SyncBranch {
+        action: Option<String>,          // sync (default), continue or abort
+        strategy: Option<String>,        // rebase or merge; defaults to the repo's configured strategy
+        base: Option<String>,            // defaults to the main branch
+        repo: Option<String>,
+    },
+}
+
+impl GitHubCommand {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GitHubCommand::Push { .. } => "push",
+            GitHubCommand::ScanTasks { .. } => "scan_tasks",
+            GitHubCommand::Merge { .. } => "merge",
+            GitHubCommand::StartTask { .. } => "start_task",
+            GitHubCommand::PromoteDraft { .. } => "promote_draft",
+            GitHubCommand::GetChecklist { .. } => "get_checklist",
+            GitHubCommand::UpdateChecklist { .. } => "update_checklist",
+            GitHubCommand::AddReaction { .. } => "add_reaction",
+            GitHubCommand::GetReactions { .. } => "get_reactions",
+            GitHubCommand::CreateIssue { .. } => "create_issue",
+            GitHubCommand::UpdateIssue { .. } => "update_issue",
+            GitHubCommand::CommentIssue { .. } => "comment_issue",
+            GitHubCommand::CloseIssue { .. } => "close_issue",
+            GitHubCommand::BulkUpdateIssues { .. } => "bulk_update_issues",
+            GitHubCommand::ListLabels { .. } => "list_labels",
+            GitHubCommand::CreateLabel { .. } => "create_label",
+            GitHubCommand::UpdateLabel { .. } => "update_label",
+            GitHubCommand::DeleteLabel { .. } => "delete_label",
+            GitHubCommand::SyncLabels { .. } => "sync_labels",
+            GitHubCommand::ListMilestones { .. } => "list_milestones",
+            GitHubCommand::CreateMilestone { .. } => "create_milestone",
+            GitHubCommand::CloseMilestone { .. } => "close_milestone",
+            GitHubCommand::AssignMilestone { .. } => "assign_milestone",
+            GitHubCommand::MilestoneProgress { .. } => "milestone_progress",
+            GitHubCommand::ListWorkflowRuns { .. } => "list_workflow_runs",
+            GitHubCommand::GetWorkflowRun { .. } => "get_workflow_run",
+            GitHubCommand::RerunWorkflowRun { .. } => "rerun_workflow_run",
+            GitHubCommand::CancelWorkflowRun { .. } => "cancel_workflow_run",
+            GitHubCommand::Promote { .. } => "promote",
+            GitHubCommand::ManageDependabot { .. } => "manage_dependabot",
+            GitHubCommand::CreateRelease { .. } => "create_release",
+            GitHubCommand::ListReleases { .. } => "list_releases",
+            GitHubCommand::DraftAdvisory { .. } => "draft_advisory",
+            GitHubCommand::Changelog { .. } => "changelog",
+            GitHubCommand::ReadFile { .. } => "read_file",
+            GitHubCommand::WriteFile { .. } => "write_file",
+            GitHubCommand::DeleteFile { .. } => "delete_file",
+            GitHubCommand::Wiki { .. } => "wiki",
+            GitHubCommand::GetBranchProtection { .. } => "get_branch_protection",
+            GitHubCommand::UpdateBranchProtection { .. } => "update_branch_protection",
+            GitHubCommand::SearchCode { .. } => "search_code",
+            GitHubCommand::SearchIssues { .. } => "search_issues",
+            GitHubCommand::SearchRepos { .. } => "search_repos",
+            GitHubCommand::Graphql { .. } => "graphql",
+            GitHubCommand::RestGet { .. } => "rest_get",
+            GitHubCommand::GetPullRequestDiff { .. } => "get_pull_request_diff",
+            GitHubCommand::GetTimeline { .. } => "get_timeline",
+            GitHubCommand::SubmitReview { .. } => "submit_review",
+            GitHubCommand::AddReviewComment { .. } => "add_review_comment",
+            GitHubCommand::ListReviews { .. } => "list_reviews",
+            GitHubCommand::ListCommits { .. } => "list_commits",
+            GitHubCommand::GetRepoMetadata { .. } => "get_repo_metadata",
+            GitHubCommand::UpdateRepoMetadata { .. } => "update_repo_metadata",
+            GitHubCommand::CreateRepository { .. } => "create_repository",
+            GitHubCommand::ForkRepository { .. } => "fork_repository",
+            GitHubCommand::AddUpstreamRemote { .. } => "add_upstream_remote",
+            GitHubCommand::SyncFork { .. } => "sync_fork",
+            GitHubCommand::ListDiscussions { .. } => "list_discussions",
+            GitHubCommand::GetDiscussion { .. } => "get_discussion",
+            GitHubCommand::CreateDiscussion { .. } => "create_discussion",
+            GitHubCommand::ReplyDiscussion { .. } => "reply_discussion",
+            GitHubCommand::AnswerDiscussion { .. } => "answer_discussion",
+            GitHubCommand::ListSecurityAlerts { .. } => "list_security_alerts",
+            GitHubCommand::DismissSecurityAlert { .. } => "dismiss_security_alert",
+            GitHubCommand::CreateGist { .. } => "create_gist",
+            GitHubCommand::ListGists { .. } => "list_gists",
+            GitHubCommand::ListNotifications { .. } => "list_notifications",
+            GitHubCommand::MarkNotificationsRead { .. } => "mark_notifications_read",
+            GitHubCommand::CheckRepoPolicy { .. } => "check_repo_policy",
+            GitHubCommand::CleanupWorkFolder { .. } => "cleanup_work_folder",
+            GitHubCommand::CompleteTask { .. } => "complete_task",
+            GitHubCommand::SuggestCommitMessage { .. } => "suggest_commit_message",
+            GitHubCommand::RequestReviewers { .. } => "request_reviewers",
+            GitHubCommand::Triage { .. } => "triage",
+            GitHubCommand::ProjectUpdateItem { .. } => "project_update_item",
+            GitHubCommand::GetProjectBoard { .. } => "get_project_board",
+            GitHubCommand::SyncBranch { .. } => "sync_branch",
+        }
+    }
+
+    /// Registered repository the command targets, if any
+    pub fn repo(&self) -> Option<&str> {
+        match self {
+            GitHubCommand::Push { repo, .. }
+            | GitHubCommand::ScanTasks { repo, .. }
+            | GitHubCommand::Merge { repo, .. }
+            | GitHubCommand::Promote { repo, .. }
+            | GitHubCommand::ManageDependabot { repo, .. }
+            | GitHubCommand::CreateRelease { repo, .. }
+            | GitHubCommand::SuggestCommitMessage { repo, .. }
+            | GitHubCommand::ForkRepository { repo, .. }
+            | GitHubCommand::AddUpstreamRemote { repo, .. }
+            | GitHubCommand::SyncFork { repo, .. }
+            | GitHubCommand::SyncBranch { repo, .. } => repo.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// What the command does to its repository, for the permission policy
+    pub fn operation(&self) -> Operation {
+        match self {
+            _ if !self.is_mutating() => Operation::Read,
+            // Forking only reads the source; the fork is checked once its owner is known
+            GitHubCommand::ForkRepository { .. } => Operation::Read,
+            GitHubCommand::Merge { .. }
+            | GitHubCommand::ManageDependabot { .. }
+            | GitHubCommand::Promote { .. }
+            | GitHubCommand::CreateRelease { .. } => Operation::Merge,
+            GitHubCommand::UpdateBranchProtection { .. }
+            | GitHubCommand::UpdateRepoMetadata { .. }
+            | GitHubCommand::CreateRepository { .. }
+            | GitHubCommand::DraftAdvisory { .. }
+            | GitHubCommand::DismissSecurityAlert { .. }
+            | GitHubCommand::Graphql { .. } => Operation::Admin,
+            _ => Operation::Push,
+        }
+    }
+
+    /// Repository the command acts on, for the permission policy
+    pub fn target(&self) -> CommandTarget<'_> {
+        match self {
+            GitHubCommand::ScanTasks { .. }
+            | GitHubCommand::GetProjectBoard { .. }
+            | GitHubCommand::SearchRepos { .. }
+            | GitHubCommand::Graphql { .. }
+            | GitHubCommand::CreateGist { .. }
+            | GitHubCommand::ListGists { .. }
+            | GitHubCommand::CheckRepoPolicy { org: Some(_), .. } => CommandTarget::Unscoped,
+            // Without a repository these search everything the token can see
+            GitHubCommand::SearchCode { repository: None, .. }
+            | GitHubCommand::SearchIssues { repository: None, .. } => CommandTarget::Unscoped,
+            // Paths `rest::normalize_path` would change are refused before any request
+            GitHubCommand::RestGet { path, .. } => {
+                match path.trim().trim_start_matches('/').strip_prefix("repos/") {
+                    Some(rest) => {
+                        let end = rest.match_indices('/').nth(1).map_or(rest.len(), |(i, _)| i);
+                        CommandTarget::Repository(&rest[..end])
+                    }
+                    None => CommandTarget::Unscoped,
+                }
+            }
+            // The inbox spans every repository unless narrowed to one
+            GitHubCommand::ListNotifications { repository: Some(repository), .. }
+            | GitHubCommand::MarkNotificationsRead { repository: Some(repository), .. } => {
+                CommandTarget::Repository(repository)
+            }
+            GitHubCommand::ListNotifications { .. } | GitHubCommand::MarkNotificationsRead { .. } => {
+                CommandTarget::Unscoped
+            }
+            // The repository doesn't exist yet; it's checked once its owner is known
+            GitHubCommand::CreateRepository { .. } => CommandTarget::Unscoped,
+            // The item's repository is checked once the item is looked up
+            GitHubCommand::ProjectUpdateItem { item_id: Some(_), .. } => CommandTarget::Unscoped,
+            // Each repository of a multi-repository sync is checked as it's reached
+            GitHubCommand::SyncLabels { repositories: Some(repositories), .. } if !repositories.is_empty() => {
+                match repositories.as_slice() {
+                    [repository] => CommandTarget::Repository(repository),
+                    _ => CommandTarget::Unscoped,
+                }
+            }
+            GitHubCommand::PromoteDraft { repository, .. }
+            | GitHubCommand::GetChecklist { repository, .. }
+            | GitHubCommand::UpdateChecklist { repository, .. }
+            | GitHubCommand::AddReaction { repository, .. }
+            | GitHubCommand::GetReactions { repository, .. }
+            | GitHubCommand::CreateIssue { repository, .. }
+            | GitHubCommand::UpdateIssue { repository, .. }
+            | GitHubCommand::CommentIssue { repository, .. }
+            | GitHubCommand::CloseIssue { repository, .. }
+            | GitHubCommand::BulkUpdateIssues { repository, .. }
+            | GitHubCommand::ListLabels { repository, .. }
+            | GitHubCommand::CreateLabel { repository, .. }
+            | GitHubCommand::UpdateLabel { repository, .. }
+            | GitHubCommand::DeleteLabel { repository, .. }
+            | GitHubCommand::ListMilestones { repository, .. }
+            | GitHubCommand::CreateMilestone { repository, .. }
+            | GitHubCommand::CloseMilestone { repository, .. }
+            | GitHubCommand::AssignMilestone { repository, .. }
+            | GitHubCommand::MilestoneProgress { repository, .. }
+            | GitHubCommand::ListSecurityAlerts { repository, .. }
+            | GitHubCommand::DismissSecurityAlert { repository, .. }
+            | GitHubCommand::ListDiscussions { repository, .. }
+            | GitHubCommand::GetDiscussion { repository, .. }
+            | GitHubCommand::CreateDiscussion { repository, .. }
+            | GitHubCommand::ReplyDiscussion { repository, .. }
+            | GitHubCommand::AnswerDiscussion { repository, .. }
+            | GitHubCommand::ListWorkflowRuns { repository, .. }
+            | GitHubCommand::GetWorkflowRun { repository, .. }
+            | GitHubCommand::RerunWorkflowRun { repository, .. }
+            | GitHubCommand::CancelWorkflowRun { repository, .. }
+            | GitHubCommand::ListReleases { repository, .. }
+            | GitHubCommand::DraftAdvisory { repository, .. }
+            | GitHubCommand::Changelog { repository, .. }
+            | GitHubCommand::ReadFile { repository, .. }
+            | GitHubCommand::RequestReviewers { repository, .. }
+            | GitHubCommand::Triage { repository, .. }
+            | GitHubCommand::ProjectUpdateItem { repository, .. }
+            | GitHubCommand::WriteFile { repository, .. }
+            | GitHubCommand::Wiki { repository, .. }
+            | GitHubCommand::DeleteFile { repository, .. }
+            | GitHubCommand::GetBranchProtection { repository, .. }
+            | GitHubCommand::UpdateBranchProtection { repository, .. }
+            | GitHubCommand::SearchCode { repository, .. }
+            | GitHubCommand::SearchIssues { repository, .. }
+            | GitHubCommand::GetPullRequestDiff { repository, .. }
+            | GitHubCommand::GetTimeline { repository, .. }
+            | GitHubCommand::SubmitReview { repository, .. }
+            | GitHubCommand::AddReviewComment { repository, .. }
+            | GitHubCommand::ListReviews { repository, .. }
+            | GitHubCommand::ListCommits { repository, .. }
+            | GitHubCommand::GetRepoMetadata { repository, .. }
+            | GitHubCommand::UpdateRepoMetadata { repository, .. }
+            | GitHubCommand::ForkRepository { repository, .. }
+            | GitHubCommand::SyncFork { repository, .. }
+            | GitHubCommand::CheckRepoPolicy { repository, .. } => match repository {
+                Some(repository) => CommandTarget::Repository(repository),
+                None => CommandTarget::Checkout,
+            },
+            _ => CommandTarget::Checkout,
+        }
+    }
+
+    /// Whether the command changes state locally or on GitHub
+    pub fn is_mutating(&self) -> bool {
+        if let GitHubCommand::Graphql { query, .. } = self {
+            return crate::github::graphql::is_mutation(query);
+        }
+        if let GitHubCommand::Wiki { action, .. } = self {
+            return matches!(action.as_deref(), Some("write" | "delete"));
+        }
+        !matches!(
+            self,
+            GitHubCommand::ScanTasks { .. }
+                | GitHubCommand::GetProjectBoard { .. }
+                | GitHubCommand::GetChecklist { .. }
+                | GitHubCommand::GetReactions { .. }
+                | GitHubCommand::ListReviews { .. }
+                | GitHubCommand::GetPullRequestDiff { .. }
+                | GitHubCommand::GetTimeline { .. }
+                | GitHubCommand::ListWorkflowRuns { .. }
+                | GitHubCommand::GetWorkflowRun { .. }
+                | GitHubCommand::BulkUpdateIssues { confirm: None | Some(false), .. }
+                | GitHubCommand::ListLabels { .. }
+                | GitHubCommand::SyncLabels { confirm: None | Some(false), .. }
+                | GitHubCommand::ListMilestones { .. }
+                | GitHubCommand::MilestoneProgress { .. }
+                | GitHubCommand::ListDiscussions { .. }
+                | GitHubCommand::GetDiscussion { .. }
+                | GitHubCommand::ListSecurityAlerts { .. }
+                | GitHubCommand::ListGists { .. }
+                | GitHubCommand::ListNotifications { .. }
+                | GitHubCommand::ManageDependabot { confirm: None | Some(false), .. }
+                | GitHubCommand::ListCommits { .. }
+                | GitHubCommand::ListReleases { .. }
+                | GitHubCommand::Changelog { .. }
+                | GitHubCommand::ReadFile { .. }
+                | GitHubCommand::GetBranchProtection { .. }
+                | GitHubCommand::SearchCode { .. }
+                | GitHubCommand::SearchIssues { .. }
+                | GitHubCommand::SearchRepos { .. }
+                | GitHubCommand::RestGet { .. }
+                | GitHubCommand::GetRepoMetadata { .. }
+                | GitHubCommand::CheckRepoPolicy { .. }
+                | GitHubCommand::SuggestCommitMessage { message: None, .. }
+                | GitHubCommand::RequestReviewers { dry_run: Some(true), .. }
+                | GitHubCommand::Triage { decisions: None, .. }
+        )
+    }
+}
+
+/// Where a command acts, as far as the permission policy is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandTarget<'a> {
+    /// An `owner/name` given in the arguments
+    Repository(&'a str),
+    /// The current (or registered) checkout's origin
+    Checkout,
+    /// Across repositories, e.g. a global search; governed by `*` rules
+    Unscoped,
 }
 
 impl McpResponse {
@@ -84,6 +846,12 @@ impl McpResponse {
         }
     }
 
+    /// An error response with the code and details `error_codes::for_error` gives
+    pub fn from_error(id: Option<Value>, error: &crate::error::AppError) -> Self {
+        let (code, data) = error_codes::for_error(error);
+        Self::error(id, code, error.to_string(), data)
+    }
+
     pub fn error(id: Option<Value>, code: i32, message: String, data: Option<Value>) -> Self {
         Self {
             jsonrpc: "2.0".to_string(),
@@ -107,6 +875,38 @@ pub mod error_codes {
     pub const AUTHENTICATION_ERROR: i32 = -32001;
     pub const RATE_LIMIT_ERROR: i32 = -32002;
     pub const WORKFLOW_ERROR: i32 = -32003;
+    pub const READ_ONLY_ERROR: i32 = -32004;
+    pub const APPROVAL_REQUIRED: i32 = -32005;
+    pub const NOT_FOUND: i32 = -32006;
+    pub const FORBIDDEN: i32 = -32007;
+    pub const VALIDATION_FAILED: i32 = -32008;
+
+    /// The code an error is reported with, and any details for the error's `data`
+    pub fn for_error(error: &crate::error::AppError) -> (i32, Option<serde_json::Value>) {
+        use crate::error::AppError;
+        use crate::github::error::GitHubError;
+
+        match error {
+            AppError::GitHub(e) => {
+                let code = match e {
+                    GitHubError::NotFound { .. } => NOT_FOUND,
+                    GitHubError::RateLimited { .. } => RATE_LIMIT_ERROR,
+                    GitHubError::Forbidden { .. } => FORBIDDEN,
+                    GitHubError::ValidationFailed { .. } => VALIDATION_FAILED,
+                    GitHubError::Failed { .. } => GITHUB_API_ERROR,
+                };
+                (code, Some(e.data()))
+            }
+            AppError::GitHubApi(_) | AppError::HttpClient(_) => (GITHUB_API_ERROR, None),
+            AppError::Authentication(_) | AppError::OAuth2(_) | AppError::Jwt(_) => (AUTHENTICATION_ERROR, None),
+            AppError::Authorization(_) => (FORBIDDEN, None),
+            AppError::RateLimit => (RATE_LIMIT_ERROR, None),
+            AppError::ReadOnly(_) => (READ_ONLY_ERROR, None),
+            AppError::ApprovalRequired(_) => (APPROVAL_REQUIRED, None),
+            AppError::Validation(_) => (INVALID_PARAMS, None),
+            _ => (INTERNAL_ERROR, None),
+        }
+    }
 }
 
 /// MCP method names
@@ -116,7 +916,18 @@ pub mod methods {
     pub const TOOLS_CALL: &str = "tools/call";
     pub const RESOURCES_LIST: &str = "resources/list";
     pub const RESOURCES_READ: &str = "resources/read";
+    pub const RESOURCES_TEMPLATES_LIST: &str = "resources/templates/list";
     pub const NOTIFICATIONS_INITIALIZED: &str = "notifications/initialized";
+    pub const NOTIFICATIONS_PROGRESS: &str = "notifications/progress";
+    pub const NOTIFICATIONS_CANCELLED: &str = "notifications/cancelled";
+
+    /// First message on a WebSocket opened without a session token
+    pub const AUTH: &str = "auth";
+    
+    // Webhook-driven notifications pushed to WebSocket clients
+    pub const NOTIFICATIONS_REVIEW_SUBMITTED: &str = "notifications/github/review_submitted";
+    pub const NOTIFICATIONS_CI_FINISHED: &str = "notifications/github/ci_finished";
+    pub const NOTIFICATIONS_ISSUE_ASSIGNED: &str = "notifications/github/issue_assigned";
     
     // Custom GitHub workflow methods
     pub const GITHUB_PUSH: &str = "github/push";
@@ -124,6 +935,18 @@ pub mod methods {
     pub const GITHUB_MERGE: &str = "github/merge";
 }
 
+/// A parameterised resource, e.g. `github://pr/{number}/diff`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpResourceTemplate {
+    #[serde(rename = "uriTemplate")]
+    pub uri_template: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
 /// Server capabilities
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerCapabilities {
@@ -0,0 +1,45 @@
+// Tool arguments are checked against the tool's declared input schema before
+// anything runs, so an agent gets back the offending field rather than a
+// deserialization failure from deep inside a workflow.
+
+use jsonschema::Validator;
+use serde::Serialize;
+use serde_json::Value;
+
+use tracing::error;
+
+/// One way the arguments don't match the schema
+#[derive(Debug, Serialize)]
+pub struct ArgumentError {
+    /// JSON pointer into the arguments, e.g. `/labels/0`; empty for the whole object
+    pub pointer: String,
+    pub message: String,
+}
+
+/// Validator for `tool`'s input schema; None when the schema itself is invalid
+pub fn compile(tool: &str, schema: &Value) -> Option<Validator> {
+    match jsonschema::validator_for(schema) {
+        Ok(validator) => Some(validator),
+        Err(e) => {
+            // A bug in the tool's definition; its calls go unchecked rather than failing
+            error!("Invalid input schema for {}: {}", tool, e);
+            None
+        }
+    }
+}
+
+pub fn validate(validator: &Validator, arguments: &Value) -> Result<(), Vec<ArgumentError>> {
+    let errors: Vec<ArgumentError> = validator
+        .iter_errors(arguments)
+        .map(|e| ArgumentError {
+            pointer: e.instance_path.to_string(),
+            message: e.to_string(),
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
@@ -0,0 +1,54 @@
+// `github://server/info`: what this instance is and what it has turned on,
+// so fleet operators and clients can spot version drift and check for a
+// feature before relying on it. Read it as a heartbeat; it makes no GitHub calls.
+
+use serde_json::{json, Value};
+
+use crate::AppState;
+use super::protocol::{MCP_VERSION, SUPPORTED_PROTOCOL_VERSIONS};
+
+pub const URI: &str = "github://server/info";
+
+/// Features this instance has enabled, by build or configuration
+fn features(state: &AppState) -> Vec<&'static str> {
+    let config = &state.config;
+    [
+        (cfg!(feature = "libgit2"), "libgit2"),
+        (config.security.read_only, "read_only"),
+        (config.security.repository_approval, "repository_approval"),
+        (config.security.audit_log_enabled, "audit_log"),
+        (config.github.graphql_mutations, "graphql_mutations"),
+        (config.github.cache_entries > 0, "response_cache"),
+        (config.github.webhook_secret.is_some(), "webhooks"),
+        (config.notifications.webhook_url.is_some(), "notification_webhook"),
+        (!config.federation.is_empty(), "federation"),
+        (true, "compact_results"),
+        (true, "batch_resource_reads"),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, feature)| enabled.then_some(feature))
+    .collect()
+}
+
+pub fn info(state: &AppState) -> Value {
+    let config = &state.config;
+    let now = chrono::Utc::now();
+
+    json!({
+        "name": env!("CARGO_PKG_NAME"),
+        "version": env!("CARGO_PKG_VERSION"),
+        "protocol_version": MCP_VERSION,
+        "supported_protocol_versions": SUPPORTED_PROTOCOL_VERSIONS,
+        "features": features(state),
+        "tools": state.tools.definitions().len(),
+        "git_backend": config.workflow.git_backend,
+        "github_host": config.github.host.web_url,
+        "profiles": {
+            "repositories": config.repositories.iter().map(|r| &r.name).collect::<Vec<_>>(),
+            "federation_peers": config.federation.iter().map(|p| &p.name).collect::<Vec<_>>()
+        },
+        "started_at": state.started_at.to_rfc3339(),
+        "uptime_seconds": (now - state.started_at).num_seconds(),
+        "timestamp": now.to_rfc3339()
+    })
+}
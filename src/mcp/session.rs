@@ -0,0 +1,231 @@
+// MCP session lifecycle.
+//
+// A client must `initialize`, receive the response, then send
+// `notifications/initialized` before anything else is served. A WebSocket
+// connection is one session; over `POST /mcp` the `initialize` response
+// carries an `Mcp-Session-Id` header that later requests send back. With
+// `SESSION_BACKEND=redis` those sessions are kept in Redis, so any replica can
+// serve the next request and they survive restarts.
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+
+use crate::store::RedisStore;
+use super::protocol::{methods, McpRequest};
+
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// HTTP sessions unused for this long are forgotten
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Phase {
+    #[default]
+    AwaitingInitialize,
+    /// `initialize` answered, waiting for `notifications/initialized`
+    Initializing,
+    Ready,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SessionState {
+    phase: Phase,
+    /// Tool results are compacted, see `compact`
+    compact: bool,
+    /// `clientInfo.name` and the protocol version agreed at `initialize`
+    client: Option<String>,
+    protocol_version: Option<String>,
+}
+
+/// An HTTP session as listed for admins
+#[derive(Debug, Serialize)]
+pub struct SessionInfo {
+    /// Enough of the `Mcp-Session-Id` to tell sessions apart, but not to use one
+    pub id_prefix: String,
+    phase: Phase,
+    pub client: Option<String>,
+    pub protocol_version: Option<String>,
+    pub compact: bool,
+    pub idle_seconds: u64,
+}
+
+#[derive(Clone, Default)]
+pub struct Session(Arc<Mutex<SessionState>>);
+
+impl Session {
+    /// Check `request` is well-formed and allowed in the session's current
+    /// phase, moving to the next phase on `initialize` and
+    /// `notifications/initialized`. Err is the message for INVALID_REQUEST.
+    pub fn admit(&self, request: &McpRequest) -> std::result::Result<(), String> {
+        if request.jsonrpc != "2.0" {
+            return Err(format!("Unsupported jsonrpc version: {}", request.jsonrpc));
+        }
+        if !matches!(request.id, None | Some(Value::String(_)) | Some(Value::Number(_))) {
+            return Err("Request id must be a string or a number".to_string());
+        }
+
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let phase = &mut state.phase;
+        match (request.method.as_str(), *phase) {
+            (methods::INITIALIZE, Phase::AwaitingInitialize) => {
+                if request.id.is_none() {
+                    return Err("initialize must be a request, not a notification".to_string());
+                }
+                *phase = Phase::Initializing;
+            }
+            (methods::INITIALIZE, _) => return Err("Session is already initialized".to_string()),
+            (methods::NOTIFICATIONS_INITIALIZED, Phase::Initializing) => *phase = Phase::Ready,
+            (methods::NOTIFICATIONS_INITIALIZED, _) => {
+                return Err("notifications/initialized must follow the initialize response".to_string())
+            }
+            (_, Phase::Ready) => {}
+            (method, _) => {
+                return Err(format!("{} is not allowed before initialization completes", method))
+            }
+        }
+        Ok(())
+    }
+
+    /// Allow `initialize` again after a malformed one
+    pub fn reset(&self) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).phase = Phase::AwaitingInitialize;
+    }
+
+    pub fn set_compact(&self, compact: bool) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).compact = compact;
+    }
+
+    pub fn compact(&self) -> bool {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).compact
+    }
+
+    pub fn set_client(&self, client: &str, protocol_version: &str) {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        state.client = Some(client.to_string());
+        state.protocol_version = Some(protocol_version.to_string());
+    }
+}
+
+/// A session as kept in Redis
+#[derive(Serialize, Deserialize)]
+struct StoredSession {
+    state: SessionState,
+    last_used: i64,
+}
+
+fn session_key(id: &str) -> String {
+    format!("session:{}", id)
+}
+
+/// Sessions started over `POST /mcp`, by `Mcp-Session-Id`
+#[derive(Clone, Default)]
+pub struct Sessions {
+    local: Arc<Mutex<HashMap<String, (Session, Instant)>>>,
+    shared: Option<RedisStore>,
+}
+
+impl Sessions {
+    pub fn new(shared: Option<RedisStore>) -> Self {
+        Self { local: Default::default(), shared }
+    }
+
+    pub async fn create(&self) -> Result<(String, Session)> {
+        let mut id = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut id);
+        let id = hex::encode(id);
+        let session = Session::default();
+
+        if self.shared.is_some() {
+            self.save(&id, &session).await?;
+            return Ok((id, session));
+        }
+
+        let mut sessions = self.local.lock().unwrap_or_else(|e| e.into_inner());
+        sessions.retain(|_, (_, last_used)| last_used.elapsed() < SESSION_IDLE_TIMEOUT);
+        sessions.insert(id.clone(), (session.clone(), Instant::now()));
+        Ok((id, session))
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<Session>> {
+        if let Some(shared) = &self.shared {
+            let stored = shared.get_json::<StoredSession>(&session_key(id)).await?;
+            return Ok(stored.map(|stored| Session(Arc::new(Mutex::new(stored.state)))));
+        }
+
+        let mut sessions = self.local.lock().unwrap_or_else(|e| e.into_inner());
+        let Some((session, last_used)) = sessions.get_mut(id) else {
+            return Ok(None);
+        };
+        if last_used.elapsed() >= SESSION_IDLE_TIMEOUT {
+            sessions.remove(id);
+            return Ok(None);
+        }
+        *last_used = Instant::now();
+        Ok(Some(session.clone()))
+    }
+
+    /// Write back what a request changed and restart the idle timeout;
+    /// in-memory sessions are shared by reference and need nothing
+    pub async fn save(&self, id: &str, session: &Session) -> Result<()> {
+        let Some(shared) = &self.shared else {
+            return Ok(());
+        };
+        let stored = StoredSession {
+            state: session.0.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+            last_used: chrono::Utc::now().timestamp(),
+        };
+        shared.set_json(&session_key(id), &stored, SESSION_IDLE_TIMEOUT).await
+    }
+
+    /// Live sessions, most recently used first
+    pub async fn list(&self) -> Result<Vec<SessionInfo>> {
+        let info = |id: &str, state: &SessionState, idle_seconds: u64| SessionInfo {
+            id_prefix: id.chars().take(8).collect(),
+            phase: state.phase,
+            client: state.client.clone(),
+            protocol_version: state.protocol_version.clone(),
+            compact: state.compact,
+            idle_seconds,
+        };
+
+        let mut list: Vec<SessionInfo> = match &self.shared {
+            Some(shared) => {
+                let now = chrono::Utc::now().timestamp();
+                shared
+                    .scan_json::<StoredSession>("session:")
+                    .await?
+                    .iter()
+                    .map(|(id, stored)| info(id, &stored.state, (now - stored.last_used).max(0) as u64))
+                    .collect()
+            }
+            None => {
+                let sessions = self.local.lock().unwrap_or_else(|e| e.into_inner());
+                sessions
+                    .iter()
+                    .filter(|(_, (_, last_used))| last_used.elapsed() < SESSION_IDLE_TIMEOUT)
+                    .map(|(id, (session, last_used))| {
+                        let state = session.0.lock().unwrap_or_else(|e| e.into_inner());
+                        info(id, &state, last_used.elapsed().as_secs())
+                    })
+                    .collect()
+            }
+        };
+        list.sort_by_key(|session| session.idle_seconds);
+        Ok(list)
+    }
+
+    pub async fn remove(&self, id: &str) -> Result<bool> {
+        if let Some(shared) = &self.shared {
+            return shared.delete(&session_key(id)).await;
+        }
+        Ok(self.local.lock().unwrap_or_else(|e| e.into_inner()).remove(id).is_some())
+    }
+}
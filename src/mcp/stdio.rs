@@ -0,0 +1,69 @@
+// MCP over stdin and stdout, for clients that launch the server as a
+// subprocess instead of connecting to it.
+//
+// Each line on stdin is one JSON-RPC message or batch and each response is
+// written as one line on stdout, which is why logs go to stderr in this mode.
+// The connection behaves like a WebSocket one: a single session, requests run
+// concurrently and can be cancelled, and webhook events arrive as
+// notifications. At end of input the server finishes what's in flight, then
+// exits.
+
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info};
+
+use crate::error::{AppError, Result};
+use crate::AppState;
+use super::cancellation::InFlight;
+use super::handlers::{forward_events, handle_message};
+use super::session::Session;
+
+pub async fn serve(state: AppState) -> Result<()> {
+    info!("Serving MCP over stdio");
+
+    let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel::<Value>();
+    let writer = tokio::spawn(async move {
+        let mut stdout = tokio::io::stdout();
+        while let Some(message) = outgoing_rx.recv().await {
+            let Ok(mut line) = serde_json::to_vec(&message) else {
+                continue;
+            };
+            line.push(b'\n');
+            if let Err(e) = async {
+                stdout.write_all(&line).await?;
+                stdout.flush().await
+            }
+            .await
+            {
+                error!("Failed to write to stdout: {}", e);
+                break;
+            }
+        }
+    });
+    // The process that launched the server is its operator, and sees every event
+    let forwarder = forward_events(&state, &outgoing, None);
+
+    let in_flight = InFlight::default();
+    let session = Session::default();
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to read stdin: {}", e)))?
+    {
+        if line.trim().is_empty() {
+            continue;
+        }
+        debug!("Received stdio message: {}", line);
+        handle_message(&state, &session, &None, &line, &outgoing, &in_flight).await;
+    }
+
+    // Requests still running hold a sender each, so the writer ends once
+    // they have all answered
+    info!("stdin closed; finishing in-flight requests");
+    forwarder.abort();
+    drop(outgoing);
+    let _ = writer.await;
+    Ok(())
+}
@@ -0,0 +1,3420 @@
+// The server's own tools. Each is registered with its name, description and
+// input schema next to the handler below that runs it; most translate their
+// arguments into a `GitHubCommand` for the workflow engine.
+
+use serde_json::{json, Value};
+
+use crate::{AppState, error::{AppError, Result}};
+use crate::github::execute_workflow_command;
+use crate::mcp::protocol::GitHubCommand;
+use super::{FunctionTool, ToolRegistry};
+
+pub fn register(registry: &mut ToolRegistry) {
+    registry.register(FunctionTool::new(
+        "github_push",
+        "Intelligent git push with PR management and workflow automation",
+        json!({
+            "type": "object",
+            "properties": {
+                "branch": {
+                    "type": "string",
+                    "description": "Branch to push (defaults to current branch)"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Optional commit message if changes need to be committed"
+                },
+                "ready_for_review": {
+                    "type": "boolean",
+                    "description": "Mark PR as ready for review after push"
+                },
+                "draft_pr": {
+                    "type": "boolean",
+                    "description": "Open a draft PR if the branch has none (defaults to the server's AUTO_DRAFT_PR setting)"
+                },
+                "request_reviewers": {
+                    "type": "boolean",
+                    "description": "Ask the CODEOWNERS of the changed paths to review the PR"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Registered repository name (see github_repositories); defaults to the server's working copy"
+                }
+            }
+        }),
+        push,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_scan_tasks",
+        "Scan GitHub Projects for tasks and present organized by type/priority",
+        json!({
+            "type": "object",
+            "properties": {
+                "project_number": {
+                    "type": "string",
+                    "description": "GitHub Project number (optional, will auto-detect from TODO.md)"
+                },
+                "owner": {
+                    "type": "string",
+                    "description": "Organization or user that owns the project (defaults to GITHUB_PROJECT_OWNER or the current repository's owner)"
+                },
+                "filter_type": {
+                    "type": "string",
+                    "enum": ["bug", "feature", "enhancement", "documentation", "refactor", "test", "chore"],
+                    "description": "Filter tasks by type"
+                },
+                "status": {
+                    "type": "string",
+                    "description": "Filter tasks by status (In Progress, To Do, etc.)"
+                },
+                "assignee": {
+                    "type": "string",
+                    "description": "Filter tasks by assignee login"
+                },
+                "label": {
+                    "type": "string",
+                    "description": "Filter tasks by label name"
+                },
+                "projects": {
+                    "type": "array",
+                    "description": "Scan several projects concurrently (defaults assignee to you)",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "owner": { "type": "string" },
+                            "project_number": { "type": "string" }
+                        },
+                        "required": ["owner", "project_number"]
+                    }
+                },
+                "all_projects": {
+                    "type": "boolean",
+                    "description": "Scan every project saved in your preferences and return a cross-project summary"
+                },
+                "filter": {
+                    "type": "string",
+                    "description": "Name of a saved filter (see github_filters_list); other arguments override its criteria"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Registered repository name (see github_repositories); defaults to the server's working copy"
+                }
+            }
+        }),
+        scan_tasks,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_merge",
+        "Complete merge workflow with tests, cleanup, and project updates",
+        json!({
+            "type": "object",
+            "properties": {
+                "branch": {
+                    "type": "string",
+                    "description": "Branch to merge (defaults to current branch)"
+                },
+                "delete_branch": {
+                    "type": "boolean",
+                    "description": "Delete the branch locally and on GitHub after merge (default: true)"
+                },
+                "cleanup_work_folder": {
+                    "type": "boolean",
+                    "description": "Delete the branch's work folder after merge (default: only report its contents so the user can decide)"
+                },
+                "ignore_checks": {
+                    "type": "boolean",
+                    "description": "Merge even if the PR's CI checks are pending or failing (default: false)"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["immediate", "auto", "queue"],
+                    "description": "Merge now, enable GitHub auto-merge, or add the PR to the merge queue; the server notifies when a deferred merge lands (defaults to MERGE_MODE)"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Registered repository name (see github_repositories); defaults to the server's working copy"
+                }
+            }
+        }),
+        merge,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_start_task",
+        "Claim an issue, move it to In Progress and create a working branch",
+        json!({
+            "type": "object",
+            "properties": {
+                "issue_number": {
+                    "type": "integer",
+                    "description": "Issue number of the task to start"
+                },
+                "project_number": {
+                    "type": "string",
+                    "description": "GitHub Project number (optional, will auto-detect from TODO.md)"
+                },
+                "owner": {
+                    "type": "string",
+                    "description": "Organization or user that owns the project"
+                },
+                "open_draft_pr": {
+                    "type": "boolean",
+                    "description": "Push the branch and open a draft PR linked to the issue (default: false)"
+                }
+            },
+            "required": ["issue_number"]
+        }),
+        start_task,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_promote_draft",
+        "Convert a GitHub Project draft item into a real issue, keeping its field values, and assign it",
+        json!({
+            "type": "object",
+            "properties": {
+                "item_id": {
+                    "type": "string",
+                    "description": "Project item ID of the draft"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Draft title, if item_id isn't known"
+                },
+                "repository": {
+                    "type": "string",
+                    "description": "Target repository as owner/name (defaults to current repository)"
+                },
+                "project_number": {
+                    "type": "string",
+                    "description": "GitHub Project number (auto-detected if not provided)"
+                },
+                "owner": {
+                    "type": "string",
+                    "description": "Org or user that owns the project"
+                },
+                "assignee": {
+                    "type": "string",
+                    "description": "Login to assign (defaults to the draft's assignees, then you)"
+                }
+            }
+        }),
+        promote_draft,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_get_checklist",
+        "List the task-list checkboxes in an issue or PR body as structured items",
+        json!({
+            "type": "object",
+            "properties": {
+                "number": {
+                    "type": "integer",
+                    "description": "Issue or pull request number"
+                },
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                }
+            },
+            "required": ["number"]
+        }),
+        get_checklist,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_update_checklist",
+        "Tick or untick checklist items in an issue or PR body",
+        json!({
+            "type": "object",
+            "properties": {
+                "number": {
+                    "type": "integer",
+                    "description": "Issue or pull request number"
+                },
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "items": {
+                    "type": "array",
+                    "items": { "type": "integer" },
+                    "description": "1-based indices from github_get_checklist"
+                },
+                "text": {
+                    "type": "string",
+                    "description": "Select items whose text contains this (case-insensitive)"
+                },
+                "checked": {
+                    "type": "boolean",
+                    "description": "Tick (true) or untick (false) the items",
+                    "default": true
+                }
+            },
+            "required": ["number"]
+        }),
+        update_checklist,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_add_reaction",
+        "React to an issue, PR or comment (e.g. thumbs-up to acknowledge a reply)",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Issue or pull request number"
+                },
+                "comment_id": {
+                    "type": "integer",
+                    "description": "Comment ID, to target a comment instead of the issue"
+                },
+                "review_comment": {
+                    "type": "boolean",
+                    "description": "comment_id refers to a PR review (diff) comment"
+                },
+                "content": {
+                    "type": "string",
+                    "enum": ["+1", "-1", "laugh", "confused", "heart", "hooray", "rocket", "eyes"],
+                    "default": "+1"
+                }
+            }
+        }),
+        add_reaction,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_get_reactions",
+        "Summarise reactions on an issue, PR or comment, or on your own comments in a thread",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Issue or pull request number"
+                },
+                "comment_id": {
+                    "type": "integer",
+                    "description": "Comment ID, to target a comment instead of the issue"
+                },
+                "review_comment": {
+                    "type": "boolean",
+                    "description": "comment_id refers to a PR review (diff) comment"
+                },
+                "own_comments": {
+                    "type": "boolean",
+                    "description": "Summarise reactions on each of your comments on `number`"
+                }
+            }
+        }),
+        get_reactions,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_issue_create",
+        "Create an issue, optionally with labels and assignees",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Issue title"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Issue body (Markdown)"
+                },
+                "labels": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Labels to apply"
+                },
+                "assignees": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Logins to assign"
+                }
+            },
+            "required": ["title"]
+        }),
+        issue_create,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_issue_update",
+        "Update an issue's title, body, state, labels or assignees",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Issue number"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "New title"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "New body (replaces the existing body)"
+                },
+                "state": {
+                    "type": "string",
+                    "enum": ["open", "closed"],
+                    "description": "New state"
+                },
+                "labels": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Replace all labels with these"
+                },
+                "assignees": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Replace all assignees with these"
+                }
+            },
+            "required": ["number"]
+        }),
+        issue_update,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_issue_comment",
+        "Comment on an issue or pull request",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Issue or pull request number"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Comment body (Markdown)"
+                }
+            },
+            "required": ["number", "body"]
+        }),
+        issue_comment,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_issue_close",
+        "Close an issue, optionally commenting first and locking the conversation",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Issue number"
+                },
+                "reason": {
+                    "type": "string",
+                    "enum": ["completed", "not_planned"],
+                    "description": "Close reason (default completed)"
+                },
+                "comment": {
+                    "type": "string",
+                    "description": "Comment to post before closing"
+                },
+                "lock": {
+                    "type": "boolean",
+                    "description": "Lock the conversation after closing"
+                },
+                "lock_reason": {
+                    "type": "string",
+                    "enum": ["off-topic", "too heated", "resolved", "spam"],
+                    "description": "Reason shown when locking (implies lock)"
+                }
+            },
+            "required": ["number"]
+        }),
+        issue_close,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_project_update_item",
+        "Add an issue or PR to a GitHub Project and set its fields (Status, Priority, iteration, dates, numbers, text), or archive it",
+        json!({
+            "type": "object",
+            "properties": {
+                "project_number": {
+                    "type": "string",
+                    "description": "Project number (defaults to the detected project)"
+                },
+                "owner": {
+                    "type": "string",
+                    "description": "Project owner (defaults to GITHUB_PROJECT_OWNER or the repository owner)"
+                },
+                "repository": {
+                    "type": "string",
+                    "description": "owner/name of the issue or PR (defaults to the current repository)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Issue or PR number; it's added to the project if it isn't there"
+                },
+                "item_id": {
+                    "type": "string",
+                    "description": "Project item ID, instead of number (e.g. for draft items)"
+                },
+                "fields": {
+                    "type": "object",
+                    "description": "Field name to value: an option name, an iteration title or current/next, a YYYY-MM-DD date, a number or text; null clears the field",
+                    "additionalProperties": {
+                        "type": ["string", "number", "null"]
+                    }
+                },
+                "archive": {
+                    "type": "boolean",
+                    "description": "true archives the item, false restores it"
+                }
+            }
+        }),
+        project_update_item,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_triage",
+        "List open issues without labels or not on the project, with full bodies and the labels, milestones and priorities to choose from; pass `decisions` to apply your classification",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "owner/name (defaults to the current repository)"
+                },
+                "project_number": {
+                    "type": "string",
+                    "description": "Project board issues should be on (defaults to the detected project)"
+                },
+                "owner": {
+                    "type": "string",
+                    "description": "Project owner (defaults to GITHUB_PROJECT_OWNER or the repository owner)"
+                },
+                "decisions": {
+                    "type": "array",
+                    "description": "One entry per issue to apply; omit to list untriaged issues",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "number": { "type": "integer" },
+                            "labels": { "type": "array", "items": { "type": "string" } },
+                            "priority": { "type": "string", "description": "An option of the project's Priority field" },
+                            "milestone": { "type": "string", "description": "Milestone number or title" },
+                            "add_to_project": { "type": "boolean", "default": true }
+                        },
+                        "required": ["number"]
+                    }
+                },
+                "limit": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": 100,
+                    "description": "Issues to list (default 30)"
+                }
+            }
+        }),
+        triage,
+    ).read_only_unless(|arguments| arguments.get("decisions").is_some_and(|decisions| !decisions.is_null())));
+
+    registry.register(FunctionTool::new(
+        "github_bulk_update_issues",
+        "Add/remove labels, set a milestone, comment on or close every issue matching a filter. Previews by default; pass confirm to apply",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "filter": {
+                    "type": "object",
+                    "description": "Which issues to update",
+                    "properties": {
+                        "state": { "type": "string", "enum": ["open", "closed", "all"], "description": "Default open" },
+                        "labels": { "type": "array", "items": { "type": "string" }, "description": "Issues must have all of these labels" },
+                        "assignee": { "type": "string", "description": "Login, `none` or `*`" },
+                        "creator": { "type": "string", "description": "Login of the issue author" },
+                        "milestone": { "type": "string", "description": "Milestone number, `none` or `*`" },
+                        "since": { "type": "string", "description": "Only issues updated since this ISO 8601 time" }
+                    }
+                },
+                "numbers": {
+                    "type": "array",
+                    "items": { "type": "integer" },
+                    "description": "Restrict to these issue numbers (e.g. from the preview)"
+                },
+                "add_labels": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Labels to add"
+                },
+                "remove_labels": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Labels to remove"
+                },
+                "milestone": {
+                    "type": "string",
+                    "description": "Milestone number or title to set, or `none` to clear"
+                },
+                "close": {
+                    "type": "boolean",
+                    "description": "Close matching issues"
+                },
+                "close_reason": {
+                    "type": "string",
+                    "enum": ["completed", "not_planned"],
+                    "description": "Close reason (default completed)"
+                },
+                "comment": {
+                    "type": "string",
+                    "description": "Comment to post on each issue"
+                },
+                "confirm": {
+                    "type": "boolean",
+                    "description": "Apply the changes to the previewed numbers; without both only a preview is returned"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum issues to consider (default 100, max 500)"
+                }
+            }
+        }),
+        bulk_update_issues,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_label_list",
+        "List a repository's labels with their colors and descriptions",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                }
+            }
+        }),
+        label_list,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_label_create",
+        "Create a label",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "name": {
+                    "type": "string"
+                },
+                "color": {
+                    "type": "string",
+                    "description": "Six hex digits, e.g. d73a4a"
+                },
+                "description": {
+                    "type": "string"
+                }
+            },
+            "required": ["name", "color"]
+        }),
+        label_create,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_label_update",
+        "Rename a label or change its color or description; issues keep a renamed label",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Current label name"
+                },
+                "new_name": {
+                    "type": "string"
+                },
+                "color": {
+                    "type": "string",
+                    "description": "Six hex digits, e.g. d73a4a"
+                },
+                "description": {
+                    "type": "string"
+                }
+            },
+            "required": ["name"]
+        }),
+        label_update,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_label_delete",
+        "Delete a label from a repository and every issue carrying it",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "name": {
+                    "type": "string"
+                }
+            },
+            "required": ["name"]
+        }),
+        label_delete,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_label_sync",
+        "Apply a declarative label set (from the arguments, .mcp-workflow.toml or a YAML file) to one or many repositories. Previews the changes unless confirm is true",
+        json!({
+            "type": "object",
+            "properties": {
+                "repositories": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Repositories as owner/name (defaults to current repository)"
+                },
+                "labels": {
+                    "type": "array",
+                    "description": "The label set; omit to read it from the repository",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "type": "string" },
+                            "color": { "type": "string" },
+                            "description": { "type": "string" },
+                            "aliases": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "description": "Existing names renamed to this label"
+                            }
+                        },
+                        "required": ["name", "color"]
+                    }
+                },
+                "file": {
+                    "type": "string",
+                    "description": "YAML label set (defaults to [labels] file in .mcp-workflow.toml, then .github/labels.yml)"
+                },
+                "source": {
+                    "type": "string",
+                    "description": "Repository as owner/name to read the file from (defaults to the local checkout)"
+                },
+                "prune": {
+                    "type": "boolean",
+                    "description": "Delete labels that aren't in the set"
+                },
+                "confirm": {
+                    "type": "boolean",
+                    "description": "Apply the changes (default: false, only preview)"
+                }
+            }
+        }),
+        label_sync,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_milestone_list",
+        "List a repository's milestones, soonest due first, with progress and whether each is on track",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "state": {
+                    "type": "string",
+                    "enum": ["open", "closed", "all"],
+                    "description": "Milestones to list (default open)"
+                }
+            }
+        }),
+        milestone_list,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_milestone_create",
+        "Create a milestone, optionally with a description and due date",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "title": {
+                    "type": "string",
+                    "description": "Milestone title, e.g. v2.0"
+                },
+                "description": {
+                    "type": "string"
+                },
+                "due_on": {
+                    "type": "string",
+                    "description": "Due date as YYYY-MM-DD"
+                }
+            },
+            "required": ["title"]
+        }),
+        milestone_create,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_milestone_close",
+        "Close a milestone, warning when open issues are still in it",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "milestone": {
+                    "type": "string",
+                    "description": "Milestone number or title"
+                }
+            },
+            "required": ["milestone"]
+        }),
+        milestone_close,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_milestone_assign",
+        "Put issues or pull requests in a milestone, or take them out of theirs with milestone \"none\"",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "milestone": {
+                    "type": "string",
+                    "description": "Milestone number or title, or \"none\""
+                },
+                "numbers": {
+                    "type": "array",
+                    "items": { "type": "integer" },
+                    "description": "Issue or PR numbers"
+                }
+            },
+            "required": ["milestone", "numbers"]
+        }),
+        milestone_assign,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_milestone_progress",
+        "Report a milestone's open and closed items, the issues left, and how far it's projected to slip past its due date",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "milestone": {
+                    "type": "string",
+                    "description": "Milestone number or title"
+                }
+            },
+            "required": ["milestone"]
+        }),
+        milestone_progress,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_workflow_runs",
+        "List GitHub Actions workflow runs for a branch or pull request",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "branch": {
+                    "type": "string",
+                    "description": "Branch name (defaults to the current branch)"
+                },
+                "pull_request": {
+                    "type": "integer",
+                    "description": "List runs for this PR's head commit instead"
+                },
+                "status": {
+                    "type": "string",
+                    "description": "Filter by status or conclusion, e.g. failure, in_progress, success"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum runs to return (default 20)"
+                }
+            }
+        }),
+        workflow_runs,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_workflow_run",
+        "Show a workflow run's jobs and steps, with error annotations and log tails for failing jobs",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "run_id": {
+                    "type": "integer",
+                    "description": "Workflow run ID"
+                },
+                "logs": {
+                    "type": "boolean",
+                    "description": "Fetch logs for failing jobs (default true)"
+                }
+            },
+            "required": ["run_id"]
+        }),
+        workflow_run,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_workflow_rerun",
+        "Re-run a workflow run's failed jobs, or the whole run",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "run_id": {
+                    "type": "integer",
+                    "description": "Workflow run ID"
+                },
+                "failed_only": {
+                    "type": "boolean",
+                    "description": "Only re-run failed jobs (default true)"
+                }
+            },
+            "required": ["run_id"]
+        }),
+        workflow_rerun,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_workflow_cancel",
+        "Cancel a queued or in-progress workflow run",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "run_id": {
+                    "type": "integer",
+                    "description": "Workflow run ID"
+                }
+            },
+            "required": ["run_id"]
+        }),
+        workflow_cancel,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_promote",
+        "Promote a tag to the next environment by dispatching the deployment workflow and tracking its run",
+        json!({
+            "type": "object",
+            "properties": {
+                "tag": {
+                    "type": "string",
+                    "description": "Existing tag or release to promote, e.g. v1.4.0"
+                },
+                "environment": {
+                    "type": "string",
+                    "description": "Target environment (defaults to the one after the tag's last promotion)"
+                },
+                "wait": {
+                    "type": "boolean",
+                    "description": "Wait for the deployment run to finish, streaming progress"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Registered repository name (defaults to the server's working directory)"
+                }
+            },
+            "required": ["tag"]
+        }),
+        promote,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_dependabot",
+        "Triage open Dependabot PRs: check CI and bump size, approve and auto-merge patch/minor updates allowed by policy, and list major updates for review",
+        json!({
+            "type": "object",
+            "properties": {
+                "confirm": {
+                    "type": "boolean",
+                    "description": "Approve and enable auto-merge on eligible PRs (default: false, only report the plan)"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Registered repository name (defaults to the server's working directory)"
+                }
+            }
+        }),
+        dependabot,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_release_create",
+        "Tag the default branch's HEAD and publish a release whose notes list the PRs merged since the previous release, optionally uploading assets",
+        json!({
+            "type": "object",
+            "properties": {
+                "tag": {
+                    "type": "string",
+                    "description": "New tag to create, e.g. v1.5.0"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Release title (defaults to the tag)"
+                },
+                "notes": {
+                    "type": "string",
+                    "description": "Introduction placed above the generated list of merged PRs"
+                },
+                "draft": {
+                    "type": "boolean",
+                    "description": "Create the release as a draft (default: true)"
+                },
+                "prerelease": {
+                    "type": "boolean",
+                    "description": "Mark the release as a pre-release"
+                },
+                "assets": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Files to upload, relative to the repository root"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Registered repository name (defaults to the server's working directory)"
+                }
+            },
+            "required": ["tag"]
+        }),
+        release_create,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_release_list",
+        "List a repository's releases, newest first, with their assets",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum releases to return (default 10, max 100)"
+                }
+            }
+        }),
+        release_list,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_changelog",
+        "Generate a Markdown changelog entry from the PRs merged between two tags or refs, grouped into features, bug fixes and chores",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "from": {
+                    "type": "string",
+                    "description": "Starting tag or ref, exclusive (defaults to the latest release's tag)"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "Ending tag or ref, inclusive (defaults to the default branch)"
+                },
+                "heading": {
+                    "type": "string",
+                    "description": "Entry heading (defaults to the `to` ref and today's date)"
+                }
+            }
+        }),
+        changelog,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_file_read",
+        "Read a file from a repository at a branch, tag or commit, without a local clone",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File path relative to the repository root"
+                },
+                "ref": {
+                    "type": "string",
+                    "description": "Branch, tag or commit SHA (defaults to the default branch)"
+                }
+            },
+            "required": ["path"]
+        }),
+        file_read,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_file_write",
+        "Create or update a file on a branch as a single commit through the Contents API",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File path relative to the repository root"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "Full new file content (UTF-8 text)"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Commit message"
+                },
+                "branch": {
+                    "type": "string",
+                    "description": "Branch to commit to (defaults to the default branch)"
+                },
+                "sha": {
+                    "type": "string",
+                    "description": "Blob SHA from github_file_read; the write is rejected if the file has changed since"
+                }
+            },
+            "required": ["path", "content", "message"]
+        }),
+        file_write,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_file_delete",
+        "Delete a file from a branch as a single commit through the Contents API",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File path relative to the repository root"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Commit message"
+                },
+                "branch": {
+                    "type": "string",
+                    "description": "Branch to commit to (defaults to the default branch)"
+                },
+                "sha": {
+                    "type": "string",
+                    "description": "Blob SHA from github_file_read; the delete is rejected if the file has changed since"
+                }
+            },
+            "required": ["path", "message"]
+        }),
+        file_delete,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_wiki",
+        "List, read, write or delete pages of a repository's wiki, e.g. to update a runbook after a change",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "read", "write", "delete"],
+                    "description": "Defaults to read when a page is given, otherwise list"
+                },
+                "page": {
+                    "type": "string",
+                    "description": "Page title, e.g. \"Deploy Runbook\""
+                },
+                "content": {
+                    "type": "string",
+                    "description": "Full new page content, for write; new pages are Markdown"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Commit message for write and delete"
+                }
+            }
+        }),
+        wiki,
+    ).read_only_unless(|arguments| matches!(arguments.get("action").and_then(Value::as_str), Some("write" | "delete"))));
+
+    registry.register(FunctionTool::new(
+        "github_branch_protection",
+        "Show a branch's protection rules and what they block, such as direct pushes or merges without reviews",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "branch": {
+                    "type": "string",
+                    "description": "Branch name (defaults to the default branch)"
+                }
+            }
+        }),
+        branch_protection,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_branch_protection_update",
+        "Change a branch's protection rules (requires admin access to the repository). Unset settings keep their current value",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "branch": {
+                    "type": "string",
+                    "description": "Branch name (defaults to the default branch)"
+                },
+                "required_approving_review_count": {
+                    "type": "integer",
+                    "description": "Approving reviews needed to merge (0 removes the review requirement)"
+                },
+                "require_code_owner_reviews": {
+                    "type": "boolean"
+                },
+                "dismiss_stale_reviews": {
+                    "type": "boolean",
+                    "description": "Dismiss approvals when new commits are pushed"
+                },
+                "required_status_checks": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Status check contexts that must pass (empty list removes the requirement)"
+                },
+                "strict_status_checks": {
+                    "type": "boolean",
+                    "description": "Require the branch to be up to date before merging"
+                },
+                "enforce_admins": {
+                    "type": "boolean",
+                    "description": "Apply the rules to administrators too"
+                },
+                "required_linear_history": {
+                    "type": "boolean"
+                },
+                "allow_force_pushes": {
+                    "type": "boolean"
+                },
+                "allow_deletions": {
+                    "type": "boolean"
+                },
+                "required_conversation_resolution": {
+                    "type": "boolean"
+                }
+            }
+        }),
+        branch_protection_update,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_search_code",
+        "Search code across GitHub or within one repository, returning paths and matching fragments",
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Code search query, e.g. 'fn main language:rust'"
+                },
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name to search within (adds a repo: qualifier)"
+                },
+                "page": {
+                    "type": "integer",
+                    "description": "Result page, starting at 1"
+                },
+                "per_page": {
+                    "type": "integer",
+                    "description": "Results per page (default 10, max 50)"
+                }
+            },
+            "required": ["query"]
+        }),
+        search_code,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_search_issues",
+        "Search issues and pull requests with GitHub's search syntax",
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Issue search query, e.g. 'is:pr is:open review-requested:@me'"
+                },
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name to search within (adds a repo: qualifier)"
+                },
+                "sort": {
+                    "type": "string",
+                    "enum": ["comments", "reactions", "created", "updated"],
+                    "description": "Sort field (defaults to best match)"
+                },
+                "order": {
+                    "type": "string",
+                    "enum": ["asc", "desc"]
+                },
+                "page": {
+                    "type": "integer",
+                    "description": "Result page, starting at 1"
+                },
+                "per_page": {
+                    "type": "integer",
+                    "description": "Results per page (default 10, max 50)"
+                }
+            },
+            "required": ["query"]
+        }),
+        search_issues,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_search_repos",
+        "Search repositories by name, description, topic, language or stars",
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Repository search query, e.g. 'mcp server language:rust stars:>100'"
+                },
+                "sort": {
+                    "type": "string",
+                    "enum": ["stars", "forks", "help-wanted-issues", "updated"],
+                    "description": "Sort field (defaults to best match)"
+                },
+                "order": {
+                    "type": "string",
+                    "enum": ["asc", "desc"]
+                },
+                "page": {
+                    "type": "integer",
+                    "description": "Result page, starting at 1"
+                },
+                "per_page": {
+                    "type": "integer",
+                    "description": "Results per page (default 10, max 50)"
+                }
+            },
+            "required": ["query"]
+        }),
+        search_repos,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_graphql",
+        "Run a GitHub GraphQL query for data the other tools don't cover. Queries are limited in depth and node count; mutations are refused unless enabled for administrators",
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "GraphQL document; page every connection with first or last"
+                },
+                "variables": {
+                    "type": "object",
+                    "description": "Values for the document's $variables"
+                }
+            },
+            "required": ["query"]
+        }),
+        graphql,
+    ).read_only_unless(|arguments| arguments.get("query").and_then(Value::as_str).is_some_and(crate::github::graphql::is_mutation)));
+
+    registry.register(FunctionTool::new(
+        "github_rest",
+        "GET any allow-listed GitHub REST API path and return its JSON, following pagination",
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "API path, e.g. /repos/owner/name/deployments"
+                },
+                "params": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" },
+                    "description": "Query parameters, e.g. {\"state\": \"open\", \"per_page\": \"100\"}"
+                },
+                "max_pages": {
+                    "type": "integer",
+                    "description": "Pages to fetch and combine (default 1, max 10)"
+                }
+            },
+            "required": ["path"]
+        }),
+        rest,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_draft_advisory",
+        "Draft a repository security advisory for coordinated disclosure, or add credits and request a CVE for an existing one",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "ghsa_id": {
+                    "type": "string",
+                    "description": "Existing advisory to update, e.g. GHSA-xxxx-xxxx-xxxx (omit to draft a new one)"
+                },
+                "summary": {
+                    "type": "string",
+                    "description": "Advisory title (required for a new draft)"
+                },
+                "description": {
+                    "type": "string",
+                    "description": "Markdown description of the vulnerability and its impact (required for a new draft)"
+                },
+                "severity": {
+                    "type": "string",
+                    "enum": ["critical", "high", "medium", "low"]
+                },
+                "cwe_ids": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "CWE identifiers, e.g. CWE-79"
+                },
+                "vulnerabilities": {
+                    "type": "array",
+                    "description": "Affected packages (required for a new draft)",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "package": {
+                                "type": "object",
+                                "properties": {
+                                    "ecosystem": { "type": "string", "description": "e.g. rust, npm, pip, maven, go" },
+                                    "name": { "type": "string" }
+                                },
+                                "required": ["ecosystem"]
+                            },
+                            "vulnerable_version_range": { "type": "string", "description": "e.g. < 1.4.2" },
+                            "patched_versions": { "type": "string", "description": "e.g. 1.4.2" },
+                            "vulnerable_functions": { "type": "array", "items": { "type": "string" } }
+                        },
+                        "required": ["package"]
+                    }
+                },
+                "credits": {
+                    "type": "array",
+                    "description": "People to credit, added to any existing credits",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "login": { "type": "string" },
+                            "type": {
+                                "type": "string",
+                                "enum": ["analyst", "finder", "reporter", "coordinator", "remediation_developer", "remediation_reviewer", "remediation_verifier", "tool", "sponsor", "other"]
+                            }
+                        },
+                        "required": ["login", "type"]
+                    }
+                },
+                "request_cve": {
+                    "type": "boolean",
+                    "description": "Ask GitHub to assign a CVE ID"
+                }
+            }
+        }),
+        draft_advisory,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_pr_diff",
+        "Read a pull request's changes as a unified diff or per-file patches, without a local checkout",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Pull request number"
+                },
+                "per_file": {
+                    "type": "boolean",
+                    "description": "Split into per-file patches with addition/deletion counts"
+                },
+                "files": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only these file paths (implies per_file)"
+                },
+                "max_bytes": {
+                    "type": "integer",
+                    "description": "Truncate the diff beyond this many bytes (default 100000)"
+                }
+            },
+            "required": ["number"]
+        }),
+        pr_diff,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_pr_review",
+        "Submit a pull request review: approve, request changes or comment, with optional inline comments",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Pull request number"
+                },
+                "event": {
+                    "type": "string",
+                    "enum": ["APPROVE", "REQUEST_CHANGES", "COMMENT"],
+                    "description": "Review outcome"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Review summary (required unless approving)"
+                },
+                "comments": {
+                    "type": "array",
+                    "description": "Inline comments anchored to lines of the diff",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "path": { "type": "string", "description": "File path in the diff" },
+                            "line": { "type": "integer", "description": "Line number in the file (last line for a range)" },
+                            "start_line": { "type": "integer", "description": "First line of a multi-line range" },
+                            "side": { "type": "string", "enum": ["LEFT", "RIGHT"], "description": "LEFT for removed lines, RIGHT (default) for added or context lines" },
+                            "body": { "type": "string", "description": "Comment text" }
+                        },
+                        "required": ["path", "line", "body"]
+                    }
+                }
+            },
+            "required": ["number", "event"]
+        }),
+        pr_review,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_pr_review_comment",
+        "Add a single comment anchored to a line (or range) of a pull request's diff",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Pull request number"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File path in the diff"
+                },
+                "line": {
+                    "type": "integer",
+                    "description": "Line number in the file (last line for a range)"
+                },
+                "start_line": {
+                    "type": "integer",
+                    "description": "First line of a multi-line range"
+                },
+                "side": {
+                    "type": "string",
+                    "enum": ["LEFT", "RIGHT"],
+                    "description": "LEFT for removed lines, RIGHT (default) for added or context lines"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Comment text"
+                }
+            },
+            "required": ["number", "path", "line", "body"]
+        }),
+        pr_review_comment,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_pr_reviews",
+        "List reviews on a pull request and who currently approves or requests changes",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Pull request number"
+                }
+            },
+            "required": ["number"]
+        }),
+        pr_reviews,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_list_commits",
+        "List commits on a branch or pull request with their GPG/SSH signature verification status",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "branch": {
+                    "type": "string",
+                    "description": "Branch or SHA to list from (defaults to the default branch)"
+                },
+                "pull_request": {
+                    "type": "integer",
+                    "description": "List this pull request's commits instead"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum commits to return for a branch (default 30, max 100)"
+                }
+            }
+        }),
+        list_commits,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_repo_create",
+        "Create a repository with standard settings: merge methods, branch cleanup, vulnerability alerts, the standard labels, and optionally an initial commit from a template and a linked project board",
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Repository name"
+                },
+                "owner": {
+                    "type": "string",
+                    "description": "Organization to create it in (defaults to the authenticated user)"
+                },
+                "description": {
+                    "type": "string",
+                    "description": "Repository description"
+                },
+                "private": {
+                    "type": "boolean",
+                    "description": "Create a private repository (default true)"
+                },
+                "default_branch": {
+                    "type": "string",
+                    "description": "Default branch name (defaults to the account's setting, usually main)"
+                },
+                "merge_methods": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": ["merge", "squash", "rebase"] },
+                    "description": "Merge methods to allow (default squash only)"
+                },
+                "delete_branch_on_merge": {
+                    "type": "boolean",
+                    "description": "Delete head branches once their PR merges (default true)"
+                },
+                "vulnerability_alerts": {
+                    "type": "boolean",
+                    "description": "Enable Dependabot vulnerability alerts (default true)"
+                },
+                "template": {
+                    "type": "string",
+                    "description": "Directory under REPO_TEMPLATES_DIR whose files make up the initial commit"
+                },
+                "labels": {
+                    "type": "boolean",
+                    "description": "Apply the standard label set (default true)"
+                },
+                "project": {
+                    "type": "boolean",
+                    "description": "Create a project board linked to the repository (default false)"
+                }
+            },
+            "required": ["name"]
+        }),
+        repo_create,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_repo_metadata",
+        "Show a repository's description, homepage and topics, and whether it meets the topic policy",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                }
+            }
+        }),
+        repo_metadata,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_update_repo_metadata",
+        "Set a repository's description, homepage or topics",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "description": {
+                    "type": "string",
+                    "description": "New description"
+                },
+                "homepage": {
+                    "type": "string",
+                    "description": "New homepage URL"
+                },
+                "topics": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Replace all topics with these"
+                },
+                "add_topics": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Topics to add"
+                },
+                "remove_topics": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Topics to remove"
+                }
+            }
+        }),
+        update_repo_metadata,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_repo_policy_check",
+        "Check repositories for required topics (e.g. team, service tier) across an org",
+        json!({
+            "type": "object",
+            "properties": {
+                "org": {
+                    "type": "string",
+                    "description": "Check every non-archived repository in this organization"
+                },
+                "repository": {
+                    "type": "string",
+                    "description": "Check a single owner/name repository (defaults to current repository)"
+                }
+            }
+        }),
+        repo_policy_check,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_cleanup_work_folder",
+        "Report or delete a branch's work folder (e.g. work/<branch>/)",
+        json!({
+            "type": "object",
+            "properties": {
+                "branch": {
+                    "type": "string",
+                    "description": "Branch whose work folder to clean (defaults to current branch)"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Only list what would be removed",
+                    "default": true
+                }
+            }
+        }),
+        cleanup_work_folder,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_complete_task",
+        "Move a task to Done, close its issue referencing the merged PR and record completion",
+        json!({
+            "type": "object",
+            "properties": {
+                "issue_number": {
+                    "type": "integer",
+                    "description": "Issue to complete (defaults to the task linked to the branch)"
+                },
+                "branch": {
+                    "type": "string",
+                    "description": "Branch the task was worked on (defaults to current branch)"
+                },
+                "pull_request": {
+                    "type": "integer",
+                    "description": "PR number to reference in the closing comment"
+                }
+            }
+        }),
+        complete_task,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_suggest_commit_message",
+        "Show the staged (or working tree) diff with per-file stats and a Conventional Commits template to write a commit message from; pass `message` to commit",
+        json!({
+            "type": "object",
+            "properties": {
+                "staged": {
+                    "type": "boolean",
+                    "description": "Use only staged changes (defaults to true when anything is staged, else the whole working tree)"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Commit the same changes with this message instead of returning the diff"
+                },
+                "max_diff_bytes": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Cut the returned diff at this size (default 60000)"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Registered repository name (see github_repositories); defaults to the server's working copy"
+                }
+            }
+        }),
+        suggest_commit_message,
+    ).read_only_unless(|arguments| arguments.get("message").is_some_and(|message| !message.is_null())));
+
+    registry.register(FunctionTool::new(
+        "github_request_reviewers",
+        "Find the CODEOWNERS of the paths a pull request changes and request their review; reports who was requested",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "owner/name (defaults to the current repository)"
+                },
+                "pull_request": {
+                    "type": "integer",
+                    "description": "PR number (defaults to the current branch's PR)"
+                },
+                "reviewers": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Also request these users, or teams as org/team"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Only report who would be requested"
+                }
+            }
+        }),
+        request_reviewers,
+    ).read_only_unless(|arguments| !arguments.get("dry_run").and_then(Value::as_bool).unwrap_or(false)));
+
+    registry.register(FunctionTool::new(
+        "github_sync_branch",
+        "Fetch and rebase or merge the current branch onto main; on conflict returns each conflicted file's hunks to resolve, then `continue` or `abort`",
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["sync", "continue", "abort"],
+                    "default": "sync",
+                    "description": "continue after resolving conflicts, or abort to restore the branch"
+                },
+                "strategy": {
+                    "type": "string",
+                    "enum": ["rebase", "merge"],
+                    "description": "Defaults to [sync] strategy in .mcp-workflow.toml, then SYNC_STRATEGY"
+                },
+                "base": {
+                    "type": "string",
+                    "description": "Branch to catch up with (defaults to the main branch)"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Registered repository name (see github_repositories); defaults to the server's working copy"
+                }
+            }
+        }),
+        sync_branch,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_fork",
+        "Fork a repository, and optionally point the checkout's origin at the fork and upstream at the original",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository to fork as owner/name (defaults to current repository)"
+                },
+                "organization": {
+                    "type": "string",
+                    "description": "Organization to fork into (defaults to the authenticated user)"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Name for the fork (defaults to the repository's name)"
+                },
+                "default_branch_only": {
+                    "type": "boolean",
+                    "description": "Copy only the default branch (default true)"
+                },
+                "remotes": {
+                    "type": "boolean",
+                    "description": "Set origin to the fork and upstream to the original in the checkout (default false)"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Registered repository name (see github_repositories); defaults to the server's working copy"
+                }
+            }
+        }),
+        fork,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_add_upstream",
+        "Add the repository the checkout's fork was made from as a remote, and fetch it",
+        json!({
+            "type": "object",
+            "properties": {
+                "upstream": {
+                    "type": "string",
+                    "description": "Upstream repository as owner/name (defaults to the fork's parent)"
+                },
+                "remote": {
+                    "type": "string",
+                    "description": "Remote name (default upstream)"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Registered repository name (see github_repositories); defaults to the server's working copy"
+                }
+            }
+        }),
+        add_upstream,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_sync_fork",
+        "Bring a fork's branch up to date with its upstream, with GitHub's merge-upstream or by fetching and rebasing or merging in the checkout and pushing",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "The fork as owner/name (defaults to current repository)"
+                },
+                "branch": {
+                    "type": "string",
+                    "description": "Branch to sync (defaults to the fork's default branch)"
+                },
+                "method": {
+                    "type": "string",
+                    "enum": ["api", "local"],
+                    "default": "api",
+                    "description": "api merges on GitHub and fails on conflicts; local syncs the checkout, where conflicts can be resolved with github_sync_branch"
+                },
+                "strategy": {
+                    "type": "string",
+                    "enum": ["rebase", "merge"],
+                    "description": "For local: defaults to [sync] strategy in .mcp-workflow.toml, then SYNC_STRATEGY"
+                },
+                "repo": {
+                    "type": "string",
+                    "description": "Registered repository name (see github_repositories); defaults to the server's working copy"
+                }
+            }
+        }),
+        sync_fork,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_discussion_list",
+        "List a repository's discussions, most recently updated first, with its discussion categories",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "category": {
+                    "type": "string",
+                    "description": "Only this category, by name or slug (e.g. Q&A or q-a)"
+                },
+                "state": {
+                    "type": "string",
+                    "enum": ["open", "closed", "all"],
+                    "default": "open"
+                },
+                "answered": {
+                    "type": "boolean",
+                    "description": "Only answered (true) or unanswered (false) discussions"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum discussions to return (default 20, max 100)"
+                }
+            }
+        }),
+        discussion_list,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_discussion_get",
+        "Read a discussion with its comments and their replies; comment IDs are used to reply and to mark an answer",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Discussion number"
+                }
+            },
+            "required": ["number"]
+        }),
+        discussion_get,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_discussion_create",
+        "Start a discussion in a category",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "category": {
+                    "type": "string",
+                    "description": "Category name or slug"
+                },
+                "title": {
+                    "type": "string"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Markdown body"
+                }
+            },
+            "required": ["category", "title", "body"]
+        }),
+        discussion_create,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_discussion_reply",
+        "Comment on a discussion, or reply under one of its top-level comments",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Discussion number"
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Markdown body"
+                },
+                "reply_to": {
+                    "type": "string",
+                    "description": "ID of the top-level comment to reply under (from github_discussion_get)"
+                }
+            },
+            "required": ["number", "body"]
+        }),
+        discussion_reply,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_discussion_answer",
+        "Mark a comment as the answer to its discussion, in a category that takes answers",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "comment_id": {
+                    "type": "string",
+                    "description": "Comment ID from github_discussion_get"
+                },
+                "unmark": {
+                    "type": "boolean",
+                    "description": "Take the answer mark back instead (default false)"
+                }
+            },
+            "required": ["comment_id"]
+        }),
+        discussion_answer,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_security_alerts",
+        "List a repository's Dependabot, code scanning and secret scanning alerts, most severe first, for security triage",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "kinds": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": ["dependabot", "code_scanning", "secret_scanning"] },
+                    "description": "Alert sources to include (default all)"
+                },
+                "severity": {
+                    "type": "string",
+                    "enum": ["low", "medium", "high", "critical"],
+                    "description": "Minimum severity; leaked secrets count as critical"
+                },
+                "state": {
+                    "type": "string",
+                    "enum": ["open", "closed", "all"],
+                    "default": "open"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum alerts per kind (default 50, max 200)"
+                }
+            }
+        }),
+        security_alerts,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_security_alert_dismiss",
+        "Dismiss a Dependabot or code scanning alert, or resolve a secret scanning alert, with a reason",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Repository as owner/name (defaults to current repository)"
+                },
+                "kind": {
+                    "type": "string",
+                    "enum": ["dependabot", "code_scanning", "secret_scanning"]
+                },
+                "number": {
+                    "type": "integer",
+                    "description": "Alert number"
+                },
+                "reason": {
+                    "type": "string",
+                    "description": "dependabot: fix_started, inaccurate, no_bandwidth, not_used, tolerable_risk. code_scanning: false_positive, wont_fix, used_in_tests. secret_scanning: false_positive, wont_fix, revoked, used_in_tests"
+                },
+                "comment": {
+                    "type": "string",
+                    "description": "Why it's dismissed, kept on the alert"
+                }
+            },
+            "required": ["kind", "number", "reason"]
+        }),
+        security_alert_dismiss,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_gist_create",
+        "Publish command output, test logs or snippets as a gist and return its URL; secret (unlisted) unless public is set. Content that looks like a credential is refused",
+        json!({
+            "type": "object",
+            "properties": {
+                "description": {
+                    "type": "string"
+                },
+                "public": {
+                    "type": "boolean",
+                    "description": "List the gist publicly (default false: secret, visible to anyone with the link)"
+                },
+                "files": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "filename": {
+                                "type": "string",
+                                "description": "Name with an extension for highlighting, e.g. test-output.log"
+                            },
+                            "content": { "type": "string" }
+                        },
+                        "required": ["filename", "content"]
+                    }
+                }
+            },
+            "required": ["files"]
+        }),
+        gist_create,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_gist_list",
+        "List the authenticated user's gists, newest first",
+        json!({
+            "type": "object",
+            "properties": {
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum gists to return (default 30, max 100)"
+                }
+            }
+        }),
+        gist_list,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_notifications_list",
+        "List the authenticated user's unread notifications: review requests, mentions and CI failures by default, each with its thread_id for github_notifications_mark_read",
+        json!({
+            "type": "object",
+            "properties": {
+                "repository": {
+                    "type": "string",
+                    "description": "Only this repository, as owner/name (defaults to the whole inbox)"
+                },
+                "reasons": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Reasons to include, e.g. review_requested, mention, ci_activity, assign, author, comment, security_alert (default review_requested, mention, team_mention, ci_activity; [] for all)"
+                },
+                "all": {
+                    "type": "boolean",
+                    "description": "Include notifications already read (default false)"
+                },
+                "participating": {
+                    "type": "boolean",
+                    "description": "Only threads you're directly part of (default false)"
+                },
+                "since": {
+                    "type": "string",
+                    "description": "Only notifications updated after this ISO 8601 time"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum notifications to return (default 50, max 200)"
+                }
+            }
+        }),
+        notifications_list,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        "github_notifications_mark_read",
+        "Mark notification threads read (or done), or everything in the inbox or a repository",
+        json!({
+            "type": "object",
+            "properties": {
+                "thread_ids": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Threads from github_notifications_list"
+                },
+                "done": {
+                    "type": "boolean",
+                    "description": "With thread_ids, also remove them from the inbox (default false)"
+                },
+                "all": {
+                    "type": "boolean",
+                    "description": "Mark everything read instead of listed threads"
+                },
+                "repository": {
+                    "type": "string",
+                    "description": "With all, only this repository, as owner/name"
+                },
+                "before": {
+                    "type": "string",
+                    "description": "With all, only notifications updated before this ISO 8601 time (default now)"
+                }
+            }
+        }),
+        notifications_mark_read,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_repositories",
+        "List, add or remove the named repositories the workflow tools can target via `repo`",
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "add", "remove"],
+                    "default": "list"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Registry name used as the `repo` argument"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Local checkout path under CHECKOUTS_DIR (for add)"
+                },
+                "repository": {
+                    "type": "string",
+                    "description": "GitHub owner/name (for add; read from the origin remote if omitted)"
+                }
+            }
+        }),
+        repositories,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_preferences",
+        "View or update your saved preferences, such as the projects scanned by default and named scan filters",
+        json!({
+            "type": "object",
+            "properties": {
+                "scan_projects": {
+                    "type": "array",
+                    "description": "Projects to scan with github_scan_tasks all_projects=true",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "owner": { "type": "string" },
+                            "project_number": { "type": "string" }
+                        },
+                        "required": ["owner", "project_number"]
+                    }
+                },
+                "scan_filters": {
+                    "type": "object",
+                    "description": "Named github_scan_tasks filters to save, merged with the existing ones; null removes a filter",
+                    "additionalProperties": {
+                        "type": ["object", "null"],
+                        "properties": {
+                            "project_number": { "type": "string" },
+                            "owner": { "type": "string" },
+                            "filter_type": { "type": "string" },
+                            "status": { "type": "string" },
+                            "assignee": { "type": "string" },
+                            "label": { "type": "string" },
+                            "projects": {
+                                "type": "array",
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "owner": { "type": "string" },
+                                        "project_number": { "type": "string" }
+                                    },
+                                    "required": ["owner", "project_number"]
+                                }
+                            },
+                            "all_projects": { "type": "boolean" }
+                        },
+                        "additionalProperties": false
+                    }
+                }
+            }
+        }),
+        preferences,
+    ));
+
+    registry.register(FunctionTool::new(
+        "github_filters_list",
+        "List your saved github_scan_tasks filters and their criteria",
+        json!({
+            "type": "object",
+            "properties": {}
+        }),
+        filters_list,
+    ).allow_read_only());
+
+    registry.register(FunctionTool::new(
+        crate::operations::APPROVE_TOOL,
+        "Approve or reject a tool call held for approval (CONFIRM_TOOLS). Only an admin other than the call's requester can approve, which runs the original call as the requester and returns its result; the requester can reject their own call",
+        json!({
+            "type": "object",
+            "properties": {
+                "approval_id": {
+                    "type": "string",
+                    "description": "approval_id returned by the held call"
+                },
+                "reject": {
+                    "type": "boolean",
+                    "description": "Reject the operation instead, so it never runs"
+                }
+            },
+            "required": ["approval_id"]
+        }),
+        approve_operation,
+    ));
+}
+
+async fn push(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "Push": {
+            "branch": arguments.get("branch"),
+            "message": arguments.get("message"),
+            "ready_for_review": arguments.get("ready_for_review"),
+            "draft_pr": arguments.get("draft_pr"),
+            "request_reviewers": arguments.get("request_reviewers"),
+            "repo": arguments.get("repo")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn scan_tasks(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ScanTasks": {
+            "project_number": arguments.get("project_number"),
+            "owner": arguments.get("owner"),
+            "filter_type": arguments.get("filter_type"),
+            "status": arguments.get("status"),
+            "assignee": arguments.get("assignee"),
+            "label": arguments.get("label"),
+            "projects": arguments.get("projects"),
+            "all_projects": arguments.get("all_projects"),
+            "filter": arguments.get("filter"),
+            "repo": arguments.get("repo")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn merge(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "Merge": {
+            "branch": arguments.get("branch"),
+            "delete_branch": arguments.get("delete_branch"),
+            "cleanup_work_folder": arguments.get("cleanup_work_folder"),
+            "ignore_checks": arguments.get("ignore_checks"),
+            "mode": arguments.get("mode"),
+            "repo": arguments.get("repo")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn start_task(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "StartTask": {
+            "issue_number": arguments.get("issue_number"),
+            "project_number": arguments.get("project_number"),
+            "owner": arguments.get("owner"),
+            "open_draft_pr": arguments.get("open_draft_pr")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn promote_draft(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "PromoteDraft": {
+            "item_id": arguments.get("item_id"),
+            "title": arguments.get("title"),
+            "repository": arguments.get("repository"),
+            "project_number": arguments.get("project_number"),
+            "owner": arguments.get("owner"),
+            "assignee": arguments.get("assignee")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn get_checklist(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "GetChecklist": {
+            "number": arguments.get("number"),
+            "repository": arguments.get("repository")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn update_checklist(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "UpdateChecklist": {
+            "number": arguments.get("number"),
+            "repository": arguments.get("repository"),
+            "items": arguments.get("items"),
+            "text": arguments.get("text"),
+            "checked": arguments.get("checked")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn add_reaction(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "AddReaction": {
+            "repository": arguments.get("repository"),
+            "number": arguments.get("number"),
+            "comment_id": arguments.get("comment_id"),
+            "review_comment": arguments.get("review_comment"),
+            "content": arguments.get("content")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn get_reactions(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "GetReactions": {
+            "repository": arguments.get("repository"),
+            "number": arguments.get("number"),
+            "comment_id": arguments.get("comment_id"),
+            "review_comment": arguments.get("review_comment"),
+            "own_comments": arguments.get("own_comments")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn issue_create(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CreateIssue": {
+            "repository": arguments.get("repository"),
+            "title": arguments.get("title"),
+            "body": arguments.get("body"),
+            "labels": arguments.get("labels"),
+            "assignees": arguments.get("assignees")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn issue_update(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "UpdateIssue": {
+            "repository": arguments.get("repository"),
+            "number": arguments.get("number"),
+            "title": arguments.get("title"),
+            "body": arguments.get("body"),
+            "state": arguments.get("state"),
+            "labels": arguments.get("labels"),
+            "assignees": arguments.get("assignees")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn issue_comment(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CommentIssue": {
+            "repository": arguments.get("repository"),
+            "number": arguments.get("number"),
+            "body": arguments.get("body")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn issue_close(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CloseIssue": {
+            "repository": arguments.get("repository"),
+            "number": arguments.get("number"),
+            "reason": arguments.get("reason"),
+            "comment": arguments.get("comment"),
+            "lock": arguments.get("lock"),
+            "lock_reason": arguments.get("lock_reason")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn bulk_update_issues(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "BulkUpdateIssues": {
+            "repository": arguments.get("repository"),
+            "filter": arguments.get("filter"),
+            "numbers": arguments.get("numbers"),
+            "add_labels": arguments.get("add_labels"),
+            "remove_labels": arguments.get("remove_labels"),
+            "milestone": arguments.get("milestone"),
+            "close": arguments.get("close"),
+            "close_reason": arguments.get("close_reason"),
+            "comment": arguments.get("comment"),
+            "confirm": arguments.get("confirm"),
+            "limit": arguments.get("limit")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn label_list(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ListLabels": {
+            "repository": arguments.get("repository")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn label_create(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CreateLabel": {
+            "repository": arguments.get("repository"),
+            "name": arguments.get("name"),
+            "color": arguments.get("color"),
+            "description": arguments.get("description")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn label_update(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "UpdateLabel": {
+            "repository": arguments.get("repository"),
+            "name": arguments.get("name"),
+            "new_name": arguments.get("new_name"),
+            "color": arguments.get("color"),
+            "description": arguments.get("description")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn label_delete(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "DeleteLabel": {
+            "repository": arguments.get("repository"),
+            "name": arguments.get("name")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn label_sync(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "SyncLabels": {
+            "repositories": arguments.get("repositories"),
+            "labels": arguments.get("labels"),
+            "file": arguments.get("file"),
+            "source": arguments.get("source"),
+            "prune": arguments.get("prune"),
+            "confirm": arguments.get("confirm")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn milestone_list(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ListMilestones": {
+            "repository": arguments.get("repository"),
+            "state": arguments.get("state")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn milestone_create(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CreateMilestone": {
+            "repository": arguments.get("repository"),
+            "title": arguments.get("title"),
+            "description": arguments.get("description"),
+            "due_on": arguments.get("due_on")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn milestone_close(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CloseMilestone": {
+            "repository": arguments.get("repository"),
+            "milestone": arguments.get("milestone")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn milestone_assign(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "AssignMilestone": {
+            "repository": arguments.get("repository"),
+            "milestone": arguments.get("milestone"),
+            "numbers": arguments.get("numbers")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn milestone_progress(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "MilestoneProgress": {
+            "repository": arguments.get("repository"),
+            "milestone": arguments.get("milestone")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn workflow_runs(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ListWorkflowRuns": {
+            "repository": arguments.get("repository"),
+            "branch": arguments.get("branch"),
+            "pull_request": arguments.get("pull_request"),
+            "status": arguments.get("status"),
+            "limit": arguments.get("limit")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn workflow_run(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "GetWorkflowRun": {
+            "repository": arguments.get("repository"),
+            "run_id": arguments.get("run_id"),
+            "logs": arguments.get("logs")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn workflow_rerun(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "RerunWorkflowRun": {
+            "repository": arguments.get("repository"),
+            "run_id": arguments.get("run_id"),
+            "failed_only": arguments.get("failed_only")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn workflow_cancel(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CancelWorkflowRun": {
+            "repository": arguments.get("repository"),
+            "run_id": arguments.get("run_id")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn promote(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "Promote": {
+            "tag": arguments.get("tag"),
+            "environment": arguments.get("environment"),
+            "wait": arguments.get("wait"),
+            "repo": arguments.get("repo")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn dependabot(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ManageDependabot": {
+            "confirm": arguments.get("confirm"),
+            "repo": arguments.get("repo")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn release_create(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CreateRelease": {
+            "tag": arguments.get("tag"),
+            "name": arguments.get("name"),
+            "notes": arguments.get("notes"),
+            "draft": arguments.get("draft"),
+            "prerelease": arguments.get("prerelease"),
+            "assets": arguments.get("assets"),
+            "repo": arguments.get("repo")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn release_list(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ListReleases": {
+            "repository": arguments.get("repository"),
+            "limit": arguments.get("limit")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn changelog(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "Changelog": {
+            "repository": arguments.get("repository"),
+            "from": arguments.get("from"),
+            "to": arguments.get("to"),
+            "heading": arguments.get("heading")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn file_read(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ReadFile": {
+            "repository": arguments.get("repository"),
+            "path": arguments.get("path"),
+            "ref": arguments.get("ref")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn file_write(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "WriteFile": {
+            "repository": arguments.get("repository"),
+            "path": arguments.get("path"),
+            "content": arguments.get("content"),
+            "message": arguments.get("message"),
+            "branch": arguments.get("branch"),
+            "sha": arguments.get("sha")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn file_delete(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "DeleteFile": {
+            "repository": arguments.get("repository"),
+            "path": arguments.get("path"),
+            "message": arguments.get("message"),
+            "branch": arguments.get("branch"),
+            "sha": arguments.get("sha")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn wiki(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "Wiki": {
+            "repository": arguments.get("repository"),
+            "action": arguments.get("action"),
+            "page": arguments.get("page"),
+            "content": arguments.get("content"),
+            "message": arguments.get("message")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn branch_protection(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "GetBranchProtection": {
+            "repository": arguments.get("repository"),
+            "branch": arguments.get("branch")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn branch_protection_update(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "UpdateBranchProtection": {
+            "repository": arguments.get("repository"),
+            "branch": arguments.get("branch"),
+            "settings": {
+                "required_approving_review_count": arguments.get("required_approving_review_count"),
+                "require_code_owner_reviews": arguments.get("require_code_owner_reviews"),
+                "dismiss_stale_reviews": arguments.get("dismiss_stale_reviews"),
+                "required_status_checks": arguments.get("required_status_checks"),
+                "strict_status_checks": arguments.get("strict_status_checks"),
+                "enforce_admins": arguments.get("enforce_admins"),
+                "required_linear_history": arguments.get("required_linear_history"),
+                "allow_force_pushes": arguments.get("allow_force_pushes"),
+                "allow_deletions": arguments.get("allow_deletions"),
+                "required_conversation_resolution": arguments.get("required_conversation_resolution")
+            }
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn search_code(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "SearchCode": {
+            "query": arguments.get("query"),
+            "repository": arguments.get("repository"),
+            "page": arguments.get("page"),
+            "per_page": arguments.get("per_page")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn search_issues(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "SearchIssues": {
+            "query": arguments.get("query"),
+            "repository": arguments.get("repository"),
+            "sort": arguments.get("sort"),
+            "order": arguments.get("order"),
+            "page": arguments.get("page"),
+            "per_page": arguments.get("per_page")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn search_repos(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "SearchRepos": {
+            "query": arguments.get("query"),
+            "sort": arguments.get("sort"),
+            "order": arguments.get("order"),
+            "page": arguments.get("page"),
+            "per_page": arguments.get("per_page")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn graphql(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "Graphql": {
+            "query": arguments.get("query"),
+            "variables": arguments.get("variables")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn rest(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "RestGet": {
+            "path": arguments.get("path"),
+            "params": arguments.get("params"),
+            "max_pages": arguments.get("max_pages")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn draft_advisory(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "DraftAdvisory": {
+            "repository": arguments.get("repository"),
+            "ghsa_id": arguments.get("ghsa_id"),
+            "summary": arguments.get("summary"),
+            "description": arguments.get("description"),
+            "severity": arguments.get("severity"),
+            "cwe_ids": arguments.get("cwe_ids"),
+            "vulnerabilities": arguments.get("vulnerabilities"),
+            "credits": arguments.get("credits"),
+            "request_cve": arguments.get("request_cve")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn pr_diff(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "GetPullRequestDiff": {
+            "repository": arguments.get("repository"),
+            "number": arguments.get("number"),
+            "per_file": arguments.get("per_file"),
+            "files": arguments.get("files"),
+            "max_bytes": arguments.get("max_bytes")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn pr_review(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "SubmitReview": {
+            "repository": arguments.get("repository"),
+            "number": arguments.get("number"),
+            "event": arguments.get("event"),
+            "body": arguments.get("body"),
+            "comments": arguments.get("comments")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn pr_review_comment(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "AddReviewComment": {
+            "repository": arguments.get("repository"),
+            "number": arguments.get("number"),
+            "comment": {
+                "path": arguments.get("path"),
+                "line": arguments.get("line"),
+                "start_line": arguments.get("start_line"),
+                "side": arguments.get("side"),
+                "body": arguments.get("body")
+            }
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn pr_reviews(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ListReviews": {
+            "repository": arguments.get("repository"),
+            "number": arguments.get("number")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn list_commits(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ListCommits": {
+            "repository": arguments.get("repository"),
+            "branch": arguments.get("branch"),
+            "pull_request": arguments.get("pull_request"),
+            "limit": arguments.get("limit")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn repo_create(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CreateRepository": {
+            "name": arguments.get("name"),
+            "owner": arguments.get("owner"),
+            "description": arguments.get("description"),
+            "private": arguments.get("private"),
+            "default_branch": arguments.get("default_branch"),
+            "merge_methods": arguments.get("merge_methods"),
+            "delete_branch_on_merge": arguments.get("delete_branch_on_merge"),
+            "vulnerability_alerts": arguments.get("vulnerability_alerts"),
+            "template": arguments.get("template"),
+            "labels": arguments.get("labels"),
+            "project": arguments.get("project")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn repo_metadata(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "GetRepoMetadata": {
+            "repository": arguments.get("repository")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn update_repo_metadata(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "UpdateRepoMetadata": {
+            "repository": arguments.get("repository"),
+            "description": arguments.get("description"),
+            "homepage": arguments.get("homepage"),
+            "topics": arguments.get("topics"),
+            "add_topics": arguments.get("add_topics"),
+            "remove_topics": arguments.get("remove_topics")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn repo_policy_check(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CheckRepoPolicy": {
+            "org": arguments.get("org"),
+            "repository": arguments.get("repository")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn cleanup_work_folder(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CleanupWorkFolder": {
+            "branch": arguments.get("branch"),
+            "dry_run": arguments.get("dry_run")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn complete_task(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CompleteTask": {
+            "issue_number": arguments.get("issue_number"),
+            "branch": arguments.get("branch"),
+            "pull_request": arguments.get("pull_request")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn suggest_commit_message(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "SuggestCommitMessage": {
+            "staged": arguments.get("staged"),
+            "message": arguments.get("message"),
+            "max_diff_bytes": arguments.get("max_diff_bytes"),
+            "repo": arguments.get("repo")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn request_reviewers(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "RequestReviewers": {
+            "repository": arguments.get("repository"),
+            "pull_request": arguments.get("pull_request"),
+            "reviewers": arguments.get("reviewers"),
+            "dry_run": arguments.get("dry_run")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn project_update_item(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ProjectUpdateItem": {
+            "project_number": arguments.get("project_number"),
+            "owner": arguments.get("owner"),
+            "repository": arguments.get("repository"),
+            "number": arguments.get("number"),
+            "item_id": arguments.get("item_id"),
+            "fields": arguments.get("fields"),
+            "archive": arguments.get("archive")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn triage(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "Triage": {
+            "repository": arguments.get("repository"),
+            "project_number": arguments.get("project_number"),
+            "owner": arguments.get("owner"),
+            "decisions": arguments.get("decisions"),
+            "limit": arguments.get("limit")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn sync_branch(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "SyncBranch": {
+            "action": arguments.get("action"),
+            "strategy": arguments.get("strategy"),
+            "base": arguments.get("base"),
+            "repo": arguments.get("repo")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn fork(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ForkRepository": {
+            "repository": arguments.get("repository"),
+            "organization": arguments.get("organization"),
+            "name": arguments.get("name"),
+            "default_branch_only": arguments.get("default_branch_only"),
+            "remotes": arguments.get("remotes"),
+            "repo": arguments.get("repo")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn add_upstream(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "AddUpstreamRemote": {
+            "upstream": arguments.get("upstream"),
+            "remote": arguments.get("remote"),
+            "repo": arguments.get("repo")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn sync_fork(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "SyncFork": {
+            "repository": arguments.get("repository"),
+            "branch": arguments.get("branch"),
+            "method": arguments.get("method"),
+            "strategy": arguments.get("strategy"),
+            "repo": arguments.get("repo")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn discussion_list(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ListDiscussions": {
+            "repository": arguments.get("repository"),
+            "category": arguments.get("category"),
+            "state": arguments.get("state"),
+            "answered": arguments.get("answered"),
+            "limit": arguments.get("limit")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn discussion_get(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "GetDiscussion": {
+            "repository": arguments.get("repository"),
+            "number": arguments.get("number")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn discussion_create(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CreateDiscussion": {
+            "repository": arguments.get("repository"),
+            "category": arguments.get("category"),
+            "title": arguments.get("title"),
+            "body": arguments.get("body")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn discussion_reply(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ReplyDiscussion": {
+            "repository": arguments.get("repository"),
+            "number": arguments.get("number"),
+            "body": arguments.get("body"),
+            "reply_to": arguments.get("reply_to")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn discussion_answer(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "AnswerDiscussion": {
+            "repository": arguments.get("repository"),
+            "comment_id": arguments.get("comment_id"),
+            "unmark": arguments.get("unmark")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn security_alerts(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ListSecurityAlerts": {
+            "repository": arguments.get("repository"),
+            "kinds": arguments.get("kinds"),
+            "severity": arguments.get("severity"),
+            "state": arguments.get("state"),
+            "limit": arguments.get("limit")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn security_alert_dismiss(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "DismissSecurityAlert": {
+            "repository": arguments.get("repository"),
+            "kind": arguments.get("kind"),
+            "number": arguments.get("number"),
+            "reason": arguments.get("reason"),
+            "comment": arguments.get("comment")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn gist_create(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "CreateGist": {
+            "description": arguments.get("description"),
+            "public": arguments.get("public"),
+            "files": arguments.get("files")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn gist_list(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ListGists": {
+            "limit": arguments.get("limit")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn notifications_list(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "ListNotifications": {
+            "repository": arguments.get("repository"),
+            "reasons": arguments.get("reasons"),
+            "all": arguments.get("all"),
+            "participating": arguments.get("participating"),
+            "since": arguments.get("since"),
+            "limit": arguments.get("limit")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn notifications_mark_read(state: AppState, arguments: Value) -> Result<Value> {
+    let command = serde_json::from_value::<GitHubCommand>(json!({
+        "MarkNotificationsRead": {
+            "thread_ids": arguments.get("thread_ids"),
+            "all": arguments.get("all"),
+            "repository": arguments.get("repository"),
+            "before": arguments.get("before"),
+            "done": arguments.get("done")
+        }
+    }))?;
+    execute_workflow_command(state, command).await
+}
+
+async fn repositories(state: AppState, arguments: Value) -> Result<Value> {
+    crate::repositories::handle_repositories_tool(state, &arguments).await
+}
+
+async fn preferences(state: AppState, arguments: Value) -> Result<Value> {
+    crate::preferences::handle_preferences_tool(state, &arguments).await
+}
+
+async fn filters_list(state: AppState, _arguments: Value) -> Result<Value> {
+    crate::preferences::handle_filters_list_tool(state).await
+}
+
+async fn approve_operation(state: AppState, arguments: Value) -> Result<Value> {
+    let id = arguments["approval_id"].as_str().unwrap_or_default();
+    let caller = crate::mcp::caller::require()?;
+    if arguments["reject"].as_bool().unwrap_or(false) {
+        if !crate::operations::reject(&state.db, id, &caller).await? {
+            return Err(AppError::Validation(format!("No pending operation {} of yours", id)));
+        }
+        return Ok(json!({ "status": "success", "approval_id": id, "rejected": true }));
+    }
+
+    let result = crate::operations::approve(&state, id, &caller).await?;
+    Ok(json!({ "status": "success", "approval_id": id, "result": result }))
+}
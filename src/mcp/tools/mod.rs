@@ -0,0 +1,201 @@
+// Tools served by `tools/list` and `tools/call`.
+//
+// A tool is anything implementing `Tool`. The registry is built once at
+// startup from the built-in tools, and extensions add their own with
+// `ToolRegistry::register` before the server starts; a tool registered under
+// an existing name replaces it. Admins can disable a tool while the server
+// runs, which hides it from `tools/list` and refuses calls to it.
+
+pub mod builtin;
+
+use futures_util::future::BoxFuture;
+use jsonschema::Validator;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+
+use crate::{AppState, error::Result};
+use super::protocol::McpTool;
+use super::schema::{self, ArgumentError};
+
+pub trait Tool: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn description(&self) -> &str;
+
+    /// JSON Schema for the call's `arguments`, checked before `execute`
+    fn input_schema(&self) -> Value;
+
+    /// Whether the tool only reads state and stays available in read-only
+    /// mode. Tools are treated as mutating unless they say otherwise.
+    fn read_only(&self) -> bool {
+        false
+    }
+
+    /// Whether this call changes state, and so is held under CONFIRM_TOOLS
+    fn mutates(&self, _arguments: &Value) -> bool {
+        !self.read_only()
+    }
+
+    fn execute(&self, state: AppState, arguments: Value) -> BoxFuture<'static, Result<Value>>;
+}
+
+type Handler = Box<dyn Fn(AppState, Value) -> BoxFuture<'static, Result<Value>> + Send + Sync>;
+
+/// A tool whose call is an async function of the state and arguments
+pub struct FunctionTool {
+    name: String,
+    description: String,
+    input_schema: Value,
+    read_only: bool,
+    mutates: Option<fn(&Value) -> bool>,
+    handler: Handler,
+}
+
+impl FunctionTool {
+    pub fn new<F, Fut>(name: &str, description: &str, input_schema: Value, handler: F) -> Self
+    where
+        F: Fn(AppState, Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Value>> + Send + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            input_schema,
+            read_only: false,
+            mutates: None,
+            handler: Box::new(move |state, arguments| Box::pin(handler(state, arguments))),
+        }
+    }
+
+    /// Keep the tool available in read-only mode
+    pub fn allow_read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    /// Keep the tool available in read-only mode for calls that only read,
+    /// treating the calls `mutates` picks out as changes
+    pub fn read_only_unless(mut self, mutates: fn(&Value) -> bool) -> Self {
+        self.read_only = true;
+        self.mutates = Some(mutates);
+        self
+    }
+}
+
+impl Tool for FunctionTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn input_schema(&self) -> Value {
+        self.input_schema.clone()
+    }
+
+    fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn mutates(&self, arguments: &Value) -> bool {
+        match self.mutates {
+            Some(mutates) => mutates(arguments),
+            None => !self.read_only,
+        }
+    }
+
+    fn execute(&self, state: AppState, arguments: Value) -> BoxFuture<'static, Result<Value>> {
+        (self.handler)(state, arguments)
+    }
+}
+
+struct Registered {
+    tool: Arc<dyn Tool>,
+    validator: Option<Validator>,
+}
+
+/// Registered tools, listed in registration order
+#[derive(Default)]
+pub struct ToolRegistry {
+    tools: Vec<Registered>,
+    disabled: RwLock<BTreeSet<String>>,
+}
+
+impl ToolRegistry {
+    /// A registry holding the server's own tools
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        builtin::register(&mut registry);
+        registry
+    }
+
+    pub fn register(&mut self, tool: impl Tool + 'static) -> &mut Self {
+        let validator = schema::compile(tool.name(), &tool.input_schema());
+        let registered = Registered { tool: Arc::new(tool), validator };
+
+        match self.tools.iter_mut().find(|r| r.tool.name() == registered.tool.name()) {
+            Some(existing) => *existing = registered,
+            None => self.tools.push(registered),
+        }
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
+        self.find(name).map(|r| r.tool.clone())
+    }
+
+    /// Check `arguments` against the input schema of tool `name`
+    pub fn validate(&self, name: &str, arguments: &Value) -> std::result::Result<(), Vec<ArgumentError>> {
+        match self.find(name).and_then(|r| r.validator.as_ref()) {
+            Some(validator) => schema::validate(validator, arguments),
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_disabled(&self, name: &str) -> bool {
+        self.disabled.read().map_or(false, |disabled| disabled.contains(name))
+    }
+
+    /// Disable or re-enable tool `name`; false if there's no such tool
+    pub fn set_disabled(&self, name: &str, disabled: bool) -> bool {
+        if self.find(name).is_none() {
+            return false;
+        }
+        if let Ok(mut set) = self.disabled.write() {
+            match disabled {
+                true => set.insert(name.to_string()),
+                false => set.remove(name),
+            };
+        }
+        true
+    }
+
+    /// Every tool's name and whether it is disabled
+    pub fn states(&self) -> Vec<(String, bool)> {
+        self.tools
+            .iter()
+            .map(|r| (r.tool.name().to_string(), self.is_disabled(r.tool.name())))
+            .collect()
+    }
+
+    /// Every enabled tool with its input schema, as advertised by `tools/list`
+    pub fn definitions(&self) -> Vec<McpTool> {
+        self.tools
+            .iter()
+            .filter(|r| !self.is_disabled(r.tool.name()))
+            .map(|r| McpTool {
+                name: r.tool.name().to_string(),
+                description: r.tool.description().to_string(),
+                input_schema: r.tool.input_schema(),
+            })
+            .collect()
+    }
+
+    fn find(&self, name: &str) -> Option<&Registered> {
+        self.tools.iter().find(|r| r.tool.name() == name)
+    }
+}
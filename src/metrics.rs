@@ -1,23 +1,35 @@
-use prometheus::{Counter, Histogram, Gauge, Registry, Encoder, TextEncoder, Opts, HistogramOpts};
+use prometheus::{Gauge, GaugeVec, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Registry, Encoder, TextEncoder, Opts, HistogramOpts};
+use std::collections::HashMap;
 use std::sync::Arc;
+use crate::history::{self, CommandStats};
+use crate::AppState;
+use tracing::warn;
 use axum::{
-    extract::State,
+    body::Body,
+    extract::{MatchedPath, State},
+    middleware::Next,
     response::{Response, IntoResponse},
-    http::{StatusCode, header},
+    http::{Request, StatusCode, header},
 };
 
 #[derive(Clone)]
 pub struct Metrics {
     pub registry: Arc<Registry>,
-    pub http_requests_total: Counter,
-    pub http_request_duration: Histogram,
-    pub github_api_requests_total: Counter,
-    pub github_api_request_duration: Histogram,
-    pub github_api_rate_limit_remaining: Gauge,
-    pub mcp_commands_total: Counter,
-    pub mcp_command_duration: Histogram,
+    pub http_requests_total: IntCounterVec,
+    pub http_request_duration: HistogramVec,
+    pub github_api_requests_total: IntCounterVec,
+    pub github_api_request_duration: HistogramVec,
+    pub github_api_rate_limit_remaining: GaugeVec,
+    pub github_api_retries_total: IntCounterVec,
+    pub mcp_commands_total: IntCounterVec,
+    pub mcp_command_duration: HistogramVec,
     pub active_connections: Gauge,
     pub database_connections: Gauge,
+    pub rate_limiters: IntGauge,
+    pub background_jobs: IntGaugeVec,
+    pub tool_calls_total: IntCounterVec,
+    pub tool_success_ratio: GaugeVec,
+    pub workflow_duration: GaugeVec,
 }
 
 impl Metrics {
@@ -25,42 +37,47 @@ impl Metrics {
         let registry = Arc::new(Registry::new());
 
         // HTTP metrics
-        let http_requests_total = Counter::with_opts(Opts::new(
+        let service: std::collections::HashMap<String, String> =
+            [("service".to_string(), "github-mcp-server".to_string())].into_iter().collect();
+
+        let http_requests_total = IntCounterVec::new(Opts::new(
             "http_requests_total",
             "Total number of HTTP requests"
-        ).const_labels([("service", "github-mcp-server")].iter().cloned().collect()))?;
+        ).const_labels(service.clone()), &["method", "path", "status"])?;
 
-        let http_request_duration = Histogram::with_opts(HistogramOpts::new(
+        let http_request_duration = HistogramVec::new(HistogramOpts::new(
             "http_request_duration_seconds",
             "HTTP request duration in seconds"
-        ).const_labels([("service", "github-mcp-server")].iter().cloned().collect()))?;
+        ).const_labels(service), &["method", "path"])?;
 
         // GitHub API metrics
-        let github_api_requests_total = Counter::with_opts(Opts::new(
+        let github_api_requests_total = IntCounterVec::new(Opts::new(
             "github_api_requests_total",
             "Total number of GitHub API requests"
-        ))?;
+        ), &["endpoint", "method"])?;
 
-        let github_api_request_duration = Histogram::with_opts(HistogramOpts::new(
+        let github_api_request_duration = HistogramVec::new(HistogramOpts::new(
             "github_api_request_duration_seconds",
             "GitHub API request duration in seconds"
-        ))?;
+        ), &["endpoint"])?;
 
-        let github_api_rate_limit_remaining = Gauge::with_opts(Opts::new(
+        let github_api_rate_limit_remaining = GaugeVec::new(Opts::new(
             "github_api_rate_limit_remaining",
-            "GitHub API rate limit remaining"
-        ))?;
+            "GitHub API requests remaining in the current window, lowest across tokens"
+        ), &["resource"])?;
+
+        let github_api_retries_total = crate::github::retry::metric();
 
         // MCP command metrics
-        let mcp_commands_total = Counter::with_opts(Opts::new(
+        let mcp_commands_total = IntCounterVec::new(Opts::new(
             "mcp_commands_total",
-            "Total number of MCP commands executed"
-        ))?;
+            "Total number of MCP requests handled, by method and outcome"
+        ), &["command", "status"])?;
 
-        let mcp_command_duration = Histogram::with_opts(HistogramOpts::new(
+        let mcp_command_duration = HistogramVec::new(HistogramOpts::new(
             "mcp_command_duration_seconds",
-            "MCP command execution duration in seconds"
-        ))?;
+            "MCP request handling duration in seconds, by method"
+        ), &["command"])?;
 
         // Connection metrics
         let active_connections = Gauge::with_opts(Opts::new(
@@ -73,16 +90,48 @@ impl Metrics {
             "Number of active database connections"
         ))?;
 
+        let rate_limiters = IntGauge::with_opts(Opts::new(
+            "rate_limiters",
+            "Per-IP rate limiters held in memory"
+        ))?;
+
+        // Automation metrics, refreshed from the scheduler and command history on scrape
+        let background_jobs = IntGaugeVec::new(Opts::new(
+            "background_jobs",
+            "Background jobs by state (running, queued, deferred, failed)"
+        ), &["state"])?;
+
+        let tool_calls_total = IntCounterVec::new(Opts::new(
+            "mcp_tool_calls_total",
+            "Workflow command calls by command and outcome"
+        ), &["command", "status"])?;
+
+        let tool_success_ratio = GaugeVec::new(Opts::new(
+            "mcp_tool_success_ratio",
+            "Share of successful calls per command over the last 7 days"
+        ), &["command"])?;
+
+        let workflow_duration = GaugeVec::new(Opts::new(
+            "workflow_duration_seconds",
+            "Workflow command duration percentiles over the last 7 days"
+        ), &["command", "quantile"])?;
+
         // Register all metrics
         registry.register(Box::new(http_requests_total.clone()))?;
         registry.register(Box::new(http_request_duration.clone()))?;
         registry.register(Box::new(github_api_requests_total.clone()))?;
         registry.register(Box::new(github_api_request_duration.clone()))?;
         registry.register(Box::new(github_api_rate_limit_remaining.clone()))?;
+        registry.register(Box::new(github_api_retries_total.clone()))?;
         registry.register(Box::new(mcp_commands_total.clone()))?;
         registry.register(Box::new(mcp_command_duration.clone()))?;
         registry.register(Box::new(active_connections.clone()))?;
         registry.register(Box::new(database_connections.clone()))?;
+        registry.register(Box::new(rate_limiters.clone()))?;
+        registry.register(Box::new(background_jobs.clone()))?;
+        registry.register(Box::new(tool_calls_total.clone()))?;
+        registry.register(Box::new(tool_success_ratio.clone()))?;
+        registry.register(Box::new(workflow_duration.clone()))?;
 
         Ok(Metrics {
             registry,
@@ -91,10 +140,16 @@ impl Metrics {
             github_api_requests_total,
             github_api_request_duration,
             github_api_rate_limit_remaining,
+            github_api_retries_total,
             mcp_commands_total,
             mcp_command_duration,
             active_connections,
             database_connections,
+            rate_limiters,
+            background_jobs,
+            tool_calls_total,
+            tool_success_ratio,
+            workflow_duration,
         })
     }
 
@@ -102,25 +157,53 @@ impl Metrics {
         self.http_requests_total
             .with_label_values(&[method, path, &status_code.to_string()])
             .inc();
-        self.http_request_duration.observe(duration);
+        self.http_request_duration.with_label_values(&[method, path]).observe(duration);
     }
 
     pub fn record_github_api_request(&self, endpoint: &str, method: &str, duration: f64) {
         self.github_api_requests_total
             .with_label_values(&[endpoint, method])
             .inc();
-        self.github_api_request_duration.observe(duration);
+        self.github_api_request_duration.with_label_values(&[endpoint]).observe(duration);
     }
 
-    pub fn update_github_rate_limit(&self, remaining: f64) {
-        self.github_api_rate_limit_remaining.set(remaining);
+    /// Replace the remaining counts with `remaining`, by resource
+    pub fn set_github_rate_limits(&self, remaining: &HashMap<String, u64>) {
+        self.github_api_rate_limit_remaining.reset();
+        for (resource, remaining) in remaining {
+            self.github_api_rate_limit_remaining.with_label_values(&[resource]).set(*remaining as f64);
+        }
     }
 
     pub fn record_mcp_command(&self, command: &str, status: &str, duration: f64) {
         self.mcp_commands_total
             .with_label_values(&[command, status])
             .inc();
-        self.mcp_command_duration.observe(duration);
+        self.mcp_command_duration.with_label_values(&[command]).observe(duration);
+    }
+
+    pub fn record_tool_call(&self, command: &str, success: bool) {
+        let status = if success { "success" } else { "error" };
+        self.tool_calls_total.with_label_values(&[command, status]).inc();
+    }
+
+    pub fn set_background_jobs(&self, state: &str, count: i64) {
+        self.background_jobs.with_label_values(&[state]).set(count);
+    }
+
+    /// Replace the per-command ratios and percentiles with `stats`
+    pub fn set_command_stats(&self, stats: &[CommandStats]) {
+        self.tool_success_ratio.reset();
+        self.workflow_duration.reset();
+        for s in stats {
+            self.tool_success_ratio.with_label_values(&[&s.command]).set(s.success_ratio());
+            self.workflow_duration
+                .with_label_values(&[&s.command, "0.5"])
+                .set(s.p50_ms as f64 / 1000.0);
+            self.workflow_duration
+                .with_label_values(&[&s.command, "0.95"])
+                .set(s.p95_ms as f64 / 1000.0);
+        }
     }
 
     pub fn set_active_connections(&self, count: f64) {
@@ -132,7 +215,36 @@ impl Metrics {
     }
 }
 
-pub async fn metrics_handler(State(metrics): State<Arc<Metrics>>) -> impl IntoResponse {
+/// Count and time every HTTP request, labelled by its route rather than the
+/// raw path so IDs in URLs don't each become a series
+pub async fn track_http_requests(State(state): State<AppState>, req: Request<Body>, next: Next) -> Response {
+    let method = req.method().to_string();
+    let path = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let started = std::time::Instant::now();
+
+    let response = next.run(req).await;
+    state
+        .metrics
+        .record_http_request(&method, &path, response.status().as_u16(), started.elapsed().as_secs_f64());
+    response
+}
+
+pub async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let metrics = &state.metrics;
+    for (job_state, count) in state.scheduler.job_counts() {
+        metrics.set_background_jobs(job_state, count);
+    }
+    metrics.set_github_rate_limits(&crate::github::budget::remaining_by_resource());
+    metrics.rate_limiters.set(state.ip_limiters.len().await as i64);
+    match history::stats(&state.db).await {
+        Ok(stats) => metrics.set_command_stats(&stats),
+        Err(e) => warn!("Failed to load command history for metrics: {}", e),
+    }
+
     let encoder = TextEncoder::new();
     let metric_families = metrics.registry.gather();
     
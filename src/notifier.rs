@@ -0,0 +1,221 @@
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::config::NotificationConfig;
+use crate::error::{AppError, Result};
+use crate::scheduler::Priority;
+use crate::AppState;
+
+/// How often held notifications are checked for a due digest
+const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Delivers user-facing notifications (reminders and the like) to a
+/// Slack-compatible webhook, or to the log when none is configured.
+/// A channel in digest mode holds notifications and sends one summary per
+/// recipient each hour or day instead.
+#[derive(Clone)]
+pub struct Notifier {
+    client: Client,
+    webhook_url: Option<String>,
+    db: sqlx::SqlitePool,
+    /// Digest window of the active channel; None delivers immediately
+    digest_minutes: Option<i64>,
+}
+
+impl Notifier {
+    pub fn new(config: &NotificationConfig, db: sqlx::SqlitePool) -> Self {
+        let mode = if config.webhook_url.is_some() { &config.webhook_digest } else { &config.log_digest };
+        Self {
+            client: Client::new(),
+            webhook_url: config.webhook_url.clone(),
+            db,
+            digest_minutes: match mode.as_str() {
+                "hourly" => Some(60),
+                "daily" => Some(24 * 60),
+                _ => None,
+            },
+        }
+    }
+
+    fn channel(&self) -> &'static str {
+        if self.webhook_url.is_some() { "webhook" } else { "log" }
+    }
+
+    /// Send `text` with structured `details` for receivers that want them, or
+    /// hold it for `recipient`'s next digest. Delivery failures are returned
+    /// so callers can decide whether to retry.
+    pub async fn notify(&self, recipient: Option<&str>, event: &str, text: &str, details: Value) -> Result<()> {
+        if self.digest_minutes.is_none() {
+            return self.deliver(event, text, &details).await;
+        }
+
+        let channel = self.channel();
+        let recipient = recipient.unwrap_or_default();
+        let details = details.to_string();
+        sqlx::query!(
+            "INSERT INTO pending_notifications (channel, recipient, event, text, details) VALUES (?, ?, ?, ?, ?)",
+            channel,
+            recipient,
+            event,
+            text,
+            details
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn deliver(&self, event: &str, text: &str, details: &Value) -> Result<()> {
+        let Some(url) = &self.webhook_url else {
+            info!("🔔 [{}] {}", event, text);
+            return Ok(());
+        };
+
+        let response = self
+            .client
+            .post(url)
+            .json(&json!({
+                "text": text,
+                "event": event,
+                "details": details
+            }))
+            .send()
+            .await
+            .map_err(AppError::HttpClient)?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            warn!("Notification webhook returned {}", status);
+            return Err(AppError::Internal(format!("Notification delivery failed: {}", status)));
+        }
+
+        Ok(())
+    }
+
+    /// Send a digest to every recipient whose oldest held notification has
+    /// waited a full window; returns the number of digests sent
+    pub async fn flush_digests(&self) -> Result<usize> {
+        let Some(minutes) = self.digest_minutes else {
+            return Ok(0);
+        };
+        let channel = self.channel();
+        let window = format!("-{} minutes", minutes);
+
+        let due = sqlx::query!(
+            r#"
+            SELECT recipient as "recipient!" FROM pending_notifications
+            WHERE channel = ?
+            GROUP BY recipient
+            HAVING MIN(created_at) <= datetime('now', ?)
+            "#,
+            channel,
+            window
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut sent = 0;
+        for row in due {
+            // One recipient's failed delivery shouldn't hold back the others
+            match self.flush_digest(channel, &row.recipient, minutes).await {
+                Ok(true) => sent += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Failed to send the notification digest for {:?}: {}", row.recipient, e),
+            }
+        }
+
+        Ok(sent)
+    }
+
+    /// Send `recipient` one digest of everything held for them; false when
+    /// nothing was held
+    async fn flush_digest(&self, channel: &str, recipient: &str, minutes: i64) -> Result<bool> {
+        let pending = sqlx::query!(
+            r#"
+            SELECT id as "id!", event, text, details, created_at as "created_at!: String"
+            FROM pending_notifications
+            WHERE channel = ? AND recipient = ?
+            ORDER BY id
+            "#,
+            channel,
+            recipient
+        )
+        .fetch_all(&self.db)
+        .await?;
+        let Some(last_id) = pending.last().map(|p| p.id) else {
+            return Ok(false);
+        };
+
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut lines = Vec::new();
+        let mut items = Vec::new();
+        for item in &pending {
+            *counts.entry(item.event.as_str()).or_default() += 1;
+            let details: Value = serde_json::from_str(&item.details).unwrap_or(Value::Null);
+            match details["url"].as_str() {
+                Some(url) => lines.push(format!("• {} ({})", item.text, url)),
+                None => lines.push(format!("• {}", item.text)),
+            }
+            items.push(json!({
+                "event": item.event,
+                "text": item.text,
+                "details": details,
+                "created_at": item.created_at
+            }));
+        }
+
+        let period = if minutes >= 24 * 60 { "Daily" } else { "Hourly" };
+        let audience = if recipient.is_empty() { String::new() } else { format!(" for @{}", recipient) };
+        let breakdown: Vec<String> = counts.iter().map(|(event, n)| format!("{} {}", n, event)).collect();
+        let text = format!(
+            "📬 {} digest{}: {} notification(s) ({})\n{}",
+            period,
+            audience,
+            pending.len(),
+            breakdown.join(", "),
+            lines.join("\n")
+        );
+
+        self.deliver("digest", &text, &json!({
+            "recipient": (!recipient.is_empty()).then_some(recipient),
+            "counts": counts,
+            "items": items
+        }))
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM pending_notifications WHERE channel = ? AND recipient = ? AND id <= ?",
+            channel,
+            recipient,
+            last_id
+        )
+        .execute(&self.db)
+        .await?;
+
+        Ok(true)
+    }
+}
+
+/// Periodically send due digests. Only runs when the active channel is in
+/// digest mode.
+pub fn spawn_digest_scheduler(state: AppState) {
+    if state.notifier.digest_minutes.is_none() {
+        return;
+    }
+
+    let scheduler = state.scheduler.clone();
+    scheduler.spawn("notification_digests", Priority::Normal, DIGEST_CHECK_INTERVAL, move || {
+        let state = state.clone();
+        async move {
+            let sent = state.notifier.flush_digests().await?;
+            if sent > 0 {
+                info!("Sent {} notification digests", sent);
+            }
+            Ok(())
+        }
+    });
+}
@@ -0,0 +1,220 @@
+// Tool calls held for a human's approval.
+//
+// Mutating tools named in `CONFIRM_TOOLS` don't run when called. The call is
+// stored as a pending operation, with who made it, and the caller gets its
+// `approval_id`. Once an admin other than the caller approves it, through
+// `POST /admin/operations/:id/approve` or the `approve_operation` tool, the
+// original call runs as the caller with its original arguments and the
+// result is kept on the operation.
+
+use rand::RngCore;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use crate::{AppState, error::{AppError, Result}, mcp::caller::{self, Caller}};
+
+/// How long an operation waits for approval before it can no longer run
+const PENDING_TTL: &str = "+24 hours";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingOperation {
+    pub id: String,
+    pub tool: String,
+    pub arguments: Value,
+    /// pending, approved (while running), rejected, executed or failed
+    pub status: String,
+    pub result: Option<Value>,
+    pub requested_by: Option<String>,
+    pub decided_by: Option<String>,
+    pub created_at: String,
+    pub decided_at: Option<String>,
+    pub expires_at: String,
+}
+
+/// The tool that approves held calls, which is never held itself
+pub const APPROVE_TOOL: &str = "approve_operation";
+
+/// Whether calls to `tool` must wait for approval. Patterns in
+/// `CONFIRM_TOOLS` are tool names, optionally ending in `*`.
+pub fn requires_confirmation(state: &AppState, tool: &str) -> bool {
+    tool != APPROVE_TOOL && state.config.security.confirm_tools.iter().any(|pattern| match pattern.strip_suffix('*') {
+        Some(prefix) => tool.starts_with(prefix),
+        None => pattern == tool,
+    })
+}
+
+/// Hold the caller's call to `tool` for approval, returning what they get
+/// instead of the tool's result
+pub async fn hold(state: &AppState, tool: &str, arguments: &Value) -> Result<Value> {
+    let requester = caller::require()?;
+    let requester_id = requester.user_id as i64;
+    let mut id = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut id);
+    let id = hex::encode(id);
+    let arguments_json = arguments.to_string();
+
+    let expires_at = sqlx::query_scalar!(
+        r#"
+        INSERT INTO pending_operations (id, tool, arguments, requested_by, requested_by_id, expires_at)
+        VALUES (?, ?, ?, ?, ?, datetime('now', ?))
+        RETURNING expires_at as "expires_at!: String"
+        "#,
+        id,
+        tool,
+        arguments_json,
+        requester.login,
+        requester_id,
+        PENDING_TTL
+    )
+    .fetch_one(&state.db)
+    .await?;
+
+    info!("Holding {}'s call to {} for approval as operation {}", requester.login, tool, id);
+    Ok(json!({
+        "status": "pending_approval",
+        "approval_id": id,
+        "tool": tool,
+        "expires_at": expires_at,
+        "message": format!(
+            "{} needs an admin's approval before it runs. Another admin can approve it with approve_operation or POST /admin/operations/{}/approve",
+            tool, id
+        )
+    }))
+}
+
+pub async fn list(db: &sqlx::SqlitePool, status: Option<&str>) -> Result<Vec<PendingOperation>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT id as "id!", tool, arguments, status, result, requested_by, decided_by,
+               created_at as "created_at!: String", decided_at as "decided_at?: String",
+               expires_at as "expires_at!: String"
+        FROM pending_operations
+        WHERE ?1 IS NULL OR status = ?1
+        ORDER BY created_at DESC
+        LIMIT 200
+        "#,
+        status
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| PendingOperation {
+            arguments: serde_json::from_str(&row.arguments).unwrap_or(Value::Null),
+            result: row.result.map(|r| serde_json::from_str(&r).unwrap_or(Value::String(r))),
+            id: row.id,
+            tool: row.tool,
+            status: row.status,
+            requested_by: row.requested_by,
+            decided_by: row.decided_by,
+            created_at: row.created_at,
+            decided_at: row.decided_at,
+            expires_at: row.expires_at,
+        })
+        .collect())
+}
+
+/// Approve operation `id` and run it as its requester, returning the tool's
+/// result. Only an admin other than the requester can approve, and only a
+/// pending, unexpired operation runs, once however many approve it.
+pub async fn approve(state: &AppState, id: &str, approver: &Caller) -> Result<Value> {
+    if !approver.admin {
+        return Err(AppError::Authorization(format!("{} is not an administrator", approver.login)));
+    }
+    let approver_id = approver.user_id as i64;
+
+    let claimed = sqlx::query!(
+        r#"
+        UPDATE pending_operations
+        SET status = 'approved', decided_by = ?, decided_at = datetime('now')
+        WHERE id = ? AND status = 'pending' AND expires_at > datetime('now')
+          AND requested_by_id IS NOT NULL AND requested_by_id != ?
+        RETURNING tool, arguments, requested_by as "requested_by!", requested_by_id as "requested_by_id!: i64"
+        "#,
+        approver.login,
+        id,
+        approver_id
+    )
+    .fetch_optional(&state.db)
+    .await?;
+    let Some(claimed) = claimed else {
+        let requested_by_id = sqlx::query_scalar!(
+            "SELECT requested_by_id FROM pending_operations WHERE id = ? AND status = 'pending'",
+            id
+        )
+        .fetch_optional(&state.db)
+        .await?
+        .flatten();
+        return Err(match requested_by_id {
+            Some(requester) if requester == approver_id => {
+                AppError::Authorization(format!("{} can't approve their own operation {}", approver.login, id))
+            }
+            _ => AppError::Validation(format!("No pending operation {}; it may have expired or been decided", id)),
+        });
+    };
+
+    info!("{} approved operation {} ({}) for {}", approver.login, id, claimed.tool, claimed.requested_by);
+    let requester = Caller {
+        user_id: claimed.requested_by_id as u64,
+        login: claimed.requested_by,
+        // Approval lets the call run, not run with more than its caller had
+        admin: false,
+    };
+    let arguments: Value = serde_json::from_str(&claimed.arguments)?;
+    let outcome = caller::scope(Some(requester), run(state, &claimed.tool, arguments)).await;
+
+    let (status, stored) = match &outcome {
+        Ok(result) => ("executed", result.to_string()),
+        Err(e) => ("failed", e.to_string()),
+    };
+    if let Err(e) = sqlx::query!(
+        "UPDATE pending_operations SET status = ?, result = ? WHERE id = ?",
+        status,
+        stored,
+        id
+    )
+    .execute(&state.db)
+    .await
+    {
+        warn!("Failed to record the outcome of operation {}: {}", id, e);
+    }
+
+    outcome
+}
+
+/// Run the held call, applying the checks that still hold at approval time
+async fn run(state: &AppState, tool_name: &str, arguments: Value) -> Result<Value> {
+    let tool = state
+        .tools
+        .get(tool_name)
+        .filter(|_| !state.tools.is_disabled(tool_name))
+        .ok_or_else(|| AppError::Validation(format!("Tool {} is no longer available", tool_name)))?;
+    if state.config.security.read_only && !tool.read_only() {
+        return Err(AppError::ReadOnly(tool_name.to_string()));
+    }
+
+    tool.execute(state.clone(), arguments).await
+}
+
+/// Reject operation `id` so it never runs; false if it wasn't pending or
+/// `rejecter` is neither an admin nor its requester
+pub async fn reject(db: &sqlx::SqlitePool, id: &str, rejecter: &Caller) -> Result<bool> {
+    let rejecter_id = rejecter.user_id as i64;
+    let result = sqlx::query!(
+        r#"
+        UPDATE pending_operations
+        SET status = 'rejected', decided_by = ?, decided_at = datetime('now')
+        WHERE id = ? AND status = 'pending' AND (? OR requested_by_id = ?)
+        "#,
+        rejecter.login,
+        id,
+        rejecter.admin,
+        rejecter_id
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
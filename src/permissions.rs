@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, error::{AppError, Result}};
+
+/// What a command does to its repository, checked against the caller's rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Read,
+    /// Any write: pushes, file edits, issues, comments, reviews
+    Push,
+    /// Merging, releasing and promoting
+    Merge,
+    /// Repository settings, branch protection and advisories
+    Admin,
+}
+
+impl Operation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Read => "read",
+            Operation::Push => "push",
+            Operation::Merge => "merge",
+            Operation::Admin => "admin",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "read" => Some(Operation::Read),
+            "push" => Some(Operation::Push),
+            "merge" => Some(Operation::Merge),
+            "admin" => Some(Operation::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// Operations `login` (or `*`) may perform on repositories matching
+/// `repository`: `owner/name`, `owner/*` or `*`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionRule {
+    #[serde(default)]
+    pub id: i64,
+    pub login: String,
+    pub repository: String,
+    pub operations: Vec<Operation>,
+}
+
+impl PermissionRule {
+    /// How closely the rule matches; None when it doesn't apply. An exact
+    /// login beats `*`, and an exact repository beats `owner/*` beats `*`.
+    fn specificity(&self, login: &str, repository: &str) -> Option<u8> {
+        let login_score = match self.login.as_str() {
+            "*" => 0,
+            rule if rule.eq_ignore_ascii_case(login) => 4,
+            _ => return None,
+        };
+        let repository_score = match self.repository.as_str() {
+            "*" => 0,
+            rule if rule.eq_ignore_ascii_case(repository) => 2,
+            rule => match rule.strip_suffix("/*") {
+                Some(owner) if repository.split('/').next().map_or(false, |o| o.eq_ignore_ascii_case(owner)) => 1,
+                _ => return None,
+            },
+        };
+        Some(login_score + repository_score)
+    }
+}
+
+/// The most specific rule for `login` on `repository`
+pub fn matching_rule<'a>(rules: &'a [PermissionRule], login: &str, repository: &str) -> Option<&'a PermissionRule> {
+    rules
+        .iter()
+        .filter_map(|rule| rule.specificity(login, repository).map(|score| (score, rule)))
+        .max_by_key(|(score, _)| *score)
+        .map(|(_, rule)| rule)
+}
+
+pub async fn list(db: &sqlx::SqlitePool) -> Result<Vec<PermissionRule>> {
+    let rows = sqlx::query!(
+        r#"SELECT id as "id!", login, repository, operations FROM repository_permissions ORDER BY login, repository"#
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| PermissionRule {
+            id: row.id,
+            login: row.login,
+            repository: row.repository,
+            operations: row.operations.split(',').filter_map(Operation::parse).collect(),
+        })
+        .collect())
+}
+
+/// Whether any rules are configured, i.e. whether the policy is enforced
+pub async fn enabled(db: &sqlx::SqlitePool) -> Result<bool> {
+    let row = sqlx::query!(r#"SELECT EXISTS(SELECT 1 FROM repository_permissions) as "exists!: bool""#)
+        .fetch_one(db)
+        .await?;
+
+    Ok(row.exists)
+}
+
+pub async fn save(db: &sqlx::SqlitePool, rule: &PermissionRule) -> Result<()> {
+    let valid_repository = rule.repository == "*"
+        || rule.repository.split_once('/').map_or(false, |(owner, name)| !owner.is_empty() && !name.is_empty());
+    if rule.login.trim().is_empty() || !valid_repository {
+        return Err(AppError::Validation(
+            "A permission needs a login (or *) and a repository as owner/name, owner/* or *".to_string(),
+        ));
+    }
+
+    let operations: Vec<&str> = rule.operations.iter().map(Operation::as_str).collect();
+    let operations = operations.join(",");
+    sqlx::query!(
+        r#"
+        INSERT INTO repository_permissions (login, repository, operations)
+        VALUES (?, ?, ?)
+        ON CONFLICT(login, repository) DO UPDATE SET operations = excluded.operations
+        "#,
+        rule.login,
+        rule.repository,
+        operations
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn remove(db: &sqlx::SqlitePool, id: i64) -> Result<bool> {
+    let result = sqlx::query!("DELETE FROM repository_permissions WHERE id = ?", id)
+        .execute(db)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Refuse `operation` on `repository` unless the caller's most specific rule
+/// allows it. With no rules configured every operation is allowed, leaving
+/// GitHub's own permissions in charge.
+pub async fn authorize(state: &AppState, repository: &str, operation: Operation) -> Result<()> {
+    let rules = list(&state.db).await?;
+    if rules.is_empty() {
+        return Ok(());
+    }
+
+    // The session token names the caller, so this costs no GitHub request
+    let login = crate::mcp::caller::require()?.login;
+
+    let allowed = matching_rule(&rules, &login, repository).map_or(false, |rule| rule.operations.contains(&operation));
+    if !allowed {
+        return Err(AppError::Authorization(format!(
+            "{} may not {} on {} under the server's repository permissions",
+            login,
+            operation.as_str(),
+            repository
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use crate::{AppState, error::{AppError, Result}, github::tasks::ProjectRef};
+
+/// Per-user preferences, stored as JSON so new keys don't need migrations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserPreferences {
+    /// Projects scanned by `github_scan_tasks` with `all_projects`
+    #[serde(default)]
+    pub scan_projects: Vec<ProjectRef>,
+    /// Named `github_scan_tasks` criteria, applied with its `filter` argument
+    #[serde(default)]
+    pub scan_filters: BTreeMap<String, SavedFilter>,
+}
+
+/// Scan criteria saved under a name; arguments passed alongside the name
+/// take precedence over the saved ones
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub projects: Option<Vec<ProjectRef>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub all_projects: Option<bool>,
+}
+
+pub async fn load(db: &sqlx::SqlitePool, login: &str) -> Result<UserPreferences> {
+    let row = sqlx::query!("SELECT preferences FROM user_preferences WHERE login = ?", login)
+        .fetch_optional(db)
+        .await?;
+
+    match row {
+        Some(row) => Ok(serde_json::from_str(&row.preferences)?),
+        None => Ok(UserPreferences::default()),
+    }
+}
+
+pub async fn save(db: &sqlx::SqlitePool, login: &str, preferences: &UserPreferences) -> Result<()> {
+    let encoded = serde_json::to_string(preferences)?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO user_preferences (login, preferences, updated_at)
+        VALUES (?, ?, datetime('now'))
+        ON CONFLICT(login) DO UPDATE SET
+            preferences = excluded.preferences,
+            updated_at = excluded.updated_at
+        "#,
+        login,
+        encoded
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+fn caller_login() -> Result<String> {
+    Ok(crate::mcp::caller::require()?.login)
+}
+
+/// The caller's saved filter `name`
+pub async fn find_filter(state: &AppState, name: &str) -> Result<SavedFilter> {
+    let login = caller_login()?;
+    load(&state.db, &login).await?.scan_filters.remove(name).ok_or_else(|| {
+        AppError::Validation(format!("No saved filter named {}. See github_filters_list", name))
+    })
+}
+
+fn validate_filter_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(AppError::Validation(format!(
+            "Invalid filter name: {}. Use up to 64 letters, digits, '-', '_' or '.'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// `github_preferences` tool: returns the caller's preferences, applying any
+/// keys present in `arguments` first
+pub async fn handle_preferences_tool(state: AppState, arguments: &Value) -> Result<Value> {
+    let login = caller_login()?;
+
+    let mut preferences = load(&state.db, &login).await?;
+    let mut updated = false;
+
+    if let Some(projects) = arguments.get("scan_projects") {
+        preferences.scan_projects = serde_json::from_value(projects.clone())
+            .map_err(|e| AppError::Validation(format!("Invalid scan_projects: {}", e)))?;
+        updated = true;
+    }
+
+    // Merged by name; null removes a filter
+    if let Some(filters) = arguments.get("scan_filters") {
+        let filters: BTreeMap<String, Option<SavedFilter>> = serde_json::from_value(filters.clone())
+            .map_err(|e| AppError::Validation(format!("Invalid scan_filters: {}", e)))?;
+        for (name, filter) in filters {
+            match filter {
+                Some(filter) => {
+                    validate_filter_name(&name)?;
+                    preferences.scan_filters.insert(name, filter);
+                }
+                None => {
+                    preferences.scan_filters.remove(&name);
+                }
+            }
+        }
+        updated = true;
+    }
+
+    if updated {
+        save(&state.db, &login, &preferences).await?;
+    }
+
+    Ok(json!({
+        "login": login,
+        "preferences": preferences,
+        "updated": updated
+    }))
+}
+
+/// `github_filters_list` tool: the caller's saved scan filters
+pub async fn handle_filters_list_tool(state: AppState) -> Result<Value> {
+    let login = caller_login()?;
+    let filters = load(&state.db, &login).await?.scan_filters;
+
+    Ok(json!({
+        "login": login,
+        "count": filters.len(),
+        "filters": filters
+    }))
+}
@@ -0,0 +1,52 @@
+// Git and merge-check subprocesses run in their own process group, so a
+// timeout or cancellation kills everything they started (ssh, credential
+// helpers, test runners), not just the direct child. Process groups are a
+// Unix feature; elsewhere only the direct child is killed.
+
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
+/// Start `command` as the leader of a new process group
+#[cfg(unix)]
+pub fn isolate(command: &mut std::process::Command) -> &mut std::process::Command {
+    command.process_group(0)
+}
+
+#[cfg(not(unix))]
+pub fn isolate(command: &mut std::process::Command) -> &mut std::process::Command {
+    command
+}
+
+/// Kill every process in the group led by `pid`
+#[cfg(unix)]
+pub fn kill_group(pid: u32) {
+    // SAFETY: kill(2) takes no pointers; a group that already exited is an ignored ESRCH
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+pub fn kill_group(_pid: u32) {}
+
+/// Kills a process group when dropped, such as with the future of a
+/// cancelled request, unless disarmed once the group's leader has exited
+pub struct GroupGuard(Option<u32>);
+
+impl GroupGuard {
+    pub fn new(pid: Option<u32>) -> Self {
+        Self(pid)
+    }
+
+    pub fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for GroupGuard {
+    fn drop(&mut self) {
+        if let Some(pid) = self.0 {
+            kill_group(pid);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use crate::{AppState, config::RepositoryConfig, error::{AppError, Result}, mcp::caller};
+
+/// A registered checkout: a local path plus the GitHub repository it tracks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Repository {
+    pub name: String,
+    pub path: String,
+    pub owner: String,
+    pub repo: String,
+}
+
+impl Repository {
+    pub fn full_name(&self) -> String {
+        format!("{}/{}", self.owner, self.repo)
+    }
+}
+
+pub async fn get(db: &sqlx::SqlitePool, name: &str) -> Result<Repository> {
+    sqlx::query_as!(
+        Repository,
+        r#"SELECT name as "name!", path, owner, repo FROM repositories WHERE name = ?"#,
+        name
+    )
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| AppError::Validation(format!("Unknown repository: {}. Register it with github_repositories", name)))
+}
+
+pub async fn list(db: &sqlx::SqlitePool) -> Result<Vec<Repository>> {
+    let repositories = sqlx::query_as!(
+        Repository,
+        r#"SELECT name as "name!", path, owner, repo FROM repositories ORDER BY name"#
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(repositories)
+}
+
+pub async fn save(db: &sqlx::SqlitePool, repository: &Repository) -> Result<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO repositories (name, path, owner, repo, updated_at)
+        VALUES (?, ?, ?, ?, datetime('now'))
+        ON CONFLICT(name) DO UPDATE SET
+            path = excluded.path,
+            owner = excluded.owner,
+            repo = excluded.repo,
+            updated_at = excluded.updated_at
+        "#,
+        repository.name,
+        repository.path,
+        repository.owner,
+        repository.repo
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn remove(db: &sqlx::SqlitePool, name: &str) -> Result<bool> {
+    let result = sqlx::query!("DELETE FROM repositories WHERE name = ?", name)
+        .execute(db)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Register `path` under `name`, reading owner/repo from its `origin` remote
+/// when not given explicitly
+pub async fn register(
+    db: &sqlx::SqlitePool,
+    name: &str,
+    path: &str,
+    full_name: Option<&str>,
+) -> Result<Repository> {
+    let checkout = PathBuf::from(path);
+    let root = crate::git::scope(Some(checkout), crate::git::run(|g, dir| g.repo_root(dir)))
+        .await
+        .map_err(|_| AppError::Validation(format!("{} is not a git checkout", path)))?;
+
+    let (owner, repo) = match full_name {
+        Some(full_name) => full_name
+            .split_once('/')
+            .map(|(o, r)| (o.to_string(), r.to_string()))
+            .ok_or_else(|| AppError::Validation(format!("Invalid repository: {}", full_name)))?,
+        None => crate::git::scope(Some(root.clone()), crate::github::workflows::get_remote_repository()).await?,
+    };
+
+    let repository = Repository {
+        name: name.to_string(),
+        path: root.to_string_lossy().to_string(),
+        owner,
+        repo,
+    };
+    save(db, &repository).await?;
+    Ok(repository)
+}
+
+/// Register the checkouts listed in `REPOSITORIES`; entries already in the DB
+/// are updated. An entry that can't be registered is skipped, not fatal.
+pub async fn sync_from_config(db: &sqlx::SqlitePool, configured: &[RepositoryConfig]) -> Result<()> {
+    for entry in configured {
+        match register(db, &entry.name, &entry.path, entry.full_name.as_deref()).await {
+            Ok(repository) => {
+                info!("Registered repository {} -> {} ({})", repository.name, repository.path, repository.full_name())
+            }
+            Err(e) => warn!("Skipping repository {} from REPOSITORIES: {}", entry.name, e),
+        }
+    }
+
+    Ok(())
+}
+
+/// `path` resolved, refusing anything outside `checkouts_dir`. A registered
+/// checkout's `.mcp-workflow.toml` decides the commands merges run, so only
+/// the operator's directory is trusted.
+fn checkout_path(checkouts_dir: &str, path: &str) -> Result<String> {
+    let base = std::fs::canonicalize(checkouts_dir)
+        .map_err(|e| AppError::Validation(format!("CHECKOUTS_DIR {} is not usable: {}", checkouts_dir, e)))?;
+    let checkout = std::fs::canonicalize(path)
+        .map_err(|_| AppError::Validation(format!("{} is not a git checkout", path)))?;
+    if !checkout.starts_with(&base) {
+        return Err(AppError::Validation(format!("{} is outside CHECKOUTS_DIR ({})", path, checkouts_dir)));
+    }
+    Ok(checkout.to_string_lossy().to_string())
+}
+
+/// `github_repositories` tool: list the registry, or add/remove an entry
+pub async fn handle_repositories_tool(state: AppState, arguments: &Value) -> Result<Value> {
+    let action = arguments.get("action").and_then(|v| v.as_str()).unwrap_or("list");
+    let name = arguments.get("name").and_then(|v| v.as_str());
+
+    if action != "list" {
+        let caller = caller::require()?;
+        if !caller.admin {
+            return Err(AppError::Authorization(format!("Only administrators can {} repositories", action)));
+        }
+    }
+
+    match action {
+        "list" => {}
+        "add" => {
+            let name = name.ok_or_else(|| AppError::Validation("name is required".to_string()))?;
+            let path = arguments
+                .get("path")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| AppError::Validation("path is required".to_string()))?;
+            let full_name = arguments.get("repository").and_then(|v| v.as_str());
+            let path = checkout_path(&state.config.workflow.checkouts_dir, path)?;
+            register(&state.db, name, &path, full_name).await?;
+        }
+        "remove" => {
+            let name = name.ok_or_else(|| AppError::Validation("name is required".to_string()))?;
+            if !remove(&state.db, name).await? {
+                return Err(AppError::Validation(format!("Unknown repository: {}", name)));
+            }
+        }
+        other => return Err(AppError::Validation(format!("Unknown action: {}", other))),
+    }
+
+    Ok(json!({
+        "action": action,
+        "repositories": list(&state.db).await?
+    }))
+}
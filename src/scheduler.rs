@@ -0,0 +1,271 @@
+// Background job runner that spends the GitHub rate limit around interactive
+// use. Jobs declare a priority class; before each run the scheduler checks
+// the budget and defers the job, rather than failing it, when the headroom
+// for that class is used up or tool calls are in flight.
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::task::AbortHandle;
+use tracing::{debug, info, warn};
+
+use crate::config::SchedulerConfig;
+use crate::error::Result;
+use crate::github::budget;
+
+/// How long background jobs wait while interactive calls are running
+const INTERACTIVE_BACKOFF: Duration = Duration::from_secs(15);
+
+/// Longest a deferred job sleeps before re-checking the budget
+const MAX_DEFERRAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    /// Tool calls; never deferred
+    Interactive,
+    /// Scans and cache warms
+    Normal,
+    /// Nudges and reminders
+    Low,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub name: &'static str,
+    pub priority: Priority,
+    pub interval_seconds: u64,
+    pub next_run_at: Option<String>,
+    pub last_run_at: Option<String>,
+    pub last_error: Option<String>,
+    pub runs: u64,
+    pub deferrals: u64,
+    pub running: bool,
+    /// Why the job is currently waiting, if it was deferred
+    pub deferred_reason: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct Scheduler {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    config: SchedulerConfig,
+    interactive: AtomicUsize,
+    jobs: Mutex<BTreeMap<&'static str, JobStatus>>,
+    /// Each job's task, to stop it when an admin cancels the job
+    tasks: Mutex<HashMap<&'static str, AbortHandle>>,
+}
+
+/// Marks an interactive call as in flight until dropped
+pub struct InteractiveGuard {
+    inner: Arc<Inner>,
+}
+
+impl Drop for InteractiveGuard {
+    fn drop(&mut self) {
+        self.inner.interactive.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Scheduler {
+    pub fn new(config: &SchedulerConfig) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                config: config.clone(),
+                interactive: AtomicUsize::new(0),
+                jobs: Mutex::new(BTreeMap::new()),
+                tasks: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Hold for the duration of a tool call so background jobs yield to it
+    pub fn interactive(&self) -> InteractiveGuard {
+        self.inner.interactive.fetch_add(1, Ordering::SeqCst);
+        InteractiveGuard { inner: self.inner.clone() }
+    }
+
+    /// Run `job` every `interval`, starting immediately, deferring runs the
+    /// budget can't currently afford
+    pub fn spawn<F, Fut>(&self, name: &'static str, priority: Priority, interval: Duration, job: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send,
+    {
+        self.update(name, |status| {
+            *status = Some(JobStatus {
+                name,
+                priority,
+                interval_seconds: interval.as_secs(),
+                next_run_at: Some(chrono::Utc::now().to_rfc3339()),
+                last_run_at: None,
+                last_error: None,
+                runs: 0,
+                deferrals: 0,
+                running: false,
+                deferred_reason: None,
+            });
+        });
+        info!("Scheduled background job {} every {}s ({:?} priority)", name, interval.as_secs(), priority);
+
+        let scheduler = self.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let wait = match scheduler.admit(priority) {
+                    Ok(()) => {
+                        scheduler.update(name, |status| {
+                            if let Some(status) = status {
+                                status.running = true;
+                                status.deferred_reason = None;
+                            }
+                        });
+                        let result = job().await;
+                        if let Err(e) = &result {
+                            warn!("Background job {} failed: {}", name, e);
+                        }
+                        scheduler.update(name, |status| {
+                            if let Some(status) = status {
+                                status.runs += 1;
+                                status.last_run_at = Some(chrono::Utc::now().to_rfc3339());
+                                status.last_error = result.as_ref().err().map(|e| e.to_string());
+                                status.running = false;
+                            }
+                        });
+                        interval
+                    }
+                    Err((wait, reason)) => {
+                        debug!("Deferring background job {} for {}s: {}", name, wait.as_secs(), reason);
+                        scheduler.update(name, |status| {
+                            if let Some(status) = status {
+                                status.deferrals += 1;
+                                status.deferred_reason = Some(reason);
+                            }
+                        });
+                        wait
+                    }
+                };
+
+                let next = chrono::Utc::now() + chrono::Duration::from_std(wait).unwrap_or_default();
+                scheduler.update(name, |status| {
+                    if let Some(status) = status {
+                        status.next_run_at = Some(next.to_rfc3339());
+                    }
+                });
+                tokio::time::sleep(wait).await;
+            }
+        });
+        if let Ok(mut tasks) = self.inner.tasks.lock() {
+            tasks.insert(name, task.abort_handle());
+        }
+    }
+
+    /// Stop job `name` until the server restarts, interrupting its current
+    /// run if any; false if there's no such job
+    pub fn cancel(&self, name: &str) -> bool {
+        let task = self.inner.tasks.lock().ok().and_then(|mut tasks| tasks.remove(name));
+        let Some(task) = task else {
+            return false;
+        };
+        task.abort();
+        if let Ok(mut jobs) = self.inner.jobs.lock() {
+            jobs.remove(name);
+        }
+        info!("Cancelled background job {}", name);
+        true
+    }
+
+    /// Percentage of each rate-limit window that `priority` must leave untouched
+    fn reserve_percent(&self, priority: Priority) -> u8 {
+        match priority {
+            Priority::Interactive => 0,
+            Priority::Normal => self.inner.config.reserve_percent,
+            Priority::Low => self.inner.config.low_priority_reserve_percent,
+        }
+    }
+
+    /// Ok to run now, or how long to wait and why
+    fn admit(&self, priority: Priority) -> std::result::Result<(), (Duration, String)> {
+        if priority == Priority::Interactive {
+            return Ok(());
+        }
+
+        if self.inner.interactive.load(Ordering::SeqCst) > 0 {
+            return Err((INTERACTIVE_BACKOFF, "interactive requests in flight".to_string()));
+        }
+
+        let reserve = self.reserve_percent(priority);
+        if let Some(snapshot) = budget::tightest() {
+            if snapshot.remaining_fraction() * 100.0 < reserve as f64 {
+                let until_reset = (snapshot.reset_at - chrono::Utc::now().timestamp()).max(1) as u64;
+                return Err((
+                    Duration::from_secs(until_reset).min(MAX_DEFERRAL),
+                    format!(
+                        "{} budget at {}/{}, keeping {}% for interactive use",
+                        snapshot.resource, snapshot.remaining, snapshot.limit, reserve
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update(&self, name: &'static str, f: impl FnOnce(&mut Option<JobStatus>)) {
+        if let Ok(mut jobs) = self.inner.jobs.lock() {
+            let mut status = jobs.remove(name);
+            f(&mut status);
+            if let Some(status) = status {
+                jobs.insert(name, status);
+            }
+        }
+    }
+
+    /// Jobs per state: running, deferred or queued for their next run, plus
+    /// failed for those whose last run errored
+    pub fn job_counts(&self) -> BTreeMap<&'static str, i64> {
+        let mut counts: BTreeMap<&'static str, i64> =
+            ["running", "queued", "deferred", "failed"].into_iter().map(|s| (s, 0)).collect();
+        if let Ok(jobs) = self.inner.jobs.lock() {
+            for job in jobs.values() {
+                let state = if job.running {
+                    "running"
+                } else if job.deferred_reason.is_some() {
+                    "deferred"
+                } else {
+                    "queued"
+                };
+                *counts.entry(state).or_default() += 1;
+                if job.last_error.is_some() {
+                    *counts.entry("failed").or_default() += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Job schedule and budget state for the admin overview
+    pub fn overview(&self) -> Value {
+        let jobs: Vec<JobStatus> = self
+            .inner
+            .jobs
+            .lock()
+            .map(|jobs| jobs.values().cloned().collect())
+            .unwrap_or_default();
+
+        json!({
+            "interactive_in_flight": self.inner.interactive.load(Ordering::SeqCst),
+            "reserve_percent": {
+                "normal": self.reserve_percent(Priority::Normal),
+                "low": self.reserve_percent(Priority::Low)
+            },
+            "jobs": jobs
+        })
+    }
+}
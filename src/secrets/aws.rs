@@ -0,0 +1,22 @@
+// AWS Secrets Manager, with credentials and region from the default chain.
+
+use std::collections::HashMap;
+
+use crate::error::{AppError, Result};
+
+/// Fields of the JSON secret string `secret_id` (a name or ARN)
+pub async fn fetch(secret_id: &str) -> Result<HashMap<String, String>> {
+    let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+    let output = aws_sdk_secretsmanager::Client::new(&config)
+        .get_secret_value()
+        .secret_id(secret_id)
+        .send()
+        .await
+        .map_err(|e| AppError::Internal(format!("AWS Secrets Manager: {}", e)))?;
+
+    let text = output
+        .secret_string()
+        .ok_or_else(|| AppError::Internal(format!("Secret {} has no string value", secret_id)))?;
+    serde_json::from_str(text)
+        .map_err(|e| AppError::Internal(format!("Secret {} isn't a JSON object of strings: {}", secret_id, e)))
+}
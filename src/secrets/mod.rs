@@ -0,0 +1,126 @@
+// Secrets from a secrets manager instead of the environment.
+//
+// With `SECRETS_BACKEND=vault` (cargo feature `vault`) or `SECRETS_BACKEND=aws`
+// (cargo feature `aws-secrets`), `GITHUB_CLIENT_SECRET`, `JWT_SECRET` and
+// `TOKEN_ENCRYPTION_KEY` are read from one secret whose fields carry those
+// names, before the rest of the configuration loads; a field the secret
+// doesn't have falls back to the environment. With `SECRETS_REFRESH_SECONDS`
+// the secret is fetched again on that interval and changes take effect
+// without a restart. A new JWT secret signs from then on while the old one
+// keeps verifying, and a new token encryption key encrypts while the old one
+// keeps decrypting, so rotation logs nobody out.
+
+#[cfg(feature = "aws-secrets")]
+mod aws;
+#[cfg(feature = "vault")]
+mod vault;
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::config::{ConfigError, SecretsBackend, SecretsConfig};
+use crate::error::{AppError, Result};
+use crate::AppState;
+
+/// The names read from the secret; anything else in it is ignored
+pub const MANAGED: [&str; 3] = ["GITHUB_CLIENT_SECRET", "JWT_SECRET", "TOKEN_ENCRYPTION_KEY"];
+
+static CURRENT: OnceLock<RwLock<HashMap<String, String>>> = OnceLock::new();
+
+fn current() -> &'static RwLock<HashMap<String, String>> {
+    CURRENT.get_or_init(Default::default)
+}
+
+/// Fetch the secret and put its values in the environment for
+/// `Config::load`; does nothing without `SECRETS_BACKEND`
+pub async fn init() -> Result<()> {
+    let Some(config) = SecretsConfig::from_env()? else {
+        return Ok(());
+    };
+
+    let values = fetch(&config.backend).await?;
+    for (name, value) in &values {
+        std::env::set_var(name, value);
+    }
+    info!("Loaded {} secrets from {}", values.len(), config.backend.name());
+    if let Ok(mut current) = current().write() {
+        *current = values;
+    }
+    Ok(())
+}
+
+/// The secret's latest value for `name`, or `fallback` when it has none
+pub fn resolve(name: &str, fallback: &str) -> String {
+    current()
+        .read()
+        .ok()
+        .and_then(|current| current.get(name).cloned())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+async fn fetch(backend: &SecretsBackend) -> Result<HashMap<String, String>> {
+    let values: Result<HashMap<String, String>> = match backend {
+        #[cfg(feature = "vault")]
+        SecretsBackend::Vault { addr, token, path } => vault::fetch(addr, token, path).await,
+        #[cfg(feature = "aws-secrets")]
+        SecretsBackend::Aws { secret_id } => aws::fetch(secret_id).await,
+        #[allow(unreachable_patterns)]
+        other => Err(AppError::Config(ConfigError::ParseError(format!(
+            "SECRETS_BACKEND={} needs a build with the `{}` feature",
+            other.name(),
+            other.feature()
+        )))),
+    };
+
+    Ok(values?.into_iter().filter(|(name, _)| MANAGED.contains(&name.as_str())).collect())
+}
+
+/// Re-fetch the secret every `SECRETS_REFRESH_SECONDS` and apply what changed
+pub fn spawn_refresh(state: AppState) {
+    let Some(config) = state.config.secrets.clone() else {
+        return;
+    };
+    if config.refresh_seconds == 0 {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.refresh_seconds));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match fetch(&config.backend).await {
+                Ok(values) => apply(&state, values),
+                Err(e) => warn!("Keeping the current secrets; refreshing from {} failed: {}", config.backend.name(), e),
+            }
+        }
+    });
+}
+
+fn apply(state: &AppState, values: HashMap<String, String>) {
+    let Ok(mut current) = current().write() else {
+        return;
+    };
+
+    for (name, value) in &values {
+        if current.get(name) == Some(value) {
+            continue;
+        }
+        let applied = match name.as_str() {
+            "JWT_SECRET" => state.jwt.rotate_secret(value),
+            "TOKEN_ENCRYPTION_KEY" => crate::security::tokens::rotate_key(value),
+            // Read through `resolve` wherever it's used
+            _ => Ok(()),
+        };
+        match applied {
+            Ok(()) => info!("Rotated {} from the secrets manager", name),
+            Err(e) => {
+                warn!("Ignoring the new {}: {}", name, e);
+                continue;
+            }
+        }
+        current.insert(name.clone(), value.clone());
+    }
+}
@@ -0,0 +1,37 @@
+// HashiCorp Vault's KV secrets engine over its HTTP API.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::error::{AppError, Result};
+
+/// Fields of the secret at `path`, e.g. `secret/data/github-mcp-server` for
+/// KV version 2 or `secret/github-mcp-server` for version 1
+pub async fn fetch(addr: &str, token: &str, path: &str) -> Result<HashMap<String, String>> {
+    let url = format!("{}/v1/{}", addr.trim_end_matches('/'), path.trim_start_matches('/'));
+    let response = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?
+        .get(&url)
+        .header("X-Vault-Token", token)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(AppError::Internal(format!("Vault returned {} for {}", response.status(), path)));
+    }
+
+    let body: Value = response.json().await?;
+    // KV v2 nests the fields one level deeper than v1
+    let fields = match &body["data"]["data"] {
+        Value::Object(fields) => fields,
+        _ => body["data"]
+            .as_object()
+            .ok_or_else(|| AppError::Internal(format!("Vault secret {} has no data", path)))?,
+    };
+
+    Ok(fields
+        .iter()
+        .filter_map(|(name, value)| Some((name.clone(), value.as_str()?.to_string())))
+        .collect())
+}
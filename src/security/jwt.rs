@@ -0,0 +1,184 @@
+use jsonwebtoken::{decode, encode, errors::ErrorKind, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use super::JwtClaims;
+use crate::config::{Config, ConfigError};
+use crate::error::{AppError, Result};
+
+/// `role` claim of tokens issued to `ADMIN_USERS`
+pub const ADMIN_ROLE: &str = "admin";
+
+/// Most keys kept for verification as HS256 secrets rotate at runtime
+const MAX_VERIFYING_KEYS: usize = 8;
+
+struct Keys {
+    signing: EncodingKey,
+    verifying: Vec<DecodingKey>,
+}
+
+/// Signing key and every key still accepted for verification. Tokens are
+/// signed with the current key only; older keys keep verifying until their
+/// tokens expire, so secrets rotate without logging everyone out.
+#[derive(Clone)]
+pub struct JwtKeys {
+    algorithm: Algorithm,
+    /// Replaced when a secrets manager rotates `JWT_SECRET`
+    keys: Arc<RwLock<Keys>>,
+    issuer: String,
+    audience: String,
+    lifetime: chrono::Duration,
+    /// Logins whose tokens carry the admin role
+    admins: Vec<String>,
+    /// Per user ID, the issue time in milliseconds at or before which tokens
+    /// are refused, set when an admin forces the user to sign in again
+    reauth: Arc<RwLock<HashMap<u64, i64>>>,
+}
+
+impl JwtKeys {
+    pub fn from_config(config: &Config) -> std::result::Result<Self, ConfigError> {
+        let security = &config.security;
+        let invalid = |what: &str, e: jsonwebtoken::errors::Error| ConfigError::ParseError(format!("Invalid {}: {}", what, e));
+
+        let (algorithm, signing, verifying) = match security.jwt_algorithm.as_str() {
+            "HS256" => {
+                let verifying = std::iter::once(&config.jwt_secret)
+                    .chain(&security.jwt_previous_secrets)
+                    .map(|secret| DecodingKey::from_secret(secret.as_bytes()))
+                    .collect();
+                (Algorithm::HS256, EncodingKey::from_secret(config.jwt_secret.as_bytes()), verifying)
+            }
+            algorithm @ ("RS256" | "EdDSA") => {
+                let private_key = security
+                    .jwt_private_key_file
+                    .as_deref()
+                    .ok_or_else(|| ConfigError::MissingEnvVar("JWT_PRIVATE_KEY_FILE".to_string()))?;
+                if security.jwt_public_key_files.is_empty() {
+                    return Err(ConfigError::MissingEnvVar("JWT_PUBLIC_KEY_FILES".to_string()));
+                }
+                let private_pem = read_pem(private_key)?;
+                let public_pems = security
+                    .jwt_public_key_files
+                    .iter()
+                    .map(|path| read_pem(path))
+                    .collect::<std::result::Result<Vec<_>, _>>()?;
+
+                if algorithm == "RS256" {
+                    let signing = EncodingKey::from_rsa_pem(&private_pem).map_err(|e| invalid("JWT private key", e))?;
+                    let verifying = public_pems
+                        .iter()
+                        .map(|pem| DecodingKey::from_rsa_pem(pem).map_err(|e| invalid("JWT public key", e)))
+                        .collect::<std::result::Result<_, _>>()?;
+                    (Algorithm::RS256, signing, verifying)
+                } else {
+                    let signing = EncodingKey::from_ed_pem(&private_pem).map_err(|e| invalid("JWT private key", e))?;
+                    let verifying = public_pems
+                        .iter()
+                        .map(|pem| DecodingKey::from_ed_pem(pem).map_err(|e| invalid("JWT public key", e)))
+                        .collect::<std::result::Result<_, _>>()?;
+                    (Algorithm::EdDSA, signing, verifying)
+                }
+            }
+            other => {
+                return Err(ConfigError::ParseError(format!(
+                    "Invalid JWT algorithm {}: expected HS256, RS256 or EdDSA",
+                    other
+                )))
+            }
+        };
+
+        Ok(Self {
+            algorithm,
+            keys: Arc::new(RwLock::new(Keys { signing, verifying })),
+            issuer: security.jwt_issuer.clone(),
+            audience: security.jwt_audience.clone(),
+            lifetime: chrono::Duration::minutes(security.access_token_minutes as i64),
+            admins: security.admin_users.clone(),
+            reauth: Arc::default(),
+        })
+    }
+
+    /// Access token for session `jti`
+    pub fn issue(&self, user_id: u64, username: &str, jti: &str) -> Result<String> {
+        let now = chrono::Utc::now();
+        let claims = JwtClaims {
+            sub: user_id.to_string(),
+            user_id,
+            username: username.to_string(),
+            role: self
+                .admins
+                .iter()
+                .any(|admin| admin.eq_ignore_ascii_case(username))
+                .then(|| ADMIN_ROLE.to_string()),
+            iss: self.issuer.clone(),
+            aud: self.audience.clone(),
+            exp: (now + self.lifetime).timestamp() as usize,
+            iat: now.timestamp() as usize,
+            iat_ms: Some(now.timestamp_millis()),
+            jti: jti.to_string(),
+        };
+
+        let keys = self.keys.read().map_err(|_| AppError::Internal("JWT keys lock poisoned".to_string()))?;
+        Ok(encode(&Header::new(self.algorithm), &claims, &keys.signing)?)
+    }
+
+    /// Sign with `secret` from now on, still accepting tokens signed with
+    /// the secrets before it
+    pub fn rotate_secret(&self, secret: &str) -> Result<()> {
+        if self.algorithm != Algorithm::HS256 {
+            return Err(AppError::Validation("JWT_SECRET is only used with HS256".to_string()));
+        }
+        let mut keys = self.keys.write().map_err(|_| AppError::Internal("JWT keys lock poisoned".to_string()))?;
+        keys.signing = EncodingKey::from_secret(secret.as_bytes());
+        keys.verifying.insert(0, DecodingKey::from_secret(secret.as_bytes()));
+        keys.verifying.truncate(MAX_VERIFYING_KEYS);
+        Ok(())
+    }
+
+    /// Refuse `user_id`'s tokens issued at or before `not_before` (unix
+    /// milliseconds)
+    pub fn require_reauth(&self, user_id: u64, not_before: i64) {
+        if let Ok(mut reauth) = self.reauth.write() {
+            reauth.insert(user_id, not_before);
+        }
+    }
+
+    /// Claims of a token signed by any accepted key, for this server's issuer
+    /// and audience, not yet expired and not issued before a forced re-auth
+    pub fn validate(&self, token: &str) -> Result<JwtClaims> {
+        let claims = self.decode(token)?;
+        let not_before = self.reauth.read().ok().and_then(|reauth| reauth.get(&claims.user_id).copied());
+        // Seconds alone can't tell a token from a sign-in right after the re-auth
+        let issued = claims.iat_ms.unwrap_or(claims.iat as i64 * 1000);
+        if not_before.is_some_and(|not_before| issued <= not_before) {
+            return Err(AppError::Authentication("Session revoked by an administrator; sign in again".to_string()));
+        }
+
+        Ok(claims)
+    }
+
+    fn decode(&self, token: &str) -> Result<JwtClaims> {
+        let mut validation = Validation::new(self.algorithm);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let keys = self.keys.read().map_err(|_| AppError::Internal("JWT keys lock poisoned".to_string()))?;
+        let mut last_error = None;
+        for key in &keys.verifying {
+            match decode::<JwtClaims>(token, key, &validation) {
+                Ok(data) => return Ok(data.claims),
+                // Signatures are checked before claims, so only a signature
+                // mismatch can succeed under another key
+                Err(e) if matches!(e.kind(), ErrorKind::InvalidSignature) => last_error = Some(e),
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| ErrorKind::InvalidSignature.into()).into())
+    }
+}
+
+fn read_pem(path: &str) -> std::result::Result<Vec<u8>, ConfigError> {
+    std::fs::read(path).map_err(|e| ConfigError::ParseError(format!("Cannot read JWT key {}: {}", path, e)))
+}
@@ -1,25 +1,151 @@
 use axum::{
+    body::Body,
+    extract::State,
     http::{HeaderValue, Request, StatusCode},
     middleware::Next,
     response::Response,
 };
-use governor::{Quota, RateLimiter};
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     net::IpAddr,
     num::NonZeroU32,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tokio::sync::RwLock;
 use tower::{Layer, Service};
 use tower_http::set_header::SetResponseHeaderLayer;
 use tracing::{debug, warn};
 
-use crate::error::{AppError, Result};
+use crate::{AppState, audit, error::{AppError, Result}, scheduler::Priority, store::RedisStore};
 
-// Rate limiting state
-type RateLimiterMap = Arc<RwLock<HashMap<IpAddr, Arc<RateLimiter<governor::state::direct::NotKeyed, governor::clock::DefaultClock, governor::state::InMemoryState>>>>>;
+pub mod jwt;
+pub mod proxies;
+pub mod secret_scan;
+pub mod tokens;
+pub mod user_limits;
+
+/// How often idle per-IP limiters are dropped
+const LIMITER_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+struct LimiterEntry {
+    limiter: Arc<DefaultDirectRateLimiter>,
+    last_seen: Instant,
+    /// Key of the entry in `Entries::recency`
+    seen: u64,
+}
+
+/// Limiters by IP, and the IPs in the order they were last seen, so the least
+/// recently seen is found without scanning them all
+#[derive(Default)]
+struct Entries {
+    limiters: HashMap<IpAddr, LimiterEntry>,
+    recency: BTreeMap<u64, IpAddr>,
+    next_seen: u64,
+}
+
+impl Entries {
+    fn touch(&mut self, ip: IpAddr) -> Option<Arc<DefaultDirectRateLimiter>> {
+        let entry = self.limiters.get_mut(&ip)?;
+        self.recency.remove(&entry.seen);
+        entry.seen = self.next_seen;
+        entry.last_seen = Instant::now();
+        self.recency.insert(self.next_seen, ip);
+        self.next_seen += 1;
+        Some(entry.limiter.clone())
+    }
+
+    fn insert(&mut self, ip: IpAddr, limiter: Arc<DefaultDirectRateLimiter>) {
+        self.limiters.insert(ip, LimiterEntry { limiter, last_seen: Instant::now(), seen: self.next_seen });
+        self.recency.insert(self.next_seen, ip);
+        self.next_seen += 1;
+    }
+
+    /// Drop the least recently seen IP's limiter unless `keep` holds for it;
+    /// returns whether one was dropped
+    fn pop_oldest_if(&mut self, keep: impl Fn(&LimiterEntry) -> bool) -> bool {
+        let Some((&seen, &ip)) = self.recency.first_key_value() else {
+            return false;
+        };
+        if self.limiters.get(&ip).is_some_and(&keep) {
+            return false;
+        }
+        self.recency.remove(&seen);
+        self.limiters.remove(&ip);
+        true
+    }
+}
+
+/// In-memory per-IP limiters. Those idle for `RATE_LIMIT_IDLE_MINUTES` are
+/// swept away, by which time their quota has fully refilled, and past
+/// `RATE_LIMIT_MAX_ENTRIES` the least recently seen IP is dropped to make room.
+#[derive(Clone)]
+pub struct IpLimiters {
+    requests_per_minute: u32,
+    max_entries: usize,
+    idle: Duration,
+    entries: Arc<RwLock<Entries>>,
+}
+
+impl IpLimiters {
+    pub fn new(security: &crate::config::SecurityConfig) -> Self {
+        Self {
+            requests_per_minute: security.rate_limit_requests_per_minute.max(1),
+            max_entries: security.rate_limit_max_entries.max(1),
+            idle: Duration::from_secs(security.rate_limit_idle_minutes * 60),
+            entries: Arc::new(RwLock::new(Entries::default())),
+        }
+    }
+
+    async fn get_or_create(&self, ip: IpAddr) -> Arc<DefaultDirectRateLimiter> {
+        let mut entries = self.entries.write().await;
+
+        if let Some(limiter) = entries.touch(ip) {
+            return limiter;
+        }
+
+        if entries.limiters.len() >= self.max_entries {
+            entries.pop_oldest_if(|_| false);
+        }
+
+        let quota = Quota::per_minute(NonZeroU32::new(self.requests_per_minute).unwrap_or(NonZeroU32::MIN));
+        let limiter = Arc::new(RateLimiter::direct(quota));
+        entries.insert(ip, limiter.clone());
+        limiter
+    }
+
+    /// Drop limiters idle for longer than the configured time; returns how many went
+    pub async fn sweep(&self) -> usize {
+        let mut entries = self.entries.write().await;
+        let mut removed = 0;
+        // Oldest first, so the walk stops at the first limiter still in use
+        while entries.pop_oldest_if(|entry| entry.last_seen.elapsed() < self.idle) {
+            removed += 1;
+        }
+        removed
+    }
+
+    pub async fn len(&self) -> usize {
+        self.entries.read().await.limiters.len()
+    }
+}
+
+/// Sweep idle rate limiters, per IP and per user, in the background
+pub fn spawn_limiter_sweep(state: AppState) {
+    let scheduler = state.scheduler.clone();
+    scheduler.spawn("rate_limiter_sweep", Priority::Low, LIMITER_SWEEP_INTERVAL, move || {
+        let state = state.clone();
+        async move {
+            let removed = state.ip_limiters.sweep().await;
+            state.user_limits.sweep();
+            if removed > 0 {
+                debug!("Dropped {} idle per-IP rate limiters", removed);
+            }
+            Ok(())
+        }
+    });
+}
 
 pub fn security_headers_layer() -> SetResponseHeaderLayer<HeaderValue> {
     SetResponseHeaderLayer::overriding(
@@ -45,36 +171,34 @@ pub fn security_headers_layer() -> SetResponseHeaderLayer<HeaderValue> {
     ))
 }
 
-pub fn rate_limiting_layer() -> RateLimitingLayer {
-    RateLimitingLayer::new(60) // 60 requests per minute
+/// `RATE_LIMIT_RPM` per client IP, counted in Redis with `RATE_LIMIT_BACKEND=redis`
+pub fn rate_limiting_layer(state: &AppState) -> RateLimitingLayer {
+    let shared = state.config.redis.as_ref().filter(|redis| redis.rate_limits).and(state.redis.clone());
+    RateLimitingLayer::new(state.ip_limiters.clone(), state.proxies.clone(), shared)
 }
 
 #[derive(Clone)]
 pub struct RateLimitingLayer {
-    requests_per_minute: u32,
-    limiters: RateLimiterMap,
+    limiters: IpLimiters,
+    proxies: proxies::TrustedProxies,
+    /// Counters shared between replicas; the in-memory limiters stand in while it's unreachable
+    shared: Option<RedisStore>,
 }
 
 impl RateLimitingLayer {
-    pub fn new(requests_per_minute: u32) -> Self {
-        Self {
-            requests_per_minute,
-            limiters: Arc::new(RwLock::new(HashMap::new())),
-        }
+    pub fn new(limiters: IpLimiters, proxies: proxies::TrustedProxies, shared: Option<RedisStore>) -> Self {
+        Self { limiters, proxies, shared }
     }
 
-    async fn get_or_create_limiter(&self, ip: IpAddr) -> Arc<RateLimiter<governor::state::direct::NotKeyed, governor::clock::DefaultClock, governor::state::InMemoryState>> {
-        let mut limiters = self.limiters.write().await;
-        
-        if let Some(limiter) = limiters.get(&ip) {
-            return limiter.clone();
+    /// Whether `ip` may make another request
+    async fn allow(&self, ip: IpAddr) -> bool {
+        if let Some(shared) = &self.shared {
+            match shared.hit(&format!("ratelimit:ip:{}", ip), Duration::from_secs(60)).await {
+                Ok(count) => return count <= u64::from(self.limiters.requests_per_minute),
+                Err(e) => warn!("Falling back to in-memory rate limiting: {}", e),
+            }
         }
-
-        let quota = Quota::per_minute(NonZeroU32::new(self.requests_per_minute).unwrap());
-        let limiter = Arc::new(RateLimiter::direct(quota));
-        limiters.insert(ip, limiter.clone());
-        
-        limiter
+        self.limiters.get_or_create(ip).await.check().is_ok()
     }
 }
 
@@ -115,18 +239,15 @@ where
 
         Box::pin(async move {
             // Extract client IP
-            let client_ip = extract_client_ip(&req).unwrap_or_else(|| "127.0.0.1".parse().unwrap());
-            
-            // Get rate limiter for this IP
-            let limiter = layer.get_or_create_limiter(client_ip).await;
+            let client_ip = layer.proxies.client_ip(&req).unwrap_or_else(|| "127.0.0.1".parse().unwrap());
             
             // Check rate limit
-            match limiter.check() {
-                Ok(_) => {
+            match layer.allow(client_ip).await {
+                true => {
                     debug!("Rate limit check passed for IP: {}", client_ip);
                     inner.call(req).await
                 }
-                Err(_) => {
+                false => {
                     warn!("Rate limit exceeded for IP: {}", client_ip);
                     let response = Response::builder()
                         .status(StatusCode::TOO_MANY_REQUESTS)
@@ -142,35 +263,13 @@ where
     }
 }
 
-fn extract_client_ip<B>(req: &Request<B>) -> Option<IpAddr> {
-    // Check X-Forwarded-For header (from reverse proxy)
-    if let Some(forwarded) = req.headers().get("x-forwarded-for") {
-        if let Ok(forwarded_str) = forwarded.to_str() {
-            if let Some(first_ip) = forwarded_str.split(',').next() {
-                if let Ok(ip) = first_ip.trim().parse() {
-                    return Some(ip);
-                }
-            }
-        }
-    }
-
-    // Check X-Real-IP header (from reverse proxy)
-    if let Some(real_ip) = req.headers().get("x-real-ip") {
-        if let Ok(ip_str) = real_ip.to_str() {
-            if let Ok(ip) = ip_str.parse() {
-                return Some(ip);
-            }
-        }
-    }
-
-    // Fallback to connection info (not available in this context)
-    None
-}
-
-pub async fn audit_log_middleware<B>(
-    req: Request<B>,
-    next: Next<B>,
-) -> std::result::Result<Response, StatusCode> {
+/// Log every request, and for those made with a valid session token write
+/// who made it, from where, and how it went to the audit log
+pub async fn audit_log_middleware(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
     let method = req.method().clone();
     let uri = req.uri().clone();
     let user_agent = req.headers()
@@ -181,6 +280,19 @@ pub async fn audit_log_middleware<B>(
     let start_time = std::time::Instant::now();
     
     debug!("Request: {} {} - User-Agent: {}", method, uri, user_agent);
+
+    // Only requests made with a valid session token are audited; the
+    // handler still rejects bad tokens on its own
+    let claims = match state.config.security.audit_log_enabled {
+        true => req
+            .headers()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .and_then(|token| state.jwt.validate(token).ok()),
+        false => None,
+    };
+    let source_ip = state.proxies.client_ip(&req);
     
     let response = next.run(req).await;
     
@@ -194,21 +306,26 @@ pub async fn audit_log_middleware<B>(
         debug!("Request completed: {} {} - {} - {:?}", method, uri, status, duration);
     }
 
-    // TODO: Store audit log in database for security monitoring
+    if let Some(claims) = claims {
+        let entry = audit::NewAuditEntry {
+            method: method.to_string(),
+            // Without the query string, which may carry tokens
+            path: uri.path().to_string(),
+            login: claims.username,
+            user_id: claims.user_id as i64,
+            source_ip: source_ip.map(|ip| ip.to_string()),
+            status: status.as_u16() as i64,
+            latency_ms: duration.as_millis() as i64,
+        };
+        // Written off the request path so a slow database doesn't delay responses
+        tokio::spawn(async move {
+            if let Err(e) = audit::record(&state.db, &entry).await {
+                warn!("Failed to write audit log entry for {} {}: {}", entry.method, entry.path, e);
+            }
+        });
+    }
     
-    Ok(response)
-}
-
-pub fn validate_jwt_token(token: &str, secret: &str) -> Result<JwtClaims> {
-    use jsonwebtoken::{decode, DecodingKey, Validation};
-
-    let token_data = decode::<JwtClaims>(
-        token,
-        &DecodingKey::from_secret(secret.as_ref()),
-        &Validation::default(),
-    )?;
-
-    Ok(token_data.claims)
+    response
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -216,8 +333,20 @@ pub struct JwtClaims {
     pub sub: String,
     pub user_id: u64,
     pub username: String,
+    /// `admin` for `ADMIN_USERS`; absent on tokens issued before roles existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    pub iss: String,
+    pub aud: String,
     pub exp: usize,
     pub iat: usize,
+    /// `iat` in milliseconds; absent on tokens issued before it was added
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat_ms: Option<i64>,
+    /// Sign-in session the token belongs to; empty on tokens issued before
+    /// sessions existed, which no session matches
+    #[serde(default)]
+    pub jti: String,
 }
 
 pub fn hash_password(password: &str) -> Result<String> {
@@ -0,0 +1,192 @@
+// Which address a request comes from.
+//
+// `X-Forwarded-For` and `X-Real-IP` are only believed when the connection
+// comes from one of `TRUSTED_PROXIES`; anyone else could set them to pick the
+// address they're rate limited and audited under. `X-Forwarded-For` is read
+// from the right, skipping trusted proxies, so a client can't prepend hops of
+// its own either.
+
+use axum::{extract::ConnectInfo, http::Request};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use crate::config::{ConfigError, SecurityConfig};
+
+/// An address or CIDR range from `TRUSTED_PROXIES`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Network {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Network {
+    fn parse(entry: &str) -> Option<Self> {
+        let (addr, prefix) = match entry.split_once('/') {
+            Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse::<u8>().ok()?)),
+            None => (entry.parse::<IpAddr>().ok()?, None),
+        };
+        let bits = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        match prefix.unwrap_or(bits) {
+            prefix if prefix <= bits => Some(Self { addr, prefix }),
+            _ => None,
+        }
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix)).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix)).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    networks: Arc<Vec<Network>>,
+}
+
+impl TrustedProxies {
+    pub fn from_config(security: &SecurityConfig) -> std::result::Result<Self, ConfigError> {
+        let networks = security
+            .trusted_proxies
+            .iter()
+            .map(|entry| {
+                Network::parse(entry)
+                    .ok_or_else(|| ConfigError::ParseError(format!("Invalid trusted proxy: {}", entry)))
+            })
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(Self { networks: Arc::new(networks) })
+    }
+
+    fn trusts(&self, ip: IpAddr) -> bool {
+        self.networks.iter().any(|network| network.contains(ip))
+    }
+
+    /// The client's address: the connection's peer, or when that's a trusted
+    /// proxy, the address it forwarded the request for
+    pub fn client_ip<B>(&self, req: &Request<B>) -> Option<IpAddr> {
+        let peer = req
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| canonical(addr.ip()))?;
+        if !self.trusts(peer) {
+            return Some(peer);
+        }
+
+        let hops: Vec<&str> = req
+            .headers()
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect();
+        if !hops.is_empty() {
+            // The nearest hop not added by a trusted proxy is the client. An
+            // unreadable hop ends the walk at the proxy that passed it on.
+            let mut client = peer;
+            for hop in hops.iter().rev() {
+                match hop.parse::<IpAddr>() {
+                    Ok(ip) => {
+                        client = canonical(ip);
+                        if !self.trusts(client) {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+            return Some(client);
+        }
+
+        req.headers()
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(canonical)
+            .or(Some(peer))
+    }
+}
+
+/// IPv4 clients of a dual-stack listener appear as IPv4-mapped IPv6 addresses
+pub fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        IpAddr::V4(_) => ip,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proxies(entries: &[&str]) -> TrustedProxies {
+        TrustedProxies {
+            networks: Arc::new(entries.iter().map(|entry| Network::parse(entry).unwrap()).collect()),
+        }
+    }
+
+    fn request(peer: &str, headers: &[(&str, &str)]) -> Request<()> {
+        let mut builder = Request::builder();
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        let mut req = builder.body(()).unwrap();
+        req.extensions_mut().insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 40000)));
+        req
+    }
+
+    #[test]
+    fn parses_addresses_and_ranges() {
+        assert!(Network::parse("10.0.0.0/8").is_some());
+        assert!(Network::parse("::1").is_some());
+        assert!(Network::parse("fd00::/8").is_some());
+        assert!(Network::parse("10.0.0.0/33").is_none());
+        assert!(Network::parse("proxy.internal").is_none());
+    }
+
+    #[test]
+    fn ranges_contain_their_addresses() {
+        let network = Network::parse("10.1.0.0/16").unwrap();
+        assert!(network.contains("10.1.200.3".parse().unwrap()));
+        assert!(!network.contains("10.2.0.1".parse().unwrap()));
+        assert!(Network::parse("0.0.0.0/0").unwrap().contains("203.0.113.9".parse().unwrap()));
+    }
+
+    #[test]
+    fn ignores_forwarded_headers_from_untrusted_peers() {
+        let req = request("203.0.113.9", &[("x-forwarded-for", "198.51.100.1"), ("x-real-ip", "198.51.100.2")]);
+        assert_eq!(proxies(&[]).client_ip(&req), Some("203.0.113.9".parse().unwrap()));
+        assert_eq!(proxies(&["10.0.0.0/8"]).client_ip(&req), Some("203.0.113.9".parse().unwrap()));
+    }
+
+    #[test]
+    fn reads_forwarded_for_from_the_right() {
+        let trusted = proxies(&["10.0.0.0/8"]);
+        // The client prepended a hop of its own; the proxy appended the real address
+        let req = request("10.0.0.5", &[("x-forwarded-for", "198.51.100.1, 203.0.113.9, 10.0.0.4")]);
+        assert_eq!(trusted.client_ip(&req), Some("203.0.113.9".parse().unwrap()));
+
+        let req = request("10.0.0.5", &[("x-forwarded-for", "junk, 10.0.0.4")]);
+        assert_eq!(trusted.client_ip(&req), Some("10.0.0.4".parse().unwrap()));
+
+        let req = request("10.0.0.5", &[("x-real-ip", "203.0.113.9")]);
+        assert_eq!(trusted.client_ip(&req), Some("203.0.113.9".parse().unwrap()));
+    }
+
+    #[test]
+    fn treats_mapped_ipv4_peers_as_ipv4() {
+        let req = request("::ffff:10.0.0.5", &[("x-forwarded-for", "203.0.113.9")]);
+        assert_eq!(proxies(&["10.0.0.0/8"]).client_ip(&req), Some("203.0.113.9".parse().unwrap()));
+    }
+}
@@ -0,0 +1,156 @@
+// GitHub tokens at rest.
+//
+// With `TOKEN_ENCRYPTION_KEY` set, stored tokens are encrypted with AES-256-GCM
+// under a key derived from it and kept as `enc:v1:<base64 nonce+ciphertext>`.
+// Keys listed in `TOKEN_ENCRYPTION_PREVIOUS_KEYS` still decrypt, so the key can
+// change without logging everyone out; `github-mcp-server token
+// encrypt-rotate` then re-encrypts every stored token under the current key,
+// after which the old ones can be dropped. Values without the prefix were
+// stored before encryption was enabled and are read as they are.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use rand::RngCore;
+use std::sync::{OnceLock, RwLock, RwLockReadGuard};
+use tracing::warn;
+
+use crate::config::SecurityConfig;
+use crate::error::{AppError, Result};
+
+const PREFIX: &str = "enc:v1:";
+
+/// Fixed so every instance derives the same key from the same passphrase
+const SALT: &[u8] = b"github-mcp-server/github-tokens";
+
+#[derive(Default)]
+struct Keys {
+    current: Option<Aes256Gcm>,
+    previous: Vec<Aes256Gcm>,
+}
+
+static KEYS: OnceLock<RwLock<Keys>> = OnceLock::new();
+
+/// Derive the keys; call once at startup, before tokens are read or written
+pub fn init(security: &SecurityConfig) -> Result<()> {
+    let current = security.token_encryption_key.as_deref().map(cipher).transpose()?;
+    if current.is_none() {
+        warn!("TOKEN_ENCRYPTION_KEY is not set; GitHub tokens are stored unencrypted");
+    }
+    let previous = security
+        .token_encryption_previous_keys
+        .iter()
+        .map(|key| cipher(key))
+        .collect::<Result<_>>()?;
+    let _ = KEYS.set(RwLock::new(Keys { current, previous }));
+    Ok(())
+}
+
+fn keys() -> RwLockReadGuard<'static, Keys> {
+    KEYS.get_or_init(Default::default).read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Encrypt under `passphrase` from now on, still decrypting with the keys before it
+pub fn rotate_key(passphrase: &str) -> Result<()> {
+    let cipher = cipher(passphrase)?;
+    let mut keys = KEYS.get_or_init(Default::default).write().unwrap_or_else(|e| e.into_inner());
+    if let Some(old) = keys.current.replace(cipher) {
+        keys.previous.insert(0, old);
+    }
+    Ok(())
+}
+
+fn cipher(passphrase: &str) -> Result<Aes256Gcm> {
+    if passphrase.len() < 16 {
+        return Err(AppError::Validation("Token encryption keys must be at least 16 characters".to_string()));
+    }
+
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), SALT, &mut key)
+        .map_err(|e| AppError::Internal(format!("Key derivation failed: {}", e)))?;
+
+    Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| AppError::Internal(format!("Invalid token encryption key: {}", e)))
+}
+
+/// `token` as it should be stored
+pub fn encrypt(token: &str) -> Result<String> {
+    let keys = keys();
+    let Some(cipher) = &keys.current else {
+        return Ok(token.to_string());
+    };
+
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), token.as_bytes())
+        .map_err(|e| AppError::Internal(format!("Token encryption failed: {}", e)))?;
+
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&ciphertext);
+    Ok(format!("{}{}", PREFIX, BASE64.encode(out)))
+}
+
+/// The token a stored value holds, trying the current key then retired ones
+pub fn decrypt(stored: &str) -> Result<String> {
+    let Some(encoded) = stored.strip_prefix(PREFIX) else {
+        return Ok(stored.to_string());
+    };
+
+    let bytes = BASE64
+        .decode(encoded)
+        .map_err(|e| AppError::Internal(format!("Invalid encrypted token: {}", e)))?;
+    if bytes.len() < 12 {
+        return Err(AppError::Internal("Invalid encrypted token".to_string()));
+    }
+
+    let (nonce, ciphertext) = bytes.split_at(12);
+    let keys = keys();
+    let plaintext = keys
+        .current
+        .iter()
+        .chain(&keys.previous)
+        .find_map(|cipher| cipher.decrypt(Nonce::from_slice(nonce), ciphertext).ok())
+        .ok_or_else(|| {
+            AppError::Authentication(
+                "Stored GitHub token was encrypted with a key that is no longer configured".to_string(),
+            )
+        })?;
+
+    String::from_utf8(plaintext).map_err(|e| AppError::Internal(format!("Invalid token encoding: {}", e)))
+}
+
+/// Re-encrypt every stored token under the current key; returns how many rows changed
+pub async fn rotate(db: &sqlx::SqlitePool) -> Result<usize> {
+    if keys().current.is_none() {
+        return Err(AppError::Validation("TOKEN_ENCRYPTION_KEY must be set to rotate token encryption".to_string()));
+    }
+
+    let mut tx = db.begin().await?;
+    let rows = sqlx::query!("SELECT id, encrypted_token, encrypted_refresh_token FROM github_tokens")
+        .fetch_all(&mut *tx)
+        .await?;
+
+    for row in &rows {
+        let token = encrypt(&decrypt(&row.encrypted_token)?)?;
+        let refresh_token = row
+            .encrypted_refresh_token
+            .as_deref()
+            .map(|t| decrypt(t).and_then(|t| encrypt(&t)))
+            .transpose()?;
+        sqlx::query!(
+            "UPDATE github_tokens SET encrypted_token = ?, encrypted_refresh_token = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+            token,
+            refresh_token,
+            row.id
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(rows.len())
+}
@@ -0,0 +1,148 @@
+// Per-user limits on MCP tool calls, on top of the per-IP limit on every request.
+//
+// Calls made with a session token count against the user it was issued to
+// (`sub`): calls to the tools in `EXPENSIVE_TOOLS` against
+// `EXPENSIVE_TOOL_RATE_LIMIT_RPM`, all others against `USER_RATE_LIMIT_RPM`.
+// A call over budget is answered with a RATE_LIMIT_ERROR MCP error saying when
+// to retry, rather than an HTTP 429, so the session carries on. Calls without
+// a session token only have the per-IP limit. With `RATE_LIMIT_BACKEND=redis`
+// the counts are shared between replicas.
+
+use governor::clock::{Clock, DefaultClock};
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
+use serde_json::{json, Value};
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::config::SecurityConfig;
+use crate::store::RedisStore;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Standard,
+    Expensive,
+}
+
+impl Class {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Standard => "standard",
+            Self::Expensive => "expensive",
+        }
+    }
+}
+
+/// One budget: calls per minute, counted per user
+#[derive(Clone)]
+struct Budget {
+    per_minute: u32,
+    limiter: Arc<DefaultKeyedRateLimiter<String>>,
+}
+
+impl Budget {
+    fn new(per_minute: u32) -> Option<Self> {
+        let quota = Quota::per_minute(NonZeroU32::new(per_minute)?);
+        Some(Self { per_minute, limiter: Arc::new(RateLimiter::keyed(quota)) })
+    }
+}
+
+/// A call refused for being over budget
+#[derive(Debug)]
+pub struct Exceeded {
+    pub class: Class,
+    pub per_minute: u32,
+    pub retry_after: Duration,
+}
+
+impl Exceeded {
+    pub fn message(&self, tool: &str) -> String {
+        format!(
+            "Rate limit exceeded for {}: {} {} tool calls per minute; retry in {}s",
+            tool,
+            self.per_minute,
+            self.class.as_str(),
+            self.retry_after.as_secs().max(1)
+        )
+    }
+
+    /// Details for the MCP error's `data`
+    pub fn data(&self) -> Value {
+        json!({
+            "scope": "user",
+            "class": self.class.as_str(),
+            "limit_per_minute": self.per_minute,
+            "retry_after_seconds": self.retry_after.as_secs().max(1)
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct UserLimits {
+    standard: Option<Budget>,
+    expensive: Option<Budget>,
+    expensive_tools: Vec<String>,
+    shared: Option<RedisStore>,
+}
+
+impl UserLimits {
+    pub fn new(security: &SecurityConfig, shared: Option<RedisStore>) -> Self {
+        Self {
+            standard: Budget::new(security.user_rate_limit_per_minute),
+            expensive: Budget::new(security.expensive_tool_rate_limit_per_minute),
+            expensive_tools: security.expensive_tools.clone(),
+            shared,
+        }
+    }
+
+    pub fn class(&self, tool: &str) -> Class {
+        let expensive = self.expensive_tools.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => tool.starts_with(prefix),
+            None => pattern == tool,
+        });
+        if expensive {
+            Class::Expensive
+        } else {
+            Class::Standard
+        }
+    }
+
+    /// Forget users whose quota has fully refilled
+    pub fn sweep(&self) {
+        for budget in self.standard.iter().chain(&self.expensive) {
+            budget.limiter.retain_recent();
+            budget.limiter.shrink_to_fit();
+        }
+    }
+
+    /// Count a call by `user` to `tool`, or say how long until it's allowed
+    pub async fn check(&self, user: &str, tool: &str) -> std::result::Result<(), Exceeded> {
+        let class = self.class(tool);
+        let budget = match class {
+            Class::Standard => &self.standard,
+            Class::Expensive => &self.expensive,
+        };
+        let Some(budget) = budget else {
+            return Ok(());
+        };
+        let exceeded = |retry_after| Exceeded { class, per_minute: budget.per_minute, retry_after };
+
+        if let Some(shared) = &self.shared {
+            let key = format!("ratelimit:user:{}:{}", user, class.as_str());
+            match shared.hit(&key, Duration::from_secs(60)).await {
+                Ok(count) if count <= u64::from(budget.per_minute) => return Ok(()),
+                Ok(_) => {
+                    let into_window = chrono::Utc::now().timestamp() as u64 % 60;
+                    return Err(exceeded(Duration::from_secs(60 - into_window)));
+                }
+                Err(e) => warn!("Falling back to in-memory user rate limiting: {}", e),
+            }
+        }
+
+        budget
+            .limiter
+            .check_key(&user.to_string())
+            .map_err(|not_until| exceeded(not_until.wait_time_from(DefaultClock::default().now())))
+    }
+}
@@ -0,0 +1,277 @@
+// The onboarding wizard, `/setup/status` and `/setup/step`.
+//
+// It's only open until every step is complete, and only to requests from the
+// server's own host or carrying the `X-Setup-Token` logged at startup. It
+// never hands out secrets: the operator generates them and sets them in the
+// environment, and the wizard checks they're there.
+
+use axum::{
+    extract::{ConnectInfo, State},
+    http::HeaderMap,
+    Json,
+};
+use reqwest::Url;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+use crate::{AppState, error::{AppError, Result}};
+
+pub const TOKEN_HEADER: &str = "x-setup-token";
+
+/// `JWT_SECRET` as shipped in `.env.example`, which doesn't count as set
+const EXAMPLE_JWT_SECRET: &str = "your-super-secret-jwt-key-change-this-in-production";
+
+/// Shortest `JWT_SECRET` the wizard accepts, the length `openssl rand -hex 16` gives
+const MIN_JWT_SECRET_LEN: usize = 32;
+
+/// Opens the wizard to other hosts; made at startup while setup is incomplete
+static SETUP_TOKEN: OnceLock<String> = OnceLock::new();
+
+/// Onboarding steps, in the order a new deployment should complete them
+pub const SETUP_STEPS: &[(&str, &str)] = &[
+    ("generate_secrets", "Generate JWT signing secret"),
+    ("register_oauth_app", "Register the GitHub OAuth app"),
+    ("run_migrations", "Run database migrations"),
+    ("validate_login", "Complete the first GitHub login"),
+];
+
+#[derive(Debug, Deserialize)]
+pub struct SetupStepRequest {
+    pub step: String,
+}
+
+/// Log a setup token when setup isn't finished, so the wizard can be reached
+/// from another host
+pub async fn init(state: &AppState) -> Result<()> {
+    if setup_completed(state).await? {
+        return Ok(());
+    }
+    let token = SETUP_TOKEN.get_or_init(crate::security::generate_secure_token);
+    warn!(
+        "Setup is incomplete. The wizard at /setup/status is open from this host, or elsewhere with the header {}: {}",
+        TOKEN_HEADER, token
+    );
+    Ok(())
+}
+
+/// Refuse wizard requests once setup is complete, and from other hosts
+/// without the setup token
+async fn require_open(state: &AppState, peer: Option<SocketAddr>, headers: &HeaderMap) -> Result<()> {
+    // Once onboarding is finished the wizard is closed, so it can't be used
+    // to probe or re-run steps on a live deployment
+    if setup_completed(state).await? {
+        return Err(AppError::Authorization("Setup has already been completed".to_string()));
+    }
+
+    // The connection's own address: forwarding headers could claim anything
+    if peer.map_or(false, |peer| crate::security::proxies::canonical(peer.ip()).is_loopback()) {
+        return Ok(());
+    }
+    let presented = headers.get(TOKEN_HEADER).and_then(|v| v.to_str().ok());
+    let matches = match (presented, SETUP_TOKEN.get()) {
+        // Compared as digests so the time taken says nothing about the token
+        (Some(presented), Some(token)) => Sha256::digest(presented.as_bytes()) == Sha256::digest(token.as_bytes()),
+        _ => false,
+    };
+    if !matches {
+        return Err(AppError::Authentication(format!(
+            "The setup wizard is only open from the server's host, or with the {} header logged at startup",
+            TOKEN_HEADER
+        )));
+    }
+    Ok(())
+}
+
+pub async fn setup_status(
+    State(state): State<AppState>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+) -> Result<Json<Value>> {
+    require_open(&state, peer.map(|ConnectInfo(addr)| addr), &headers).await?;
+
+    let mut steps = Vec::new();
+    let mut next_step = None;
+
+    for (step, title) in SETUP_STEPS {
+        let completed = is_step_completed(&state, step).await?;
+        if !completed && next_step.is_none() {
+            next_step = Some(*step);
+        }
+        steps.push(json!({
+            "step": step,
+            "title": title,
+            "completed": completed
+        }));
+    }
+
+    Ok(Json(json!({
+        "completed": next_step.is_none(),
+        "next_step": next_step,
+        "steps": steps,
+        "callback_url": state.config.github.redirect_uri,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+pub async fn setup_step(
+    State(state): State<AppState>,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Json(request): Json<SetupStepRequest>,
+) -> Result<Json<Value>> {
+    require_open(&state, peer.map(|ConnectInfo(addr)| addr), &headers).await?;
+
+    if !SETUP_STEPS.iter().any(|(step, _)| *step == request.step) {
+        return Err(AppError::Validation(format!("Unknown setup step: {}", request.step)));
+    }
+
+    info!("Running setup step: {}", request.step);
+
+    let (completed, result) = match request.step.as_str() {
+        "generate_secrets" => step_generate_secrets(&state),
+        "register_oauth_app" => step_register_oauth_app(&state)?,
+        "run_migrations" => step_run_migrations(&state).await?,
+        "validate_login" => step_validate_login(&state).await?,
+        _ => unreachable!(),
+    };
+
+    record_step(&state.db, &request.step, completed, &result).await?;
+
+    Ok(Json(json!({
+        "step": request.step,
+        "completed": completed,
+        "result": result,
+        "timestamp": chrono::Utc::now().to_rfc3339()
+    })))
+}
+
+fn jwt_secret_configured(state: &AppState) -> bool {
+    let secret = &state.config.jwt_secret;
+    secret.len() >= MIN_JWT_SECRET_LEN && secret != EXAMPLE_JWT_SECRET
+}
+
+fn step_generate_secrets(state: &AppState) -> (bool, Value) {
+    // Anyone holding the secret can sign session tokens, so it's made where
+    // it's stored rather than sent over the network
+    let configured = jwt_secret_configured(state);
+    (configured, json!({
+        "jwt_secret_configured": configured,
+        "instructions": if configured {
+            "JWT_SECRET is set"
+        } else {
+            "Generate a secret with `openssl rand -hex 32`, set it as JWT_SECRET in your .env file and restart the server"
+        }
+    }))
+}
+
+fn step_register_oauth_app(state: &AppState) -> Result<(bool, Value)> {
+    let callback_url = state.config.github.redirect_uri.clone();
+    let mut homepage = Url::parse(&callback_url)
+        .map_err(|e| AppError::Validation(format!("Invalid redirect URI: {}", e)))?;
+    homepage.set_path("/");
+
+    let registration_url = Url::parse_with_params(
+        &state.config.github.host.web("settings/applications/new"),
+        &[
+            ("oauth_application[name]", "GitHub MCP Server"),
+            ("oauth_application[url]", homepage.as_str()),
+            ("oauth_application[callback_url]", callback_url.as_str()),
+        ],
+    )
+    .map_err(|e| AppError::Internal(format!("Failed to build registration URL: {}", e)))?;
+
+    let configured = !state.config.github.client_id.is_empty()
+        && !state.config.github.client_secret.is_empty();
+
+    Ok((configured, json!({
+        "registration_url": registration_url.as_str(),
+        "homepage_url": homepage.as_str(),
+        "callback_url": callback_url,
+        "client_id_configured": configured,
+        "instructions": "Create the OAuth app, then set GITHUB_CLIENT_ID and GITHUB_CLIENT_SECRET and restart the server"
+    })))
+}
+
+async fn step_run_migrations(state: &AppState) -> Result<(bool, Value)> {
+    sqlx::migrate!("./migrations")
+        .run(&state.db)
+        .await
+        .map_err(|e| AppError::Internal(format!("Migration failed: {}", e)))?;
+
+    Ok((true, json!({ "message": "Database migrations applied" })))
+}
+
+async fn step_validate_login(state: &AppState) -> Result<(bool, Value)> {
+    let row = sqlx::query!(
+        "SELECT COUNT(*) as count FROM github_tokens WHERE expires_at > datetime('now')"
+    )
+    .fetch_one(&state.db)
+    .await?;
+
+    let logged_in = row.count > 0;
+    Ok((logged_in, json!({
+        "authenticated_users": row.count,
+        "login_url": "/auth/github",
+        "instructions": if logged_in {
+            "First login verified"
+        } else {
+            "Open /auth/github in a browser and sign in with GitHub, then re-run this step"
+        }
+    })))
+}
+
+async fn is_step_completed(state: &AppState, step: &str) -> Result<bool> {
+    // Steps that reflect live configuration are checked directly rather than trusting the DB
+    match step {
+        "generate_secrets" => return Ok(jwt_secret_configured(state)),
+        "register_oauth_app" => {
+            return Ok(!state.config.github.client_id.is_empty()
+                && !state.config.github.client_secret.is_empty());
+        }
+        "validate_login" => return Ok(step_validate_login(state).await?.0),
+        _ => {}
+    }
+
+    let row = sqlx::query!("SELECT completed FROM setup_steps WHERE step = ?", step)
+        .fetch_optional(&state.db)
+        .await?;
+
+    Ok(row.map(|r| r.completed).unwrap_or(false))
+}
+
+async fn setup_completed(state: &AppState) -> Result<bool> {
+    for (step, _) in SETUP_STEPS {
+        if !is_step_completed(state, step).await? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+async fn record_step(db: &sqlx::SqlitePool, step: &str, completed: bool, result: &Value) -> Result<()> {
+    let details = serde_json::to_string(result)?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO setup_steps (step, completed, details, completed_at, updated_at)
+        VALUES (?, ?, ?, CASE WHEN ? THEN datetime('now') END, datetime('now'))
+        ON CONFLICT(step) DO UPDATE SET
+            completed = excluded.completed,
+            details = excluded.details,
+            completed_at = excluded.completed_at,
+            updated_at = excluded.updated_at
+        "#,
+        step,
+        completed,
+        details,
+        completed
+    )
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
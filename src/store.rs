@@ -0,0 +1,103 @@
+// State shared between replicas through Redis.
+//
+// With `REDIS_URL` set, rate-limit counters (`RATE_LIMIT_BACKEND=redis`) and
+// MCP sessions over `POST /mcp` (`SESSION_BACKEND=redis`) live in Redis, so
+// limits hold across replicas and restarts and a session started on one
+// replica can continue on another. Everything else, and both of these by
+// default, stays in process memory. Keys are prefixed with `github-mcp:` so
+// the server can share a Redis instance.
+
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::{de::DeserializeOwned, Serialize};
+use std::time::Duration;
+
+use crate::error::{AppError, Result};
+
+const KEY_PREFIX: &str = "github-mcp:";
+
+#[derive(Clone)]
+pub struct RedisStore {
+    conn: ConnectionManager,
+}
+
+fn redis_error(e: redis::RedisError) -> AppError {
+    AppError::Internal(format!("Redis error: {}", e))
+}
+
+impl RedisStore {
+    /// Connect to `url`; the connection is re-established on its own after failures
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url).map_err(redis_error)?;
+        let conn = client.get_connection_manager().await.map_err(redis_error)?;
+        Ok(Self { conn })
+    }
+
+    /// Count a hit against `key` in the current fixed `window`; returns the
+    /// hits so far in that window
+    pub async fn hit(&self, key: &str, window: Duration) -> Result<u64> {
+        let window = window.as_secs().max(1);
+        let slot = chrono::Utc::now().timestamp() as u64 / window;
+        let key = format!("{}{}:{}", KEY_PREFIX, key, slot);
+
+        let mut conn = self.conn.clone();
+        let (count,): (u64,) = redis::pipe()
+            .atomic()
+            .incr(&key, 1)
+            .expire(&key, window as i64 * 2)
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .map_err(redis_error)?;
+        Ok(count)
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let mut conn = self.conn.clone();
+        let value: Option<String> = conn.get(format!("{}{}", KEY_PREFIX, key)).await.map_err(redis_error)?;
+        Ok(value.map(|v| serde_json::from_str(&v)).transpose()?)
+    }
+
+    /// Store `value` under `key` until it's `ttl` old
+    pub async fn set_json<T: Serialize>(&self, key: &str, value: &T, ttl: Duration) -> Result<()> {
+        let mut conn = self.conn.clone();
+        conn.set_ex(format!("{}{}", KEY_PREFIX, key), serde_json::to_string(value)?, ttl.as_secs().max(1))
+            .await
+            .map_err(redis_error)
+    }
+
+    /// False when there was nothing to delete
+    pub async fn delete(&self, key: &str) -> Result<bool> {
+        let mut conn = self.conn.clone();
+        let deleted: u64 = conn.del(format!("{}{}", KEY_PREFIX, key)).await.map_err(redis_error)?;
+        Ok(deleted > 0)
+    }
+
+    /// Every value whose key starts with `prefix`, with the key less the prefix
+    pub async fn scan_json<T: DeserializeOwned>(&self, prefix: &str) -> Result<Vec<(String, T)>> {
+        let full_prefix = format!("{}{}", KEY_PREFIX, prefix);
+        let mut conn = self.conn.clone();
+        let keys: Vec<String> = {
+            let mut iter = conn
+                .scan_match::<_, String>(format!("{}*", full_prefix))
+                .await
+                .map_err(redis_error)?;
+            let mut keys = Vec::new();
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+            keys
+        };
+
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            // Expired between the scan and the read
+            let Some(value) = conn.get::<_, Option<String>>(&key).await.map_err(redis_error)? else {
+                continue;
+            };
+            let name = key[full_prefix.len()..].to_string();
+            values.push((name, serde_json::from_str(&value)?));
+        }
+        Ok(values)
+    }
+}
@@ -0,0 +1,200 @@
+// HTTPS without a reverse proxy.
+//
+// With `TLS_CERT_PATH` and `TLS_KEY_PATH` set, the server terminates TLS
+// itself (rustls, HTTP/1.1 and HTTP/2 over ALPN). `TLS_RELOAD_SECONDS` makes
+// it watch both files and switch to a rotated certificate without a restart;
+// connections already open keep the one they started with. With
+// `HTTP_REDIRECT_PORT`, a plain HTTP listener answers every request with a
+// permanent redirect to the same URL over HTTPS.
+
+use axum::{
+    extract::{ConnectInfo, Request},
+    http::{header, StatusCode, Uri},
+    response::{IntoResponse, Redirect, Response},
+    Router,
+};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto::Builder,
+};
+use std::io::BufReader;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tower::ServiceExt;
+use tracing::{debug, info, warn};
+
+use crate::config::TlsConfig;
+use crate::error::{AppError, Result};
+
+/// How long a client has to finish the handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Read the certificate chain and key into a server configuration
+fn load(tls: &TlsConfig) -> Result<Arc<ServerConfig>> {
+    let read = |path: &str| {
+        std::fs::File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| AppError::Config(crate::config::ConfigError::ParseError(format!("Cannot read {}: {}", path, e))))
+    };
+    let invalid = |message: String| AppError::Config(crate::config::ConfigError::ParseError(message));
+
+    let certs: Vec<Certificate> = rustls_pemfile::certs(&mut read(&tls.cert_path)?)
+        .map_err(|e| invalid(format!("Invalid certificate in {}: {}", tls.cert_path, e)))?
+        .into_iter()
+        .map(Certificate)
+        .collect();
+    if certs.is_empty() {
+        return Err(invalid(format!("No certificate found in {}", tls.cert_path)));
+    }
+
+    let mut reader = read(&tls.key_path)?;
+    let key = loop {
+        match rustls_pemfile::read_one(&mut reader).map_err(|e| invalid(format!("Invalid key in {}: {}", tls.key_path, e)))? {
+            Some(rustls_pemfile::Item::PKCS8Key(key) | rustls_pemfile::Item::RSAKey(key) | rustls_pemfile::Item::ECKey(key)) => {
+                break PrivateKey(key)
+            }
+            Some(_) => continue,
+            None => return Err(invalid(format!("No private key found in {}", tls.key_path))),
+        }
+    };
+
+    let mut config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| invalid(format!("Certificate and key don't make a usable pair: {}", e)))?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+/// Whether the certificate and key load, without serving anything
+pub fn check(tls: &TlsConfig) -> Result<()> {
+    load(tls).map(|_| ())
+}
+
+fn modified(tls: &TlsConfig) -> Option<(SystemTime, SystemTime)> {
+    let modified = |path: &str| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    Some((modified(&tls.cert_path)?, modified(&tls.key_path)?))
+}
+
+/// Reload the certificate whenever either file changes. A rotation caught
+/// half-written fails to load and is retried on the next check.
+fn spawn_reload(tls: TlsConfig, current: Arc<RwLock<Arc<ServerConfig>>>) {
+    if tls.reload_seconds == 0 {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut seen = modified(&tls);
+        let mut interval = tokio::time::interval(Duration::from_secs(tls.reload_seconds));
+        loop {
+            interval.tick().await;
+            let now = modified(&tls);
+            if now.is_none() || now == seen {
+                continue;
+            }
+            match load(&tls) {
+                Ok(config) => {
+                    if let Ok(mut current) = current.write() {
+                        *current = config;
+                    }
+                    seen = now;
+                    info!("Reloaded TLS certificate from {}", tls.cert_path);
+                }
+                Err(e) => warn!("Keeping the current TLS certificate: {}", e),
+            }
+        }
+    });
+}
+
+/// Serve `app` over HTTPS on `listener` until the process exits
+pub async fn serve(listener: TcpListener, app: Router, tls: &TlsConfig) -> Result<()> {
+    let current = Arc::new(RwLock::new(load(tls)?));
+    spawn_reload(tls.clone(), current.clone());
+    if let Some(port) = tls.redirect_port {
+        spawn_redirect(listener.local_addr().map_err(|e| AppError::Internal(e.to_string()))?, port).await?;
+    }
+
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                warn!("Failed to accept a connection: {}", e);
+                continue;
+            }
+        };
+        let acceptor = match current.read() {
+            Ok(config) => TlsAcceptor::from(config.clone()),
+            Err(_) => continue,
+        };
+        let app = app.clone();
+
+        tokio::spawn(async move {
+            let stream = match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(e)) => {
+                    debug!("TLS handshake with {} failed: {}", addr, e);
+                    return;
+                }
+                Err(_) => {
+                    debug!("TLS handshake with {} timed out", addr);
+                    return;
+                }
+            };
+
+            // Handlers read the peer address as axum's `ConnectInfo`
+            let service = hyper::service::service_fn(move |mut request: hyper::Request<hyper::body::Incoming>| {
+                request.extensions_mut().insert(ConnectInfo(addr));
+                app.clone().oneshot(request.map(axum::body::Body::new))
+            });
+            if let Err(e) = Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                debug!("Connection from {} ended with an error: {}", addr, e);
+            }
+        });
+    }
+}
+
+/// Listen on `port` and redirect everything to the HTTPS listener at `https`
+async fn spawn_redirect(https: SocketAddr, port: u16) -> Result<()> {
+    let listener = TcpListener::bind(SocketAddr::new(https.ip(), port))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to bind the HTTP redirect port {}: {}", port, e)))?;
+    info!("Redirecting HTTP on port {} to HTTPS on port {}", port, https.port());
+
+    let https_port = https.port();
+    let redirect = Router::new().fallback(move |request: Request| async move { redirect_to_https(request, https_port) });
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, redirect).await {
+            warn!("HTTP redirect listener stopped: {}", e);
+        }
+    });
+    Ok(())
+}
+
+fn redirect_to_https(request: Request, https_port: u16) -> Response {
+    let host = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.parse::<axum::http::uri::Authority>().ok());
+    let Some(host) = host else {
+        return (StatusCode::BAD_REQUEST, "Missing Host header").into_response();
+    };
+
+    let authority = match https_port {
+        443 => host.host().to_string(),
+        port => format!("{}:{}", host.host(), port),
+    };
+    let path = request.uri().path_and_query().map_or("/", |p| p.as_str());
+    match format!("https://{}{}", authority, path).parse::<Uri>() {
+        Ok(uri) => Redirect::permanent(&uri.to_string()).into_response(),
+        Err(_) => (StatusCode::BAD_REQUEST, "Invalid request URI").into_response(),
+    }
+}
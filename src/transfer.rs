@@ -0,0 +1,305 @@
+// Export/import of server state so a deployment can move between hosts.
+//
+// GitHub tokens are decrypted with the local key and re-wrapped under a
+// transfer key derived from the `TRANSFER_KEY` passphrase, so the archive is
+// safe to copy around and the destination re-encrypts with its own key.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::error::{AppError, Result};
+
+const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateArchive {
+    pub version: u32,
+    pub exported_at: String,
+    pub salt: String,
+    pub users: Vec<ExportedUser>,
+    pub github_tokens: Vec<ExportedToken>,
+    pub workflow_states: Vec<ExportedWorkflowState>,
+    #[serde(default)]
+    pub preferences: Vec<ExportedPreferences>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedPreferences {
+    pub login: String,
+    pub preferences: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedUser {
+    pub github_id: i64,
+    pub username: String,
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedToken {
+    pub user_id: i64,
+    pub username: String,
+    pub wrapped_token: String,
+    pub wrapped_refresh_token: Option<String>,
+    pub expires_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedWorkflowState {
+    pub user_id: i64,
+    pub repository: String,
+    pub branch: String,
+    pub workflow_type: String,
+    pub state: String,
+    pub created_at: Option<String>,
+}
+
+pub async fn export_state(db: &sqlx::SqlitePool, path: &str, transfer_key: &str) -> Result<()> {
+    let mut salt = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let cipher = transfer_cipher(transfer_key, &salt)?;
+
+    let users = sqlx::query_as!(
+        ExportedUser,
+        "SELECT github_id, username, name, email, avatar_url FROM users"
+    )
+    .fetch_all(db)
+    .await?;
+
+    let token_rows = sqlx::query!(
+        "SELECT user_id, username, encrypted_token, encrypted_refresh_token, expires_at FROM github_tokens"
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut github_tokens = Vec::with_capacity(token_rows.len());
+    for row in token_rows {
+        let token = crate::github::api::decrypt_token(&row.encrypted_token)?;
+        let refresh_token = row.encrypted_refresh_token
+            .as_deref()
+            .map(crate::github::api::decrypt_token)
+            .transpose()?;
+
+        github_tokens.push(ExportedToken {
+            user_id: row.user_id,
+            username: row.username,
+            wrapped_token: wrap(&cipher, &token)?,
+            wrapped_refresh_token: refresh_token.map(|t| wrap(&cipher, &t)).transpose()?,
+            expires_at: row.expires_at.to_string(),
+        });
+    }
+
+    let workflow_states = sqlx::query!(
+        "SELECT user_id, repository, branch, workflow_type, state, created_at FROM workflow_states"
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|row| ExportedWorkflowState {
+        user_id: row.user_id,
+        repository: row.repository,
+        branch: row.branch,
+        workflow_type: row.workflow_type,
+        state: row.state,
+        created_at: row.created_at.map(|t| t.to_string()),
+    })
+    .collect();
+
+    let preferences = sqlx::query_as!(
+        ExportedPreferences,
+        r#"SELECT login as "login!", preferences FROM user_preferences"#
+    )
+    .fetch_all(db)
+    .await?;
+
+    let archive = StateArchive {
+        version: ARCHIVE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        salt: BASE64.encode(salt),
+        users,
+        github_tokens,
+        workflow_states,
+        preferences,
+    };
+
+    let contents = serde_json::to_vec_pretty(&archive)?;
+    tokio::fs::write(path, contents)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to write archive {}: {}", path, e)))?;
+
+    info!(
+        "Exported {} users, {} tokens, {} workflow states and {} preference sets to {}",
+        archive.users.len(),
+        archive.github_tokens.len(),
+        archive.workflow_states.len(),
+        archive.preferences.len(),
+        path
+    );
+
+    Ok(())
+}
+
+pub async fn import_state(db: &sqlx::SqlitePool, path: &str, transfer_key: &str) -> Result<()> {
+    let contents = tokio::fs::read(path)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to read archive {}: {}", path, e)))?;
+    let archive: StateArchive = serde_json::from_slice(&contents)?;
+
+    if archive.version != ARCHIVE_VERSION {
+        return Err(AppError::Validation(format!("Unsupported archive version: {}", archive.version)));
+    }
+
+    let salt = BASE64
+        .decode(&archive.salt)
+        .map_err(|e| AppError::Validation(format!("Invalid archive salt: {}", e)))?;
+    let cipher = transfer_cipher(transfer_key, &salt)?;
+
+    let mut tx = db.begin().await?;
+
+    for user in &archive.users {
+        sqlx::query!(
+            r#"
+            INSERT INTO users (github_id, username, name, email, avatar_url)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(github_id) DO UPDATE SET
+                username = excluded.username,
+                name = excluded.name,
+                email = excluded.email,
+                avatar_url = excluded.avatar_url,
+                updated_at = datetime('now')
+            "#,
+            user.github_id,
+            user.username,
+            user.name,
+            user.email,
+            user.avatar_url
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for token in &archive.github_tokens {
+        let access_token = crate::auth::encrypt_token(&unwrap(&cipher, &token.wrapped_token)?)?;
+        let refresh_token = token.wrapped_refresh_token
+            .as_deref()
+            .map(|t| unwrap(&cipher, t).and_then(|t| crate::auth::encrypt_token(&t)))
+            .transpose()?;
+
+        sqlx::query!(
+            r#"
+            INSERT OR REPLACE INTO github_tokens
+            (user_id, username, encrypted_token, encrypted_refresh_token, expires_at, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, datetime('now'), datetime('now'))
+            "#,
+            token.user_id,
+            token.username,
+            access_token,
+            refresh_token,
+            token.expires_at
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for workflow in &archive.workflow_states {
+        sqlx::query!(
+            r#"
+            INSERT INTO workflow_states (user_id, repository, branch, workflow_type, state, created_at)
+            VALUES (?, ?, ?, ?, ?, COALESCE(?, datetime('now')))
+            ON CONFLICT(user_id, repository, branch, workflow_type, created_at) DO UPDATE SET
+                state = excluded.state,
+                updated_at = datetime('now')
+            "#,
+            workflow.user_id,
+            workflow.repository,
+            workflow.branch,
+            workflow.workflow_type,
+            workflow.state,
+            workflow.created_at
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for prefs in &archive.preferences {
+        sqlx::query!(
+            r#"
+            INSERT INTO user_preferences (login, preferences, updated_at)
+            VALUES (?, ?, datetime('now'))
+            ON CONFLICT(login) DO UPDATE SET
+                preferences = excluded.preferences,
+                updated_at = excluded.updated_at
+            "#,
+            prefs.login,
+            prefs.preferences
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    info!(
+        "Imported {} users, {} tokens, {} workflow states and {} preference sets from {}",
+        archive.users.len(),
+        archive.github_tokens.len(),
+        archive.workflow_states.len(),
+        archive.preferences.len(),
+        path
+    );
+
+    Ok(())
+}
+
+fn transfer_cipher(transfer_key: &str, salt: &[u8]) -> Result<Aes256Gcm> {
+    if transfer_key.len() < 12 {
+        return Err(AppError::Validation("Transfer key must be at least 12 characters".to_string()));
+    }
+
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(transfer_key.as_bytes(), salt, &mut key)
+        .map_err(|e| AppError::Internal(format!("Key derivation failed: {}", e)))?;
+
+    Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| AppError::Internal(format!("Invalid transfer key: {}", e)))
+}
+
+fn wrap(cipher: &Aes256Gcm, plaintext: &str) -> Result<String> {
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_bytes())
+        .map_err(|e| AppError::Internal(format!("Token wrapping failed: {}", e)))?;
+
+    let mut out = nonce.to_vec();
+    out.extend_from_slice(&ciphertext);
+    Ok(BASE64.encode(out))
+}
+
+fn unwrap(cipher: &Aes256Gcm, wrapped: &str) -> Result<String> {
+    let bytes = BASE64
+        .decode(wrapped)
+        .map_err(|e| AppError::Validation(format!("Invalid wrapped token: {}", e)))?;
+    if bytes.len() < 12 {
+        return Err(AppError::Validation("Invalid wrapped token".to_string()));
+    }
+
+    let (nonce, ciphertext) = bytes.split_at(12);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| AppError::Authentication("Wrong transfer key or corrupted archive".to_string()))?;
+
+    String::from_utf8(plaintext)
+        .map_err(|e| AppError::Validation(format!("Invalid token encoding: {}", e)))
+}
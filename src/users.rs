@@ -0,0 +1,109 @@
+use serde::Serialize;
+
+use crate::{AppState, error::{AppError, Result}};
+
+/// A user who has connected a GitHub account through OAuth
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectedUser {
+    pub user_id: i64,
+    pub username: String,
+    pub admin: bool,
+    /// When the stored GitHub token stops being used
+    pub token_expires_at: String,
+    pub token_expired: bool,
+    pub has_refresh_token: bool,
+    pub connected_at: String,
+    pub updated_at: String,
+    /// Set once an admin has made the user sign in again
+    pub reauth_forced_at: Option<String>,
+    pub reauth_forced_by: Option<String>,
+}
+
+pub async fn list(state: &AppState) -> Result<Vec<ConnectedUser>> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT t.user_id, t.username, t.expires_at as "expires_at!: String",
+               t.expires_at <= datetime('now') as "expired!: bool",
+               t.encrypted_refresh_token IS NOT NULL as "has_refresh_token!: bool",
+               t.created_at as "created_at!: String", t.updated_at as "updated_at!: String",
+               f.created_at as "reauth_forced_at?: String", f.forced_by as "reauth_forced_by?"
+        FROM github_tokens t
+        LEFT JOIN forced_reauth f ON f.user_id = t.user_id
+        ORDER BY t.username
+        "#
+    )
+    .fetch_all(&state.db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ConnectedUser {
+            admin: state.config.security.admin_users.iter().any(|u| u.eq_ignore_ascii_case(&row.username)),
+            user_id: row.user_id,
+            username: row.username,
+            token_expires_at: row.expires_at,
+            token_expired: row.expired,
+            has_refresh_token: row.has_refresh_token,
+            connected_at: row.created_at,
+            updated_at: row.updated_at,
+            reauth_forced_at: row.reauth_forced_at,
+            reauth_forced_by: row.reauth_forced_by,
+        })
+        .collect())
+}
+
+pub async fn find(state: &AppState, user_id: i64) -> Result<ConnectedUser> {
+    list(state)
+        .await?
+        .into_iter()
+        .find(|user| user.user_id == user_id)
+        .ok_or_else(|| AppError::Validation(format!("No connected user with id {}", user_id)))
+}
+
+/// Delete `user_id`'s stored GitHub token, returning it so it can also be
+/// revoked at GitHub
+pub async fn remove_token(db: &sqlx::SqlitePool, user_id: i64) -> Result<Option<String>> {
+    let row = sqlx::query!("DELETE FROM github_tokens WHERE user_id = ? RETURNING encrypted_token", user_id)
+        .fetch_optional(db)
+        .await?;
+
+    row.map(|row| crate::github::api::decrypt_token(&row.encrypted_token)).transpose()
+}
+
+/// End every session `user_id` has open and refuse the access tokens already
+/// issued; they sign in again to get new ones
+pub async fn force_reauth(state: &AppState, user_id: i64, username: &str, admin: &str) -> Result<()> {
+    let not_before = chrono::Utc::now().timestamp_millis();
+    sqlx::query!(
+        r#"
+        INSERT INTO forced_reauth (user_id, username, not_before, forced_by)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT(user_id) DO UPDATE SET
+            not_before = excluded.not_before,
+            forced_by = excluded.forced_by,
+            created_at = datetime('now')
+        "#,
+        user_id,
+        username,
+        not_before,
+        admin
+    )
+    .execute(&state.db)
+    .await?;
+
+    crate::auth::sessions::revoke_user(state, user_id).await?;
+    state.jwt.require_reauth(user_id as u64, not_before);
+    Ok(())
+}
+
+/// Apply the forced re-auths recorded before a restart
+pub async fn load_reauth(state: &AppState) -> Result<()> {
+    let rows = sqlx::query!("SELECT user_id, not_before FROM forced_reauth")
+        .fetch_all(&state.db)
+        .await?;
+
+    for row in rows {
+        state.jwt.require_reauth(row.user_id as u64, row.not_before);
+    }
+    Ok(())
+}
@@ -0,0 +1,276 @@
+use axum::{body::Bytes, extract::State, http::{HeaderMap, StatusCode}, Json};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tracing::{debug, info, warn, Instrument};
+
+use crate::{AppState, config::ConfigError, error::{AppError, Result}};
+use crate::github::api::{GitHubIssue, GitHubPullRequest, GitHubReview, GitHubWorkflowRun};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookRepository {
+    pub full_name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookUser {
+    pub login: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushCommit {
+    pub id: String,
+    pub message: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PushEvent {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub before: String,
+    pub after: String,
+    #[serde(default)]
+    pub created: bool,
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub forced: bool,
+    #[serde(default)]
+    pub commits: Vec<PushCommit>,
+    pub repository: WebhookRepository,
+    pub sender: Option<WebhookUser>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullRequestEvent {
+    pub action: String,
+    pub number: u64,
+    pub pull_request: GitHubPullRequest,
+    pub repository: WebhookRepository,
+    pub sender: Option<WebhookUser>,
+}
+
+/// The subset of a pull request sent with review events
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookPullRequest {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PullRequestReviewEvent {
+    pub action: String,
+    pub review: GitHubReview,
+    pub pull_request: WebhookPullRequest,
+    pub repository: WebhookRepository,
+    pub sender: Option<WebhookUser>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssuesEvent {
+    pub action: String,
+    pub issue: GitHubIssue,
+    /// The user added or removed by `assigned`/`unassigned`
+    #[serde(default)]
+    pub assignee: Option<WebhookUser>,
+    pub repository: WebhookRepository,
+    pub sender: Option<WebhookUser>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowRunEvent {
+    pub action: String,
+    pub workflow_run: GitHubWorkflowRun,
+    pub repository: WebhookRepository,
+    pub sender: Option<WebhookUser>,
+}
+
+#[derive(Debug, Clone)]
+pub enum WebhookEvent {
+    Push(PushEvent),
+    PullRequest(PullRequestEvent),
+    PullRequestReview(PullRequestReviewEvent),
+    Issues(IssuesEvent),
+    WorkflowRun(WorkflowRunEvent),
+    /// Sent once when a webhook is created
+    Ping { hook_id: Option<u64>, zen: Option<String> },
+    /// Events without a typed form, or whose payload didn't match it
+    Other { event: String, payload: Value },
+}
+
+impl WebhookEvent {
+    /// Type `payload` by its `X-GitHub-Event` name, falling back to `Other`
+    pub fn parse(event: &str, payload: Value) -> Self {
+        let typed = match event {
+            "push" => serde_json::from_value(payload.clone()).map(WebhookEvent::Push),
+            "pull_request" => serde_json::from_value(payload.clone()).map(WebhookEvent::PullRequest),
+            "pull_request_review" => serde_json::from_value(payload.clone()).map(WebhookEvent::PullRequestReview),
+            "issues" => serde_json::from_value(payload.clone()).map(WebhookEvent::Issues),
+            "workflow_run" => serde_json::from_value(payload.clone()).map(WebhookEvent::WorkflowRun),
+            "ping" => Ok(WebhookEvent::Ping {
+                hook_id: payload["hook_id"].as_u64(),
+                zen: payload["zen"].as_str().map(String::from),
+            }),
+            _ => return WebhookEvent::Other { event: event.to_string(), payload },
+        };
+
+        typed.unwrap_or_else(|e| {
+            warn!("Untyped {} webhook payload: {}", event, e);
+            WebhookEvent::Other { event: event.to_string(), payload }
+        })
+    }
+
+    /// The `X-GitHub-Event` name
+    pub fn name(&self) -> &str {
+        match self {
+            WebhookEvent::Push(_) => "push",
+            WebhookEvent::PullRequest(_) => "pull_request",
+            WebhookEvent::PullRequestReview(_) => "pull_request_review",
+            WebhookEvent::Issues(_) => "issues",
+            WebhookEvent::WorkflowRun(_) => "workflow_run",
+            WebhookEvent::Ping { .. } => "ping",
+            WebhookEvent::Other { event, .. } => event,
+        }
+    }
+}
+
+type HandlerFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+type Handler = Arc<dyn Fn(AppState, Arc<WebhookEvent>) -> HandlerFuture + Send + Sync>;
+
+/// Internal handlers called for each verified delivery
+#[derive(Clone, Default)]
+pub struct Dispatcher {
+    handlers: Vec<(&'static str, &'static [&'static str], Handler)>,
+}
+
+impl Dispatcher {
+    /// Call `handler` for the named events, or for every event when `events` is empty
+    pub fn register<F, Fut>(&mut self, name: &'static str, events: &'static [&'static str], handler: F)
+    where
+        F: Fn(AppState, Arc<WebhookEvent>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let handler: Handler = Arc::new(move |state, event| Box::pin(handler(state, event)));
+        self.handlers.push((name, events, handler));
+    }
+
+    /// Run every matching handler in registration order; returns their failures
+    async fn dispatch(&self, state: &AppState, event: Arc<WebhookEvent>) -> Vec<String> {
+        let mut errors = Vec::new();
+        for (name, events, handler) in &self.handlers {
+            if !events.is_empty() && !events.contains(&event.name()) {
+                continue;
+            }
+            if let Err(e) = handler(state.clone(), event.clone()).await {
+                warn!("Webhook handler {} failed on {}: {}", name, event.name(), e);
+                errors.push(format!("{}: {}", name, e));
+            }
+        }
+        errors
+    }
+}
+
+/// Whether `signature` (`sha256=<hex>`) is the HMAC of `body` under `secret`
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(expected) = signature.strip_prefix("sha256=").and_then(|hex| hex::decode(hex).ok()) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    // Constant-time comparison
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// `POST /webhooks/github`: verify, store and hand the delivery to the
+/// registered handlers. Handlers run after the response, since GitHub
+/// gives up on deliveries that take longer than ten seconds.
+pub async fn receive(State(state): State<AppState>, headers: HeaderMap, body: Bytes) -> Result<(StatusCode, Json<Value>)> {
+    let secret = state
+        .config
+        .github
+        .webhook_secret
+        .as_deref()
+        .ok_or_else(|| ConfigError::MissingEnvVar("GITHUB_WEBHOOK_SECRET".to_string()))?;
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    let signature = header("x-hub-signature-256")
+        .ok_or_else(|| AppError::Authentication("Missing X-Hub-Signature-256".to_string()))?;
+    if !verify_signature(secret, &body, signature) {
+        warn!("Rejected webhook delivery with an invalid signature");
+        return Err(AppError::Authentication("Invalid webhook signature".to_string()));
+    }
+
+    let event_name = header("x-github-event")
+        .ok_or_else(|| AppError::Validation("Missing X-GitHub-Event".to_string()))?
+        .to_string();
+    let delivery_id = header("x-github-delivery")
+        .map(String::from)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let payload: Value = serde_json::from_slice(&body)?;
+    let action = payload["action"].as_str().map(String::from);
+    let repository = payload["repository"]["full_name"].as_str().map(String::from);
+    let sender = payload["sender"]["login"].as_str().map(String::from);
+    let payload_text = payload.to_string();
+
+    let inserted = sqlx::query!(
+        r#"
+        INSERT OR IGNORE INTO webhook_events (delivery_id, event, action, repository, sender, payload)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+        delivery_id,
+        event_name,
+        action,
+        repository,
+        sender,
+        payload_text
+    )
+    .execute(&state.db)
+    .await?
+    .rows_affected();
+
+    if inserted == 0 {
+        debug!("Ignoring redelivered webhook {}", delivery_id);
+        return Ok((StatusCode::OK, Json(json!({ "status": "duplicate", "delivery": delivery_id }))));
+    }
+
+    info!("Received {} webhook {} for {}", event_name, delivery_id, repository.as_deref().unwrap_or("-"));
+    let event = Arc::new(WebhookEvent::parse(&event_name, payload));
+    let response = json!({ "status": "accepted", "delivery": delivery_id, "event": event_name });
+
+    tokio::spawn(
+        async move {
+            let errors = state.webhooks.dispatch(&state, event).await;
+            let error = (!errors.is_empty()).then(|| errors.join("\n"));
+            let recorded = sqlx::query!(
+                "UPDATE webhook_events SET handled_at = datetime('now'), error = ? WHERE delivery_id = ?",
+                error,
+                delivery_id
+            )
+            .execute(&state.db)
+            .await;
+            if let Err(e) = recorded {
+                warn!("Failed to record webhook {} handling: {}", delivery_id, e);
+            }
+        }
+        .instrument(tracing::Span::current()),
+    );
+
+    Ok((StatusCode::ACCEPTED, Json(response)))
+}
+
+/// Built-in handler confirming a newly created webhook reached the server
+pub async fn log_ping(_state: AppState, event: Arc<WebhookEvent>) -> Result<()> {
+    if let WebhookEvent::Ping { hook_id, zen } = event.as_ref() {
+        info!("GitHub webhook {} connected: {}", hook_id.unwrap_or_default(), zen.as_deref().unwrap_or_default());
+    }
+    Ok(())
+}